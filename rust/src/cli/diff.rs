@@ -0,0 +1,199 @@
+use serde_yaml::Value;
+
+const DIFF_CONTEXT_LINES: usize = 3;
+
+const COLOR_RED: &str = "\x1b[31m";
+const COLOR_GREEN: &str = "\x1b[32m";
+const COLOR_CYAN: &str = "\x1b[36m";
+const COLOR_BOLD: &str = "\x1b[1m";
+const COLOR_RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+// The desired interface without type defined is serialized with
+// `type: unknown`, remove it to prevent it been treated as difference.
+pub(crate) fn purge_unknown_iface_type(state: &mut Value) {
+    if let Some(Value::Sequence(ifaces)) = state.get_mut("interfaces") {
+        for iface in ifaces.iter_mut() {
+            if let Value::Mapping(iface) = iface {
+                let type_key = Value::String("type".to_string());
+                if iface.get(&type_key)
+                    == Some(&Value::String("unknown".to_string()))
+                {
+                    iface.remove(&type_key);
+                }
+            }
+        }
+    }
+}
+
+// Only keep the properties of current state which are mentioned in desired
+// state, so the diff only shows the drift of what user cares about.
+// The interfaces are matched by name and type(if defined in desire).
+pub(crate) fn trim_to_desired(current: &Value, desired: &Value) -> Value {
+    match (current, desired) {
+        (Value::Mapping(cur), Value::Mapping(des)) => {
+            let mut ret = serde_yaml::Mapping::new();
+            for (key, des_value) in des.iter() {
+                if let Some(cur_value) = cur.get(key) {
+                    ret.insert(
+                        key.clone(),
+                        trim_to_desired(cur_value, des_value),
+                    );
+                }
+            }
+            Value::Mapping(ret)
+        }
+        (Value::Sequence(cur), Value::Sequence(des))
+            if !des.is_empty()
+                && des.iter().all(|v| v.get("name").is_some()) =>
+        {
+            let mut ret = Vec::new();
+            for des_value in des {
+                if let Some(cur_value) = cur.iter().find(|cur_value| {
+                    cur_value.get("name") == des_value.get("name")
+                        && (des_value.get("type").is_none()
+                            || cur_value.get("type") == des_value.get("type"))
+                }) {
+                    ret.push(trim_to_desired(cur_value, des_value));
+                }
+            }
+            Value::Sequence(ret)
+        }
+        _ => current.clone(),
+    }
+}
+
+// Generate unified diff of two text with the `-` lines in red, `+` lines in
+// green. Return empty string if no difference found.
+pub(crate) fn gen_colored_diff(
+    old_name: &str,
+    old: &str,
+    new_name: &str,
+    new: &str,
+    use_color: bool,
+) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let diff_lines = diff_lines(&old_lines, &new_lines);
+    if diff_lines.iter().all(|l| matches!(l, DiffLine::Same(_))) {
+        return String::new();
+    }
+
+    let paint = |color: &str, line: String| {
+        if use_color {
+            format!("{}{}{}", color, line, COLOR_RESET)
+        } else {
+            line
+        }
+    };
+
+    let mut ret = vec![
+        paint(COLOR_BOLD, format!("--- {}", old_name)),
+        paint(COLOR_BOLD, format!("+++ {}", new_name)),
+    ];
+    for (start, end) in gen_hunk_ranges(&diff_lines) {
+        let hunk = &diff_lines[start..end];
+        // Line numbers of hunk header are 1-based
+        let old_start = diff_lines[..start]
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_start = diff_lines[..start]
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        let old_count = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|l| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        ret.push(paint(
+            COLOR_CYAN,
+            format!(
+                "@@ -{},{} +{},{} @@",
+                hunk_start_line(old_start, old_count),
+                old_count,
+                hunk_start_line(new_start, new_count),
+                new_count
+            ),
+        ));
+        for line in hunk {
+            ret.push(match line {
+                DiffLine::Same(l) => format!(" {}", l),
+                DiffLine::Removed(l) => paint(COLOR_RED, format!("-{}", l)),
+                DiffLine::Added(l) => paint(COLOR_GREEN, format!("+{}", l)),
+            });
+        }
+    }
+    ret.join("\n")
+}
+
+fn hunk_start_line(lines_before: usize, count: usize) -> usize {
+    if count == 0 {
+        lines_before
+    } else {
+        lines_before + 1
+    }
+}
+
+// Longest common subsequence based line diff.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                std::cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ret = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ret.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ret.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            ret.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    ret.extend(old[i..].iter().map(|l| DiffLine::Removed(l)));
+    ret.extend(new[j..].iter().map(|l| DiffLine::Added(l)));
+    ret
+}
+
+// Return the [start, end) index of each hunk with context lines included,
+// hunks with overlapping context are merged.
+fn gen_hunk_ranges(diff_lines: &[DiffLine]) -> Vec<(usize, usize)> {
+    let mut ret: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in diff_lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Same(_)))
+    {
+        let start = index.saturating_sub(DIFF_CONTEXT_LINES);
+        let end =
+            std::cmp::min(index + 1 + DIFF_CONTEXT_LINES, diff_lines.len());
+        match ret.last_mut() {
+            Some(last) if last.1 >= start => last.1 = end,
+            _ => ret.push((start, end)),
+        }
+    }
+    ret
+}
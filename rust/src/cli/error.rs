@@ -1,7 +1,57 @@
-use nmstate::NmstateError;
+use std::collections::BTreeMap;
 
+use nmstate::{ErrorKind, NmstateError};
+
+const EXIT_CODE_FAILURE: i32 = 1;
+const EXIT_CODE_INVALID_ARGUMENT: i32 = 2;
+const EXIT_CODE_VERIFICATION_ERROR: i32 = 3;
+const EXIT_CODE_PLUGIN_FAILURE: i32 = 4;
+const EXIT_CODE_TIMEOUT: i32 = 5;
+const EXIT_CODE_NOT_IMPLEMENTED: i32 = 6;
+const EXIT_CODE_DEPENDENCY_ERROR: i32 = 7;
+const EXIT_CODE_IP_ADDRESS_CONFLICT: i32 = 8;
+
+#[derive(Debug)]
 pub(crate) struct CliError {
     pub(crate) msg: String,
+    // The kind of NmstateError, None for error of CLI itself.
+    pub(crate) kind: Option<ErrorKind>,
+    // The machine readable context of NmstateError
+    pub(crate) context: BTreeMap<String, String>,
+}
+
+impl CliError {
+    // Distinct exit code for each kind of NmstateError, so script could
+    // branch on failure type.
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self.kind {
+            Some(ErrorKind::InvalidArgument) => EXIT_CODE_INVALID_ARGUMENT,
+            Some(ErrorKind::VerificationError)
+            | Some(ErrorKind::KernelIntegerRoundedError) => {
+                EXIT_CODE_VERIFICATION_ERROR
+            }
+            Some(ErrorKind::PluginFailure) | Some(ErrorKind::OvsDbError) => {
+                EXIT_CODE_PLUGIN_FAILURE
+            }
+            Some(ErrorKind::Timeout) => EXIT_CODE_TIMEOUT,
+            Some(ErrorKind::NotImplementedError) => EXIT_CODE_NOT_IMPLEMENTED,
+            Some(ErrorKind::DependencyError) => EXIT_CODE_DEPENDENCY_ERROR,
+            Some(ErrorKind::IpAddressConflict) => EXIT_CODE_IP_ADDRESS_CONFLICT,
+            Some(ErrorKind::Bug) | None => EXIT_CODE_FAILURE,
+        }
+    }
+
+    pub(crate) fn to_json_string(&self) -> String {
+        serde_json::json!({
+            "kind": self.kind.map(|k| k.to_string()),
+            "code": self.kind.map(|k| k.code()),
+            "code-name": self.kind.map(|k| k.as_str()),
+            "context": self.context,
+            "msg": self.msg,
+            "exit-code": self.exit_code(),
+        })
+        .to_string()
+    }
 }
 
 impl std::fmt::Display for CliError {
@@ -14,6 +64,8 @@ impl From<std::io::Error> for CliError {
     fn from(e: std::io::Error) -> Self {
         Self {
             msg: format!("std::io::Error: {}", e),
+            kind: None,
+            context: BTreeMap::new(),
         }
     }
 }
@@ -22,6 +74,8 @@ impl From<NmstateError> for CliError {
     fn from(e: NmstateError) -> Self {
         Self {
             msg: format!("NmstateError: {}", e),
+            kind: Some(e.kind()),
+            context: e.context().clone(),
         }
     }
 }
@@ -30,6 +84,18 @@ impl From<serde_yaml::Error> for CliError {
     fn from(e: serde_yaml::Error) -> Self {
         Self {
             msg: format!("serde_yaml::Error: {}", e),
+            kind: None,
+            context: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(e: serde_json::Error) -> Self {
+        Self {
+            msg: format!("serde_json::Error: {}", e),
+            kind: None,
+            context: BTreeMap::new(),
         }
     }
 }
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::fs::{File, OpenOptions, Permissions};
+use std::io::Write;
+use std::os::unix::fs::{fchown, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use nmstate::ErrorKind;
+
+use crate::error::CliError;
+use crate::state::{state_from_file, state_from_reader};
+
+const NM_CONF_KEY: &str = "NetworkManager";
+const KEYFILE_SUFFIX: &str = ".nmconnection";
+const KEYFILE_MODE: u32 = 0o600;
+const WORLD_READABLE_MODE: u32 = 0o004;
+const SELINUX_XATTR_NAME: &str = "security.selinux";
+// The label of /etc/NetworkManager/system-connections in SELinux policy
+pub(crate) const NM_KEYFILE_SELINUX_LABEL: &str =
+    "system_u:object_r:NetworkManager_etc_rw_t:s0";
+
+// Keys under these sections are all secrets
+const SECRET_SECTIONS: [&str; 1] = ["vpn-secrets"];
+// Keys holding secrets, key ends with these suffixes are also treated as
+// secrets, e.g. `private-key-password`.
+const SECRET_KEYS: [&str; 10] = [
+    "psk",
+    "password",
+    "private-key",
+    "pin",
+    "mka-cak",
+    "preshared-key",
+    "wep-key0",
+    "wep-key1",
+    "wep-key2",
+    "wep-key3",
+];
+
+pub(crate) fn gen_conf(
+    file_path: &str,
+    output_dir: Option<&str>,
+    selinux_label: Option<&str>,
+) -> Result<String, CliError> {
+    let net_state = state_from_file(file_path)?;
+    let confs = net_state.gen_conf()?;
+    match output_dir {
+        Some(output_dir) => {
+            let keyfiles = confs
+                .get(NM_CONF_KEY)
+                .map(|k| k.as_slice())
+                .unwrap_or_default();
+            let written =
+                write_keyfiles(Path::new(output_dir), keyfiles, selinux_label)?;
+            Ok(written
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<String>>()
+                .join("\n"))
+        }
+        None => Ok(serde_yaml::to_string(&confs)?),
+    }
+}
+
+// The keyfiles are generated by the same code path of apply, so they are what
+// apply would save to a host without any NetworkManager profile.
+pub(crate) fn apply_offline(
+    file_path: Option<&str>,
+) -> Result<String, CliError> {
+    let net_state = match file_path {
+        Some(file_path) => state_from_file(file_path)?,
+        None => state_from_reader(std::io::stdin())?,
+    };
+    Ok(serde_yaml::to_string(&net_state.gen_conf()?)?)
+}
+
+// NetworkManager ignores keyfiles not owned by root or readable by others.
+// Keyfile holding secrets is refused to be placed in world-readable folder.
+fn write_keyfiles(
+    output_dir: &Path,
+    keyfiles: &[String],
+    selinux_label: Option<&str>,
+) -> Result<Vec<PathBuf>, CliError> {
+    let dir_mode = match std::fs::metadata(output_dir) {
+        Ok(m) if m.is_dir() => m.permissions().mode(),
+        Ok(_) => {
+            return Err(invalid_arg(format!(
+                "Output path {} is not a directory",
+                output_dir.display()
+            )));
+        }
+        Err(e) => {
+            return Err(invalid_arg(format!(
+                "Failed to access output directory {}: {}",
+                output_dir.display(),
+                e
+            )));
+        }
+    };
+
+    // Validate all keyfiles before writing any of them
+    let mut pending = Vec::new();
+    for keyfile in keyfiles {
+        let file_name = match get_keyfile_id(keyfile) {
+            Some(id) => {
+                format!("{}{}", sanitize_file_name(&id), KEYFILE_SUFFIX)
+            }
+            None => {
+                return Err(CliError {
+                    msg: format!(
+                        "Generated keyfile has no connection id: {}",
+                        keyfile
+                    ),
+                    kind: Some(ErrorKind::Bug),
+                    context: BTreeMap::new(),
+                });
+            }
+        };
+        if dir_mode & WORLD_READABLE_MODE > 0 {
+            if let Some(key) = get_secret_key(keyfile) {
+                return Err(invalid_arg(format!(
+                    "Refusing to store keyfile {} holding secret {} \
+                    in world-readable directory {}",
+                    file_name,
+                    key,
+                    output_dir.display()
+                )));
+            }
+        }
+        pending.push((output_dir.join(file_name), keyfile));
+    }
+
+    let mut ret = Vec::new();
+    for (path, keyfile) in pending {
+        write_keyfile(&path, keyfile, selinux_label)?;
+        ret.push(path);
+    }
+    Ok(ret)
+}
+
+// The permission and ownership are set on file descriptor before writing
+// content, hence content is never exposed to other users.
+pub(crate) fn write_keyfile(
+    path: &Path,
+    content: &str,
+    selinux_label: Option<&str>,
+) -> Result<(), CliError> {
+    // Do not follow symbolic link or block on FIFO placed at keyfile path
+    let mut fd = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .mode(KEYFILE_MODE)
+        .custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK)
+        .open(path)
+        .map_err(|e| file_error(path, "open", e))?;
+    if !fd
+        .metadata()
+        .map_err(|e| file_error(path, "query metadata of", e))?
+        .is_file()
+    {
+        return Err(invalid_arg(format!(
+            "Keyfile path {} is not a regular file",
+            path.display()
+        )));
+    }
+    fd.set_permissions(Permissions::from_mode(KEYFILE_MODE))
+        .map_err(|e| file_error(path, "set permission of", e))?;
+    fchown(&fd, Some(0), Some(0))
+        .map_err(|e| file_error(path, "change ownership to root of", e))?;
+    if let Some(label) = selinux_label {
+        set_selinux_label(&fd, path, label)?;
+    }
+    fd.set_len(0).map_err(|e| file_error(path, "truncate", e))?;
+    fd.write_all(content.as_bytes())
+        .map_err(|e| file_error(path, "write", e))?;
+    Ok(())
+}
+
+fn set_selinux_label(
+    fd: &File,
+    path: &Path,
+    label: &str,
+) -> Result<(), CliError> {
+    use std::os::unix::io::AsRawFd;
+
+    let name = CString::new(SELINUX_XATTR_NAME).map_err(|e| CliError {
+        msg: e.to_string(),
+        kind: Some(ErrorKind::Bug),
+        context: BTreeMap::new(),
+    })?;
+    // SELinux context stored in xattr is NULL terminated
+    let value = CString::new(label)
+        .map_err(|e| invalid_arg(format!("Invalid SELinux label: {}", e)))?;
+    let value = value.as_bytes_with_nul();
+    let rc = unsafe {
+        libc::fsetxattr(
+            fd.as_raw_fd(),
+            name.as_ptr(),
+            value.as_ptr() as *const libc::c_void,
+            value.len(),
+            0,
+        )
+    };
+    if rc < 0 {
+        Err(file_error(
+            path,
+            &format!("set SELinux label {} on", label),
+            std::io::Error::last_os_error(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn get_keyfile_id(keyfile: &str) -> Option<String> {
+    let mut section = "";
+    for line in keyfile.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            section = &line[1..line.len() - 1];
+        } else if section == "connection" {
+            if let Some(id) = line.strip_prefix("id=") {
+                if !id.is_empty() {
+                    return Some(id.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+// Return `section.key` of first secret found in keyfile
+fn get_secret_key(keyfile: &str) -> Option<String> {
+    let mut section = "";
+    for line in keyfile.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            section = &line[1..line.len() - 1];
+            continue;
+        }
+        let key = match line.split_once('=') {
+            Some((key, value)) if !value.is_empty() => key,
+            _ => continue,
+        };
+        if SECRET_SECTIONS.contains(&section)
+            || SECRET_KEYS.iter().any(|s| key.ends_with(s))
+        {
+            return Some(format!("{}.{}", section, key));
+        }
+    }
+    None
+}
+
+// Connection id might hold `/` or start with `.`
+fn sanitize_file_name(id: &str) -> String {
+    let mut ret: String = id
+        .chars()
+        .map(|c| if c == '/' || c.is_control() { '_' } else { c })
+        .collect();
+    if ret.starts_with('.') {
+        ret.replace_range(0..1, "_");
+    }
+    ret
+}
+
+fn invalid_arg(msg: String) -> CliError {
+    CliError {
+        msg,
+        kind: Some(ErrorKind::InvalidArgument),
+        context: BTreeMap::new(),
+    }
+}
+
+fn file_error(path: &Path, action: &str, e: std::io::Error) -> CliError {
+    CliError {
+        msg: format!("Failed to {} {}: {}", action, path.display(), e),
+        kind: None,
+        context: BTreeMap::new(),
+    }
+}
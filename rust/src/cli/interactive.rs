@@ -0,0 +1,114 @@
+use std::io::{BufRead, Write};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use nmstate::{AuditLogBackend, NetworkState};
+
+use crate::error::CliError;
+use crate::state::state_from_file;
+
+// Extra time for NetworkManager to wait before rollback on its own, so the
+// countdown of this CLI always finishes first. NetworkManager only rollback
+// by itself when this CLI is gone, for example the SSH session disconnected.
+const CHECKPOINT_TIMEOUT_GRACE_SECONDS: u32 = 10;
+
+pub(crate) fn apply_interactive(
+    file_path: &str,
+    no_verify: bool,
+    dad_check: bool,
+    strict_veth_type: bool,
+    timeout: u32,
+    audit_log_backends: &[AuditLogBackend],
+) -> Result<String, CliError> {
+    let mut net_state = state_from_file(file_path)?;
+    net_state.set_verify_change(!no_verify);
+    net_state.set_dad_check(dad_check);
+    net_state.set_strict_veth_type(strict_veth_type);
+    net_state.set_audit_log_backends(audit_log_backends);
+
+    let (add_net_state, chg_net_state, del_net_state) = net_state.gen_plan()?;
+    let mut plan = serde_yaml::Mapping::new();
+    for (action, state) in [
+        ("add", add_net_state),
+        ("change", chg_net_state),
+        ("delete", del_net_state),
+    ] {
+        plan.insert(
+            serde_yaml::Value::String(action.to_string()),
+            serde_yaml::to_value(&state)?,
+        );
+    }
+    println!("{}", serde_yaml::to_string(&plan)?);
+
+    let answers = spawn_answer_reader();
+    if !ask(&answers, "Apply above changes? [y/N]: ", None) {
+        return Ok("Cancelled".to_string());
+    }
+
+    net_state.set_commit(false);
+    net_state.set_timeout(timeout + CHECKPOINT_TIMEOUT_GRACE_SECONDS);
+    net_state.apply()?;
+
+    if ask(
+        &answers,
+        &format!(
+            "Applied, keep the changes? Rollback in {} seconds [y/N]: ",
+            timeout
+        ),
+        Some(timeout),
+    ) {
+        NetworkState::checkpoint_commit("")?;
+        Ok("Changes committed".to_string())
+    } else {
+        NetworkState::checkpoint_rollback_with_audit("", audit_log_backends)?;
+        Ok("Changes rollbacked".to_string())
+    }
+}
+
+// Read the lines of stdin in a thread, so we can wait for answer with timeout.
+fn spawn_answer_reader() -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            match line {
+                Ok(line) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+    receiver
+}
+
+fn ask(
+    answers: &mpsc::Receiver<String>,
+    question: &str,
+    timeout: Option<u32>,
+) -> bool {
+    print!("{}", question);
+    std::io::stdout().flush().ok();
+    is_answered_yes(answers, timeout)
+}
+
+// Return true only if user answered yes before timeout. Stdin closed or any
+// other answer is treated as no.
+pub(crate) fn is_answered_yes(
+    answers: &mpsc::Receiver<String>,
+    timeout: Option<u32>,
+) -> bool {
+    let answer = match timeout {
+        Some(timeout) => answers
+            .recv_timeout(Duration::from_secs(timeout.into()))
+            .map_err(|_| println!("\nTimeout")),
+        None => answers.recv().map_err(|_| println!()),
+    };
+    match answer {
+        Ok(answer) => {
+            ["y", "yes"].contains(&answer.trim().to_lowercase().as_str())
+        }
+        Err(()) => false,
+    }
+}
@@ -0,0 +1,221 @@
+use nmstate::{InterfaceState, NetworkState};
+use serde_yaml::Value;
+
+use crate::diff::{purge_unknown_iface_type, trim_to_desired};
+use crate::error::CliError;
+use crate::state::state_from_file;
+
+const SYSFS_NET_PATH: &str = "/sys/class/net";
+
+const IFACE_COUNTERS: [(&str, &str); 8] = [
+    ("rx_bytes", "Total bytes received"),
+    ("tx_bytes", "Total bytes transmitted"),
+    ("rx_packets", "Total packets received"),
+    ("tx_packets", "Total packets transmitted"),
+    ("rx_errors", "Total receive errors"),
+    ("tx_errors", "Total transmit errors"),
+    ("rx_dropped", "Total received packets dropped"),
+    ("tx_dropped", "Total transmitted packets dropped"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MetricType {
+    Gauge,
+    Counter,
+}
+
+impl std::fmt::Display for MetricType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Gauge => "gauge",
+                Self::Counter => "counter",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Metric {
+    pub(crate) name: String,
+    pub(crate) help: String,
+    pub(crate) metric_type: MetricType,
+    // Label values of interface name and samples
+    pub(crate) samples: Vec<(String, u64)>,
+}
+
+impl Metric {
+    pub(crate) fn new(name: &str, help: &str, metric_type: MetricType) -> Self {
+        Self {
+            name: format!("nmstate_{}", name),
+            help: help.to_string(),
+            metric_type,
+            samples: Vec::new(),
+        }
+    }
+
+    pub(crate) fn to_text(&self) -> String {
+        let mut ret = vec![
+            format!("# HELP {} {}", self.name, self.help),
+            format!("# TYPE {} {}", self.name, self.metric_type),
+        ];
+        for (iface_name, value) in self.samples.iter() {
+            if iface_name.is_empty() {
+                ret.push(format!("{} {}", self.name, value));
+            } else {
+                ret.push(format!(
+                    "{}{{interface=\"{}\"}} {}",
+                    self.name,
+                    escape_label_value(iface_name),
+                    value
+                ));
+            }
+        }
+        ret.join("\n")
+    }
+}
+
+// Generate Prometheus text format metrics of current network state.
+// When desired state file defined, the drift status of each desired interface
+// and whole state is included.
+pub(crate) fn gen_metrics(
+    kernel_only: bool,
+    state_file: Option<&str>,
+) -> Result<String, CliError> {
+    let mut net_state = NetworkState::new();
+    net_state.set_kernel_only(kernel_only);
+    net_state.retrieve()?;
+
+    let mut metrics = gen_iface_metrics(&net_state);
+
+    if let Some(state_file) = state_file {
+        metrics.append(&mut gen_drift_metrics(
+            &state_from_file(state_file)?,
+            &net_state,
+        )?);
+    }
+
+    Ok(metrics_to_text(&metrics))
+}
+
+pub(crate) fn metrics_to_text(metrics: &[Metric]) -> String {
+    metrics
+        .iter()
+        .map(|m| m.to_text())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// The carrier and counters are read from sysfs, interfaces not found in sysfs
+// have no sample for them.
+pub(crate) fn gen_iface_metrics(net_state: &NetworkState) -> Vec<Metric> {
+    let mut ifaces = net_state.interfaces.to_vec();
+    ifaces.sort_by(|a, b| a.name().cmp(b.name()));
+
+    let mut up = Metric::new(
+        "interface_up",
+        "Whether interface is in up state",
+        MetricType::Gauge,
+    );
+    let mut carrier = Metric::new(
+        "interface_carrier",
+        "Whether interface has carrier",
+        MetricType::Gauge,
+    );
+    let mut mtu =
+        Metric::new("interface_mtu", "MTU of interface", MetricType::Gauge);
+    let mut counters: Vec<Metric> = IFACE_COUNTERS
+        .iter()
+        .map(|(name, help)| {
+            Metric::new(
+                &format!("interface_{}_total", name),
+                help,
+                MetricType::Counter,
+            )
+        })
+        .collect();
+
+    for iface in ifaces.iter() {
+        let name = iface.name().to_string();
+        up.samples.push((
+            name.clone(),
+            (iface.base_iface().state == InterfaceState::Up) as u64,
+        ));
+        if let Some(v) = read_sysfs_u64(&name, "carrier") {
+            carrier.samples.push((name.clone(), v));
+        }
+        if let Some(v) = iface.base_iface().mtu {
+            mtu.samples.push((name.clone(), v));
+        }
+        for (metric, (counter_name, _)) in
+            counters.iter_mut().zip(IFACE_COUNTERS.iter())
+        {
+            if let Some(v) =
+                read_sysfs_u64(&name, &format!("statistics/{}", counter_name))
+            {
+                metric.samples.push((name.clone(), v));
+            }
+        }
+    }
+
+    let mut metrics = vec![up, carrier, mtu];
+    metrics.append(&mut counters);
+    metrics
+}
+
+pub(crate) fn gen_drift_metrics(
+    desired: &NetworkState,
+    current: &NetworkState,
+) -> Result<Vec<Metric>, CliError> {
+    let mut des_value = serde_yaml::to_value(desired)?;
+    purge_unknown_iface_type(&mut des_value);
+    let cur_value =
+        trim_to_desired(&serde_yaml::to_value(current)?, &des_value);
+    let cur_ifaces_value = serde_yaml::to_value(current.interfaces.to_vec())?;
+
+    let mut iface_drift = Metric::new(
+        "interface_drift",
+        "Whether current interface differs from desired state",
+        MetricType::Gauge,
+    );
+    if let Some(Value::Sequence(des_ifaces)) = des_value.get("interfaces") {
+        for des_iface in des_ifaces {
+            let des_iface_value = Value::Sequence(vec![des_iface.clone()]);
+            let cur_iface_value =
+                trim_to_desired(&cur_ifaces_value, &des_iface_value);
+            if let Some(Value::String(name)) = des_iface.get("name") {
+                iface_drift.samples.push((
+                    name.to_string(),
+                    (cur_iface_value != des_iface_value) as u64,
+                ));
+            }
+        }
+    }
+    let mut state_drift = Metric::new(
+        "state_drift",
+        "Whether current network state differs from desired state",
+        MetricType::Gauge,
+    );
+    state_drift
+        .samples
+        .push((String::new(), (cur_value != des_value) as u64));
+    Ok(vec![iface_drift, state_drift])
+}
+
+fn read_sysfs_u64(iface_name: &str, file_name: &str) -> Option<u64> {
+    std::fs::read_to_string(format!(
+        "{}/{}/{}",
+        SYSFS_NET_PATH, iface_name, file_name
+    ))
+    .ok()
+    .and_then(|s| s.trim().parse::<u64>().ok())
+}
+
+pub(crate) fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
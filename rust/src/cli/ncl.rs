@@ -1,18 +1,49 @@
+mod diff;
 mod error;
+mod gen_conf;
+mod interactive;
+mod metrics;
+mod persist;
+mod query;
+mod state;
+mod table;
+mod unit_tests;
 
+use std::collections::BTreeMap;
 use std::io::{self, Read};
 
 use env_logger::Builder;
 use log::LevelFilter;
-use nmstate::{DnsState, NetworkState, RouteRules, Routes};
+use nmstate::{
+    AuditLogBackend, DnsState, ErrorKind, HostNameState, Nat64Status,
+    NetworkState, OvsDbStatus, RouteRules, Routes,
+};
 use serde::Serialize;
 use serde_yaml::{self, Value};
 
+use crate::diff::{
+    gen_colored_diff, purge_unknown_iface_type, trim_to_desired,
+};
 use crate::error::CliError;
+use crate::gen_conf::{apply_offline, gen_conf, NM_KEYFILE_SELINUX_LABEL};
+use crate::interactive::apply_interactive;
+use crate::metrics::gen_metrics;
+use crate::persist::persist_check;
+use crate::query::{query_result_to_string, query_value};
+use crate::state::{state_from_file, state_from_reader, states_from_reader};
+use crate::table::ifaces_to_table;
 
 const SUB_CMD_GEN_CONF: &str = "gc";
 const SUB_CMD_SHOW: &str = "show";
 const SUB_CMD_APPLY: &str = "apply";
+const SUB_CMD_METRICS: &str = "metrics";
+const SUB_CMD_PERSIST_CHECK: &str = "persist-check";
+
+const DEFAULT_CONFIRM_TIMEOUT: &str = "60";
+
+const OUTPUT_YAML: &str = "yaml";
+const OUTPUT_JSON: &str = "json";
+const OUTPUT_TABLE: &str = "table";
 
 fn main() {
     let matches = clap::App::new("nmstatectl")
@@ -27,6 +58,13 @@ fn main() {
                 .help("Set verbose level")
                 .global(true),
         )
+        .arg(
+            clap::Arg::with_name("JSON_ERROR")
+                .long("json-error")
+                .takes_value(false)
+                .help("Emit error as JSON object on stderr")
+                .global(true),
+        )
         .subcommand(
             clap::SubCommand::with_name(SUB_CMD_SHOW)
                 .about("Show network state")
@@ -41,6 +79,97 @@ fn main() {
                         .long("kernel")
                         .takes_value(false)
                         .help("Show kernel network state only"),
+                )
+                .arg(
+                    clap::Arg::with_name("RUNNING_CONFIG")
+                        .short("r")
+                        .long("running-config")
+                        .takes_value(false)
+                        .help("Show running configuration only"),
+                )
+                .arg(
+                    clap::Arg::with_name("SHOW_SECRETS")
+                        .short("s")
+                        .long("show-secrets")
+                        .takes_value(false)
+                        .help("Show secrets(hide by default)"),
+                )
+                .arg(
+                    clap::Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .takes_value(true)
+                        .possible_values(&[
+                            OUTPUT_YAML,
+                            OUTPUT_JSON,
+                            OUTPUT_TABLE,
+                        ])
+                        .default_value(OUTPUT_YAML)
+                        .help("Output format"),
+                )
+                .arg(
+                    clap::Arg::with_name("DIFF_AGAINST")
+                        .long("diff-against")
+                        .takes_value(true)
+                        .value_name("STATE_FILE")
+                        .conflicts_with("IFNAME")
+                        .help(
+                            "Show the difference between desired state \
+                            file and current network state",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("ANONYMIZE")
+                        .long("anonymize")
+                        .takes_value(false)
+                        .conflicts_with("SHOW_SECRETS")
+                        .help(
+                            "Hide secrets and scramble MAC addresses, public \
+                            IP addresses and hostnames, suitable for \
+                            attaching to bug report",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("CURRENT")
+                        .long("current")
+                        .takes_value(true)
+                        .value_name("STATE_FILE")
+                        .conflicts_with_all(&[
+                            "KERNEL",
+                            "RUNNING_CONFIG",
+                            "SHOW_SECRETS",
+                        ])
+                        .help(
+                            "Use the recorded state file as current network \
+                            state instead of querying the system",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("CAPABILITIES")
+                        .long("capabilities")
+                        .takes_value(false)
+                        .conflicts_with_all(&[
+                            "IFNAME",
+                            "DIFF_AGAINST",
+                            "QUERY",
+                            "CURRENT",
+                        ])
+                        .help(
+                            "Show the capabilities and health of \
+                            backends, like OpenvSwitch database and \
+                            NAT64 prefix discovery",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("QUERY")
+                        .long("query")
+                        .takes_value(true)
+                        .value_name("EXPRESSION")
+                        .conflicts_with_all(&["IFNAME", "DIFF_AGAINST"])
+                        .help(
+                            "Only show the values matching expression, \
+                            example: interfaces[name==\"bond0\"].mtu",
+                        ),
                 ),
         )
         .subcommand(
@@ -68,6 +197,87 @@ fn main() {
                         .long("kernel")
                         .takes_value(false)
                         .help("Apply network state to kernel only"),
+                )
+                .arg(
+                    clap::Arg::with_name("DAD_CHECK")
+                        .long("dad-check")
+                        .takes_value(false)
+                        .help(
+                            "Probe newly added static IPv4 addresses and \
+                            fail if any of them is used by other host",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("STRICT_VETH_TYPE")
+                        .long("strict-veth-type")
+                        .takes_value(false)
+                        .help(
+                            "Do not treat veth and ethernet as the same \
+                            interface type",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("INTERACTIVE")
+                        .short("i")
+                        .long("interactive")
+                        .takes_value(false)
+                        .requires("STATE_FILE")
+                        .conflicts_with("KERNEL")
+                        .help(
+                            "Show the changes and ask for confirmation \
+                            before and after applying, rollback if not \
+                            confirmed in time",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("SEQUENTIAL")
+                        .long("sequential")
+                        .takes_value(false)
+                        .conflicts_with("INTERACTIVE")
+                        .help(
+                            "Apply each YAML document as individual state \
+                            in sequence instead of merging them",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("OFFLINE")
+                        .long("offline")
+                        .takes_value(false)
+                        .conflicts_with_all(&[
+                            "KERNEL",
+                            "INTERACTIVE",
+                            "SEQUENTIAL",
+                        ])
+                        .help(
+                            "Do not touch the system, print the \
+                            NetworkManager keyfiles apply would save when \
+                            no profile exists, identical to gen-conf output",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("AUDIT_LOG")
+                        .long("audit-log")
+                        .takes_value(true)
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .number_of_values(1)
+                        .value_name("BACKEND")
+                        .possible_values(&["journald", "auditd"])
+                        .help(
+                            "Emit audit record of apply and rollback to \
+                            journald or Linux audit",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("TIMEOUT")
+                        .long("timeout")
+                        .takes_value(true)
+                        .value_name("SECONDS")
+                        .default_value(DEFAULT_CONFIRM_TIMEOUT)
+                        .help(
+                            "Seconds to wait for confirmation in \
+                            interactive mode",
+                        ),
                 ),
         )
         .subcommand(
@@ -78,6 +288,65 @@ fn main() {
                         .required(true)
                         .index(1)
                         .help("Network state file"),
+                )
+                .arg(
+                    clap::Arg::with_name("OUTPUT_DIR")
+                        .short("o")
+                        .long("output-dir")
+                        .takes_value(true)
+                        .value_name("DIR")
+                        .help(
+                            "Write NetworkManager keyfiles into specified \
+                            folder with 0600 permission and root ownership",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("SELINUX_LABEL")
+                        .long("selinux-label")
+                        .takes_value(true)
+                        .min_values(0)
+                        .value_name("CONTEXT")
+                        .requires("OUTPUT_DIR")
+                        .help(
+                            "Set SELinux label on written keyfiles, default \
+                            to the label of NetworkManager keyfile folder",
+                        ),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name(SUB_CMD_METRICS)
+                .about("Show network state in Prometheus text format")
+                .arg(
+                    clap::Arg::with_name("STATE_FILE")
+                        .required(false)
+                        .index(1)
+                        .help(
+                            "Desired network state file for reporting \
+                            drift status",
+                        ),
+                )
+                .arg(
+                    clap::Arg::with_name("KERNEL")
+                        .short("k")
+                        .long("kernel")
+                        .takes_value(false)
+                        .help("Show kernel network state only"),
+                ),
+        )
+        .subcommand(
+            clap::SubCommand::with_name(SUB_CMD_PERSIST_CHECK)
+                .about(
+                    "Report config which is not stored on disk and will be \
+                    lost after reboot",
+                )
+                .arg(
+                    clap::Arg::with_name("STATE_FILE")
+                        .required(false)
+                        .index(1)
+                        .help(
+                            "Desired network state file, check current \
+                            state if not defined",
+                        ),
                 ),
         )
         .get_matches();
@@ -99,27 +368,104 @@ fn main() {
     }
     log_builder.init();
 
+    let json_error = matches.is_present("JSON_ERROR");
+
     if let Some(matches) = matches.subcommand_matches(SUB_CMD_GEN_CONF) {
         if let Some(file_path) = matches.value_of("STATE_FILE") {
-            print_result_and_exit(gen_conf(file_path));
+            let selinux_label = if matches.is_present("SELINUX_LABEL") {
+                Some(
+                    matches
+                        .value_of("SELINUX_LABEL")
+                        .unwrap_or(NM_KEYFILE_SELINUX_LABEL),
+                )
+            } else {
+                None
+            };
+            print_result_and_exit(
+                gen_conf(
+                    file_path,
+                    matches.value_of("OUTPUT_DIR"),
+                    selinux_label,
+                ),
+                json_error,
+            );
         }
     } else if let Some(matches) = matches.subcommand_matches(SUB_CMD_SHOW) {
-        print_result_and_exit(show(matches));
+        print_result_and_exit(show(matches), json_error);
+    } else if let Some(matches) = matches.subcommand_matches(SUB_CMD_METRICS) {
+        print_result_and_exit(
+            gen_metrics(
+                matches.is_present("KERNEL"),
+                matches.value_of("STATE_FILE"),
+            ),
+            json_error,
+        );
+    } else if let Some(matches) =
+        matches.subcommand_matches(SUB_CMD_PERSIST_CHECK)
+    {
+        print_result_and_exit(
+            persist_check(matches.value_of("STATE_FILE")),
+            json_error,
+        );
     } else if let Some(matches) = matches.subcommand_matches(SUB_CMD_APPLY) {
         let is_kernel = matches.is_present("KERNEL");
         let no_verify = matches.is_present("NO_VERIFY");
+        let dad_check = matches.is_present("DAD_CHECK");
+        let strict_veth_type = matches.is_present("STRICT_VETH_TYPE");
+        let audit_log_backends = parse_audit_log_backends(matches);
+        let sequential = matches.is_present("SEQUENTIAL");
+        if matches.is_present("OFFLINE") {
+            print_result_and_exit(
+                apply_offline(matches.value_of("STATE_FILE")),
+                json_error,
+            );
+        }
+        if matches.is_present("INTERACTIVE") {
+            print_result_and_exit(
+                parse_timeout(matches).and_then(|timeout| {
+                    apply_interactive(
+                        matches.value_of("STATE_FILE").unwrap_or_default(),
+                        no_verify,
+                        dad_check,
+                        strict_veth_type,
+                        timeout,
+                        &audit_log_backends,
+                    )
+                }),
+                json_error,
+            );
+        }
         if let Some(file_path) = matches.value_of("STATE_FILE") {
-            print_result_and_exit(apply_from_file(
-                file_path, is_kernel, no_verify,
-            ));
+            print_result_and_exit(
+                apply_from_file(
+                    file_path,
+                    is_kernel,
+                    no_verify,
+                    dad_check,
+                    strict_veth_type,
+                    &audit_log_backends,
+                    sequential,
+                ),
+                json_error,
+            );
         } else {
-            print_result_and_exit(apply_from_stdin(is_kernel, no_verify));
+            print_result_and_exit(
+                apply_from_stdin(
+                    is_kernel,
+                    no_verify,
+                    dad_check,
+                    strict_veth_type,
+                    &audit_log_backends,
+                    sequential,
+                ),
+                json_error,
+            );
         }
     }
 }
 
 // Use T instead of String where T has Serialize
-fn print_result_and_exit(result: Result<String, CliError>) {
+fn print_result_and_exit(result: Result<String, CliError>, json_error: bool) {
     match result {
         Ok(s) => {
             println!("{}", s);
@@ -127,21 +473,21 @@ fn print_result_and_exit(result: Result<String, CliError>) {
         }
         Err(e) => {
             eprintln!("{}", e);
-            std::process::exit(1);
+            if json_error {
+                eprintln!("{}", e.to_json_string());
+            }
+            std::process::exit(e.exit_code());
         }
     }
 }
 
-fn gen_conf(file_path: &str) -> Result<String, CliError> {
-    let fd = std::fs::File::open(file_path)?;
-    let net_state: NetworkState = serde_yaml::from_reader(fd)?;
-    let confs = net_state.gen_conf()?;
-    Ok(serde_yaml::to_string(&confs)?)
-}
-
 #[derive(Clone, Debug, PartialEq, Serialize)]
 struct SortedNetworkState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hostname: Option<HostNameState>,
+    #[serde(rename = "dns-resolver")]
     dns: DnsState,
+    #[serde(rename = "route-rules")]
     rules: RouteRules,
     routes: Routes,
     interfaces: Vec<Value>,
@@ -183,6 +529,7 @@ fn sort_netstate(
             }
         }
         return Ok(SortedNetworkState {
+            hostname: net_state.hostname,
             interfaces: new_ifaces,
             routes: net_state.routes,
             rules: net_state.rules,
@@ -191,6 +538,7 @@ fn sort_netstate(
     }
 
     Ok(SortedNetworkState {
+        hostname: net_state.hostname,
         interfaces: Vec::new(),
         routes: net_state.routes,
         rules: net_state.rules,
@@ -199,12 +547,60 @@ fn sort_netstate(
 }
 
 // Ordering the outputs
+#[derive(Debug, Serialize)]
+struct Capabilities {
+    ovsdb: OvsDbStatus,
+    nat64: Nat64Status,
+}
+
+fn show_capabilities(output_format: &str) -> Result<String, CliError> {
+    let caps = Capabilities {
+        ovsdb: OvsDbStatus::retrieve(),
+        nat64: Nat64Status::retrieve(),
+    };
+    Ok(match output_format {
+        OUTPUT_JSON => serde_json::to_string_pretty(&caps)?,
+        _ => serde_yaml::to_string(&caps)?,
+    })
+}
+
 fn show(matches: &clap::ArgMatches) -> Result<String, CliError> {
-    let mut net_state = NetworkState::new();
-    if matches.is_present("KERNEL") {
-        net_state.set_kernel_only(true);
+    if matches.is_present("CAPABILITIES") {
+        return show_capabilities(
+            matches.value_of("OUTPUT").unwrap_or(OUTPUT_YAML),
+        );
     }
-    net_state.retrieve()?;
+    let net_state = if let Some(file_path) = matches.value_of("CURRENT") {
+        state_from_file(file_path)?
+    } else {
+        let mut net_state = NetworkState::new();
+        if matches.is_present("KERNEL") {
+            net_state.set_kernel_only(true);
+        }
+        net_state.set_running_config_only(matches.is_present("RUNNING_CONFIG"));
+        net_state.set_include_secrets(matches.is_present("SHOW_SECRETS"));
+        net_state.retrieve()?;
+        net_state
+    };
+    let net_state = if matches.is_present("ANONYMIZE") {
+        net_state.anonymize()?
+    } else {
+        net_state
+    };
+    if let Some(file_path) = matches.value_of("DIFF_AGAINST") {
+        return show_diff(
+            file_path,
+            net_state,
+            matches.value_of("CURRENT").unwrap_or("current"),
+        );
+    }
+    if let Some(query) = matches.value_of("QUERY") {
+        return query_result_to_string(&query_value(
+            &serde_yaml::to_value(&sort_netstate(net_state)?)?,
+            query,
+        )?);
+    }
+    let output_format = matches.value_of("OUTPUT").unwrap_or(OUTPUT_YAML);
     Ok(if let Some(ifname) = matches.value_of("IFNAME") {
         let mut new_net_state = NetworkState::new();
         new_net_state.set_kernel_only(matches.is_present("KERNEL"));
@@ -213,39 +609,142 @@ fn show(matches: &clap::ArgMatches) -> Result<String, CliError> {
                 new_net_state.append_interface_data(iface.clone())
             }
         }
-        serde_yaml::to_string(&new_net_state)?
+        net_state_to_string(new_net_state, output_format)?
     } else {
-        serde_yaml::to_string(&sort_netstate(net_state)?)?
+        net_state_to_string(net_state, output_format)?
     })
 }
 
+fn net_state_to_string(
+    net_state: NetworkState,
+    output_format: &str,
+) -> Result<String, CliError> {
+    Ok(match output_format {
+        OUTPUT_TABLE => ifaces_to_table(&net_state),
+        OUTPUT_JSON => {
+            serde_json::to_string_pretty(&sort_netstate(net_state)?)?
+        }
+        _ => serde_yaml::to_string(&sort_netstate(net_state)?)?,
+    })
+}
+
+// The `-` lines are desired but not found in current, the `+` lines are found
+// in current but not desired.
+fn show_diff(
+    file_path: &str,
+    cur_net_state: NetworkState,
+    cur_name: &str,
+) -> Result<String, CliError> {
+    let mut des_value =
+        serde_yaml::to_value(&sort_netstate(state_from_file(file_path)?)?)?;
+    purge_unknown_iface_type(&mut des_value);
+    let cur_value = trim_to_desired(
+        &serde_yaml::to_value(&sort_netstate(cur_net_state)?)?,
+        &des_value,
+    );
+    Ok(gen_colored_diff(
+        file_path,
+        &serde_yaml::to_string(&des_value)?,
+        cur_name,
+        &serde_yaml::to_string(&cur_value)?,
+        std::env::var_os("NO_COLOR").is_none(),
+    ))
+}
+
+fn parse_timeout(matches: &clap::ArgMatches) -> Result<u32, CliError> {
+    let timeout = matches.value_of("TIMEOUT").unwrap_or_default();
+    timeout.parse::<u32>().map_err(|e| CliError {
+        msg: format!("Invalid timeout {}: {}", timeout, e),
+        kind: Some(ErrorKind::InvalidArgument),
+        context: BTreeMap::new(),
+    })
+}
+
+// The possible values are limited by clap
+fn parse_audit_log_backends(
+    matches: &clap::ArgMatches,
+) -> Vec<AuditLogBackend> {
+    matches
+        .values_of("AUDIT_LOG")
+        .map(|values| {
+            values
+                .filter_map(|v| match v {
+                    "journald" => Some(AuditLogBackend::Journald),
+                    "auditd" => Some(AuditLogBackend::Auditd),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn apply_from_stdin(
     kernel_only: bool,
     no_verify: bool,
+    dad_check: bool,
+    strict_veth_type: bool,
+    audit_log_backends: &[AuditLogBackend],
+    sequential: bool,
 ) -> Result<String, CliError> {
-    apply(io::stdin(), kernel_only, no_verify)
+    apply(
+        io::stdin(),
+        kernel_only,
+        no_verify,
+        dad_check,
+        strict_veth_type,
+        audit_log_backends,
+        sequential,
+    )
 }
 
 fn apply_from_file(
     file_path: &str,
     kernel_only: bool,
     no_verify: bool,
+    dad_check: bool,
+    strict_veth_type: bool,
+    audit_log_backends: &[AuditLogBackend],
+    sequential: bool,
 ) -> Result<String, CliError> {
-    apply(std::fs::File::open(file_path)?, kernel_only, no_verify)
+    apply(
+        std::fs::File::open(file_path)?,
+        kernel_only,
+        no_verify,
+        dad_check,
+        strict_veth_type,
+        audit_log_backends,
+        sequential,
+    )
 }
 
 fn apply<R>(
     reader: R,
     kernel_only: bool,
     no_verify: bool,
+    dad_check: bool,
+    strict_veth_type: bool,
+    audit_log_backends: &[AuditLogBackend],
+    sequential: bool,
 ) -> Result<String, CliError>
 where
     R: Read,
 {
-    let mut net_state: NetworkState = serde_yaml::from_reader(reader)?;
-    net_state.set_kernel_only(kernel_only);
-    net_state.set_verify_change(!no_verify);
-    net_state.apply()?;
-    let sorted_net_state = sort_netstate(net_state)?;
-    Ok(serde_yaml::to_string(&sorted_net_state)?)
+    let mut net_states = if sequential {
+        states_from_reader(reader)?
+    } else {
+        vec![state_from_reader(reader)?]
+    };
+    for net_state in net_states.iter_mut() {
+        net_state.set_kernel_only(kernel_only);
+        net_state.set_verify_change(!no_verify);
+        net_state.set_dad_check(dad_check);
+        net_state.set_strict_veth_type(strict_veth_type);
+        net_state.set_audit_log_backends(audit_log_backends);
+    }
+    NetworkState::apply_batch(&net_states)?;
+    let mut ret = String::new();
+    for net_state in net_states {
+        ret.push_str(&serde_yaml::to_string(&sort_netstate(net_state)?)?);
+    }
+    Ok(ret)
 }
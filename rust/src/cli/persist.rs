@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use nmstate::{ErrorKind, NetworkState, PersistIssue, PersistIssueReason};
+
+use crate::error::CliError;
+use crate::state::state_from_file;
+
+// Check whether desired state (or current state if no state file defined)
+// will survive reboot. When checking current state, interfaces without any
+// NetworkManager profile are ignored as they are not managed by nmstate.
+pub(crate) fn persist_check(
+    state_file: Option<&str>,
+) -> Result<String, CliError> {
+    let issues = match state_file {
+        Some(state_file) => state_from_file(state_file)?.persist_check()?,
+        None => {
+            let mut net_state = NetworkState::new();
+            net_state.retrieve()?;
+            net_state
+                .persist_check()?
+                .into_iter()
+                .filter(|i| i.reason != PersistIssueReason::NoProfile)
+                .collect()
+        }
+    };
+    persist_issues_to_result(&issues)
+}
+
+// Any issue found fails with VerificationError, hence non-zero exit code.
+pub(crate) fn persist_issues_to_result(
+    issues: &[PersistIssue],
+) -> Result<String, CliError> {
+    if issues.is_empty() {
+        Ok("All config is persistent".to_string())
+    } else {
+        Err(CliError {
+            msg: format!(
+                "Found config which will be lost after reboot:\n{}",
+                serde_yaml::to_string(issues)?
+            ),
+            kind: Some(ErrorKind::VerificationError),
+            context: BTreeMap::new(),
+        })
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use nmstate::ErrorKind;
+use serde_yaml::Value;
+
+use crate::error::CliError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum QueryFilter {
+    Index(usize),
+    // Property path and expected value
+    Equal(Vec<String>, String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct QuerySegment {
+    key: Option<String>,
+    filters: Vec<QueryFilter>,
+}
+
+// Extract values from serialized state using expression like:
+//      interfaces[name=="bond0"].link-aggregation.mode
+//      interfaces[type==ethernet][0].name
+// Every matched value is returned.
+pub(crate) fn query_value(
+    value: &Value,
+    query: &str,
+) -> Result<Vec<Value>, CliError> {
+    let mut values = vec![value.clone()];
+    for segment in parse_query(query)? {
+        let mut new_values = Vec::new();
+        for value in values {
+            let value = match segment.key.as_ref() {
+                Some(key) => match value.get(key.as_str()) {
+                    Some(v) => v.clone(),
+                    None => continue,
+                },
+                None => value,
+            };
+            new_values.extend(apply_filters(value, &segment.filters));
+        }
+        values = new_values;
+    }
+    Ok(values)
+}
+
+// Scalar is printed as raw string, others in YAML.
+pub(crate) fn query_result_to_string(
+    values: &[Value],
+) -> Result<String, CliError> {
+    match values {
+        [value] => match value_to_raw_string(value) {
+            Some(s) => Ok(s),
+            None => Ok(serde_yaml::to_string(value)?),
+        },
+        _ => Ok(serde_yaml::to_string(values)?),
+    }
+}
+
+// The filters are applied in sequence to the items of array, so
+// `[type==ethernet][0]` means the first ethernet interface.
+fn apply_filters(value: Value, filters: &[QueryFilter]) -> Vec<Value> {
+    if filters.is_empty() {
+        return vec![value];
+    }
+    let mut items = match value {
+        Value::Sequence(items) => items,
+        _ => return Vec::new(),
+    };
+    for filter in filters {
+        items = match filter {
+            QueryFilter::Index(index) => {
+                items.into_iter().nth(*index).into_iter().collect()
+            }
+            QueryFilter::Equal(path, expected) => items
+                .into_iter()
+                .filter(|item| {
+                    get_by_path(item, path)
+                        .and_then(value_to_raw_string)
+                        .as_ref()
+                        == Some(expected)
+                })
+                .collect(),
+        };
+    }
+    items
+}
+
+fn get_by_path<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let mut ret = value;
+    for key in path {
+        ret = ret.get(key.as_str())?;
+    }
+    Some(ret)
+}
+
+fn value_to_raw_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some("null".to_string()),
+        _ => None,
+    }
+}
+
+fn parse_query(query: &str) -> Result<Vec<QuerySegment>, CliError> {
+    let mut ret = Vec::new();
+    for segment_str in split_outside_brackets(query, '.')? {
+        let (key, mut remain) = match segment_str.find('[') {
+            Some(pos) => (&segment_str[..pos], &segment_str[pos..]),
+            None => (segment_str.as_str(), ""),
+        };
+        let mut segment = QuerySegment {
+            key: if key.is_empty() {
+                None
+            } else {
+                Some(key.to_string())
+            },
+            filters: Vec::new(),
+        };
+        while !remain.is_empty() {
+            let end = find_closing_bracket(remain)
+                .ok_or_else(|| invalid_query(query, "unclosed bracket"))?;
+            segment
+                .filters
+                .push(parse_filter(query, remain[1..end].trim())?);
+            remain = &remain[end + 1..];
+            if !remain.is_empty() && !remain.starts_with('[') {
+                return Err(invalid_query(
+                    query,
+                    "unexpected characters after bracket",
+                ));
+            }
+        }
+        if segment.key.is_none() && segment.filters.is_empty() {
+            return Err(invalid_query(query, "empty property name"));
+        }
+        ret.push(segment);
+    }
+    Ok(ret)
+}
+
+fn parse_filter(query: &str, filter: &str) -> Result<QueryFilter, CliError> {
+    if let Some(pos) = filter.find("==") {
+        let path: Vec<String> = filter[..pos]
+            .trim()
+            .split('.')
+            .map(|k| k.to_string())
+            .collect();
+        if path.iter().any(|k| k.is_empty()) {
+            return Err(invalid_query(query, "empty property name in filter"));
+        }
+        let expected = filter[pos + 2..].trim();
+        let expected = if expected.len() >= 2
+            && ((expected.starts_with('"') && expected.ends_with('"'))
+                || (expected.starts_with('\'') && expected.ends_with('\'')))
+        {
+            &expected[1..expected.len() - 1]
+        } else {
+            expected
+        };
+        Ok(QueryFilter::Equal(path, expected.to_string()))
+    } else {
+        filter
+            .parse::<usize>()
+            .map(QueryFilter::Index)
+            .map_err(|_| {
+                invalid_query(query, "filter should be index or `key==value`")
+            })
+    }
+}
+
+// Split by separator which is not quoted or inside of brackets.
+fn split_outside_brackets(
+    query: &str,
+    separator: char,
+) -> Result<Vec<String>, CliError> {
+    let mut ret = Vec::new();
+    let mut cur = String::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    for c in query.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| invalid_query(query, "unopened bracket"))?
+            }
+            (None, c) if c == separator && depth == 0 => {
+                ret.push(std::mem::take(&mut cur));
+                continue;
+            }
+            _ => (),
+        }
+        cur.push(c);
+    }
+    if quote.is_some() {
+        return Err(invalid_query(query, "unclosed quote"));
+    }
+    ret.push(cur);
+    Ok(ret)
+}
+
+// The input should start with `[`, return the index of paired `]`.
+fn find_closing_bracket(input: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (index, c) in input.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, ']') => return Some(index),
+            _ => (),
+        }
+    }
+    None
+}
+
+fn invalid_query(query: &str, reason: &str) -> CliError {
+    CliError {
+        msg: format!("Invalid query {}: {}", query, reason),
+        kind: Some(ErrorKind::InvalidArgument),
+        context: BTreeMap::new(),
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+
+use nmstate::{ErrorKind, NetworkState};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::error::CliError;
+
+const HOSTNAME_FILE: &str = "/proc/sys/kernel/hostname";
+const TEMPLATE_VAR_HOSTNAME: &str = "hostname";
+const TEMPLATE_VAR_MAC_PREFIX: &str = "mac:";
+// The `${iface.<property>}` variables are expanded by nmstate library against
+// the interface they are defined in.
+const TEMPLATE_VAR_IFACE_PREFIX: &str = "iface.";
+const YAML_MERGE_KEY: &str = "<<";
+
+pub(crate) fn state_from_file(
+    file_path: &str,
+) -> Result<NetworkState, CliError> {
+    state_from_reader(std::fs::File::open(file_path)?)
+}
+
+// Multiple YAML documents are merged into single state, document after
+// takes precedence.
+pub(crate) fn state_from_reader<R>(reader: R) -> Result<NetworkState, CliError>
+where
+    R: Read,
+{
+    let mut merged = Value::Null;
+    for value in yaml_docs_from_reader(reader)? {
+        merge_state_value(&mut merged, value);
+    }
+    Ok(serde_yaml::from_value(merged)?)
+}
+
+// Each YAML document is treated as a individual state.
+pub(crate) fn states_from_reader<R>(
+    reader: R,
+) -> Result<Vec<NetworkState>, CliError>
+where
+    R: Read,
+{
+    let mut ret = Vec::new();
+    for value in yaml_docs_from_reader(reader)? {
+        ret.push(serde_yaml::from_value(value)?);
+    }
+    if ret.is_empty() {
+        ret.push(NetworkState::new());
+    }
+    Ok(ret)
+}
+
+// Empty documents are ignored.
+fn yaml_docs_from_reader<R>(mut reader: R) -> Result<Vec<Value>, CliError>
+where
+    R: Read,
+{
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let mut ret = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(&content) {
+        let mut value = Value::deserialize(document)?;
+        if value.is_null() {
+            continue;
+        }
+        resolve_yaml_merge_keys(&mut value)?;
+        expand_template_vars(&mut value)?;
+        ret.push(value);
+    }
+    Ok(ret)
+}
+
+// Interfaces with the same name and type are merged, routes and route rules
+// are appended, other properties are merged recursively.
+pub(crate) fn merge_state_value(value: &mut Value, other: Value) {
+    let (map, other_map) = match (&mut *value, other) {
+        (Value::Mapping(map), Value::Mapping(other_map)) => (map, other_map),
+        (_, other) => {
+            *value = other;
+            return;
+        }
+    };
+    for (key, other_v) in other_map {
+        let v = match map.get_mut(&key) {
+            Some(v) => v,
+            None => {
+                map.insert(key, other_v);
+                continue;
+            }
+        };
+        match key.as_str() {
+            Some("interfaces") => merge_ifaces_value(v, other_v),
+            Some("routes") | Some("route-rules") => {
+                merge_config_list_value(v, other_v)
+            }
+            _ => merge_yaml_value(v, other_v),
+        }
+    }
+}
+
+fn merge_ifaces_value(value: &mut Value, other: Value) {
+    let (ifaces, other_ifaces) = match (&mut *value, other) {
+        (Value::Sequence(ifaces), Value::Sequence(other_ifaces)) => {
+            (ifaces, other_ifaces)
+        }
+        (_, other) => {
+            *value = other;
+            return;
+        }
+    };
+    for other_iface in other_ifaces {
+        match ifaces.iter_mut().find(|i| is_same_iface(i, &other_iface)) {
+            Some(iface) => merge_yaml_value(iface, other_iface),
+            None => ifaces.push(other_iface),
+        }
+    }
+}
+
+// Interface without type defined matches any type.
+fn is_same_iface(iface: &Value, other: &Value) -> bool {
+    let name = iface.get("name").and_then(|n| n.as_str());
+    let iface_type = iface.get("type").and_then(|t| t.as_str());
+    let other_type = other.get("type").and_then(|t| t.as_str());
+    name.is_some()
+        && name == other.get("name").and_then(|n| n.as_str())
+        && (iface_type.is_none()
+            || other_type.is_none()
+            || iface_type == other_type)
+}
+
+fn merge_config_list_value(value: &mut Value, other: Value) {
+    if let (Value::Mapping(map), Value::Mapping(other_map)) =
+        (&mut *value, &other)
+    {
+        let config_key = Value::String("config".to_string());
+        if let (Some(Value::Sequence(config)), Some(Value::Sequence(other))) =
+            (map.get_mut(&config_key), other_map.get(&config_key))
+        {
+            config.extend(other.iter().cloned());
+            for (k, v) in other_map {
+                if k != &config_key {
+                    map.insert(k.clone(), v.clone());
+                }
+            }
+            return;
+        }
+    }
+    merge_yaml_value(value, other);
+}
+
+fn merge_yaml_value(value: &mut Value, other: Value) {
+    match (&mut *value, other) {
+        (Value::Mapping(map), Value::Mapping(other_map)) => {
+            for (k, other_v) in other_map {
+                match map.get_mut(&k) {
+                    Some(v) => merge_yaml_value(v, other_v),
+                    None => {
+                        map.insert(k, other_v);
+                    }
+                }
+            }
+        }
+        (_, other) => *value = other,
+    }
+}
+
+// The serde_yaml resolves anchors and aliases, but treats merge key `<<` as
+// normal string key. Following YAML 1.1 merge key spec, keys defined in
+// mapping itself take precedence over merged ones, and earlier mapping in
+// merge sequence takes precedence over later ones.
+pub(crate) fn resolve_yaml_merge_keys(
+    value: &mut Value,
+) -> Result<(), CliError> {
+    match value {
+        Value::Mapping(map) => {
+            let merge_key = Value::String(YAML_MERGE_KEY.to_string());
+            let merge_value = map.remove(&merge_key);
+            for (_, v) in map.iter_mut() {
+                resolve_yaml_merge_keys(v)?;
+            }
+            if let Some(mut merge_value) = merge_value {
+                resolve_yaml_merge_keys(&mut merge_value)?;
+                let merge_maps = match merge_value {
+                    Value::Mapping(m) => vec![m],
+                    Value::Sequence(seq) => seq
+                        .into_iter()
+                        .map(|v| match v {
+                            Value::Mapping(m) => Ok(m),
+                            _ => Err(invalid_merge_value(&v)),
+                        })
+                        .collect::<Result<Vec<Mapping>, CliError>>()?,
+                    v => return Err(invalid_merge_value(&v)),
+                };
+                for merge_map in merge_maps {
+                    for (k, v) in merge_map {
+                        if !map.contains_key(&k) {
+                            map.insert(k, v);
+                        }
+                    }
+                }
+            }
+        }
+        Value::Sequence(seq) => {
+            for v in seq.iter_mut() {
+                resolve_yaml_merge_keys(v)?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn invalid_merge_value(value: &Value) -> CliError {
+    CliError {
+        msg: format!(
+            "The value of YAML merge key `{}` should be a mapping or \
+            a sequence of mappings, but got {:?}",
+            YAML_MERGE_KEY, value
+        ),
+        kind: Some(ErrorKind::InvalidArgument),
+        context: BTreeMap::new(),
+    }
+}
+
+// Expand the `${hostname}` and `${mac:<iface_name>}` variables using
+// current state, so one state file can be shared by multiple hosts. Only the
+// string values are expanded, mapping keys and comments are untouched. Use
+// `$${` for literal `${`.
+pub(crate) fn expand_template_vars(value: &mut Value) -> Result<(), CliError> {
+    let mut cur_state: Option<NetworkState> = None;
+    expand_template_vars_in_value(value, &mut cur_state)
+}
+
+fn expand_template_vars_in_value(
+    value: &mut Value,
+    cur_state: &mut Option<NetworkState>,
+) -> Result<(), CliError> {
+    match value {
+        Value::Mapping(map) => {
+            for (_, v) in map.iter_mut() {
+                expand_template_vars_in_value(v, cur_state)?;
+            }
+        }
+        Value::Sequence(items) => {
+            for v in items.iter_mut() {
+                expand_template_vars_in_value(v, cur_state)?;
+            }
+        }
+        Value::String(s) if s.contains('$') => {
+            *s = expand_template_vars_in_str(s, cur_state)?;
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn expand_template_vars_in_str(
+    content: &str,
+    cur_state: &mut Option<NetworkState>,
+) -> Result<String, CliError> {
+    let mut ret = String::new();
+    let mut remain = content;
+    while let Some(start) = remain.find('$') {
+        ret.push_str(&remain[..start]);
+        let rest = &remain[start..];
+        if let Some(rest) = rest.strip_prefix("$${") {
+            ret.push_str("${");
+            remain = rest;
+            continue;
+        }
+        let var_and_rest = match rest.strip_prefix("${") {
+            Some(r) => r,
+            None => {
+                ret.push('$');
+                remain = &rest[1..];
+                continue;
+            }
+        };
+        let end = match var_and_rest.find('}') {
+            Some(e) => e,
+            None => {
+                return Err(CliError {
+                    msg: format!(
+                        "Template variable is not closed: ${{{}",
+                        var_and_rest.lines().next().unwrap_or_default()
+                    ),
+                    kind: Some(ErrorKind::InvalidArgument),
+                    context: BTreeMap::new(),
+                })
+            }
+        };
+        let var = var_and_rest[..end].trim();
+        if var.starts_with(TEMPLATE_VAR_IFACE_PREFIX) {
+            ret.push_str(&rest[..2 + end + 1]);
+        } else {
+            ret.push_str(&resolve_template_var(var, cur_state)?);
+        }
+        remain = &var_and_rest[end + 1..];
+    }
+    ret.push_str(remain);
+    Ok(ret)
+}
+
+fn resolve_template_var(
+    var: &str,
+    cur_state: &mut Option<NetworkState>,
+) -> Result<String, CliError> {
+    if var == TEMPLATE_VAR_HOSTNAME {
+        Ok(std::fs::read_to_string(HOSTNAME_FILE)?.trim().to_string())
+    } else if let Some(iface_name) = var.strip_prefix(TEMPLATE_VAR_MAC_PREFIX) {
+        if cur_state.is_none() {
+            let mut state = NetworkState::new();
+            state.set_kernel_only(true);
+            state.retrieve()?;
+            *cur_state = Some(state);
+        }
+        cur_state
+            .as_ref()
+            .and_then(|state| {
+                state
+                    .interfaces
+                    .to_vec()
+                    .into_iter()
+                    .find(|i| i.name() == iface_name)
+                    .and_then(|i| i.base_iface().mac_address.clone())
+            })
+            .ok_or_else(|| CliError {
+                msg: format!(
+                    "Failed to resolve template variable ${{{}}}: \
+                    interface {} not found or has no MAC address",
+                    var, iface_name
+                ),
+                kind: Some(ErrorKind::InvalidArgument),
+                context: BTreeMap::new(),
+            })
+    } else {
+        Err(CliError {
+            msg: format!("Unknown template variable ${{{}}}", var),
+            kind: Some(ErrorKind::InvalidArgument),
+            context: BTreeMap::new(),
+        })
+    }
+}
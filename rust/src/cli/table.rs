@@ -0,0 +1,81 @@
+use nmstate::{Interface, InterfaceIpAddr, NetworkState};
+
+const TABLE_HEADERS: [&str; 7] = [
+    "NAME",
+    "TYPE",
+    "STATE",
+    "MAC",
+    "ADDRESSES",
+    "MTU",
+    "CONTROLLER",
+];
+const TABLE_COLUMN_SEPARATOR: &str = "  ";
+const TABLE_EMPTY_CELL: &str = "-";
+
+// Show interfaces in compact table for human, one interface per line.
+pub(crate) fn ifaces_to_table(net_state: &NetworkState) -> String {
+    let mut ifaces = net_state.interfaces.to_vec();
+    ifaces.sort_by(|a, b| a.name().cmp(b.name()));
+
+    let mut rows: Vec<Vec<String>> =
+        vec![TABLE_HEADERS.iter().map(|h| h.to_string()).collect()];
+    rows.extend(ifaces.into_iter().map(iface_to_row));
+
+    let mut widths = vec![0usize; TABLE_HEADERS.len()];
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = std::cmp::max(*width, cell.len());
+        }
+    }
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<1$}", cell, width))
+                .collect::<Vec<String>>()
+                .join(TABLE_COLUMN_SEPARATOR)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn iface_to_row(iface: &Interface) -> Vec<String> {
+    let base_iface = iface.base_iface();
+    let mut addrs: Vec<String> = Vec::new();
+    if let Some(ipv4) = base_iface.ipv4.as_ref().filter(|i| i.enabled) {
+        addrs.extend(ipv4.addresses.iter().map(addr_to_string));
+    }
+    if let Some(ipv6) = base_iface.ipv6.as_ref().filter(|i| i.enabled) {
+        addrs.extend(ipv6.addresses.iter().map(addr_to_string));
+    }
+    vec![
+        base_iface.name.to_string(),
+        base_iface.iface_type.to_string(),
+        base_iface.state.to_string(),
+        base_iface
+            .mac_address
+            .clone()
+            .unwrap_or_else(|| TABLE_EMPTY_CELL.to_string()),
+        if addrs.is_empty() {
+            TABLE_EMPTY_CELL.to_string()
+        } else {
+            addrs.join(",")
+        },
+        base_iface
+            .mtu
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| TABLE_EMPTY_CELL.to_string()),
+        base_iface
+            .controller
+            .clone()
+            .filter(|c| !c.is_empty())
+            .unwrap_or_else(|| TABLE_EMPTY_CELL.to_string()),
+    ]
+}
+
+fn addr_to_string(addr: &InterfaceIpAddr) -> String {
+    format!("{}/{}", addr.ip, addr.prefix_length)
+}
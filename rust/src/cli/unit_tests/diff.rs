@@ -0,0 +1,158 @@
+use serde_yaml::Value;
+
+use crate::diff::{
+    gen_colored_diff, purge_unknown_iface_type, trim_to_desired,
+};
+
+fn yaml_diff(old: &str, new: &str, use_color: bool) -> String {
+    gen_colored_diff("current", old, "desired", new, use_color)
+}
+
+#[test]
+fn test_diff_no_change() {
+    let content = "hostname:\n  config: host-a\n";
+    assert_eq!(yaml_diff(content, content, false), "");
+}
+
+#[test]
+fn test_diff_changed_key() {
+    assert_eq!(
+        yaml_diff(
+            "name: eth1\nmtu: 1500\nstate: up\n",
+            "name: eth1\nmtu: 9000\nstate: up\n",
+            false
+        ),
+        "--- current\n\
+        +++ desired\n\
+        @@ -1,3 +1,3 @@\n \
+        name: eth1\n\
+        -mtu: 1500\n\
+        +mtu: 9000\n \
+        state: up"
+    );
+}
+
+#[test]
+fn test_diff_added_key() {
+    assert_eq!(
+        yaml_diff("name: eth1\n", "name: eth1\nmtu: 9000\n", false),
+        "--- current\n\
+        +++ desired\n\
+        @@ -1,1 +1,2 @@\n \
+        name: eth1\n\
+        +mtu: 9000"
+    );
+}
+
+#[test]
+fn test_diff_removed_key() {
+    assert_eq!(
+        yaml_diff("name: eth1\nmtu: 9000\n", "name: eth1\n", false),
+        "--- current\n\
+        +++ desired\n\
+        @@ -1,2 +1,1 @@\n \
+        name: eth1\n\
+        -mtu: 9000"
+    );
+}
+
+#[test]
+fn test_diff_into_empty() {
+    assert_eq!(
+        yaml_diff("", "mtu: 9000\n", false),
+        "--- current\n+++ desired\n@@ -0,0 +1,1 @@\n+mtu: 9000"
+    );
+}
+
+#[test]
+fn test_diff_hunks_split_by_context() {
+    let old: Vec<String> = (0..10).map(|i| format!("a{}: {}", i, i)).collect();
+    let mut new = old.clone();
+    new[0] = "a0: changed".to_string();
+    new[9] = "a9: changed".to_string();
+    let diff = yaml_diff(&old.join("\n"), &new.join("\n"), false);
+    assert_eq!(diff.matches("@@ -").count(), 2);
+    assert!(diff.contains("@@ -1,4 +1,4 @@"));
+    assert!(diff.contains("@@ -7,4 +7,4 @@"));
+}
+
+#[test]
+fn test_diff_color() {
+    let old = "mtu: 1500\n";
+    let new = "mtu: 9000\n";
+    let diff = yaml_diff(old, new, true);
+    assert!(diff.contains("\x1b[31m-mtu: 1500\x1b[0m"));
+    assert!(diff.contains("\x1b[32m+mtu: 9000\x1b[0m"));
+    assert!(diff.contains("\x1b[36m@@ -1,1 +1,1 @@\x1b[0m"));
+
+    let diff = yaml_diff(old, new, false);
+    assert!(!diff.contains('\x1b'));
+}
+
+#[test]
+fn test_trim_to_desired() {
+    let current: Value = serde_yaml::from_str(
+        r#"---
+hostname:
+  running: host-a
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 1500
+  mac-address: 00:23:45:67:89:1A
+- name: eth1
+  type: ovs-interface
+  mtu: 1400
+- name: eth2
+  type: ethernet
+  mtu: 1500
+"#,
+    )
+    .unwrap();
+    let desired: Value = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 9000
+- name: eth3
+  mtu: 9000
+"#,
+    )
+    .unwrap();
+    let expected: Value = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 1500
+"#,
+    )
+    .unwrap();
+    assert_eq!(trim_to_desired(&current, &desired), expected);
+}
+
+#[test]
+fn test_purge_unknown_iface_type() {
+    let mut state: Value = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: unknown
+- name: eth2
+  type: ethernet
+"#,
+    )
+    .unwrap();
+    purge_unknown_iface_type(&mut state);
+    let expected: Value = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+- name: eth2
+  type: ethernet
+"#,
+    )
+    .unwrap();
+    assert_eq!(state, expected);
+}
@@ -0,0 +1,45 @@
+use nmstate::{ErrorKind, NmstateError};
+
+use crate::error::CliError;
+
+#[test]
+fn test_error_kind_to_exit_code() {
+    for (kind, exit_code) in [
+        (ErrorKind::InvalidArgument, 2),
+        (ErrorKind::PluginFailure, 4),
+        (ErrorKind::Bug, 1),
+        (ErrorKind::VerificationError, 3),
+        (ErrorKind::NotImplementedError, 6),
+        (ErrorKind::KernelIntegerRoundedError, 3),
+        (ErrorKind::DependencyError, 7),
+        (ErrorKind::IpAddressConflict, 8),
+        (ErrorKind::Timeout, 5),
+        (ErrorKind::OvsDbError, 4),
+    ] {
+        let e = CliError::from(NmstateError::new(kind, String::new()));
+        assert_eq!(e.exit_code(), exit_code, "exit code of {}", kind);
+    }
+}
+
+#[test]
+fn test_cli_error_exit_code() {
+    let e = CliError::from(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "not found",
+    ));
+    assert_eq!(e.kind, None);
+    assert_eq!(e.exit_code(), 1);
+}
+
+#[test]
+fn test_cli_error_to_json_string() {
+    let e = CliError::from(NmstateError::new(
+        ErrorKind::Timeout,
+        "timeout".to_string(),
+    ));
+    let value: serde_json::Value =
+        serde_json::from_str(&e.to_json_string()).unwrap();
+    assert_eq!(value["exit-code"], 5);
+    assert_eq!(value["kind"], ErrorKind::Timeout.to_string());
+    assert_eq!(value["code"], ErrorKind::Timeout.code());
+}
@@ -0,0 +1,19 @@
+use crate::gen_conf::write_keyfile;
+
+#[test]
+fn test_write_keyfile_not_follow_symlink() {
+    let dir = std::env::temp_dir()
+        .join(format!("nmstate-gen-conf-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let target = dir.join("target");
+    let link = dir.join("eth1.nmconnection");
+    std::fs::write(&target, "original").unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    let result = write_keyfile(&link, "[connection]\nid=eth1\n", None);
+    let content = std::fs::read_to_string(&target).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(result.is_err());
+    assert_eq!(content, "original");
+}
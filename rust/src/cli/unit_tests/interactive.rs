@@ -0,0 +1,48 @@
+use std::sync::mpsc;
+
+use crate::interactive::is_answered_yes;
+
+fn answer(input: &str, timeout: Option<u32>) -> bool {
+    let (sender, receiver) = mpsc::channel();
+    sender.send(input.to_string()).unwrap();
+    is_answered_yes(&receiver, timeout)
+}
+
+#[test]
+fn test_interactive_answer_yes() {
+    for input in ["y", "Y", "yes", "YES", " yes \r"] {
+        assert!(answer(input, None), "{:?} should commit", input);
+        assert!(answer(input, Some(1)), "{:?} should commit", input);
+    }
+}
+
+#[test]
+fn test_interactive_answer_no() {
+    for input in ["", "n", "no", "ye", "yes please", "N"] {
+        assert!(!answer(input, None), "{:?} should rollback", input);
+        assert!(!answer(input, Some(1)), "{:?} should rollback", input);
+    }
+}
+
+#[test]
+fn test_interactive_answer_timeout() {
+    let (_sender, receiver) = mpsc::channel::<String>();
+    assert!(!is_answered_yes(&receiver, Some(0)));
+}
+
+#[test]
+fn test_interactive_stdin_closed() {
+    let (sender, receiver) = mpsc::channel::<String>();
+    drop(sender);
+    assert!(!is_answered_yes(&receiver, None));
+    assert!(!is_answered_yes(&receiver, Some(1)));
+}
+
+#[test]
+fn test_interactive_only_first_answer_counts() {
+    let (sender, receiver) = mpsc::channel();
+    sender.send("n".to_string()).unwrap();
+    sender.send("y".to_string()).unwrap();
+    assert!(!is_answered_yes(&receiver, None));
+    assert!(is_answered_yes(&receiver, None));
+}
@@ -0,0 +1,129 @@
+use nmstate::NetworkState;
+
+use crate::metrics::{
+    escape_label_value, gen_drift_metrics, gen_iface_metrics, metrics_to_text,
+    Metric, MetricType,
+};
+
+fn gen_net_state(mtu: u64) -> NetworkState {
+    serde_yaml::from_str(&format!(
+        r#"---
+interfaces:
+- name: nmstate-test1
+  type: dummy
+  state: up
+  mtu: {}
+- name: nmstate-test0
+  type: dummy
+  state: down
+"#,
+        mtu
+    ))
+    .unwrap()
+}
+
+#[test]
+fn test_metrics_escape_label_value() {
+    assert_eq!(escape_label_value("eth1"), "eth1");
+    assert_eq!(escape_label_value("a\"b"), "a\\\"b");
+    assert_eq!(escape_label_value("a\\b"), "a\\\\b");
+    assert_eq!(escape_label_value("a\nb"), "a\\nb");
+}
+
+#[test]
+fn test_metric_to_text() {
+    let mut metric =
+        Metric::new("interface_mtu", "MTU of interface", MetricType::Gauge);
+    metric.samples.push(("eth1".to_string(), 1500));
+    metric.samples.push(("a\"b".to_string(), 9000));
+    assert_eq!(
+        metric.to_text(),
+        "# HELP nmstate_interface_mtu MTU of interface\n\
+        # TYPE nmstate_interface_mtu gauge\n\
+        nmstate_interface_mtu{interface=\"eth1\"} 1500\n\
+        nmstate_interface_mtu{interface=\"a\\\"b\"} 9000"
+    );
+}
+
+#[test]
+fn test_metric_to_text_no_label() {
+    let mut metric = Metric::new(
+        "state_drift",
+        "Whether current network state differs from desired state",
+        MetricType::Gauge,
+    );
+    metric.samples.push((String::new(), 0));
+    assert_eq!(
+        metric.to_text(),
+        "# HELP nmstate_state_drift Whether current network state differs \
+        from desired state\n\
+        # TYPE nmstate_state_drift gauge\n\
+        nmstate_state_drift 0"
+    );
+}
+
+#[test]
+fn test_metric_to_text_counter() {
+    let metric = Metric::new(
+        "interface_rx_bytes_total",
+        "Total bytes received",
+        MetricType::Counter,
+    );
+    assert_eq!(
+        metric.to_text(),
+        "# HELP nmstate_interface_rx_bytes_total Total bytes received\n\
+        # TYPE nmstate_interface_rx_bytes_total counter"
+    );
+}
+
+#[test]
+fn test_iface_metrics() {
+    let metrics = gen_iface_metrics(&gen_net_state(1500));
+    let text = metrics_to_text(&metrics);
+
+    // Every family has HELP and TYPE
+    for metric in metrics.iter() {
+        assert!(text.contains(&format!("# HELP {} ", metric.name)));
+        assert!(text.contains(&format!("# TYPE {} ", metric.name)));
+    }
+    assert!(text.contains("# TYPE nmstate_interface_up gauge"));
+    assert!(text.contains("# TYPE nmstate_interface_carrier gauge"));
+    assert!(text.contains("# TYPE nmstate_interface_mtu gauge"));
+    assert!(text.contains("# TYPE nmstate_interface_rx_bytes_total counter"));
+    assert!(text.contains("# TYPE nmstate_interface_tx_dropped_total counter"));
+
+    // Sorted by interface name
+    assert!(text.contains(
+        "nmstate_interface_up{interface=\"nmstate-test0\"} 0\n\
+        nmstate_interface_up{interface=\"nmstate-test1\"} 1"
+    ));
+    assert!(text
+        .contains("nmstate_interface_mtu{interface=\"nmstate-test1\"} 1500"));
+    assert!(
+        !text.contains("nmstate_interface_mtu{interface=\"nmstate-test0\"}")
+    );
+}
+
+#[test]
+fn test_drift_metrics() {
+    let desired = gen_net_state(1500);
+    let text = metrics_to_text(
+        &gen_drift_metrics(&desired, &gen_net_state(1500)).unwrap(),
+    );
+    assert!(text.contains("# TYPE nmstate_interface_drift gauge"));
+    assert!(
+        text.contains("nmstate_interface_drift{interface=\"nmstate-test1\"} 0")
+    );
+    assert!(text.contains("nmstate_state_drift 0"));
+
+    let text = metrics_to_text(
+        &gen_drift_metrics(&desired, &gen_net_state(9000)).unwrap(),
+    );
+    assert!(
+        text.contains("nmstate_interface_drift{interface=\"nmstate-test0\"} 0")
+    );
+    assert!(
+        text.contains("nmstate_interface_drift{interface=\"nmstate-test1\"} 1")
+    );
+    assert!(text.contains("nmstate_state_drift 1"));
+}
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod diff;
+#[cfg(test)]
+mod error;
+#[cfg(test)]
+mod gen_conf;
+#[cfg(test)]
+mod interactive;
+#[cfg(test)]
+mod metrics;
+#[cfg(test)]
+mod persist;
+#[cfg(test)]
+mod query;
+#[cfg(test)]
+mod state;
+#[cfg(test)]
+mod table;
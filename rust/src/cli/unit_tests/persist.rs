@@ -0,0 +1,41 @@
+use nmstate::{ErrorKind, InterfaceType, PersistIssue, PersistIssueReason};
+
+use crate::persist::persist_issues_to_result;
+
+#[test]
+fn test_persist_no_issue() {
+    assert_eq!(
+        persist_issues_to_result(&[]).unwrap(),
+        "All config is persistent"
+    );
+}
+
+#[test]
+fn test_persist_issues_report() {
+    let issues = [
+        PersistIssue {
+            name: "eth1".to_string(),
+            iface_type: InterfaceType::Ethernet,
+            reason: PersistIssueReason::InMemoryOnly,
+        },
+        PersistIssue {
+            name: "bond0".to_string(),
+            iface_type: InterfaceType::Bond,
+            reason: PersistIssueReason::AutoconnectDisabled,
+        },
+    ];
+    let e = persist_issues_to_result(&issues).unwrap_err();
+    assert_eq!(e.kind, Some(ErrorKind::VerificationError));
+    assert_eq!(e.exit_code(), 3);
+    assert_eq!(
+        e.msg,
+        "Found config which will be lost after reboot:\n\
+        ---\n\
+        - name: eth1\n  \
+          type: ethernet\n  \
+          reason: in-memory-only\n\
+        - name: bond0\n  \
+          type: bond\n  \
+          reason: autoconnect-disabled\n"
+    );
+}
@@ -0,0 +1,130 @@
+use nmstate::ErrorKind;
+use serde_yaml::Value;
+
+use crate::query::{query_result_to_string, query_value};
+
+fn gen_state() -> Value {
+    serde_yaml::from_str(
+        r#"---
+hostname:
+  running: host-a
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 1500
+- name: bond0
+  type: bond
+  mtu: 9000
+  link-aggregation:
+    mode: active-backup
+- name: eth2
+  type: ethernet
+  mtu: 1500
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_query_path() {
+    let values = query_value(&gen_state(), "hostname.running").unwrap();
+    assert_eq!(values, vec![Value::from("host-a")]);
+    assert_eq!(query_result_to_string(&values).unwrap(), "host-a");
+}
+
+#[test]
+fn test_query_filter_by_value() {
+    let values = query_value(
+        &gen_state(),
+        r#"interfaces[name=="bond0"].link-aggregation.mode"#,
+    )
+    .unwrap();
+    assert_eq!(values, vec![Value::from("active-backup")]);
+
+    let values =
+        query_value(&gen_state(), "interfaces[type==ethernet].name").unwrap();
+    assert_eq!(values, vec![Value::from("eth1"), Value::from("eth2")]);
+    assert_eq!(
+        query_result_to_string(&values).unwrap(),
+        "---\n- eth1\n- eth2\n"
+    );
+}
+
+#[test]
+fn test_query_filter_by_number() {
+    let values =
+        query_value(&gen_state(), "interfaces[mtu=='9000'].name").unwrap();
+    assert_eq!(values, vec![Value::from("bond0")]);
+}
+
+#[test]
+fn test_query_index() {
+    let values = query_value(&gen_state(), "interfaces[2].name").unwrap();
+    assert_eq!(values, vec![Value::from("eth2")]);
+
+    let values =
+        query_value(&gen_state(), "interfaces[type==ethernet][1].name")
+            .unwrap();
+    assert_eq!(values, vec![Value::from("eth2")]);
+}
+
+#[test]
+fn test_query_index_out_of_range() {
+    let values = query_value(&gen_state(), "interfaces[3].name").unwrap();
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_query_missing_key() {
+    assert!(query_value(&gen_state(), "dns.running").unwrap().is_empty());
+    assert!(query_value(&gen_state(), "interfaces[name==eth9].mtu")
+        .unwrap()
+        .is_empty());
+    // Only bond0 has link-aggregation
+    let values =
+        query_value(&gen_state(), "interfaces.link-aggregation").unwrap();
+    assert!(values.is_empty());
+    let values =
+        query_value(&gen_state(), "interfaces[0].link-aggregation").unwrap();
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_query_filter_on_non_sequence() {
+    let values = query_value(&gen_state(), "hostname[0]").unwrap();
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_query_non_scalar_result_in_yaml() {
+    let values = query_value(
+        &gen_state(),
+        r#"interfaces[name=="bond0"].link-aggregation"#,
+    )
+    .unwrap();
+    assert_eq!(
+        query_result_to_string(&values).unwrap(),
+        "---\nmode: active-backup\n"
+    );
+}
+
+#[test]
+fn test_query_malformed() {
+    for query in [
+        "interfaces[0",
+        "interfaces]",
+        "interfaces[abc]",
+        "interfaces[==eth1]",
+        "interfaces[name==\"eth1]",
+        "interfaces[0]name",
+        "interfaces..name",
+        "",
+    ] {
+        let result = query_value(&gen_state(), query);
+        assert!(result.is_err(), "{}", query);
+        if let Err(e) = result {
+            assert_eq!(e.kind, Some(ErrorKind::InvalidArgument));
+            assert!(e.msg.starts_with("Invalid query"));
+        }
+    }
+}
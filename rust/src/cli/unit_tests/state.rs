@@ -0,0 +1,256 @@
+use serde_yaml::Value;
+
+use crate::state::{
+    expand_template_vars, merge_state_value, resolve_yaml_merge_keys,
+    state_from_reader, states_from_reader,
+};
+
+fn merge_yaml_docs(docs: &[&str]) -> Value {
+    let mut merged = Value::Null;
+    for doc in docs {
+        merge_state_value(&mut merged, serde_yaml::from_str(doc).unwrap());
+    }
+    merged
+}
+
+#[test]
+fn test_template_var_keep_iface_var() {
+    let content = r#"---
+interfaces:
+- name: br0
+  type: ovs-bridge
+  ovs-db:
+    external_ids:
+      iface-id: ${iface.name}
+      mtu: "${ iface.mtu }"
+"#;
+    let mut value: Value = serde_yaml::from_str(content).unwrap();
+    expand_template_vars(&mut value).unwrap();
+    assert_eq!(value, serde_yaml::from_str::<Value>(content).unwrap());
+}
+
+#[test]
+fn test_template_var_unknown() {
+    let mut value: Value = serde_yaml::from_str("hostname: ${foo}\n").unwrap();
+    let result = expand_template_vars(&mut value);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert!(e.msg.contains("foo"));
+    }
+}
+
+#[test]
+fn test_template_var_escape() {
+    let mut value: Value = serde_yaml::from_str(
+        r#"---
+description: "cost $5, literal $${hostname} and $${mac:eth1}"
+"#,
+    )
+    .unwrap();
+    expand_template_vars(&mut value).unwrap();
+    assert_eq!(
+        value["description"].as_str(),
+        Some("cost $5, literal ${hostname} and ${mac:eth1}")
+    );
+}
+
+#[test]
+fn test_template_var_ignore_comments_and_keys() {
+    let mut value: Value = serde_yaml::from_str(
+        r#"---
+# Use ${foo} for bar
+ovs-db:
+  external_ids:
+    ${foo}: 1
+"#,
+    )
+    .unwrap();
+    let expected = value.clone();
+    expand_template_vars(&mut value).unwrap();
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn test_template_var_unclosed() {
+    let mut value: Value =
+        serde_yaml::from_str("hostname: \"${hostname\"\n").unwrap();
+    assert!(expand_template_vars(&mut value).is_err());
+}
+
+#[test]
+fn test_merge_state_ifaces_by_name_and_type() {
+    let merged = merge_yaml_docs(&[
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 1500
+- name: bond0
+  type: bond
+"#,
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: false
+- name: bond0
+  type: team
+- name: eth2
+  state: absent
+"#,
+    ]);
+    let expected: Value = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 1500
+  ipv4:
+    enabled: false
+- name: bond0
+  type: bond
+- name: bond0
+  type: team
+- name: eth2
+  state: absent
+"#,
+    )
+    .unwrap();
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn test_merge_state_routes_and_rules_appended() {
+    let merged = merge_yaml_docs(&[
+        r#"---
+routes:
+  config:
+  - destination: 198.51.100.0/24
+    next-hop-interface: eth1
+route-rules:
+  config:
+  - ip-to: 192.0.2.0/24
+    route-table: 100
+"#,
+        r#"---
+routes:
+  config:
+  - destination: 203.0.113.0/24
+    next-hop-interface: eth1
+route-rules:
+  config:
+  - ip-to: 198.51.100.0/24
+    route-table: 100
+"#,
+    ]);
+    let routes = merged["routes"]["config"].as_sequence().unwrap();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0]["destination"], "198.51.100.0/24");
+    assert_eq!(routes[1]["destination"], "203.0.113.0/24");
+    let rules = merged["route-rules"]["config"].as_sequence().unwrap();
+    assert_eq!(rules.len(), 2);
+    assert_eq!(rules[0]["ip-to"], "192.0.2.0/24");
+    assert_eq!(rules[1]["ip-to"], "198.51.100.0/24");
+}
+
+#[test]
+fn test_state_from_reader_multiple_docs() {
+    let content = r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 1500
+---
+---
+interfaces:
+- name: eth1
+  type: ethernet
+  mtu: 9000
+"#;
+    let state = state_from_reader(content.as_bytes()).unwrap();
+    let ifaces = state.interfaces.to_vec();
+    assert_eq!(ifaces.len(), 1);
+    assert_eq!(ifaces[0].base_iface().mtu, Some(9000));
+}
+
+#[test]
+fn test_states_from_reader_sequential() {
+    let content = r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+---
+---
+interfaces:
+- name: eth2
+  type: ethernet
+"#;
+    let states = states_from_reader(content.as_bytes()).unwrap();
+    assert_eq!(states.len(), 2);
+    assert_eq!(states[0].interfaces.to_vec()[0].name(), "eth1");
+    assert_eq!(states[1].interfaces.to_vec()[0].name(), "eth2");
+}
+
+#[test]
+fn test_states_from_reader_empty_docs() {
+    let states = states_from_reader("---\n---\n".as_bytes()).unwrap();
+    assert_eq!(states.len(), 1);
+    assert!(states[0].interfaces.to_vec().is_empty());
+}
+
+#[test]
+fn test_yaml_merge_key_precedence() {
+    let mut value: Value = serde_yaml::from_str(
+        r#"---
+base: &base
+  mtu: 1500
+  state: up
+jumbo: &jumbo
+  mtu: 9000
+  type: ethernet
+iface:
+  <<: [*jumbo, *base]
+  name: eth1
+  state: down
+"#,
+    )
+    .unwrap();
+    resolve_yaml_merge_keys(&mut value).unwrap();
+    let expected: Value = serde_yaml::from_str(
+        r#"---
+name: eth1
+state: down
+mtu: 9000
+type: ethernet
+"#,
+    )
+    .unwrap();
+    assert_eq!(value["iface"], expected);
+}
+
+#[test]
+fn test_yaml_merge_key_in_state() {
+    let content = r#"---
+interfaces:
+- &eth_base
+  name: eth1
+  type: ethernet
+  mtu: 9000
+- <<: *eth_base
+  name: eth2
+"#;
+    let state = state_from_reader(content.as_bytes()).unwrap();
+    let ifaces = state.interfaces.to_vec();
+    assert_eq!(ifaces.len(), 2);
+    for iface in ifaces {
+        assert_eq!(iface.base_iface().mtu, Some(9000));
+    }
+}
+
+#[test]
+fn test_yaml_merge_key_invalid_value() {
+    let mut value: Value =
+        serde_yaml::from_str("---\niface:\n  <<: 1500\n").unwrap();
+    assert!(resolve_yaml_merge_keys(&mut value).is_err());
+}
@@ -0,0 +1,85 @@
+use nmstate::NetworkState;
+
+use crate::table::ifaces_to_table;
+use crate::{net_state_to_string, OUTPUT_JSON, OUTPUT_TABLE};
+
+fn gen_net_state() -> NetworkState {
+    serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  mac-address: 00:23:45:67:89:1A
+  mtu: 1500
+  controller: bond0
+- name: bond0
+  type: bond
+  state: up
+  mtu: 9000
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+  ipv6:
+    enabled: true
+    address:
+    - ip: 2001:db8::1
+      prefix-length: 64
+  link-aggregation:
+    mode: active-backup
+    port:
+    - eth1
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_ifaces_to_table() {
+    assert_eq!(
+        ifaces_to_table(&gen_net_state()),
+        [
+            "NAME   TYPE      STATE  MAC                \
+            ADDRESSES                    MTU   CONTROLLER",
+            "bond0  bond      up     -                  \
+            192.0.2.1/24,2001:db8::1/64  9000  -",
+            "eth1   ethernet  up     00:23:45:67:89:1A  \
+            -                            1500  bond0",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_ifaces_to_table_empty() {
+    assert_eq!(
+        ifaces_to_table(&NetworkState::new()),
+        "NAME  TYPE  STATE  MAC  ADDRESSES  MTU  CONTROLLER"
+    );
+}
+
+#[test]
+fn test_net_state_to_table() {
+    let net_state = gen_net_state();
+    assert_eq!(
+        net_state_to_string(net_state.clone(), OUTPUT_TABLE).unwrap(),
+        ifaces_to_table(&net_state)
+    );
+}
+
+#[test]
+fn test_net_state_to_json() {
+    let output = net_state_to_string(gen_net_state(), OUTPUT_JSON).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let ifaces = value["interfaces"].as_array().unwrap();
+    assert_eq!(ifaces.len(), 2);
+    // Interfaces are sorted by name
+    assert_eq!(ifaces[0]["name"], "bond0");
+    assert_eq!(ifaces[0]["mtu"], 9000);
+    assert_eq!(ifaces[1]["name"], "eth1");
+    assert_eq!(ifaces[1]["controller"], "bond0");
+    // Top priority properties are placed first
+    assert!(output.find("\"name\"").unwrap() < output.find("\"mtu\"").unwrap());
+}
@@ -21,6 +21,7 @@ const NMSTATE_FLAG_INCLUDE_STATUS_DATA: u32 = 1 << 3;
 const NMSTATE_FLAG_INCLUDE_SECRETS: u32 = 1 << 4;
 // TODO
 // const NMSTATE_FLAG_MEMORY_ONLY: u32 = 1 << 5;
+const NMSTATE_FLAG_RUNNING_CONFIG_ONLY: u32 = 1 << 6;
 
 const NMSTATE_PASS: c_int = 0;
 const NMSTATE_FAIL: c_int = 1;
@@ -59,6 +60,10 @@ pub extern "C" fn nmstate_net_state_retrieve(
         net_state.set_include_secrets(true);
     }
 
+    if (flags & NMSTATE_FLAG_RUNNING_CONFIG_ONLY) > 0 {
+        net_state.set_running_config_only(true);
+    }
+
     // TODO: save log to the output pointer
 
     match net_state.retrieve() {
@@ -0,0 +1,121 @@
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, RtnlSocket, NLM_F_CREATE, NLM_F_REPLACE},
+    ErrorKind, InterfaceIpAddr, InterfaceIpAddrFlag, Interfaces, NmstateError,
+};
+
+const RTM_NEWADDR: u16 = 20;
+const IFA_ADDRESS: u16 = 1;
+const IFA_FLAGS: u16 = 8;
+const IFA_F_NODAD: u32 = 0x02;
+const IFA_F_HOMEADDRESS: u32 = 0x10;
+const IFA_F_MANAGETEMPADDR: u32 = 0x100;
+const RT_SCOPE_UNIVERSE: u8 = 0;
+
+impl InterfaceIpAddrFlag {
+    fn to_kernel_flag(self) -> u32 {
+        match self {
+            Self::Nodad => IFA_F_NODAD,
+            Self::Home => IFA_F_HOMEADDRESS,
+            Self::Mngtmpaddr => IFA_F_MANAGETEMPADDR,
+        }
+    }
+}
+
+// Nispor does not support IPv6 address flags, hence we replace the address with
+// desired flags via netlink after applied in kernel mode.
+// The `nodad` flag only takes effect when address is created, so this function
+// should also be invoked before applying with `current` defined to create
+// flagged addresses on interfaces already exist.
+pub(crate) fn apply_ipv6_addr_flags(
+    desired: &Interfaces,
+    current: Option<&Interfaces>,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for iface in desired.kernel_ifaces.values().filter(|i| {
+        i.is_up()
+            && current
+                .map(|c| c.kernel_ifaces.contains_key(i.name()))
+                .unwrap_or(true)
+    }) {
+        let addrs: Vec<&InterfaceIpAddr> =
+            match iface.base_iface().ipv6.as_ref() {
+                Some(ip_conf) if ip_conf.enabled => ip_conf
+                    .addresses
+                    .iter()
+                    .filter(|a| {
+                        a.flags.as_ref().map(|f| !f.is_empty()) == Some(true)
+                    })
+                    .collect(),
+                _ => continue,
+            };
+        if addrs.is_empty() {
+            continue;
+        }
+        let iface_index = get_iface_index(iface.name())? as u32;
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            for addr in addrs {
+                info!(
+                    "Setting flags {:?} of IPv6 address {}/{} on \
+                    interface {}",
+                    addr.flags.as_deref().unwrap_or_default(),
+                    addr.ip,
+                    addr.prefix_length,
+                    iface.name()
+                );
+                replace_ipv6_addr(socket, iface_index, addr)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn replace_ipv6_addr(
+    socket: &mut RtnlSocket,
+    iface_index: u32,
+    addr: &InterfaceIpAddr,
+) -> Result<(), NmstateError> {
+    let ip = Ipv6Addr::from_str(&addr.ip)?;
+    let flags = addr
+        .flags
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .fold(0u32, |flags, f| flags | f.to_kernel_flag());
+
+    // ifaddrmsg
+    let mut payload = vec![
+        libc::AF_INET6 as u8,
+        addr.prefix_length,
+        // Only lower 8 bits could be stored in ifaddrmsg, the full flags is
+        // stored in IFA_FLAGS.
+        flags as u8,
+        RT_SCOPE_UNIVERSE,
+    ];
+    payload.extend_from_slice(&iface_index.to_ne_bytes());
+    append_nla(&mut payload, IFA_ADDRESS, &ip.octets());
+    append_nla(&mut payload, IFA_FLAGS, &flags.to_ne_bytes());
+
+    match socket.request(RTM_NEWADDR, NLM_F_CREATE | NLM_F_REPLACE, &payload)? {
+        None => Ok(()),
+        Some(err) => {
+            let e = NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to set flags of IPv6 address {}/{}: {}",
+                    addr.ip, addr.prefix_length, err
+                ),
+            );
+            log::error!("{}", e);
+            Err(e)
+        }
+    }
+}
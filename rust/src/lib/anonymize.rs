@@ -0,0 +1,348 @@
+use std::collections::hash_map::Entry;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hash};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde_json::Value;
+
+use crate::{ErrorKind, Interface, NetworkState, NmstateError};
+
+// The scrambled public IPv4 address is placed in reserved 240.0.0.0/4.
+const ANONYMIZED_IPV4_PREFIX: u32 = 0xf000_0000;
+const ANONYMIZED_IPV4_PREFIX_MASK: u32 = 0xf000_0000;
+// The public IPv4 addresses are grouped into /24 networks, hence up to
+// 2^20 networks could be placed in 240.0.0.0/4.
+const ANONYMIZED_IPV4_HOST_BITS: u32 = 8;
+const ANONYMIZED_IPV4_NET_MAX: u32 = 1 << 20;
+// The scrambled global IPv6 address is placed in 2001:db8::/32 reserved for
+// documentation, the public IPv6 addresses are grouped into /64 networks.
+const ANONYMIZED_IPV6_PREFIX: u128 = 0x2001_0db8 << 96;
+const ANONYMIZED_IPV6_HOST_BITS: u32 = 64;
+
+impl NetworkState {
+    // Generate a copy of network state suitable for attaching to bug report:
+    //  * Secrets are hidden.
+    //  * The MAC addresses, public IP addresses, hostnames, DNS search
+    //    domains, PPPoE usernames, WiFi SSIDs and EAP identities, IPsec
+    //    peers are replaced with scrambled ones.
+    // The same value is scrambled into the same one within single call, and
+    // the interface names are kept, so the topology is preserved. A new
+    // random key is used by each call, so the scrambled value cannot be
+    // reversed by hashing the guessed ones.
+    pub fn anonymize(&self) -> Result<Self, NmstateError> {
+        let mut anonymizer = Anonymizer::new();
+        let mut net_state = self.clone();
+        net_state.interfaces.hide_secrets();
+        if let Some(hostname) = net_state.hostname.as_mut() {
+            for name in hostname
+                .running
+                .iter_mut()
+                .chain(hostname.config.iter_mut())
+            {
+                // Empty config means removing the static hostname
+                if !name.is_empty() {
+                    *name = anonymizer.anonymize_name("host", name);
+                }
+            }
+        }
+        for dns_conf in net_state
+            .dns
+            .running
+            .iter_mut()
+            .chain(net_state.dns.config.iter_mut())
+        {
+            for domain in dns_conf.search.iter_mut().flatten() {
+                *domain = format!(
+                    "{}.example",
+                    anonymizer.anonymize_name("domain", domain)
+                );
+            }
+        }
+        for iface in net_state
+            .interfaces
+            .kernel_ifaces
+            .values_mut()
+            .chain(net_state.interfaces.user_ifaces.values_mut())
+        {
+            anonymizer.anonymize_iface(iface)?;
+        }
+        let mut value = serde_json::to_value(&net_state)?;
+        anonymizer.anonymize_value(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Anonymizer {
+    key: RandomState,
+    // Original network to scrambled network and next free host ID
+    ipv4_nets: HashMap<u32, (u32, u32)>,
+    ipv4_addrs: HashMap<u32, u32>,
+    ipv6_nets: HashMap<u128, (u128, u128)>,
+    ipv6_addrs: HashMap<u128, u128>,
+}
+
+impl Anonymizer {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn hash<T: Hash + ?Sized>(&self, value: &T) -> u64 {
+        self.key.hash_one(value)
+    }
+
+    fn anonymize_name(&self, prefix: &str, name: &str) -> String {
+        format!("{}-{:08x}", prefix, self.hash(name) as u32)
+    }
+
+    fn anonymize_iface(
+        &mut self,
+        iface: &mut Interface,
+    ) -> Result<(), NmstateError> {
+        match iface {
+            Interface::Pppoe(iface) => {
+                if let Some(conf) = iface.pppoe.as_mut() {
+                    conf.username = self.anonymize_name("user", &conf.username);
+                }
+            }
+            Interface::Wifi(iface) => {
+                if let Some(conf) = iface.wifi.as_mut() {
+                    conf.ssid = self.anonymize_name("ssid", &conf.ssid);
+                    if let Some(identity) = conf
+                        .security
+                        .as_mut()
+                        .and_then(|s| s.eap.as_mut())
+                        .and_then(|e| e.identity.as_mut())
+                    {
+                        *identity = self.anonymize_name("user", identity);
+                    }
+                }
+            }
+            Interface::Ipsec(iface) => {
+                if let Some(conf) = iface.libreswan.as_mut() {
+                    conf.right = self.anonymize_peer(&conf.right)?;
+                    for peer in conf
+                        .left
+                        .iter_mut()
+                        .chain(conf.leftid.iter_mut())
+                        .chain(conf.rightid.iter_mut())
+                    {
+                        *peer = self.anonymize_peer(peer)?;
+                    }
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    // The IPsec peer could be IP address, hostname, `@` prefixed FQDN or
+    // libreswan keywords like `%any` which are kept.
+    fn anonymize_peer(&mut self, peer: &str) -> Result<String, NmstateError> {
+        if peer.is_empty() || peer.starts_with('%') {
+            Ok(peer.to_string())
+        } else if let Some(fqdn) = peer.strip_prefix('@') {
+            Ok(format!("@{}", self.anonymize_name("host", fqdn)))
+        } else if let Some(new_ip) = self.anonymize_ip(peer)? {
+            Ok(new_ip)
+        } else if peer.parse::<IpAddr>().is_ok() {
+            Ok(peer.to_string())
+        } else {
+            Ok(self.anonymize_name("host", peer))
+        }
+    }
+
+    fn anonymize_value(
+        &mut self,
+        value: &mut Value,
+    ) -> Result<(), NmstateError> {
+        match value {
+            Value::Object(map) => {
+                for v in map.values_mut() {
+                    self.anonymize_value(v)?;
+                }
+            }
+            Value::Array(items) => {
+                for v in items.iter_mut() {
+                    self.anonymize_value(v)?;
+                }
+            }
+            Value::String(s) => {
+                if let Some(new_value) = self.anonymize_mac(s) {
+                    *s = new_value;
+                } else if let Some(new_value) = self.anonymize_ip(s)? {
+                    *s = new_value;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    // Both ethernet and InfiniBand MAC addresses are supported. Multicast MAC
+    // address is not identifying, hence kept.
+    pub(crate) fn anonymize_mac(&self, mac: &str) -> Option<String> {
+        let bytes: Vec<u8> = mac
+            .split(':')
+            .map(|b| {
+                if b.len() == 2 {
+                    u8::from_str_radix(b, 16).ok()
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<u8>>>()?;
+        if (bytes.len() != 6 && bytes.len() != 20)
+            || bytes.iter().all(|b| *b == 0)
+            || bytes[0] & 0x01 > 0
+        {
+            return None;
+        }
+        let mut seed = self.hash(&bytes);
+        let mut new_bytes = Vec::with_capacity(bytes.len());
+        for i in 0..bytes.len() {
+            if i % 8 == 0 && i > 0 {
+                seed = self.hash(&seed);
+            }
+            new_bytes.push((seed >> ((i % 8) * 8)) as u8);
+        }
+        // Unicast and locally administered
+        new_bytes[0] = (new_bytes[0] & 0xfc) | 0x02;
+        Some(
+            new_bytes
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<String>>()
+                .join(":"),
+        )
+    }
+
+    // Support both plain IP address and IP address with prefix length.
+    // Each public network is assigned with a distinct scrambled network in
+    // the order of appearance, and each host in it is assigned with a
+    // distinct host ID, hence addresses in the same subnet are still in the
+    // same subnet after anonymized while no two networks or addresses are
+    // merged into one.
+    pub(crate) fn anonymize_ip(
+        &mut self,
+        ip_str: &str,
+    ) -> Result<Option<String>, NmstateError> {
+        let (addr_str, prefix) = match ip_str.split_once('/') {
+            Some((a, p)) => (a, Some(p)),
+            None => (ip_str, None),
+        };
+        let new_ip = match addr_str.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) if is_public_ipv4(&ip) => {
+                IpAddr::V4(self.anonymize_ipv4(u32::from(ip))?)
+            }
+            Ok(IpAddr::V6(ip)) if is_public_ipv6(&ip) => {
+                IpAddr::V6(self.anonymize_ipv6(u128::from(ip))?)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(match prefix {
+            Some(p) => format!("{}/{}", new_ip, p),
+            None => new_ip.to_string(),
+        }))
+    }
+
+    // The network address and broadcast address of /24 are kept as host ID
+    // 0 and 255, other hosts are numbered from 1.
+    fn anonymize_ipv4(&mut self, ip: u32) -> Result<Ipv4Addr, NmstateError> {
+        if let Some(new_ip) = self.ipv4_addrs.get(&ip) {
+            return Ok(Ipv4Addr::from(*new_ip));
+        }
+        let host_mask = (1u32 << ANONYMIZED_IPV4_HOST_BITS) - 1;
+        let net_count = self.ipv4_nets.len() as u32;
+        let (new_net, next_host) =
+            match self.ipv4_nets.entry(ip >> ANONYMIZED_IPV4_HOST_BITS) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => {
+                    if net_count >= ANONYMIZED_IPV4_NET_MAX {
+                        return Err(too_many_networks_error("IPv4"));
+                    }
+                    e.insert((
+                        ANONYMIZED_IPV4_PREFIX
+                            | (net_count << ANONYMIZED_IPV4_HOST_BITS),
+                        1,
+                    ))
+                }
+            };
+        let host = match ip & host_mask {
+            0 => 0,
+            h if h == host_mask => host_mask,
+            _ => {
+                let h = *next_host;
+                *next_host += 1;
+                h
+            }
+        };
+        let new_ip = *new_net | host;
+        self.ipv4_addrs.insert(ip, new_ip);
+        Ok(Ipv4Addr::from(new_ip))
+    }
+
+    // The subnet-router anycast address is kept as host ID 0, other hosts
+    // are numbered from 1.
+    fn anonymize_ipv6(&mut self, ip: u128) -> Result<Ipv6Addr, NmstateError> {
+        if let Some(new_ip) = self.ipv6_addrs.get(&ip) {
+            return Ok(Ipv6Addr::from(*new_ip));
+        }
+        let host_mask = (1u128 << ANONYMIZED_IPV6_HOST_BITS) - 1;
+        let net_count = self.ipv6_nets.len();
+        let (new_net, next_host) =
+            match self.ipv6_nets.entry(ip >> ANONYMIZED_IPV6_HOST_BITS) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => {
+                    let net_count = u32::try_from(net_count)
+                        .map_err(|_| too_many_networks_error("IPv6"))?;
+                    e.insert((
+                        ANONYMIZED_IPV6_PREFIX
+                            | ((net_count as u128)
+                                << ANONYMIZED_IPV6_HOST_BITS),
+                        1,
+                    ))
+                }
+            };
+        let host = if ip & host_mask == 0 {
+            0
+        } else {
+            let h = *next_host;
+            *next_host += 1;
+            h
+        };
+        let new_ip = *new_net | host;
+        self.ipv6_addrs.insert(ip, new_ip);
+        Ok(Ipv6Addr::from(new_ip))
+    }
+}
+
+fn too_many_networks_error(family: &str) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::InvalidArgument,
+        format!("Too many public {} networks to anonymize", family),
+    );
+    log::error!("{}", e);
+    e
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_multicast()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        // Reserved for future use
+        || u32::from(*ip) & ANONYMIZED_IPV4_PREFIX_MASK
+            == ANONYMIZED_IPV4_PREFIX)
+}
+
+// Only the global unicast 2000::/3 is public
+fn is_public_ipv6(ip: &Ipv6Addr) -> bool {
+    ip.segments()[0] & 0xe000 == 0x2000
+        && !(ip.segments()[0] == 0x2001 && ip.segments()[1] == 0x0db8)
+}
@@ -0,0 +1,285 @@
+use std::os::unix::fs::MetadataExt;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    audit_log::{send_audit_record, send_journald_entry, uid_to_user_name},
+    InterfaceType, NetworkState, NmstateError,
+};
+
+const LOGIN_UID_FILE: &str = "/proc/self/loginuid";
+// Unset login UID
+const AUDIT_UID_UNSET: u32 = u32::MAX;
+// Allow `journalctl MESSAGE_ID=` filtering on nmstate audit entries
+const JOURNALD_MESSAGE_ID: &str = "8d4c3f6e2b1a4f0e9c7d5b3a1e2f4c6d";
+const JOURNALD_PRIORITY_NOTICE: &str = "5";
+const JOURNALD_PRIORITY_ERROR: &str = "3";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditLogBackend {
+    // Structured entry of systemd-journald
+    Journald,
+    // Linux audit record of USYS_CONFIG type, requires CAP_AUDIT_WRITE
+    Auditd,
+}
+
+impl std::fmt::Display for AuditLogBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Journald => "journald",
+                Self::Auditd => "auditd",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuditOperation {
+    Apply,
+    Rollback,
+}
+
+impl std::fmt::Display for AuditOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Apply => "apply",
+                Self::Rollback => "rollback",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AuditRecord {
+    pub(crate) operation: AuditOperation,
+    // SHA-256 of desired states in JSON, empty for rollback of checkpoint
+    // not created in this process.
+    pub(crate) state_hash: String,
+    // Summary of each applied state
+    pub(crate) summaries: Vec<String>,
+    pub(crate) checkpoint: Option<String>,
+    // None means succeeded
+    pub(crate) error: Option<String>,
+}
+
+impl AuditRecord {
+    pub(crate) fn new(operation: AuditOperation) -> Self {
+        Self {
+            operation,
+            state_hash: String::new(),
+            summaries: Vec::new(),
+            checkpoint: None,
+            error: None,
+        }
+    }
+
+    pub(crate) fn set_state_hash(&mut self, net_states: &[NetworkState]) {
+        let content = net_states
+            .iter()
+            .map(|s| serde_json::to_string(s).unwrap_or_default())
+            .collect::<Vec<String>>()
+            .join("\n");
+        self.state_hash = sha256_hex(content.as_bytes());
+    }
+
+    pub(crate) fn set_result(&mut self, result: &Result<(), NmstateError>) {
+        self.error = result.as_ref().err().map(|e| e.to_string());
+    }
+
+    fn result_str(&self) -> &str {
+        if self.error.is_some() {
+            "failed"
+        } else {
+            "success"
+        }
+    }
+
+    fn summary(&self) -> String {
+        if self.summaries.is_empty() {
+            "none".to_string()
+        } else {
+            self.summaries.join("; ")
+        }
+    }
+
+    fn user(&self) -> String {
+        match get_login_uid().or_else(get_uid) {
+            Some(uid) => match uid_to_user_name(uid) {
+                Some(name) => name,
+                None => uid.to_string(),
+            },
+            None => "unknown".to_string(),
+        }
+    }
+
+    pub(crate) fn to_journald_fields(&self) -> Vec<(&'static str, String)> {
+        let mut message = format!(
+            "nmstate {} {} by user {}: {}",
+            self.operation,
+            self.result_str(),
+            self.user(),
+            self.summary()
+        );
+        if let Some(error) = self.error.as_ref() {
+            message.push_str(&format!(", error: {}", error));
+        }
+        let mut ret = vec![
+            ("MESSAGE", message),
+            ("MESSAGE_ID", JOURNALD_MESSAGE_ID.to_string()),
+            ("SYSLOG_IDENTIFIER", "nmstate".to_string()),
+            (
+                "PRIORITY",
+                if self.error.is_some() {
+                    JOURNALD_PRIORITY_ERROR
+                } else {
+                    JOURNALD_PRIORITY_NOTICE
+                }
+                .to_string(),
+            ),
+            ("NMSTATE_OPERATION", self.operation.to_string()),
+            ("NMSTATE_RESULT", self.result_str().to_string()),
+            ("NMSTATE_STATE_HASH", self.state_hash.clone()),
+            ("NMSTATE_SUMMARY", self.summary()),
+            ("NMSTATE_USER", self.user()),
+        ];
+        if let Some(uid) = get_uid() {
+            ret.push(("NMSTATE_UID", uid.to_string()));
+        }
+        if let Some(login_uid) = get_login_uid() {
+            ret.push(("NMSTATE_LOGIN_UID", login_uid.to_string()));
+        }
+        if let Some(checkpoint) = self.checkpoint.as_ref() {
+            ret.push(("NMSTATE_CHECKPOINT", checkpoint.to_string()));
+        }
+        if let Some(error) = self.error.as_ref() {
+            ret.push(("NMSTATE_ERROR", error.to_string()));
+        }
+        ret
+    }
+
+    // The UID and login UID are included by kernel in audit record
+    pub(crate) fn to_audit_msg(&self) -> String {
+        let mut ret = format!(
+            "op=nmstate-{} state_hash={} summary={}",
+            self.operation,
+            audit_encode_value(&self.state_hash),
+            audit_encode_value(&self.summary()),
+        );
+        if let Some(checkpoint) = self.checkpoint.as_ref() {
+            ret.push_str(&format!(
+                " checkpoint={}",
+                audit_encode_value(checkpoint)
+            ));
+        }
+        if let Some(error) = self.error.as_ref() {
+            ret.push_str(&format!(" error={}", audit_encode_value(error)));
+        }
+        ret.push_str(&format!(" res={}", self.result_str()));
+        ret
+    }
+}
+
+// Failure on audit logging only generates warning
+pub(crate) fn emit_audit_record(
+    backends: &[AuditLogBackend],
+    record: &AuditRecord,
+) {
+    for backend in backends {
+        let result = match backend {
+            AuditLogBackend::Journald => {
+                send_journald_entry(&record.to_journald_fields())
+            }
+            AuditLogBackend::Auditd => {
+                send_audit_record(&record.to_audit_msg())
+            }
+        };
+        if let Err(e) = result {
+            warn!("Failed to emit audit record via {}: {}", backend, e);
+        }
+    }
+}
+
+// Summary of interface changes, for example:
+//  add [bond0, eth1.10], change [eth1], delete [eth2], hostname
+pub(crate) fn gen_audit_summary(
+    add_net_state: &NetworkState,
+    chg_net_state: &NetworkState,
+    del_net_state: &NetworkState,
+) -> String {
+    let mut ret = Vec::new();
+    for (action, net_state) in [
+        ("add", add_net_state),
+        ("change", chg_net_state),
+        ("delete", del_net_state),
+    ]
+    .iter()
+    {
+        let mut names: Vec<&str> =
+            net_state.interfaces.iter().map(|i| i.name()).collect();
+        if names.is_empty() {
+            continue;
+        }
+        names.sort_unstable();
+        names.dedup();
+        ret.push(format!("{} [{}]", action, names.join(", ")));
+    }
+    if chg_net_state.hostname.is_some() {
+        ret.push("hostname".to_string());
+    }
+    if ret.is_empty() {
+        "no change".to_string()
+    } else {
+        ret.join(", ")
+    }
+}
+
+// Format the interface type resolved from current state as
+// `name: type, name: type`.
+pub(crate) fn gen_iface_type_resolutions_summary(
+    resolutions: &[(String, InterfaceType)],
+) -> String {
+    resolutions
+        .iter()
+        .map(|(name, iface_type)| format!("{}: {}", name, iface_type))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn get_uid() -> Option<u32> {
+    std::fs::metadata("/proc/self").ok().map(|m| m.uid())
+}
+
+fn get_login_uid() -> Option<u32> {
+    std::fs::read_to_string(LOGIN_UID_FILE)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .filter(|u| *u != AUDIT_UID_UNSET)
+}
+
+// Follow the encoding of audit_encode_nv_string() from libaudit, value
+// holding space, quote or control characters is hex encoded, others are
+// quoted.
+pub(crate) fn audit_encode_value(value: &str) -> String {
+    if value.bytes().any(|c| c <= b' ' || c == b'"' || c >= 0x7f) {
+        value.bytes().map(|c| format!("{:02X}", c)).collect()
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+// Only used for identifying the applied state.
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
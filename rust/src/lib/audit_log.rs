@@ -0,0 +1,96 @@
+use std::os::unix::net::UnixDatagram;
+use std::time::Duration;
+
+use crate::{rtnl::RtnlSocket, ErrorKind, NmstateError};
+
+const JOURNALD_SOCKET_PATH: &str = "/run/systemd/journal/socket";
+const AUDIT_USYS_CONFIG: u16 = 1111;
+const AUDIT_ACK_TIMEOUT_MILLISECONDS: u32 = 1000;
+const PASSWD_BUFFER_SIZE: usize = 1024;
+const PASSWD_BUFFER_SIZE_MAX: usize = 1024 * 1024;
+
+// Using the native protocol of systemd-journald, value holding new line is
+// sent in binary format.
+pub(crate) fn send_journald_entry(
+    fields: &[(&str, String)],
+) -> Result<(), NmstateError> {
+    let mut data: Vec<u8> = Vec::new();
+    for (key, value) in fields {
+        data.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            data.push(b'\n');
+            data.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            data.push(b'=');
+        }
+        data.extend_from_slice(value.as_bytes());
+        data.push(b'\n');
+    }
+    let socket = UnixDatagram::unbound().map_err(journald_error)?;
+    socket
+        .set_write_timeout(Some(Duration::from_secs(1)))
+        .map_err(journald_error)?;
+    socket
+        .send_to(&data, JOURNALD_SOCKET_PATH)
+        .map_err(journald_error)?;
+    Ok(())
+}
+
+fn journald_error(e: std::io::Error) -> NmstateError {
+    NmstateError::new(
+        ErrorKind::PluginFailure,
+        format!(
+            "Failed to send journal entry to {}: {}",
+            JOURNALD_SOCKET_PATH, e
+        ),
+    )
+}
+
+pub(crate) fn send_audit_record(msg: &str) -> Result<(), NmstateError> {
+    let mut socket = RtnlSocket::with_protocol(libc::NETLINK_AUDIT)?;
+    socket.set_recv_timeout(AUDIT_ACK_TIMEOUT_MILLISECONDS)?;
+    // The message is NULL terminated
+    let mut payload = msg.as_bytes().to_vec();
+    payload.push(0);
+    // Kernel reply error like EPERM in the ACK message
+    match socket.request(AUDIT_USYS_CONFIG, 0, &payload) {
+        Ok(None) => Ok(()),
+        Ok(Some(e)) => Err(NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Kernel refused audit record: {}", e),
+        )),
+        Err(e) if e.kind() == ErrorKind::Timeout => {
+            log::warn!("Timeout on waiting ACK of audit record");
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn uid_to_user_name(uid: u32) -> Option<String> {
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let mut buffer = vec![0 as libc::c_char; PASSWD_BUFFER_SIZE];
+    loop {
+        let rc = unsafe {
+            libc::getpwuid_r(
+                uid,
+                &mut passwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+        if rc == libc::ERANGE && buffer.len() < PASSWD_BUFFER_SIZE_MAX {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if rc != 0 || result.is_null() || passwd.pw_name.is_null() {
+            return None;
+        }
+        return unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) }
+            .to_str()
+            .ok()
+            .map(|s| s.to_string());
+    }
+}
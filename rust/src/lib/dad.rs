@@ -0,0 +1,484 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use log::{debug, info};
+
+use crate::{ErrorKind, Interfaces, NmstateError};
+
+const ETH_P_ARP: u16 = 0x0806;
+const ETH_P_IP: u16 = 0x0800;
+const ARPHRD_ETHER: u16 = 1;
+const ARPOP_REQUEST: u16 = 1;
+const ARPOP_REPLY: u16 = 2;
+const ARP_PACKET_LEN: usize = 28;
+const ARP_PROBE_COUNT: u32 = 3;
+const ARP_PROBE_WAIT_MILLISECONDS: u64 = 300;
+const ICMPV6_NEIGHBOR_ADVERTISEMENT: u8 = 136;
+const NDISC_NA_FLAG_OVERRIDE: u8 = 0x20;
+const NDISC_OPT_TARGET_LL_ADDR: u8 = 2;
+const NDISC_NA_PACKET_LEN: usize = 32;
+const IPV6_ALL_NODES_MULTICAST: Ipv6Addr =
+    Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+
+// Send ARP probes(RFC 5227) for static IPv4 addresses which is not
+// assigned to any interface of current host yet.
+// Only interfaces exist in current state could be probed.
+pub(crate) fn check_ipv4_address_conflict(
+    desired: &Interfaces,
+    current: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut cur_ips: Vec<Ipv4Addr> = Vec::new();
+    for cur_iface in current.kernel_ifaces.values() {
+        if let Some(ip_conf) = cur_iface.base_iface().ipv4.as_ref() {
+            for addr in &ip_conf.addresses {
+                if let Ok(ip) = Ipv4Addr::from_str(&addr.ip) {
+                    cur_ips.push(ip);
+                }
+            }
+        }
+    }
+
+    for iface in desired.kernel_ifaces.values().filter(|i| i.is_up()) {
+        let ip_conf = match iface.base_iface().ipv4.as_ref() {
+            Some(i) if i.enabled && !i.dhcp => i,
+            _ => continue,
+        };
+        let new_ips: Vec<Ipv4Addr> = ip_conf
+            .addresses
+            .iter()
+            .filter_map(|a| Ipv4Addr::from_str(&a.ip).ok())
+            .filter(|ip| !cur_ips.contains(ip))
+            .collect();
+        if new_ips.is_empty() {
+            continue;
+        }
+        let mac = match current
+            .kernel_ifaces
+            .get(iface.name())
+            .filter(|i| i.is_up())
+            .and_then(|i| i.base_iface().mac_address.as_deref())
+            .and_then(parse_mac)
+        {
+            Some(m) => m,
+            None => {
+                debug!(
+                    "Skipping duplicate address detection on interface {} \
+                    as it is not up with ethernet MAC address yet",
+                    iface.name()
+                );
+                continue;
+            }
+        };
+        for ip in new_ips {
+            info!(
+                "Probing duplicate address {} on interface {}",
+                ip,
+                iface.name()
+            );
+            if let Some(conflict_mac) = arp_probe(iface.name(), &mac, &ip)? {
+                let e = NmstateError::new(
+                    ErrorKind::IpAddressConflict,
+                    format!(
+                        "IP address {} desired for interface {} is already \
+                        used by host with MAC address {}",
+                        ip,
+                        iface.name(),
+                        conflict_mac
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let mut ret = [0u8; 6];
+    let octets: Vec<&str> = mac.split(':').collect();
+    if octets.len() != ret.len() {
+        return None;
+    }
+    for (i, octet) in octets.iter().enumerate() {
+        ret[i] = u8::from_str_radix(octet, 16).ok()?;
+    }
+    Some(ret)
+}
+
+fn arp_probe(
+    iface_name: &str,
+    mac: &[u8; 6],
+    ip: &Ipv4Addr,
+) -> Result<Option<String>, NmstateError> {
+    let socket = ArpSocket::new(iface_name)?;
+    // Sender IP is all zero for ARP probe
+    let packet = gen_arp_request(mac, &Ipv4Addr::UNSPECIFIED, ip);
+
+    for _ in 0..ARP_PROBE_COUNT {
+        socket.send(&packet)?;
+        let deadline =
+            Instant::now() + Duration::from_millis(ARP_PROBE_WAIT_MILLISECONDS);
+        while let Some(reply) = socket.recv(deadline)? {
+            if reply.len() < ARP_PACKET_LEN
+                || reply[6..8] != ARPOP_REPLY.to_be_bytes()
+                || reply[14..18] != ip.octets()
+            {
+                continue;
+            }
+            return Ok(Some(
+                reply[8..14]
+                    .iter()
+                    .map(|o| format!("{:02X}", o))
+                    .collect::<Vec<String>>()
+                    .join(":"),
+            ));
+        }
+    }
+    Ok(None)
+}
+
+fn gen_arp_request(
+    mac: &[u8; 6],
+    sender_ip: &Ipv4Addr,
+    target_ip: &Ipv4Addr,
+) -> [u8; ARP_PACKET_LEN] {
+    let mut packet = [0u8; ARP_PACKET_LEN];
+    packet[0..2].copy_from_slice(&ARPHRD_ETHER.to_be_bytes());
+    packet[2..4].copy_from_slice(&ETH_P_IP.to_be_bytes());
+    packet[4] = 6;
+    packet[5] = 4;
+    packet[6..8].copy_from_slice(&ARPOP_REQUEST.to_be_bytes());
+    packet[8..14].copy_from_slice(mac);
+    packet[14..18].copy_from_slice(&sender_ip.octets());
+    // Target MAC is all zero
+    packet[24..28].copy_from_slice(&target_ip.octets());
+    packet
+}
+
+// Send gratuitous ARP(ARP announcement of RFC 5227) and unsolicited neighbor
+// advertisement for static IP addresses of interfaces with address-announce
+// defined. Only for kernel mode, NetworkManager mode rejects address-announce
+// as it has no setting for it.
+pub(crate) fn announce_addresses(
+    desired: &Interfaces,
+    current: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in desired.kernel_ifaces.values().filter(|i| i.is_up()) {
+        let announce_conf = match iface.base_iface().address_announce.as_ref() {
+            Some(c) => c,
+            None => continue,
+        };
+        let cur_iface = match current.kernel_ifaces.get(iface.name()) {
+            Some(i) => i,
+            None => continue,
+        };
+        let mac = match cur_iface
+            .base_iface()
+            .mac_address
+            .as_deref()
+            .and_then(parse_mac)
+        {
+            Some(m) => m,
+            None => {
+                log::warn!(
+                    "Cannot announce addresses of interface {} as it has \
+                    no ethernet MAC address",
+                    iface.name()
+                );
+                continue;
+            }
+        };
+        let ipv4_addrs: Vec<Ipv4Addr> = cur_iface
+            .base_iface()
+            .ipv4
+            .as_ref()
+            .filter(|i| i.enabled)
+            .map(|i| {
+                i.addresses
+                    .iter()
+                    .filter_map(|a| Ipv4Addr::from_str(&a.ip).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let ipv6_addrs: Vec<Ipv6Addr> = cur_iface
+            .base_iface()
+            .ipv6
+            .as_ref()
+            .filter(|i| i.enabled)
+            .map(|i| {
+                i.addresses
+                    .iter()
+                    .filter_map(|a| Ipv6Addr::from_str(&a.ip).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if ipv4_addrs.is_empty() && ipv6_addrs.is_empty() {
+            continue;
+        }
+        info!(
+            "Announcing addresses {:?} {:?} of interface {}",
+            ipv4_addrs,
+            ipv6_addrs,
+            iface.name()
+        );
+        let arp_socket = if ipv4_addrs.is_empty() {
+            None
+        } else {
+            Some(ArpSocket::new(iface.name())?)
+        };
+        let na_socket = if ipv6_addrs.is_empty() {
+            None
+        } else {
+            Some(NdiscSocket::new(iface.name())?)
+        };
+        for i in 0..announce_conf.count() {
+            if i > 0 {
+                std::thread::sleep(Duration::from_millis(
+                    announce_conf.interval().into(),
+                ));
+            }
+            if let Some(socket) = arp_socket.as_ref() {
+                for ip in &ipv4_addrs {
+                    socket.send(&gen_arp_request(&mac, ip, ip))?;
+                }
+            }
+            if let Some(socket) = na_socket.as_ref() {
+                for ip in &ipv6_addrs {
+                    socket.send_unsolicited_na(&mac, ip)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+struct ArpSocket {
+    fd: libc::c_int,
+    iface_index: libc::c_int,
+}
+
+impl ArpSocket {
+    fn new(iface_name: &str) -> Result<Self, NmstateError> {
+        let iface_index = get_iface_index(iface_name)?;
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_PACKET,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                ETH_P_ARP.to_be() as libc::c_int,
+            )
+        };
+        if fd < 0 {
+            return Err(last_os_error("Failed to create ARP socket"));
+        }
+        let socket = Self { fd, iface_index };
+        let addr = socket.gen_sockaddr(&[0u8; 6]);
+        let rc = unsafe {
+            libc::bind(
+                socket.fd,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(last_os_error(&format!(
+                "Failed to bind ARP socket to interface {}",
+                iface_name
+            )));
+        }
+        Ok(socket)
+    }
+
+    fn gen_sockaddr(&self, mac: &[u8; 6]) -> libc::sockaddr_ll {
+        let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = ETH_P_ARP.to_be();
+        addr.sll_ifindex = self.iface_index;
+        addr.sll_halen = mac.len() as u8;
+        addr.sll_addr[..mac.len()].copy_from_slice(mac);
+        addr
+    }
+
+    fn send(&self, packet: &[u8]) -> Result<(), NmstateError> {
+        let addr = self.gen_sockaddr(&[0xff; 6]);
+        let rc = unsafe {
+            libc::sendto(
+                self.fd,
+                packet.as_ptr() as *const libc::c_void,
+                packet.len(),
+                0,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            Err(last_os_error("Failed to send ARP probe"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Return None on timeout
+    fn recv(&self, deadline: Instant) -> Result<Option<Vec<u8>>, NmstateError> {
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        let mut poll_fd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = (deadline - now).as_millis() as libc::c_int;
+        let rc = unsafe { libc::poll(&mut poll_fd, 1, timeout) };
+        if rc < 0 {
+            return Err(last_os_error("Failed to wait ARP reply"));
+        } else if rc == 0 {
+            return Ok(None);
+        }
+        let mut buffer = vec![0u8; 1500];
+        let rc = unsafe {
+            libc::recv(
+                self.fd,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+                0,
+            )
+        };
+        if rc < 0 {
+            return Err(last_os_error("Failed to receive ARP reply"));
+        }
+        buffer.truncate(rc as usize);
+        Ok(Some(buffer))
+    }
+}
+
+impl Drop for ArpSocket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+struct NdiscSocket {
+    fd: libc::c_int,
+    iface_index: u32,
+}
+
+impl NdiscSocket {
+    fn new(iface_name: &str) -> Result<Self, NmstateError> {
+        let iface_index = get_iface_index(iface_name)? as u32;
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_INET6,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::IPPROTO_ICMPV6,
+            )
+        };
+        if fd < 0 {
+            return Err(last_os_error("Failed to create ICMPv6 socket"));
+        }
+        let socket = Self { fd, iface_index };
+        // Neighbor discovery messages require hop limit 255
+        socket.set_opt(libc::IPV6_MULTICAST_HOPS, 255)?;
+        socket.set_opt(libc::IPV6_MULTICAST_IF, iface_index as libc::c_int)?;
+        Ok(socket)
+    }
+
+    fn set_opt(
+        &self,
+        opt: libc::c_int,
+        value: libc::c_int,
+    ) -> Result<(), NmstateError> {
+        let rc = unsafe {
+            libc::setsockopt(
+                self.fd,
+                libc::IPPROTO_IPV6,
+                opt,
+                &value as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            Err(last_os_error("Failed to set ICMPv6 socket option"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // The checksum will be filled by kernel
+    fn send_unsolicited_na(
+        &self,
+        mac: &[u8; 6],
+        ip: &Ipv6Addr,
+    ) -> Result<(), NmstateError> {
+        let mut packet = [0u8; NDISC_NA_PACKET_LEN];
+        packet[0] = ICMPV6_NEIGHBOR_ADVERTISEMENT;
+        packet[4] = NDISC_NA_FLAG_OVERRIDE;
+        packet[8..24].copy_from_slice(&ip.octets());
+        packet[24] = NDISC_OPT_TARGET_LL_ADDR;
+        // Option length is in the unit of 8 octets
+        packet[25] = 1;
+        packet[26..32].copy_from_slice(mac);
+
+        let mut addr: libc::sockaddr_in6 = unsafe { std::mem::zeroed() };
+        addr.sin6_family = libc::AF_INET6 as u16;
+        addr.sin6_addr.s6_addr = IPV6_ALL_NODES_MULTICAST.octets();
+        addr.sin6_scope_id = self.iface_index;
+        let rc = unsafe {
+            libc::sendto(
+                self.fd,
+                packet.as_ptr() as *const libc::c_void,
+                packet.len(),
+                0,
+                &addr as *const libc::sockaddr_in6 as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            Err(last_os_error("Failed to send unsolicited NA"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for NdiscSocket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+pub(crate) fn get_iface_index(
+    iface_name: &str,
+) -> Result<libc::c_int, NmstateError> {
+    let c_iface_name = std::ffi::CString::new(iface_name).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Invalid interface name {}: {}", iface_name, e),
+        )
+    })?;
+    let iface_index =
+        unsafe { libc::if_nametoindex(c_iface_name.as_ptr()) } as libc::c_int;
+    if iface_index == 0 {
+        Err(last_os_error(&format!(
+            "Failed to find interface index of {}",
+            iface_name
+        )))
+    } else {
+        Ok(iface_index)
+    }
+}
+
+pub(crate) fn last_os_error(msg: &str) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::PluginFailure,
+        format!("{}: {}", msg, std::io::Error::last_os_error()),
+    );
+    log::error!("{}", e);
+    e
+}
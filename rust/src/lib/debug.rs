@@ -0,0 +1,19 @@
+use nm_dbus::NmConnection;
+
+use crate::{nm::nm_conn_to_keyfile, NmstateError};
+
+// Raw objects generated for backends to apply desired state, only for
+// debugging and testing.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BackendPlan {
+    // NetworkManager connections to save and activate for added and changed
+    // interfaces. OVS bridges, ports and interfaces are included as
+    // NetworkManager manages OVSDB on behalf of nmstate.
+    pub nm_connections: Vec<NmConnection>,
+}
+
+impl BackendPlan {
+    pub fn nm_keyfiles(&self) -> Result<Vec<String>, NmstateError> {
+        self.nm_connections.iter().map(nm_conn_to_keyfile).collect()
+    }
+}
@@ -3,8 +3,8 @@ use std::net::{Ipv4Addr, Ipv6Addr};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ip::is_ipv6_addr, ErrorKind, Interface, Interfaces, NetworkState,
-    NmstateError,
+    ip::is_ipv6_addr, ErrorKind, Interface, InterfaceType, Interfaces,
+    NetworkState, NmstateError,
 };
 
 const DEFAULT_DNS_PRIORITY: i32 = 40;
@@ -194,6 +194,27 @@ impl DnsClientState {
                 v4_servers.push(srv.to_string())
             }
         }
+        // The search only DNS config should be stored into IPv6 interface
+        // when no IPv4 interface is suitable, like IPv6-only host.
+        if v4_servers.is_empty() && v6_servers.is_empty() {
+            if searches.is_empty() {
+                return Ok(());
+            }
+            let is_ipv6 = v4_iface_name.is_empty() && !v6_iface_name.is_empty();
+            return _save_dns_to_iface(
+                is_ipv6,
+                if is_ipv6 {
+                    v6_iface_name
+                } else {
+                    v4_iface_name
+                },
+                (Vec::new(), searches),
+                add_net_state,
+                chg_net_state,
+                current,
+                true,
+            );
+        }
         if !v6_servers.is_empty() {
             _save_dns_to_iface(
                 true,
@@ -273,7 +294,10 @@ pub(crate) fn reselect_dns_ifaces(
 
 // Return None if specified interface has IP configuration as None.
 fn is_iface_valid_for_dns(is_ipv6: bool, iface: &Interface) -> Option<bool> {
-    if is_ipv6 {
+    // DNS should never be stored in loopback interface
+    if iface.iface_type() == InterfaceType::Loopback {
+        None
+    } else if is_ipv6 {
         iface.base_iface().ipv6.as_ref().map(|ip_conf| {
             ip_conf.enabled
                 && ((!ip_conf.dhcp && !ip_conf.autoconf)
@@ -1,3 +1,7 @@
+use std::collections::BTreeMap;
+
+use crate::ErrorMsg;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ErrorKind {
     InvalidArgument,
@@ -6,6 +10,45 @@ pub enum ErrorKind {
     VerificationError,
     NotImplementedError,
     KernelIntegerRoundedError,
+    DependencyError,
+    IpAddressConflict,
+    Timeout,
+    // OVSDB refused the transaction permanently
+    OvsDbError,
+}
+
+impl ErrorKind {
+    // Stable numeric code of each kind, never reuse a retired code
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::InvalidArgument => 1,
+            Self::PluginFailure => 2,
+            Self::Bug => 3,
+            Self::VerificationError => 4,
+            Self::NotImplementedError => 5,
+            Self::KernelIntegerRoundedError => 6,
+            Self::DependencyError => 7,
+            Self::IpAddressConflict => 8,
+            Self::Timeout => 9,
+            Self::OvsDbError => 10,
+        }
+    }
+
+    // Stable string code of each kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InvalidArgument => "invalid-argument",
+            Self::PluginFailure => "plugin-failure",
+            Self::Bug => "bug",
+            Self::VerificationError => "verification-mismatch",
+            Self::NotImplementedError => "not-implemented",
+            Self::KernelIntegerRoundedError => "kernel-integer-rounded",
+            Self::DependencyError => "dependency-error",
+            Self::IpAddressConflict => "ip-address-conflict",
+            Self::Timeout => "timeout",
+            Self::OvsDbError => "ovsdb-error",
+        }
+    }
 }
 
 impl std::fmt::Display for ErrorKind {
@@ -24,11 +67,47 @@ impl std::fmt::Display for NmstateError {
 pub struct NmstateError {
     kind: ErrorKind,
     msg: String,
+    // Machine readable details, e.g. the interface name and property path
+    context: BTreeMap<String, String>,
+    // The ID of message catalog entry and the parameters used to render it
+    msg_id: Option<&'static str>,
+    msg_params: BTreeMap<String, String>,
 }
 
 impl NmstateError {
+    pub const CONTEXT_INTERFACE: &'static str = "interface";
+    pub const CONTEXT_PROPERTY: &'static str = "property";
+    pub const CONTEXT_BACKEND: &'static str = "backend";
+    // Set when the waiting check of verification still failed after retried
+    // for the specified milliseconds, e.g. waiting for carrier timed out.
+    pub const CONTEXT_VERIFY_TIMEOUT: &'static str = "verify-timeout";
+
     pub fn new(kind: ErrorKind, msg: String) -> Self {
-        Self { kind, msg }
+        Self {
+            kind,
+            msg,
+            context: BTreeMap::new(),
+            msg_id: None,
+            msg_params: BTreeMap::new(),
+        }
+    }
+
+    pub fn from_msg(
+        kind: ErrorKind,
+        msg: ErrorMsg,
+        params: &[(&str, String)],
+    ) -> Self {
+        let msg_params: BTreeMap<String, String> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Self {
+            kind,
+            msg: ErrorMsg::render(msg.template, &msg_params),
+            context: BTreeMap::new(),
+            msg_id: Some(msg.id),
+            msg_params,
+        }
     }
 
     pub fn kind(&self) -> ErrorKind {
@@ -38,6 +117,32 @@ impl NmstateError {
     pub fn msg(&self) -> &str {
         self.msg.as_str()
     }
+
+    pub fn code(&self) -> u32 {
+        self.kind.code()
+    }
+
+    // None if the message is not from the message catalog
+    pub fn msg_id(&self) -> Option<&str> {
+        self.msg_id
+    }
+
+    pub fn msg_params(&self) -> &BTreeMap<String, String> {
+        &self.msg_params
+    }
+
+    pub fn context(&self) -> &BTreeMap<String, String> {
+        &self.context
+    }
+
+    // Existing context of the same key is not overridden, so the most
+    // specific context set by the inner layer is preserved.
+    pub fn with_context(mut self, key: &str, value: &str) -> Self {
+        self.context
+            .entry(key.to_string())
+            .or_insert_with(|| value.to_string());
+        self
+    }
 }
 
 impl From<serde_json::Error> for NmstateError {
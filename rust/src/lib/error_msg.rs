@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+// Catalog of user facing error messages. The `id` is stable for
+// programmatic matching, downstream could translate or rewrite the
+// `template` and render it with the parameters of `NmstateError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorMsg {
+    pub id: &'static str,
+    pub template: &'static str,
+}
+
+impl ErrorMsg {
+    pub const IFACE_CANNOT_HAVE_IP: Self = Self {
+        id: "iface-cannot-have-ip",
+        template: "Interface {interface} cannot have IP enabled as it is \
+            attached to a controller",
+    };
+    pub const BOND_MODE_MANDATORY: Self = Self {
+        id: "bond-mode-mandatory",
+        template: "Bond mode is mandatory",
+    };
+    pub const VERIFY_MISMATCH: Self = Self {
+        id: "verify-mismatch",
+        template: "Verification failure: {property} desire '{desire}', \
+            current '{current}'",
+    };
+    pub const VERIFY_KERNEL_INTEGER_ROUNDED: Self = Self {
+        id: "verify-kernel-integer-rounded",
+        template: "Linux kernel configured with 250 HZ will round up/down \
+            the integer in linux bridge {interface} option '{property}' \
+            from {desire} to {current}.",
+    };
+    pub const VERIFY_IFACE_NOT_FOUND: Self = Self {
+        id: "verify-iface-not-found",
+        template: "Failed to find desired interface {interface} {type}",
+    };
+    pub const VERIFY_ABSENT_IFACE_FOUND: Self = Self {
+        id: "verify-absent-iface-found",
+        template: "Absent interface {interface}/{type} still found as \
+            {current}",
+    };
+    pub const VERIFY_ABSENT_IFACE_UP: Self = Self {
+        id: "verify-absent-iface-up",
+        template: "Absent interface {interface}/{type} still found as \
+            state up: {current}",
+    };
+    pub const VERIFY_IFACE_NO_CARRIER: Self = Self {
+        id: "verify-iface-no-carrier",
+        template: "Timeout waiting carrier of interface {interface} which \
+            has require-carrier enabled, please check the cable and link \
+            partner",
+    };
+
+    pub const ALL: [Self; 8] = [
+        Self::IFACE_CANNOT_HAVE_IP,
+        Self::BOND_MODE_MANDATORY,
+        Self::VERIFY_MISMATCH,
+        Self::VERIFY_KERNEL_INTEGER_ROUNDED,
+        Self::VERIFY_IFACE_NOT_FOUND,
+        Self::VERIFY_ABSENT_IFACE_FOUND,
+        Self::VERIFY_ABSENT_IFACE_UP,
+        Self::VERIFY_IFACE_NO_CARRIER,
+    ];
+
+    // Failures of the checks waiting for something to happen, e.g. carrier
+    // up, reported as timeout when still failing after all retries.
+    pub(crate) const WAIT_TIMEOUT: [Self; 1] = [Self::VERIFY_IFACE_NO_CARRIER];
+
+    pub(crate) fn is_wait_timeout(id: &str) -> bool {
+        Self::WAIT_TIMEOUT.iter().any(|m| m.id == id)
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().find(|m| m.id == id).copied()
+    }
+
+    // Replace `{name}` in template with the value of parameter `name`.
+    // Unknown placeholders are kept as it is.
+    pub fn render(template: &str, params: &BTreeMap<String, String>) -> String {
+        let mut ret = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            ret.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match rest.find('}').and_then(|end| {
+                params.get(&rest[1..end]).map(|value| (end, value))
+            }) {
+                Some((end, value)) => {
+                    ret.push_str(value);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    ret.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        ret.push_str(rest);
+        ret
+    }
+}
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    nm::{
+        hostnamed_hostname_set, hostnamed_static_hostname_get,
+        hostnamed_static_hostname_set, nm_hostname_get, nm_hostname_set,
+    },
+    ErrorKind, NmstateError,
+};
+
+const STATIC_HOSTNAME_FILE: &str = "/etc/hostname";
+const RUNNING_HOSTNAME_FILE: &str = "/proc/sys/kernel/hostname";
+// HOST_NAME_MAX of Linux
+const HOSTNAME_MAX_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostNameState {
+    // The hostname currently used by kernel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<String>,
+    // The static hostname stored on disk, empty string means remove it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+}
+
+// The backends are tried in order until one of them succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HostNameBackend {
+    // systemd-hostnamed via D-Bus
+    Hostnamed,
+    // NetworkManager, static hostname only
+    NetworkManager,
+    // /etc/hostname for static hostname and procfs for running hostname
+    File,
+}
+
+impl std::fmt::Display for HostNameBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Hostnamed => "systemd-hostnamed",
+                Self::NetworkManager => "NetworkManager",
+                Self::File => "file",
+            }
+        )
+    }
+}
+
+pub(crate) const DEFAULT_HOSTNAME_BACKENDS: [HostNameBackend; 3] = [
+    HostNameBackend::Hostnamed,
+    HostNameBackend::NetworkManager,
+    HostNameBackend::File,
+];
+
+pub(crate) const DEFAULT_HOSTNAME_BACKENDS_KERNEL_ONLY: [HostNameBackend; 2] =
+    [HostNameBackend::Hostnamed, HostNameBackend::File];
+
+impl HostNameState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(running) = self.running.as_ref() {
+            if running.is_empty() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    "Running hostname cannot be empty".to_string(),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        for hostname in [self.running.as_ref(), self.config.as_ref()]
+            .iter()
+            .flatten()
+        {
+            if hostname.len() > HOSTNAME_MAX_LEN
+                || hostname.contains(char::is_whitespace)
+            {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Invalid hostname {:?}: should be no more than {} \
+                        characters without white space",
+                        hostname, HOSTNAME_MAX_LEN
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    // Whether current state already holds the desired hostname
+    pub(crate) fn is_matched(&self, current: Option<&Self>) -> bool {
+        let cur_running = current.and_then(|c| c.running.as_deref());
+        let cur_config =
+            current.and_then(|c| c.config.as_deref()).unwrap_or("");
+        self.running.as_deref().map(|r| Some(r) == cur_running) != Some(false)
+            && self.config.as_deref().map(|c| c == cur_config) != Some(false)
+    }
+
+    pub(crate) fn verify(
+        &self,
+        current: Option<&Self>,
+    ) -> Result<(), NmstateError> {
+        if self.is_matched(current) {
+            Ok(())
+        } else {
+            // Do not log verification error as we have fail-retry
+            Err(NmstateError::new(
+                ErrorKind::VerificationError,
+                format!(
+                    "Failed to apply hostname config: desire {:?} got {:?}",
+                    self, current
+                ),
+            ))
+        }
+    }
+}
+
+// Try backends in order, return the result of first succeeded one or the
+// error of last backend. Only the DependencyError(backend unavailable) falls
+// back to next backend, other errors are returned immediately.
+pub(crate) fn with_hostname_backends<T, F>(
+    backends: &[HostNameBackend],
+    action: &str,
+    func: F,
+) -> Result<T, NmstateError>
+where
+    F: Fn(HostNameBackend) -> Result<T, NmstateError>,
+{
+    let mut last_error = None;
+    for backend in backends {
+        match func(*backend) {
+            Ok(ret) => return Ok(ret),
+            Err(e) if e.kind() == ErrorKind::DependencyError => {
+                log::info!(
+                    "Failed to {} via {}: {}, trying next backend",
+                    action,
+                    backend,
+                    e
+                );
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("No hostname backend defined to {}", action),
+        )
+    }))
+}
+
+pub(crate) fn get_hostname_state(
+    backends: &[HostNameBackend],
+    running_config_only: bool,
+) -> HostNameState {
+    let running = if running_config_only {
+        None
+    } else {
+        match std::fs::read_to_string(RUNNING_HOSTNAME_FILE) {
+            Ok(s) => Some(s.trim().to_string()),
+            Err(e) => {
+                log::warn!("Failed to read {}: {}", RUNNING_HOSTNAME_FILE, e);
+                None
+            }
+        }
+    };
+    let config = match with_hostname_backends(
+        backends,
+        "retrieve static hostname",
+        |backend| match backend {
+            HostNameBackend::Hostnamed => hostnamed_static_hostname_get(),
+            HostNameBackend::NetworkManager => nm_hostname_get(),
+            HostNameBackend::File => static_hostname_file_get(),
+        },
+    ) {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("Failed to retrieve static hostname: {}", e);
+            None
+        }
+    };
+    HostNameState { running, config }
+}
+
+// Please be noticed, the checkpoint of NetworkManager does not cover hostname,
+// so the hostname change will not be rollbacked on failure.
+pub(crate) fn apply_hostname(
+    desired: &HostNameState,
+    backends: &[HostNameBackend],
+) -> Result<(), NmstateError> {
+    if let Some(config) = desired.config.as_deref() {
+        log::info!("Setting static hostname to {:?}", config);
+        with_hostname_backends(backends, "set static hostname", |backend| {
+            match backend {
+                HostNameBackend::Hostnamed => {
+                    hostnamed_static_hostname_set(config)
+                }
+                HostNameBackend::NetworkManager => nm_hostname_set(config),
+                HostNameBackend::File => static_hostname_file_set(config),
+            }
+        })?;
+    }
+    if let Some(running) = desired.running.as_deref() {
+        log::info!("Setting running hostname to {:?}", running);
+        with_hostname_backends(backends, "set running hostname", |backend| {
+            match backend {
+                HostNameBackend::Hostnamed => hostnamed_hostname_set(running),
+                HostNameBackend::NetworkManager => Err(NmstateError::new(
+                    ErrorKind::DependencyError,
+                    "NetworkManager does not support setting running \
+                        hostname"
+                        .to_string(),
+                )),
+                HostNameBackend::File => {
+                    write_file(RUNNING_HOSTNAME_FILE, running)
+                }
+            }
+        })?;
+    }
+    Ok(())
+}
+
+fn static_hostname_file_get() -> Result<Option<String>, NmstateError> {
+    match std::fs::read_to_string(STATIC_HOSTNAME_FILE) {
+        Ok(content) => Ok(content
+            .lines()
+            .map(|l| l.trim())
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| l.to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Failed to read {}: {}", STATIC_HOSTNAME_FILE, e),
+        )),
+    }
+}
+
+fn static_hostname_file_set(hostname: &str) -> Result<(), NmstateError> {
+    if hostname.is_empty() {
+        match std::fs::remove_file(STATIC_HOSTNAME_FILE) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(NmstateError::new(
+                    ErrorKind::PluginFailure,
+                    format!("Failed to remove {}: {}", STATIC_HOSTNAME_FILE, e),
+                ))
+            }
+            _ => Ok(()),
+        }
+    } else {
+        write_file(STATIC_HOSTNAME_FILE, &format!("{}\n", hostname))
+    }
+}
+
+fn write_file(path: &str, content: &str) -> Result<(), NmstateError> {
+    std::fs::write(path, content).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Failed to write {}: {}", path, e),
+        )
+    })
+}
@@ -3,9 +3,12 @@ use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::{
     state::get_json_value_difference, BaseInterface, BondInterface,
-    DummyInterface, ErrorKind, EthernetInterface, LinuxBridgeInterface,
+    DummyInterface, ErrorKind, ErrorMsg, EthernetInterface, GeneveInterface,
+    GreInterface, HsrInterface, InfiniBandInterface, IpVlanInterface,
+    IpsecInterface, LinuxBridgeInterface, LoopbackInterface, MacSecInterface,
     MacVlanInterface, MacVtapInterface, NmstateError, OvsBridgeInterface,
-    OvsInterface, VlanInterface,
+    OvsInterface, PppoeInterface, TeamInterface, VlanInterface, VrfInterface,
+    WifiInterface, WireGuardInterface, XfrmInterface,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -15,16 +18,34 @@ pub enum InterfaceType {
     LinuxBridge,
     Dummy,
     Ethernet,
+    Geneve,
+    Gre,
+    #[serde(rename = "gretap")]
+    GreTap,
+    Hsr,
+    #[serde(rename = "infiniband")]
+    InfiniBand,
+    #[serde(rename = "ipvlan")]
+    IpVlan,
+    Ipsec,
     Loopback,
+    #[serde(rename = "macsec")]
+    MacSec,
     MacVlan,
     MacVtap,
     OvsBridge,
     OvsInterface,
+    Pppoe,
+    Team,
     Tun,
     Veth,
     Vlan,
     Vrf,
     Vxlan,
+    Wifi,
+    #[serde(rename = "wireguard")]
+    WireGuard,
+    Xfrm,
     Unknown,
     Other(String),
 }
@@ -42,16 +63,29 @@ impl From<&str> for InterfaceType {
             "linux-bridge" => InterfaceType::LinuxBridge,
             "dummy" => InterfaceType::Dummy,
             "ethernet" => InterfaceType::Ethernet,
+            "geneve" => InterfaceType::Geneve,
+            "gre" => InterfaceType::Gre,
+            "gretap" => InterfaceType::GreTap,
+            "hsr" => InterfaceType::Hsr,
+            "infiniband" => InterfaceType::InfiniBand,
+            "ipvlan" => InterfaceType::IpVlan,
+            "ipsec" => InterfaceType::Ipsec,
             "loopback" => InterfaceType::Loopback,
+            "macsec" => InterfaceType::MacSec,
             "macvlan" => InterfaceType::MacVlan,
             "macvtap" => InterfaceType::MacVtap,
             "ovs-bridge" => InterfaceType::OvsBridge,
             "ovs-interface" => InterfaceType::OvsInterface,
+            "pppoe" => InterfaceType::Pppoe,
+            "team" => InterfaceType::Team,
             "tun" => InterfaceType::Tun,
             "veth" => InterfaceType::Veth,
             "vlan" => InterfaceType::Vlan,
             "vrf" => InterfaceType::Vrf,
             "vxlan" => InterfaceType::Vxlan,
+            "wifi" => InterfaceType::Wifi,
+            "wireguard" => InterfaceType::WireGuard,
+            "xfrm" => InterfaceType::Xfrm,
             "unknown" => InterfaceType::Unknown,
             _ => InterfaceType::Other(s.to_string()),
         }
@@ -68,16 +102,29 @@ impl std::fmt::Display for InterfaceType {
                 InterfaceType::LinuxBridge => "linux-bridge",
                 InterfaceType::Dummy => "dummy",
                 InterfaceType::Ethernet => "ethernet",
+                InterfaceType::Geneve => "geneve",
+                InterfaceType::Gre => "gre",
+                InterfaceType::GreTap => "gretap",
+                InterfaceType::Hsr => "hsr",
+                InterfaceType::InfiniBand => "infiniband",
+                InterfaceType::IpVlan => "ipvlan",
+                InterfaceType::Ipsec => "ipsec",
                 InterfaceType::Loopback => "loopback",
+                InterfaceType::MacSec => "macsec",
                 InterfaceType::MacVlan => "macvlan",
                 InterfaceType::MacVtap => "macvtap",
                 InterfaceType::OvsBridge => "ovs-bridge",
                 InterfaceType::OvsInterface => "ovs-interface",
+                InterfaceType::Pppoe => "pppoe",
+                InterfaceType::Team => "team",
                 InterfaceType::Tun => "tun",
                 InterfaceType::Veth => "veth",
                 InterfaceType::Vlan => "vlan",
                 InterfaceType::Vrf => "vrf",
                 InterfaceType::Vxlan => "vxlan",
+                InterfaceType::Wifi => "wifi",
+                InterfaceType::WireGuard => "wireguard",
+                InterfaceType::Xfrm => "xfrm",
                 InterfaceType::Unknown => "unknown",
                 InterfaceType::Other(ref s) => s,
             }
@@ -86,9 +133,15 @@ impl std::fmt::Display for InterfaceType {
 }
 
 impl InterfaceType {
-    const USERSPACE_IFACE_TYPES: [Self; 2] = [Self::OvsBridge, Self::Unknown];
-    const CONTROLLER_IFACES_TYPES: [Self; 3] =
-        [Self::Bond, Self::LinuxBridge, Self::OvsBridge];
+    const USERSPACE_IFACE_TYPES: [Self; 3] =
+        [Self::Ipsec, Self::OvsBridge, Self::Unknown];
+    const CONTROLLER_IFACES_TYPES: [Self; 5] = [
+        Self::Bond,
+        Self::LinuxBridge,
+        Self::OvsBridge,
+        Self::Team,
+        Self::Vrf,
+    ];
 
     // Unknown and other interfaces are also considered as userspace
     pub(crate) fn is_userspace(&self) -> bool {
@@ -102,6 +155,37 @@ impl InterfaceType {
     pub(crate) fn is_controller(&self) -> bool {
         Self::CONTROLLER_IFACES_TYPES.contains(self)
     }
+
+    // NetworkManager reports veth as ethernet, hence they are treated as the
+    // same type unless `strict_veth` is set.
+    pub(crate) fn is_same_type(&self, other: &Self, strict_veth: bool) -> bool {
+        self == other
+            || (!strict_veth
+                && [self, other]
+                    .iter()
+                    .all(|t| matches!(t, Self::Ethernet | Self::Veth)))
+    }
+}
+
+// Whether the config of interface should survive reboot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InterfacePersist {
+    Disk,
+    Memory,
+}
+
+impl std::fmt::Display for InterfacePersist {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Disk => "disk",
+                Self::Memory => "memory",
+            }
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -119,6 +203,21 @@ impl Default for InterfaceState {
     }
 }
 
+impl std::fmt::Display for InterfaceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Up => "up",
+                Self::Down => "down",
+                Self::Absent => "absent",
+                Self::Unknown => "unknown",
+            }
+        )
+    }
+}
+
 impl From<&str> for InterfaceState {
     fn from(s: &str) -> Self {
         match s {
@@ -148,13 +247,27 @@ pub enum Interface {
     Bond(BondInterface),
     Dummy(DummyInterface),
     Ethernet(EthernetInterface),
+    Geneve(GeneveInterface),
+    Gre(GreInterface),
+    Hsr(HsrInterface),
+    InfiniBand(InfiniBandInterface),
+    IpVlan(IpVlanInterface),
+    Ipsec(IpsecInterface),
     LinuxBridge(LinuxBridgeInterface),
+    Loopback(LoopbackInterface),
     OvsBridge(OvsBridgeInterface),
     OvsInterface(OvsInterface),
+    Pppoe(PppoeInterface),
     Unknown(UnknownInterface),
     Vlan(VlanInterface),
+    MacSec(MacSecInterface),
     MacVlan(MacVlanInterface),
     MacVtap(MacVtapInterface),
+    Vrf(VrfInterface),
+    Team(TeamInterface),
+    Wifi(WifiInterface),
+    WireGuard(WireGuardInterface),
+    Xfrm(XfrmInterface),
 }
 
 impl<'de> Deserialize<'de> for Interface {
@@ -162,76 +275,126 @@ impl<'de> Deserialize<'de> for Interface {
     where
         D: Deserializer<'de>,
     {
-        let v = serde_json::Value::deserialize(deserializer)?;
-        match Option::deserialize(&v["type"])
-            .map_err(serde::de::Error::custom)?
-        {
+        Interface::from_value(serde_json::Value::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Interface {
+    // The interface type is required before deserializing into specific
+    // interface, so the JSON value is consumed here without being cloned.
+    pub(crate) fn from_value(
+        v: serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        match Option::deserialize(&v["type"])? {
             Some(InterfaceType::Ethernet) => {
-                let inner = EthernetInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = EthernetInterface::deserialize(v)?;
                 Ok(Interface::Ethernet(inner))
             }
             Some(InterfaceType::LinuxBridge) => {
-                let inner = LinuxBridgeInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = LinuxBridgeInterface::deserialize(v)?;
                 Ok(Interface::LinuxBridge(inner))
             }
             Some(InterfaceType::Bond) => {
-                let inner = BondInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = BondInterface::deserialize(v)?;
                 Ok(Interface::Bond(inner))
             }
             Some(InterfaceType::Veth) => {
-                let inner = EthernetInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = EthernetInterface::deserialize(v)?;
                 Ok(Interface::Ethernet(inner))
             }
             Some(InterfaceType::Vlan) => {
-                let inner = VlanInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = VlanInterface::deserialize(v)?;
                 Ok(Interface::Vlan(inner))
             }
             Some(InterfaceType::Dummy) => {
-                let inner = DummyInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = DummyInterface::deserialize(v)?;
                 Ok(Interface::Dummy(inner))
             }
             Some(InterfaceType::OvsInterface) => {
-                let inner = OvsInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = OvsInterface::deserialize(v)?;
                 Ok(Interface::OvsInterface(inner))
             }
             Some(InterfaceType::OvsBridge) => {
-                let inner = OvsBridgeInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = OvsBridgeInterface::deserialize(v)?;
                 Ok(Interface::OvsBridge(inner))
             }
+            Some(InterfaceType::MacSec) => {
+                let inner = MacSecInterface::deserialize(v)?;
+                Ok(Interface::MacSec(inner))
+            }
             Some(InterfaceType::MacVlan) => {
-                let inner = MacVlanInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = MacVlanInterface::deserialize(v)?;
                 Ok(Interface::MacVlan(inner))
             }
             Some(InterfaceType::MacVtap) => {
-                let inner = MacVtapInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = MacVtapInterface::deserialize(v)?;
                 Ok(Interface::MacVtap(inner))
             }
+            Some(InterfaceType::Vrf) => {
+                let inner = VrfInterface::deserialize(v)?;
+                Ok(Interface::Vrf(inner))
+            }
+            Some(InterfaceType::Team) => {
+                let inner = TeamInterface::deserialize(v)?;
+                Ok(Interface::Team(inner))
+            }
+            Some(InterfaceType::Geneve) => {
+                let inner = GeneveInterface::deserialize(v)?;
+                Ok(Interface::Geneve(inner))
+            }
+            Some(InterfaceType::Gre) | Some(InterfaceType::GreTap) => {
+                let inner = GreInterface::deserialize(v)?;
+                Ok(Interface::Gre(inner))
+            }
+            Some(InterfaceType::Hsr) => {
+                let inner = HsrInterface::deserialize(v)?;
+                Ok(Interface::Hsr(inner))
+            }
+            Some(InterfaceType::InfiniBand) => {
+                let inner = InfiniBandInterface::deserialize(v)?;
+                Ok(Interface::InfiniBand(inner))
+            }
+            Some(InterfaceType::IpVlan) => {
+                let inner = IpVlanInterface::deserialize(v)?;
+                Ok(Interface::IpVlan(inner))
+            }
+            Some(InterfaceType::Ipsec) => {
+                let inner = IpsecInterface::deserialize(v)?;
+                Ok(Interface::Ipsec(inner))
+            }
+            Some(InterfaceType::Loopback) => {
+                let inner = LoopbackInterface::deserialize(v)?;
+                Ok(Interface::Loopback(inner))
+            }
+            Some(InterfaceType::Pppoe) => {
+                let inner = PppoeInterface::deserialize(v)?;
+                Ok(Interface::Pppoe(inner))
+            }
+            Some(InterfaceType::Wifi) => {
+                let inner = WifiInterface::deserialize(v)?;
+                Ok(Interface::Wifi(inner))
+            }
+            Some(InterfaceType::WireGuard) => {
+                let inner = WireGuardInterface::deserialize(v)?;
+                Ok(Interface::WireGuard(inner))
+            }
+            Some(InterfaceType::Xfrm) => {
+                let inner = XfrmInterface::deserialize(v)?;
+                Ok(Interface::Xfrm(inner))
+            }
             Some(iface_type) => {
                 warn!("Unsupported interface type {}", iface_type);
-                let inner = UnknownInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = UnknownInterface::deserialize(v)?;
                 Ok(Interface::Unknown(inner))
             }
             None => {
-                let inner = UnknownInterface::deserialize(v)
-                    .map_err(serde::de::Error::custom)?;
+                let inner = UnknownInterface::deserialize(v)?;
                 Ok(Interface::Unknown(inner))
             }
         }
     }
-}
 
-impl Interface {
     pub fn name(&self) -> &str {
         self.base_iface().name.as_str()
     }
@@ -289,6 +452,11 @@ impl Interface {
                 new_iface.base = iface.base.clone_name_type_only();
                 Self::Bond(new_iface)
             }
+            Self::MacSec(iface) => {
+                let mut new_iface = MacSecInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::MacSec(new_iface)
+            }
             Self::MacVlan(iface) => {
                 let mut new_iface = MacVlanInterface::new();
                 new_iface.base = iface.base.clone_name_type_only();
@@ -299,6 +467,71 @@ impl Interface {
                 new_iface.base = iface.base.clone_name_type_only();
                 Self::MacVtap(new_iface)
             }
+            Self::Vrf(iface) => {
+                let mut new_iface = VrfInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Vrf(new_iface)
+            }
+            Self::Team(iface) => {
+                let mut new_iface = TeamInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Team(new_iface)
+            }
+            Self::Geneve(iface) => {
+                let mut new_iface = GeneveInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Geneve(new_iface)
+            }
+            Self::Gre(iface) => {
+                let mut new_iface = GreInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Gre(new_iface)
+            }
+            Self::Hsr(iface) => {
+                let mut new_iface = HsrInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Hsr(new_iface)
+            }
+            Self::InfiniBand(iface) => {
+                let mut new_iface = InfiniBandInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::InfiniBand(new_iface)
+            }
+            Self::IpVlan(iface) => {
+                let mut new_iface = IpVlanInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::IpVlan(new_iface)
+            }
+            Self::Ipsec(iface) => {
+                let mut new_iface = IpsecInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Ipsec(new_iface)
+            }
+            Self::Loopback(iface) => {
+                let mut new_iface = LoopbackInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Loopback(new_iface)
+            }
+            Self::Pppoe(iface) => {
+                let mut new_iface = PppoeInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Pppoe(new_iface)
+            }
+            Self::Wifi(iface) => {
+                let mut new_iface = WifiInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Wifi(new_iface)
+            }
+            Self::WireGuard(iface) => {
+                let mut new_iface = WireGuardInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::WireGuard(new_iface)
+            }
+            Self::Xfrm(iface) => {
+                let mut new_iface = XfrmInterface::new();
+                new_iface.base = iface.base.clone_name_type_only();
+                Self::Xfrm(new_iface)
+            }
             Self::Unknown(iface) => {
                 let mut new_iface = UnknownInterface::new();
                 new_iface.base = iface.base.clone_name_type_only();
@@ -320,7 +553,13 @@ impl Interface {
     }
 
     pub fn is_virtual(&self) -> bool {
-        !matches!(self, Self::Ethernet(_) | Self::Unknown(_))
+        !matches!(
+            self,
+            Self::Ethernet(_)
+                | Self::Loopback(_)
+                | Self::Unknown(_)
+                | Self::Wifi(_)
+        )
     }
 
     // OVS Interface should be deleted along with its controller
@@ -337,8 +576,22 @@ impl Interface {
             Self::Dummy(iface) => &iface.base,
             Self::OvsBridge(iface) => &iface.base,
             Self::OvsInterface(iface) => &iface.base,
+            Self::MacSec(iface) => &iface.base,
             Self::MacVlan(iface) => &iface.base,
             Self::MacVtap(iface) => &iface.base,
+            Self::Vrf(iface) => &iface.base,
+            Self::Team(iface) => &iface.base,
+            Self::Geneve(iface) => &iface.base,
+            Self::Gre(iface) => &iface.base,
+            Self::Hsr(iface) => &iface.base,
+            Self::InfiniBand(iface) => &iface.base,
+            Self::IpVlan(iface) => &iface.base,
+            Self::Ipsec(iface) => &iface.base,
+            Self::Loopback(iface) => &iface.base,
+            Self::Pppoe(iface) => &iface.base,
+            Self::Wifi(iface) => &iface.base,
+            Self::WireGuard(iface) => &iface.base,
+            Self::Xfrm(iface) => &iface.base,
             Self::Unknown(iface) => &iface.base,
         }
     }
@@ -352,8 +605,22 @@ impl Interface {
             Self::Dummy(iface) => &mut iface.base,
             Self::OvsInterface(iface) => &mut iface.base,
             Self::OvsBridge(iface) => &mut iface.base,
+            Self::MacSec(iface) => &mut iface.base,
             Self::MacVlan(iface) => &mut iface.base,
             Self::MacVtap(iface) => &mut iface.base,
+            Self::Vrf(iface) => &mut iface.base,
+            Self::Team(iface) => &mut iface.base,
+            Self::Geneve(iface) => &mut iface.base,
+            Self::Gre(iface) => &mut iface.base,
+            Self::Hsr(iface) => &mut iface.base,
+            Self::InfiniBand(iface) => &mut iface.base,
+            Self::IpVlan(iface) => &mut iface.base,
+            Self::Ipsec(iface) => &mut iface.base,
+            Self::Loopback(iface) => &mut iface.base,
+            Self::Pppoe(iface) => &mut iface.base,
+            Self::Wifi(iface) => &mut iface.base,
+            Self::WireGuard(iface) => &mut iface.base,
+            Self::Xfrm(iface) => &mut iface.base,
             Self::Unknown(iface) => &mut iface.base,
         }
     }
@@ -365,6 +632,8 @@ impl Interface {
                 Self::LinuxBridge(_) => Some(Vec::new()),
                 Self::OvsBridge(_) => Some(Vec::new()),
                 Self::Bond(_) => Some(Vec::new()),
+                Self::Vrf(_) => Some(Vec::new()),
+                Self::Team(_) => Some(Vec::new()),
                 _ => None,
             }
         } else {
@@ -372,6 +641,8 @@ impl Interface {
                 Self::LinuxBridge(iface) => iface.ports(),
                 Self::OvsBridge(iface) => iface.ports(),
                 Self::Bond(iface) => iface.ports(),
+                Self::Vrf(iface) => iface.ports(),
+                Self::Team(iface) => iface.ports(),
                 _ => None,
             }
         }
@@ -434,6 +705,16 @@ impl Interface {
                     );
                 }
             }
+            Self::MacSec(iface) => {
+                if let Self::MacSec(other_iface) = other {
+                    iface.update_macsec(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
             Self::MacVlan(iface) => {
                 if let Self::MacVlan(other_iface) = other {
                     iface.update_mac_vlan(other_iface);
@@ -454,7 +735,130 @@ impl Interface {
                     );
                 }
             }
-            Self::Unknown(_) | Self::Dummy(_) | Self::OvsInterface(_) => (),
+            Self::Vrf(iface) => {
+                if let Self::Vrf(other_iface) = other {
+                    iface.update_vrf(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Team(iface) => {
+                if let Self::Team(other_iface) = other {
+                    iface.update_team(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Geneve(iface) => {
+                if let Self::Geneve(other_iface) = other {
+                    iface.update_geneve(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Gre(iface) => {
+                if let Self::Gre(other_iface) = other {
+                    iface.update_gre(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Ipsec(iface) => {
+                if let Self::Ipsec(other_iface) = other {
+                    iface.update_ipsec(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Hsr(iface) => {
+                if let Self::Hsr(other_iface) = other {
+                    iface.update_hsr(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::InfiniBand(iface) => {
+                if let Self::InfiniBand(other_iface) = other {
+                    iface.update_infiniband(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::IpVlan(iface) => {
+                if let Self::IpVlan(other_iface) = other {
+                    iface.update_ipvlan(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Pppoe(iface) => {
+                if let Self::Pppoe(other_iface) = other {
+                    iface.update_pppoe(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Wifi(iface) => {
+                if let Self::Wifi(other_iface) = other {
+                    iface.update_wifi(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::WireGuard(iface) => {
+                if let Self::WireGuard(other_iface) = other {
+                    iface.update_wireguard(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Xfrm(iface) => {
+                if let Self::Xfrm(other_iface) = other {
+                    iface.update_xfrm(other_iface);
+                } else {
+                    warn!(
+                        "Don't know how to update iface {:?} with {:?}",
+                        iface, other
+                    );
+                }
+            }
+            Self::Unknown(_)
+            | Self::Dummy(_)
+            | Self::Loopback(_)
+            | Self::OvsInterface(_) => (),
         }
     }
 
@@ -473,6 +877,33 @@ impl Interface {
             Self::OvsBridge(ref mut iface) => {
                 iface.pre_verify_cleanup();
             }
+            Self::Vrf(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::Team(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::Ipsec(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::MacSec(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::InfiniBand(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::Pppoe(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::Wifi(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::WireGuard(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
+            Self::Loopback(ref mut iface) => {
+                iface.pre_verify_cleanup();
+            }
             _ => (),
         }
     }
@@ -498,9 +929,20 @@ impl Interface {
         }
         self_clone.pre_verify_cleanup();
         current_clone.pre_verify_cleanup();
+        if let (Self::Bond(des_iface), Self::Bond(cur_iface)) =
+            (&self_clone, &mut current_clone)
+        {
+            cur_iface.retain_desired_port_confs(des_iface);
+        }
         if self_clone.iface_type() == InterfaceType::Unknown {
             current_clone.base_iface_mut().iface_type = InterfaceType::Unknown;
         }
+        if let (Some(des_ethtool), Some(cur_ethtool)) = (
+            self_clone.base_iface_mut().ethtool.as_mut(),
+            current_clone.base_iface().ethtool.as_ref(),
+        ) {
+            des_ethtool.accept_driver_rounding(cur_ethtool);
+        }
 
         let self_value = serde_json::to_value(&self_clone)?;
         let current_value = serde_json::to_value(&current_clone)?;
@@ -517,21 +959,25 @@ impl Interface {
                 serde_json::Value::Number(cur),
             ) = (desire, current)
             {
-                if desire.as_u64().unwrap_or(0) - cur.as_u64().unwrap_or(0) == 1
+                if des
+                    .as_u64()
+                    .unwrap_or(0)
+                    .checked_sub(cur.as_u64().unwrap_or(0))
+                    == Some(1)
                     && LinuxBridgeInterface::is_interger_rounded_up(&reference)
                 {
-                    let e = NmstateError::new(
+                    let e = NmstateError::from_msg(
                         ErrorKind::KernelIntegerRoundedError,
-                        format!(
-                            "Linux kernel configured with 250 HZ \
-                                will round up/down the integer in linux \
-                                bridge {} option '{}' from {:?} to {:?}.",
-                            self.name(),
-                            reference,
-                            des,
-                            cur
-                        ),
-                    );
+                        ErrorMsg::VERIFY_KERNEL_INTEGER_ROUNDED,
+                        &[
+                            ("interface", self.name().to_string()),
+                            ("property", reference.clone()),
+                            ("desire", des.to_string()),
+                            ("current", cur.to_string()),
+                        ],
+                    )
+                    .with_context(NmstateError::CONTEXT_INTERFACE, self.name())
+                    .with_context(NmstateError::CONTEXT_PROPERTY, &reference);
                     error!("{}", e);
                     return Err(e);
                 }
@@ -541,24 +987,66 @@ impl Interface {
                 return Ok(());
             }
 
-            Err(NmstateError::new(
+            Err(NmstateError::from_msg(
                 ErrorKind::VerificationError,
-                format!(
-                    "Verification failure: {} desire '{}', current '{}'",
-                    reference, desire, current
-                ),
-            ))
+                ErrorMsg::VERIFY_MISMATCH,
+                &[
+                    ("property", reference.clone()),
+                    ("desire", desire.to_string()),
+                    ("current", current.to_string()),
+                ],
+            )
+            .with_context(NmstateError::CONTEXT_INTERFACE, self.name())
+            .with_context(NmstateError::CONTEXT_PROPERTY, &reference))
         } else {
             Ok(())
         }
     }
 
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        self.base_iface().validate()?;
         match self {
             Interface::LinuxBridge(iface) => iface.validate(),
             Interface::Bond(iface) => iface.validate(),
+            Interface::MacSec(iface) => iface.validate(),
             Interface::MacVlan(iface) => iface.validate(),
             Interface::MacVtap(iface) => iface.validate(),
+            Interface::OvsBridge(iface) => iface.validate(),
+            Interface::Vlan(iface) => iface.validate(),
+            Interface::Vrf(iface) => iface.validate(),
+            Interface::Team(iface) => iface.validate(),
+            Interface::Geneve(iface) => iface.validate(),
+            Interface::Gre(iface) => iface.validate(),
+            Interface::Hsr(iface) => iface.validate(),
+            Interface::InfiniBand(iface) => iface.validate(),
+            Interface::IpVlan(iface) => iface.validate(),
+            Interface::Ipsec(iface) => iface.validate(),
+            Interface::Loopback(iface) => iface.validate(),
+            Interface::Pppoe(iface) => iface.validate(),
+            Interface::Wifi(iface) => iface.validate(),
+            Interface::WireGuard(iface) => iface.validate(),
+            Interface::Xfrm(iface) => iface.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn resolve_ports_add_remove(
+        &mut self,
+        current: Option<&Self>,
+    ) -> Result<(), NmstateError> {
+        match self {
+            Self::LinuxBridge(iface) => {
+                iface.resolve_ports_add_remove(match current {
+                    Some(Self::LinuxBridge(c)) => Some(c),
+                    _ => None,
+                })
+            }
+            Self::Bond(iface) => {
+                iface.resolve_ports_add_remove(match current {
+                    Some(Self::Bond(c)) => Some(c),
+                    _ => None,
+                })
+            }
             _ => Ok(()),
         }
     }
@@ -568,6 +1056,10 @@ impl Interface {
             br_iface.remove_port(port_name);
         } else if let Interface::Bond(iface) = self {
             iface.remove_port(port_name);
+        } else if let Interface::Vrf(iface) = self {
+            iface.remove_port(port_name);
+        } else if let Interface::Team(iface) = self {
+            iface.remove_port(port_name);
         }
     }
 
@@ -575,8 +1067,13 @@ impl Interface {
         match self {
             Interface::Vlan(vlan) => vlan.parent(),
             Interface::OvsInterface(ovs) => ovs.parent(),
+            Interface::MacSec(iface) => iface.parent(),
             Interface::MacVlan(vlan) => vlan.parent(),
             Interface::MacVtap(vtap) => vtap.parent(),
+            Interface::InfiniBand(iface) => iface.parent(),
+            Interface::IpVlan(iface) => iface.parent(),
+            Interface::Pppoe(iface) => iface.parent(),
+            Interface::Xfrm(iface) => iface.parent(),
             _ => None,
         }
     }
@@ -2,8 +2,9 @@ use log::error;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ErrorKind, InterfaceIpv4, InterfaceIpv6, InterfaceState, InterfaceType,
-    NmstateError, RouteEntry, RouteRuleEntry,
+    ErrorKind, ErrorMsg, EthtoolConfig, InterfaceAddrAnnounce, InterfaceIpv4,
+    InterfaceIpv6, InterfacePersist, InterfaceState, InterfaceType,
+    NmstateError, OvsDbIfaceConfig, RouteEntry, RouteRuleEntry,
 };
 
 // TODO: Use prop_list to Serialize like InterfaceIpv4 did
@@ -31,9 +32,51 @@ pub struct BaseInterface {
     pub controller: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub accept_all_mac_addresses: Option<bool>,
+    // Opt-in to manage existing interface of type unsupported by nmstate,
+    // only the MTU and state are applied, kernel mode only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passthrough: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ethtool: Option<EthtoolConfig>,
+    // Only applicable to NetworkManager backend, default to disk.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persist: Option<InterfacePersist>,
+    // Only applicable to NetworkManager backend, restrict the profile to
+    // these users. Default to all users.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    // Only applicable to NetworkManager backend, UUIDs of the profiles(e.g.
+    // VPN) to activate after this interface activated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondaries: Option<Vec<String>>,
+    // Override the activation order computed from controller relationship,
+    // interface with lower priority will be activated first. Port should
+    // have larger priority than its controller.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
+    // Only applicable to OVS bridge, OVS interface and OVS system port.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "ovs-db")]
+    pub ovsdb: Option<OvsDbIfaceConfig>,
+    // Only for query: the ID of desired state which generated the
+    // NetworkManager profile of this interface. Ignored when applying.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_id: Option<String>,
     #[serde(skip_serializing)]
     pub copy_mac_from: Option<String>,
-    #[serde(skip)]
+    #[serde(skip_serializing)]
+    pub address_announce: Option<InterfaceAddrAnnounce>,
+    // When DHCP or IPv6 autoconf switch from ON to OFF, the dynamic IP
+    // addresses and routes are converted to static unless this is set to
+    // false. Default to true.
+    #[serde(skip_serializing)]
+    pub keep_dynamic_addresses: Option<bool>,
+    // Verification waits for the carrier(lower layer up) of the interface
+    // instead of administrative up only. Default to false.
+    #[serde(skip_serializing)]
+    pub require_carrier: Option<bool>,
+    // Could be defined in desire state along with `controller` property to
+    // attach port to controller without listing it in controller ports.
+    #[serde(skip_serializing)]
     pub controller_type: Option<InterfaceType>,
     // The interface lowest up_priority will be activated first.
     // The up_priority should be its controller's up_priority
@@ -41,6 +84,9 @@ pub struct BaseInterface {
     // The 0 means top controller or no controller.
     #[serde(skip)]
     pub(crate) up_priority: u32,
+    // Only for query: whether kernel reports carrier on this interface
+    #[serde(skip)]
+    pub(crate) carrier: Option<bool>,
     #[serde(skip)]
     pub(crate) routes: Option<Vec<RouteEntry>>,
     #[serde(skip)]
@@ -72,9 +118,37 @@ impl BaseInterface {
         if other.prop_list.contains(&"controller_type") {
             self.controller_type = other.controller_type.clone();
         }
+        if other.prop_list.contains(&"carrier") {
+            self.carrier = other.carrier;
+        }
         if other.prop_list.contains(&"accept_all_mac_addresses") {
             self.accept_all_mac_addresses = other.accept_all_mac_addresses;
         }
+        if other.prop_list.contains(&"ethtool") {
+            if let Some(other_ethtool) = other.ethtool.as_ref() {
+                self.ethtool
+                    .get_or_insert_with(EthtoolConfig::new)
+                    .update(other_ethtool);
+            }
+        }
+        if other.prop_list.contains(&"persist") {
+            self.persist = other.persist;
+        }
+        if other.prop_list.contains(&"permissions") {
+            self.permissions = other.permissions.clone();
+        }
+        if other.prop_list.contains(&"secondaries") {
+            self.secondaries = other.secondaries.clone();
+        }
+        if other.prop_list.contains(&"priority") {
+            self.priority = other.priority;
+        }
+        if other.prop_list.contains(&"ovsdb") {
+            self.ovsdb = other.ovsdb.clone();
+        }
+        if other.prop_list.contains(&"state_id") {
+            self.state_id = other.state_id.clone();
+        }
 
         if other.prop_list.contains(&"ipv4") {
             if let Some(ref other_ipv4) = other.ipv4 {
@@ -106,12 +180,25 @@ impl BaseInterface {
         if !self.can_have_ip()
             && (self.ipv4.as_ref().map(|ipv4| ipv4.enabled) == Some(true)
                 || self.ipv6.as_ref().map(|ipv6| ipv6.enabled) == Some(true))
+        {
+            let e = NmstateError::from_msg(
+                ErrorKind::InvalidArgument,
+                ErrorMsg::IFACE_CANNOT_HAVE_IP,
+                &[("interface", self.name.clone())],
+            );
+            error!("{}", e);
+            return Err(e);
+        }
+        // The NAT64 prefix used by CLAT is learned from IPv6 RA
+        if self.ipv4.as_ref().map(|ipv4| ipv4.is_clat_enabled()) == Some(true)
+            && self.ipv6.as_ref().map(|ipv6| ipv6.enabled && ipv6.autoconf)
+                != Some(true)
         {
             let e = NmstateError::new(
                 ErrorKind::InvalidArgument,
                 format!(
-                    "Interface {} cannot have IP enabled as it is \
-                    attached to a controller",
+                    "Interface {} has IPv4 CLAT enabled which requires \
+                    IPv6 autoconf enabled",
                     self.name
                 ),
             );
@@ -119,6 +206,9 @@ impl BaseInterface {
             return Err(e);
         }
 
+        if let Some(ethtool) = self.ethtool.as_mut() {
+            ethtool.apply_feature_alias();
+        }
         if let Some(ref mut ipv4) = self.ipv4 {
             ipv4.pre_edit_cleanup()?
         }
@@ -129,6 +219,20 @@ impl BaseInterface {
     }
 
     pub(crate) fn pre_verify_cleanup(&mut self) {
+        // Current state does not include where the config is stored or the
+        // activation order
+        self.persist = None;
+        self.priority = None;
+        self.state_id = None;
+        self.passthrough = None;
+        // Current state does not show empty list
+        if self.permissions.as_ref().map(|p| p.is_empty()) == Some(true) {
+            self.permissions = None;
+        }
+        if self.secondaries.as_ref().map(|s| s.is_empty()) == Some(true) {
+            self.secondaries = None;
+        }
+
         // * If cannot have IP, set ip: none
         if !self.can_have_ip() {
             self.ipv4 = None;
@@ -136,6 +240,10 @@ impl BaseInterface {
             self.prop_list.retain(|p| p != &"ipv4" && p != &"ipv6");
         }
 
+        if let Some(ethtool) = self.ethtool.as_mut() {
+            ethtool.apply_feature_alias();
+        }
+
         if let Some(ref mut ipv4) = self.ipv4 {
             ipv4.pre_verify_cleanup();
         }
@@ -145,9 +253,47 @@ impl BaseInterface {
         }
     }
 
+    pub(crate) fn verify_carrier(
+        &self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        if self.requires_carrier()
+            && self.state == InterfaceState::Up
+            && current.carrier != Some(true)
+        {
+            let e = NmstateError::from_msg(
+                ErrorKind::VerificationError,
+                ErrorMsg::VERIFY_IFACE_NO_CARRIER,
+                &[("interface", self.name.clone())],
+            );
+            error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn requires_carrier(&self) -> bool {
+        self.require_carrier == Some(true)
+    }
+
+    pub(crate) fn is_passthrough(&self) -> bool {
+        self.passthrough == Some(true)
+    }
+
+    pub(crate) fn keep_dynamic_addresses(&self) -> bool {
+        self.keep_dynamic_addresses != Some(false)
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn is_memory_only(&self) -> bool {
+        self.persist == Some(InterfacePersist::Memory)
+    }
+
+    // VRF port is still routed, hence can have IP
     pub fn can_have_ip(&self) -> bool {
         self.controller == None
             || self.iface_type == InterfaceType::OvsInterface
+            || self.controller_type == Some(InterfaceType::Vrf)
     }
 
     pub(crate) fn is_up_priority_valid(&self) -> bool {
@@ -167,6 +313,32 @@ impl BaseInterface {
 
     // TODO: Validate IP, controller and etc
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        for user in self.permissions.as_deref().unwrap_or_default() {
+            if user.is_empty() || user.contains(':') || user.contains(';') {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Invalid user name '{}' in permissions of interface {}",
+                        user, self.name
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
+        for uuid in self.secondaries.as_deref().unwrap_or_default() {
+            if !is_uuid(uuid) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Secondary {} of interface {} is not a valid UUID",
+                        uuid, self.name
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
         Ok(())
     }
 
@@ -189,6 +361,15 @@ impl BaseInterface {
     }
 }
 
+// In the format of 8-4-4-4-12 hex digits
+fn is_uuid(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    parts.len() == 5
+        && parts.iter().zip([8, 4, 4, 4, 12]).all(|(p, len)| {
+            p.len() == len && p.chars().all(|c| c.is_ascii_hexdigit())
+        })
+}
+
 fn default_state() -> InterfaceState {
     InterfaceState::Up
 }
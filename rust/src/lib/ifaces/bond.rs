@@ -1,5 +1,9 @@
-use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+use crate::{
+    ifaces::inter_ifaces_controller::validate_ports_add_remove, BaseInterface,
+    ErrorKind, ErrorMsg, InterfaceType, NmstateError,
+};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use std::net::{Ipv4Addr, Ipv6Addr};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -30,12 +34,52 @@ impl BondInterface {
         }
     }
 
-    // Return None when desire state does not mention ports
+    // Return None when desire state does not mention ports. The ports listed
+    // in `ports-config` are used when `port` is not defined.
     pub(crate) fn ports(&self) -> Option<Vec<&str>> {
+        let bond_conf = self.bond.as_ref()?;
+        if let Some(ports) = bond_conf.port.as_ref() {
+            Some(ports.as_slice().iter().map(|p| p.as_str()).collect())
+        } else {
+            bond_conf.ports_config.as_ref().map(|port_confs| {
+                port_confs.iter().map(|p| p.name.as_str()).collect()
+            })
+        }
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn get_port_conf(
+        &self,
+        port_name: &str,
+    ) -> Option<&BondPortConfig> {
         self.bond
             .as_ref()
-            .and_then(|bond_conf| bond_conf.port.as_ref())
-            .map(|ports| ports.as_slice().iter().map(|p| p.as_str()).collect())
+            .and_then(|bond_conf| bond_conf.ports_config.as_ref())
+            .and_then(|port_confs| {
+                port_confs
+                    .iter()
+                    .find(|port_conf| port_conf.name == port_name)
+            })
+    }
+
+    // Current state contains the config of all ports, only keep the ports
+    // mentioned in `ports-config` of desire state for verification.
+    pub(crate) fn retain_desired_port_confs(&mut self, desired: &Self) {
+        let des_port_names: Option<Vec<&str>> = desired
+            .bond
+            .as_ref()
+            .and_then(|c| c.ports_config.as_ref())
+            .map(|confs| confs.iter().map(|c| c.name.as_str()).collect());
+        if let Some(bond_conf) = self.bond.as_mut() {
+            match des_port_names {
+                Some(names) => {
+                    if let Some(port_confs) = bond_conf.ports_config.as_mut() {
+                        port_confs.retain(|c| names.contains(&c.name.as_str()))
+                    }
+                }
+                None => bond_conf.ports_config = None,
+            }
+        }
     }
 
     pub(crate) fn pre_verify_cleanup(&mut self) {
@@ -52,29 +96,73 @@ impl BondInterface {
             if let Some(ref mut port_conf) = &mut bond_conf.port {
                 port_conf.sort_unstable_by_key(|p| p.clone())
             }
+            if let Some(ref mut port_confs) = &mut bond_conf.ports_config {
+                port_confs.sort_unstable_by(|a, b| a.name.cmp(&b.name))
+            }
         }
     }
 
     fn drop_empty_arp_ip_target(&mut self) {
         if let Some(ref mut bond_conf) = self.bond {
             if let Some(ref mut bond_opts) = &mut bond_conf.options {
-                if let Some(ref mut arp_ip_target) = bond_opts.arp_ip_target {
-                    if arp_ip_target.is_empty() {
-                        bond_opts.arp_ip_target = None;
-                    }
+                if bond_opts.arp_ip_target.as_ref().map(|t| t.is_empty())
+                    == Some(true)
+                {
+                    bond_opts.arp_ip_target = None;
+                }
+                if bond_opts.ns_ip6_target.as_ref().map(|t| t.is_empty())
+                    == Some(true)
+                {
+                    bond_opts.ns_ip6_target = None;
                 }
             }
         }
     }
 
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
-        self.base.validate()?;
         if let Some(bond_conf) = &self.bond {
             bond_conf.validate(&self.base)?;
         }
         Ok(())
     }
 
+    // Convert `ports-add` and `ports-remove` into full port list based on
+    // current ports.
+    pub(crate) fn resolve_ports_add_remove(
+        &mut self,
+        current: Option<&Self>,
+    ) -> Result<(), NmstateError> {
+        let bond_conf = match self.bond.as_mut() {
+            Some(c) if c.ports_add.is_some() || c.ports_remove.is_some() => c,
+            _ => return Ok(()),
+        };
+        let ports_add = bond_conf.ports_add.take().unwrap_or_default();
+        let ports_remove = bond_conf.ports_remove.take().unwrap_or_default();
+        validate_ports_add_remove(
+            &self.base.name,
+            bond_conf.port.is_some(),
+            &ports_add.iter().map(|p| p.as_str()).collect::<Vec<&str>>(),
+            &ports_remove,
+        )?;
+        let mut ports: Vec<String> = current
+            .and_then(|c| c.ports())
+            .unwrap_or_default()
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        for port in ports_add {
+            if !ports.contains(&port) {
+                ports.push(port);
+            }
+        }
+        ports.retain(|p| !ports_remove.contains(p));
+        bond_conf.port = Some(ports);
+        if let Some(port_confs) = bond_conf.ports_config.as_mut() {
+            port_confs.retain(|c| !ports_remove.contains(&c.name));
+        }
+        Ok(())
+    }
+
     pub(crate) fn remove_port(&mut self, port_to_remove: &str) {
         if let Some(index) = self.bond.as_ref().and_then(|bond_conf| {
             bond_conf.port.as_ref().and_then(|ports| {
@@ -144,6 +232,16 @@ pub struct BondConfig {
     pub options: Option<BondOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port: Option<Vec<String>>,
+    // Per port config, ports listed here are also attached to the bond when
+    // `port` is not defined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports_config: Option<Vec<BondPortConfig>>,
+    // Attach or detach ports without listing all ports of the bond,
+    // cannot be used along with `port`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports_add: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports_remove: Option<Vec<String>>,
 }
 
 impl BondConfig {
@@ -159,20 +257,76 @@ impl BondConfig {
             if let Some(mode) = &self.mode {
                 opts.validate(mode, base)?;
             } else {
-                return Err(NmstateError::new(
+                return Err(NmstateError::from_msg(
                     ErrorKind::InvalidArgument,
-                    "Bond mode is mandatory".to_string(),
+                    ErrorMsg::BOND_MODE_MANDATORY,
+                    &[],
                 ));
             }
         }
 
         if self.mode.is_none() {
-            return Err(NmstateError::new(
+            return Err(NmstateError::from_msg(
                 ErrorKind::InvalidArgument,
-                "Bond mode is mandatory".to_string(),
+                ErrorMsg::BOND_MODE_MANDATORY,
+                &[],
             ));
         }
 
+        if let Some(port_confs) = self.ports_config.as_ref() {
+            self.validate_ports_config(port_confs, base)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_ports_config(
+        &self,
+        port_confs: &[BondPortConfig],
+        base: &BaseInterface,
+    ) -> Result<(), NmstateError> {
+        for port_conf in port_confs {
+            if let Some(ports) = self.port.as_ref().or(self.ports_add.as_ref())
+            {
+                if !ports.contains(&port_conf.name) {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Port {} in ports-config of bond {} is not \
+                            listed in port",
+                            port_conf.name, base.name
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+            // Kernel only use port priority for choosing active port
+            if port_conf.priority.is_some()
+                && !matches!(
+                    self.mode,
+                    Some(BondMode::ActiveBackup)
+                        | Some(BondMode::TLB)
+                        | Some(BondMode::ALB)
+                )
+            {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The priority of port {} is only valid for bond \
+                        mode {}, {} or {}, but bond {} is in mode {}",
+                        port_conf.name,
+                        BondMode::ActiveBackup,
+                        BondMode::TLB,
+                        BondMode::ALB,
+                        base.name,
+                        self.mode.as_ref().unwrap_or(&BondMode::Unknown),
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
         Ok(())
     }
 
@@ -181,10 +335,31 @@ impl BondConfig {
             self.mode = other.mode.clone();
             self.options = other.options.clone();
             self.port = other.port.clone();
+            if other.ports_config.is_some() {
+                self.ports_config = other.ports_config.clone();
+            }
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct BondPortConfig {
+    pub name: String,
+    // Port with higher priority is preferred as active port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    // Used by the multiqueue classifier to steer traffic to this port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_id: Option<u16>,
+}
+
+impl BondPortConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub enum BondAdSelect {
@@ -377,6 +552,9 @@ impl BondXmitHashPolicy {
     }
 }
 
+// Kernel limit of both arp_ip_target and ns_ip6_target
+const BOND_MAX_ARP_TARGETS: usize = 16;
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct BondOptions {
     #[serde(
@@ -405,8 +583,14 @@ pub struct BondOptions {
         default
     )]
     pub arp_interval: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub arp_ip_target: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "json_to_ip_targets",
+        alias = "arp_ip_targets",
+        alias = "arp-ip-targets",
+        default
+    )]
+    pub arp_ip_target: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arp_validate: Option<BondArpValidate>,
     #[serde(
@@ -449,12 +633,26 @@ pub struct BondOptions {
         default
     )]
     pub num_unsol_na: Option<u8>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "json_to_ip_targets",
+        alias = "ns_ip6_targets",
+        alias = "ns-ip6-targets",
+        default
+    )]
+    pub ns_ip6_target: Option<Vec<String>>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         deserialize_with = "json_to_u32",
         default
     )]
     pub packets_per_slave: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "json_to_u32",
+        default
+    )]
+    pub peer_notif_delay: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub primary: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -530,6 +728,52 @@ where
     }
 }
 
+// Besides list, also support the comma separated string used by kernel
+fn json_to_ip_targets<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let json_value: serde_json::Value = Deserialize::deserialize(deserializer)?;
+    match json_value {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) => Ok(Some(
+            s.split(',')
+                .map(|t| t.trim())
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string())
+                .collect(),
+        )),
+        serde_json::Value::Array(targets) => {
+            let mut ret = Vec::new();
+            for target in targets {
+                if let Some(t) = target.as_str() {
+                    ret.push(t.to_string());
+                } else {
+                    return Err(D::Error::custom(NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Bond IP target {} is not valid, only string \
+                            is allowed",
+                            target
+                        ),
+                    )));
+                }
+            }
+            Ok(Some(ret))
+        }
+        _ => Err(D::Error::custom(NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Bond IP targets {} is not valid, only list or comma \
+                separated string is allowed",
+                json_value
+            ),
+        ))),
+    }
+}
+
 impl BondOptions {
     pub fn new() -> Self {
         Self::default()
@@ -543,6 +787,8 @@ impl BondOptions {
         self.fix_mac_restricted_mode(mode, base)?;
         self.validate_ad_actor_system_mac_address()?;
         self.validate_miimon_and_arp_interval()?;
+        self.validate_ip_targets(mode)?;
+        self.validate_mode_specific_options(mode)?;
         Ok(())
     }
 
@@ -590,4 +836,159 @@ impl BondOptions {
         }
         Ok(())
     }
+
+    fn validate_ip_targets(&self, mode: &BondMode) -> Result<(), NmstateError> {
+        let arp_ip_targets = self.arp_ip_target.as_deref().unwrap_or_default();
+        let ns_ip6_targets = self.ns_ip6_target.as_deref().unwrap_or_default();
+        for (opt_name, targets) in [
+            ("arp_ip_target", arp_ip_targets),
+            ("ns_ip6_target", ns_ip6_targets),
+        ] {
+            if targets.len() > BOND_MAX_ARP_TARGETS {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Bond option {} can only hold up to {} targets, \
+                        but got {}",
+                        opt_name,
+                        BOND_MAX_ARP_TARGETS,
+                        targets.len()
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        for target in arp_ip_targets {
+            if target.parse::<Ipv4Addr>().is_err() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Bond option arp_ip_target only support IPv4 \
+                        address, but got {}",
+                        target
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        for target in ns_ip6_targets {
+            if target.parse::<Ipv6Addr>().is_err() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Bond option ns_ip6_target only support IPv6 \
+                        address, but got {}",
+                        target
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        if arp_ip_targets.is_empty() && ns_ip6_targets.is_empty() {
+            return Ok(());
+        }
+        // Kernel only does ARP/NS monitoring in these modes
+        if [BondMode::LACP, BondMode::TLB, BondMode::ALB].contains(mode) {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Bond option arp_ip_target and ns_ip6_target are not \
+                    supported in {} mode",
+                    mode
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if self.miimon.unwrap_or_default() > 0
+            && self.arp_interval.unwrap_or_default() == 0
+        {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Bond option arp_ip_target and ns_ip6_target are only \
+                used by ARP monitoring, but bond is using MII monitoring \
+                as miimon is set"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    // Options only take effect in certain bond modes, kernel will not
+    // report them in other modes which fails verification.
+    fn validate_mode_specific_options(
+        &self,
+        mode: &BondMode,
+    ) -> Result<(), NmstateError> {
+        let lacp_only = [BondMode::LACP];
+        let tlb_alb_only = [BondMode::TLB, BondMode::ALB];
+        let igmp_modes = [
+            BondMode::RoundRobin,
+            BondMode::ActiveBackup,
+            BondMode::TLB,
+            BondMode::ALB,
+        ];
+        let opts: [(&str, bool, &[BondMode]); 10] = [
+            (
+                "ad_actor_sys_prio",
+                self.ad_actor_sys_prio.is_some(),
+                &lacp_only,
+            ),
+            (
+                "ad_actor_system",
+                self.ad_actor_system.is_some(),
+                &lacp_only,
+            ),
+            ("ad_select", self.ad_select.is_some(), &lacp_only),
+            (
+                "ad_user_port_key",
+                self.ad_user_port_key.is_some(),
+                &lacp_only,
+            ),
+            ("lacp_rate", self.lacp_rate.is_some(), &lacp_only),
+            ("lp_interval", self.lp_interval.is_some(), &tlb_alb_only),
+            (
+                "num_grat_arp",
+                self.num_grat_arp.is_some(),
+                &[BondMode::ActiveBackup],
+            ),
+            (
+                "packets_per_slave",
+                self.packets_per_slave.is_some(),
+                &[BondMode::RoundRobin],
+            ),
+            ("resend_igmp", self.resend_igmp.is_some(), &igmp_modes),
+            (
+                "tlb_dynamic_lb",
+                self.tlb_dynamic_lb.is_some(),
+                &[BondMode::TLB],
+            ),
+        ];
+        for (opt_name, is_set, modes) in opts {
+            if is_set && !modes.contains(mode) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Bond option {} is not supported in {} mode, \
+                        only supported in: {}",
+                        opt_name,
+                        mode,
+                        modes
+                            .iter()
+                            .map(|m| m.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use log::info;
+
 use crate::{
-    BaseInterface, InterfaceType, Interfaces, NmstateError, SrIovConfig,
+    BaseInterface, ErrorKind, Interface, InterfaceState, InterfaceType,
+    Interfaces, NmstateError, SrIovConfig,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -54,6 +57,10 @@ impl EthernetInterface {
         Self::default()
     }
 
+    pub(crate) fn veth_peer(&self) -> Option<&str> {
+        self.veth.as_ref().map(|v| v.peer.as_str())
+    }
+
     pub(crate) fn sriov_is_enabled(&self) -> bool {
         self.ethernet
             .as_ref()
@@ -150,3 +157,168 @@ impl VethConfig {
         }
     }
 }
+
+fn get_veth_peer<'a>(
+    ifaces: &'a Interfaces,
+    iface_name: &str,
+) -> Option<&'a str> {
+    if let Some(Interface::Ethernet(eth_iface)) =
+        ifaces.kernel_ifaces.get(iface_name)
+    {
+        eth_iface.veth_peer()
+    } else {
+        None
+    }
+}
+
+fn veth_conf_error(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
+
+// When both ends of new veth pair are defined in desire state, the peer is
+// created along with the interface holding the `veth` section, hence its
+// own config is applied as change afterwards.
+pub(crate) fn move_new_veth_peers_to_chg(
+    add_ifaces: &mut Interfaces,
+    chg_ifaces: &mut Interfaces,
+) -> Result<(), NmstateError> {
+    let mut iface_names: Vec<String> = add_ifaces
+        .kernel_ifaces
+        .keys()
+        .filter(|n| get_veth_peer(add_ifaces, n).is_some())
+        .cloned()
+        .collect();
+    iface_names.sort_unstable();
+    for iface_name in iface_names {
+        let peer = match get_veth_peer(add_ifaces, &iface_name) {
+            Some(p) => p.to_string(),
+            // Already moved as peer of other interface
+            None => continue,
+        };
+        match add_ifaces.kernel_ifaces.get(&peer) {
+            Some(Interface::Ethernet(peer_iface)) => {
+                if let Some(peer_of_peer) = peer_iface.veth_peer() {
+                    if peer_of_peer != iface_name {
+                        return Err(veth_conf_error(format!(
+                            "Veth interface {} is holding peer {}, but its \
+                            veth peer is defined as {}",
+                            iface_name, peer, peer_of_peer
+                        )));
+                    }
+                }
+            }
+            Some(_) => {
+                return Err(veth_conf_error(format!(
+                    "The veth peer {} of interface {} should be defined as \
+                    veth or ethernet type",
+                    peer, iface_name
+                )));
+            }
+            None => continue,
+        }
+        if let Some(Interface::Ethernet(mut peer_iface)) =
+            add_ifaces.kernel_ifaces.remove(&peer)
+        {
+            add_ifaces.insert_order.retain(|(n, _)| n != &peer);
+            peer_iface.veth = None;
+            peer_iface.base.iface_type = InterfaceType::Veth;
+            info!(
+                "Changing interface {} after created as veth peer of {}",
+                peer, iface_name
+            );
+            chg_ifaces.push(Interface::Ethernet(peer_iface));
+        }
+    }
+    Ok(())
+}
+
+// Kernel removes both ends of veth pair when deleting either of them, mark
+// the peer as absent also so that its config is removed.
+pub(crate) fn mark_veth_peers_as_absent(
+    del_ifaces: &mut Interfaces,
+    ifaces: &Interfaces,
+    current: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut peers = Vec::new();
+    for iface_name in del_ifaces.kernel_ifaces.keys() {
+        let peer = match get_veth_peer(current, iface_name) {
+            Some(p) => p,
+            None => continue,
+        };
+        if del_ifaces.kernel_ifaces.contains_key(peer) {
+            continue;
+        }
+        if let Some(peer_iface) = ifaces.kernel_ifaces.get(peer) {
+            if peer_iface.is_up() {
+                return Err(veth_conf_error(format!(
+                    "Veth interface {} cannot be up as its peer {} is \
+                    marked as absent",
+                    peer, iface_name
+                )));
+            }
+        }
+        if let Some(cur_peer_iface) = current.kernel_ifaces.get(peer) {
+            let mut new_iface = cur_peer_iface.clone_name_type_only();
+            new_iface.base_iface_mut().state = InterfaceState::Absent;
+            info!(
+                "Marking veth interface {} as absent as its peer {} is so",
+                peer, iface_name
+            );
+            peers.push(new_iface);
+        }
+    }
+    for peer in peers {
+        del_ifaces.push(peer);
+    }
+    Ok(())
+}
+
+fn is_veth(iface: &Interface) -> Option<bool> {
+    if let Interface::Ethernet(eth_iface) = iface {
+        Some(
+            eth_iface.base.iface_type == InterfaceType::Veth
+                || eth_iface.veth.is_some(),
+        )
+    } else {
+        None
+    }
+}
+
+fn veth_or_ethernet(is_veth: bool) -> &'static str {
+    if is_veth {
+        "veth"
+    } else {
+        "ethernet"
+    }
+}
+
+// In strict mode, the `veth` and `ethernet` types are not interchangeable
+// between desired and current interfaces. NetworkManager reports veth as
+// ethernet, hence the current veth is identified by its `veth` section.
+pub(crate) fn validate_veth_type_strict(
+    ifaces: &Interfaces,
+    cur_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in ifaces.kernel_ifaces.values() {
+        let cur_iface = match cur_ifaces.kernel_ifaces.get(iface.name()) {
+            Some(i) => i,
+            None => continue,
+        };
+        if let (Some(des_is_veth), Some(cur_is_veth)) =
+            (is_veth(iface), is_veth(cur_iface))
+        {
+            if des_is_veth != cur_is_veth {
+                return Err(veth_conf_error(format!(
+                    "Interface {} is {} but desired as {} in strict veth \
+                    type mode",
+                    iface.name(),
+                    veth_or_ethernet(cur_is_veth),
+                    veth_or_ethernet(des_is_veth)
+                )));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ErrorKind, NmstateError};
+
+// The ethtool CLI short names and long names mapped to kernel feature names
+const ETHTOOL_FEATURE_ALIASES: [(&str, &str); 20] = [
+    ("rx", "rx-checksum"),
+    ("rx-checksumming", "rx-checksum"),
+    ("sg", "tx-scatter-gather"),
+    ("scatter-gather", "tx-scatter-gather"),
+    ("tso", "tx-tcp-segmentation"),
+    ("tcp-segmentation-offload", "tx-tcp-segmentation"),
+    ("gso", "tx-generic-segmentation"),
+    ("generic-segmentation-offload", "tx-generic-segmentation"),
+    ("gro", "rx-gro"),
+    ("generic-receive-offload", "rx-gro"),
+    ("lro", "rx-lro"),
+    ("large-receive-offload", "rx-lro"),
+    ("rxvlan", "rx-vlan-hw-parse"),
+    ("rx-vlan-offload", "rx-vlan-hw-parse"),
+    ("txvlan", "tx-vlan-hw-insert"),
+    ("tx-vlan-offload", "tx-vlan-hw-insert"),
+    ("ntuple", "rx-ntuple-filter"),
+    ("ntuple-filters", "rx-ntuple-filter"),
+    ("rxhash", "rx-hashing"),
+    ("receive-hashing", "rx-hashing"),
+];
+
+// The largest hardware timer tick known to be used for interrupt coalescing
+const ETHTOOL_COALESCE_USECS_TICK_MAX: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolConfig {
+    // The kernel feature name, e.g. `rx-gro`, or ethtool alias, e.g. `gro`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature: Option<BTreeMap<String, bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause: Option<EthtoolPauseConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coalesce: Option<EthtoolCoalesceConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ring: Option<EthtoolRingConfig>,
+}
+
+impl EthtoolConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn update(&mut self, other: &Self) {
+        if let Some(other_features) = other.feature.as_ref() {
+            self.feature
+                .get_or_insert_with(BTreeMap::new)
+                .extend(other_features.clone());
+        }
+        if other.pause.is_some() {
+            self.pause = other.pause.clone();
+        }
+        if other.coalesce.is_some() {
+            self.coalesce = other.coalesce.clone();
+        }
+        if other.ring.is_some() {
+            self.ring = other.ring.clone();
+        }
+    }
+
+    // Driver might round the ring sizes and coalesce timers to what hardware
+    // supports, use current value if it is the rounded desired one.
+    pub(crate) fn accept_driver_rounding(&mut self, current: &Self) {
+        if let (Some(ring), Some(cur_ring)) =
+            (self.ring.as_mut(), current.ring.as_ref())
+        {
+            for (des, cur) in [
+                (&mut ring.rx, cur_ring.rx),
+                (&mut ring.rx_jumbo, cur_ring.rx_jumbo),
+                (&mut ring.rx_mini, cur_ring.rx_mini),
+                (&mut ring.tx, cur_ring.tx),
+            ] {
+                if let (Some(d), Some(c)) = (des.as_mut(), cur) {
+                    if is_ring_size_rounded(*d, c) {
+                        *d = c;
+                    }
+                }
+            }
+        }
+        if let (Some(coalesce), Some(cur_coalesce)) =
+            (self.coalesce.as_mut(), current.coalesce.as_ref())
+        {
+            for (des, cur) in [
+                (&mut coalesce.rx_usecs, cur_coalesce.rx_usecs),
+                (&mut coalesce.rx_usecs_high, cur_coalesce.rx_usecs_high),
+                (&mut coalesce.rx_usecs_irq, cur_coalesce.rx_usecs_irq),
+                (&mut coalesce.rx_usecs_low, cur_coalesce.rx_usecs_low),
+                (&mut coalesce.tx_usecs, cur_coalesce.tx_usecs),
+                (&mut coalesce.tx_usecs_high, cur_coalesce.tx_usecs_high),
+                (&mut coalesce.tx_usecs_irq, cur_coalesce.tx_usecs_irq),
+                (&mut coalesce.tx_usecs_low, cur_coalesce.tx_usecs_low),
+                (
+                    &mut coalesce.stats_block_usecs,
+                    cur_coalesce.stats_block_usecs,
+                ),
+            ] {
+                if let (Some(d), Some(c)) = (des.as_mut(), cur) {
+                    if is_coalesce_usecs_rounded(*d, c) {
+                        *d = c;
+                    }
+                }
+            }
+        }
+    }
+
+    // Kernel only reports features by their kernel names
+    pub(crate) fn apply_feature_alias(&mut self) {
+        if let Some(features) = self.feature.as_mut() {
+            for (alias, name) in ETHTOOL_FEATURE_ALIASES.iter() {
+                if let Some(value) = features.remove(*alias) {
+                    features.insert(name.to_string(), value);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolPauseConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autoneg: Option<bool>,
+}
+
+impl EthtoolPauseConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// The property names are identical to the `ethtool -C` parameters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolCoalesceConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_rx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_tx: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkt_rate_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkt_rate_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_frames_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rx_usecs_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_interval: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats_block_usecs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_frames_low: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs_high: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs_irq: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_usecs_low: Option<u32>,
+}
+
+impl EthtoolCoalesceConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Set ring size to `EthtoolRingConfig::RING_SIZE_MAX`(`max` in YAML) to use
+// the maximum size reported by device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct EthtoolRingConfig {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "ring_size_deserialize",
+        serialize_with = "ring_size_serialize"
+    )]
+    pub rx: Option<u32>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "ring_size_deserialize",
+        serialize_with = "ring_size_serialize"
+    )]
+    pub rx_jumbo: Option<u32>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "ring_size_deserialize",
+        serialize_with = "ring_size_serialize"
+    )]
+    pub rx_mini: Option<u32>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "ring_size_deserialize",
+        serialize_with = "ring_size_serialize"
+    )]
+    pub tx: Option<u32>,
+    // Maximum ring sizes reported by device, only for resolving `max`.
+    #[serde(skip)]
+    pub(crate) rx_max: Option<u32>,
+    #[serde(skip)]
+    pub(crate) rx_jumbo_max: Option<u32>,
+    #[serde(skip)]
+    pub(crate) rx_mini_max: Option<u32>,
+    #[serde(skip)]
+    pub(crate) tx_max: Option<u32>,
+}
+
+impl EthtoolRingConfig {
+    pub const RING_SIZE_MAX: u32 = u32::MAX;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn has_max(&self) -> bool {
+        [self.rx, self.rx_jumbo, self.rx_mini, self.tx]
+            .contains(&Some(Self::RING_SIZE_MAX))
+    }
+
+    pub(crate) fn resolve_max(
+        &mut self,
+        iface_name: &str,
+        current: Option<&Self>,
+    ) -> Result<(), NmstateError> {
+        let cur = current.cloned().unwrap_or_default();
+        for (name, des, cur_max) in [
+            ("rx", &mut self.rx, cur.rx_max),
+            ("rx-jumbo", &mut self.rx_jumbo, cur.rx_jumbo_max),
+            ("rx-mini", &mut self.rx_mini, cur.rx_mini_max),
+            ("tx", &mut self.tx, cur.tx_max),
+        ] {
+            if *des != Some(Self::RING_SIZE_MAX) {
+                continue;
+            }
+            match cur_max {
+                Some(m) => *des = Some(m),
+                None => {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Interface {} does not report maximum {} ring \
+                            size, cannot resolve `max`",
+                            iface_name, name
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn ring_size_deserialize<'de, D>(
+    deserializer: D,
+) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: serde_json::Value = Deserialize::deserialize(deserializer)?;
+    match (v.as_u64(), v.as_str()) {
+        (_, Some("max")) => Ok(Some(EthtoolRingConfig::RING_SIZE_MAX)),
+        (Some(s), _) if s < EthtoolRingConfig::RING_SIZE_MAX.into() => {
+            Ok(Some(s as u32))
+        }
+        (_, Some(s)) if s.parse::<u32>().is_ok() => {
+            s.parse::<u32>().map(Some).map_err(D::Error::custom)
+        }
+        _ => Err(D::Error::custom(format!(
+            "Invalid ring size {}, should be unsigned integer or max",
+            v
+        ))),
+    }
+}
+
+fn ring_size_serialize<S>(
+    size: &Option<u32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match size {
+        Some(EthtoolRingConfig::RING_SIZE_MAX) => {
+            serializer.serialize_str("max")
+        }
+        Some(s) => serializer.serialize_u32(*s),
+        None => serializer.serialize_none(),
+    }
+}
+
+// Driver rounds up the ring size, at most to the next power of two
+fn is_ring_size_rounded(desire: u32, current: u32) -> bool {
+    current >= desire && current / 2 < desire
+}
+
+// Driver rounds down the coalesce timer to the tick of hardware timer, zero
+// means disabled and never rounded.
+fn is_coalesce_usecs_rounded(desire: u32, current: u32) -> bool {
+    current != 0
+        && current <= desire
+        && desire - current < ETHTOOL_COALESCE_USECS_TICK_MAX
+}
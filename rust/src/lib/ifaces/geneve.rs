@@ -0,0 +1,95 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneveInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geneve: Option<GeneveConfig>,
+}
+
+impl Default for GeneveInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Geneve,
+                ..Default::default()
+            },
+            geneve: None,
+        }
+    }
+}
+
+impl GeneveInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.geneve.as_ref() {
+            if conf.id > GeneveConfig::MAX_ID {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Geneve ID {} of interface {} exceeds the maximum \
+                        value {}",
+                        conf.id,
+                        self.base.name,
+                        GeneveConfig::MAX_ID
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if IpAddr::from_str(&conf.remote).is_err() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Invalid remote IP address {} of Geneve interface {}",
+                        conf.remote, self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_geneve(&mut self, other: &GeneveInterface) {
+        if let Some(conf) = &mut self.geneve {
+            conf.update(other.geneve.as_ref());
+        } else {
+            self.geneve = other.geneve.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct GeneveConfig {
+    // Virtual Network Identifier(VNI)
+    pub id: u32,
+    pub remote: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_port: Option<u16>,
+}
+
+impl GeneveConfig {
+    pub const MAX_ID: u32 = 0xffffff;
+
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.id = other.id;
+            self.remote = other.remote.clone();
+            if other.destination_port.is_some() {
+                self.destination_port = other.destination_port;
+            }
+        }
+    }
+}
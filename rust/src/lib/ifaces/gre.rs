@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ip::is_ipv6_addr, BaseInterface, ErrorKind, InterfaceType, NmstateError,
+};
+
+// Both `gre` and `gretap` interface types are stored in this struct, the
+// `gretap` is layer 2 tunnel which could be attached to bridge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GreInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gre: Option<GreConfig>,
+}
+
+impl Default for GreInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Gre,
+                ..Default::default()
+            },
+            gre: None,
+        }
+    }
+}
+
+impl GreInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn is_tap(&self) -> bool {
+        self.base.iface_type == InterfaceType::GreTap
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.gre.as_ref() {
+            if is_ipv6_addr(&conf.remote)
+                || conf.local.as_deref().map(is_ipv6_addr) == Some(true)
+            {
+                let e = NmstateError::new(
+                    ErrorKind::NotImplementedError,
+                    format!(
+                        "IPv6 tunnel endpoint is not supported yet \
+                        for GRE interface {}",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_gre(&mut self, other: &GreInterface) {
+        if let Some(conf) = &mut self.gre {
+            conf.update(other.gre.as_ref());
+        } else {
+            self.gre = other.gre.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct GreConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<String>,
+    pub remote: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ikey: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub okey: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u8>,
+}
+
+impl GreConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if other.local.is_some() {
+                self.local = other.local.clone();
+            }
+            self.remote = other.remote.clone();
+            if other.ikey.is_some() {
+                self.ikey = other.ikey;
+            }
+            if other.okey.is_some() {
+                self.okey = other.okey;
+            }
+            if other.ttl.is_some() {
+                self.ttl = other.ttl;
+            }
+        }
+    }
+}
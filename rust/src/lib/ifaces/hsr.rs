@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HsrInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hsr: Option<HsrConfig>,
+}
+
+impl Default for HsrInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Hsr,
+                ..Default::default()
+            },
+            hsr: None,
+        }
+    }
+}
+
+impl HsrInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.hsr.as_ref() {
+            if conf.port1.is_empty() || conf.port2.is_empty() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Both port1 and port2 are required for HSR \
+                        interface {}",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if conf.port1 == conf.port2 {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The port1 and port2 of HSR interface {} should \
+                        not be the same interface {}",
+                        self.base.name, conf.port1
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if conf.port1 == self.base.name || conf.port2 == self.base.name {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The port of HSR interface {} should not be itself",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_hsr(&mut self, other: &HsrInterface) {
+        if let Some(conf) = &mut self.hsr {
+            conf.update(other.hsr.as_ref());
+        } else {
+            self.hsr = other.hsr.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct HsrConfig {
+    pub port1: String,
+    pub port2: String,
+    // The last byte of the supervision frame multicast address
+    // 01:15:4e:00:01:XX, kernel default to 0.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_spec: Option<u8>,
+    // Default to HSR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<HsrProtocol>,
+}
+
+impl HsrConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.port1 = other.port1.clone();
+            self.port2 = other.port2.clone();
+            if other.multicast_spec.is_some() {
+                self.multicast_spec = other.multicast_spec;
+            }
+            if other.protocol.is_some() {
+                self.protocol = other.protocol;
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HsrProtocol {
+    #[default]
+    Hsr,
+    Prp,
+}
+
+impl std::fmt::Display for HsrProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Hsr => "hsr",
+                Self::Prp => "prp",
+            }
+        )
+    }
+}
@@ -0,0 +1,196 @@
+use std::convert::TryFrom;
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfiniBandInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub infiniband: Option<InfiniBandConfig>,
+}
+
+impl Default for InfiniBandInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::InfiniBand,
+                ..Default::default()
+            },
+            infiniband: None,
+        }
+    }
+}
+
+impl InfiniBandInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = &self.infiniband {
+            conf.validate(&self.base.name)?;
+        }
+        Ok(())
+    }
+
+    // Only the PKEY child interface has parent
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.infiniband
+            .as_ref()
+            .and_then(|cfg| cfg.base_iface.as_deref())
+    }
+
+    // Kernel always set the full membership bit of PKEY
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(pkey) =
+            self.infiniband.as_mut().and_then(|c| c.pkey.as_mut())
+        {
+            *pkey |= InfiniBandConfig::PKEY_FULL_MEMBERSHIP;
+        }
+    }
+
+    pub(crate) fn update_infiniband(&mut self, other: &InfiniBandInterface) {
+        if let Some(conf) = &mut self.infiniband {
+            conf.update(other.infiniband.as_ref());
+        } else {
+            self.infiniband = other.infiniband.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct InfiniBandConfig {
+    // The parent interface of PKEY child interface, should be defined along
+    // with `pkey`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_iface: Option<String>,
+    // The partition key in hex string, e.g. `0x8001`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "pkey_deserialize",
+        serialize_with = "pkey_serialize"
+    )]
+    pub pkey: Option<u16>,
+    #[serde(default)]
+    pub mode: InfiniBandMode,
+}
+
+impl InfiniBandConfig {
+    // The default PKEY used by base interface
+    pub const PKEY_DEFAULT: u16 = 0xffff;
+    pub const PKEY_FULL_MEMBERSHIP: u16 = 0x8000;
+    // The PKEY with only the full membership bit is invalid
+    const PKEY_INVALID: [u16; 2] = [0, Self::PKEY_FULL_MEMBERSHIP];
+
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        if let Some(pkey) = self.pkey {
+            if Self::PKEY_INVALID.contains(&pkey) {
+                return Err(infiniband_conf_error(format!(
+                    "The pkey {:#06x} of InfiniBand interface {} is invalid",
+                    pkey, iface_name
+                )));
+            }
+        }
+        match (self.base_iface.as_deref(), self.pkey) {
+            (Some(base_iface), Some(_)) if base_iface == iface_name => {
+                Err(infiniband_conf_error(format!(
+                    "The base-iface of InfiniBand interface {} should not be \
+                    itself",
+                    iface_name
+                )))
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                Err(infiniband_conf_error(format!(
+                    "The base-iface and pkey of InfiniBand interface {} \
+                    should be defined together",
+                    iface_name
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if other.base_iface.is_some() {
+                self.base_iface = other.base_iface.clone();
+            }
+            if other.pkey.is_some() {
+                self.pkey = other.pkey;
+            }
+            self.mode = other.mode;
+        }
+    }
+}
+
+fn infiniband_conf_error(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
+
+// Accept integer, decimal string or hex string with `0x` prefix. The
+// default PKEY means no PKEY.
+fn pkey_deserialize<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: serde_json::Value = Deserialize::deserialize(deserializer)?;
+    let pkey = match (v.as_u64(), v.as_str()) {
+        (Some(p), _) => u16::try_from(p).ok(),
+        (_, Some(s)) => match s.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16).ok(),
+            None => s.parse::<u16>().ok(),
+        },
+        _ => None,
+    };
+    match pkey {
+        Some(InfiniBandConfig::PKEY_DEFAULT) => Ok(None),
+        Some(p) => Ok(Some(p)),
+        None => Err(D::Error::custom(format!(
+            "Invalid InfiniBand pkey {}, should be 16 bits unsigned integer \
+            or hex string like 0x8001",
+            v
+        ))),
+    }
+}
+
+fn pkey_serialize<S>(
+    pkey: &Option<u16>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match pkey {
+        Some(p) => serializer.serialize_str(&format!("{:#06x}", p)),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum InfiniBandMode {
+    // Kernel default
+    #[default]
+    Datagram,
+    Connected,
+}
+
+#[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+impl InfiniBandMode {
+    // The transport mode used by NetworkManager
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Datagram => "datagram",
+            Self::Connected => "connected",
+        }
+    }
+}
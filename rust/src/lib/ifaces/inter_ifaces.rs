@@ -7,11 +7,13 @@ use serde::{
 
 use crate::{
     ifaces::inter_ifaces_controller::{
-        check_overbook_ports, find_unknown_type_port, handle_changed_ports,
-        set_ifaces_up_priority,
+        check_ifaces_up_priority, check_overbook_ports, find_unknown_type_port,
+        handle_changed_ports, set_ifaces_up_priority,
     },
+    ifaces::{mark_veth_peers_as_absent, move_new_veth_peers_to_chg},
     ip::include_current_ip_address_if_dhcp_on_to_off,
-    ErrorKind, Interface, InterfaceState, InterfaceType, NmstateError,
+    ErrorKind, ErrorMsg, Interface, InterfaceState, InterfaceType,
+    NmstateError,
 };
 
 // The max loop count for Interfaces.set_up_priority()
@@ -70,22 +72,28 @@ impl Interfaces {
         Self::default()
     }
 
+    // Sorted by up priority, then alphabet activation order which is required
+    // to simulate the OS boot-up. For the same name, kernel interface is
+    // placed before userspace one.
+    // Please use `iter()` if the order is not required, the sort is
+    // noticeable on host with thousands of interfaces.
     pub fn to_vec(&self) -> Vec<&Interface> {
-        let mut ifaces = Vec::new();
-        for iface in self.kernel_ifaces.values() {
-            ifaces.push(iface);
-        }
-        for iface in self.user_ifaces.values() {
-            ifaces.push(iface);
-        }
-        ifaces.sort_unstable_by_key(|iface| iface.name());
-        // Use sort_by_key() instead of unstable one, do we can alphabet
-        // activation order which is required to simulate the OS boot-up.
-        ifaces.sort_by_key(|iface| iface.base_iface().up_priority);
-
+        let mut ifaces: Vec<&Interface> = self.iter().collect();
+        ifaces.sort_unstable_by(|a, b| {
+            (a.base_iface().up_priority, a.name(), a.is_userspace()).cmp(&(
+                b.base_iface().up_priority,
+                b.name(),
+                b.is_userspace(),
+            ))
+        });
         ifaces
     }
 
+    // Iterate all interfaces in arbitrary order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Interface> {
+        self.kernel_ifaces.values().chain(self.user_ifaces.values())
+    }
+
     pub(crate) fn get_iface<'a, 'b>(
         &'a self,
         iface_name: &'b str,
@@ -161,33 +169,9 @@ impl Interfaces {
         cur_clone.remove_unknown_type_port();
 
         for iface in self.to_vec() {
-            if iface.is_absent() || (iface.is_virtual() && iface.is_down()) {
-                if let Some(cur_iface) =
-                    cur_clone.get_iface(iface.name(), iface.iface_type())
-                {
-                    verify_desire_absent_but_found_in_current(
-                        iface, cur_iface,
-                    )?;
-                }
-            } else if let Some(cur_iface) =
-                cur_clone.get_iface(iface.name(), iface.iface_type())
-            {
-                iface.verify(cur_iface)?;
-                if let Interface::Ethernet(eth_iface) = iface {
-                    if eth_iface.sriov_is_enabled() {
-                        eth_iface.verify_sriov(cur_ifaces)?;
-                    }
-                }
-            } else {
-                return Err(NmstateError::new(
-                    ErrorKind::VerificationError,
-                    format!(
-                        "Failed to find desired interface {} {:?}",
-                        iface.name(),
-                        iface.iface_type()
-                    ),
-                ));
-            }
+            verify_iface(iface, &cur_clone, cur_ifaces).map_err(|e| {
+                e.with_context(NmstateError::CONTEXT_INTERFACE, iface.name())
+            })?;
         }
         Ok(())
     }
@@ -195,9 +179,7 @@ impl Interfaces {
     fn remove_unknown_type_port(&mut self) {
         let mut pending_actions: Vec<(String, InterfaceType, String)> =
             Vec::new();
-        for iface in
-            self.kernel_ifaces.values().chain(self.user_ifaces.values())
-        {
+        for iface in self.iter() {
             if !iface.is_controller() {
                 continue;
             }
@@ -245,6 +227,11 @@ impl Interfaces {
                 if iface.is_up() {
                     iface.validate()?;
                 }
+                validate_ovsdb_iface(iface, current)?;
+                if iface.base_iface().is_passthrough() {
+                    chg_ifaces.push(gen_passthrough_iface(iface, current)?);
+                    continue;
+                }
                 match current.kernel_ifaces.get(iface.name()) {
                     Some(cur_iface) => {
                         let mut chg_iface = iface.clone();
@@ -276,15 +263,27 @@ impl Interfaces {
         // of nmstate is expecting dynamic IP address goes static. This should
         // be done by top level code.
         include_current_ip_address_if_dhcp_on_to_off(&mut chg_ifaces, current);
+        // Veth peer is created or removed along with the other end
+        move_new_veth_peers_to_chg(&mut add_ifaces, &mut chg_ifaces)?;
+        mark_veth_peers_as_absent(&mut del_ifaces, self, current)?;
         mark_orphan_interface_as_absent(&mut del_ifaces, &chg_ifaces, current);
 
         Ok((add_ifaces, chg_ifaces, del_ifaces))
     }
 
     pub fn set_up_priority(&mut self) -> Result<(), NmstateError> {
+        for iface in self
+            .kernel_ifaces
+            .values_mut()
+            .chain(self.user_ifaces.values_mut())
+        {
+            if let Some(priority) = iface.base_iface().priority {
+                iface.base_iface_mut().up_priority = priority;
+            }
+        }
         for _ in 0..INTERFACES_SET_PRIORITY_MAX_RETRY {
             if set_ifaces_up_priority(self) {
-                return Ok(());
+                return check_ifaces_up_priority(self);
             }
         }
         error!(
@@ -311,10 +310,251 @@ impl Interfaces {
         })
     }
 
-    pub(crate) fn resolve_unknown_ifaces(
+    pub(crate) fn has_carrier_required(&self) -> bool {
+        self.iter()
+            .any(|i| i.is_up() && i.base_iface().requires_carrier())
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn has_ovs(&self) -> bool {
+        self.iter().any(|i| {
+            !i.is_absent()
+                && (i.iface_type() == InterfaceType::OvsBridge
+                    || i.iface_type() == InterfaceType::OvsInterface)
+        })
+    }
+
+    pub(crate) fn get_vrf_table_id(&self, vrf_name: &str) -> Option<u32> {
+        match self.kernel_ifaces.get(vrf_name) {
+            Some(iface) if iface.is_absent() => None,
+            Some(Interface::Vrf(vrf_iface)) => vrf_iface.table_id(),
+            _ => None,
+        }
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn has_ipv4_shared(&self) -> bool {
+        self.iter().any(|i| {
+            i.is_up()
+                && i.base_iface()
+                    .ipv4
+                    .as_ref()
+                    .map(|ip| ip.is_shared())
+                    .unwrap_or_default()
+        })
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn has_macsec_offload(&self) -> bool {
+        self.kernel_ifaces.values().any(|i| {
+            i.is_up()
+                && matches!(i, Interface::MacSec(m) if m
+                    .macsec
+                    .as_ref()
+                    .and_then(|c| c.offload)
+                    .is_some())
+        })
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn has_ipv4_clat(&self) -> bool {
+        self.iter().any(|i| {
+            i.is_up()
+                && i.base_iface()
+                    .ipv4
+                    .as_ref()
+                    .map(|ip| ip.is_clat_enabled())
+                    .unwrap_or_default()
+        })
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn has_up_iface_type(&self, iface_type: &InterfaceType) -> bool {
+        self.kernel_ifaces
+            .values()
+            .any(|i| i.is_up() && &i.iface_type() == iface_type)
+    }
+
+    // Without current state, the interface type, the removal and the
+    // interfaces referred by controller or port cannot be resolved.
+    pub(crate) fn validate_offline(&self) -> Result<(), NmstateError> {
+        for iface in self.iter() {
+            let reason = if iface.iface_type() == InterfaceType::Unknown {
+                Some("has no interface type defined".to_string())
+            } else if iface.is_absent() {
+                Some("is marked as absent".to_string())
+            } else if let Some(port_name) = iface
+                .ports()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|p| !self.kernel_ifaces.contains_key(*p))
+            {
+                Some(format!("holds undefined port {}", port_name))
+            } else {
+                iface
+                    .base_iface()
+                    .controller
+                    .as_deref()
+                    .filter(|c| {
+                        !c.is_empty()
+                            && !self.kernel_ifaces.contains_key(*c)
+                            && !self.user_ifaces.keys().any(|(n, _)| n == c)
+                    })
+                    .map(|c| {
+                        format!("is attached to undefined controller {}", c)
+                    })
+            };
+            if let Some(reason) = reason {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Interface {} {}, which cannot be resolved without \
+                        current state",
+                        iface.name(),
+                        reason
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    // Reject the features not supported by the backend before applying
+    // anything.
+    pub(crate) fn validate_backend_support(
+        &self,
+        current: &Self,
+        kernel_only: bool,
+    ) -> Result<(), NmstateError> {
+        for iface in self.iter().filter(|i| !i.is_absent()) {
+            let unsupported = if kernel_only {
+                get_kernel_unsupported_feature(
+                    iface,
+                    current.kernel_ifaces.get(iface.name()),
+                )
+            } else {
+                get_nm_unsupported_feature(
+                    iface,
+                    current.kernel_ifaces.get(iface.name()),
+                )
+            };
+            if let Some(feature) = unsupported {
+                let e =
+                    NmstateError::new(
+                        ErrorKind::NotImplementedError,
+                        format!(
+                        "{} does not support {} of interface {}, please use \
+                        {} mode instead",
+                        if kernel_only {
+                            "Kernel mode"
+                        } else {
+                            "NetworkManager"
+                        },
+                        feature,
+                        iface.name(),
+                        if kernel_only { "NetworkManager" } else { "kernel" },
+                    ),
+                    );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        for iface in self
+            .kernel_ifaces
+            .values_mut()
+            .chain(self.user_ifaces.values_mut())
+        {
+            match iface {
+                Interface::Ipsec(iface) => iface.hide_secrets(),
+                Interface::Wifi(iface) => iface.hide_secrets(),
+                Interface::Pppoe(iface) => iface.hide_secrets(),
+                Interface::MacSec(iface) => iface.hide_secrets(),
+                _ => (),
+            }
+        }
+    }
+
+    // Resolve the `${iface.<property>}` templates in OVS external_ids using
+    // desired interface properties, then current ones.
+    pub(crate) fn resolve_ovsdb_templates(
+        &mut self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        for iface in self
+            .kernel_ifaces
+            .values_mut()
+            .chain(self.user_ifaces.values_mut())
+            .filter(|i| !i.is_absent())
+        {
+            if iface.base_iface().ovsdb.is_none() {
+                continue;
+            }
+            let mut iface_values = vec![serde_json::to_value(&*iface)?];
+            if let Some(cur_iface) =
+                current.get_iface(iface.name(), iface.iface_type())
+            {
+                iface_values.push(serde_json::to_value(cur_iface)?);
+            }
+            let iface_name = iface.name().to_string();
+            if let Some(ovsdb_conf) = iface.base_iface_mut().ovsdb.as_mut() {
+                ovsdb_conf.resolve_templates(&iface_name, &iface_values)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Resolve the `max` ethtool ring sizes using the maximums reported by
+    // current interface.
+    pub(crate) fn resolve_ethtool_ring_max(
+        &mut self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        for (iface_name, iface) in self
+            .kernel_ifaces
+            .iter_mut()
+            .filter(|(_, i)| !i.is_absent())
+        {
+            if let Some(ring) = iface
+                .base_iface_mut()
+                .ethtool
+                .as_mut()
+                .and_then(|e| e.ring.as_mut())
+                .filter(|r| r.has_max())
+            {
+                let cur_ring = current
+                    .kernel_ifaces
+                    .get(iface_name)
+                    .and_then(|i| i.base_iface().ethtool.as_ref())
+                    .and_then(|e| e.ring.as_ref());
+                ring.resolve_max(iface_name, cur_ring)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn resolve_ports_add_remove(
         &mut self,
         cur_ifaces: &Self,
     ) -> Result<(), NmstateError> {
+        for (iface_name, iface) in self.kernel_ifaces.iter_mut() {
+            iface.resolve_ports_add_remove(
+                cur_ifaces.kernel_ifaces.get(iface_name),
+            )?;
+        }
+        Ok(())
+    }
+
+    // Return the interface name and type resolved from current state.
+    pub(crate) fn resolve_unknown_ifaces(
+        &mut self,
+        cur_ifaces: &Self,
+    ) -> Result<Vec<(String, InterfaceType)>, NmstateError> {
         let mut resolved_ifaces: Vec<Interface> = Vec::new();
         for ((iface_name, iface_type), iface) in self.user_ifaces.iter() {
             if iface_type != &InterfaceType::Unknown {
@@ -322,7 +562,7 @@ impl Interfaces {
             }
 
             if iface.is_absent() {
-                for cur_iface in cur_ifaces.to_vec() {
+                for cur_iface in cur_ifaces.iter() {
                     if cur_iface.name() == iface_name {
                         let mut new_iface = cur_iface.clone();
                         new_iface.base_iface_mut().state =
@@ -332,14 +572,29 @@ impl Interfaces {
                 }
             } else {
                 let mut found_iface = Vec::new();
-                for cur_iface in cur_ifaces.to_vec() {
-                    if cur_iface.name() == iface_name {
+                for cur_iface in cur_ifaces.iter() {
+                    if cur_iface.name() == iface_name
+                        && !self.has_typed_iface(
+                            iface_name,
+                            &cur_iface.iface_type(),
+                        )
+                    {
                         let mut new_iface = iface.clone();
                         new_iface.base_iface_mut().iface_type =
                             cur_iface.iface_type().clone();
                         found_iface.push(new_iface);
                     }
                 }
+                if found_iface.len() > 1
+                    && is_same_name_ovs_br_and_iface(iface_name, cur_ifaces)
+                {
+                    // The OVS bridge cannot hold kernel properties, hence
+                    // the interface without type is referring to the OVS
+                    // internal interface.
+                    found_iface.retain(|i| {
+                        i.iface_type() == InterfaceType::OvsInterface
+                    });
+                }
                 match found_iface.len() {
                     0 => {
                         let e = NmstateError::new(
@@ -354,7 +609,7 @@ impl Interfaces {
                         return Err(e);
                     }
                     1 => {
-                        let new_iface = Interface::deserialize(
+                        let new_iface = Interface::from_value(
                             serde_json::to_value(&found_iface[0])?,
                         )?;
 
@@ -376,14 +631,35 @@ impl Interfaces {
             }
         }
 
+        let mut resolutions = Vec::new();
         for new_iface in resolved_ifaces {
             self.user_ifaces.remove(&(
                 new_iface.name().to_string(),
                 InterfaceType::Unknown,
             ));
+            resolutions
+                .push((new_iface.name().to_string(), new_iface.iface_type()));
             self.push(new_iface);
         }
-        Ok(())
+        resolutions.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Ok(resolutions)
+    }
+
+    // Whether desired state contains specified interface with known type
+    fn has_typed_iface(
+        &self,
+        iface_name: &str,
+        iface_type: &InterfaceType,
+    ) -> bool {
+        if iface_type.is_userspace() {
+            self.user_ifaces
+                .contains_key(&(iface_name.to_string(), iface_type.clone()))
+        } else {
+            self.kernel_ifaces
+                .get(iface_name)
+                .map(|i| &i.iface_type() == iface_type)
+                .unwrap_or_default()
+        }
     }
 
     fn apply_copy_mac_from(
@@ -445,34 +721,71 @@ impl Interfaces {
     }
 }
 
+fn verify_iface(
+    iface: &Interface,
+    cur_clone: &Interfaces,
+    cur_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    if iface.is_absent() || (iface.is_virtual() && iface.is_down()) {
+        if let Some(cur_iface) =
+            cur_clone.get_iface(iface.name(), iface.iface_type())
+        {
+            verify_desire_absent_but_found_in_current(iface, cur_iface)?;
+        }
+    } else if let Some(cur_iface) =
+        cur_clone.get_iface(iface.name(), iface.iface_type())
+    {
+        iface.base_iface().verify_carrier(cur_iface.base_iface())?;
+        iface.verify(cur_iface)?;
+        if let Interface::Ethernet(eth_iface) = iface {
+            if eth_iface.sriov_is_enabled() {
+                eth_iface.verify_sriov(cur_ifaces)?;
+            }
+        }
+    } else {
+        return Err(NmstateError::from_msg(
+            ErrorKind::VerificationError,
+            ErrorMsg::VERIFY_IFACE_NOT_FOUND,
+            &[
+                ("interface", iface.name().to_string()),
+                ("type", format!("{:?}", iface.iface_type())),
+            ],
+        ));
+    }
+    Ok(())
+}
+
 fn verify_desire_absent_but_found_in_current(
     des_iface: &Interface,
     cur_iface: &Interface,
 ) -> Result<(), NmstateError> {
-    if cur_iface.is_virtual() {
+    if cur_iface.iface_type() == InterfaceType::Loopback {
+        // Loopback interface cannot be deleted, absent only removes its
+        // config
+        Ok(())
+    } else if cur_iface.is_virtual() {
         // Virtual interface should be deleted by absent action
-        let e = NmstateError::new(
+        let e = NmstateError::from_msg(
             ErrorKind::VerificationError,
-            format!(
-                "Absent interface {}/{} still found as {:?}",
-                des_iface.name(),
-                des_iface.iface_type(),
-                cur_iface
-            ),
+            ErrorMsg::VERIFY_ABSENT_IFACE_FOUND,
+            &[
+                ("interface", des_iface.name().to_string()),
+                ("type", des_iface.iface_type().to_string()),
+                ("current", format!("{:?}", cur_iface)),
+            ],
         );
         error!("{}", e);
         Err(e)
     } else if cur_iface.is_up() {
         // Real hardware should be marked as down by absent action
-        let e = NmstateError::new(
+        let e = NmstateError::from_msg(
             ErrorKind::VerificationError,
-            format!(
-                "Absent interface {}/{} still found as \
-                state up: {:?}",
-                des_iface.name(),
-                des_iface.iface_type(),
-                cur_iface
-            ),
+            ErrorMsg::VERIFY_ABSENT_IFACE_UP,
+            &[
+                ("interface", des_iface.name().to_string()),
+                ("type", des_iface.iface_type().to_string()),
+                ("current", format!("{:?}", cur_iface)),
+            ],
         );
         error!("{}", e);
         Err(e)
@@ -486,11 +799,12 @@ fn gen_ifaces_to_del(
     cur_ifaces: &Interfaces,
 ) -> Vec<Interface> {
     let mut del_ifaces = Vec::new();
-    let cur_ifaces = cur_ifaces.to_vec();
-    for cur_iface in cur_ifaces {
+    for cur_iface in cur_ifaces.iter() {
         if cur_iface.name() == del_iface.name()
             && (del_iface.iface_type() == InterfaceType::Unknown
-                || del_iface.iface_type() == cur_iface.iface_type())
+                || del_iface
+                    .iface_type()
+                    .is_same_type(&cur_iface.iface_type(), false))
         {
             let mut tmp_iface = del_iface.clone();
             tmp_iface.base_iface_mut().iface_type = cur_iface.iface_type();
@@ -505,6 +819,72 @@ fn gen_ifaces_to_del(
     del_ifaces
 }
 
+// The passthrough interface is not created or removed by nmstate, only the
+// MTU and state are changed. Only kernel mode supports it as
+// NetworkManager cannot hold MTU in generic profile, and ethtool is not
+// supported as kernel mode cannot change ethtool settings.
+fn gen_passthrough_iface(
+    iface: &Interface,
+    cur_ifaces: &Interfaces,
+) -> Result<Interface, NmstateError> {
+    let base_iface = iface.base_iface();
+    if !base_iface.iface_type.is_other() {
+        return Err(passthrough_error(format!(
+            "Passthrough is only for interface of type unsupported by \
+            nmstate, but interface {} is {}",
+            base_iface.name, base_iface.iface_type
+        )));
+    }
+    if cur_ifaces
+        .get_iface(&base_iface.name, base_iface.iface_type.clone())
+        .is_none()
+    {
+        return Err(passthrough_error(format!(
+            "Passthrough interface {} with type {} not found in current \
+            state",
+            base_iface.name, base_iface.iface_type
+        )));
+    }
+    if base_iface.ipv4.is_some()
+        || base_iface.ipv6.is_some()
+        || base_iface.controller.is_some()
+        || base_iface.mac_address.is_some()
+    {
+        return Err(passthrough_error(format!(
+            "Passthrough interface {} only supports MTU and state",
+            base_iface.name
+        )));
+    }
+    if base_iface.ethtool.is_some() {
+        let e = NmstateError::new(
+            ErrorKind::NotImplementedError,
+            format!(
+                "Ethtool settings of passthrough interface {} is not \
+                supported",
+                base_iface.name
+            ),
+        );
+        error!("{}", e);
+        return Err(e);
+    }
+    let mut new_iface = iface.clone_name_type_only();
+    let new_base = new_iface.base_iface_mut();
+    new_base.state = base_iface.state.clone();
+    new_base.mtu = base_iface.mtu;
+    new_base.passthrough = Some(true);
+    info!(
+        "Changing passthrough interface {} with type {}",
+        new_base.name, new_base.iface_type
+    );
+    Ok(new_iface)
+}
+
+fn passthrough_error(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    error!("{}", e);
+    e
+}
+
 fn is_opt_str_empty(opt_string: &Option<String>) -> bool {
     if let Some(s) = opt_string {
         s.is_empty()
@@ -542,3 +922,125 @@ fn mark_orphan_interface_as_absent(
         }
     }
 }
+
+// OVS bridge could have an OVS internal interface using the same name as its
+// port.
+fn is_same_name_ovs_br_and_iface(
+    iface_name: &str,
+    cur_ifaces: &Interfaces,
+) -> bool {
+    match (
+        cur_ifaces.get_iface(iface_name, InterfaceType::OvsBridge),
+        cur_ifaces.get_iface(iface_name, InterfaceType::OvsInterface),
+    ) {
+        (Some(br_iface), Some(ovs_iface)) => {
+            ovs_iface.iface_type() == InterfaceType::OvsInterface
+                && ovs_iface.base_iface().controller.as_deref()
+                    == Some(iface_name)
+                && br_iface
+                    .ports()
+                    .map(|ports| ports.contains(&iface_name))
+                    .unwrap_or_default()
+        }
+        _ => false,
+    }
+}
+
+fn get_nm_unsupported_feature(
+    iface: &Interface,
+    current: Option<&Interface>,
+) -> Option<&'static str> {
+    let base_iface = iface.base_iface();
+    if base_iface.is_passthrough() {
+        Some("passthrough")
+    } else if base_iface.address_announce.is_some() {
+        Some("address announcement")
+    } else if base_iface
+        .ipv6
+        .as_ref()
+        .map(|i| i.has_addr_flags())
+        .unwrap_or_default()
+    {
+        Some("IPv6 address flags")
+    } else if let Interface::LinuxBridge(br_iface) = iface {
+        let cur_br_iface = match current {
+            Some(Interface::LinuxBridge(i)) => Some(i),
+            _ => None,
+        };
+        br_iface
+            .is_multicast_versions_changed(cur_br_iface)
+            .then_some("changing multicast IGMP or MLD version")
+    } else {
+        None
+    }
+}
+
+fn get_kernel_unsupported_feature(
+    iface: &Interface,
+    current: Option<&Interface>,
+) -> Option<&'static str> {
+    match iface {
+        Interface::Vlan(vlan_iface) => {
+            let cur_vlan_iface = match current {
+                Some(Interface::Vlan(i)) => Some(i),
+                _ => None,
+            };
+            vlan_iface.get_kernel_unsupported_prop(cur_vlan_iface)
+        }
+        // Nispor can neither create GRE interface nor report its settings,
+        // hence verification would always fail.
+        Interface::Gre(gre_iface)
+            if gre_iface.gre.is_some() || current.is_none() =>
+        {
+            Some("creating or changing GRE tunnel")
+        }
+        // Nispor can neither create WireGuard interface nor report its keys
+        // and peers.
+        Interface::WireGuard(wg_iface)
+            if wg_iface.wireguard.is_some() || current.is_none() =>
+        {
+            Some("creating or changing WireGuard")
+        }
+        _ => None,
+    }
+}
+
+// The OVS external_ids are stored in the OVSDB Bridge or Interface table,
+// hence only OVS bridge, OVS internal interface or system interface attached
+// to OVS bridge could have them.
+fn validate_ovsdb_iface(
+    iface: &Interface,
+    current: &Interfaces,
+) -> Result<(), NmstateError> {
+    let base_iface = iface.base_iface();
+    if base_iface.ovsdb.is_none()
+        || matches!(
+            iface.iface_type(),
+            InterfaceType::OvsBridge | InterfaceType::OvsInterface
+        )
+    {
+        return Ok(());
+    }
+    let ctrl_type = if base_iface.controller.is_some() {
+        base_iface.controller_type.as_ref()
+    } else {
+        current
+            .kernel_ifaces
+            .get(iface.name())
+            .and_then(|i| i.base_iface().controller_type.as_ref())
+    };
+    if ctrl_type != Some(&InterfaceType::OvsBridge) {
+        let e = NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "The ovs-db setting of interface {} is only supported by OVS \
+                bridge, OVS interface or system interface attached to OVS \
+                bridge",
+                iface.name()
+            ),
+        );
+        error!("{}", e);
+        return Err(e);
+    }
+    Ok(())
+}
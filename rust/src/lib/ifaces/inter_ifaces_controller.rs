@@ -16,6 +16,8 @@ pub(crate) fn handle_changed_ports(
         String,
         (Option<String>, Option<InterfaceType>),
     > = HashMap::new();
+    handle_port_side_controller(ifaces, cur_ifaces, &mut pending_changes)?;
+
     for iface in ifaces.kernel_ifaces.values() {
         if !iface.is_controller() {
             continue;
@@ -66,6 +68,8 @@ pub(crate) fn handle_changed_ports(
         }
     }
 
+    include_undeclared_controllers(ifaces, cur_ifaces, &pending_changes);
+
     for (iface_name, (ctrl_name, ctrl_type)) in pending_changes.drain() {
         match ifaces.kernel_ifaces.get_mut(&iface_name) {
             Some(iface) => {
@@ -140,6 +144,48 @@ pub(crate) fn handle_changed_ports(
     Ok(())
 }
 
+// Port could be attached to controller which only exists in current state,
+// include the controller to edit so that the attachment is done in the same
+// transaction.
+fn include_undeclared_controllers(
+    ifaces: &mut Interfaces,
+    cur_ifaces: &Interfaces,
+    pending_changes: &HashMap<String, (Option<String>, Option<InterfaceType>)>,
+) {
+    let mut new_ctrl_ifaces: Vec<Interface> = Vec::new();
+    for (port_name, (ctrl_name, ctrl_type)) in pending_changes.iter() {
+        let (ctrl_name, ctrl_type) = match (ctrl_name, ctrl_type) {
+            (Some(n), Some(t)) => (n, t),
+            _ => continue,
+        };
+        if cur_ifaces
+            .kernel_ifaces
+            .get(port_name)
+            .and_then(|i| i.base_iface().controller.as_ref())
+            == Some(ctrl_name)
+            || find_controller(ifaces, ctrl_name, ctrl_type).is_some()
+            || new_ctrl_ifaces.iter().any(|i| {
+                i.name() == ctrl_name.as_str() && &i.iface_type() == ctrl_type
+            })
+        {
+            continue;
+        }
+        if let Some(cur_ctrl_iface) =
+            find_controller(cur_ifaces, ctrl_name, ctrl_type)
+        {
+            info!(
+                "Include controller {} to edit as its port {} is attaching \
+                to it",
+                ctrl_name, port_name
+            );
+            new_ctrl_ifaces.push(cur_ctrl_iface.clone_name_type_only());
+        }
+    }
+    for ctrl_iface in new_ctrl_ifaces {
+        ifaces.push(ctrl_iface);
+    }
+}
+
 fn gen_ovs_interface(iface_name: &str, ctrl_name: &str) -> Interface {
     let mut base_iface = BaseInterface::new();
     base_iface.name = iface_name.to_string();
@@ -153,6 +199,121 @@ fn gen_ovs_interface(iface_name: &str, ctrl_name: &str) -> Interface {
     })
 }
 
+// Port could define `controller` property directly instead of been listed
+// in the port list of controller. Empty controller name means detaching from
+// current controller. Error will be raised if port list of any desired
+// controller conflicts with it.
+fn handle_port_side_controller(
+    ifaces: &Interfaces,
+    cur_ifaces: &Interfaces,
+    pending_changes: &mut HashMap<
+        String,
+        (Option<String>, Option<InterfaceType>),
+    >,
+) -> Result<(), NmstateError> {
+    for iface in ifaces.kernel_ifaces.values().filter(|i| i.is_up()) {
+        let ctrl_name = match iface.base_iface().controller.as_deref() {
+            Some(c) => c,
+            None => continue,
+        };
+        let port_name = iface.name();
+        for ctrl_iface in ifaces.iter().filter(|i| i.name() != ctrl_name) {
+            if let Some(ports) = ctrl_iface.ports() {
+                if ports.contains(&port_name) {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Interface {} desires controller '{}', but is \
+                            listed as port of {}",
+                            port_name,
+                            ctrl_name,
+                            ctrl_iface.name()
+                        ),
+                    );
+                    error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        if ctrl_name.is_empty() {
+            if cur_ifaces
+                .kernel_ifaces
+                .get(port_name)
+                .and_then(|i| i.base_iface().controller.as_ref())
+                .is_some()
+            {
+                pending_changes.insert(port_name.to_string(), (None, None));
+            }
+            continue;
+        }
+        let ctrl_type = iface
+            .base_iface()
+            .controller_type
+            .clone()
+            .unwrap_or_default();
+        let desired_ctrl = find_controller(ifaces, ctrl_name, &ctrl_type);
+        let ctrl_iface = match desired_ctrl
+            .or_else(|| find_controller(cur_ifaces, ctrl_name, &ctrl_type))
+        {
+            Some(c) if c.is_controller() && !c.is_absent() => c,
+            _ => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Controller {} of interface {} not found or is not \
+                        a valid controller",
+                        ctrl_name, port_name
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        };
+        match desired_ctrl.and_then(|i| i.ports()) {
+            Some(ports) => {
+                if !ports.contains(&port_name) {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Interface {} desires controller {}, but it is \
+                            not listed in the port list of {}",
+                            port_name, ctrl_name, ctrl_name
+                        ),
+                    );
+                    error!("{}", e);
+                    return Err(e);
+                }
+            }
+            None => {
+                pending_changes.insert(
+                    port_name.to_string(),
+                    (
+                        Some(ctrl_name.to_string()),
+                        Some(ctrl_iface.iface_type()),
+                    ),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// OVS internal interface might share the same name with its OVS bridge, so
+// only controller is searched when type is unknown.
+fn find_controller<'a>(
+    ifaces: &'a Interfaces,
+    ctrl_name: &str,
+    ctrl_type: &InterfaceType,
+) -> Option<&'a Interface> {
+    if ctrl_type == &InterfaceType::Unknown {
+        ifaces
+            .iter()
+            .find(|i| i.name() == ctrl_name && i.is_controller())
+    } else {
+        ifaces.get_iface(ctrl_name, ctrl_type.clone())
+    }
+}
+
 fn handle_changed_ports_of_iface(
     iface: &Interface,
     ifaces: &Interfaces,
@@ -222,6 +383,10 @@ pub(crate) fn set_ifaces_up_priority(ifaces: &mut Interfaces) -> bool {
         if !iface.is_up() {
             continue;
         }
+        // Priority defined by user is set by Interfaces::set_up_priority()
+        if iface.base_iface().priority.is_some() {
+            continue;
+        }
         if iface.base_iface().is_up_priority_valid() {
             continue;
         }
@@ -271,6 +436,80 @@ pub(crate) fn set_ifaces_up_priority(ifaces: &mut Interfaces) -> bool {
     ret
 }
 
+pub(crate) fn validate_ports_add_remove(
+    iface_name: &str,
+    has_port_list: bool,
+    ports_add: &[&str],
+    ports_remove: &[String],
+) -> Result<(), NmstateError> {
+    let e = if has_port_list {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Interface {} cannot have `port` along with `ports-add` or \
+                `ports-remove`",
+                iface_name
+            ),
+        )
+    } else if let Some(port) = ports_remove
+        .iter()
+        .find(|p| ports_add.contains(&p.as_str()))
+    {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Port {} is listed in both `ports-add` and `ports-remove` \
+                of interface {}",
+                port, iface_name
+            ),
+        )
+    } else {
+        return Ok(());
+    };
+    error!("{}", e);
+    Err(e)
+}
+
+// Port should be activated after its controller.
+pub(crate) fn check_ifaces_up_priority(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in ifaces.iter().filter(|i| i.is_up()) {
+        let ctrl_name = match iface.base_iface().controller.as_ref() {
+            Some(c) if !c.is_empty() => c,
+            _ => continue,
+        };
+        let ctrl_iface = match ifaces.get_iface(
+            ctrl_name,
+            iface
+                .base_iface()
+                .controller_type
+                .clone()
+                .unwrap_or_default(),
+        ) {
+            Some(c) => c,
+            None => continue,
+        };
+        if iface.base_iface().up_priority <= ctrl_iface.base_iface().up_priority
+        {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Interface {} has priority {} which should be larger \
+                    than the priority {} of its controller {}",
+                    iface.name(),
+                    iface.base_iface().up_priority,
+                    ctrl_iface.base_iface().up_priority,
+                    ctrl_name
+                ),
+            );
+            error!("{}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn find_unknown_type_port<'a>(
     iface: &'a Interface,
     cur_ifaces: &Interfaces,
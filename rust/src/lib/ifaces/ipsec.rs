@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+// The IPsec connection is userspace only, the interface name is used as the
+// name of NetworkManager VPN connection backed by libreswan plugin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpsecInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub libreswan: Option<LibreswanConfig>,
+}
+
+impl Default for IpsecInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Ipsec,
+                ..Default::default()
+            },
+            libreswan: None,
+        }
+    }
+}
+
+impl IpsecInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.libreswan.as_ref() {
+            if conf.right.is_empty() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The right(remote peer) is required for IPsec \
+                        connection {}",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if conf.psk.is_some()
+                && (conf.leftcert.is_some() || conf.leftrsasigkey.is_some())
+            {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The psk cannot be used along with leftcert or \
+                        leftrsasigkey for IPsec connection {}",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_ipsec(&mut self, other: &IpsecInterface) {
+        if let Some(conf) = &mut self.libreswan {
+            conf.update(other.libreswan.as_ref());
+        } else {
+            self.libreswan = other.libreswan.clone();
+        }
+    }
+
+    // The PSK might not be exposed by current state
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(conf) = self.libreswan.as_mut() {
+            conf.psk = None;
+        }
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if let Some(conf) = self.libreswan.as_mut() {
+            if conf.psk.is_some() {
+                conf.psk =
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+            }
+        }
+    }
+}
+
+// The property names are following libreswan `ipsec.conf` options.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct LibreswanConfig {
+    pub right: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rightid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rightrsasigkey: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leftid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leftrsasigkey: Option<String>,
+    // Nickname of certificate stored in libreswan NSS database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leftcert: Option<String>,
+    // Could be `no`, `never`, `yes`, `insist`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ikev2: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub psk: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ike: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub esp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ikelifetime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salifetime: Option<String>,
+}
+
+impl LibreswanConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.right = other.right.clone();
+            for (value, other_value) in [
+                (&mut self.rightid, &other.rightid),
+                (&mut self.rightrsasigkey, &other.rightrsasigkey),
+                (&mut self.left, &other.left),
+                (&mut self.leftid, &other.leftid),
+                (&mut self.leftrsasigkey, &other.leftrsasigkey),
+                (&mut self.leftcert, &other.leftcert),
+                (&mut self.ikev2, &other.ikev2),
+                (&mut self.psk, &other.psk),
+                (&mut self.ike, &other.ike),
+                (&mut self.esp, &other.esp),
+                (&mut self.ikelifetime, &other.ikelifetime),
+                (&mut self.salifetime, &other.salifetime),
+            ] {
+                if other_value.is_some() {
+                    *value = other_value.clone();
+                }
+            }
+        }
+    }
+}
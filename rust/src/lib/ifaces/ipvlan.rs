@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpVlanInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipvlan: Option<IpVlanConfig>,
+}
+
+impl Default for IpVlanInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::IpVlan,
+                ..Default::default()
+            },
+            ipvlan: None,
+        }
+    }
+}
+
+impl IpVlanInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = &self.ipvlan {
+            if conf.base_iface.is_empty() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The base-iface is required for IPVLAN interface {}",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if conf.private == Some(true) && conf.vepa == Some(true) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The private and vepa flags of IPVLAN interface {} \
+                        are mutually exclusive",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.ipvlan.as_ref().map(|cfg| cfg.base_iface.as_str())
+    }
+
+    pub(crate) fn update_ipvlan(&mut self, other: &IpVlanInterface) {
+        if let Some(conf) = &mut self.ipvlan {
+            conf.update(other.ipvlan.as_ref());
+        } else {
+            self.ipvlan = other.ipvlan.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct IpVlanConfig {
+    pub base_iface: String,
+    #[serde(default)]
+    pub mode: IpVlanMode,
+    // Only allow traffic to and from the parent, not between ports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
+    // Send traffic between ports to the parent's external switch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vepa: Option<bool>,
+}
+
+impl IpVlanConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.base_iface = other.base_iface.clone();
+            self.mode = other.mode;
+            if other.private.is_some() {
+                self.private = other.private;
+            }
+            if other.vepa.is_some() {
+                self.vepa = other.vepa;
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum IpVlanMode {
+    L2,
+    // Kernel default
+    #[default]
+    L3,
+    L3s,
+}
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use log::{error, warn};
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+use crate::{
+    ifaces::inter_ifaces_controller::validate_ports_add_remove, BaseInterface,
+    ErrorKind, InterfaceType, NmstateError,
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LinuxBridgeInterface {
@@ -22,10 +25,11 @@ impl Default for LinuxBridgeInterface {
 }
 
 impl LinuxBridgeInterface {
-    pub(crate) const INTEGER_ROUNDED_OPTIONS: [&'static str; 5] = [
+    pub(crate) const INTEGER_ROUNDED_OPTIONS: [&'static str; 6] = [
         "interface.bridge.options.multicast-last-member-interval",
         "interface.bridge.options.multicast-membership-interval",
         "interface.bridge.options.multicast-querier-interval",
+        "interface.bridge.options.multicast-query-interval",
         "interface.bridge.options.multicast-query-response-interval",
         "interface.bridge.options.multicast-startup-query-interval",
     ];
@@ -38,6 +42,26 @@ impl LinuxBridgeInterface {
         }
     }
 
+    fn multicast_versions(&self) -> (Option<u8>, Option<u8>) {
+        self.bridge
+            .as_ref()
+            .and_then(|br_conf| br_conf.options.as_ref())
+            .map(|o| (o.multicast_igmp_version, o.multicast_mld_version))
+            .unwrap_or_default()
+    }
+
+    // NetworkManager has no setting for multicast IGMP and MLD versions
+    pub(crate) fn is_multicast_versions_changed(
+        &self,
+        current: Option<&Self>,
+    ) -> bool {
+        let (des_igmp, des_mld) = self.multicast_versions();
+        let (cur_igmp, cur_mld) =
+            current.map(|c| c.multicast_versions()).unwrap_or_default();
+        (des_igmp.is_some() && des_igmp != cur_igmp)
+            || (des_mld.is_some() && des_mld != cur_mld)
+    }
+
     // Return None when desire state does not mentioned ports.
     pub(crate) fn ports(&self) -> Option<Vec<&str>> {
         self.bridge
@@ -123,6 +147,42 @@ impl LinuxBridgeInterface {
         }
     }
 
+    // Convert `ports-add` and `ports-remove` into full port list based on
+    // current port configures.
+    pub(crate) fn resolve_ports_add_remove(
+        &mut self,
+        current: Option<&Self>,
+    ) -> Result<(), NmstateError> {
+        let br_conf = match self.bridge.as_mut() {
+            Some(c) if c.ports_add.is_some() || c.ports_remove.is_some() => c,
+            _ => return Ok(()),
+        };
+        let ports_add = br_conf.ports_add.take().unwrap_or_default();
+        let ports_remove = br_conf.ports_remove.take().unwrap_or_default();
+        validate_ports_add_remove(
+            &self.base.name,
+            br_conf.port.is_some(),
+            &ports_add
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<&str>>(),
+            &ports_remove,
+        )?;
+        let mut port_confs: Vec<LinuxBridgePortConfig> = current
+            .and_then(|c| c.bridge.as_ref())
+            .and_then(|c| c.port.clone())
+            .unwrap_or_default();
+        for port_add in ports_add {
+            match port_confs.iter_mut().find(|p| p.name == port_add.name) {
+                Some(port_conf) => port_conf.merge(port_add),
+                None => port_confs.push(port_add),
+            }
+        }
+        port_confs.retain(|p| !ports_remove.contains(&p.name));
+        br_conf.port = Some(port_confs);
+        Ok(())
+    }
+
     pub(crate) fn remove_port(&mut self, port_name: &str) {
         if let Some(index) = self.bridge.as_ref().and_then(|br_conf| {
             br_conf.port.as_ref().and_then(|port_confs| {
@@ -139,7 +199,6 @@ impl LinuxBridgeInterface {
     }
 
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
-        self.base.validate()?;
         self.bridge
             .as_ref()
             .map(LinuxBridgeConfig::validate)
@@ -147,6 +206,7 @@ impl LinuxBridgeInterface {
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
     pub(crate) fn get_port_conf(
         &self,
         port_name: &str,
@@ -161,12 +221,19 @@ impl LinuxBridgeInterface {
             })
     }
 
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
     pub(crate) fn vlan_filtering_is_enabled(&self) -> bool {
         self.bridge
             .as_ref()
             .and_then(|br_conf| br_conf.port.as_ref())
             .map(|port_confs| {
-                port_confs.iter().any(|port_conf| port_conf.vlan.is_some())
+                port_confs.iter().any(|port_conf| {
+                    port_conf
+                        .vlan
+                        .as_ref()
+                        .map(|v| !v.is_empty())
+                        .unwrap_or_default()
+                })
             })
             .unwrap_or(false)
     }
@@ -231,6 +298,13 @@ pub struct LinuxBridgeConfig {
     pub options: Option<LinuxBridgeOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port: Option<Vec<LinuxBridgePortConfig>>,
+    // Attach or detach ports without listing all ports of the bridge,
+    // cannot be used along with `port`. For existing port, the defined
+    // port configure will be merged into current one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports_add: Option<Vec<LinuxBridgePortConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports_remove: Option<Vec<String>>,
 }
 
 impl LinuxBridgeConfig {
@@ -243,6 +317,17 @@ impl LinuxBridgeConfig {
             .as_ref()
             .map(LinuxBridgeOptions::validate)
             .transpose()?;
+        for port_conf in self
+            .port
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .chain(self.ports_add.as_deref().unwrap_or_default())
+        {
+            if let Some(vlan_conf) = port_conf.vlan.as_ref() {
+                vlan_conf.validate(&port_conf.name)?;
+            }
+        }
         Ok(())
     }
 }
@@ -265,6 +350,21 @@ impl LinuxBridgePortConfig {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn merge(&mut self, other: Self) {
+        if other.stp_hairpin_mode.is_some() {
+            self.stp_hairpin_mode = other.stp_hairpin_mode;
+        }
+        if other.stp_path_cost.is_some() {
+            self.stp_path_cost = other.stp_path_cost;
+        }
+        if other.stp_priority.is_some() {
+            self.stp_priority = other.stp_priority;
+        }
+        if other.vlan.is_some() {
+            self.vlan = other.vlan;
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -290,12 +390,16 @@ pub struct LinuxBridgeOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mac_ageing_time: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_igmp_version: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub multicast_last_member_count: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub multicast_last_member_interval: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub multicast_membership_interval: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub multicast_mld_version: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub multicast_querier: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub multicast_querier_interval: Option<u64>,
@@ -315,14 +419,90 @@ pub struct LinuxBridgeOptions {
     pub multicast_startup_query_interval: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stp: Option<LinuxBridgeStpOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan_protocol: Option<LinuxBridgeVlanProtocol>,
 }
 
 impl LinuxBridgeOptions {
+    pub const MULTICAST_IGMP_VERSIONS: [u8; 2] = [2, 3];
+    pub const MULTICAST_MLD_VERSIONS: [u8; 2] = [1, 2];
+    // Kernel does not allow forwarding STP, MAC pause and slow protocol(LACP)
+    // frames.
+    pub const GROUP_FORWARD_MASK_RESTRICTED: u16 = 0x7;
+
     pub fn new() -> Self {
         Self::default()
     }
 
+    // Return the group forward mask defined by either `group-forward-mask`
+    // or `group-fwd-mask`.
+    pub(crate) fn get_group_forward_mask(&self) -> Option<u16> {
+        self.group_forward_mask.or(self.group_fwd_mask)
+    }
+
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let (Some(mask), Some(fwd_mask)) =
+            (self.group_forward_mask, self.group_fwd_mask)
+        {
+            if mask != fwd_mask {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The group-forward-mask {} and group-fwd-mask {} \
+                        should be identical when both defined",
+                        mask, fwd_mask
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
+        if let Some(mask) = self.get_group_forward_mask() {
+            if mask & Self::GROUP_FORWARD_MASK_RESTRICTED > 0 {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Desired group forward mask {:#06x} is invalid, \
+                        kernel does not allow forwarding STP, MAC pause and \
+                        LACP frames (mask {:#06x})",
+                        mask,
+                        Self::GROUP_FORWARD_MASK_RESTRICTED
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
+        if let Some(v) = self.multicast_igmp_version {
+            if !Self::MULTICAST_IGMP_VERSIONS.contains(&v) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Desired multicast IGMP version {} is not \
+                        supported, only {:?} are allowed",
+                        v,
+                        Self::MULTICAST_IGMP_VERSIONS
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
+        if let Some(v) = self.multicast_mld_version {
+            if !Self::MULTICAST_MLD_VERSIONS.contains(&v) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Desired multicast MLD version {} is not \
+                        supported, only {:?} are allowed",
+                        v,
+                        Self::MULTICAST_MLD_VERSIONS
+                    ),
+                );
+                error!("{}", e);
+                return Err(e);
+            }
+        }
         self.stp
             .as_ref()
             .map(LinuxBridgeStpOptions::validate)
@@ -433,6 +613,18 @@ pub enum LinuxBridgeMulticastRouterType {
     Enabled,
 }
 
+impl LinuxBridgeMulticastRouterType {
+    // The value used by kernel sysfs `multicast_router`
+    #[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+    pub(crate) fn to_kernel_value(&self) -> u8 {
+        match self {
+            Self::Disabled => 0,
+            Self::Auto => 1,
+            Self::Enabled => 2,
+        }
+    }
+}
+
 impl Default for LinuxBridgeMulticastRouterType {
     fn default() -> Self {
         Self::Auto
@@ -453,6 +645,37 @@ impl std::fmt::Display for LinuxBridgeMulticastRouterType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinuxBridgeVlanProtocol {
+    #[serde(rename = "802.1q")]
+    Ieee8021Q,
+    #[serde(rename = "802.1ad")]
+    Ieee8021Ad,
+}
+
+impl LinuxBridgeVlanProtocol {
+    #[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+    pub(crate) fn ether_type(&self) -> u16 {
+        match self {
+            Self::Ieee8021Q => 0x8100,
+            Self::Ieee8021Ad => 0x88a8,
+        }
+    }
+}
+
+impl std::fmt::Display for LinuxBridgeVlanProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ieee8021Q => "802.1q",
+                Self::Ieee8021Ad => "802.1ad",
+            }
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct LinuxBridgePortVlanConfig {
@@ -467,10 +690,96 @@ pub struct LinuxBridgePortVlanConfig {
 }
 
 impl LinuxBridgePortVlanConfig {
+    const VLAN_ID_MIN: u16 = 1;
+    const VLAN_ID_MAX: u16 = 4094;
+
     pub fn new() -> Self {
         Self::default()
     }
 
+    // Empty VLAN section means VLAN filtering disabled on this port
+    pub(crate) fn is_empty(&self) -> bool {
+        self.mode.is_none()
+            && self.tag.is_none()
+            && self.enable_native.is_none()
+            && self.trunk_tags.as_ref().map(Vec::is_empty) != Some(false)
+    }
+
+    pub(crate) fn validate(&self, port_name: &str) -> Result<(), NmstateError> {
+        let trunk_tags = self.trunk_tags.as_deref().unwrap_or_default();
+        let mut vlan_ids: Vec<u16> = self.tag.iter().copied().collect();
+        for trunk_tag in trunk_tags {
+            let (min, max) = trunk_tag.get_vlan_tag_range();
+            if min > max {
+                return Err(vlan_conf_error(
+                    port_name,
+                    format!(
+                        "VLAN range min {} is bigger than max {}",
+                        min, max
+                    ),
+                ));
+            }
+            vlan_ids.push(min);
+            vlan_ids.push(max);
+        }
+        if let Some(vlan_id) = vlan_ids
+            .iter()
+            .find(|i| **i < Self::VLAN_ID_MIN || **i > Self::VLAN_ID_MAX)
+        {
+            return Err(vlan_conf_error(
+                port_name,
+                format!(
+                    "VLAN ID {} is not in the range of {} to {}",
+                    vlan_id,
+                    Self::VLAN_ID_MIN,
+                    Self::VLAN_ID_MAX
+                ),
+            ));
+        }
+
+        match self.mode {
+            Some(LinuxBridgePortVlanMode::Access) | None => {
+                if !trunk_tags.is_empty() {
+                    return Err(vlan_conf_error(
+                        port_name,
+                        "Access port cannot have trunk-tags".to_string(),
+                    ));
+                }
+                if self.enable_native == Some(true) {
+                    return Err(vlan_conf_error(
+                        port_name,
+                        "Access port cannot have enable-native".to_string(),
+                    ));
+                }
+            }
+            Some(LinuxBridgePortVlanMode::Trunk) => {
+                if trunk_tags.is_empty() {
+                    return Err(vlan_conf_error(
+                        port_name,
+                        "Trunk port requires trunk-tags".to_string(),
+                    ));
+                }
+                if self.enable_native == Some(true) && self.tag.is_none() {
+                    return Err(vlan_conf_error(
+                        port_name,
+                        "Trunk port with enable-native requires tag \
+                        for the native VLAN"
+                            .to_string(),
+                    ));
+                }
+                if self.enable_native != Some(true) && self.tag.is_some() {
+                    return Err(vlan_conf_error(
+                        port_name,
+                        "Trunk port requires enable-native: true to use \
+                        tag for the native VLAN"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn flatten_vlan_ranges(&mut self) {
         if let Some(trunk_tags) = &self.trunk_tags {
             let mut new_trunk_tags = Vec::new();
@@ -509,6 +818,15 @@ impl LinuxBridgePortVlanConfig {
     }
 }
 
+fn vlan_conf_error(port_name: &str, msg: String) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::InvalidArgument,
+        format!("Invalid VLAN config of bridge port {}: {}", port_name, msg),
+    );
+    error!("{}", e);
+    e
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum LinuxBridgePortVlanMode {
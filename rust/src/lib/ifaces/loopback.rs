@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceIpAddr, InterfaceType, NmstateError,
+};
+
+// The loopback interface is always exist in kernel, this is only used for
+// holding MTU, IP addresses and routes attached to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoopbackInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+}
+
+impl Default for LoopbackInterface {
+    fn default() -> Self {
+        let mut base = BaseInterface::new();
+        base.iface_type = InterfaceType::Loopback;
+        Self { base }
+    }
+}
+
+impl LoopbackInterface {
+    // The addresses always assigned by kernel
+    pub(crate) const IPV4_ADDR: &'static str = "127.0.0.1";
+    pub(crate) const IPV4_PREFIX_LEN: u8 = 8;
+    pub(crate) const IPV6_ADDR: &'static str = "::1";
+    pub(crate) const IPV6_PREFIX_LEN: u8 = 128;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(ipv4) = self.base.ipv4.as_ref() {
+            if !ipv4.enabled || ipv4.dhcp {
+                return Err(loopback_conf_error(
+                    "Loopback interface cannot disable IPv4 or enable DHCP",
+                ));
+            }
+        }
+        if let Some(ipv6) = self.base.ipv6.as_ref() {
+            if !ipv6.enabled || ipv6.dhcp || ipv6.autoconf {
+                return Err(loopback_conf_error(
+                    "Loopback interface cannot disable IPv6 or enable \
+                    DHCPv6 or autoconf",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // The implicit addresses are ignored in verification as they are
+    // always kept.
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(ipv4) = self.base.ipv4.as_mut() {
+            ipv4.addresses.retain(|a| {
+                !is_implicit_addr(a, Self::IPV4_ADDR, Self::IPV4_PREFIX_LEN)
+            });
+        }
+        if let Some(ipv6) = self.base.ipv6.as_mut() {
+            ipv6.addresses.retain(|a| {
+                !is_implicit_addr(a, Self::IPV6_ADDR, Self::IPV6_PREFIX_LEN)
+            });
+        }
+    }
+}
+
+fn is_implicit_addr(addr: &InterfaceIpAddr, ip: &str, prefix_len: u8) -> bool {
+    addr.ip == ip && addr.prefix_length == prefix_len
+}
+
+fn loopback_conf_error(msg: &str) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg.to_string());
+    log::error!("{}", e);
+    e
+}
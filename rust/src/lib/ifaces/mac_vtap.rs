@@ -66,6 +66,12 @@ pub struct MacVtapConfig {
     pub mode: MacVtapMode,
     #[serde(skip_serializing_if = "Option::is_none", rename = "promiscuous")]
     pub accept_all_mac: Option<bool>,
+    // The user ID owning the `/dev/tapN` device node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_owner: Option<u32>,
+    // The group ID owning the `/dev/tapN` device node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap_group: Option<u32>,
 }
 
 impl MacVtapConfig {
@@ -74,6 +80,12 @@ impl MacVtapConfig {
             self.base_iface = other.base_iface.clone();
             self.mode = other.mode;
             self.accept_all_mac = other.accept_all_mac;
+            if other.tap_owner.is_some() {
+                self.tap_owner = other.tap_owner;
+            }
+            if other.tap_group.is_some() {
+                self.tap_group = other.tap_group;
+            }
         }
     }
 }
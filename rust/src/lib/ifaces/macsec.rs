@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacSecInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macsec: Option<MacSecConfig>,
+}
+
+impl Default for MacSecInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::MacSec,
+                ..Default::default()
+            },
+            macsec: None,
+        }
+    }
+}
+
+impl MacSecInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = &self.macsec {
+            conf.validate(&self.base.name)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.macsec.as_ref().map(|cfg| cfg.base_iface.as_str())
+    }
+
+    pub(crate) fn update_macsec(&mut self, other: &MacSecInterface) {
+        if let Some(conf) = &mut self.macsec {
+            conf.update(other.macsec.as_ref());
+        } else {
+            self.macsec = other.macsec.clone();
+        }
+    }
+
+    // The MKA CAK might not be exposed by current state
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(conf) = self.macsec.as_mut() {
+            conf.mka_cak = None;
+        }
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if let Some(conf) = self.macsec.as_mut() {
+            if conf.mka_cak.is_some() {
+                conf.mka_cak =
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct MacSecConfig {
+    pub base_iface: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encrypt: Option<bool>,
+    // The MKA pre-shared Connectivity Association Key and its name. Changing
+    // them rotates the key without recreating the interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mka_cak: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mka_ckn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<MacSecValidate>,
+    // Include the SCI in every packet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_sci: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offload: Option<MacSecOffload>,
+}
+
+impl MacSecConfig {
+    pub const MKA_CAK_LENS: [usize; 2] = [32, 64];
+    pub const MKA_CKN_LEN_MAX: usize = 64;
+    pub const PORT_MAX: u32 = 65534;
+
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        if self.base_iface.is_empty() || self.base_iface == iface_name {
+            return Err(macsec_conf_error(format!(
+                "The base-iface of MACsec interface {} should be defined \
+                and not be itself",
+                iface_name
+            )));
+        }
+        if let Some(port) = self.port {
+            if !(1..=Self::PORT_MAX).contains(&port) {
+                return Err(macsec_conf_error(format!(
+                    "The port {} of MACsec interface {} is not in the range \
+                    of [1,{}]",
+                    port,
+                    iface_name,
+                    Self::PORT_MAX
+                )));
+            }
+        }
+        let cak = self
+            .mka_cak
+            .as_deref()
+            .filter(|c| *c != NetworkState::PASSWORD_HID_BY_NMSTATE);
+        if let Some(cak) = cak {
+            if !Self::MKA_CAK_LENS.contains(&cak.len()) || !is_hex(cak) {
+                return Err(macsec_conf_error(format!(
+                    "The mka-cak of MACsec interface {} should be {:?} \
+                    hexadecimal characters",
+                    iface_name,
+                    Self::MKA_CAK_LENS
+                )));
+            }
+        }
+        if let Some(ckn) = self.mka_ckn.as_deref() {
+            if ckn.is_empty()
+                || ckn.len() > Self::MKA_CKN_LEN_MAX
+                || ckn.len() % 2 != 0
+                || !is_hex(ckn)
+            {
+                return Err(macsec_conf_error(format!(
+                    "The mka-ckn of MACsec interface {} should be even \
+                    number of hexadecimal characters up to {}",
+                    iface_name,
+                    Self::MKA_CKN_LEN_MAX
+                )));
+            }
+        }
+        if self.mka_cak.is_some() != self.mka_ckn.is_some() {
+            return Err(macsec_conf_error(format!(
+                "The mka-cak and mka-ckn of MACsec interface {} should be \
+                defined together",
+                iface_name
+            )));
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.base_iface = other.base_iface.clone();
+            if other.encrypt.is_some() {
+                self.encrypt = other.encrypt;
+            }
+            if other.mka_cak.is_some() {
+                self.mka_cak = other.mka_cak.clone();
+            }
+            if other.mka_ckn.is_some() {
+                self.mka_ckn = other.mka_ckn.clone();
+            }
+            if other.port.is_some() {
+                self.port = other.port;
+            }
+            if other.validation.is_some() {
+                self.validation = other.validation;
+            }
+            if other.send_sci.is_some() {
+                self.send_sci = other.send_sci;
+            }
+            if other.offload.is_some() {
+                self.offload = other.offload;
+            }
+        }
+    }
+}
+
+fn is_hex(value: &str) -> bool {
+    value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn macsec_conf_error(msg: String) -> NmstateError {
+    let e = NmstateError::new(ErrorKind::InvalidArgument, msg);
+    log::error!("{}", e);
+    e
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MacSecValidate {
+    Disabled,
+    Check,
+    Strict,
+}
+
+#[cfg_attr(
+    not(any(feature = "nm-backend", feature = "kernel-backend")),
+    allow(dead_code)
+)]
+impl MacSecValidate {
+    // The value used by both kernel and NetworkManager
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            Self::Disabled => 0,
+            Self::Check => 1,
+            Self::Strict => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Disabled),
+            1 => Some(Self::Check),
+            2 => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+// Offload the MACsec operations to PHY or MAC of the base interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MacSecOffload {
+    Off,
+    Phy,
+    Mac,
+}
+
+#[cfg_attr(
+    not(any(feature = "nm-backend", feature = "kernel-backend")),
+    allow(dead_code)
+)]
+impl MacSecOffload {
+    // The value used by both kernel and NetworkManager
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            Self::Off => 0,
+            Self::Phy => 1,
+            Self::Mac => 2,
+        }
+    }
+
+    pub(crate) fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Off),
+            1 => Some(Self::Phy),
+            2 => Some(Self::Mac),
+            _ => None,
+        }
+    }
+}
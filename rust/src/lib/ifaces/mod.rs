@@ -2,39 +2,85 @@ mod base;
 mod bond;
 mod dummy;
 mod ethernet;
+mod ethtool;
+mod geneve;
+mod gre;
+mod hsr;
+mod infiniband;
 mod inter_ifaces;
+mod ipsec;
+mod ipvlan;
 // The pub(crate) is only for unit test
 pub(crate) mod inter_ifaces_controller;
 mod linux_bridge;
+mod loopback;
 mod mac_vlan;
 mod mac_vtap;
+mod macsec;
 mod ovs;
+mod pppoe;
 mod sriov;
+mod team;
 mod vlan;
+mod vrf;
+mod wifi;
+mod wireguard;
+mod xfrm;
 
 pub use base::*;
 pub use bond::{
     BondAdSelect, BondAllPortsActive, BondArpAllTargets, BondArpValidate,
     BondConfig, BondFailOverMac, BondInterface, BondLacpRate, BondMode,
-    BondOptions, BondPrimaryReselect, BondXmitHashPolicy,
+    BondOptions, BondPortConfig, BondPrimaryReselect, BondXmitHashPolicy,
 };
 pub use dummy::DummyInterface;
+pub(crate) use ethernet::{
+    mark_veth_peers_as_absent, move_new_veth_peers_to_chg,
+    validate_veth_type_strict,
+};
 pub use ethernet::{
     EthernetConfig, EthernetDuplex, EthernetInterface, VethConfig,
 };
+pub use ethtool::{
+    EthtoolCoalesceConfig, EthtoolConfig, EthtoolPauseConfig, EthtoolRingConfig,
+};
+pub use geneve::{GeneveConfig, GeneveInterface};
+pub use gre::{GreConfig, GreInterface};
+pub use hsr::{HsrConfig, HsrInterface, HsrProtocol};
+pub use infiniband::{InfiniBandConfig, InfiniBandInterface, InfiniBandMode};
 pub use inter_ifaces::*;
+pub use ipsec::{IpsecInterface, LibreswanConfig};
+pub use ipvlan::{IpVlanConfig, IpVlanInterface, IpVlanMode};
 pub use linux_bridge::{
     LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeMulticastRouterType,
     LinuxBridgeOptions, LinuxBridgePortConfig, LinuxBridgePortTunkTag,
     LinuxBridgePortVlanConfig, LinuxBridgePortVlanMode,
-    LinuxBridgePortVlanRange, LinuxBridgeStpOptions,
+    LinuxBridgePortVlanRange, LinuxBridgeStpOptions, LinuxBridgeVlanProtocol,
 };
+pub use loopback::LoopbackInterface;
 pub use mac_vlan::{MacVlanConfig, MacVlanInterface, MacVlanMode};
 pub use mac_vtap::{MacVtapConfig, MacVtapInterface, MacVtapMode};
+pub use macsec::{
+    MacSecConfig, MacSecInterface, MacSecOffload, MacSecValidate,
+};
 pub use ovs::{
-    OvsBridgeBondConfig, OvsBridgeBondMode, OvsBridgeBondPortConfig,
-    OvsBridgeConfig, OvsBridgeInterface, OvsBridgeOptions, OvsBridgePortConfig,
-    OvsInterface,
+    OvsBridgeBondConfig, OvsBridgeBondLacpMode, OvsBridgeBondMode,
+    OvsBridgeBondPortConfig, OvsBridgeConfig, OvsBridgeDatapath,
+    OvsBridgeInterface, OvsBridgeOptions, OvsBridgePortConfig,
+    OvsDbIfaceConfig, OvsInterface,
 };
+pub use pppoe::{PppoeConfig, PppoeInterface};
 pub use sriov::{SrIovConfig, SrIovVfConfig};
-pub use vlan::{VlanConfig, VlanInterface};
+pub use team::{
+    TeamConfig, TeamInterface, TeamLinkWatcherConfig, TeamLinkWatcherType,
+    TeamPortConfig, TeamRunnerConfig, TeamRunnerMode,
+};
+pub use vlan::{VlanConfig, VlanInterface, VlanProtocol, VlanQosMapping};
+pub(crate) use vrf::{allocate_vrf_table_ids, validate_vrf_table_ids};
+pub use vrf::{VrfConfig, VrfInterface};
+pub use wifi::{
+    WifiBand, WifiConfig, WifiEapConfig, WifiInterface, WifiKeyManagement,
+    WifiSecurityConfig,
+};
+pub use wireguard::{WireGuardConfig, WireGuardInterface, WireGuardPeerConfig};
+pub use xfrm::{XfrmConfig, XfrmInterface};
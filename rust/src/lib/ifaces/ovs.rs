@@ -1,8 +1,12 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, LinuxBridgePortVlanConfig,
+    NmstateError,
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OvsBridgeInterface {
@@ -51,6 +55,18 @@ impl OvsBridgeInterface {
         self.sort_ports()
     }
 
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        for port_conf in self.port_confs() {
+            if let Some(bond_conf) = &port_conf.bond {
+                bond_conf.validate()?;
+            }
+            if let Some(vlan_conf) = &port_conf.vlan {
+                vlan_conf.validate(&port_conf.name)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn new() -> Self {
         Self::default()
     }
@@ -113,6 +129,12 @@ pub struct OvsBridgeOptions {
     pub mcast_snooping_enable: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fail_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datapath: Option<OvsBridgeDatapath>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcast_snooping_aging_time: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcast_snooping_flood_unregistered: Option<bool>,
 }
 
 impl OvsBridgeOptions {
@@ -121,6 +143,40 @@ impl OvsBridgeOptions {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OvsBridgeDatapath {
+    System,
+    Netdev,
+}
+
+impl TryFrom<&str> for OvsBridgeDatapath {
+    type Error = NmstateError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "system" => Ok(Self::System),
+            "netdev" => Ok(Self::Netdev),
+            _ => Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Unsupported OVS bridge datapath {}", value),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OvsBridgeDatapath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::System => "system",
+                Self::Netdev => "netdev",
+            }
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct OvsBridgePortConfig {
@@ -130,6 +186,8 @@ pub struct OvsBridgePortConfig {
         rename = "link-aggregation"
     )]
     pub bond: Option<OvsBridgeBondConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<LinuxBridgePortVlanConfig>,
 }
 
 impl OvsBridgePortConfig {
@@ -173,6 +231,14 @@ pub struct OvsBridgeBondConfig {
     pub bond_downdelay: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bond_updelay: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lacp: Option<OvsBridgeBondLacpMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bond_primary: Option<String>,
+    // Stored at the `other_config` column of OVSDB Port table, for example
+    // `lacp-time` or `bond-rebalance-interval`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other_config: Option<HashMap<String, String>>,
 }
 
 impl OvsBridgeBondConfig {
@@ -194,6 +260,35 @@ impl OvsBridgeBondConfig {
             bond_ports.sort_unstable_by_key(|p| p.name.clone())
         }
     }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(primary) = self.bond_primary.as_ref() {
+            if !self.ports().contains(&primary.as_str()) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "OVS bond primary {} is not a port of the bond",
+                        primary
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        if let Some(other_config) = self.other_config.as_ref() {
+            if other_config.contains_key("bond-primary") {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    "Please use bond-primary property instead of \
+                    bond-primary in other-config of OVS bond"
+                        .to_string(),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -253,3 +348,144 @@ impl std::fmt::Display for OvsBridgeBondMode {
         )
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OvsBridgeBondLacpMode {
+    Off,
+    Active,
+    Passive,
+}
+
+impl TryFrom<&str> for OvsBridgeBondLacpMode {
+    type Error = NmstateError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "off" => Ok(Self::Off),
+            "active" => Ok(Self::Active),
+            "passive" => Ok(Self::Passive),
+            _ => Err(NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Unsupported OVS Bond LACP mode {}", value),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OvsBridgeBondLacpMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Off => "off",
+                Self::Active => "active",
+                Self::Passive => "passive",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct OvsDbIfaceConfig {
+    // Value could reference property of this interface in the form of
+    // `${iface.mac-address}`, resolved at apply time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_ids: Option<HashMap<String, String>>,
+}
+
+const OVSDB_TEMPLATE_PREFIX: &str = "${iface.";
+const OVSDB_TEMPLATE_SUFFIX: &str = "}";
+
+impl OvsDbIfaceConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Property is searched in the order of `iface_values`, for example
+    // desired interface first then current interface.
+    pub(crate) fn resolve_templates(
+        &mut self,
+        iface_name: &str,
+        iface_values: &[serde_json::Value],
+    ) -> Result<(), NmstateError> {
+        if let Some(ext_ids) = self.external_ids.as_mut() {
+            for value in ext_ids.values_mut() {
+                if value.contains(OVSDB_TEMPLATE_PREFIX) {
+                    *value = resolve_template(value, iface_name, iface_values)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resolve_template(
+    template: &str,
+    iface_name: &str,
+    iface_values: &[serde_json::Value],
+) -> Result<String, NmstateError> {
+    let mut ret = String::new();
+    let mut remains = template;
+    while let Some(start) = remains.find(OVSDB_TEMPLATE_PREFIX) {
+        ret.push_str(&remains[..start]);
+        remains = &remains[start + OVSDB_TEMPLATE_PREFIX.len()..];
+        let end = match remains.find(OVSDB_TEMPLATE_SUFFIX) {
+            Some(e) => e,
+            None => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Unterminated template in OVS external_ids \
+                        value {} of interface {}",
+                        template, iface_name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        };
+        let prop_path = &remains[..end];
+        match iface_values
+            .iter()
+            .find_map(|v| get_iface_prop_as_string(v, prop_path))
+        {
+            Some(prop_value) => ret.push_str(&prop_value),
+            None => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Failed to resolve {}{}{} in OVS external_ids: \
+                        interface {} has no such property",
+                        OVSDB_TEMPLATE_PREFIX,
+                        prop_path,
+                        OVSDB_TEMPLATE_SUFFIX,
+                        iface_name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        remains = &remains[end + OVSDB_TEMPLATE_SUFFIX.len()..];
+    }
+    ret.push_str(remains);
+    Ok(ret)
+}
+
+// The `prop_path` is dot separated, for example `ethernet.speed`
+fn get_iface_prop_as_string(
+    iface_value: &serde_json::Value,
+    prop_path: &str,
+) -> Option<String> {
+    let mut value = iface_value;
+    for prop_name in prop_path.split('.') {
+        value = value.as_object()?.get(prop_name)?;
+    }
+    match value {
+        serde_json::Value::String(s) => Some(s.to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+// The interface name is the PPP interface created on top of parent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PppoeInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pppoe: Option<PppoeConfig>,
+}
+
+impl Default for PppoeInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Pppoe,
+                ..Default::default()
+            },
+            pppoe: None,
+        }
+    }
+}
+
+impl PppoeInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.pppoe.as_ref().map(|cfg| cfg.parent.as_str())
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.pppoe.as_ref() {
+            if conf.parent.is_empty() || conf.username.is_empty() {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Both parent and username are required for PPPoE \
+                        interface {}",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if conf.parent == self.base.name {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The parent of PPPoE interface {} should not be \
+                        itself",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_pppoe(&mut self, other: &PppoeInterface) {
+        if let Some(conf) = &mut self.pppoe {
+            conf.update(other.pppoe.as_ref());
+        } else {
+            self.pppoe = other.pppoe.clone();
+        }
+    }
+
+    // The password might not be exposed by current state
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(conf) = self.pppoe.as_mut() {
+            conf.password = None;
+        }
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if let Some(conf) = self.pppoe.as_mut() {
+            if conf.password.is_some() {
+                conf.password =
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct PppoeConfig {
+    // The ethernet interface the PPPoE session established on
+    pub parent: String,
+    pub username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    // Only connect to access concentrator providing this service
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+}
+
+impl PppoeConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.parent = other.parent.clone();
+            self.username = other.username.clone();
+            if other.password.is_some() {
+                self.password = other.password.clone();
+            }
+            if other.service.is_some() {
+                self.service = other.service.clone();
+            }
+        }
+    }
+}
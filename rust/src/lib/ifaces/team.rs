@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<TeamConfig>,
+}
+
+impl Default for TeamInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Team,
+                ..Default::default()
+            },
+            team: None,
+        }
+    }
+}
+
+impl TeamInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Return None when desire state does not mention ports
+    pub(crate) fn ports(&self) -> Option<Vec<&str>> {
+        self.team
+            .as_ref()
+            .and_then(|team_conf| team_conf.ports.as_ref())
+            .map(|ports| ports.iter().map(|p| p.name.as_str()).collect())
+    }
+
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn get_port_conf(
+        &self,
+        port_name: &str,
+    ) -> Option<&TeamPortConfig> {
+        self.team
+            .as_ref()
+            .and_then(|team_conf| team_conf.ports.as_ref())
+            .and_then(|ports| ports.iter().find(|p| p.name == port_name))
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        for watcher in self
+            .team
+            .as_ref()
+            .and_then(|c| c.link_watchers.as_ref())
+            .map(|w| w.as_slice())
+            .unwrap_or_default()
+        {
+            if watcher.name != TeamLinkWatcherType::Ethtool
+                && watcher.target_host.is_none()
+            {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The target-host is required by {} link watcher \
+                        of team interface {}",
+                        watcher.name, self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(ports) = self
+            .team
+            .as_mut()
+            .and_then(|team_conf| team_conf.ports.as_mut())
+        {
+            ports.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+
+    pub(crate) fn update_team(&mut self, other: &TeamInterface) {
+        if let Some(team_conf) = &mut self.team {
+            team_conf.update(other.team.as_ref());
+        } else {
+            self.team = other.team.clone();
+        }
+    }
+
+    pub(crate) fn remove_port(&mut self, port_to_remove: &str) {
+        if let Some(ports) = self
+            .team
+            .as_mut()
+            .and_then(|team_conf| team_conf.ports.as_mut())
+        {
+            ports.retain(|p| p.name != port_to_remove);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TeamConfig {
+    #[serde(skip_serializing_if = "Option::is_none", rename = "port")]
+    pub ports: Option<Vec<TeamPortConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runner: Option<TeamRunnerConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_watchers: Option<Vec<TeamLinkWatcherConfig>>,
+}
+
+impl TeamConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if other.ports.is_some() {
+                self.ports = other.ports.clone();
+            }
+            if other.runner.is_some() {
+                self.runner = other.runner.clone();
+            }
+            if other.link_watchers.is_some() {
+                self.link_watchers = other.link_watchers.clone();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TeamPortConfig {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prio: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sticky: Option<bool>,
+    // Only for `lacp` runner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lacp_prio: Option<u16>,
+    // Only for `lacp` runner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lacp_key: Option<u16>,
+}
+
+impl TeamPortConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TeamRunnerConfig {
+    pub name: TeamRunnerMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TeamRunnerMode {
+    Lacp,
+    ActiveBackup,
+    RoundRobin,
+    Broadcast,
+    Random,
+    LoadBalance,
+}
+
+impl std::fmt::Display for TeamRunnerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Lacp => "lacp",
+                Self::ActiveBackup => "activebackup",
+                Self::RoundRobin => "roundrobin",
+                Self::Broadcast => "broadcast",
+                Self::Random => "random",
+                Self::LoadBalance => "loadbalance",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TeamLinkWatcherConfig {
+    pub name: TeamLinkWatcherType,
+    // Only for `ethtool` link watcher, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_up: Option<u32>,
+    // Only for `ethtool` link watcher, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_down: Option<u32>,
+    // Only for `arp_ping` and `nsna_ping` link watcher, in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u32>,
+    // Only for `arp_ping` and `nsna_ping` link watcher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_host: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TeamLinkWatcherType {
+    #[serde(rename = "ethtool")]
+    Ethtool,
+    #[serde(rename = "arp_ping")]
+    ArpPing,
+    #[serde(rename = "nsna_ping")]
+    NsnaPing,
+}
+
+impl std::fmt::Display for TeamLinkWatcherType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ethtool => "ethtool",
+                Self::ArpPing => "arp_ping",
+                Self::NsnaPing => "nsna_ping",
+            }
+        )
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{BaseInterface, InterfaceType};
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VlanInterface {
@@ -31,6 +31,93 @@ impl VlanInterface {
         self.vlan.as_ref().map(|cfg| cfg.base_iface.as_str())
     }
 
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = self.vlan.as_ref() {
+            // The VLAN header priority is 3 bits
+            for (map, is_ingress) in [
+                (conf.ingress_qos_map.as_deref(), true),
+                (conf.egress_qos_map.as_deref(), false),
+            ] {
+                for qos in map.unwrap_or_default() {
+                    let pcp = if is_ingress { qos.from } else { qos.to };
+                    if pcp > VlanQosMapping::PCP_MAX {
+                        let e = NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "The VLAN header priority {} of {} QoS \
+                                mapping on VLAN interface {} should be \
+                                in the range of 0 to {}",
+                                pcp,
+                                if is_ingress { "ingress" } else { "egress" },
+                                self.base.name,
+                                VlanQosMapping::PCP_MAX
+                            ),
+                        );
+                        log::error!("{}", e);
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Nispor can only create 802.1q VLAN with kernel default flags and no QoS
+    // mapping, return the first unsupported property changed from current
+    // state or kernel default.
+    pub(crate) fn get_kernel_unsupported_prop(
+        &self,
+        current: Option<&Self>,
+    ) -> Option<&'static str> {
+        let conf = self.vlan.as_ref()?;
+        let cur_conf = current.and_then(|c| c.vlan.as_ref());
+        let cur_protocol = cur_conf
+            .and_then(|c| c.protocol)
+            .unwrap_or(VlanProtocol::Ieee8021Q);
+        if conf.protocol.is_some() && conf.protocol != Some(cur_protocol) {
+            return Some("changing VLAN protocol");
+        }
+        for (name, des, cur, default) in [
+            (
+                "changing VLAN reorder-headers flag",
+                conf.reorder_headers,
+                cur_conf.and_then(|c| c.reorder_headers),
+                true,
+            ),
+            (
+                "changing VLAN gvrp flag",
+                conf.gvrp,
+                cur_conf.and_then(|c| c.gvrp),
+                false,
+            ),
+            (
+                "changing VLAN mvrp flag",
+                conf.mvrp,
+                cur_conf.and_then(|c| c.mvrp),
+                false,
+            ),
+            (
+                "changing VLAN loose-binding flag",
+                conf.loose_binding,
+                cur_conf.and_then(|c| c.loose_binding),
+                false,
+            ),
+        ] {
+            if des.is_some() && des != Some(cur.unwrap_or(default)) {
+                return Some(name);
+            }
+        }
+        for (name, map) in [
+            ("VLAN ingress QoS mapping", conf.ingress_qos_map.as_ref()),
+            ("VLAN egress QoS mapping", conf.egress_qos_map.as_ref()),
+        ] {
+            if map.map(|m| !m.is_empty()) == Some(true) {
+                return Some(name);
+            }
+        }
+        None
+    }
+
     pub(crate) fn update_vlan(&mut self, other: &VlanInterface) {
         // TODO: this should be done by Trait
         if let Some(vlan_conf) = &mut self.vlan {
@@ -46,13 +133,80 @@ impl VlanInterface {
 pub struct VlanConfig {
     pub base_iface: String,
     pub id: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<VlanProtocol>,
+    // Mapping VLAN header priority to linux internal packet priority for
+    // incoming packet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingress_qos_map: Option<Vec<VlanQosMapping>>,
+    // Mapping linux internal packet priority to VLAN header priority for
+    // outgoing packet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub egress_qos_map: Option<Vec<VlanQosMapping>>,
+    // Kernel default to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reorder_headers: Option<bool>,
+    // GARP VLAN Registration Protocol, default to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gvrp: Option<bool>,
+    // Multiple VLAN Registration Protocol, default to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mvrp: Option<bool>,
+    // When enabled, the VLAN operating state is not bound to the state of
+    // base interface. Default to false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loose_binding: Option<bool>,
 }
 
 impl VlanConfig {
     fn update(&mut self, other: Option<&Self>) {
         if let Some(other) = other {
-            self.base_iface = other.base_iface.clone();
+            // NetworkManager might refer the parent by profile UUID which is
+            // not resolved to interface name
+            if !other.base_iface.is_empty() {
+                self.base_iface = other.base_iface.clone();
+            }
             self.id = other.id;
+            if other.protocol.is_some() {
+                self.protocol = other.protocol;
+            }
+            if other.ingress_qos_map.is_some() {
+                self.ingress_qos_map = other.ingress_qos_map.clone();
+            }
+            if other.egress_qos_map.is_some() {
+                self.egress_qos_map = other.egress_qos_map.clone();
+            }
+            if other.reorder_headers.is_some() {
+                self.reorder_headers = other.reorder_headers;
+            }
+            if other.gvrp.is_some() {
+                self.gvrp = other.gvrp;
+            }
+            if other.mvrp.is_some() {
+                self.mvrp = other.mvrp;
+            }
+            if other.loose_binding.is_some() {
+                self.loose_binding = other.loose_binding;
+            }
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VlanProtocol {
+    #[serde(rename = "802.1q")]
+    Ieee8021Q,
+    // Service VLAN tag used by QinQ stacking
+    #[serde(rename = "802.1ad")]
+    Ieee8021Ad,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VlanQosMapping {
+    pub from: u32,
+    pub to: u32,
+}
+
+impl VlanQosMapping {
+    pub const PCP_MAX: u32 = 7;
+}
@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    BaseInterface, ErrorKind, Interface, InterfaceType, Interfaces,
+    NmstateError,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VrfInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrf: Option<VrfConfig>,
+}
+
+impl Default for VrfInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Vrf,
+                ..Default::default()
+            },
+            vrf: None,
+        }
+    }
+}
+
+impl VrfInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if self.vrf.as_ref().map(|c| c.table_id) == Some(0) {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Route table ID 0 is reserved, please use other ID \
+                    for VRF {}",
+                    self.base.name
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    // Return None when desire state does not mention ports
+    pub(crate) fn ports(&self) -> Option<Vec<&str>> {
+        self.vrf
+            .as_ref()
+            .and_then(|vrf_conf| vrf_conf.port.as_ref())
+            .map(|ports| ports.as_slice().iter().map(|p| p.as_str()).collect())
+    }
+
+    pub(crate) fn table_id(&self) -> Option<u32> {
+        self.vrf.as_ref().map(|c| c.table_id)
+    }
+
+    pub(crate) fn is_table_id_auto(&self) -> bool {
+        self.table_id() == Some(VrfConfig::TABLE_ID_AUTO)
+    }
+
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(ports) = self
+            .vrf
+            .as_mut()
+            .and_then(|vrf_conf| vrf_conf.port.as_mut())
+        {
+            ports.sort_unstable();
+        }
+    }
+
+    pub(crate) fn update_vrf(&mut self, other: &VrfInterface) {
+        if let Some(vrf_conf) = &mut self.vrf {
+            vrf_conf.update(other.vrf.as_ref());
+        } else {
+            self.vrf = other.vrf.clone();
+        }
+    }
+
+    pub(crate) fn remove_port(&mut self, port_to_remove: &str) {
+        if let Some(ports) = self
+            .vrf
+            .as_mut()
+            .and_then(|vrf_conf| vrf_conf.port.as_mut())
+        {
+            ports.retain(|port_name| port_name != port_to_remove);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct VrfConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<Vec<String>>,
+    // Set to `VrfConfig::TABLE_ID_AUTO`(`auto` in YAML) to allocate a free
+    // route table ID from current state.
+    #[serde(
+        rename = "route-table-id",
+        deserialize_with = "table_id_deserialize",
+        serialize_with = "table_id_serialize"
+    )]
+    pub table_id: u32,
+}
+
+impl VrfConfig {
+    pub const TABLE_ID_AUTO: u32 = u32::MAX;
+    // Search free table ID from here to avoid the commonly used small ones.
+    pub const TABLE_ID_AUTO_START: u32 = 1000;
+    // The RT_TABLE_DEFAULT, RT_TABLE_MAIN and RT_TABLE_LOCAL
+    const RESERVED_TABLE_IDS: [u32; 3] = [253, 254, 255];
+
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if other.port.is_some() {
+                self.port = other.port.clone();
+            }
+            self.table_id = other.table_id;
+        }
+    }
+}
+
+fn table_id_deserialize<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v: serde_json::Value = Deserialize::deserialize(deserializer)?;
+    match (v.as_u64(), v.as_str()) {
+        (_, Some("auto")) => Ok(VrfConfig::TABLE_ID_AUTO),
+        (Some(t), _) if t < VrfConfig::TABLE_ID_AUTO.into() => Ok(t as u32),
+        (_, Some(s)) if s.parse::<u32>().is_ok() => {
+            s.parse::<u32>().map_err(D::Error::custom)
+        }
+        _ => Err(D::Error::custom(format!(
+            "Invalid route-table-id {}, should be unsigned integer or auto",
+            v
+        ))),
+    }
+}
+
+fn table_id_serialize<S>(
+    table_id: &u32,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if *table_id == VrfConfig::TABLE_ID_AUTO {
+        serializer.serialize_str("auto")
+    } else {
+        serializer.serialize_u32(*table_id)
+    }
+}
+
+// Allocate free route table ID for VRFs with `route-table-id: auto`. The
+// existing VRF keeps its current table ID.
+pub(crate) fn allocate_vrf_table_ids(
+    ifaces: &mut Interfaces,
+    cur_ifaces: &Interfaces,
+    mut used_table_ids: HashSet<u32>,
+) {
+    for iface in ifaces
+        .kernel_ifaces
+        .values()
+        .chain(cur_ifaces.kernel_ifaces.values())
+    {
+        if let Interface::Vrf(vrf_iface) = iface {
+            if let Some(t) = vrf_iface.table_id() {
+                used_table_ids.insert(t);
+            }
+        }
+    }
+    let mut auto_iface_names: Vec<String> = ifaces
+        .kernel_ifaces
+        .values()
+        .filter_map(|i| match i {
+            Interface::Vrf(vrf_iface) if vrf_iface.is_table_id_auto() => {
+                Some(vrf_iface.base.name.to_string())
+            }
+            _ => None,
+        })
+        .collect();
+    // Sort to make sure the allocation is identical on every invocation
+    auto_iface_names.sort_unstable();
+
+    let mut next_table_id = VrfConfig::TABLE_ID_AUTO_START;
+    for iface_name in auto_iface_names {
+        let cur_table_id = cur_ifaces
+            .get_vrf_table_id(&iface_name)
+            .filter(|t| *t != VrfConfig::TABLE_ID_AUTO);
+        let table_id = match cur_table_id {
+            Some(t) => t,
+            None => {
+                while used_table_ids.contains(&next_table_id)
+                    || VrfConfig::RESERVED_TABLE_IDS.contains(&next_table_id)
+                {
+                    next_table_id += 1;
+                }
+                used_table_ids.insert(next_table_id);
+                next_table_id
+            }
+        };
+        if let Some(Interface::Vrf(vrf_iface)) =
+            ifaces.kernel_ifaces.get_mut(&iface_name)
+        {
+            if let Some(vrf_conf) = vrf_iface.vrf.as_mut() {
+                log::info!(
+                    "Using route table ID {} for VRF {}",
+                    table_id,
+                    iface_name
+                );
+                vrf_conf.table_id = table_id;
+            }
+        }
+    }
+}
+
+// Each VRF should use its own route table.
+pub(crate) fn validate_vrf_table_ids(
+    ifaces: &Interfaces,
+    cur_ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut table_ids: BTreeMap<&str, u32> = BTreeMap::new();
+    for iface in cur_ifaces.kernel_ifaces.values() {
+        if let Interface::Vrf(vrf_iface) = iface {
+            if let Some(t) = vrf_iface.table_id() {
+                table_ids.insert(vrf_iface.base.name.as_str(), t);
+            }
+        }
+    }
+    for iface in ifaces.kernel_ifaces.values() {
+        if let Interface::Vrf(vrf_iface) = iface {
+            if iface.is_absent() {
+                table_ids.remove(vrf_iface.base.name.as_str());
+            } else if let Some(t) = vrf_iface.table_id() {
+                table_ids.insert(vrf_iface.base.name.as_str(), t);
+            }
+        }
+    }
+    let mut table_owners: BTreeMap<u32, &str> = BTreeMap::new();
+    for (iface_name, table_id) in table_ids {
+        if let Some(other) = table_owners.insert(table_id, iface_name) {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "VRF {} and {} cannot use the same route table ID {}",
+                    other, iface_name, table_id
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
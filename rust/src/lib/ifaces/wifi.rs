@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BaseInterface, ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+const WPA_PSK_MIN_LEN: usize = 8;
+const WPA_PSK_MAX_LEN: usize = 64;
+
+// Only station(infrastructure) mode is supported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WifiInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wifi: Option<WifiConfig>,
+}
+
+impl Default for WifiInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Wifi,
+                ..Default::default()
+            },
+            wifi: None,
+        }
+    }
+}
+
+impl WifiInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        let conf = match self.wifi.as_ref() {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+        if conf.ssid.is_empty() || conf.ssid.len() > WifiConfig::SSID_MAX_LEN {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "The SSID of WiFi interface {} should be 1 to {} bytes",
+                    self.base.name,
+                    WifiConfig::SSID_MAX_LEN
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if let Some(sec) = conf.security.as_ref() {
+            sec.validate(&self.base.name)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_wifi(&mut self, other: &WifiInterface) {
+        if let Some(conf) = &mut self.wifi {
+            conf.update(other.wifi.as_ref());
+        } else {
+            self.wifi = other.wifi.clone();
+        }
+    }
+
+    // The secrets might not be exposed by current state
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(sec) =
+            self.wifi.as_mut().and_then(|conf| conf.security.as_mut())
+        {
+            sec.psk = None;
+            if let Some(eap) = sec.eap.as_mut() {
+                eap.password = None;
+            }
+        }
+    }
+
+    pub(crate) fn hide_secrets(&mut self) {
+        if let Some(sec) =
+            self.wifi.as_mut().and_then(|conf| conf.security.as_mut())
+        {
+            if sec.psk.is_some() {
+                sec.psk =
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+            }
+            if let Some(eap) = sec.eap.as_mut() {
+                if eap.password.is_some() {
+                    eap.password =
+                        Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct WifiConfig {
+    pub ssid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<WifiBand>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<WifiSecurityConfig>,
+}
+
+impl WifiConfig {
+    pub const SSID_MAX_LEN: usize = 32;
+
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.ssid = other.ssid.clone();
+            if other.band.is_some() {
+                self.band = other.band;
+            }
+            if other.security.is_some() {
+                self.security = other.security.clone();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WifiBand {
+    #[serde(rename = "2.4GHz")]
+    Band2_4GHz,
+    #[serde(rename = "5GHz")]
+    Band5GHz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WifiKeyManagement {
+    WpaPsk,
+    WpaEap,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WifiSecurityConfig {
+    pub key_management: WifiKeyManagement,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub psk: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eap: Option<WifiEapConfig>,
+}
+
+impl WifiSecurityConfig {
+    fn validate(&self, iface_name: &str) -> Result<(), NmstateError> {
+        match self.key_management {
+            WifiKeyManagement::WpaPsk => {
+                if let Some(psk) = self.psk.as_deref() {
+                    if psk != NetworkState::PASSWORD_HID_BY_NMSTATE
+                        && (psk.len() < WPA_PSK_MIN_LEN
+                            || psk.len() > WPA_PSK_MAX_LEN)
+                    {
+                        let e = NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "The WPA-PSK of WiFi interface {} should be \
+                                {} to {} characters",
+                                iface_name, WPA_PSK_MIN_LEN, WPA_PSK_MAX_LEN
+                            ),
+                        );
+                        log::error!("{}", e);
+                        return Err(e);
+                    }
+                }
+            }
+            WifiKeyManagement::WpaEap => {
+                if self.eap.is_none() {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "The eap section is required for WPA-EAP of \
+                            WiFi interface {}",
+                            iface_name
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct WifiEapConfig {
+    // EAP method like `peap`, `ttls` or `tls`
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase2_auth: Option<String>,
+}
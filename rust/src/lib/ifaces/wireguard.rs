@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WireGuardInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wireguard: Option<WireGuardConfig>,
+}
+
+impl Default for WireGuardInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::WireGuard,
+                ..Default::default()
+            },
+            wireguard: None,
+        }
+    }
+}
+
+impl WireGuardInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(peers) =
+            self.wireguard.as_ref().and_then(|c| c.peers.as_ref())
+        {
+            for peer in peers {
+                if peer.public_key.is_empty() {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "WireGuard peer of interface {} has no public-key",
+                            self.base.name
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Secrets are never shown in current state
+    pub(crate) fn pre_verify_cleanup(&mut self) {
+        if let Some(conf) = self.wireguard.as_mut() {
+            conf.private_key = None;
+            if let Some(peers) = conf.peers.as_mut() {
+                for peer in peers.iter_mut() {
+                    peer.preshared_key = None;
+                    peer.allowed_ips.sort_unstable();
+                }
+                peers.sort_unstable_by(|a, b| a.public_key.cmp(&b.public_key));
+            }
+        }
+    }
+
+    pub(crate) fn update_wireguard(&mut self, other: &WireGuardInterface) {
+        if let Some(conf) = &mut self.wireguard {
+            conf.update(other.wireguard.as_ref());
+        } else {
+            self.wireguard = other.wireguard.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct WireGuardConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fwmark: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peers: Option<Vec<WireGuardPeerConfig>>,
+}
+
+impl WireGuardConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            if other.private_key.is_some() {
+                self.private_key = other.private_key.clone();
+            }
+            if other.listen_port.is_some() {
+                self.listen_port = other.listen_port;
+            }
+            if other.fwmark.is_some() {
+                self.fwmark = other.fwmark;
+            }
+            if other.peers.is_some() {
+                self.peers = other.peers.clone();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct WireGuardPeerConfig {
+    pub public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub allowed_ips: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistent_keepalive: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preshared_key: Option<String>,
+}
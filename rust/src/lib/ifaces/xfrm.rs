@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BaseInterface, ErrorKind, InterfaceType, NmstateError};
+
+// Route based IPsec interface, only supported in kernel only mode.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XfrmInterface {
+    #[serde(flatten)]
+    pub base: BaseInterface,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xfrm: Option<XfrmConfig>,
+}
+
+impl Default for XfrmInterface {
+    fn default() -> Self {
+        Self {
+            base: BaseInterface {
+                iface_type: InterfaceType::Xfrm,
+                ..Default::default()
+            },
+            xfrm: None,
+        }
+    }
+}
+
+impl XfrmInterface {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if let Some(conf) = &self.xfrm {
+            if conf.if_id == 0 {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The if-id of XFRM interface {} should not be 0",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+            if conf.base_iface.as_deref() == Some(self.base.name.as_str()) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The base-iface of XFRM interface {} should not be \
+                        itself",
+                        self.base.name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn parent(&self) -> Option<&str> {
+        self.xfrm.as_ref().and_then(|cfg| cfg.base_iface.as_deref())
+    }
+
+    pub(crate) fn update_xfrm(&mut self, other: &XfrmInterface) {
+        if let Some(conf) = &mut self.xfrm {
+            conf.update(other.xfrm.as_ref());
+        } else {
+            self.xfrm = other.xfrm.clone();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct XfrmConfig {
+    // The XFRM policies and states with the same if_id are routed through
+    // this interface.
+    pub if_id: u32,
+    // The underlying interface for the encrypted traffic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_iface: Option<String>,
+}
+
+impl XfrmConfig {
+    fn update(&mut self, other: Option<&Self>) {
+        if let Some(other) = other {
+            self.if_id = other.if_id;
+            if other.base_iface.is_some() {
+                self.base_iface = other.base_iface.clone();
+            }
+        }
+    }
+}
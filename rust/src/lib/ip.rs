@@ -1,18 +1,32 @@
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
-use log::{debug, warn};
+use log::{debug, info, warn};
 use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 
-use crate::{DnsClientState, ErrorKind, Interfaces, NmstateError};
+use crate::{
+    DnsClientState, ErrorKind, Interface, InterfaceType, Interfaces,
+    NmstateError, RouteEntry, RouteProtocol, Routes,
+};
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct InterfaceIpv4 {
     pub enabled: bool,
     pub prop_list: Vec<&'static str>,
     pub dhcp: bool,
+    // Auto-assign address from 169.254.0.0/16 (RFC 3927)
+    pub link_local: bool,
     pub addresses: Vec<InterfaceIpAddr>,
+    // Addresses auto-assigned by IPv4 link-local, only for query.
+    pub link_local_addresses: Vec<InterfaceIpAddr>,
+    // Share the connection to other computers, static addresses are kept as
+    // the gateway address of the shared network.
+    pub method: Option<InterfaceIpv4Method>,
+    // Translate IPv4 traffic to IPv6 via NAT64 prefix (PREF64) announced by
+    // IPv6 router advertisement, RFC 6877 464XLAT.
+    pub clat: Option<InterfaceIpv4Clat>,
     pub(crate) dns: Option<DnsClientState>,
     pub auto_dns: Option<bool>,
     pub auto_gateway: Option<bool>,
@@ -31,7 +45,7 @@ impl Serialize for InterfaceIpv4 {
                 if self.dhcp {
                     self.prop_list.len()
                 } else {
-                    std::cmp::min(3, self.prop_list.len())
+                    std::cmp::min(4, self.prop_list.len())
                 }
             } else {
                 1
@@ -42,6 +56,16 @@ impl Serialize for InterfaceIpv4 {
             if self.prop_list.contains(&"dhcp") {
                 serial_struct.serialize_field("dhcp", &self.dhcp)?;
             }
+            if self.prop_list.contains(&"link_local") && self.link_local {
+                serial_struct
+                    .serialize_field("link-local", &self.link_local)?;
+            }
+            if self.prop_list.contains(&"method") && self.method.is_some() {
+                serial_struct.serialize_field("method", &self.method)?;
+            }
+            if self.prop_list.contains(&"clat") && self.clat.is_some() {
+                serial_struct.serialize_field("clat", &self.clat)?;
+            }
             if self.dhcp {
                 if self.prop_list.contains(&"auto_dns") {
                     serial_struct
@@ -61,10 +85,21 @@ impl Serialize for InterfaceIpv4 {
                         &self.auto_table_id,
                     )?;
                 }
+            } else if self.prop_list.contains(&"auto_gateway")
+                && self.auto_gateway == Some(false)
+            {
+                serial_struct
+                    .serialize_field("auto-gateway", &self.auto_gateway)?;
             }
             if self.prop_list.contains(&"addresses") {
                 serial_struct.serialize_field("address", &self.addresses)?;
             }
+            if self.link_local && !self.link_local_addresses.is_empty() {
+                serial_struct.serialize_field(
+                    "link-local-address",
+                    &self.link_local_addresses,
+                )?;
+            }
         }
         serial_struct.end()
     }
@@ -78,7 +113,11 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
         enum Field {
             Enabled,
             Dhcp,
+            LinkLocal,
+            Method,
+            Clat,
             Address,
+            LinkLocalAddress,
             AutoDns,
             AutoGateway,
             AutoRoutes,
@@ -100,8 +139,10 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
                         formatter: &mut fmt::Formatter,
                     ) -> fmt::Result {
                         formatter.write_str(
-                            "`enabled`, `dhcp`, `address`\
-                            `auto-dns`, `auto-gateway`, `auto-routes` or \
+                            "`enabled`, `dhcp`, `link-local`, `method`, \
+                            `clat`, `address`, `link-local-address`, \
+                            `auto-dns`, \
+                            `auto-gateway`, `auto-routes` or \
                             `auto-route-table-id`",
                         )
                     }
@@ -113,7 +154,11 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
                         match value {
                             "enabled" => Ok(Field::Enabled),
                             "dhcp" => Ok(Field::Dhcp),
+                            "link-local" => Ok(Field::LinkLocal),
+                            "method" => Ok(Field::Method),
+                            "clat" => Ok(Field::Clat),
                             "address" => Ok(Field::Address),
+                            "link-local-address" => Ok(Field::LinkLocalAddress),
                             "auto-dns" => Ok(Field::AutoDns),
                             "auto-gateway" => Ok(Field::AutoGateway),
                             "auto-routes" => Ok(Field::AutoRoutes),
@@ -143,6 +188,9 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
             {
                 let mut enabled = false;
                 let mut dhcp = false;
+                let mut link_local = false;
+                let mut method = None;
+                let mut clat = None;
                 let mut prop_list: Vec<&'static str> = Vec::new();
                 let mut addresses: Vec<InterfaceIpAddr> = Vec::new();
                 let mut auto_dns = None;
@@ -168,6 +216,35 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
                             dhcp = map.next_value()?;
                             prop_list.push("dhcp");
                         }
+                        Field::LinkLocal => {
+                            if prop_list.contains(&"link_local") {
+                                return Err(de::Error::duplicate_field(
+                                    "link-local",
+                                ));
+                            }
+                            link_local = map.next_value()?;
+                            prop_list.push("link_local");
+                        }
+                        Field::Method => {
+                            if prop_list.contains(&"method") {
+                                return Err(de::Error::duplicate_field(
+                                    "method",
+                                ));
+                            }
+                            method = map.next_value()?;
+                            prop_list.push("method");
+                        }
+                        Field::Clat => {
+                            if prop_list.contains(&"clat") {
+                                return Err(de::Error::duplicate_field("clat"));
+                            }
+                            clat = map.next_value()?;
+                            prop_list.push("clat");
+                        }
+                        // Auto-assigned link-local addresses is query only
+                        Field::LinkLocalAddress => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
                         Field::Address => {
                             if prop_list.contains(&"addresses") {
                                 return Err(de::Error::duplicate_field(
@@ -219,7 +296,11 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
                     enabled,
                     prop_list,
                     dhcp,
+                    link_local,
                     addresses,
+                    link_local_addresses: Vec::new(),
+                    method,
+                    clat,
                     auto_dns,
                     auto_gateway,
                     auto_routes,
@@ -231,7 +312,11 @@ impl<'de> Deserialize<'de> for InterfaceIpv4 {
         const FIELDS: &[&str] = &[
             "enabled",
             "dhcp",
+            "link-local",
+            "method",
+            "clat",
             "address",
+            "link-local-address",
             "auto-dns",
             "auto-gateway",
             "auto-routes",
@@ -250,6 +335,24 @@ impl InterfaceIpv4 {
         Self::default()
     }
 
+    // Whether default gateway is not allowed on this interface regardless
+    // static or dynamic IP.
+    pub(crate) fn is_auto_gateway_disabled(&self) -> bool {
+        self.enabled && self.auto_gateway == Some(false)
+    }
+
+    pub(crate) fn is_shared(&self) -> bool {
+        self.enabled && self.method == Some(InterfaceIpv4Method::Shared)
+    }
+
+    pub(crate) fn is_clat_enabled(&self) -> bool {
+        self.enabled
+            && matches!(
+                self.clat,
+                Some(InterfaceIpv4Clat::Auto | InterfaceIpv4Clat::Force)
+            )
+    }
+
     pub(crate) fn update(&mut self, other: &Self) {
         if other.prop_list.contains(&"enabled") {
             self.enabled = other.enabled;
@@ -257,6 +360,15 @@ impl InterfaceIpv4 {
         if other.prop_list.contains(&"dhcp") {
             self.dhcp = other.dhcp;
         }
+        if other.prop_list.contains(&"link_local") {
+            self.link_local = other.link_local;
+        }
+        if other.prop_list.contains(&"method") {
+            self.method = other.method;
+        }
+        if other.prop_list.contains(&"clat") {
+            self.clat = other.clat;
+        }
         if other.prop_list.contains(&"addresses") {
             self.addresses = other.addresses.clone();
         }
@@ -280,16 +392,74 @@ impl InterfaceIpv4 {
                 self.prop_list.push(other_prop_name);
             }
         }
+        if self.link_local {
+            self.split_link_local_addresses();
+        }
+    }
+
+    // Move the auto-assigned link-local addresses out of `addresses`
+    fn split_link_local_addresses(&mut self) {
+        let (ll_addrs, addrs) = self
+            .addresses
+            .drain(..)
+            .partition(|addr| is_ipv4_link_local(&addr.ip));
+        self.addresses = addrs;
+        self.link_local_addresses = ll_addrs;
     }
 
     // Clean up before sending to plugin for applying
     // * Convert expanded IP address to compacted
     // * Set auto_dns, auto_gateway and auto_routes to true if DHCP enabled and
     //   those options is None
-    // * Remove static IP address when DHCP enabled.
+    // * Remove static IP address when DHCP or link-local enabled.
     pub(crate) fn pre_edit_cleanup(&mut self) -> Result<(), NmstateError> {
         for addr in &mut self.addresses {
             addr.sanitize()?;
+            if addr.flags.as_ref().map(|f| !f.is_empty()) == Some(true) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "IP address flags are only supported by IPv6, \
+                        but defined for IPv4 address {}/{}",
+                        addr.ip, addr.prefix_length
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        if self.enabled && self.dhcp && self.link_local {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "IPv4 DHCP and link-local cannot be both enabled".to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if self.is_shared() && (self.dhcp || self.link_local) {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "IPv4 shared method cannot be used with DHCP or link-local"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if self.is_clat_enabled() && !self.dhcp {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "IPv4 CLAT requires IPv4 DHCP enabled".to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if self.enabled && self.link_local && !self.addresses.is_empty() {
+            log::warn!(
+                "Static addresses {:?} are ignored when IPv4 link-local \
+                is enabled",
+                self.addresses.as_slice()
+            );
+            self.addresses = Vec::new();
         }
         if self.enabled && self.dhcp {
             if self.auto_dns.is_none() {
@@ -316,11 +486,18 @@ impl InterfaceIpv4 {
     // Clean up before verification
     // * Sort IP address
     // * Convert expanded IP address to compacted
+    // * Remove IP address flags
     // * Add optional properties to prop_list
     // * Ignore DHCP options if DHCP disabled
-    // * Ignore address if DHCP enabled
+    // * Ignore address if DHCP or link-local enabled
+    // * Remove query only link-local addresses
     // * Set DHCP as off if enabled and dhcp is None
+    // * Treat `auto` method as not defined, as current never shows it
     pub(crate) fn pre_verify_cleanup(&mut self) {
+        self.link_local_addresses.clear();
+        if self.method == Some(InterfaceIpv4Method::Auto) {
+            self.method = None;
+        }
         self.addresses.sort_unstable_by(|a, b| {
             (&a.ip, a.prefix_length).cmp(&(&b.ip, b.prefix_length))
         });
@@ -328,6 +505,8 @@ impl InterfaceIpv4 {
             if let Err(e) = addr.sanitize() {
                 warn!("BUG: IP address sanitize failure: {}", e);
             }
+            addr.flags = None;
+            addr.allow_duplicate = None;
         }
         debug!("IPv4 after pre_verify_cleanup: {:?}", self);
         self.prop_list.push("dhcp");
@@ -337,12 +516,48 @@ impl InterfaceIpv4 {
                     .contains(p)
             });
         }
-        if self.enabled && self.dhcp && self.prop_list.contains(&"addresses") {
+        if self.enabled
+            && (self.dhcp || self.link_local)
+            && self.prop_list.contains(&"addresses")
+        {
             self.prop_list.retain(|p| p != &"addresses")
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceIpv4Method {
+    // Not sharing, IP is configured by `dhcp`, `link-local` or static
+    // addresses as usual. Used to switch back from `shared`.
+    Auto,
+    // Share the connection via NAT with DHCP and DNS served by dnsmasq
+    Shared,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceIpv4Clat {
+    Disabled,
+    // Only when PREF64 is announced and no native IPv4 address is assigned
+    Auto,
+    // Even native IPv4 address is assigned
+    Force,
+}
+
+impl std::fmt::Display for InterfaceIpv4Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Auto => "auto",
+                Self::Shared => "shared",
+            }
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct InterfaceIpv6 {
     pub enabled: bool,
@@ -369,7 +584,7 @@ impl Serialize for InterfaceIpv6 {
                     self.prop_list.len()
                 } else {
                     // If DHCP disabled, we can only show
-                    std::cmp::min(4, self.prop_list.len())
+                    std::cmp::min(5, self.prop_list.len())
                 }
             } else {
                 1
@@ -402,6 +617,11 @@ impl Serialize for InterfaceIpv6 {
                         &self.auto_table_id,
                     )?;
                 }
+            } else if self.prop_list.contains(&"auto_gateway")
+                && self.auto_gateway == Some(false)
+            {
+                serial_struct
+                    .serialize_field("auto-gateway", &self.auto_gateway)?;
             }
             if self.prop_list.contains(&"addresses") {
                 serial_struct.serialize_field("address", &self.addresses)?;
@@ -605,6 +825,20 @@ impl InterfaceIpv6 {
         Self::default()
     }
 
+    // Whether default gateway is not allowed on this interface regardless
+    // static or dynamic IP.
+    pub(crate) fn is_auto_gateway_disabled(&self) -> bool {
+        self.enabled && self.auto_gateway == Some(false)
+    }
+
+    pub(crate) fn has_addr_flags(&self) -> bool {
+        self.enabled
+            && self
+                .addresses
+                .iter()
+                .any(|a| a.flags.as_ref().map(|f| !f.is_empty()) == Some(true))
+    }
+
     pub(crate) fn update(&mut self, other: &Self) {
         if other.prop_list.contains(&"enabled") {
             self.enabled = other.enabled;
@@ -643,6 +877,7 @@ impl InterfaceIpv6 {
     // Clean up before verification
     // * Remove link-local address
     // * Sanitize the expanded IP address
+    // * Remove IP address flags
     // * Add optional properties to prop_list
     // * Ignore DHCP options if DHCP disabled
     // * Ignore IP address when DHCP/autoconf enabled.
@@ -657,6 +892,8 @@ impl InterfaceIpv6 {
             if let Err(e) = addr.sanitize() {
                 warn!("BUG: IP address sanitize failure: {}", e);
             }
+            addr.flags = None;
+            addr.allow_duplicate = None;
         }
         self.prop_list.push("dhcp");
         self.prop_list.push("autoconf");
@@ -724,6 +961,39 @@ impl InterfaceIpv6 {
 pub struct InterfaceIpAddr {
     pub ip: String,
     pub prefix_length: u8,
+    // Kernel IPv6 address flags. Not included in verification as neither
+    // NetworkManager nor nispor report them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<Vec<InterfaceIpAddrFlag>>,
+    // Allow the same address and prefix to be assigned to other interface,
+    // for example, anycast address. Only for applying.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_duplicate: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceIpAddrFlag {
+    // Skip duplicate address detection
+    Nodad,
+    // Mobile IPv6 home address
+    Home,
+    // Use this address as template for temporary addresses
+    Mngtmpaddr,
+}
+
+impl std::fmt::Display for InterfaceIpAddrFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Nodad => "nodad",
+                Self::Home => "home",
+                Self::Mngtmpaddr => "mngtmpaddr",
+            }
+        )
+    }
 }
 
 impl InterfaceIpAddr {
@@ -735,6 +1005,47 @@ impl InterfaceIpAddr {
     }
 }
 
+#[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+const DEFAULT_ADDR_ANNOUNCE_COUNT: u32 = 3;
+#[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+const DEFAULT_ADDR_ANNOUNCE_INTERVAL_MILLISECONDS: u32 = 1000;
+
+// Send gratuitous ARP for IPv4 and unsolicited neighbor advertisement for IPv6
+// static addresses after applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct InterfaceAddrAnnounce {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    // In milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u32>,
+}
+
+impl InterfaceAddrAnnounce {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+    pub(crate) fn count(&self) -> u32 {
+        self.count.unwrap_or(DEFAULT_ADDR_ANNOUNCE_COUNT)
+    }
+
+    #[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+    pub(crate) fn interval(&self) -> u32 {
+        self.interval
+            .unwrap_or(DEFAULT_ADDR_ANNOUNCE_INTERVAL_MILLISECONDS)
+    }
+}
+
+// The IPv4 link-local address range is 169.254.0.0/16.
+fn is_ipv4_link_local(ip: &str) -> bool {
+    std::net::Ipv4Addr::from_str(ip)
+        .map(|ip| ip.is_link_local())
+        .unwrap_or_default()
+}
+
 pub(crate) fn is_ipv6_addr(addr: &str) -> bool {
     addr.contains(':')
 }
@@ -771,7 +1082,12 @@ impl std::convert::TryFrom<&str> for InterfaceIpAddr {
                 e
             })?
         };
-        Ok(Self { ip, prefix_length })
+        Ok(Self {
+            ip,
+            prefix_length,
+            flags: None,
+            allow_duplicate: None,
+        })
     }
 }
 
@@ -786,6 +1102,9 @@ pub(crate) fn include_current_ip_address_if_dhcp_on_to_off(
     current: &Interfaces,
 ) {
     for (iface_name, iface) in chg_net_state.kernel_ifaces.iter_mut() {
+        if !iface.base_iface().keep_dynamic_addresses() {
+            continue;
+        }
         let cur_iface = if let Some(c) = current.kernel_ifaces.get(iface_name) {
             c
         } else {
@@ -822,3 +1141,415 @@ pub(crate) fn include_current_ip_address_if_dhcp_on_to_off(
         }
     }
 }
+
+// Like include_current_ip_address_if_dhcp_on_to_off(), the routes learned
+// from DHCP or IPv6 RA are converted to static routes with their metric and
+// route table preserved.
+pub(crate) fn include_current_dhcp_routes_if_dhcp_on_to_off(
+    chg_net_state: &mut Interfaces,
+    current: &Interfaces,
+    desired_routes: &Routes,
+    current_routes: &Routes,
+) {
+    let absent_routes: Vec<&RouteEntry> = desired_routes
+        .config
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.is_absent())
+        .collect();
+    for (iface_name, iface) in chg_net_state.kernel_ifaces.iter_mut() {
+        if !iface.base_iface().keep_dynamic_addresses() {
+            continue;
+        }
+        let cur_iface = if let Some(c) = current.kernel_ifaces.get(iface_name) {
+            c
+        } else {
+            continue;
+        };
+        let ipv4_dhcp_off = match (
+            cur_iface.base_iface().ipv4.as_ref(),
+            iface.base_iface().ipv4.as_ref(),
+        ) {
+            (Some(cur_ip_conf), Some(ip_conf)) => {
+                cur_ip_conf.dhcp && ip_conf.enabled && !ip_conf.dhcp
+            }
+            _ => false,
+        };
+        let ipv6_dhcp_off = match (
+            cur_iface.base_iface().ipv6.as_ref(),
+            iface.base_iface().ipv6.as_ref(),
+        ) {
+            (Some(cur_ip_conf), Some(ip_conf)) => {
+                (cur_ip_conf.dhcp || cur_ip_conf.autoconf)
+                    && ip_conf.enabled
+                    && !ip_conf.dhcp
+                    && !ip_conf.autoconf
+            }
+            _ => false,
+        };
+        if !ipv4_dhcp_off && !ipv6_dhcp_off {
+            continue;
+        }
+        let dyn_routes: Vec<RouteEntry> = current_routes
+            .running
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|r| {
+                r.next_hop_iface.as_deref() == Some(iface_name.as_str())
+                    && if r.is_ipv6() {
+                        ipv6_dhcp_off
+                            && (r.protocol == Some(RouteProtocol::Dhcp)
+                                || r.protocol == Some(RouteProtocol::Ra))
+                    } else {
+                        ipv4_dhcp_off && r.protocol == Some(RouteProtocol::Dhcp)
+                    }
+                    && !absent_routes.iter().any(|a| a.is_match(r))
+            })
+            .map(|r| {
+                let mut route = r.clone();
+                route.protocol = None;
+                route
+            })
+            .collect();
+        if dyn_routes.is_empty() {
+            continue;
+        }
+        let routes = iface.base_iface_mut().routes.get_or_insert_with(|| {
+            current_routes
+                .config
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .filter(|r| {
+                    r.next_hop_iface.as_deref() == Some(iface_name.as_str())
+                })
+                .cloned()
+                .collect()
+        });
+        for route in dyn_routes {
+            if !routes.iter().any(|r| route.is_match(r)) {
+                info!(
+                    "Converting dynamic route {:?} to static as DHCP \
+                    is disabled on interface {}",
+                    route, iface_name
+                );
+                routes.push(route);
+            }
+        }
+    }
+}
+
+// When static IP address is moving from one interface to another, we should
+// remove it from the old interface before adding it to new one, to prevent
+// duplicate address.
+// Return the interfaces with migrating IP addresses removed for applying
+// beforehand.
+pub(crate) fn gen_ip_migration_pre_stage(
+    desired: &Interfaces,
+    current: &Interfaces,
+) -> Option<Interfaces> {
+    let mut ret = Interfaces::new();
+    for des_iface in desired.kernel_ifaces.values().filter(|i| i.is_up()) {
+        let des_base = des_iface.base_iface();
+        let des_ipv4_addrs = des_base
+            .ipv4
+            .as_ref()
+            .filter(|i| i.enabled && !i.dhcp)
+            .map(|i| i.addresses.as_slice())
+            .unwrap_or_default();
+        let des_ipv6_addrs = des_base
+            .ipv6
+            .as_ref()
+            .filter(|i| i.enabled && !i.dhcp && !i.autoconf)
+            .map(|i| i.addresses.as_slice())
+            .unwrap_or_default();
+        if des_ipv4_addrs.is_empty() && des_ipv6_addrs.is_empty() {
+            continue;
+        }
+        for cur_iface in current
+            .kernel_ifaces
+            .values()
+            .filter(|i| i.name() != des_iface.name())
+        {
+            // Only move IP if old interface is also mentioned in desire state
+            let old_des_iface =
+                match desired.kernel_ifaces.get(cur_iface.name()) {
+                    Some(i) if i.is_up() => i,
+                    _ => continue,
+                };
+            let old_des_base = old_des_iface.base_iface();
+            let cur_base = cur_iface.base_iface();
+            let ipv4_moving = cur_base
+                .ipv4
+                .as_ref()
+                .filter(|i| i.enabled && !i.dhcp)
+                .map(|i| {
+                    get_moving_addrs(
+                        &i.addresses,
+                        des_ipv4_addrs,
+                        old_des_base.ipv4.as_ref().map(|i| &i.addresses),
+                    )
+                })
+                .unwrap_or_default();
+            let ipv6_moving = cur_base
+                .ipv6
+                .as_ref()
+                .filter(|i| i.enabled && !i.dhcp && !i.autoconf)
+                .map(|i| {
+                    get_moving_addrs(
+                        &i.addresses,
+                        des_ipv6_addrs,
+                        old_des_base.ipv6.as_ref().map(|i| &i.addresses),
+                    )
+                })
+                .unwrap_or_default();
+            if ipv4_moving.is_empty() && ipv6_moving.is_empty() {
+                continue;
+            }
+            info!(
+                "Removing IP addresses {:?} from interface {} before \
+                assigning them to interface {}",
+                [ipv4_moving.as_slice(), ipv6_moving.as_slice()].concat(),
+                cur_iface.name(),
+                des_iface.name()
+            );
+            let mut iface = match ret.kernel_ifaces.get(cur_iface.name()) {
+                Some(i) => i.clone(),
+                None => {
+                    let mut iface = cur_iface.clone_name_type_only();
+                    iface.base_iface_mut().ipv4 = cur_base.ipv4.clone();
+                    iface.base_iface_mut().ipv6 = cur_base.ipv6.clone();
+                    iface
+                }
+            };
+            if let Some(ip_conf) = iface.base_iface_mut().ipv4.as_mut() {
+                ip_conf.addresses.retain(|a| !ipv4_moving.contains(a));
+                if !ip_conf.prop_list.contains(&"addresses") {
+                    ip_conf.prop_list.push("addresses");
+                }
+            }
+            if let Some(ip_conf) = iface.base_iface_mut().ipv6.as_mut() {
+                ip_conf.addresses.retain(|a| !ipv6_moving.contains(a));
+                if !ip_conf.prop_list.contains(&"addresses") {
+                    ip_conf.prop_list.push("addresses");
+                }
+            }
+            ret.push(iface);
+        }
+    }
+    if ret.kernel_ifaces.is_empty() {
+        None
+    } else {
+        Some(ret)
+    }
+}
+
+// Return current addresses found in new interface but not in the desired
+// config of old interface.
+fn get_moving_addrs(
+    cur_addrs: &[InterfaceIpAddr],
+    new_des_addrs: &[InterfaceIpAddr],
+    old_des_addrs: Option<&Vec<InterfaceIpAddr>>,
+) -> Vec<InterfaceIpAddr> {
+    cur_addrs
+        .iter()
+        .filter(|cur_addr| {
+            new_des_addrs.iter().any(|a| is_same_ip(a, cur_addr))
+                && !old_des_addrs
+                    .map(|addrs| addrs.iter().any(|a| is_same_ip(a, cur_addr)))
+                    .unwrap_or_default()
+        })
+        .cloned()
+        .collect()
+}
+
+fn is_same_ip(addr: &InterfaceIpAddr, other: &InterfaceIpAddr) -> bool {
+    match (
+        std::net::IpAddr::from_str(&addr.ip),
+        std::net::IpAddr::from_str(&other.ip),
+    ) {
+        (Ok(ip), Ok(other_ip)) => ip == other_ip,
+        _ => addr.ip == other.ip,
+    }
+}
+
+type VrfIpAddr = (Option<String>, std::net::IpAddr, u8);
+
+// The same static IP address and prefix should not be assigned to multiple
+// interfaces of the same VRF after applied, unless `allow-duplicate` is set
+// for the address in desire state. The desire state should be the output of
+// `Interfaces::gen_state_for_apply()`.
+// Only duplicate address newly added by desire state is treated as error,
+// existing duplicate address in current state is only warned, as
+// `allow-duplicate` is not stored after applied.
+pub(crate) fn check_duplicate_static_addrs(
+    add_ifaces: &Interfaces,
+    chg_ifaces: &Interfaces,
+    del_ifaces: &Interfaces,
+    current: &Interfaces,
+) -> Result<(), NmstateError> {
+    // Key is VRF name, IP address and prefix, value is interface name,
+    // whether duplicate is allowed and whether address is newly added.
+    let mut addr_owners: HashMap<VrfIpAddr, (String, bool, bool)> =
+        HashMap::new();
+
+    let mut iface_names: Vec<&str> = add_ifaces
+        .kernel_ifaces
+        .keys()
+        .chain(chg_ifaces.kernel_ifaces.keys())
+        .chain(current.kernel_ifaces.keys())
+        .map(|n| n.as_str())
+        .filter(|n| !del_ifaces.kernel_ifaces.contains_key(*n))
+        .collect();
+    iface_names.sort_unstable();
+    iface_names.dedup();
+
+    for iface_name in iface_names {
+        let des_iface = add_ifaces
+            .kernel_ifaces
+            .get(iface_name)
+            .or_else(|| chg_ifaces.kernel_ifaces.get(iface_name));
+        let cur_iface = current.kernel_ifaces.get(iface_name);
+        if !des_iface
+            .or(cur_iface)
+            .map(|i| i.is_up())
+            .unwrap_or_default()
+        {
+            continue;
+        }
+        let vrf = match get_ip_vrf(des_iface, cur_iface) {
+            Some(vrf) => vrf,
+            // Port of non-VRF controller
+            None => continue,
+        };
+        let cur_addrs = if cur_iface.map(|i| i.is_up()).unwrap_or_default() {
+            get_merged_static_addrs(None, cur_iface)
+        } else {
+            Vec::new()
+        };
+        for addr in get_merged_static_addrs(des_iface, cur_iface) {
+            let ip = match std::net::IpAddr::from_str(&addr.ip) {
+                Ok(i) => i,
+                Err(_) => continue,
+            };
+            let allow_dup = addr.allow_duplicate == Some(true);
+            let is_new = des_iface.is_some()
+                && !cur_addrs.iter().any(|a| {
+                    a.prefix_length == addr.prefix_length
+                        && is_same_ip(a, &addr)
+                });
+            match addr_owners.get(&(vrf.clone(), ip, addr.prefix_length)) {
+                Some((owner, owner_allow_dup, owner_is_new))
+                    if !allow_dup
+                        && !owner_allow_dup
+                        && !is_new
+                        && !owner_is_new =>
+                {
+                    log::warn!(
+                        "Static IP address {}/{} is already assigned to both \
+                        interface {} and {} in current state",
+                        addr.ip,
+                        addr.prefix_length,
+                        owner,
+                        iface_name
+                    );
+                }
+                Some((owner, owner_allow_dup, _))
+                    if !allow_dup && !owner_allow_dup =>
+                {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Static IP address {}/{} is assigned to both \
+                            interface {} and {}, please set \
+                            `allow-duplicate: true` for the address if this \
+                            is intended",
+                            addr.ip, addr.prefix_length, owner, iface_name
+                        ),
+                    );
+                    log::error!("{}", e);
+                    return Err(e);
+                }
+                Some(_) => (),
+                None => {
+                    addr_owners.insert(
+                        (vrf.clone(), ip, addr.prefix_length),
+                        (iface_name.to_string(), allow_dup, is_new),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Return Some(None) for interface not in VRF, Some(Some(vrf_name)) for VRF
+// port, None for port of other controller which cannot have IP.
+fn get_ip_vrf(
+    des_iface: Option<&Interface>,
+    cur_iface: Option<&Interface>,
+) -> Option<Option<String>> {
+    let base = match (des_iface, cur_iface) {
+        (Some(des), Some(cur)) if des.base_iface().controller.is_none() => {
+            cur.base_iface()
+        }
+        (Some(i), _) | (None, Some(i)) => i.base_iface(),
+        (None, None) => return Some(None),
+    };
+    match base.controller.as_deref() {
+        None | Some("") => Some(None),
+        Some(ctrl) => {
+            if base.controller_type == Some(InterfaceType::Vrf) {
+                Some(Some(ctrl.to_string()))
+            } else if base.can_have_ip() {
+                Some(None)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+// Static addresses after desire config merged into current, link-local
+// addresses are excluded.
+fn get_merged_static_addrs(
+    des_iface: Option<&Interface>,
+    cur_iface: Option<&Interface>,
+) -> Vec<InterfaceIpAddr> {
+    let cur_base = cur_iface.map(|i| i.base_iface());
+    let des_base = des_iface.map(|i| i.base_iface());
+    let mut ret = Vec::new();
+
+    let mut ipv4 = cur_base
+        .and_then(|b| b.ipv4.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    if let Some(des_ipv4) = des_base.and_then(|b| b.ipv4.as_ref()) {
+        ipv4.update(des_ipv4);
+    }
+    if ipv4.enabled && !ipv4.dhcp {
+        ret.extend(
+            ipv4.addresses
+                .into_iter()
+                .filter(|a| !is_ipv4_link_local(&a.ip)),
+        );
+    }
+
+    let mut ipv6 = cur_base
+        .and_then(|b| b.ipv6.as_ref())
+        .cloned()
+        .unwrap_or_default();
+    if let Some(des_ipv6) = des_base.and_then(|b| b.ipv6.as_ref()) {
+        ipv6.update(des_ipv6);
+    }
+    if ipv6.enabled && !ipv6.dhcp && !ipv6.autoconf {
+        ret.extend(
+            ipv6.addresses.into_iter().filter(|a| {
+                !is_ipv6_unicast_link_local(&a.ip, a.prefix_length)
+            }),
+        );
+    }
+    ret
+}
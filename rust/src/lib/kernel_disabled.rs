@@ -0,0 +1,84 @@
+// Placeholder of kernel backend when `kernel-backend` feature is disabled,
+// only the data model, validation and serialization are supported in this
+// case.
+use crate::{ErrorKind, Interfaces, NetworkState, NmstateError, Routes};
+
+fn kernel_disabled_error() -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::DependencyError,
+        "Kernel backend is not enabled at build time, querying or applying \
+        network state is not supported"
+            .to_string(),
+    );
+    log::error!("{}", e);
+    e
+}
+
+pub(crate) fn nispor_retrieve(
+    _running_config_only: bool,
+) -> Result<NetworkState, NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn nispor_apply(
+    _add_net_state: &NetworkState,
+    _chg_net_state: &NetworkState,
+    _del_net_state: &NetworkState,
+    _full_net_state: &NetworkState,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn apply_bridge_sysfs_options(
+    _desired: &Interfaces,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn apply_mac_vtap_tap_owners(
+    _desired: &Interfaces,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn apply_ipv6_addr_flags(
+    _desired: &Interfaces,
+    _current: Option<&Interfaces>,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn check_ipv4_address_conflict(
+    _desired: &Interfaces,
+    _current: &Interfaces,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn announce_addresses(
+    _desired: &Interfaces,
+    _current: &Interfaces,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn send_journald_entry(
+    _fields: &[(&str, String)],
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn send_audit_record(_msg: &str) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
+
+pub(crate) fn uid_to_user_name(_uid: u32) -> Option<String> {
+    None
+}
+
+pub(crate) fn apply_typed_routes(
+    _desired: &Routes,
+    _current: &Routes,
+) -> Result<(), NmstateError> {
+    Err(kernel_disabled_error())
+}
@@ -1,37 +1,109 @@
+#[cfg(feature = "kernel-backend")]
+mod addr_flags;
+mod anonymize;
+mod audit;
+#[cfg(feature = "kernel-backend")]
+mod audit_log;
+#[cfg(feature = "kernel-backend")]
+mod dad;
+#[cfg(feature = "debug-backend")]
+mod debug;
 mod dns;
 mod error;
+mod error_msg;
+mod hostname;
 mod iface;
 mod ifaces;
 mod ip;
+#[cfg(not(feature = "kernel-backend"))]
+mod kernel_disabled;
+mod nat64;
 mod net_state;
+#[cfg(feature = "kernel-backend")]
 mod nispor;
+#[cfg(feature = "nm-backend")]
 mod nm;
+#[cfg(not(feature = "nm-backend"))]
+mod nm_disabled;
+mod ovsdb;
+mod persist;
+mod profile;
 mod route;
 mod route_rule;
+#[cfg(feature = "kernel-backend")]
+mod rtnl;
 mod state;
+#[cfg(feature = "kernel-backend")]
+mod typed_route;
 mod unit_tests;
 
+#[cfg(not(feature = "kernel-backend"))]
+use crate::kernel_disabled as addr_flags;
+#[cfg(not(feature = "kernel-backend"))]
+use crate::kernel_disabled as audit_log;
+#[cfg(not(feature = "kernel-backend"))]
+use crate::kernel_disabled as dad;
+#[cfg(not(feature = "kernel-backend"))]
+use crate::kernel_disabled as nispor;
+#[cfg(not(feature = "kernel-backend"))]
+use crate::kernel_disabled as typed_route;
+#[cfg(not(feature = "nm-backend"))]
+use crate::nm_disabled as nm;
+
+pub use crate::audit::AuditLogBackend;
+#[cfg(feature = "debug-backend")]
+pub use crate::debug::BackendPlan;
 pub use crate::dns::{DnsClientState, DnsState};
 pub use crate::error::{ErrorKind, NmstateError};
+pub use crate::error_msg::ErrorMsg;
+pub use crate::hostname::{HostNameBackend, HostNameState};
 pub use crate::iface::{
-    Interface, InterfaceState, InterfaceType, UnknownInterface,
+    Interface, InterfacePersist, InterfaceState, InterfaceType,
+    UnknownInterface,
 };
 pub use crate::ifaces::{
     BaseInterface, BondAdSelect, BondAllPortsActive, BondArpAllTargets,
     BondArpValidate, BondConfig, BondFailOverMac, BondInterface, BondLacpRate,
-    BondMode, BondOptions, BondPrimaryReselect, BondXmitHashPolicy,
-    DummyInterface, EthernetConfig, EthernetDuplex, EthernetInterface,
-    Interfaces, LinuxBridgeConfig, LinuxBridgeInterface,
-    LinuxBridgeMulticastRouterType, LinuxBridgeOptions, LinuxBridgePortConfig,
-    LinuxBridgePortTunkTag, LinuxBridgePortVlanConfig, LinuxBridgePortVlanMode,
-    LinuxBridgePortVlanRange, LinuxBridgeStpOptions, MacVlanConfig,
-    MacVlanInterface, MacVlanMode, MacVtapConfig, MacVtapInterface,
-    MacVtapMode, OvsBridgeBondConfig, OvsBridgeBondMode,
-    OvsBridgeBondPortConfig, OvsBridgeConfig, OvsBridgeInterface,
-    OvsBridgeOptions, OvsBridgePortConfig, OvsInterface, SrIovConfig,
-    SrIovVfConfig, VethConfig, VlanConfig, VlanInterface,
+    BondMode, BondOptions, BondPortConfig, BondPrimaryReselect,
+    BondXmitHashPolicy, DummyInterface, EthernetConfig, EthernetDuplex,
+    EthernetInterface, EthtoolCoalesceConfig, EthtoolConfig,
+    EthtoolPauseConfig, EthtoolRingConfig, GeneveConfig, GeneveInterface,
+    GreConfig, GreInterface, HsrConfig, HsrInterface, HsrProtocol,
+    InfiniBandConfig, InfiniBandInterface, InfiniBandMode, Interfaces,
+    IpVlanConfig, IpVlanInterface, IpVlanMode, IpsecInterface, LibreswanConfig,
+    LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeMulticastRouterType,
+    LinuxBridgeOptions, LinuxBridgePortConfig, LinuxBridgePortTunkTag,
+    LinuxBridgePortVlanConfig, LinuxBridgePortVlanMode,
+    LinuxBridgePortVlanRange, LinuxBridgeStpOptions, LinuxBridgeVlanProtocol,
+    LoopbackInterface, MacSecConfig, MacSecInterface, MacSecOffload,
+    MacSecValidate, MacVlanConfig, MacVlanInterface, MacVlanMode,
+    MacVtapConfig, MacVtapInterface, MacVtapMode, OvsBridgeBondConfig,
+    OvsBridgeBondLacpMode, OvsBridgeBondMode, OvsBridgeBondPortConfig,
+    OvsBridgeConfig, OvsBridgeDatapath, OvsBridgeInterface, OvsBridgeOptions,
+    OvsBridgePortConfig, OvsDbIfaceConfig, OvsInterface, PppoeConfig,
+    PppoeInterface, SrIovConfig, SrIovVfConfig, TeamConfig, TeamInterface,
+    TeamLinkWatcherConfig, TeamLinkWatcherType, TeamPortConfig,
+    TeamRunnerConfig, TeamRunnerMode, VethConfig, VlanConfig, VlanInterface,
+    VlanProtocol, VlanQosMapping, VrfConfig, VrfInterface, WifiBand,
+    WifiConfig, WifiEapConfig, WifiInterface, WifiKeyManagement,
+    WifiSecurityConfig, WireGuardConfig, WireGuardInterface,
+    WireGuardPeerConfig, XfrmConfig, XfrmInterface,
+};
+pub use crate::ip::{
+    InterfaceAddrAnnounce, InterfaceIpAddr, InterfaceIpAddrFlag, InterfaceIpv4,
+    InterfaceIpv4Clat, InterfaceIpv4Method, InterfaceIpv6,
 };
-pub use crate::ip::{InterfaceIpAddr, InterfaceIpv4, InterfaceIpv6};
+pub use crate::nat64::Nat64Status;
 pub use crate::net_state::NetworkState;
-pub use crate::route::{RouteEntry, RouteState, Routes};
-pub use crate::route_rule::{RouteRuleEntry, RouteRuleState, RouteRules};
+pub use crate::ovsdb::OvsDbStatus;
+pub use crate::persist::{PersistIssue, PersistIssueReason};
+pub use crate::profile::{
+    BondedUplinkProfile, BridgedUplinkProfile, NetworkProfile,
+    VlanUplinkProfile,
+};
+pub use crate::route::{
+    RouteEntry, RouteProtocol, RouteState, RouteType, Routes,
+};
+pub use crate::route_rule::{
+    RouteRuleEntry, RouteRuleFamily, RouteRuleState, RouteRules,
+};
@@ -0,0 +1,78 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+
+use serde::Serialize;
+
+// RFC 7050: the DNS64 server synthesizes AAAA records of this well-known
+// name from the well-known IPv4 addresses.
+const NAT64_DISCOVERY_NAME: &str = "ipv4only.arpa";
+const NAT64_WELL_KNOWN_IPV4: [Ipv4Addr; 2] =
+    [Ipv4Addr::new(192, 0, 0, 170), Ipv4Addr::new(192, 0, 0, 171)];
+// RFC 6052 section 2.2: the byte 8 (bits 64 to 71) is reserved
+const NAT64_PREFIX_LENS: [u8; 6] = [96, 64, 56, 48, 40, 32];
+
+// The NAT64 prefixes discovered via DNS64 for IPv6-only host which may
+// require CLAT to reach IPv4 only services.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Nat64Status {
+    pub prefixes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Nat64Status {
+    pub fn retrieve() -> Self {
+        let mut status = Self::default();
+        let addrs = match (NAT64_DISCOVERY_NAME, 0).to_socket_addrs() {
+            Ok(a) => a,
+            Err(e) => {
+                status.error = Some(format!(
+                    "Failed to resolve {}: {}",
+                    NAT64_DISCOVERY_NAME, e
+                ));
+                return status;
+            }
+        };
+        for addr in addrs {
+            if let SocketAddr::V6(addr) = addr {
+                if let Some(prefix) = nat64_prefix_of(addr.ip()) {
+                    if !status.prefixes.contains(&prefix) {
+                        status.prefixes.push(prefix);
+                    }
+                }
+            }
+        }
+        status
+    }
+}
+
+// Return the NAT64 prefix in `<address>/<prefix_length>` format if
+// specified address is synthesized from the well-known IPv4 addresses.
+pub(crate) fn nat64_prefix_of(addr: &Ipv6Addr) -> Option<String> {
+    let octets = addr.octets();
+    for prefix_len in NAT64_PREFIX_LENS {
+        let start = (prefix_len / 8) as usize;
+        let ipv4_octets: Vec<u8> = octets[start..]
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| prefix_len == 96 || start + i != 8)
+            .map(|(_, b)| *b)
+            .take(4)
+            .collect();
+        if ipv4_octets.len() != 4 {
+            continue;
+        }
+        let ipv4 = Ipv4Addr::new(
+            ipv4_octets[0],
+            ipv4_octets[1],
+            ipv4_octets[2],
+            ipv4_octets[3],
+        );
+        if NAT64_WELL_KNOWN_IPV4.contains(&ipv4) {
+            let mut prefix = [0u8; 16];
+            prefix[..start].copy_from_slice(&octets[..start]);
+            return Some(format!("{}/{}", Ipv6Addr::from(prefix), prefix_len));
+        }
+    }
+    None
+}
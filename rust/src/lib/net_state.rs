@@ -1,31 +1,64 @@
 use std::collections::HashMap;
 
 use log::{debug, info, warn};
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{
+    de::{IgnoredAny, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
 
 use crate::{
+    addr_flags::apply_ipv6_addr_flags,
+    audit::{
+        emit_audit_record, gen_audit_summary,
+        gen_iface_type_resolutions_summary, AuditOperation, AuditRecord,
+    },
+    dad::{announce_addresses, check_ipv4_address_conflict},
     dns::{
         get_cur_dns_ifaces, is_dns_changed, purge_dns_config,
         reselect_dns_ifaces,
     },
-    nispor::{nispor_apply, nispor_retrieve},
+    hostname::{
+        apply_hostname, get_hostname_state, DEFAULT_HOSTNAME_BACKENDS,
+        DEFAULT_HOSTNAME_BACKENDS_KERNEL_ONLY,
+    },
+    ifaces::{
+        allocate_vrf_table_ids, validate_veth_type_strict,
+        validate_vrf_table_ids,
+    },
+    ip::{
+        check_duplicate_static_addrs, gen_ip_migration_pre_stage,
+        include_current_dhcp_routes_if_dhcp_on_to_off,
+    },
+    nispor::{
+        apply_bridge_sysfs_options, apply_mac_vtap_tap_owners, nispor_apply,
+        nispor_retrieve,
+    },
     nm::{
         nm_apply, nm_checkpoint_create, nm_checkpoint_destroy,
-        nm_checkpoint_rollback, nm_checkpoint_timeout_extend, nm_gen_conf,
+        nm_checkpoint_get_last, nm_checkpoint_rollback,
+        nm_checkpoint_timeout_extend, nm_gen_conf, nm_persist_check,
         nm_retrieve,
     },
-    DnsState, ErrorKind, Interface, InterfaceType, Interfaces, NmstateError,
-    RouteRules, Routes,
+    persist::get_runtime_only_issues,
+    profile::expand_profiles,
+    typed_route::apply_typed_routes,
+    AuditLogBackend, DnsState, ErrorKind, ErrorMsg, HostNameBackend,
+    HostNameState, Interface, InterfaceType, Interfaces, NmstateError,
+    PersistIssue, RouteRules, Routes,
 };
 
 const VERIFY_RETRY_INTERVAL_MILLISECONDS: u64 = 1000;
 const VERIFY_RETRY_COUNT: usize = 5;
 const VERIFY_RETRY_COUNT_SRIOV: usize = 60;
 const VERIFY_RETRY_COUNT_KERNEL_MODE: usize = 5;
+const VERIFY_RETRY_COUNT_CARRIER: usize = 30;
+const DEFAULT_ROLLBACK_TIMEOUT: u32 = 30;
 
 #[derive(Clone, Debug, Serialize, Default, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct NetworkState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<HostNameState>,
     #[serde(rename = "dns-resolver", default)]
     pub dns: DnsState,
     #[serde(rename = "route-rules", default)]
@@ -47,40 +80,115 @@ pub struct NetworkState {
     include_secrets: bool,
     #[serde(skip)]
     include_status_data: bool,
+    #[serde(skip)]
+    dad_check: bool,
+    #[serde(skip)]
+    running_config_only: bool,
+    #[serde(skip)]
+    no_commit: bool,
+    #[serde(skip)]
+    timeout: Option<u32>,
+    #[serde(skip)]
+    hostname_backends: Option<Vec<HostNameBackend>>,
+    #[serde(skip)]
+    audit_log_backends: Vec<AuditLogBackend>,
+    #[serde(skip)]
+    strict_veth_type: bool,
 }
 
 impl<'de> Deserialize<'de> for NetworkState {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NetworkStateVisitor)
+    }
+}
+
+// Deserialize the top level properties directly without storing whole state
+// into serde_json::Value. Only the interfaces are kept in JSON value as
+// profiles might need to be merged in.
+struct NetworkStateVisitor;
+
+impl<'de> Visitor<'de> for NetworkStateVisitor {
+    type Value = NetworkState;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("network state")
+    }
+
+    // Empty document
+    fn visit_unit<E>(self) -> Result<NetworkState, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(NetworkState::new())
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<NetworkState, M::Error>
+    where
+        M: MapAccess<'de>,
     {
         let mut net_state = NetworkState::new();
-        let v = serde_json::Value::deserialize(deserializer)?;
-        if let Some(ifaces_value) = v.get("interfaces") {
-            net_state.prop_list.push("interfaces");
-            net_state.interfaces = Interfaces::deserialize(ifaces_value)
-                .map_err(serde::de::Error::custom)?;
-        }
-        if let Some(dns_value) = v.get("dns-resolver") {
-            net_state.prop_list.push("dns");
-            net_state.dns = DnsState::deserialize(dns_value)
-                .map_err(serde::de::Error::custom)?;
+        let mut ifaces_value: Option<Vec<serde_json::Value>> = None;
+        let mut profiles_value: Option<serde_json::Value> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "interfaces" => {
+                    ifaces_value = Some(map.next_value()?);
+                }
+                "profiles" => {
+                    profiles_value = Some(map.next_value()?);
+                }
+                "hostname" => {
+                    net_state.prop_list.push("hostname");
+                    net_state.hostname = Some(map.next_value()?);
+                }
+                "dns-resolver" => {
+                    net_state.prop_list.push("dns");
+                    net_state.dns = map.next_value()?;
+                }
+                "routes" => {
+                    net_state.prop_list.push("routes");
+                    net_state.routes = map.next_value()?;
+                }
+                "route-rules" => {
+                    net_state.prop_list.push("rules");
+                    net_state.rules = map.next_value()?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
         }
-        if let Some(route_value) = v.get("routes") {
-            net_state.prop_list.push("routes");
-            net_state.routes = Routes::deserialize(route_value)
-                .map_err(serde::de::Error::custom)?;
+        if let Some(profiles_value) = profiles_value {
+            ifaces_value = Some(
+                expand_profiles(&profiles_value, ifaces_value)
+                    .map_err(serde::de::Error::custom)?,
+            );
         }
-        if let Some(rule_value) = v.get("route-rules") {
-            net_state.prop_list.push("rules");
-            net_state.rules = RouteRules::deserialize(rule_value)
-                .map_err(serde::de::Error::custom)?;
+        if let Some(ifaces_value) = ifaces_value {
+            net_state.prop_list.push("interfaces");
+            for iface_value in ifaces_value {
+                net_state.interfaces.push(
+                    Interface::from_value(iface_value)
+                        .map_err(serde::de::Error::custom)?,
+                );
+            }
         }
         Ok(net_state)
     }
 }
 
 impl NetworkState {
+    // Placeholder of secrets in retrieved state when `include_secrets` not
+    // set. Applying it will preserve the current secret.
+    pub const PASSWORD_HID_BY_NMSTATE: &'static str =
+        "<_password_hid_by_nmstate>";
+
     pub fn set_kernel_only(&mut self, value: bool) -> &mut Self {
         self.kernel_only = value;
         self
@@ -101,10 +209,84 @@ impl NetworkState {
         self
     }
 
+    // Probe newly added static IPv4 addresses before applying, fail with
+    // ErrorKind::IpAddressConflict if any of them is used by other host.
+    pub fn set_dad_check(&mut self, value: bool) -> &mut Self {
+        self.dad_check = value;
+        self
+    }
+
+    // Only retrieve the configuration of network, the running routes, route
+    // rules and DNS will be excluded.
+    pub fn set_running_config_only(&mut self, value: bool) -> &mut Self {
+        self.running_config_only = value;
+        self
+    }
+
+    // When not commit, the checkpoint is kept after applied and will be
+    // rollbacked automatically after timeout unless
+    // NetworkState::checkpoint_commit() is invoked.
+    pub fn set_commit(&mut self, value: bool) -> &mut Self {
+        self.no_commit = !value;
+        self
+    }
+
+    // Timeout in seconds for checkpoint rollback.
+    pub fn set_timeout(&mut self, value: u32) -> &mut Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    // The backends used in order to retrieve and apply hostname, the next
+    // one is used when previous failed. By default, systemd-hostnamed,
+    // NetworkManager, and then files, NetworkManager is skipped in kernel
+    // only mode.
+    pub fn set_hostname_backends(
+        &mut self,
+        backends: &[HostNameBackend],
+    ) -> &mut Self {
+        self.hostname_backends = Some(backends.to_vec());
+        self
+    }
+
+    // Emit audit record via specified backends for each apply and rollback.
+    // Only the backends of first state are used in NetworkState::apply_batch().
+    pub fn set_audit_log_backends(
+        &mut self,
+        backends: &[AuditLogBackend],
+    ) -> &mut Self {
+        self.audit_log_backends = backends.to_vec();
+        self
+    }
+
+    // Distinguish `veth` from `ethernet` when matching desired interfaces
+    // against current ones. By default, they are treated as the same type
+    // as NetworkManager reports veth as ethernet.
+    pub fn set_strict_veth_type(&mut self, value: bool) -> &mut Self {
+        self.strict_veth_type = value;
+        self
+    }
+
     pub fn new() -> Self {
         Default::default()
     }
 
+    // ID of this desired network state stored in the NetworkManager profiles
+    // generated from it. The JSON value has sorted keys, hence stable.
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn gen_state_id(&self) -> Result<String, NmstateError> {
+        let content = serde_json::to_value(self)?.to_string();
+        Ok(format!("{:016x}", fnv1a_hash(content.as_bytes())))
+    }
+
+    fn get_hostname_backends(&self) -> &[HostNameBackend] {
+        match self.hostname_backends.as_deref() {
+            Some(backends) => backends,
+            None if self.kernel_only => &DEFAULT_HOSTNAME_BACKENDS_KERNEL_ONLY,
+            None => &DEFAULT_HOSTNAME_BACKENDS,
+        }
+    }
+
     // We provide this instead asking use to do serde_json::from_str(), so that
     // we could provide better error NmstateError instead of serde_json one.
     pub fn new_from_json(net_state_json: &str) -> Result<Self, NmstateError> {
@@ -122,7 +304,7 @@ impl NetworkState {
     }
 
     pub fn retrieve(&mut self) -> Result<&mut Self, NmstateError> {
-        let state = nispor_retrieve()?;
+        let state = nispor_retrieve(self.running_config_only)?;
         if state.prop_list.contains(&"interfaces") {
             self.interfaces = state.interfaces;
         }
@@ -133,26 +315,337 @@ impl NetworkState {
             self.rules = state.rules;
         }
         if !self.kernel_only {
-            let nm_state = nm_retrieve()?;
+            let nm_state =
+                nm_retrieve(self.running_config_only, self.include_secrets)?;
             // TODO: Priority handling
             self.update_state(&nm_state);
         }
+        if !self.include_secrets {
+            self.interfaces.hide_secrets();
+        }
+        self.hostname = Some(get_hostname_state(
+            self.get_hostname_backends(),
+            self.running_config_only,
+        ));
+        self.prop_list.push("hostname");
         Ok(self)
     }
 
     pub fn apply(&self) -> Result<(), NmstateError> {
-        let mut desire_state_to_verify = self.clone();
-        let mut desire_state_to_apply = self.clone();
+        Self::apply_batch(std::slice::from_ref(self))
+    }
+
+    // Apply states in sequence under a single checkpoint. Each state is
+    // verified after been applied, any failure will rollback all of them.
+    pub fn apply_batch(net_states: &[Self]) -> Result<(), NmstateError> {
+        let first_state = match net_states.first() {
+            Some(s) => s,
+            None => {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    "No network state to apply".to_string(),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        };
+        if net_states
+            .iter()
+            .any(|s| s.kernel_only != first_state.kernel_only)
+        {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Cannot mix kernel only network state with others in \
+                single batch"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if first_state.kernel_only && first_state.no_commit {
+            let e = NmstateError::new(
+                ErrorKind::NotImplementedError,
+                "Applying without commit is not supported in kernel only \
+                mode"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        // The first state is validated before creating checkpoint.
+        let first_plan = first_state.gen_apply_plan()?;
+
+        let audit_log_backends = first_state.audit_log_backends.as_slice();
+        let mut audit_record = AuditRecord::new(AuditOperation::Apply);
+        if !audit_log_backends.is_empty() {
+            audit_record.set_state_hash(net_states);
+        }
+        audit_record.summaries.push(first_plan.gen_audit_summary());
+
+        if !first_state.kernel_only {
+            let timeout =
+                first_state.timeout.unwrap_or(DEFAULT_ROLLBACK_TIMEOUT);
+            let checkpoint = nm_checkpoint_create(timeout)?;
+            info!("Created checkpoint {}", &checkpoint);
+            audit_record.checkpoint = Some(checkpoint.clone());
+
+            let mut applied = false;
+            let result =
+                with_nm_checkpoint(&checkpoint, first_state.no_commit, || {
+                    first_state.apply_plan(&first_plan, Some(&checkpoint))?;
+                    let mut plans = Vec::new();
+                    for net_state in &net_states[1..] {
+                        let plan = net_state.gen_apply_plan()?;
+                        audit_record.summaries.push(plan.gen_audit_summary());
+                        net_state.apply_plan(&plan, Some(&checkpoint))?;
+                        plans.push(plan);
+                    }
+                    Self::verify_batch(net_states, &first_plan, &plans)?;
+                    if first_state.no_commit {
+                        // Restart the rollback countdown after all applied
+                        nm_checkpoint_timeout_extend(&checkpoint, timeout)?;
+                    }
+                    applied = true;
+                    Ok(())
+                });
+            audit_record.set_result(&result);
+            emit_audit_record(audit_log_backends, &audit_record);
+            if result.is_err() && !applied {
+                audit_record.operation = AuditOperation::Rollback;
+                emit_audit_record(audit_log_backends, &audit_record);
+            }
+            result
+        } else {
+            // TODO: Need checkpoint for kernel only mode
+            let result = (|| {
+                first_state.apply_plan(&first_plan, None)?;
+                let mut plans = Vec::new();
+                for net_state in &net_states[1..] {
+                    let plan = net_state.gen_apply_plan()?;
+                    audit_record.summaries.push(plan.gen_audit_summary());
+                    net_state.apply_plan(&plan, None)?;
+                    plans.push(plan);
+                }
+                Self::verify_batch(net_states, &first_plan, &plans)
+            })();
+            audit_record.set_result(&result);
+            emit_audit_record(audit_log_backends, &audit_record);
+            result
+        }
+    }
+
+    // Verify the combined desired state of batch against the final current
+    // state, so later state reverting the changes of earlier ones is noticed.
+    fn verify_batch(
+        net_states: &[Self],
+        first_plan: &ApplyPlan,
+        plans: &[ApplyPlan],
+    ) -> Result<(), NmstateError> {
+        let last_plan = match plans.last() {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        let desire_states: Vec<&Self> = net_states
+            .iter()
+            .zip(std::iter::once(first_plan).chain(plans.iter()))
+            .filter(|(net_state, _)| !net_state.no_verify)
+            .map(|(_, plan)| &plan.desire_state_to_verify)
+            .collect();
+        let desire_state = match Self::merge_batch_states(&desire_states) {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+        info!(
+            "Verifying combined state of {} batch states",
+            plans.len() + 1
+        );
+        with_retry(
+            VERIFY_RETRY_INTERVAL_MILLISECONDS,
+            last_plan.retry_count,
+            || {
+                let mut new_cur_net_state = last_plan.cur_net_state.clone();
+                new_cur_net_state.retrieve()?;
+                desire_state.verify(&new_cur_net_state)
+            },
+        )
+    }
+
+    // Interface defined in later state replaces the one of earlier state,
+    // other sections are taken from the last state defining them.
+    pub(crate) fn merge_batch_states(states: &[&Self]) -> Option<Self> {
+        let (first, others) = states.split_first()?;
+        let mut merged = (*first).clone();
+        for state in others {
+            if state.prop_list.contains(&"interfaces") {
+                for iface in state.interfaces.to_vec() {
+                    merged.interfaces.push(iface.clone());
+                }
+            }
+            if state.prop_list.contains(&"hostname") {
+                merged.hostname = state.hostname.clone();
+            }
+            if state.prop_list.contains(&"dns") {
+                merged.dns = state.dns.clone();
+            }
+            if state.prop_list.contains(&"routes") {
+                merged.routes = state.routes.clone();
+            }
+            if state.prop_list.contains(&"rules") {
+                merged.rules = state.rules.clone();
+            }
+            for prop in state.prop_list.iter() {
+                if !merged.prop_list.contains(prop) {
+                    merged.prop_list.push(prop);
+                }
+            }
+        }
+        Some(merged)
+    }
+
+    // Commit the checkpoint created by applying with `set_commit(false)`.
+    // Empty checkpoint means the last checkpoint.
+    pub fn checkpoint_commit(checkpoint: &str) -> Result<(), NmstateError> {
+        let checkpoint = if checkpoint.is_empty() {
+            nm_checkpoint_get_last()?
+        } else {
+            checkpoint.to_string()
+        };
+        nm_checkpoint_destroy(&checkpoint)?;
+        info!("Committed checkpoint {}", checkpoint);
+        Ok(())
+    }
+
+    // Rollback the checkpoint created by applying with `set_commit(false)`.
+    // Empty checkpoint means the last checkpoint.
+    pub fn checkpoint_rollback(checkpoint: &str) -> Result<(), NmstateError> {
+        Self::checkpoint_rollback_with_audit(checkpoint, &[])
+    }
+
+    // Same as NetworkState::checkpoint_rollback() with audit record emitted
+    // via specified backends.
+    pub fn checkpoint_rollback_with_audit(
+        checkpoint: &str,
+        audit_log_backends: &[AuditLogBackend],
+    ) -> Result<(), NmstateError> {
+        let checkpoint = if checkpoint.is_empty() {
+            nm_checkpoint_get_last()?
+        } else {
+            checkpoint.to_string()
+        };
+        let result = nm_checkpoint_rollback(&checkpoint);
+        if result.is_ok() {
+            info!("Rollbacked to checkpoint {}", checkpoint);
+        }
+        if !audit_log_backends.is_empty() {
+            let mut audit_record = AuditRecord::new(AuditOperation::Rollback);
+            audit_record.checkpoint = Some(checkpoint);
+            audit_record.set_result(&result);
+            emit_audit_record(audit_log_backends, &audit_record);
+        }
+        result
+    }
+
+    // Return three NetworkState which will be applied without touching
+    // system:
+    //  * State for addition.
+    //  * State for change.
+    //  * State for deletion.
+    pub fn gen_plan(&self) -> Result<(Self, Self, Self), NmstateError> {
+        let plan = self.gen_apply_plan()?;
+        Ok((plan.add_net_state, plan.chg_net_state, plan.del_net_state))
+    }
+
+    // Same as NetworkState::gen_plan() but using specified current state
+    // instead of querying the system, hence also works without any backend.
+    pub fn gen_plan_with_current(
+        &self,
+        current: &Self,
+    ) -> Result<(Self, Self, Self), NmstateError> {
+        let plan = self.gen_apply_plan_with_current(current.clone())?;
+        Ok((plan.add_net_state, plan.chg_net_state, plan.del_net_state))
+    }
+
+    fn gen_apply_plan(&self) -> Result<ApplyPlan, NmstateError> {
         let mut cur_net_state = NetworkState::new();
         cur_net_state.set_kernel_only(self.kernel_only);
+        cur_net_state.hostname_backends = self.hostname_backends.clone();
         cur_net_state.retrieve()?;
+        self.gen_apply_plan_with_current(cur_net_state)
+    }
 
-        desire_state_to_verify
+    fn gen_apply_plan_with_current(
+        &self,
+        cur_net_state: Self,
+    ) -> Result<ApplyPlan, NmstateError> {
+        let mut desire_state_to_verify = self.clone();
+        let mut desire_state_to_apply = self.clone();
+
+        let iface_type_resolutions = desire_state_to_verify
             .interfaces
             .resolve_unknown_ifaces(&cur_net_state.interfaces)?;
+        desire_state_to_verify.interfaces.validate_backend_support(
+            &cur_net_state.interfaces,
+            self.kernel_only,
+        )?;
+        if self.strict_veth_type {
+            validate_veth_type_strict(
+                &desire_state_to_verify.interfaces,
+                &cur_net_state.interfaces,
+            )?;
+        }
+        // Silently picking the type from current state might modify the
+        // wrong device, hence let user know.
+        for (iface_name, iface_type) in &iface_type_resolutions {
+            warn!(
+                "Interface {} has no type defined, resolved as {} from \
+                current state",
+                iface_name, iface_type
+            );
+        }
         desire_state_to_apply
             .interfaces
             .resolve_unknown_ifaces(&cur_net_state.interfaces)?;
+        desire_state_to_verify
+            .interfaces
+            .resolve_ports_add_remove(&cur_net_state.interfaces)?;
+        desire_state_to_apply
+            .interfaces
+            .resolve_ports_add_remove(&cur_net_state.interfaces)?;
+        desire_state_to_verify
+            .interfaces
+            .resolve_ovsdb_templates(&cur_net_state.interfaces)?;
+        desire_state_to_apply
+            .interfaces
+            .resolve_ovsdb_templates(&cur_net_state.interfaces)?;
+        desire_state_to_verify
+            .interfaces
+            .resolve_ethtool_ring_max(&cur_net_state.interfaces)?;
+        desire_state_to_apply
+            .interfaces
+            .resolve_ethtool_ring_max(&cur_net_state.interfaces)?;
+        desire_state_to_verify.resolve_vrf_auto_table_ids(&cur_net_state);
+        desire_state_to_apply.resolve_vrf_auto_table_ids(&cur_net_state);
+        desire_state_to_verify.resolve_vrf_table_names(&cur_net_state)?;
+        desire_state_to_apply.resolve_vrf_table_names(&cur_net_state)?;
+        desire_state_to_verify
+            .routes
+            .assign_gateway_metrics(&cur_net_state.routes);
+        desire_state_to_apply
+            .routes
+            .assign_gateway_metrics(&cur_net_state.routes);
+
+        let pre_stage = gen_ip_migration_pre_stage(
+            &desire_state_to_apply.interfaces,
+            &cur_net_state.interfaces,
+        )
+        .map(|ifaces| {
+            let mut net_state = NetworkState::new();
+            net_state.kernel_only = self.kernel_only;
+            net_state.no_verify = self.no_verify;
+            net_state.prop_list.push("interfaces");
+            net_state.interfaces = ifaces;
+            net_state
+        });
 
         let (add_net_state, chg_net_state, del_net_state) =
             desire_state_to_apply.gen_state_for_apply(&cur_net_state)?;
@@ -161,69 +654,130 @@ impl NetworkState {
         debug!("Changing net state {:?}", &chg_net_state);
         debug!("Deleting net state {:?}", &del_net_state);
 
-        if !self.kernel_only {
-            let retry_count =
-                if desire_state_to_apply.interfaces.has_sriov_enabled() {
-                    VERIFY_RETRY_COUNT_SRIOV
-                } else {
-                    VERIFY_RETRY_COUNT
-                };
+        let retry_count = if self.kernel_only {
+            VERIFY_RETRY_COUNT_KERNEL_MODE
+        } else if desire_state_to_apply.interfaces.has_sriov_enabled() {
+            VERIFY_RETRY_COUNT_SRIOV
+        } else {
+            VERIFY_RETRY_COUNT
+        };
+        // Link negotiation could take a while after interface up
+        let retry_count =
+            if desire_state_to_apply.interfaces.has_carrier_required() {
+                retry_count.max(VERIFY_RETRY_COUNT_CARRIER)
+            } else {
+                retry_count
+            };
 
-            let checkpoint = nm_checkpoint_create()?;
-            info!("Created checkpoint {}", &checkpoint);
+        Ok(ApplyPlan {
+            desire_state_to_verify,
+            cur_net_state,
+            add_net_state,
+            chg_net_state,
+            del_net_state,
+            retry_count,
+            pre_stage,
+            iface_type_resolutions,
+        })
+    }
 
-            with_nm_checkpoint(&checkpoint, || {
-                nm_apply(
-                    &add_net_state,
-                    &chg_net_state,
-                    &del_net_state,
-                    // TODO: Passing full(desire + current) network state
-                    // instead of current,
-                    &cur_net_state,
-                    self,
-                    &checkpoint,
-                )?;
-                nm_checkpoint_timeout_extend(
-                    &checkpoint,
-                    (VERIFY_RETRY_INTERVAL_MILLISECONDS * retry_count as u64
-                        / 1000) as u32,
-                )?;
-                if !self.no_verify {
-                    with_retry(
-                        VERIFY_RETRY_INTERVAL_MILLISECONDS,
-                        retry_count,
-                        || {
-                            let mut new_cur_net_state = cur_net_state.clone();
-                            new_cur_net_state.retrieve()?;
-                            desire_state_to_verify.verify(&new_cur_net_state)
-                        },
-                    )
-                } else {
-                    Ok(())
-                }
-            })
+    fn apply_plan(
+        &self,
+        plan: &ApplyPlan,
+        checkpoint: Option<&str>,
+    ) -> Result<(), NmstateError> {
+        if let Some(pre_stage) = plan.pre_stage.as_ref() {
+            info!("Applying IP migration pre-stage {:?}", pre_stage);
+            pre_stage.apply_plan(&pre_stage.gen_apply_plan()?, checkpoint)?;
+            let mut plan = self.gen_apply_plan()?;
+            plan.pre_stage = None;
+            return self.apply_plan(&plan, checkpoint);
+        }
+        if self.dad_check {
+            check_ipv4_address_conflict(
+                &plan.desire_state_to_verify.interfaces,
+                &plan.cur_net_state.interfaces,
+            )?;
+        }
+        if self.kernel_only {
+            apply_ipv6_addr_flags(
+                &plan.desire_state_to_verify.interfaces,
+                Some(&plan.cur_net_state.interfaces),
+            )?;
+        }
+        if let Some(checkpoint) = checkpoint {
+            nm_apply(
+                &plan.add_net_state,
+                &plan.chg_net_state,
+                &plan.del_net_state,
+                // TODO: Passing full(desire + current) network state
+                // instead of current,
+                &plan.cur_net_state,
+                self,
+                checkpoint,
+            )
+            .map_err(|e| {
+                e.with_context(NmstateError::CONTEXT_BACKEND, "NetworkManager")
+            })?;
+            nm_checkpoint_timeout_extend(
+                checkpoint,
+                (VERIFY_RETRY_INTERVAL_MILLISECONDS * plan.retry_count as u64
+                    / 1000) as u32,
+            )?;
         } else {
-            // TODO: Need checkpoint for kernel only mode
             nispor_apply(
-                &add_net_state,
-                &chg_net_state,
-                &del_net_state,
-                &cur_net_state,
+                &plan.add_net_state,
+                &plan.chg_net_state,
+                &plan.del_net_state,
+                &plan.cur_net_state,
+            )
+            .map_err(|e| {
+                e.with_context(NmstateError::CONTEXT_BACKEND, "kernel")
+            })?;
+            apply_typed_routes(
+                &plan.desire_state_to_verify.routes,
+                &plan.cur_net_state.routes,
+            )?;
+            apply_ipv6_addr_flags(
+                &plan.desire_state_to_verify.interfaces,
+                None,
+            )?;
+            apply_bridge_sysfs_options(
+                &plan.desire_state_to_verify.interfaces,
+            )?;
+        }
+        apply_mac_vtap_tap_owners(&plan.desire_state_to_verify.interfaces)?;
+        if let Some(hostname) = plan.chg_net_state.hostname.as_ref() {
+            apply_hostname(hostname, self.get_hostname_backends())?;
+        }
+        if !self.no_verify {
+            with_retry(
+                VERIFY_RETRY_INTERVAL_MILLISECONDS,
+                plan.retry_count,
+                || {
+                    let mut new_cur_net_state = plan.cur_net_state.clone();
+                    new_cur_net_state.retrieve()?;
+                    plan.desire_state_to_verify.verify(&new_cur_net_state)
+                },
+            )?;
+        }
+        if self.kernel_only
+            && plan
+                .desire_state_to_verify
+                .interfaces
+                .kernel_ifaces
+                .values()
+                .any(|i| i.base_iface().address_announce.is_some())
+        {
+            let mut new_cur_net_state = NetworkState::new();
+            new_cur_net_state.set_kernel_only(true);
+            new_cur_net_state.retrieve()?;
+            announce_addresses(
+                &plan.desire_state_to_verify.interfaces,
+                &new_cur_net_state.interfaces,
             )?;
-            if !self.no_verify {
-                with_retry(
-                    VERIFY_RETRY_INTERVAL_MILLISECONDS,
-                    VERIFY_RETRY_COUNT_KERNEL_MODE,
-                    || {
-                        let mut new_cur_net_state = cur_net_state.clone();
-                        new_cur_net_state.retrieve()?;
-                        desire_state_to_verify.verify(&new_cur_net_state)
-                    },
-                )
-            } else {
-                Ok(())
-            }
         }
+        Ok(())
     }
 
     fn update_state(&mut self, other: &Self) {
@@ -235,16 +789,71 @@ impl NetworkState {
         }
     }
 
+    // Use the same plan and NM connections as apply against empty current
+    // state, so the generated keyfiles are identical to what apply saves.
     pub fn gen_conf(
         &self,
     ) -> Result<HashMap<String, Vec<String>>, NmstateError> {
         let mut ret = HashMap::new();
-        let (add_net_state, _, _) = self.gen_state_for_apply(&Self::new())?;
-        ret.insert("NetworkManager".to_string(), nm_gen_conf(&add_net_state)?);
+        self.interfaces.validate_offline()?;
+        let plan = self.gen_apply_plan_with_current(Self::new())?;
+        ret.insert(
+            "NetworkManager".to_string(),
+            nm_gen_conf(&plan.add_net_state, self)?,
+        );
         Ok(ret)
     }
 
-    fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+    // Generate the raw backend objects for desired state without applying.
+    #[cfg(feature = "debug-backend")]
+    pub fn dump_backend_plan(
+        &self,
+    ) -> Result<crate::BackendPlan, NmstateError> {
+        if self.kernel_only {
+            let e = NmstateError::new(
+                ErrorKind::NotImplementedError,
+                "Dumping backend plan is not supported in kernel only mode"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        let plan = self.gen_apply_plan()?;
+        Ok(crate::BackendPlan {
+            nm_connections: crate::nm::nm_gen_backend_plan(
+                &plan.add_net_state,
+                &plan.chg_net_state,
+                &plan.cur_net_state,
+                self,
+            )?,
+        })
+    }
+
+    // Report desired interfaces whose config will be lost after reboot.
+    pub fn persist_check(&self) -> Result<Vec<PersistIssue>, NmstateError> {
+        if self.kernel_only {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "Kernel only mode does not store any config, persist check \
+                is not supported"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        let mut cur_net_state = NetworkState::new();
+        cur_net_state.retrieve()?;
+        let mut ifaces = self.interfaces.clone();
+        ifaces.resolve_unknown_ifaces(&cur_net_state.interfaces)?;
+        let mut issues = nm_persist_check(&ifaces)?;
+        issues.extend(get_runtime_only_issues(&ifaces));
+        Ok(issues)
+    }
+
+    pub(crate) fn verify(&self, current: &Self) -> Result<(), NmstateError> {
+        if let Some(hostname) = self.hostname.as_ref() {
+            hostname.verify(current.hostname.as_ref())?;
+        }
         self.interfaces.verify(&current.interfaces)?;
         self.routes.verify(&current.routes)?;
         self.rules.verify(&current.rules)?;
@@ -262,8 +871,17 @@ impl NetworkState {
         current: &Self,
     ) -> Result<(Self, Self, Self), NmstateError> {
         self.routes.validate()?;
+        if !self.kernel_only {
+            self.routes.validate_host_wide_routes()?;
+        }
+        self.routes.validate_default_gateways(&current.routes)?;
+        self.validate_never_default_routes(current)?;
+        validate_vrf_table_ids(&self.interfaces, &current.interfaces)?;
         self.rules.validate()?;
         self.dns.validate()?;
+        if let Some(hostname) = self.hostname.as_ref() {
+            hostname.validate()?;
+        }
 
         let mut add_net_state = NetworkState::new();
         let mut chg_net_state = NetworkState::new();
@@ -274,15 +892,35 @@ impl NetworkState {
         let (add_ifaces, chg_ifaces, del_ifaces) =
             ifaces.gen_state_for_apply(&current.interfaces)?;
 
+        check_duplicate_static_addrs(
+            &add_ifaces,
+            &chg_ifaces,
+            &del_ifaces,
+            &current.interfaces,
+        )?;
+
         add_net_state.interfaces = add_ifaces;
         chg_net_state.interfaces = chg_ifaces;
         del_net_state.interfaces = del_ifaces;
 
+        if let Some(hostname) = self.hostname.as_ref() {
+            if !hostname.is_matched(current.hostname.as_ref()) {
+                chg_net_state.prop_list.push("hostname");
+                chg_net_state.hostname = Some(hostname.clone());
+            }
+        }
+
         self.include_route_changes(
             &mut add_net_state,
             &mut chg_net_state,
             current,
         );
+        include_current_dhcp_routes_if_dhcp_on_to_off(
+            &mut chg_net_state.interfaces,
+            &current.interfaces,
+            &self.routes,
+            &current.routes,
+        );
 
         self.include_rule_changes(
             &mut add_net_state,
@@ -299,6 +937,118 @@ impl NetworkState {
         Ok((add_net_state, chg_net_state, del_net_state))
     }
 
+    // The route table IDs used by current routes and route rules are not
+    // available for VRF auto allocation.
+    pub(crate) fn resolve_vrf_auto_table_ids(&mut self, current: &Self) {
+        let used_table_ids = current
+            .routes
+            .running
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|r| r.table_id)
+            .chain(
+                current
+                    .rules
+                    .running
+                    .as_deref()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|r| r.table_id),
+            )
+            .collect();
+        allocate_vrf_table_ids(
+            &mut self.interfaces,
+            &current.interfaces,
+            used_table_ids,
+        );
+    }
+
+    // Convert the VRF name referred by `table` of routes and route rules to
+    // route table ID of that VRF from desired or current state.
+    pub(crate) fn resolve_vrf_table_names(
+        &mut self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        let ifaces = &self.interfaces;
+        for route in self.routes.config.as_deref_mut().unwrap_or_default() {
+            if let Some(vrf_name) = route.vrf_name.as_deref() {
+                route.table_id = Some(resolve_vrf_table_id(
+                    vrf_name,
+                    route.table_id,
+                    ifaces,
+                    &current.interfaces,
+                    &format!("route {:?}", route),
+                )?);
+            }
+        }
+        for rule in self.rules.config.as_deref_mut().unwrap_or_default() {
+            if let Some(vrf_name) = rule.vrf_name.as_deref() {
+                rule.table_id = Some(resolve_vrf_table_id(
+                    vrf_name,
+                    rule.table_id,
+                    ifaces,
+                    &current.interfaces,
+                    &format!("route rule {:?}", rule),
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    // Desired default gateway is not allowed on interface with
+    // `auto-gateway: false`.
+    fn validate_never_default_routes(
+        &self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        for route in self
+            .routes
+            .config
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|r| !r.is_absent() && r.is_default_gateway())
+        {
+            let iface_name = match route.next_hop_iface.as_deref() {
+                Some(i) => i,
+                None => continue,
+            };
+            let iface =
+                match self.interfaces.kernel_ifaces.get(iface_name).or_else(
+                    || current.interfaces.kernel_ifaces.get(iface_name),
+                ) {
+                    Some(i) => i,
+                    None => continue,
+                };
+            let base_iface = iface.base_iface();
+            let never_default = if route.is_ipv6() {
+                base_iface
+                    .ipv6
+                    .as_ref()
+                    .map(|i| i.is_auto_gateway_disabled())
+            } else {
+                base_iface
+                    .ipv4
+                    .as_ref()
+                    .map(|i| i.is_auto_gateway_disabled())
+            };
+            if never_default == Some(true) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "Desired default gateway {:?} conflicts with \
+                        auto-gateway: false of interface {}",
+                        route, iface_name
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
     fn include_route_changes(
         &self,
         add_net_state: &mut Self,
@@ -362,9 +1112,9 @@ impl NetworkState {
 
         // Convert table id to interface name
         for (table_id, rules) in changed_rules.drain() {
-            // We does not differentiate the IPv4 and IPv6 route table ID.
-            // The verification process will find out the error.
-            // We did not head any use case been limited by this.
+            // The route table ID is shared by IPv4 and IPv6, each rule has
+            // its IP family detected or expanded, so it will be placed into
+            // the IP setting of matching family.
             let iface_name =
                 self.get_iface_name_for_route_table(current, table_id)?;
             let cur_iface = current
@@ -529,15 +1279,98 @@ impl NetworkState {
     }
 }
 
-fn with_nm_checkpoint<T>(checkpoint: &str, func: T) -> Result<(), NmstateError>
+// The changes required to apply a single network state.
+struct ApplyPlan {
+    desire_state_to_verify: NetworkState,
+    cur_net_state: NetworkState,
+    add_net_state: NetworkState,
+    chg_net_state: NetworkState,
+    del_net_state: NetworkState,
+    retry_count: usize,
+    // Interfaces need to release IP addresses before applying
+    pre_stage: Option<NetworkState>,
+    // Interface name and type resolved from current state
+    iface_type_resolutions: Vec<(String, InterfaceType)>,
+}
+
+impl ApplyPlan {
+    fn gen_audit_summary(&self) -> String {
+        let summary = gen_audit_summary(
+            &self.add_net_state,
+            &self.chg_net_state,
+            &self.del_net_state,
+        );
+        if self.iface_type_resolutions.is_empty() {
+            summary
+        } else {
+            format!(
+                "{}, resolved type [{}]",
+                summary,
+                gen_iface_type_resolutions_summary(
+                    &self.iface_type_resolutions
+                )
+            )
+        }
+    }
+}
+
+fn resolve_vrf_table_id(
+    vrf_name: &str,
+    table_id: Option<u32>,
+    ifaces: &Interfaces,
+    cur_ifaces: &Interfaces,
+    entry_desc: &str,
+) -> Result<u32, NmstateError> {
+    let vrf_table_id = match ifaces
+        .get_vrf_table_id(vrf_name)
+        .or_else(|| cur_ifaces.get_vrf_table_id(vrf_name))
+    {
+        Some(t) => t,
+        None => {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "VRF {} with route table ID defined is not found for {}",
+                    vrf_name, entry_desc
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    };
+    match table_id {
+        Some(t) if t != 0 && t != vrf_table_id => {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "The route table ID {} conflicts with table ID {} of \
+                    VRF {} for {}",
+                    t, vrf_table_id, vrf_name, entry_desc
+                ),
+            );
+            log::error!("{}", e);
+            Err(e)
+        }
+        _ => Ok(vrf_table_id),
+    }
+}
+
+fn with_nm_checkpoint<T>(
+    checkpoint: &str,
+    no_commit: bool,
+    func: T,
+) -> Result<(), NmstateError>
 where
     T: FnOnce() -> Result<(), NmstateError>,
 {
     match func() {
         Ok(()) => {
-            nm_checkpoint_destroy(checkpoint)?;
-
-            info!("Destroyed checkpoint {}", checkpoint);
+            if no_commit {
+                info!("Skipped commit of checkpoint {}", checkpoint);
+            } else {
+                nm_checkpoint_destroy(checkpoint)?;
+                info!("Destroyed checkpoint {}", checkpoint);
+            }
             Ok(())
         }
         Err(e) => {
@@ -550,7 +1383,7 @@ where
     }
 }
 
-fn with_retry<T>(
+pub(crate) fn with_retry<T>(
     interval_ms: u64,
     count: usize,
     func: T,
@@ -562,7 +1395,16 @@ where
     while cur_count < count {
         if let Err(e) = func() {
             if cur_count == count - 1 {
-                return Err(e);
+                return Err(
+                    if e.msg_id().map(ErrorMsg::is_wait_timeout) == Some(true) {
+                        e.with_context(
+                            NmstateError::CONTEXT_VERIFY_TIMEOUT,
+                            &(interval_ms * count as u64).to_string(),
+                        )
+                    } else {
+                        e
+                    },
+                );
             } else {
                 info!("Retrying on verification failure: {}", e);
                 std::thread::sleep(std::time::Duration::from_millis(
@@ -577,3 +1419,13 @@ where
     }
     Ok(())
 }
+
+// 64 bits FNV-1a hash
+#[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+fn fnv1a_hash(data: &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    data.iter().fold(FNV_OFFSET_BASIS, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(FNV_PRIME)
+    })
+}
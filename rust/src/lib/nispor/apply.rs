@@ -2,9 +2,18 @@ use log::warn;
 
 use crate::{
     nispor::{
+        ethtool::warn_ethtool_unsupported,
+        geneve::create_geneve_ifaces,
+        hsr::create_hsr_ifaces,
+        infiniband::apply_infiniband_ifaces,
         ip::{nmstate_ipv4_to_np, nmstate_ipv6_to_np},
+        ipvlan::create_ipvlan_ifaces,
+        loopback::{apply_loopback_mtu, nms_loopback_ip_to_np},
+        macsec::apply_macsec_ifaces,
+        passthrough::apply_passthrough_ifaces,
         veth::nms_veth_conf_to_np,
         vlan::nms_vlan_conf_to_np,
+        xfrm::apply_xfrm_ifaces,
     },
     ErrorKind, Interface, InterfaceType, NetworkState, NmstateError,
 };
@@ -15,9 +24,25 @@ pub(crate) fn nispor_apply(
     del_net_state: &NetworkState,
     _full_net_state: &NetworkState,
 ) -> Result<(), NmstateError> {
+    warn_ethtool_unsupported(&add_net_state.interfaces);
+    warn_ethtool_unsupported(&chg_net_state.interfaces);
     apply_single_state(del_net_state)?;
+    // Nispor cannot create Geneve, HSR, InfiniBand PKEY, IPVLAN, MACsec or
+    // XFRM interface, create them beforehand so that nispor could apply the
+    // IP and state
+    create_geneve_ifaces(&add_net_state.interfaces)?;
+    create_hsr_ifaces(&add_net_state.interfaces)?;
+    apply_infiniband_ifaces(&add_net_state.interfaces)?;
+    create_ipvlan_ifaces(&add_net_state.interfaces)?;
+    apply_macsec_ifaces(&add_net_state.interfaces)?;
+    apply_xfrm_ifaces(&add_net_state.interfaces)?;
     apply_single_state(add_net_state)?;
+    apply_infiniband_ifaces(&chg_net_state.interfaces)?;
+    apply_macsec_ifaces(&chg_net_state.interfaces)?;
+    apply_xfrm_ifaces(&chg_net_state.interfaces)?;
     apply_single_state(chg_net_state)?;
+    apply_loopback_mtu(&chg_net_state.interfaces)?;
+    apply_passthrough_ifaces(&chg_net_state.interfaces)?;
     Ok(())
 }
 
@@ -30,6 +55,10 @@ fn net_state_to_nispor(
         if iface.is_up() {
             let np_iface_type = nmstate_iface_type_to_np(&iface.iface_type());
             if np_iface_type == nispor::IfaceType::Unknown {
+                // Handled by apply_passthrough_ifaces()
+                if iface.base_iface().is_passthrough() {
+                    continue;
+                }
                 warn!(
                     "Unknown interface type {} for interface {}",
                     iface.iface_type(),
@@ -38,7 +67,10 @@ fn net_state_to_nispor(
                 continue;
             }
             np_ifaces.push(nmstate_iface_to_np(iface, np_iface_type)?);
-        } else if iface.is_absent() {
+        } else if iface.is_absent()
+            // Loopback interface cannot be deleted
+            && iface.iface_type() != InterfaceType::Loopback
+        {
             np_ifaces.push(nispor::IfaceConf {
                 name: iface.name().to_string(),
                 iface_type: Some(nmstate_iface_type_to_np(&iface.iface_type())),
@@ -60,8 +92,17 @@ fn nmstate_iface_type_to_np(
         InterfaceType::LinuxBridge => nispor::IfaceType::Bridge,
         InterfaceType::Bond => nispor::IfaceType::Bond,
         InterfaceType::Ethernet => nispor::IfaceType::Ethernet,
+        InterfaceType::Loopback => nispor::IfaceType::Loopback,
         InterfaceType::Veth => nispor::IfaceType::Veth,
         InterfaceType::Vlan => nispor::IfaceType::Vlan,
+        InterfaceType::Geneve => nispor::IfaceType::Other("geneve".to_string()),
+        InterfaceType::Hsr => nispor::IfaceType::Other("hsr".to_string()),
+        InterfaceType::InfiniBand => {
+            nispor::IfaceType::Other("ipoib".to_string())
+        }
+        InterfaceType::IpVlan => nispor::IfaceType::Other("ipvlan".to_string()),
+        InterfaceType::MacSec => nispor::IfaceType::Other("macsec".to_string()),
+        InterfaceType::Xfrm => nispor::IfaceType::Other("xfrm".to_string()),
         _ => nispor::IfaceType::Unknown,
     }
 }
@@ -87,6 +128,10 @@ fn nmstate_iface_to_np(
 
     np_iface.mac_address = base_iface.mac_address.clone();
 
+    if let Interface::Loopback(lo_iface) = nms_iface {
+        nms_loopback_ip_to_np(lo_iface, &mut np_iface);
+    }
+
     if let Interface::Ethernet(eth_iface) = nms_iface {
         np_iface.veth = nms_veth_conf_to_np(eth_iface.veth.as_ref());
     } else if let Interface::Vlan(vlan_iface) = nms_iface {
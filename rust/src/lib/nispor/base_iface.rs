@@ -1,5 +1,8 @@
 use crate::{
-    nispor::ip::{np_ipv4_to_nmstate, np_ipv6_to_nmstate},
+    nispor::{
+        ethtool::np_ethtool_to_nmstate,
+        ip::{np_ipv4_to_nmstate, np_ipv6_to_nmstate},
+    },
     BaseInterface, InterfaceState, InterfaceType,
 };
 
@@ -20,6 +23,20 @@ fn np_iface_type_to_nmstate(
         nispor::IfaceType::Vlan => InterfaceType::Vlan,
         nispor::IfaceType::Vrf => InterfaceType::Vrf,
         nispor::IfaceType::Vxlan => InterfaceType::Vxlan,
+        nispor::IfaceType::Other(s) if s == "wireguard" => {
+            InterfaceType::WireGuard
+        }
+        nispor::IfaceType::Other(s) if s == "geneve" => InterfaceType::Geneve,
+        nispor::IfaceType::Other(s) if s == "team" => InterfaceType::Team,
+        nispor::IfaceType::Other(s) if s == "gre" => InterfaceType::Gre,
+        nispor::IfaceType::Other(s) if s == "gretap" => InterfaceType::GreTap,
+        nispor::IfaceType::Other(s) if s == "hsr" => InterfaceType::Hsr,
+        nispor::IfaceType::Other(s) if s == "Ipoib" => {
+            InterfaceType::InfiniBand
+        }
+        nispor::IfaceType::Other(s) if s == "IpVlan" => InterfaceType::IpVlan,
+        nispor::IfaceType::Other(s) if s == "macsec" => InterfaceType::MacSec,
+        nispor::IfaceType::Other(s) if s == "xfrm" => InterfaceType::Xfrm,
         _ => InterfaceType::Other(format!("{:?}", np_iface_type)),
     }
 }
@@ -43,7 +60,7 @@ impl From<(&nispor::IfaceState, &[nispor::IfaceFlags])> for InterfaceState {
 pub(crate) fn np_iface_to_base_iface(
     np_iface: &nispor::Iface,
 ) -> BaseInterface {
-    let base_iface = BaseInterface {
+    let mut base_iface = BaseInterface {
         name: np_iface.name.to_string(),
         state: (&np_iface.state, np_iface.flags.as_slice()).into(),
         iface_type: np_iface_type_to_nmstate(&np_iface.iface_type),
@@ -52,11 +69,17 @@ pub(crate) fn np_iface_to_base_iface(
         mac_address: Some(np_iface.mac_address.to_uppercase()),
         permanent_mac_address: get_permanent_mac_address(np_iface),
         controller: np_iface.controller.as_ref().map(|c| c.to_string()),
+        controller_type: match np_iface.controller_type {
+            Some(nispor::ControllerType::Vrf) => Some(InterfaceType::Vrf),
+            _ => None,
+        },
         mtu: if np_iface.mtu >= 0 {
             Some(np_iface.mtu as u64)
         } else {
             Some(0u64)
         },
+        ethtool: np_ethtool_to_nmstate(np_iface),
+        carrier: Some(np_iface.flags.contains(&nispor::IfaceFlags::LowerUp)),
         accept_all_mac_addresses: if np_iface
             .flags
             .contains(&nispor::IfaceFlags::Promisc)
@@ -75,9 +98,14 @@ pub(crate) fn np_iface_to_base_iface(
             "controller",
             "mtu",
             "accept_all_mac_addresses",
+            "ethtool",
+            "carrier",
         ],
         ..Default::default()
     };
+    if base_iface.controller_type.is_some() {
+        base_iface.prop_list.push("controller_type");
+    }
     base_iface
 }
 
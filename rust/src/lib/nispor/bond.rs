@@ -1,20 +1,36 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::Ipv6Addr;
+
 use log::warn;
 
 use crate::{
+    rtnl::{parse_nlas, RtnlLinkInfo},
     BaseInterface, BondAdSelect, BondAllPortsActive, BondArpAllTargets,
     BondArpValidate, BondConfig, BondFailOverMac, BondInterface, BondLacpRate,
-    BondMode, BondOptions, BondPrimaryReselect, BondXmitHashPolicy,
+    BondMode, BondOptions, BondPortConfig, BondPrimaryReselect,
+    BondXmitHashPolicy,
 };
 
+// Introduced by kernel 6.0
+const IFLA_BOND_SLAVE_PRIO: u16 = 9;
+// Introduced by kernel 5.19
+const IFLA_BOND_NS_IP6_TARGET: u16 = 31;
+
 pub(crate) fn np_bond_to_nmstate(
     np_iface: &nispor::Iface,
+    link_info: Option<&RtnlLinkInfo>,
     base_iface: BaseInterface,
 ) -> BondInterface {
     let mut bond_iface = BondInterface::new();
     let mut bond_conf = BondConfig::new();
 
     bond_iface.base = base_iface;
-    bond_conf.options = Some(np_bond_options_to_nmstate(np_iface));
+    let mut bond_opts = np_bond_options_to_nmstate(np_iface);
+    if np_iface.bond.is_some() {
+        bond_opts.ns_ip6_target = link_info.and_then(get_ns_ip6_target);
+    }
+    bond_conf.options = Some(bond_opts);
     if let Some(np_bond) = &np_iface.bond {
         bond_conf.port = Some(
             np_bond
@@ -42,6 +58,50 @@ pub(crate) fn np_bond_to_nmstate(
     bond_iface
 }
 
+pub(crate) fn append_bond_port_config(
+    bond_iface: &mut BondInterface,
+    port_np_ifaces: Vec<&nispor::Iface>,
+    link_infos: &HashMap<u32, RtnlLinkInfo>,
+) {
+    let mut port_confs: Vec<BondPortConfig> = Vec::new();
+    for port_np_iface in port_np_ifaces {
+        let mut port_conf = BondPortConfig::new();
+        port_conf.name = port_np_iface.name.to_string();
+        if let Some(np_port_info) = &port_np_iface.bond_subordinate {
+            port_conf.queue_id = Some(np_port_info.queue_id);
+        }
+        if let Some(link_info) = link_infos.get(&port_np_iface.index) {
+            for (nla_type, d) in parse_nlas(&link_info.info_port_data) {
+                if nla_type == IFLA_BOND_SLAVE_PRIO && d.len() == 4 {
+                    port_conf.priority =
+                        Some(i32::from_ne_bytes([d[0], d[1], d[2], d[3]]));
+                }
+            }
+        }
+        port_confs.push(port_conf);
+    }
+
+    if let Some(bond_conf) = bond_iface.bond.as_mut() {
+        bond_conf.ports_config = Some(port_confs);
+    }
+}
+
+fn get_ns_ip6_target(link_info: &RtnlLinkInfo) -> Option<Vec<String>> {
+    for (nla_type, data) in parse_nlas(&link_info.info_data) {
+        if nla_type == IFLA_BOND_NS_IP6_TARGET {
+            return Some(
+                parse_nlas(data)
+                    .into_iter()
+                    .filter_map(|(_, d)| <[u8; 16]>::try_from(d).ok())
+                    .map(|d| Ipv6Addr::from(d).to_string())
+                    .collect(),
+            );
+        }
+    }
+    // Kernel not supporting ns_ip6_target
+    None
+}
+
 fn np_bond_options_to_nmstate(np_iface: &nispor::Iface) -> BondOptions {
     let mut options = BondOptions::default();
     if let Some(ref np_bond) = &np_iface.bond {
@@ -86,7 +146,12 @@ fn np_bond_options_to_nmstate(np_iface: &nispor::Iface) -> BondOptions {
                 }
             });
         options.arp_interval = np_bond.arp_interval;
-        options.arp_ip_target = np_bond.arp_ip_target.clone();
+        options.arp_ip_target = np_bond.arp_ip_target.as_ref().map(|t| {
+            t.split(',')
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_string())
+                .collect()
+        });
         options.arp_validate =
             np_bond.arp_validate.as_ref().and_then(|r| match r {
                 nispor::BondArpValidate::None => Some(BondArpValidate::None),
@@ -137,6 +202,7 @@ fn np_bond_options_to_nmstate(np_iface: &nispor::Iface) -> BondOptions {
         options.num_grat_arp = np_bond.num_grat_arp;
         options.num_unsol_na = np_bond.num_unsol_na;
         options.packets_per_slave = np_bond.packets_per_subordinate;
+        options.peer_notif_delay = np_bond.peer_notif_delay;
         options.primary = np_bond.primary.clone();
         options.primary_reselect =
             np_bond.primary_reselect.as_ref().and_then(|r| match r {
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use log::warn;
+
+use crate::{
+    EthtoolCoalesceConfig, EthtoolConfig, EthtoolPauseConfig,
+    EthtoolRingConfig, Interfaces,
+};
+
+pub(crate) fn np_ethtool_to_nmstate(
+    np_iface: &nispor::Iface,
+) -> Option<EthtoolConfig> {
+    let np_ethtool = np_iface.ethtool.as_ref()?;
+    Some(EthtoolConfig {
+        feature: np_ethtool.features.as_ref().map(|np_features| {
+            let mut features = BTreeMap::new();
+            for (name, value) in np_features
+                .fixed
+                .iter()
+                .chain(np_features.changeable.iter())
+            {
+                features.insert(name.to_string(), *value);
+            }
+            features
+        }),
+        pause: np_ethtool.pause.as_ref().map(|p| EthtoolPauseConfig {
+            rx: Some(p.rx),
+            tx: Some(p.tx),
+            autoneg: Some(p.auto_negotiate),
+        }),
+        coalesce: np_ethtool.coalesce.as_ref().map(np_coalesce_to_nmstate),
+        ring: np_ethtool.ring.as_ref().map(|r| EthtoolRingConfig {
+            rx: r.rx,
+            rx_jumbo: r.rx_jumbo,
+            rx_mini: r.rx_mini,
+            tx: r.tx,
+            rx_max: r.rx_max,
+            rx_jumbo_max: r.rx_jumbo_max,
+            rx_mini_max: r.rx_mini_max,
+            tx_max: r.tx_max,
+        }),
+    })
+}
+
+fn np_coalesce_to_nmstate(
+    np_coalesce: &nispor::EthtoolCoalesceInfo,
+) -> EthtoolCoalesceConfig {
+    EthtoolCoalesceConfig {
+        adaptive_rx: np_coalesce.use_adaptive_rx,
+        adaptive_tx: np_coalesce.use_adaptive_tx,
+        pkt_rate_high: np_coalesce.pkt_rate_high,
+        pkt_rate_low: np_coalesce.pkt_rate_low,
+        rx_frames: np_coalesce.rx_max_frames,
+        rx_frames_high: np_coalesce.rx_max_frames_high,
+        rx_frames_irq: np_coalesce.rx_max_frames_irq,
+        rx_frames_low: np_coalesce.rx_max_frames_low,
+        rx_usecs: np_coalesce.rx_usecs,
+        rx_usecs_high: np_coalesce.rx_usecs_high,
+        rx_usecs_irq: np_coalesce.rx_usecs_irq,
+        rx_usecs_low: np_coalesce.rx_usecs_low,
+        sample_interval: np_coalesce.rate_sample_interval,
+        stats_block_usecs: np_coalesce.stats_block_usecs,
+        tx_frames: np_coalesce.tx_max_frames,
+        tx_frames_high: np_coalesce.tx_max_frames_high,
+        tx_frames_irq: np_coalesce.tx_max_frames_irq,
+        tx_frames_low: np_coalesce.tx_max_frames_low,
+        tx_usecs: np_coalesce.tx_usecs,
+        tx_usecs_high: np_coalesce.tx_usecs_high,
+        tx_usecs_irq: np_coalesce.tx_usecs_irq,
+        tx_usecs_low: np_coalesce.tx_usecs_low,
+    }
+}
+
+// Nispor does not support changing ethtool settings yet, the verification
+// will fail if desired ethtool settings differ from current.
+pub(crate) fn warn_ethtool_unsupported(ifaces: &Interfaces) {
+    for iface in ifaces
+        .iter()
+        .filter(|i| i.is_up() && i.base_iface().ethtool.is_some())
+    {
+        warn!(
+            "Ethtool settings of interface {} is not supported in kernel \
+            mode, ignoring",
+            iface.name()
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, parse_nlas, RtnlLinkInfo, RtnlSocket},
+    BaseInterface, ErrorKind, GeneveConfig, GeneveInterface, Interface,
+    Interfaces, NmstateError,
+};
+
+const IFLA_GENEVE_ID: u16 = 1;
+const IFLA_GENEVE_REMOTE: u16 = 2;
+const IFLA_GENEVE_PORT: u16 = 5;
+const IFLA_GENEVE_REMOTE6: u16 = 7;
+
+pub(crate) fn np_geneve_to_nmstate(
+    link_info: Option<&RtnlLinkInfo>,
+    base_iface: BaseInterface,
+) -> GeneveInterface {
+    let geneve_conf =
+        link_info.map(|link_info| parse_geneve_info_data(&link_info.info_data));
+    GeneveInterface {
+        base: base_iface,
+        geneve: geneve_conf,
+    }
+}
+
+fn parse_geneve_info_data(data: &[u8]) -> GeneveConfig {
+    let mut conf = GeneveConfig::default();
+    for (nla_type, d) in parse_nlas(data) {
+        match nla_type {
+            IFLA_GENEVE_ID if d.len() == 4 => {
+                conf.id = u32::from_ne_bytes([d[0], d[1], d[2], d[3]]);
+            }
+            IFLA_GENEVE_REMOTE if d.len() == 4 => {
+                conf.remote =
+                    Ipv4Addr::from([d[0], d[1], d[2], d[3]]).to_string();
+            }
+            IFLA_GENEVE_REMOTE6 if d.len() == 16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(d);
+                conf.remote = Ipv6Addr::from(octets).to_string();
+            }
+            // Network byte order
+            IFLA_GENEVE_PORT if d.len() == 2 => {
+                conf.destination_port = Some(u16::from_be_bytes([d[0], d[1]]));
+            }
+            _ => (),
+        }
+    }
+    conf
+}
+
+// Kernel does not support changing the VNI or remote of existing Geneve
+// interface, hence only new interfaces are created here.
+pub(crate) fn create_geneve_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for (iface, conf) in ifaces.kernel_ifaces.values().filter_map(|i| {
+        if let Interface::Geneve(iface) = i {
+            if i.is_up() {
+                return iface.geneve.as_ref().map(|c| (iface, c));
+            }
+        }
+        None
+    }) {
+        if get_iface_index(&iface.base.name).is_ok() {
+            continue;
+        }
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            info!(
+                "Creating Geneve interface {} with VNI {} and remote {}",
+                iface.base.name, conf.id, conf.remote
+            );
+            create_geneve_iface(socket, &iface.base.name, conf)?;
+        }
+    }
+    Ok(())
+}
+
+fn create_geneve_iface(
+    socket: &mut RtnlSocket,
+    name: &str,
+    conf: &GeneveConfig,
+) -> Result<(), NmstateError> {
+    let remote = conf.remote.parse::<IpAddr>().map_err(|e| {
+        let e = NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!(
+                "Invalid remote IP address {} of Geneve interface {}: {}",
+                conf.remote, name, e
+            ),
+        );
+        log::error!("{}", e);
+        e
+    })?;
+    socket.new_link(name, "geneve", None, |data| {
+        append_nla(data, IFLA_GENEVE_ID, &conf.id.to_ne_bytes());
+        match remote {
+            IpAddr::V4(ip) => {
+                append_nla(data, IFLA_GENEVE_REMOTE, &ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                append_nla(data, IFLA_GENEVE_REMOTE6, &ip.octets());
+            }
+        }
+        if let Some(port) = conf.destination_port {
+            append_nla(data, IFLA_GENEVE_PORT, &port.to_be_bytes());
+        }
+    })
+}
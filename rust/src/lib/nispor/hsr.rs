@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, parse_nlas, RtnlLinkInfo, RtnlSocket},
+    BaseInterface, HsrConfig, HsrInterface, HsrProtocol, Interface, Interfaces,
+    NmstateError,
+};
+
+const IFLA_HSR_SLAVE1: u16 = 1;
+const IFLA_HSR_SLAVE2: u16 = 2;
+const IFLA_HSR_MULTICAST_SPEC: u16 = 3;
+const IFLA_HSR_SUPERVISION_ADDR: u16 = 4;
+const IFLA_HSR_PROTOCOL: u16 = 7;
+const HSR_PROTOCOL_PRP: u8 = 1;
+
+pub(crate) fn np_hsr_to_nmstate(
+    np_ifaces: &HashMap<String, nispor::Iface>,
+    link_info: Option<&RtnlLinkInfo>,
+    base_iface: BaseInterface,
+) -> HsrInterface {
+    HsrInterface {
+        base: base_iface,
+        hsr: link_info.map(|i| parse_hsr_info_data(&i.info_data, np_ifaces)),
+    }
+}
+
+fn parse_hsr_info_data(
+    data: &[u8],
+    np_ifaces: &HashMap<String, nispor::Iface>,
+) -> HsrConfig {
+    let index_to_name = |d: &[u8]| -> String {
+        if d.len() < 4 {
+            return String::new();
+        }
+        let index = u32::from_ne_bytes([d[0], d[1], d[2], d[3]]);
+        np_ifaces
+            .values()
+            .find(|i| i.index == index)
+            .map(|i| i.name.to_string())
+            .unwrap_or_default()
+    };
+    let mut conf = HsrConfig {
+        protocol: Some(HsrProtocol::Hsr),
+        ..Default::default()
+    };
+    for (nla_type, d) in parse_nlas(data) {
+        match nla_type {
+            IFLA_HSR_SLAVE1 => conf.port1 = index_to_name(d),
+            IFLA_HSR_SLAVE2 => conf.port2 = index_to_name(d),
+            // The multicast spec is the last byte of supervision address
+            IFLA_HSR_SUPERVISION_ADDR if d.len() == 6 => {
+                conf.multicast_spec = Some(d[5]);
+            }
+            IFLA_HSR_PROTOCOL if d.first() == Some(&HSR_PROTOCOL_PRP) => {
+                conf.protocol = Some(HsrProtocol::Prp);
+            }
+            _ => (),
+        }
+    }
+    conf
+}
+
+// The ports of HSR interface should exist before this function invoked.
+pub(crate) fn create_hsr_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for (iface, conf) in ifaces.kernel_ifaces.values().filter_map(|i| {
+        if let Interface::Hsr(iface) = i {
+            if i.is_up() {
+                return iface.hsr.as_ref().map(|c| (iface, c));
+            }
+        }
+        None
+    }) {
+        if get_iface_index(&iface.base.name).is_ok() {
+            continue;
+        }
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            info!(
+                "Creating {} interface {} with ports {} and {}",
+                conf.protocol.unwrap_or_default(),
+                iface.base.name,
+                conf.port1,
+                conf.port2
+            );
+            create_hsr_iface(socket, &iface.base.name, conf)?;
+        }
+    }
+    Ok(())
+}
+
+fn create_hsr_iface(
+    socket: &mut RtnlSocket,
+    name: &str,
+    conf: &HsrConfig,
+) -> Result<(), NmstateError> {
+    let port1_index = get_iface_index(&conf.port1)? as u32;
+    let port2_index = get_iface_index(&conf.port2)? as u32;
+    socket.new_link(name, "hsr", None, |data| {
+        append_nla(data, IFLA_HSR_SLAVE1, &port1_index.to_ne_bytes());
+        append_nla(data, IFLA_HSR_SLAVE2, &port2_index.to_ne_bytes());
+        if let Some(spec) = conf.multicast_spec {
+            append_nla(data, IFLA_HSR_MULTICAST_SPEC, &[spec]);
+        }
+        if conf.protocol == Some(HsrProtocol::Prp) {
+            append_nla(data, IFLA_HSR_PROTOCOL, &[HSR_PROTOCOL_PRP]);
+        }
+    })
+}
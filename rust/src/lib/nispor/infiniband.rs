@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, parse_nlas, RtnlLinkInfo, RtnlSocket},
+    BaseInterface, InfiniBandConfig, InfiniBandInterface, InfiniBandMode,
+    Interface, Interfaces, NmstateError,
+};
+
+const IFLA_IPOIB_PKEY: u16 = 1;
+const IFLA_IPOIB_MODE: u16 = 2;
+const IPOIB_MODE_DATAGRAM: u16 = 0;
+const IPOIB_MODE_CONNECTED: u16 = 1;
+
+pub(crate) fn np_infiniband_to_nmstate(
+    np_iface: &nispor::Iface,
+    np_ifaces: &HashMap<String, nispor::Iface>,
+    link_info: Option<&RtnlLinkInfo>,
+    base_iface: BaseInterface,
+) -> InfiniBandInterface {
+    let ib_conf = link_info.map(|link_info| {
+        let mut conf = InfiniBandConfig::default();
+        let mut pkey = None;
+        for (nla_type, d) in parse_nlas(&link_info.info_data) {
+            if d.len() < 2 {
+                continue;
+            }
+            let v = u16::from_ne_bytes([d[0], d[1]]);
+            match nla_type {
+                IFLA_IPOIB_PKEY => pkey = Some(v),
+                IFLA_IPOIB_MODE => {
+                    conf.mode = match v {
+                        IPOIB_MODE_CONNECTED => InfiniBandMode::Connected,
+                        _ => InfiniBandMode::Datagram,
+                    }
+                }
+                _ => (),
+            }
+        }
+        // Only the PKEY child interface has link to its parent
+        conf.base_iface = link_info
+            .link
+            .filter(|index| *index != np_iface.index)
+            .and_then(|index| np_ifaces.values().find(|i| i.index == index))
+            .map(|i| i.name.to_string());
+        if conf.base_iface.is_some() {
+            conf.pkey = pkey.filter(|p| *p != InfiniBandConfig::PKEY_DEFAULT);
+        }
+        conf
+    });
+    InfiniBandInterface {
+        base: base_iface,
+        infiniband: ib_conf,
+    }
+}
+
+// Create the PKEY child interfaces or change the transport mode of existing
+// ones, the parent interface should exist before this function invoked.
+pub(crate) fn apply_infiniband_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for (iface, conf) in ifaces.kernel_ifaces.values().filter_map(|i| {
+        if let Interface::InfiniBand(iface) = i {
+            if i.is_up() {
+                return iface.infiniband.as_ref().map(|c| (iface, c));
+            }
+        }
+        None
+    }) {
+        let exists = get_iface_index(&iface.base.name).is_ok();
+        let (base_iface, pkey) = match (conf.base_iface.as_deref(), conf.pkey) {
+            (Some(b), Some(p)) => (b, p),
+            _ if exists => ("", InfiniBandConfig::PKEY_DEFAULT),
+            // The IPoIB interface without PKEY is created by driver
+            _ => continue,
+        };
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            let mode = match conf.mode {
+                InfiniBandMode::Datagram => IPOIB_MODE_DATAGRAM,
+                InfiniBandMode::Connected => IPOIB_MODE_CONNECTED,
+            };
+            if exists {
+                info!(
+                    "Changing InfiniBand interface {} to {:?} mode",
+                    iface.base.name, conf.mode
+                );
+                socket.change_link(
+                    &iface.base.name,
+                    "ipoib",
+                    None,
+                    |data| {
+                        append_nla(data, IFLA_IPOIB_MODE, &mode.to_ne_bytes());
+                    },
+                )?;
+            } else {
+                info!(
+                    "Creating InfiniBand interface {} on {} with pkey {:#06x}",
+                    iface.base.name, base_iface, pkey
+                );
+                let base_index = get_iface_index(base_iface)? as u32;
+                socket.new_link(
+                    &iface.base.name,
+                    "ipoib",
+                    Some(base_index),
+                    |data| {
+                        append_nla(data, IFLA_IPOIB_PKEY, &pkey.to_ne_bytes());
+                        append_nla(data, IFLA_IPOIB_MODE, &mode.to_ne_bytes());
+                    },
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -18,6 +18,8 @@ pub(crate) fn np_ipv4_to_nmstate(
             ip.addresses.push(InterfaceIpAddr {
                 ip: np_addr.address.clone(),
                 prefix_length: np_addr.prefix_len,
+                flags: None,
+                allow_duplicate: None,
             });
         }
         Some(ip)
@@ -53,6 +55,8 @@ pub(crate) fn np_ipv6_to_nmstate(
             ip.addresses.push(InterfaceIpAddr {
                 ip: np_addr.address.clone(),
                 prefix_length: np_addr.prefix_len,
+                flags: None,
+                allow_duplicate: None,
             });
         }
         Some(ip)
@@ -75,6 +79,18 @@ pub(crate) fn nmstate_ipv4_to_np(
 ) -> nispor::IpConf {
     let mut np_ip_conf = nispor::IpConf::default();
     if let Some(nms_ipv4) = nms_ipv4 {
+        if nms_ipv4.enabled && nms_ipv4.link_local {
+            log::warn!(
+                "IPv4 link-local is not supported in kernel only mode, \
+                ignoring"
+            );
+        }
+        if nms_ipv4.is_shared() {
+            log::warn!(
+                "IPv4 shared method is not supported in kernel only mode, \
+                only static addresses will be applied"
+            );
+        }
         for nms_addr in &nms_ipv4.addresses {
             np_ip_conf.addresses.push(nispor::IpAddrConf {
                 address: nms_addr.ip.to_string(),
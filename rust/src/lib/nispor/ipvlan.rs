@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, parse_nlas, RtnlLinkInfo, RtnlSocket},
+    BaseInterface, Interface, Interfaces, IpVlanConfig, IpVlanInterface,
+    IpVlanMode, NmstateError,
+};
+
+const IFLA_IPVLAN_MODE: u16 = 1;
+const IFLA_IPVLAN_FLAGS: u16 = 2;
+const IPVLAN_MODE_L2: u16 = 0;
+const IPVLAN_MODE_L3: u16 = 1;
+const IPVLAN_MODE_L3S: u16 = 2;
+const IPVLAN_F_PRIVATE: u16 = 0x01;
+const IPVLAN_F_VEPA: u16 = 0x02;
+
+pub(crate) fn np_ipvlan_to_nmstate(
+    np_ifaces: &HashMap<String, nispor::Iface>,
+    link_info: Option<&RtnlLinkInfo>,
+    base_iface: BaseInterface,
+) -> IpVlanInterface {
+    let ipvlan_conf = link_info.map(|link_info| {
+        let mut conf = IpVlanConfig {
+            base_iface: link_info
+                .link
+                .and_then(|index| np_ifaces.values().find(|i| i.index == index))
+                .map(|i| i.name.to_string())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        let mut flags = 0u16;
+        for (nla_type, d) in parse_nlas(&link_info.info_data) {
+            if d.len() < 2 {
+                continue;
+            }
+            let v = u16::from_ne_bytes([d[0], d[1]]);
+            match nla_type {
+                IFLA_IPVLAN_MODE => {
+                    conf.mode = match v {
+                        IPVLAN_MODE_L2 => IpVlanMode::L2,
+                        IPVLAN_MODE_L3S => IpVlanMode::L3s,
+                        _ => IpVlanMode::L3,
+                    }
+                }
+                IFLA_IPVLAN_FLAGS => flags = v,
+                _ => (),
+            }
+        }
+        conf.private = Some(flags & IPVLAN_F_PRIVATE > 0);
+        conf.vepa = Some(flags & IPVLAN_F_VEPA > 0);
+        conf
+    });
+    IpVlanInterface {
+        base: base_iface,
+        ipvlan: ipvlan_conf,
+    }
+}
+
+// The base interface should exist before this function invoked.
+pub(crate) fn create_ipvlan_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for (iface, conf) in ifaces.kernel_ifaces.values().filter_map(|i| {
+        if let Interface::IpVlan(iface) = i {
+            if i.is_up() {
+                return iface.ipvlan.as_ref().map(|c| (iface, c));
+            }
+        }
+        None
+    }) {
+        if get_iface_index(&iface.base.name).is_ok() {
+            continue;
+        }
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            info!(
+                "Creating IPVLAN interface {} on {}",
+                iface.base.name, conf.base_iface
+            );
+            let base_index = get_iface_index(&conf.base_iface)? as u32;
+            let mode = match conf.mode {
+                IpVlanMode::L2 => IPVLAN_MODE_L2,
+                IpVlanMode::L3 => IPVLAN_MODE_L3,
+                IpVlanMode::L3s => IPVLAN_MODE_L3S,
+            };
+            let mut flags = 0u16;
+            if conf.private == Some(true) {
+                flags |= IPVLAN_F_PRIVATE;
+            }
+            if conf.vepa == Some(true) {
+                flags |= IPVLAN_F_VEPA;
+            }
+            socket.new_link(
+                &iface.base.name,
+                "ipvlan",
+                Some(base_index),
+                |data| {
+                    append_nla(data, IFLA_IPVLAN_MODE, &mode.to_ne_bytes());
+                    append_nla(data, IFLA_IPVLAN_FLAGS, &flags.to_ne_bytes());
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -1,13 +1,16 @@
 use std::convert::TryFrom;
 
-use log::warn;
+use log::{debug, warn};
 
 use crate::{
     nispor::linux_bridge_port_vlan::parse_port_vlan_conf, BaseInterface,
-    LinuxBridgeConfig, LinuxBridgeInterface, LinuxBridgeMulticastRouterType,
-    LinuxBridgeOptions, LinuxBridgePortConfig, LinuxBridgeStpOptions,
+    ErrorKind, Interface, Interfaces, LinuxBridgeConfig, LinuxBridgeInterface,
+    LinuxBridgeMulticastRouterType, LinuxBridgeOptions, LinuxBridgePortConfig,
+    LinuxBridgeStpOptions, LinuxBridgeVlanProtocol, NmstateError,
 };
 
+const SYSFS_NET_PATH: &str = "/sys/class/net";
+
 pub(crate) fn np_bridge_to_nmstate(
     np_iface: &nispor::Iface,
     base_iface: BaseInterface,
@@ -83,12 +86,14 @@ fn np_bridge_options_to_nmstate(
         options.hash_max = np_bridge.multicast_hash_max;
         options.hello_timer = np_bridge.hello_timer;
         options.mac_ageing_time = np_bridge.ageing_time.map(devide_by_user_hz);
+        options.multicast_igmp_version = np_bridge.multicast_igmp_version;
         options.multicast_last_member_count =
             np_bridge.multicast_last_member_count;
         options.multicast_last_member_interval =
             np_bridge.multicast_last_member_interval;
         options.multicast_membership_interval =
             np_bridge.multicast_membership_interval;
+        options.multicast_mld_version = np_bridge.multicast_mld_version;
         options.multicast_querier = np_bridge.multicast_querier;
         options.multicast_querier_interval =
             np_bridge.multicast_querier_interval;
@@ -118,6 +123,19 @@ fn np_bridge_options_to_nmstate(
             np_bridge.multicast_startup_query_count;
         options.multicast_startup_query_interval =
             np_bridge.multicast_startup_query_interval;
+        options.vlan_protocol =
+            np_bridge.vlan_protocol.as_ref().and_then(|p| match p {
+                nispor::BridgeVlanProtocol::Ieee8021Q => {
+                    Some(LinuxBridgeVlanProtocol::Ieee8021Q)
+                }
+                nispor::BridgeVlanProtocol::Ieee8021AD => {
+                    Some(LinuxBridgeVlanProtocol::Ieee8021Ad)
+                }
+                _ => {
+                    warn!("Unsupported linux bridge VLAN protocol {:?}", p);
+                    None
+                }
+            });
     }
     options
 }
@@ -129,8 +147,11 @@ fn np_bridge_options_to_nmstate(
 //   * hello_time
 //   * max_age
 fn devide_by_user_hz(v: u32) -> u32 {
-    let user_hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) } as u32;
-    v / user_hz
+    v / user_hz()
+}
+
+fn user_hz() -> u32 {
+    unsafe { libc::sysconf(libc::_SC_CLK_TCK) as u32 }
 }
 
 fn get_stp_options(np_bridge: &nispor::BridgeInfo) -> LinuxBridgeStpOptions {
@@ -157,3 +178,124 @@ fn get_stp_options(np_bridge: &nispor::BridgeInfo) -> LinuxBridgeStpOptions {
     stp_opt.priority = np_bridge.priority;
     stp_opt
 }
+
+// Nispor does not support changing linux bridge options, hence we write these
+// options to sysfs after applied in kernel mode. The STP timers are in the unit
+// of USER_HZ in sysfs.
+pub(crate) fn apply_bridge_sysfs_options(
+    desired: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in desired.kernel_ifaces.values().filter(|i| i.is_up()) {
+        let br_opts = match iface {
+            Interface::LinuxBridge(br_iface) => {
+                match br_iface
+                    .bridge
+                    .as_ref()
+                    .and_then(|br_conf| br_conf.options.as_ref())
+                {
+                    Some(o) => o,
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+        let mut sysfs_opts = vec![
+            (
+                "multicast_igmp_version",
+                br_opts.multicast_igmp_version.map(|v| v.to_string()),
+            ),
+            (
+                "multicast_mld_version",
+                br_opts.multicast_mld_version.map(|v| v.to_string()),
+            ),
+        ];
+        sysfs_opts.extend(gen_kernel_only_sysfs_opts(br_opts));
+        for (opt_name, value) in sysfs_opts {
+            if let Some(value) = value {
+                write_bridge_sysfs(iface.name(), opt_name, &value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn gen_kernel_only_sysfs_opts(
+    br_opts: &LinuxBridgeOptions,
+) -> Vec<(&'static str, Option<String>)> {
+    let stp_opts = br_opts.stp.as_ref();
+    vec![
+        (
+            "group_fwd_mask",
+            br_opts
+                .get_group_forward_mask()
+                .map(|v| format!("{:#x}", v)),
+        ),
+        (
+            "multicast_snooping",
+            br_opts.multicast_snooping.map(|v| u8::from(v).to_string()),
+        ),
+        (
+            "multicast_querier",
+            br_opts.multicast_querier.map(|v| u8::from(v).to_string()),
+        ),
+        (
+            "multicast_router",
+            br_opts
+                .multicast_router
+                .as_ref()
+                .map(|v| v.to_kernel_value().to_string()),
+        ),
+        (
+            "vlan_protocol",
+            br_opts
+                .vlan_protocol
+                .as_ref()
+                .map(|v| format!("{:#x}", v.ether_type())),
+        ),
+        (
+            "forward_delay",
+            stp_opts
+                .and_then(|o| o.forward_delay)
+                .map(|v| (u32::from(v) * user_hz()).to_string()),
+        ),
+        (
+            "hello_time",
+            stp_opts
+                .and_then(|o| o.hello_time)
+                .map(|v| (u32::from(v) * user_hz()).to_string()),
+        ),
+        (
+            "max_age",
+            stp_opts
+                .and_then(|o| o.max_age)
+                .map(|v| (u32::from(v) * user_hz()).to_string()),
+        ),
+        (
+            "priority",
+            stp_opts.and_then(|o| o.priority).map(|v| v.to_string()),
+        ),
+        (
+            "stp_state",
+            stp_opts
+                .and_then(|o| o.enabled)
+                .map(|v| u8::from(v).to_string()),
+        ),
+    ]
+}
+
+fn write_bridge_sysfs(
+    br_name: &str,
+    opt_name: &str,
+    value: &str,
+) -> Result<(), NmstateError> {
+    let path = format!("{}/{}/bridge/{}", SYSFS_NET_PATH, br_name, opt_name);
+    debug!("Setting {} to {}", path, value);
+    std::fs::write(&path, value).map_err(|e| {
+        let e = NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Failed to write {} to {}: {}", value, path, e),
+        );
+        log::error!("{}", e);
+        e
+    })
+}
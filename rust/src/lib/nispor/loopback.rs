@@ -0,0 +1,64 @@
+use log::info;
+
+use crate::{
+    nispor::ip::{nmstate_ipv4_to_np, nmstate_ipv6_to_np},
+    rtnl::RtnlSocket,
+    Interface, InterfaceIpAddr, Interfaces, LoopbackInterface, NmstateError,
+};
+
+// Nispor removes the addresses not listed, include the loopback addresses
+// assigned by kernel to keep them.
+pub(crate) fn nms_loopback_ip_to_np(
+    iface: &LoopbackInterface,
+    np_iface: &mut nispor::IfaceConf,
+) {
+    let mut ipv4 = iface.base.ipv4.clone();
+    if let Some(ipv4) = ipv4.as_mut() {
+        include_addr(
+            &mut ipv4.addresses,
+            LoopbackInterface::IPV4_ADDR,
+            LoopbackInterface::IPV4_PREFIX_LEN,
+        );
+    }
+    let mut ipv6 = iface.base.ipv6.clone();
+    if let Some(ipv6) = ipv6.as_mut() {
+        include_addr(
+            &mut ipv6.addresses,
+            LoopbackInterface::IPV6_ADDR,
+            LoopbackInterface::IPV6_PREFIX_LEN,
+        );
+    }
+    np_iface.ipv4 = ipv4.as_ref().map(|i| nmstate_ipv4_to_np(Some(i)));
+    np_iface.ipv6 = ipv6.as_ref().map(|i| nmstate_ipv6_to_np(Some(i)));
+}
+
+fn include_addr(addrs: &mut Vec<InterfaceIpAddr>, ip: &str, prefix_len: u8) {
+    if !addrs
+        .iter()
+        .any(|a| a.ip == ip && a.prefix_length == prefix_len)
+    {
+        addrs.push(InterfaceIpAddr {
+            ip: ip.to_string(),
+            prefix_length: prefix_len,
+            flags: None,
+            allow_duplicate: None,
+        });
+    }
+}
+
+// Nispor does not support changing MTU, hence we set it via netlink
+// directly.
+pub(crate) fn apply_loopback_mtu(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in ifaces.kernel_ifaces.values().filter(|i| i.is_up()) {
+        if let Interface::Loopback(iface) = iface {
+            if let Some(mtu) = iface.base.mtu {
+                info!("Changing MTU of {} to {}", iface.base.name, mtu);
+                RtnlSocket::new()?
+                    .set_link_mtu(&iface.base.name, mtu as u32)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,10 +1,17 @@
+use std::os::unix::fs::MetadataExt;
+
+use log::{info, warn};
+
 use crate::{
-    BaseInterface, MacVlanConfig, MacVlanInterface, MacVlanMode, MacVtapConfig,
-    MacVtapInterface, MacVtapMode,
+    dad::get_iface_index, BaseInterface, ErrorKind, Interface, Interfaces,
+    MacVlanConfig, MacVlanInterface, MacVlanMode, MacVtapConfig,
+    MacVtapInterface, MacVtapMode, NmstateError,
 };
 
 const MACVLAN_FLAG_NOPROMISC: u16 = 1;
 const MACVTAP_FLAG_NOPROMISC: u16 = 1;
+const TAP_DEV_WAIT_RETRY_COUNT: usize = 50;
+const TAP_DEV_WAIT_RETRY_INTERVAL_MILLISECONDS: u64 = 100;
 
 pub(crate) fn np_mac_vlan_to_nmstate(
     np_iface: &nispor::Iface,
@@ -46,33 +53,114 @@ pub(crate) fn np_mac_vtap_to_nmstate(
     np_iface: &nispor::Iface,
     base_iface: BaseInterface,
 ) -> MacVtapInterface {
-    let vtap_conf =
-        np_iface
-            .mac_vtap
-            .as_ref()
-            .map(|np_vtap_info| MacVtapConfig {
-                mode: match &np_vtap_info.mode {
-                    nispor::MacVtapMode::Private => MacVtapMode::Private,
-                    nispor::MacVtapMode::Vepa => MacVtapMode::Vepa,
-                    nispor::MacVtapMode::Bridge => MacVtapMode::Bridge,
-                    nispor::MacVtapMode::PassThrough => MacVtapMode::Passthru,
-                    nispor::MacVtapMode::Source => MacVtapMode::Source,
-                    _ => {
-                        log::warn!(
-                            "Unknown supported MacVtap mode {:?}",
-                            np_vtap_info.mode
-                        );
-                        MacVtapMode::Unknown
-                    }
-                },
-                accept_all_mac: Some(
-                    np_vtap_info.flags & MACVTAP_FLAG_NOPROMISC == 0,
-                ),
-                base_iface: np_vtap_info.base_iface.clone(),
-            });
+    let vtap_conf = np_iface
+        .mac_vtap
+        .as_ref()
+        .map(|np_vtap_info| MacVtapConfig {
+            mode: match &np_vtap_info.mode {
+                nispor::MacVtapMode::Private => MacVtapMode::Private,
+                nispor::MacVtapMode::Vepa => MacVtapMode::Vepa,
+                nispor::MacVtapMode::Bridge => MacVtapMode::Bridge,
+                nispor::MacVtapMode::PassThrough => MacVtapMode::Passthru,
+                nispor::MacVtapMode::Source => MacVtapMode::Source,
+                _ => {
+                    log::warn!(
+                        "Unknown supported MacVtap mode {:?}",
+                        np_vtap_info.mode
+                    );
+                    MacVtapMode::Unknown
+                }
+            },
+            accept_all_mac: Some(
+                np_vtap_info.flags & MACVTAP_FLAG_NOPROMISC == 0,
+            ),
+            base_iface: np_vtap_info.base_iface.clone(),
+            ..Default::default()
+        })
+        .map(|mut conf| {
+            if let Ok(m) = std::fs::metadata(tap_dev_path(np_iface.index)) {
+                conf.tap_owner = Some(m.uid());
+                conf.tap_group = Some(m.gid());
+            }
+            conf
+        });
 
     MacVtapInterface {
         base: base_iface,
         mac_vtap: vtap_conf,
     }
 }
+
+fn tap_dev_path(iface_index: u32) -> String {
+    format!("/dev/tap{}", iface_index)
+}
+
+// Neither NetworkManager nor nispor support changing the owner of MacVtap
+// device node, hence we change it after applied. The change is lost once
+// the interface recreated, e.g. after reboot, which is reported by persist
+// check.
+pub(crate) fn apply_mac_vtap_tap_owners(
+    desired: &Interfaces,
+) -> Result<(), NmstateError> {
+    for iface in desired.kernel_ifaces.values().filter(|i| i.is_up()) {
+        let conf = match iface {
+            Interface::MacVtap(MacVtapInterface {
+                mac_vtap: Some(conf),
+                ..
+            }) if conf.tap_owner.is_some() || conf.tap_group.is_some() => conf,
+            _ => continue,
+        };
+        let iface_index = get_iface_index(iface.name())? as u32;
+        let path = tap_dev_path(iface_index);
+        info!(
+            "Changing owner of {} to user {:?} group {:?}",
+            path, conf.tap_owner, conf.tap_group
+        );
+        warn!(
+            "The owner of {} for MacVtap interface {} is not persistent, it \
+            will be reset once the interface recreated",
+            path,
+            iface.name()
+        );
+        if let Err(e) = chown_tap_dev(&path, conf.tap_owner, conf.tap_group) {
+            let e = NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to change owner of {} for MacVtap interface {}: \
+                    {}",
+                    path,
+                    iface.name(),
+                    e
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+// The device node is created by udev after the interface created, retry on
+// ENOENT.
+fn chown_tap_dev(
+    path: &str,
+    owner: Option<u32>,
+    group: Option<u32>,
+) -> Result<(), std::io::Error> {
+    let mut retry = 0;
+    loop {
+        match std::os::unix::fs::chown(path, owner, group) {
+            Err(e)
+                if e.kind() == std::io::ErrorKind::NotFound
+                    && retry < TAP_DEV_WAIT_RETRY_COUNT =>
+            {
+                log::debug!("Waiting {} to be created", path);
+                std::thread::sleep(std::time::Duration::from_millis(
+                    TAP_DEV_WAIT_RETRY_INTERVAL_MILLISECONDS,
+                ));
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
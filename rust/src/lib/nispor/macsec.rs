@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, parse_nlas, RtnlLinkInfo, RtnlSocket},
+    BaseInterface, ErrorKind, Interface, Interfaces, MacSecConfig,
+    MacSecInterface, MacSecOffload, MacSecValidate, NmstateError,
+};
+
+const IFLA_MACSEC_PORT: u16 = 2;
+const IFLA_MACSEC_ENCRYPT: u16 = 7;
+const IFLA_MACSEC_INC_SCI: u16 = 9;
+const IFLA_MACSEC_VALIDATION: u16 = 13;
+const IFLA_MACSEC_OFFLOAD: u16 = 15;
+
+pub(crate) fn np_macsec_to_nmstate(
+    np_ifaces: &HashMap<String, nispor::Iface>,
+    link_info: Option<&RtnlLinkInfo>,
+    base_iface: BaseInterface,
+) -> MacSecInterface {
+    let macsec_conf = link_info.map(|link_info| {
+        let mut conf = MacSecConfig {
+            base_iface: link_info
+                .link
+                .and_then(|index| np_ifaces.values().find(|i| i.index == index))
+                .map(|i| i.name.to_string())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        for (nla_type, d) in parse_nlas(&link_info.info_data) {
+            match (nla_type, d.len()) {
+                // The port is in network byte order
+                (IFLA_MACSEC_PORT, 2..) => {
+                    conf.port = Some(u16::from_be_bytes([d[0], d[1]]).into())
+                }
+                (IFLA_MACSEC_ENCRYPT, 1..) => conf.encrypt = Some(d[0] > 0),
+                (IFLA_MACSEC_INC_SCI, 1..) => conf.send_sci = Some(d[0] > 0),
+                (IFLA_MACSEC_VALIDATION, 1..) => {
+                    conf.validation = MacSecValidate::from_u8(d[0])
+                }
+                (IFLA_MACSEC_OFFLOAD, 1..) => {
+                    conf.offload = MacSecOffload::from_u8(d[0])
+                }
+                _ => (),
+            }
+        }
+        conf
+    });
+    MacSecInterface {
+        base: base_iface,
+        macsec: macsec_conf,
+    }
+}
+
+// Create the MACsec interfaces or change the existing ones, the base
+// interface should exist before this function invoked.
+pub(crate) fn apply_macsec_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for (iface, conf) in ifaces.kernel_ifaces.values().filter_map(|i| {
+        if let Interface::MacSec(iface) = i {
+            if i.is_up() {
+                return iface.macsec.as_ref().map(|c| (iface, c));
+            }
+        }
+        None
+    }) {
+        // The MKA is done by wpa_supplicant which is managed by
+        // NetworkManager
+        if conf.mka_cak.is_some() {
+            let e = NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "MACsec MKA of interface {} is not supported in kernel \
+                    mode",
+                    iface.base.name
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            let fill = |data: &mut Vec<u8>, is_new: bool| {
+                // Kernel refuses to change the port of existing interface
+                if let (Some(port), true) = (conf.port, is_new) {
+                    append_nla(
+                        data,
+                        IFLA_MACSEC_PORT,
+                        &(port as u16).to_be_bytes(),
+                    );
+                }
+                if let Some(v) = conf.encrypt {
+                    append_nla(data, IFLA_MACSEC_ENCRYPT, &[v as u8]);
+                }
+                if let Some(v) = conf.send_sci {
+                    append_nla(data, IFLA_MACSEC_INC_SCI, &[v as u8]);
+                }
+                if let Some(v) = conf.validation {
+                    append_nla(data, IFLA_MACSEC_VALIDATION, &[v.to_u8()]);
+                }
+                if let Some(v) = conf.offload {
+                    append_nla(data, IFLA_MACSEC_OFFLOAD, &[v.to_u8()]);
+                }
+            };
+            if get_iface_index(&iface.base.name).is_ok() {
+                info!("Changing MACsec interface {}", iface.base.name);
+                socket.change_link(&iface.base.name, "macsec", None, |d| {
+                    fill(d, false)
+                })?;
+            } else {
+                info!(
+                    "Creating MACsec interface {} on {}",
+                    iface.base.name, conf.base_iface
+                );
+                let base_index = get_iface_index(&conf.base_iface)? as u32;
+                socket.new_link(
+                    &iface.base.name,
+                    "macsec",
+                    Some(base_index),
+                    |d| fill(d, true),
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -3,15 +3,27 @@ mod base_iface;
 mod bond;
 mod error;
 mod ethernet;
+mod ethtool;
+mod geneve;
+mod hsr;
+mod infiniband;
 mod ip;
+mod ipvlan;
 mod linux_bridge;
 mod linux_bridge_port_vlan;
+mod loopback;
 mod mac_vlan;
+mod macsec;
+mod passthrough;
 mod route;
 mod route_rule;
 mod show;
 mod veth;
 mod vlan;
+mod vrf;
+mod xfrm;
 
 pub(crate) use apply::nispor_apply;
+pub(crate) use linux_bridge::apply_bridge_sysfs_options;
+pub(crate) use mac_vlan::apply_mac_vtap_tap_owners;
 pub(crate) use show::nispor_retrieve;
@@ -0,0 +1,38 @@
+use log::info;
+
+use crate::{rtnl::RtnlSocket, InterfaceState, Interfaces, NmstateError};
+
+// Nispor does not support interface of unknown type, hence we change the
+// MTU and state of passthrough interfaces via netlink directly.
+pub(crate) fn apply_passthrough_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for base_iface in ifaces
+        .iter()
+        .map(|i| i.base_iface())
+        .filter(|b| b.is_passthrough())
+    {
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            if let Some(mtu) = base_iface.mtu {
+                info!("Changing MTU of {} to {}", base_iface.name, mtu);
+                socket.set_link_mtu(&base_iface.name, mtu as u32)?;
+            }
+            let up = match base_iface.state {
+                InterfaceState::Up => true,
+                InterfaceState::Down => false,
+                _ => continue,
+            };
+            info!(
+                "Bringing passthrough interface {} {}",
+                base_iface.name,
+                if up { "up" } else { "down" }
+            );
+            socket.set_link_up(&base_iface.name, up)?;
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use log::warn;
 
-use crate::{RouteEntry, Routes};
+use crate::{RouteEntry, RouteProtocol, RouteType, Routes};
 
 const SUPPORTED_ROUTE_SCOPE: [nispor::RouteScope; 2] =
     [nispor::RouteScope::Universe, nispor::RouteScope::Link];
@@ -21,13 +21,10 @@ pub(crate) fn get_routes(np_routes: &[nispor::Route]) -> Routes {
     for np_route in np_routes.iter().filter(|np_route| {
         SUPPORTED_ROUTE_SCOPE.contains(&np_route.scope)
             && np_route.table != LOCAL_ROUTE_TABLE
-            && np_route.oif.as_ref() != Some(&"lo".to_string())
     }) {
         if is_multipath(np_route) {
-            for flat_np_route in flat_multipath_route(np_route) {
-                running_routes.push(np_route_to_nmstate(&flat_np_route));
-            }
-        } else if np_route.oif.is_some() {
+            running_routes.extend(flat_multipath_route(np_route));
+        } else if np_route.oif.is_some() || is_typed_route(np_route) {
             running_routes.push(np_route_to_nmstate(np_route));
         }
     }
@@ -39,13 +36,10 @@ pub(crate) fn get_routes(np_routes: &[nispor::Route]) -> Routes {
         SUPPORTED_ROUTE_SCOPE.contains(&np_route.scope)
             && SUPPORTED_STATIC_ROUTE_PROTOCOL.contains(&np_route.protocol)
             && np_route.table != LOCAL_ROUTE_TABLE
-            && np_route.oif.as_ref() != Some(&"lo".to_string())
     }) {
         if is_multipath(np_route) {
-            for flat_np_route in flat_multipath_route(np_route) {
-                config_routes.push(np_route_to_nmstate(&flat_np_route));
-            }
-        } else if np_route.oif.is_some() {
+            config_routes.extend(flat_multipath_route(np_route));
+        } else if np_route.oif.is_some() || is_typed_route(np_route) {
             config_routes.push(np_route_to_nmstate(np_route));
         }
     }
@@ -101,10 +95,36 @@ fn np_route_to_nmstate(np_route: &nispor::Route) -> RouteEntry {
     route_entry.next_hop_addr = next_hop_addr;
     route_entry.metric = np_route.metric.map(i64::from);
     route_entry.table_id = Some(np_route.table);
+    route_entry.protocol =
+        Some(np_route_protocol_to_nmstate(&np_route.protocol));
+    route_entry.route_type = np_route_type_to_nmstate(&np_route.route_type);
+    if route_entry.route_type.is_some() {
+        // Typed route has no next hop, IPv6 typed route is using loopback
+        // interface in kernel.
+        route_entry.next_hop_addr = None;
+        if route_entry.next_hop_iface.as_deref() == Some("lo") {
+            route_entry.next_hop_iface = None;
+        }
+    }
 
     route_entry
 }
 
+fn np_route_type_to_nmstate(
+    np_route_type: &nispor::RouteType,
+) -> Option<RouteType> {
+    match np_route_type {
+        nispor::RouteType::BlackHole => Some(RouteType::Blackhole),
+        nispor::RouteType::Prohibit => Some(RouteType::Prohibit),
+        nispor::RouteType::Unreachable => Some(RouteType::Unreachable),
+        _ => None,
+    }
+}
+
+fn is_typed_route(np_route: &nispor::Route) -> bool {
+    np_route_type_to_nmstate(&np_route.route_type).is_some()
+}
+
 fn is_multipath(np_route: &nispor::Route) -> bool {
     np_route
         .multipath
@@ -113,15 +133,32 @@ fn is_multipath(np_route: &nispor::Route) -> bool {
         .unwrap_or_default()
 }
 
-fn flat_multipath_route(np_route: &nispor::Route) -> Vec<nispor::Route> {
-    let mut ret: Vec<nispor::Route> = Vec::new();
+// Each next hop of multipath route is shown as individual route with weight
+fn flat_multipath_route(np_route: &nispor::Route) -> Vec<RouteEntry> {
+    let mut ret: Vec<RouteEntry> = Vec::new();
     if let Some(mpath_routes) = np_route.multipath.as_ref() {
         for mp_route in mpath_routes {
             let mut new_np_route = np_route.clone();
             new_np_route.via = Some(mp_route.via.to_string());
             new_np_route.oif = Some(mp_route.iface.to_string());
-            ret.push(new_np_route);
+            let mut route_entry = np_route_to_nmstate(&new_np_route);
+            route_entry.weight = Some(mp_route.weight);
+            ret.push(route_entry);
         }
     }
     ret
 }
+
+pub(crate) fn np_route_protocol_to_nmstate(
+    np_protocol: &nispor::RouteProtocol,
+) -> RouteProtocol {
+    match np_protocol {
+        nispor::RouteProtocol::Kernel => RouteProtocol::Kernel,
+        nispor::RouteProtocol::Boot => RouteProtocol::Boot,
+        nispor::RouteProtocol::Static => RouteProtocol::Static,
+        nispor::RouteProtocol::Dhcp => RouteProtocol::Dhcp,
+        nispor::RouteProtocol::Ra => RouteProtocol::Ra,
+        nispor::RouteProtocol::IcmpRedirect => RouteProtocol::Redirect,
+        _ => RouteProtocol::Other,
+    }
+}
@@ -1,31 +1,66 @@
-use crate::{RouteRuleEntry, RouteRules};
+use crate::{
+    nispor::route::np_route_protocol_to_nmstate, RouteRuleEntry,
+    RouteRuleFamily, RouteRules,
+};
+
+const RT_TABLE_DEFAULT: u32 = 253;
+const RT_TABLE_MAIN: u32 = 254;
+const RT_TABLE_LOCAL: u32 = 255;
+const LOCAL_RULE_PRIORITY: u32 = 0;
+const MAIN_RULE_PRIORITY: u32 = 32766;
+const DEFAULT_RULE_PRIORITY: u32 = 32767;
 
 pub(crate) fn get_route_rules(np_rules: &[nispor::RouteRule]) -> RouteRules {
     let mut ret = RouteRules::new();
 
-    let mut rules = Vec::new();
+    let mut running_rules = Vec::new();
+    let mut config_rules = Vec::new();
     for np_rule in np_rules {
         let mut rule = RouteRuleEntry::new();
         // We only support route rules with 'table' action
         if np_rule.action != nispor::RuleAction::Table {
             continue;
         }
-        // Neither ip_from or ip_to should be defeind
-        if np_rule.dst.is_none() && np_rule.src.is_none() {
-            continue;
-        }
-        if np_rule.dst.as_deref() == Some("")
-            && np_rule.src.as_deref() == Some("")
-        {
+        rule.family = match np_rule.address_family {
+            nispor::AddressFamily::IPv4 => Some(RouteRuleFamily::Ipv4),
+            nispor::AddressFamily::IPv6 => Some(RouteRuleFamily::Ipv6),
+            _ => continue,
+        };
+        rule.ip_to = np_rule.dst.clone().filter(|d| !d.is_empty());
+        rule.ip_from = np_rule.src.clone().filter(|s| !s.is_empty());
+        let no_ip = rule.ip_to.is_none() && rule.ip_from.is_none();
+        if no_ip && has_unsupported_selector(np_rule) {
             continue;
         }
-        rule.ip_to = np_rule.dst.clone();
-        rule.ip_from = np_rule.src.clone();
         rule.table_id = np_rule.table;
         rule.priority = np_rule.priority.map(i64::from);
-        rules.push(rule);
+        rule.protocol =
+            np_rule.protocol.as_ref().map(np_route_protocol_to_nmstate);
+        if !(no_ip && is_kernel_builtin_rule(np_rule)) {
+            config_rules.push(rule.clone());
+        }
+        running_rules.push(rule);
     }
-    ret.config = Some(rules);
+    ret.running = Some(running_rules);
+    ret.config = Some(config_rules);
 
     ret
 }
+
+// The kernel creates rules to local, main and default route table for each
+// IP family.
+fn is_kernel_builtin_rule(np_rule: &nispor::RouteRule) -> bool {
+    let priority = np_rule.priority.unwrap_or_default();
+    match np_rule.table {
+        Some(RT_TABLE_LOCAL) => priority == LOCAL_RULE_PRIORITY,
+        Some(RT_TABLE_MAIN) => priority == MAIN_RULE_PRIORITY,
+        Some(RT_TABLE_DEFAULT) => priority == DEFAULT_RULE_PRIORITY,
+        _ => false,
+    }
+}
+
+// Rule matching on selectors nmstate does not support should not be shown as
+// a rule matching all traffic.
+fn has_unsupported_selector(np_rule: &nispor::RouteRule) -> bool {
+    np_rule.iif.is_some() || np_rule.oif.is_some() || np_rule.fw_mark.is_some()
+}
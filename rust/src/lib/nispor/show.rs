@@ -1,31 +1,49 @@
+use std::collections::HashMap;
+
 use log::{debug, warn};
 
 use crate::{
     nispor::{
         base_iface::np_iface_to_base_iface,
-        bond::np_bond_to_nmstate,
+        bond::{append_bond_port_config, np_bond_to_nmstate},
         error::np_error_to_nmstate,
         ethernet::np_ethernet_to_nmstate,
+        geneve::np_geneve_to_nmstate,
+        hsr::np_hsr_to_nmstate,
+        infiniband::np_infiniband_to_nmstate,
+        ipvlan::np_ipvlan_to_nmstate,
         linux_bridge::{append_bridge_port_config, np_bridge_to_nmstate},
         mac_vlan::{np_mac_vlan_to_nmstate, np_mac_vtap_to_nmstate},
+        macsec::np_macsec_to_nmstate,
         route::get_routes,
         route_rule::get_route_rules,
         veth::np_veth_to_nmstate,
         vlan::np_vlan_to_nmstate,
+        vrf::np_vrf_to_nmstate,
+        xfrm::np_xfrm_to_nmstate,
     },
-    DummyInterface, Interface, InterfaceType, NetworkState, NmstateError,
-    OvsInterface, UnknownInterface,
+    rtnl::{RtnlLinkInfo, RtnlSocket},
+    DummyInterface, GreInterface, Interface, InterfaceType, LoopbackInterface,
+    NetworkState, NmstateError, OvsInterface, PppoeInterface, TeamInterface,
+    UnknownInterface, WifiInterface, WireGuardInterface,
 };
 
-pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
+// Link layer type of PPP interface
+const ARPHRD_PPP: &str = "512";
+
+pub(crate) fn nispor_retrieve(
+    running_config_only: bool,
+) -> Result<NetworkState, NmstateError> {
     let mut net_state = NetworkState::new();
     net_state.prop_list.push("interfaces");
     net_state.prop_list.push("routes");
     net_state.prop_list.push("rules");
     let np_state = nispor::NetState::retrieve().map_err(np_error_to_nmstate)?;
+    let link_infos = get_link_infos();
 
     for (_, np_iface) in np_state.ifaces.iter() {
         let mut base_iface = np_iface_to_base_iface(np_iface);
+        let link_info = link_infos.get(&np_iface.index);
         // The `ovs-system` is reserved for OVS kernel datapath
         if np_iface.name == "ovs-system" {
             continue;
@@ -48,11 +66,43 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
                 Interface::LinuxBridge(br_iface)
             }
             InterfaceType::Bond => {
-                Interface::Bond(np_bond_to_nmstate(np_iface, base_iface))
+                let mut bond_iface =
+                    np_bond_to_nmstate(np_iface, link_info, base_iface);
+                let mut port_np_ifaces = Vec::new();
+                for port_name in bond_iface.ports().unwrap_or_default() {
+                    if let Some(p) = np_state.ifaces.get(port_name) {
+                        port_np_ifaces.push(p)
+                    }
+                }
+                append_bond_port_config(
+                    &mut bond_iface,
+                    port_np_ifaces,
+                    &link_infos,
+                );
+                Interface::Bond(bond_iface)
+            }
+            // Kernel report WiFi device as ethernet link with wireless
+            // extension exposed in sysfs
+            InterfaceType::Ethernet if is_wifi_iface(&np_iface.name) => {
+                base_iface.iface_type = InterfaceType::Wifi;
+                Interface::Wifi({
+                    let mut iface = WifiInterface::new();
+                    iface.base = base_iface;
+                    iface
+                })
             }
             InterfaceType::Ethernet => Interface::Ethernet(
                 np_ethernet_to_nmstate(np_iface, base_iface),
             ),
+            // Nispor does not know PPP link, nmstate only support PPPoE
+            InterfaceType::Other(_) if is_ppp_iface(&np_iface.name) => {
+                base_iface.iface_type = InterfaceType::Pppoe;
+                Interface::Pppoe({
+                    let mut iface = PppoeInterface::new();
+                    iface.base = base_iface;
+                    iface
+                })
+            }
             InterfaceType::Veth => {
                 base_iface.iface_type = InterfaceType::Ethernet;
                 Interface::Ethernet(np_veth_to_nmstate(np_iface, base_iface))
@@ -60,8 +110,14 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
             InterfaceType::Vlan => {
                 Interface::Vlan(np_vlan_to_nmstate(np_iface, base_iface))
             }
-            InterfaceType::Loopback | InterfaceType::Tun => {
-                // Nmstate has no plan on supporting loopback/tun interface
+            // Loopback interface is included for the routes attached to it
+            InterfaceType::Loopback => Interface::Loopback({
+                let mut iface = LoopbackInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::Tun => {
+                // Nmstate has no plan on supporting tun interface
                 continue;
             }
             InterfaceType::Dummy => Interface::Dummy({
@@ -80,6 +136,60 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
             InterfaceType::MacVtap => {
                 Interface::MacVtap(np_mac_vtap_to_nmstate(np_iface, base_iface))
             }
+            InterfaceType::Vrf => {
+                Interface::Vrf(np_vrf_to_nmstate(np_iface, base_iface))
+            }
+            // Nispor does not provide team information, teamd is required
+            InterfaceType::Team => Interface::Team({
+                let mut iface = TeamInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::Geneve => {
+                Interface::Geneve(np_geneve_to_nmstate(link_info, base_iface))
+            }
+            // Nispor does not provide GRE link information yet, GRE settings
+            // are rejected in kernel mode
+            InterfaceType::Gre | InterfaceType::GreTap => Interface::Gre({
+                let mut iface = GreInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::Hsr => Interface::Hsr(np_hsr_to_nmstate(
+                &np_state.ifaces,
+                link_info,
+                base_iface,
+            )),
+            InterfaceType::InfiniBand => {
+                Interface::InfiniBand(np_infiniband_to_nmstate(
+                    np_iface,
+                    &np_state.ifaces,
+                    link_info,
+                    base_iface,
+                ))
+            }
+            InterfaceType::IpVlan => Interface::IpVlan(np_ipvlan_to_nmstate(
+                &np_state.ifaces,
+                link_info,
+                base_iface,
+            )),
+            InterfaceType::MacSec => Interface::MacSec(np_macsec_to_nmstate(
+                &np_state.ifaces,
+                link_info,
+                base_iface,
+            )),
+            InterfaceType::Xfrm => Interface::Xfrm(np_xfrm_to_nmstate(
+                &np_state.ifaces,
+                link_info,
+                base_iface,
+            )),
+            // Nispor does not provide WireGuard link information yet,
+            // WireGuard settings are rejected in kernel mode
+            InterfaceType::WireGuard => Interface::WireGuard({
+                let mut iface = WireGuardInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
             _ => {
                 warn!(
                     "Got unsupported interface {} type {:?}",
@@ -97,6 +207,35 @@ pub(crate) fn nispor_retrieve() -> Result<NetworkState, NmstateError> {
     }
     net_state.routes = get_routes(&np_state.routes);
     net_state.rules = get_route_rules(&np_state.rules);
+    if running_config_only {
+        net_state.routes.running = None;
+        net_state.rules.running = None;
+    }
 
     Ok(net_state)
 }
+
+// Nispor does not provide the link information of some interface types, hence
+// we dump all links via netlink once per retrieve and leave the parsing of the
+// raw IFLA_INFO_DATA and IFLA_INFO_SLAVE_DATA attributes to the interface type
+// specific functions.
+fn get_link_infos() -> HashMap<u32, RtnlLinkInfo> {
+    match RtnlSocket::new().and_then(|mut s| s.dump_link_info()) {
+        Ok(link_infos) => link_infos,
+        Err(e) => {
+            warn!("Failed to query link information via netlink: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+fn is_wifi_iface(iface_name: &str) -> bool {
+    std::path::Path::new(&format!("/sys/class/net/{}/wireless", iface_name))
+        .exists()
+}
+
+fn is_ppp_iface(iface_name: &str) -> bool {
+    std::fs::read_to_string(format!("/sys/class/net/{}/type", iface_name))
+        .map(|t| t.trim() == ARPHRD_PPP)
+        .unwrap_or_default()
+}
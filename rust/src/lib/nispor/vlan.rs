@@ -1,4 +1,4 @@
-use crate::{BaseInterface, VlanConfig, VlanInterface};
+use crate::{BaseInterface, VlanConfig, VlanInterface, VlanProtocol};
 
 pub(crate) fn np_vlan_to_nmstate(
     np_iface: &nispor::Iface,
@@ -7,6 +7,18 @@ pub(crate) fn np_vlan_to_nmstate(
     let vlan_conf = np_iface.vlan.as_ref().map(|np_vlan_info| VlanConfig {
         id: np_vlan_info.vlan_id,
         base_iface: np_vlan_info.base_iface.clone(),
+        protocol: match np_vlan_info.protocol {
+            nispor::VlanProtocol::Ieee8021Q => Some(VlanProtocol::Ieee8021Q),
+            nispor::VlanProtocol::Ieee8021AD => Some(VlanProtocol::Ieee8021Ad),
+            _ => None,
+        },
+        // Nispor does not expose QoS mapping yet
+        ingress_qos_map: None,
+        egress_qos_map: None,
+        reorder_headers: Some(np_vlan_info.is_reorder_hdr),
+        gvrp: Some(np_vlan_info.is_gvrp),
+        mvrp: Some(np_vlan_info.is_mvrp),
+        loose_binding: Some(np_vlan_info.is_loose_binding),
     });
 
     VlanInterface {
@@ -15,6 +27,9 @@ pub(crate) fn np_vlan_to_nmstate(
     }
 }
 
+// Nispor cannot set VLAN protocol, flags or QoS mapping yet, kernel defaults
+// are used and other values are rejected by
+// `VlanInterface::get_kernel_unsupported_prop()` before applying.
 pub(crate) fn nms_vlan_conf_to_np(
     nms_vlan_conf: Option<&VlanConfig>,
 ) -> Option<nispor::VlanConf> {
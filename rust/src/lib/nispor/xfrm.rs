@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, parse_nlas, RtnlLinkInfo, RtnlSocket},
+    BaseInterface, Interface, Interfaces, NmstateError, XfrmConfig,
+    XfrmInterface,
+};
+
+const IFLA_XFRM_LINK: u16 = 1;
+const IFLA_XFRM_IF_ID: u16 = 2;
+
+pub(crate) fn np_xfrm_to_nmstate(
+    np_ifaces: &HashMap<String, nispor::Iface>,
+    link_info: Option<&RtnlLinkInfo>,
+    base_iface: BaseInterface,
+) -> XfrmInterface {
+    let xfrm_conf = link_info.map(|link_info| {
+        let mut conf = XfrmConfig::default();
+        for (nla_type, d) in parse_nlas(&link_info.info_data) {
+            if d.len() < 4 {
+                continue;
+            }
+            let v = u32::from_ne_bytes([d[0], d[1], d[2], d[3]]);
+            match nla_type {
+                IFLA_XFRM_LINK => {
+                    conf.base_iface = np_ifaces
+                        .values()
+                        .find(|i| v != 0 && i.index == v)
+                        .map(|i| i.name.to_string())
+                }
+                IFLA_XFRM_IF_ID => conf.if_id = v,
+                _ => (),
+            }
+        }
+        conf
+    });
+    XfrmInterface {
+        base: base_iface,
+        xfrm: xfrm_conf,
+    }
+}
+
+// Create the XFRM interfaces or change the existing ones, the base interface
+// should exist before this function invoked.
+pub(crate) fn apply_xfrm_ifaces(
+    ifaces: &Interfaces,
+) -> Result<(), NmstateError> {
+    let mut socket: Option<RtnlSocket> = None;
+    for (iface, conf) in ifaces.kernel_ifaces.values().filter_map(|i| {
+        if let Interface::Xfrm(iface) = i {
+            if i.is_up() {
+                return iface.xfrm.as_ref().map(|c| (iface, c));
+            }
+        }
+        None
+    }) {
+        if socket.is_none() {
+            socket = Some(RtnlSocket::new()?);
+        }
+        if let Some(socket) = socket.as_mut() {
+            let base_index = match conf.base_iface.as_deref() {
+                Some(base_iface) => get_iface_index(base_iface)? as u32,
+                None => 0,
+            };
+            let fill = |data: &mut Vec<u8>| {
+                append_nla(data, IFLA_XFRM_LINK, &base_index.to_ne_bytes());
+                append_nla(data, IFLA_XFRM_IF_ID, &conf.if_id.to_ne_bytes());
+            };
+            if get_iface_index(&iface.base.name).is_ok() {
+                info!(
+                    "Changing XFRM interface {} with if_id {}",
+                    iface.base.name, conf.if_id
+                );
+                socket.change_link(&iface.base.name, "xfrm", None, fill)?;
+            } else {
+                info!(
+                    "Creating XFRM interface {} with if_id {}",
+                    iface.base.name, conf.if_id
+                );
+                socket.new_link(&iface.base.name, "xfrm", None, fill)?;
+            }
+        }
+    }
+    Ok(())
+}
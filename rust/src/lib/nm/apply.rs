@@ -1,22 +1,34 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use log::info;
 use nm_dbus::{NmApi, NmConnection, NmDeviceState};
 
 use crate::{
     nm::connection::{
-        create_index_for_nm_conns_by_name_type, iface_to_nm_connections,
-        iface_type_to_nm, NM_SETTING_OVS_PORT_SETTING_NAME,
+        create_index_for_nm_conns_by_name_type, gen_nm_user_setting,
+        iface_to_nm_connections, iface_type_to_nm, nm_conn_to_keyfile,
+        NM_SETTING_OVS_PORT_SETTING_NAME,
     },
     nm::device::create_index_for_nm_devs,
     nm::error::nm_error_to_nmstate,
+    nm::ip::dnsmasq_is_available,
+    nm::ipsec::{is_libreswan_vpn, nm_ipsec_secrets_merge},
+    nm::macsec::nm_macsec_secrets_merge,
+    nm::ovs::ovsdb_is_running,
+    nm::pppoe::nm_pppoe_secrets_merge,
     nm::profile::{
         activate_nm_profiles, deactivate_nm_profiles, delete_exist_profiles,
-        get_exist_profile, save_nm_profiles, use_uuid_for_controller_reference,
+        get_exist_profile, save_nm_profiles, update_exist_profiles,
+        use_uuid_for_controller_reference,
     },
     nm::route::is_route_removed,
-    Interface, InterfaceType, NetworkState, NmstateError, OvsBridgeInterface,
-    RouteEntry,
+    nm::version::{
+        nm_supports_clat, nm_supports_hsr, nm_supports_ipvlan,
+        nm_supports_macsec_offload,
+    },
+    nm::wifi::nm_wifi_secrets_merge,
+    ErrorKind, Interface, InterfaceType, NetworkState, NmstateError,
+    OvsBridgeInterface, RouteEntry,
 };
 
 pub(crate) fn nm_apply(
@@ -27,37 +39,152 @@ pub(crate) fn nm_apply(
     des_net_state: &NetworkState,
     checkpoint: &str,
 ) -> Result<(), NmstateError> {
+    let has_ovs = add_net_state.interfaces.has_ovs()
+        || chg_net_state.interfaces.has_ovs();
+    if has_ovs && !ovsdb_is_running() {
+        return Err(ovsdb_not_running_error());
+    }
+    if (add_net_state.interfaces.has_ipv4_shared()
+        || chg_net_state.interfaces.has_ipv4_shared())
+        && !dnsmasq_is_available()
+    {
+        let e = NmstateError::new(
+            ErrorKind::DependencyError,
+            "Desired state contains IPv4 shared method, but dnsmasq is not \
+            installed, please install dnsmasq package"
+                .to_string(),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    if (add_net_state.interfaces.has_ipv4_clat()
+        || chg_net_state.interfaces.has_ipv4_clat())
+        && !nm_supports_clat()?
+    {
+        let e = NmstateError::new(
+            ErrorKind::DependencyError,
+            "Desired state contains IPv4 CLAT, but it requires \
+            NetworkManager 1.52 or newer"
+                .to_string(),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    if (add_net_state.interfaces.has_macsec_offload()
+        || chg_net_state.interfaces.has_macsec_offload())
+        && !nm_supports_macsec_offload()?
+    {
+        let e = NmstateError::new(
+            ErrorKind::DependencyError,
+            "Desired state contains MACsec offload, but it requires \
+            NetworkManager 1.46 or newer"
+                .to_string(),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    for (iface_type, nm_version, is_supported) in [
+        (
+            InterfaceType::Hsr,
+            "1.46",
+            nm_supports_hsr as fn() -> Result<bool, NmstateError>,
+        ),
+        (InterfaceType::IpVlan, "1.52", nm_supports_ipvlan),
+    ] {
+        if (add_net_state.interfaces.has_up_iface_type(&iface_type)
+            || chg_net_state.interfaces.has_up_iface_type(&iface_type))
+            && !is_supported()?
+        {
+            let e = NmstateError::new(
+                ErrorKind::DependencyError,
+                format!(
+                    "Desired state contains {} interface, but it requires \
+                    NetworkManager {} or newer",
+                    iface_type, nm_version
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    }
+
     let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
 
-    delete_net_state(&nm_api, del_net_state)?;
+    // Listing all NM connections is slow on host with hundreds of profiles,
+    // hence we only retrieve once and maintain it during the whole apply.
+    let mut exist_nm_conns =
+        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
+    // Profiles retrieved have no secrets, load them so that profile update
+    // will not wipe the existing WiFi, PPPoE or IPsec secrets
+    for nm_conn in exist_nm_conns.iter_mut() {
+        if is_libreswan_vpn(nm_conn) {
+            nm_ipsec_secrets_merge(&nm_api, nm_conn);
+        }
+        if nm_conn.wireless_security.is_some() {
+            nm_wifi_secrets_merge(&nm_api, nm_conn);
+        }
+        if nm_conn.pppoe.is_some() {
+            nm_pppoe_secrets_merge(&nm_api, nm_conn);
+        }
+        if nm_conn.macsec.is_some() {
+            nm_macsec_secrets_merge(&nm_api, nm_conn);
+        }
+    }
+
+    delete_net_state(&nm_api, del_net_state, &mut exist_nm_conns)?;
     apply_single_state(
         &nm_api,
         add_net_state,
         cur_net_state,
         des_net_state,
         checkpoint,
-    )?;
+        &mut exist_nm_conns,
+    )
+    .map_err(|e| check_ovsdb_on_failure(has_ovs, e))?;
     apply_single_state(
         &nm_api,
         chg_net_state,
         cur_net_state,
         des_net_state,
         checkpoint,
-    )?;
+        &mut exist_nm_conns,
+    )
+    .map_err(|e| check_ovsdb_on_failure(has_ovs, e))?;
 
     Ok(())
 }
 
+fn ovsdb_not_running_error() -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::DependencyError,
+        "Desired state contains OVS interfaces, but the OVS database is \
+        not reachable, please make sure openvswitch.service is running"
+            .to_string(),
+    );
+    log::error!("{}", e);
+    e
+}
+
+// The OVS daemon might be stopped in the middle of apply, the NM error we get
+// in that case does not tell anything about OVS.
+fn check_ovsdb_on_failure(has_ovs: bool, e: NmstateError) -> NmstateError {
+    if has_ovs && !ovsdb_is_running() {
+        log::error!("{}", e);
+        ovsdb_not_running_error()
+    } else {
+        e
+    }
+}
+
 fn delete_net_state(
     nm_api: &NmApi,
     net_state: &NetworkState,
+    exist_nm_conns: &mut Vec<NmConnection>,
 ) -> Result<(), NmstateError> {
     // TODO: Should we remove inactive connections also?
-    let all_nm_conns = nm_api.connections_get().map_err(nm_error_to_nmstate)?;
-
     let nm_conns_name_type_index =
-        create_index_for_nm_conns_by_name_type(&all_nm_conns);
-    let mut uuids_to_delete: HashSet<&str> = HashSet::new();
+        create_index_for_nm_conns_by_name_type(exist_nm_conns);
+    let mut uuids_to_delete: HashSet<String> = HashSet::new();
 
     for iface in &(net_state.interfaces.to_vec()) {
         if !iface.is_absent() {
@@ -77,7 +204,7 @@ fn delete_net_state(
                         &iface.iface_type(),
                         uuid
                     );
-                    uuids_to_delete.insert(uuid);
+                    uuids_to_delete.insert(uuid.to_string());
                 }
                 // Delete OVS port profile along with OVS Interface
                 if iface.iface_type() == InterfaceType::OvsInterface {
@@ -90,7 +217,7 @@ fn delete_net_state(
                             uuid,
                             &iface.name(),
                         );
-                        uuids_to_delete.insert(uuid);
+                        uuids_to_delete.insert(uuid.to_string());
                     }
                 }
             }
@@ -102,8 +229,9 @@ fn delete_net_state(
             .connection_delete(uuid)
             .map_err(nm_error_to_nmstate)?;
     }
+    update_exist_profiles(exist_nm_conns, &[], &uuids_to_delete);
 
-    delete_orphan_ports(nm_api, &uuids_to_delete)?;
+    delete_orphan_ports(nm_api, exist_nm_conns, &uuids_to_delete)?;
     delete_unmanged_virtual_interface_as_desired(nm_api, net_state)?;
     Ok(())
 }
@@ -114,11 +242,8 @@ fn apply_single_state(
     cur_net_state: &NetworkState,
     des_net_state: &NetworkState,
     checkpoint: &str,
+    exist_nm_conns: &mut Vec<NmConnection>,
 ) -> Result<(), NmstateError> {
-    let mut nm_conns_to_activate: Vec<NmConnection> = Vec::new();
-
-    let exist_nm_conns =
-        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
     let nm_acs = nm_api
         .active_connections_get()
         .map_err(nm_error_to_nmstate)?;
@@ -135,44 +260,19 @@ fn apply_single_state(
         })
         .collect();
 
+    let (mut nm_conns_to_activate, memory_only_uuids) = gen_nm_conns_for_state(
+        net_state,
+        des_net_state,
+        exist_nm_conns,
+        &nm_ac_uuids,
+    )?;
     let ifaces = net_state.interfaces.to_vec();
-
-    for iface in ifaces.iter() {
-        if iface.iface_type() != InterfaceType::Unknown && iface.is_up() {
-            let mut ctrl_iface: Option<&Interface> = None;
-            if let Some(ctrl_iface_name) = &iface.base_iface().controller {
-                if let Some(ctrl_type) = &iface.base_iface().controller_type {
-                    ctrl_iface = des_net_state
-                        .interfaces
-                        .get_iface(ctrl_iface_name, ctrl_type.clone());
-                }
-            }
-            let mut routes: Vec<&RouteEntry> = Vec::new();
-            if let Some(config_routes) = net_state.routes.config.as_ref() {
-                for route in config_routes {
-                    if let Some(i) = route.next_hop_iface.as_ref() {
-                        if i == iface.name() {
-                            routes.push(route);
-                        }
-                    }
-                }
-            }
-            for nm_conn in iface_to_nm_connections(
-                iface,
-                ctrl_iface,
-                &exist_nm_conns,
-                &nm_ac_uuids,
-            )? {
-                nm_conns_to_activate.push(nm_conn);
-            }
-        }
-    }
     let nm_conns_to_deactivate = ifaces
         .into_iter()
         .filter(|iface| iface.is_down())
         .filter_map(|iface| {
             get_exist_profile(
-                &exist_nm_conns,
+                exist_nm_conns,
                 &iface.base_iface().name,
                 &iface.base_iface().iface_type,
                 &nm_ac_uuids,
@@ -191,7 +291,7 @@ fn apply_single_state(
         &mut nm_conns_to_activate,
         &des_net_state.interfaces.user_ifaces,
         &cur_net_state.interfaces.user_ifaces,
-        &exist_nm_conns,
+        exist_nm_conns,
     )?;
 
     let nm_conns_to_deactivate_first = gen_nm_conn_need_to_deactivate_first(
@@ -203,8 +303,14 @@ fn apply_single_state(
         nm_conns_to_deactivate_first.as_slice(),
         checkpoint,
     )?;
-    save_nm_profiles(nm_api, nm_conns_to_activate.as_slice(), checkpoint)?;
-    delete_exist_profiles(nm_api, &exist_nm_conns, &nm_conns_to_activate)?;
+    save_nm_profiles(
+        nm_api,
+        nm_conns_to_activate.as_slice(),
+        &memory_only_uuids,
+        checkpoint,
+    )?;
+    let deleted_uuids =
+        delete_exist_profiles(nm_api, exist_nm_conns, &nm_conns_to_activate)?;
 
     activate_nm_profiles(nm_api, nm_conns_to_activate.as_slice(), checkpoint)?;
     deactivate_nm_profiles(
@@ -212,9 +318,134 @@ fn apply_single_state(
         nm_conns_to_deactivate.as_slice(),
         checkpoint,
     )?;
+    update_exist_profiles(
+        exist_nm_conns,
+        nm_conns_to_activate.as_slice(),
+        &deleted_uuids,
+    );
     Ok(())
 }
 
+// Generate the NM connections for added and changed interfaces without
+// saving or activating them.
+#[cfg(feature = "debug-backend")]
+pub(crate) fn nm_gen_backend_plan(
+    add_net_state: &NetworkState,
+    chg_net_state: &NetworkState,
+    cur_net_state: &NetworkState,
+    des_net_state: &NetworkState,
+) -> Result<Vec<NmConnection>, NmstateError> {
+    let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+    let exist_nm_conns =
+        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
+    let nm_acs = nm_api
+        .active_connections_get()
+        .map_err(nm_error_to_nmstate)?;
+    let nm_ac_uuids: Vec<&str> =
+        nm_acs.iter().map(|nm_ac| &nm_ac.uuid as &str).collect();
+
+    let mut ret = Vec::new();
+    for net_state in [add_net_state, chg_net_state] {
+        let (mut nm_conns, _) = gen_nm_conns_for_state(
+            net_state,
+            des_net_state,
+            &exist_nm_conns,
+            &nm_ac_uuids,
+        )?;
+        use_uuid_for_controller_reference(
+            &mut nm_conns,
+            &des_net_state.interfaces.user_ifaces,
+            &cur_net_state.interfaces.user_ifaces,
+            &exist_nm_conns,
+        )?;
+        ret.extend(nm_conns);
+    }
+    Ok(ret)
+}
+
+// Render the keyfiles apply would save for specified state when no
+// NetworkManager profile exists yet, hence no daemon is required.
+pub(crate) fn nm_gen_conf(
+    add_net_state: &NetworkState,
+    des_net_state: &NetworkState,
+) -> Result<Vec<String>, NmstateError> {
+    let (mut nm_conns, _) =
+        gen_nm_conns_for_state(add_net_state, des_net_state, &[], &[])?;
+    use_uuid_for_controller_reference(
+        &mut nm_conns,
+        &des_net_state.interfaces.user_ifaces,
+        &HashMap::new(),
+        &[],
+    )?;
+    nm_conns.iter().map(nm_conn_to_keyfile).collect()
+}
+
+// Generate the NM connections to save and activate for specified state, also
+// return the UUIDs of connections which should be stored in memory only.
+fn gen_nm_conns_for_state(
+    net_state: &NetworkState,
+    des_net_state: &NetworkState,
+    exist_nm_conns: &[NmConnection],
+    nm_ac_uuids: &[&str],
+) -> Result<(Vec<NmConnection>, HashSet<String>), NmstateError> {
+    let mut nm_conns_to_activate: Vec<NmConnection> = Vec::new();
+    let mut memory_only_uuids: HashSet<String> = HashSet::new();
+    let ifaces = net_state.interfaces.to_vec();
+    let state_id = des_net_state.gen_state_id()?;
+
+    for iface in ifaces.iter() {
+        if iface.iface_type() == InterfaceType::Xfrm && iface.is_up() {
+            let e = NmstateError::new(
+                ErrorKind::NotImplementedError,
+                format!(
+                    "NetworkManager cannot manage XFRM interface {}, please \
+                    use kernel only mode",
+                    iface.name()
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        if iface.iface_type() != InterfaceType::Unknown && iface.is_up() {
+            let mut ctrl_iface: Option<&Interface> = None;
+            if let Some(ctrl_iface_name) = &iface.base_iface().controller {
+                if let Some(ctrl_type) = &iface.base_iface().controller_type {
+                    ctrl_iface = des_net_state
+                        .interfaces
+                        .get_iface(ctrl_iface_name, ctrl_type.clone());
+                }
+            }
+            let mut routes: Vec<&RouteEntry> = Vec::new();
+            if let Some(config_routes) = net_state.routes.config.as_ref() {
+                for route in config_routes {
+                    if let Some(i) = route.next_hop_iface.as_ref() {
+                        if i == iface.name() {
+                            routes.push(route);
+                        }
+                    }
+                }
+            }
+            for mut nm_conn in iface_to_nm_connections(
+                iface,
+                ctrl_iface,
+                exist_nm_conns,
+                nm_ac_uuids,
+            )? {
+                gen_nm_user_setting(&state_id, &mut nm_conn);
+                // The OVS port profiles generated for OVS bridge also
+                // follow the storage of the bridge.
+                if iface.base_iface().is_memory_only() {
+                    if let Some(uuid) = nm_conn.uuid() {
+                        memory_only_uuids.insert(uuid.to_string());
+                    }
+                }
+                nm_conns_to_activate.push(nm_conn);
+            }
+        }
+    }
+    Ok((nm_conns_to_activate, memory_only_uuids))
+}
+
 fn delete_unmanged_virtual_interface_as_desired(
     nm_api: &NmApi,
     net_state: &NetworkState,
@@ -251,11 +482,11 @@ fn delete_unmanged_virtual_interface_as_desired(
 // If any connection still referring to deleted UUID, we should delete it also
 fn delete_orphan_ports(
     nm_api: &NmApi,
-    uuids_deleted: &HashSet<&str>,
+    exist_nm_conns: &mut Vec<NmConnection>,
+    uuids_deleted: &HashSet<String>,
 ) -> Result<(), NmstateError> {
-    let mut uuids_to_delete = Vec::new();
-    let all_nm_conns = nm_api.connections_get().map_err(nm_error_to_nmstate)?;
-    for nm_conn in &all_nm_conns {
+    let mut uuids_to_delete: HashSet<String> = HashSet::new();
+    for nm_conn in exist_nm_conns.iter() {
         if nm_conn.iface_type() != Some(NM_SETTING_OVS_PORT_SETTING_NAME) {
             continue;
         }
@@ -268,7 +499,7 @@ fn delete_orphan_ports(
                         nm_conn.iface_type().unwrap_or(""),
                         uuid
                     );
-                    uuids_to_delete.push(uuid);
+                    uuids_to_delete.insert(uuid.to_string());
                 }
             }
         }
@@ -278,6 +509,7 @@ fn delete_orphan_ports(
             .connection_delete(uuid)
             .map_err(nm_error_to_nmstate)?;
     }
+    update_exist_profiles(exist_nm_conns, &[], &uuids_to_delete);
     Ok(())
 }
 
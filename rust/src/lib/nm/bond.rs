@@ -2,6 +2,28 @@ use nm_dbus::{NmConnection, NmSettingBond};
 
 use crate::{BondConfig, BondInterface, BondMode, BondOptions};
 
+pub(crate) fn gen_nm_bond_port_setting(
+    bond_iface: &BondInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let port_conf = match nm_conn
+        .iface_name()
+        .and_then(|port_name| bond_iface.get_port_conf(port_name))
+    {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_port_set =
+        nm_conn.bond_port.as_ref().cloned().unwrap_or_default();
+    if let Some(v) = port_conf.queue_id {
+        nm_port_set.queue_id = Some(v.into());
+    }
+    if let Some(v) = port_conf.priority {
+        nm_port_set.prio = Some(v);
+    }
+    nm_conn.bond_port = Some(nm_port_set);
+}
+
 pub(crate) fn gen_nm_bond_setting(
     bond_iface: &BondInterface,
     nm_conn: &mut NmConnection,
@@ -81,7 +103,7 @@ fn apply_bond_options(
     if let Some(v) = bond_opts.arp_ip_target.as_ref() {
         nm_bond_set
             .options
-            .insert("arp_ip_target".to_string(), v.clone());
+            .insert("arp_ip_target".to_string(), v.join(","));
     }
     if let Some(v) = bond_opts.arp_validate.as_ref() {
         nm_bond_set
@@ -128,11 +150,21 @@ fn apply_bond_options(
             .options
             .insert("num_unsol_na".to_string(), v.to_string());
     }
+    if let Some(v) = bond_opts.ns_ip6_target.as_ref() {
+        nm_bond_set
+            .options
+            .insert("ns_ip6_target".to_string(), v.join(","));
+    }
     if let Some(v) = bond_opts.packets_per_slave.as_ref() {
         nm_bond_set
             .options
             .insert("packets_per_slave".to_string(), v.to_string());
     }
+    if let Some(v) = bond_opts.peer_notif_delay.as_ref() {
+        nm_bond_set
+            .options
+            .insert("peer_notif_delay".to_string(), v.to_string());
+    }
     if let Some(v) = bond_opts.primary.as_ref() {
         nm_bond_set.options.insert("primary".to_string(), v.clone());
     }
@@ -1,8 +1,11 @@
-use nm_dbus::{NmConnection, NmSettingBridge, NmSettingBridgeVlanRange};
+use nm_dbus::{
+    NmConnection, NmSettingBridge, NmSettingBridgeVlanRange, NmVlanProtocol,
+};
 
 use crate::{
     LinuxBridgeInterface, LinuxBridgeOptions, LinuxBridgePortTunkTag,
     LinuxBridgePortVlanConfig, LinuxBridgePortVlanMode, LinuxBridgeStpOptions,
+    LinuxBridgeVlanProtocol,
 };
 
 pub(crate) fn gen_nm_br_setting(
@@ -81,6 +84,13 @@ fn apply_br_options(
         nm_br_set.multicast_startup_query_interval = Some(*v);
     }
 
+    if let Some(v) = br_opts.vlan_protocol.as_ref() {
+        nm_br_set.vlan_protocol = Some(match v {
+            LinuxBridgeVlanProtocol::Ieee8021Q => NmVlanProtocol::Dot1Q,
+            LinuxBridgeVlanProtocol::Ieee8021Ad => NmVlanProtocol::Dot1Ad,
+        });
+    }
+
     if let Some(stp_opts) = br_opts.stp.as_ref() {
         apply_stp_setting(nm_br_set, stp_opts);
     }
@@ -150,8 +160,10 @@ fn nmstate_port_vlans_to_nm_vlan_range(
                     ret.push(trunk_tag_to_nm_vlan_range(trunk_tag));
                 }
             }
-            if let Some(t) = port_vlan_conf.tag {
-                ret.push(access_tag_to_nm_vlan_range(t))
+            if port_vlan_conf.enable_native == Some(true) {
+                if let Some(t) = port_vlan_conf.tag {
+                    ret.push(access_tag_to_nm_vlan_range(t))
+                }
             }
         }
         Some(LinuxBridgePortVlanMode::Access) => {
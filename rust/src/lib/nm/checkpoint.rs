@@ -1,14 +1,33 @@
 use log::warn;
 use nm_dbus::NmApi;
 
-use crate::{nm::error::nm_error_to_nmstate, NmstateError};
+use crate::{nm::error::nm_error_to_nmstate, ErrorKind, NmstateError};
 
 // Wait maximum 30 seconds for rollback
 const CHECKPOINT_ROLLBACK_TIMEOUT: u32 = 30;
 
-pub(crate) fn nm_checkpoint_create() -> Result<String, NmstateError> {
+pub(crate) fn nm_checkpoint_create(
+    timeout: u32,
+) -> Result<String, NmstateError> {
     let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
-    nm_api.checkpoint_create().map_err(nm_error_to_nmstate)
+    nm_api
+        .checkpoint_create(timeout)
+        .map_err(nm_error_to_nmstate)
+}
+
+pub(crate) fn nm_checkpoint_get_last() -> Result<String, NmstateError> {
+    let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+    match nm_api.checkpoints_get().map_err(nm_error_to_nmstate)?.pop() {
+        Some(checkpoint) => Ok(checkpoint),
+        None => {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                "No checkpoint found".to_string(),
+            );
+            log::error!("{}", e);
+            Err(e)
+        }
+    }
 }
 
 pub(crate) fn nm_checkpoint_rollback(
@@ -5,17 +5,29 @@ use nm_dbus::{
 };
 
 use crate::{
-    nm::bond::gen_nm_bond_setting,
+    nm::bond::{gen_nm_bond_port_setting, gen_nm_bond_setting},
     nm::bridge::{gen_nm_br_port_setting, gen_nm_br_setting},
+    nm::ethtool::gen_nm_ethtool_setting,
+    nm::gre::gen_nm_gre_setting,
+    nm::hsr::gen_nm_hsr_setting,
+    nm::infiniband::gen_nm_infiniband_setting,
     nm::ip::gen_nm_ip_setting,
+    nm::ipsec::{gen_nm_ipsec_vpn_setting, NM_SETTING_VPN_SETTING_NAME},
+    nm::ipvlan::gen_nm_ipvlan_setting,
+    nm::loopback::gen_nm_loopback_setting,
+    nm::macsec::gen_nm_macsec_setting,
     nm::ovs::{
         create_ovs_port_nm_conn, gen_nm_ovs_br_setting,
-        gen_nm_ovs_iface_setting,
+        gen_nm_ovs_ext_ids_setting, gen_nm_ovs_iface_setting,
     },
+    nm::pppoe::gen_nm_pppoe_setting,
     nm::profile::get_exist_profile,
     nm::sriov::gen_nm_sriov_setting,
+    nm::team::{gen_nm_team_port_setting, gen_nm_team_setting},
+    nm::wifi::gen_nm_wifi_setting,
     nm::wired::gen_nm_wired_setting,
-    ErrorKind, Interface, InterfaceType, NetworkState, NmstateError,
+    nm::wireguard::gen_nm_wireguard_setting,
+    ErrorKind, Interface, InterfaceType, NmstateError,
 };
 
 pub(crate) const NM_SETTING_BRIDGE_SETTING_NAME: &str = "bridge";
@@ -27,38 +39,39 @@ pub(crate) const NM_SETTING_VETH_SETTING_NAME: &str = "veth";
 pub(crate) const NM_SETTING_BOND_SETTING_NAME: &str = "bond";
 pub(crate) const NM_SETTING_DUMMY_SETTING_NAME: &str = "dummy";
 pub(crate) const NM_SETTING_MACVLAN_SETTING_NAME: &str = "macvlan";
+pub(crate) const NM_SETTING_VLAN_SETTING_NAME: &str = "vlan";
+pub(crate) const NM_SETTING_VRF_SETTING_NAME: &str = "vrf";
+pub(crate) const NM_SETTING_IP_TUNNEL_SETTING_NAME: &str = "ip-tunnel";
+pub(crate) const NM_SETTING_TEAM_SETTING_NAME: &str = "team";
+pub(crate) const NM_SETTING_WIREGUARD_SETTING_NAME: &str = "wireguard";
+pub(crate) const NM_SETTING_WIRELESS_SETTING_NAME: &str = "802-11-wireless";
+pub(crate) const NM_SETTING_LOOPBACK_SETTING_NAME: &str = "loopback";
+pub(crate) const NM_SETTING_PPPOE_SETTING_NAME: &str = "pppoe";
+pub(crate) const NM_SETTING_HSR_SETTING_NAME: &str = "hsr";
+pub(crate) const NM_SETTING_IPVLAN_SETTING_NAME: &str = "ipvlan";
+pub(crate) const NM_SETTING_INFINIBAND_SETTING_NAME: &str = "infiniband";
+pub(crate) const NM_SETTING_MACSEC_SETTING_NAME: &str = "macsec";
+// The NM device of activated PPPoE connection
+pub(crate) const NM_DEVICE_TYPE_PPP: &str = "ppp";
 
-pub(crate) fn nm_gen_conf(
-    net_state: &NetworkState,
-) -> Result<Vec<String>, NmstateError> {
-    let mut ret = Vec::new();
-    let ifaces = net_state.interfaces.to_vec();
-    for iface in &ifaces {
-        let mut ctrl_iface: Option<&Interface> = None;
-        if let Some(ctrl_iface_name) = &iface.base_iface().controller {
-            if let Some(ctrl_type) = &iface.base_iface().controller_type {
-                ctrl_iface = net_state
-                    .interfaces
-                    .get_iface(ctrl_iface_name, ctrl_type.clone());
-            }
-        }
+const NM_PERMISSION_USER_PREFIX: &str = "user:";
 
-        for nm_conn in iface_to_nm_connections(iface, ctrl_iface, &[], &[])? {
-            ret.push(match nm_conn.to_keyfile() {
-                Ok(s) => s,
-                Err(e) => {
-                    return Err(NmstateError::new(
-                        ErrorKind::PluginFailure,
-                        format!(
-                        "Bug in NM plugin, failed to generate configure: {}",
-                        e
-                    ),
-                    ));
-                }
-            })
-        }
-    }
-    Ok(ret)
+// Key of NM user data holding the ID of desired state
+pub(crate) const NM_USER_DATA_STATE_ID: &str = "nmstate.state-id";
+
+// The only place rendering NmConnection into keyfile, shared by gen_conf and
+// offline apply so the generated files are identical to what apply saves.
+pub(crate) fn nm_conn_to_keyfile(
+    nm_conn: &NmConnection,
+) -> Result<String, NmstateError> {
+    nm_conn.to_keyfile().map_err(|e| {
+        let e = NmstateError::new(
+            ErrorKind::PluginFailure,
+            format!("Bug in NM plugin, failed to generate configure: {}", e),
+        );
+        log::error!("{}", e);
+        e
+    })
 }
 
 pub(crate) fn iface_to_nm_connections(
@@ -69,6 +82,20 @@ pub(crate) fn iface_to_nm_connections(
 ) -> Result<Vec<NmConnection>, NmstateError> {
     let mut ret: Vec<NmConnection> = Vec::new();
     let base_iface = iface.base_iface();
+    // NetworkManager can only manage unknown type device via generic
+    // profile which cannot hold MTU
+    if base_iface.is_passthrough() {
+        let e = NmstateError::new(
+            ErrorKind::NotImplementedError,
+            format!(
+                "NetworkManager does not support passthrough interface {}, \
+                please use kernel mode instead",
+                base_iface.name
+            ),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
     let exist_nm_conn = get_exist_profile(
         exist_nm_conns,
         &base_iface.name,
@@ -85,7 +112,10 @@ pub(crate) fn iface_to_nm_connections(
         iface.base_iface().rules.as_deref(),
         &mut nm_conn,
     )?;
-    gen_nm_wired_setting(iface, &mut nm_conn);
+    if iface.iface_type() != InterfaceType::Loopback {
+        gen_nm_wired_setting(iface, &mut nm_conn);
+        gen_nm_ethtool_setting(iface, &mut nm_conn);
+    }
 
     match iface {
         Interface::OvsBridge(ovs_br_iface) => {
@@ -133,12 +163,61 @@ pub(crate) fn iface_to_nm_connections(
                 nm_conn.mac_vlan = Some(NmSettingMacVlan::from(conf));
             }
         }
+        Interface::Vrf(iface) => {
+            if let Some(table_id) = iface.table_id() {
+                let mut nm_vrf_set =
+                    nm_conn.vrf.as_ref().cloned().unwrap_or_default();
+                nm_vrf_set.table = Some(table_id);
+                nm_conn.vrf = Some(nm_vrf_set);
+            }
+        }
+        Interface::WireGuard(iface) => {
+            gen_nm_wireguard_setting(iface, &mut nm_conn);
+        }
+        Interface::Gre(iface) => {
+            gen_nm_gre_setting(iface, &mut nm_conn);
+        }
+        Interface::Team(iface) => {
+            gen_nm_team_setting(iface, &mut nm_conn);
+        }
+        Interface::Wifi(iface) => {
+            gen_nm_wifi_setting(iface, &mut nm_conn);
+        }
+        Interface::Pppoe(iface) => {
+            gen_nm_pppoe_setting(iface, &mut nm_conn);
+        }
+        Interface::Ipsec(iface) => {
+            gen_nm_ipsec_vpn_setting(iface, &mut nm_conn);
+        }
+        Interface::Hsr(iface) => {
+            gen_nm_hsr_setting(iface, &mut nm_conn);
+        }
+        Interface::IpVlan(iface) => {
+            gen_nm_ipvlan_setting(iface, &mut nm_conn);
+        }
+        Interface::InfiniBand(iface) => {
+            gen_nm_infiniband_setting(iface, &mut nm_conn);
+        }
+        Interface::MacSec(iface) => {
+            gen_nm_macsec_setting(iface, &mut nm_conn);
+        }
+        Interface::Loopback(iface) => {
+            gen_nm_loopback_setting(iface, &mut nm_conn);
+        }
         _ => (),
     };
 
+    gen_nm_ovs_ext_ids_setting(iface, &mut nm_conn);
+
     if let Some(Interface::LinuxBridge(br_iface)) = ctrl_iface {
         gen_nm_br_port_setting(br_iface, &mut nm_conn);
     }
+    if let Some(Interface::Team(team_iface)) = ctrl_iface {
+        gen_nm_team_port_setting(team_iface, &mut nm_conn);
+    }
+    if let Some(Interface::Bond(bond_iface)) = ctrl_iface {
+        gen_nm_bond_port_setting(bond_iface, &mut nm_conn);
+    }
 
     // When detaching a OVS system interface from OVS bridge, we should remove
     // its NmSettingOvsIface setting
@@ -160,11 +239,40 @@ pub(crate) fn iface_type_to_nm(
         InterfaceType::Ethernet => Ok("802-3-ethernet".into()),
         InterfaceType::OvsBridge => Ok("ovs-bridge".into()),
         InterfaceType::OvsInterface => Ok("ovs-interface".into()),
-        InterfaceType::Vlan => Ok("vlan".to_string()),
+        InterfaceType::Vlan => Ok(NM_SETTING_VLAN_SETTING_NAME.to_string()),
         InterfaceType::Dummy => Ok("dummy".to_string()),
         InterfaceType::MacVlan => Ok("macvlan".to_string()),
         InterfaceType::MacVtap => Ok("macvlan".to_string()),
+        InterfaceType::Vrf => Ok("vrf".to_string()),
+        InterfaceType::WireGuard => Ok("wireguard".to_string()),
+        InterfaceType::Gre | InterfaceType::GreTap => {
+            Ok(NM_SETTING_IP_TUNNEL_SETTING_NAME.to_string())
+        }
+        InterfaceType::Team => Ok(NM_SETTING_TEAM_SETTING_NAME.to_string()),
+        InterfaceType::Wifi => Ok(NM_SETTING_WIRELESS_SETTING_NAME.to_string()),
+        InterfaceType::Pppoe => Ok(NM_SETTING_PPPOE_SETTING_NAME.to_string()),
+        InterfaceType::Ipsec => Ok(NM_SETTING_VPN_SETTING_NAME.to_string()),
+        InterfaceType::Hsr => Ok(NM_SETTING_HSR_SETTING_NAME.to_string()),
+        InterfaceType::IpVlan => Ok(NM_SETTING_IPVLAN_SETTING_NAME.to_string()),
+        InterfaceType::InfiniBand => {
+            Ok(NM_SETTING_INFINIBAND_SETTING_NAME.to_string())
+        }
+        InterfaceType::MacSec => Ok(NM_SETTING_MACSEC_SETTING_NAME.to_string()),
+        InterfaceType::Loopback => {
+            Ok(NM_SETTING_LOOPBACK_SETTING_NAME.to_string())
+        }
         InterfaceType::Other(s) => Ok(s.to_string()),
+        // NetworkManager has no setting for Geneve tunnel
+        InterfaceType::Geneve => {
+            let e = NmstateError::new(
+                ErrorKind::NotImplementedError,
+                "NetworkManager does not support Geneve interface, \
+                please use kernel mode instead"
+                    .to_string(),
+            );
+            log::error!("{}", e);
+            Err(e)
+        }
         _ => Err(NmstateError::new(
             ErrorKind::NotImplementedError,
             format!("Does not support iface type: {:?} yet", iface_type),
@@ -172,12 +280,30 @@ pub(crate) fn iface_type_to_nm(
     }
 }
 
+pub(crate) fn gen_nm_user_setting(state_id: &str, nm_conn: &mut NmConnection) {
+    let mut nm_user_set = nm_conn.user.as_ref().cloned().unwrap_or_default();
+    nm_user_set
+        .data
+        .get_or_insert_with(HashMap::new)
+        .insert(NM_USER_DATA_STATE_ID.to_string(), state_id.to_string());
+    nm_conn.user = Some(nm_user_set);
+}
+
+pub(crate) fn nm_user_state_id_get(nm_conn: &NmConnection) -> Option<String> {
+    nm_conn
+        .user
+        .as_ref()
+        .and_then(|u| u.data.as_ref())
+        .and_then(|d| d.get(NM_USER_DATA_STATE_ID))
+        .cloned()
+}
+
 pub(crate) fn create_index_for_nm_conns_by_name_type(
     nm_conns: &[NmConnection],
 ) -> HashMap<(&str, &str), Vec<&NmConnection>> {
     let mut ret: HashMap<(&str, &str), Vec<&NmConnection>> = HashMap::new();
     for nm_conn in nm_conns {
-        if let Some(iface_name) = nm_conn.iface_name() {
+        if let Some(iface_name) = nm_conn_iface_name(nm_conn) {
             if let Some(mut nm_iface_type) = nm_conn.iface_type() {
                 if nm_iface_type == NM_SETTING_VETH_SETTING_NAME {
                     nm_iface_type = NM_SETTING_WIRED_SETTING_NAME;
@@ -196,6 +322,16 @@ pub(crate) fn create_index_for_nm_conns_by_name_type(
     ret
 }
 
+// VPN connection is not bound to any interface, nmstate use connection name
+// as its interface name.
+pub(crate) fn nm_conn_iface_name(nm_conn: &NmConnection) -> Option<&str> {
+    if nm_conn.iface_type() == Some(NM_SETTING_VPN_SETTING_NAME) {
+        nm_conn.id()
+    } else {
+        nm_conn.iface_name()
+    }
+}
+
 pub(crate) fn create_index_for_nm_conns_by_ctrler_type(
     nm_conns: &[NmConnection],
 ) -> HashMap<(&str, &str), Vec<&NmConnection>> {
@@ -301,7 +437,11 @@ pub(crate) fn gen_nm_conn_setting(
         new_nm_conn_set
     };
 
-    nm_conn_set.iface_name = Some(iface.name().to_string());
+    nm_conn_set.iface_name = if iface.iface_type() == InterfaceType::Ipsec {
+        None
+    } else {
+        Some(iface.name().to_string())
+    };
     nm_conn_set.autoconnect = Some(true);
     nm_conn_set.autoconnect_ports = if iface.is_controller() {
         Some(true)
@@ -332,6 +472,49 @@ pub(crate) fn gen_nm_conn_setting(
             };
         }
     }
+    if let Some(users) = iface.base_iface().permissions.as_ref() {
+        nm_conn_set.permissions = Some(
+            users
+                .iter()
+                .map(|user| format!("{}{}:", NM_PERMISSION_USER_PREFIX, user))
+                .collect(),
+        );
+    }
+    if let Some(uuids) = iface.base_iface().secondaries.as_ref() {
+        nm_conn_set.secondaries = Some(uuids.clone());
+    }
     nm_conn.connection = Some(nm_conn_set);
     Ok(())
 }
+
+// Only user permission is supported by NetworkManager
+pub(crate) fn nm_permissions_to_nmstate(
+    nm_conn: &NmConnection,
+) -> Option<Vec<String>> {
+    let users: Vec<String> = nm_conn
+        .connection
+        .as_ref()
+        .and_then(|c| c.permissions.as_ref())?
+        .iter()
+        .filter_map(|p| {
+            p.strip_prefix(NM_PERMISSION_USER_PREFIX)
+                .map(|u| u.split(':').next().unwrap_or_default().to_string())
+        })
+        .collect();
+    if users.is_empty() {
+        None
+    } else {
+        Some(users)
+    }
+}
+
+pub(crate) fn nm_secondaries_to_nmstate(
+    nm_conn: &NmConnection,
+) -> Option<Vec<String>> {
+    nm_conn
+        .connection
+        .as_ref()
+        .and_then(|c| c.secondaries.as_ref())
+        .filter(|s| !s.is_empty())
+        .cloned()
+}
@@ -4,7 +4,14 @@ use crate::{ErrorKind, NmstateError};
 
 pub(crate) fn nm_error_to_nmstate(nm_error: NmError) -> NmstateError {
     NmstateError::new(
-        ErrorKind::Bug,
+        match nm_error.kind {
+            nm_dbus::ErrorKind::Timeout => ErrorKind::Timeout,
+            nm_dbus::ErrorKind::ServiceUnavailable => {
+                ErrorKind::DependencyError
+            }
+            nm_dbus::ErrorKind::PermissionDenied => ErrorKind::PluginFailure,
+            _ => ErrorKind::Bug,
+        },
         format!(
             "{}: {} dbus: {:?}",
             nm_error.kind, nm_error.msg, nm_error.dbus_error
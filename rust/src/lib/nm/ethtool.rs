@@ -0,0 +1,87 @@
+use std::convert::TryFrom;
+
+use nm_dbus::{NmConnection, NmSettingEthtool};
+use serde::Serialize;
+
+use crate::Interface;
+
+// NetworkManager uses ethtool short names for some kernel features
+const NM_ETHTOOL_FEATURE_NAMES: [(&str, &str); 10] = [
+    ("rx-checksum", "rx"),
+    ("tx-scatter-gather", "sg"),
+    ("tx-tcp-segmentation", "tso"),
+    ("tx-generic-segmentation", "gso"),
+    ("rx-gro", "gro"),
+    ("rx-lro", "lro"),
+    ("rx-vlan-hw-parse", "rxvlan"),
+    ("tx-vlan-hw-insert", "txvlan"),
+    ("rx-ntuple-filter", "ntuple"),
+    ("rx-hashing", "rxhash"),
+];
+
+pub(crate) fn gen_nm_ethtool_setting(
+    iface: &Interface,
+    nm_conn: &mut NmConnection,
+) {
+    let ethtool_conf = match iface.base_iface().ethtool.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_ethtool_set =
+        nm_conn.ethtool.as_ref().cloned().unwrap_or_default();
+    if let Some(features) = ethtool_conf.feature.as_ref() {
+        let nm_features =
+            nm_ethtool_set.features.get_or_insert_with(Default::default);
+        for (name, value) in features.iter() {
+            let nm_name = NM_ETHTOOL_FEATURE_NAMES
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, n)| *n)
+                .unwrap_or(name.as_str());
+            nm_features.insert(NmSettingEthtool::feature_name(nm_name), *value);
+        }
+    }
+    if let Some(pause) = ethtool_conf.pause.as_ref() {
+        if pause.autoneg.is_some() {
+            nm_ethtool_set.pause_autoneg = pause.autoneg;
+        }
+        if pause.rx.is_some() {
+            nm_ethtool_set.pause_rx = pause.rx;
+        }
+        if pause.tx.is_some() {
+            nm_ethtool_set.pause_tx = pause.tx;
+        }
+    }
+    // NetworkManager property names are identical to nmstate ones with
+    // prefix, boolean like `adaptive-rx` is stored as integer.
+    if let Some(coalesce) = ethtool_conf.coalesce.as_ref() {
+        let nm_coalesce =
+            nm_ethtool_set.coalesce.get_or_insert_with(Default::default);
+        for (name, value) in conf_to_u32_values(coalesce) {
+            nm_coalesce.insert(NmSettingEthtool::coalesce_name(&name), value);
+        }
+    }
+    if let Some(ring) = ethtool_conf.ring.as_ref() {
+        let nm_ring = nm_ethtool_set.ring.get_or_insert_with(Default::default);
+        for (name, value) in conf_to_u32_values(ring) {
+            nm_ring.insert(NmSettingEthtool::ring_name(&name), value);
+        }
+    }
+    nm_conn.ethtool = Some(nm_ethtool_set);
+}
+
+fn conf_to_u32_values<T: Serialize>(conf: &T) -> Vec<(String, u32)> {
+    let mut ret = Vec::new();
+    if let Ok(serde_json::Value::Object(values)) = serde_json::to_value(conf) {
+        for (name, value) in values {
+            let value = match value {
+                serde_json::Value::Bool(b) => Some(b.into()),
+                v => v.as_u64().and_then(|v| u32::try_from(v).ok()),
+            };
+            if let Some(value) = value {
+                ret.push((name, value));
+            }
+        }
+    }
+    ret
+}
@@ -0,0 +1,57 @@
+use std::convert::TryFrom;
+
+use nm_dbus::{NmConnection, NmSettingIpTunnel};
+
+use crate::{GreConfig, GreInterface, InterfaceType};
+
+pub(crate) fn gen_nm_gre_setting(
+    iface: &GreInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let mut nm_tunnel_set =
+        nm_conn.ip_tunnel.as_ref().cloned().unwrap_or_default();
+    nm_tunnel_set.mode = Some(if iface.is_tap() {
+        NmSettingIpTunnel::MODE_GRETAP
+    } else {
+        NmSettingIpTunnel::MODE_GRE
+    });
+    if let Some(conf) = iface.gre.as_ref() {
+        nm_tunnel_set.local = conf.local.clone();
+        nm_tunnel_set.remote = Some(conf.remote.to_string());
+        nm_tunnel_set.input_key = conf.ikey.map(|k| k.to_string());
+        nm_tunnel_set.output_key = conf.okey.map(|k| k.to_string());
+        if let Some(ttl) = conf.ttl {
+            nm_tunnel_set.ttl = Some(ttl.into());
+        }
+    }
+    nm_conn.ip_tunnel = Some(nm_tunnel_set);
+}
+
+// Return None if not GRE tunnel
+pub(crate) fn nm_ip_tunnel_iface_type(
+    nm_tunnel_set: &NmSettingIpTunnel,
+) -> Option<InterfaceType> {
+    match nm_tunnel_set.mode {
+        Some(NmSettingIpTunnel::MODE_GRE) => Some(InterfaceType::Gre),
+        Some(NmSettingIpTunnel::MODE_GRETAP) => Some(InterfaceType::GreTap),
+        _ => None,
+    }
+}
+
+pub(crate) fn nm_ip_tunnel_to_nmstate(
+    nm_tunnel_set: &NmSettingIpTunnel,
+) -> Option<GreConfig> {
+    nm_tunnel_set.remote.as_ref().map(|remote| GreConfig {
+        local: nm_tunnel_set.local.clone(),
+        remote: remote.to_string(),
+        ikey: nm_tunnel_set
+            .input_key
+            .as_deref()
+            .and_then(|k| k.parse::<u32>().ok()),
+        okey: nm_tunnel_set
+            .output_key
+            .as_deref()
+            .and_then(|k| k.parse::<u32>().ok()),
+        ttl: nm_tunnel_set.ttl.and_then(|t| u8::try_from(t).ok()),
+    })
+}
@@ -0,0 +1,45 @@
+use nm_dbus::{HostnamedApi, NmApi};
+
+use crate::{nm::error::nm_error_to_nmstate, NmstateError};
+
+pub(crate) fn nm_hostname_get() -> Result<Option<String>, NmstateError> {
+    let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+    let hostname = nm_api.hostname_get().map_err(nm_error_to_nmstate)?;
+    Ok(if hostname.is_empty() {
+        None
+    } else {
+        Some(hostname)
+    })
+}
+
+// Empty hostname means removing the static hostname
+pub(crate) fn nm_hostname_set(hostname: &str) -> Result<(), NmstateError> {
+    let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+    nm_api.hostname_set(hostname).map_err(nm_error_to_nmstate)
+}
+
+pub(crate) fn hostnamed_static_hostname_get(
+) -> Result<Option<String>, NmstateError> {
+    let api = HostnamedApi::new().map_err(nm_error_to_nmstate)?;
+    let hostname = api.static_hostname_get().map_err(nm_error_to_nmstate)?;
+    Ok(if hostname.is_empty() {
+        None
+    } else {
+        Some(hostname)
+    })
+}
+
+pub(crate) fn hostnamed_static_hostname_set(
+    hostname: &str,
+) -> Result<(), NmstateError> {
+    let api = HostnamedApi::new().map_err(nm_error_to_nmstate)?;
+    api.static_hostname_set(hostname)
+        .map_err(nm_error_to_nmstate)
+}
+
+pub(crate) fn hostnamed_hostname_set(
+    hostname: &str,
+) -> Result<(), NmstateError> {
+    let api = HostnamedApi::new().map_err(nm_error_to_nmstate)?;
+    api.hostname_set(hostname).map_err(nm_error_to_nmstate)
+}
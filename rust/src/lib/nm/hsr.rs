@@ -0,0 +1,40 @@
+use std::convert::TryFrom;
+
+use nm_dbus::{NmConnection, NmSettingHsr};
+
+use crate::{HsrConfig, HsrInterface, HsrProtocol};
+
+pub(crate) fn gen_nm_hsr_setting(
+    iface: &HsrInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.hsr.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_hsr_set = nm_conn.hsr.as_ref().cloned().unwrap_or_default();
+    nm_hsr_set.port1 = Some(conf.port1.to_string());
+    nm_hsr_set.port2 = Some(conf.port2.to_string());
+    if let Some(v) = conf.multicast_spec {
+        nm_hsr_set.multicast_spec = Some(v.into());
+    }
+    if let Some(v) = conf.protocol {
+        nm_hsr_set.prp = Some(v == HsrProtocol::Prp);
+    }
+    nm_conn.hsr = Some(nm_hsr_set);
+}
+
+pub(crate) fn nm_hsr_to_nmstate(nm_hsr_set: &NmSettingHsr) -> HsrConfig {
+    HsrConfig {
+        port1: nm_hsr_set.port1.clone().unwrap_or_default(),
+        port2: nm_hsr_set.port2.clone().unwrap_or_default(),
+        multicast_spec: nm_hsr_set
+            .multicast_spec
+            .and_then(|v| u8::try_from(v).ok()),
+        protocol: Some(if nm_hsr_set.prp == Some(true) {
+            HsrProtocol::Prp
+        } else {
+            HsrProtocol::Hsr
+        }),
+    }
+}
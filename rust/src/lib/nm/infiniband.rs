@@ -0,0 +1,47 @@
+use std::convert::TryFrom;
+
+use nm_dbus::{NmConnection, NmSettingInfiniBand};
+
+use crate::{InfiniBandConfig, InfiniBandInterface, InfiniBandMode};
+
+pub(crate) fn gen_nm_infiniband_setting(
+    iface: &InfiniBandInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.infiniband.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_ib_set =
+        nm_conn.infiniband.as_ref().cloned().unwrap_or_default();
+    nm_ib_set.mode = Some(conf.mode.as_str().to_string());
+    match (conf.base_iface.as_deref(), conf.pkey) {
+        (Some(base_iface), Some(pkey)) => {
+            nm_ib_set.parent = Some(base_iface.to_string());
+            nm_ib_set.pkey = Some(pkey.into());
+        }
+        _ => {
+            nm_ib_set.parent = None;
+            nm_ib_set.pkey = Some(NmSettingInfiniBand::PKEY_DEFAULT);
+        }
+    }
+    nm_conn.infiniband = Some(nm_ib_set);
+}
+
+pub(crate) fn nm_infiniband_to_nmstate(
+    nm_ib_set: &NmSettingInfiniBand,
+) -> InfiniBandConfig {
+    let pkey = nm_ib_set
+        .pkey
+        .and_then(|p| u16::try_from(p).ok())
+        .filter(|p| *p != InfiniBandConfig::PKEY_DEFAULT)
+        .map(|p| p | InfiniBandConfig::PKEY_FULL_MEMBERSHIP);
+    InfiniBandConfig {
+        base_iface: pkey.and(nm_ib_set.parent.clone()),
+        pkey: nm_ib_set.parent.as_ref().and(pkey),
+        mode: match nm_ib_set.mode.as_deref() {
+            Some("connected") => InfiniBandMode::Connected,
+            _ => InfiniBandMode::Datagram,
+        },
+    }
+}
@@ -4,12 +4,28 @@ use crate::{
     nm::dns::{apply_nm_dns_setting, nm_dns_to_nmstate},
     nm::route::gen_nm_ip_routes,
     nm::route_rule::gen_nm_ip_rules,
-    ErrorKind, Interface, InterfaceIpv4, InterfaceIpv6, NmstateError,
-    RouteEntry, RouteRuleEntry,
+    ErrorKind, Interface, InterfaceIpv4, InterfaceIpv4Clat,
+    InterfaceIpv4Method, InterfaceIpv6, NmstateError, RouteEntry,
+    RouteRuleEntry,
 };
 use nm_dbus::{NmConnection, NmSettingIp, NmSettingIpMethod};
 
 const NM_CONFIG_ADDR_GEN_MODE_EUI64: i32 = 0;
+const NM_SETTING_IP4_CONFIG_CLAT_NO: i32 = 0;
+const NM_SETTING_IP4_CONFIG_CLAT_AUTO: i32 = 1;
+const NM_SETTING_IP4_CONFIG_CLAT_FORCE: i32 = 2;
+// NetworkManager spawns dnsmasq for DHCP and DNS of IPv4 shared method
+const DNSMASQ_PATHS: [&str; 3] = [
+    "/usr/sbin/dnsmasq",
+    "/sbin/dnsmasq",
+    "/usr/local/sbin/dnsmasq",
+];
+
+pub(crate) fn dnsmasq_is_available() -> bool {
+    DNSMASQ_PATHS
+        .iter()
+        .any(|path| std::path::Path::new(path).is_file())
+}
 
 fn gen_nm_ipv4_setting(
     iface_ip: &InterfaceIpv4,
@@ -21,6 +37,14 @@ fn gen_nm_ipv4_setting(
     let method = if iface_ip.enabled {
         if iface_ip.dhcp {
             NmSettingIpMethod::Auto
+        } else if iface_ip.link_local {
+            NmSettingIpMethod::LinkLocal
+        } else if iface_ip.is_shared() {
+            for ip_addr in &iface_ip.addresses {
+                addresses
+                    .push(format!("{}/{}", ip_addr.ip, ip_addr.prefix_length));
+            }
+            NmSettingIpMethod::Shared
         } else if !iface_ip.addresses.is_empty() {
             for ip_addr in &iface_ip.addresses {
                 addresses
@@ -54,10 +78,21 @@ fn gen_nm_ipv4_setting(
         if let Some(routes) = routes {
             nm_setting.routes = gen_nm_ip_routes(routes, false)?;
         }
+        if let Some(v) = iface_ip.auto_gateway {
+            nm_setting.never_default = Some(flip_bool(v));
+        }
     }
     if let Some(rules) = rules {
         nm_setting.route_rules = gen_nm_ip_rules(rules, false)?;
     }
+    // Older NetworkManager does not support CLAT, only set when desired
+    if let Some(clat) = iface_ip.clat {
+        nm_setting.clat = Some(match clat {
+            InterfaceIpv4Clat::Disabled => NM_SETTING_IP4_CONFIG_CLAT_NO,
+            InterfaceIpv4Clat::Auto => NM_SETTING_IP4_CONFIG_CLAT_AUTO,
+            InterfaceIpv4Clat::Force => NM_SETTING_IP4_CONFIG_CLAT_FORCE,
+        });
+    }
     if let Some(dns) = &iface_ip.dns {
         apply_nm_dns_setting(&mut nm_setting, dns);
     }
@@ -123,6 +158,9 @@ fn gen_nm_ipv6_setting(
         if let Some(routes) = routes {
             nm_setting.routes = gen_nm_ip_routes(routes, true)?;
         }
+        if let Some(v) = iface_ip.auto_gateway {
+            nm_setting.never_default = Some(flip_bool(v));
+        }
     }
     if let Some(rules) = rules {
         nm_setting.route_rules = gen_nm_ip_rules(rules, true)?;
@@ -158,6 +196,15 @@ pub(crate) fn gen_nm_ip_setting(
         };
         gen_nm_ipv4_setting(&ipv4_conf, routes, rules, nm_conn)?;
         gen_nm_ipv6_setting(&ipv6_conf, routes, rules, nm_conn)?;
+        // For IPv6-only host, NetworkManager should not consider the
+        // activation succeeded before IPv6 is configured.
+        if let Some(nm_setting) = nm_conn.ipv6.as_mut() {
+            nm_setting.may_fail = if ipv6_conf.enabled {
+                Some(ipv4_conf.enabled)
+            } else {
+                None
+            };
+        }
     } else {
         nm_conn.ipv4 = None;
         nm_conn.ipv6 = None;
@@ -169,22 +216,42 @@ pub(crate) fn nm_ip_setting_to_nmstate4(
     nm_ip_setting: &NmSettingIp,
 ) -> InterfaceIpv4 {
     if let Some(nm_ip_method) = &nm_ip_setting.method {
-        let (enabled, dhcp) = match nm_ip_method {
-            NmSettingIpMethod::Disabled => (false, false),
-            NmSettingIpMethod::LinkLocal
-            | NmSettingIpMethod::Manual
-            | NmSettingIpMethod::Shared => (true, false),
-            NmSettingIpMethod::Auto => (true, true),
+        let (enabled, dhcp, link_local) = match nm_ip_method {
+            NmSettingIpMethod::Disabled => (false, false, false),
+            NmSettingIpMethod::LinkLocal => (true, false, true),
+            NmSettingIpMethod::Manual | NmSettingIpMethod::Shared => {
+                (true, false, false)
+            }
+            NmSettingIpMethod::Auto => (true, true, false),
             _ => {
                 log::warn!("Unexpected NM IP method {:?}", nm_ip_method);
-                (true, false)
+                (true, false, false)
+            }
+        };
+        let method = match nm_ip_method {
+            NmSettingIpMethod::Shared => Some(InterfaceIpv4Method::Shared),
+            _ => None,
+        };
+        let clat = match nm_ip_setting.clat {
+            Some(NM_SETTING_IP4_CONFIG_CLAT_NO) => {
+                Some(InterfaceIpv4Clat::Disabled)
+            }
+            Some(NM_SETTING_IP4_CONFIG_CLAT_AUTO) => {
+                Some(InterfaceIpv4Clat::Auto)
+            }
+            Some(NM_SETTING_IP4_CONFIG_CLAT_FORCE) => {
+                Some(InterfaceIpv4Clat::Force)
             }
+            _ => None,
         };
         let (auto_dns, auto_gateway, auto_routes, auto_table_id) =
             parse_dhcp_opts(nm_ip_setting);
         InterfaceIpv4 {
             enabled,
             dhcp,
+            link_local,
+            method,
+            clat,
             auto_dns,
             auto_routes,
             auto_gateway,
@@ -192,6 +259,9 @@ pub(crate) fn nm_ip_setting_to_nmstate4(
             prop_list: vec![
                 "enabled",
                 "dhcp",
+                "link_local",
+                "method",
+                "clat",
                 "dns",
                 "auto_dns",
                 "auto_routes",
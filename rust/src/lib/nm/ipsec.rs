@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use log::warn;
+use nm_dbus::{NmApi, NmConnection, NmSettingVpn};
+
+use crate::{IpsecInterface, LibreswanConfig, NetworkState};
+
+pub(crate) const NM_SETTING_VPN_SETTING_NAME: &str = "vpn";
+pub(crate) const NM_VPN_SERVICE_TYPE_LIBRESWAN: &str =
+    "org.freedesktop.NetworkManager.libreswan";
+
+const NM_LIBRESWAN_PSK_KEY: &str = "pskvalue";
+const NM_LIBRESWAN_PSK_INPUT_MODES_KEY: &str = "pskinputmodes";
+const NM_LIBRESWAN_PSK_INPUT_MODE_SAVE: &str = "save";
+
+pub(crate) fn gen_nm_ipsec_vpn_setting(
+    iface: &IpsecInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.libreswan.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_vpn_set = nm_conn.vpn.as_ref().cloned().unwrap_or_default();
+    nm_vpn_set.service_type = Some(NM_VPN_SERVICE_TYPE_LIBRESWAN.to_string());
+    let mut data = nm_vpn_set.data.take().unwrap_or_default();
+    data.insert("right".to_string(), conf.right.to_string());
+    for (key, value) in libreswan_opts(conf) {
+        if let Some(v) = value {
+            data.insert(key.to_string(), v.to_string());
+        }
+    }
+    // The hidden PSK means preserving current one
+    if let Some(psk) = conf
+        .psk
+        .as_deref()
+        .filter(|p| *p != NetworkState::PASSWORD_HID_BY_NMSTATE)
+    {
+        data.insert(
+            NM_LIBRESWAN_PSK_INPUT_MODES_KEY.to_string(),
+            NM_LIBRESWAN_PSK_INPUT_MODE_SAVE.to_string(),
+        );
+        let mut secrets = nm_vpn_set.secrets.take().unwrap_or_default();
+        secrets.insert(NM_LIBRESWAN_PSK_KEY.to_string(), psk.to_string());
+        nm_vpn_set.secrets = Some(secrets);
+    }
+    nm_vpn_set.data = Some(data);
+    nm_conn.vpn = Some(nm_vpn_set);
+}
+
+// The options stored as vpn.data using libreswan option name as key
+fn libreswan_opts(conf: &LibreswanConfig) -> [(&str, Option<&str>); 11] {
+    [
+        ("rightid", conf.rightid.as_deref()),
+        ("rightrsasigkey", conf.rightrsasigkey.as_deref()),
+        ("left", conf.left.as_deref()),
+        ("leftid", conf.leftid.as_deref()),
+        ("leftrsasigkey", conf.leftrsasigkey.as_deref()),
+        ("leftcert", conf.leftcert.as_deref()),
+        ("ikev2", conf.ikev2.as_deref()),
+        ("ike", conf.ike.as_deref()),
+        ("esp", conf.esp.as_deref()),
+        ("ikelifetime", conf.ikelifetime.as_deref()),
+        ("salifetime", conf.salifetime.as_deref()),
+    ]
+}
+
+pub(crate) fn is_libreswan_vpn(nm_conn: &NmConnection) -> bool {
+    nm_conn.vpn.as_ref().and_then(|v| v.service_type.as_deref())
+        == Some(NM_VPN_SERVICE_TYPE_LIBRESWAN)
+}
+
+pub(crate) fn nm_ipsec_secrets_get(
+    nm_api: &NmApi,
+    nm_saved_conn: &NmConnection,
+) -> NmConnection {
+    match nm_api
+        .connection_secrets_get(nm_saved_conn, NM_SETTING_VPN_SETTING_NAME)
+    {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(
+                "Failed to retrieve {} secrets of connection {:?}: {}",
+                NM_SETTING_VPN_SETTING_NAME,
+                nm_saved_conn.uuid(),
+                e
+            );
+            NmConnection::new()
+        }
+    }
+}
+
+pub(crate) fn nm_ipsec_secrets_merge(
+    nm_api: &NmApi,
+    nm_conn: &mut NmConnection,
+) {
+    let nm_secrets = nm_ipsec_secrets_get(nm_api, nm_conn);
+    if let (Some(nm_vpn_set), Some(secrets)) =
+        (nm_conn.vpn.as_mut(), nm_secrets.vpn.and_then(|s| s.secrets))
+    {
+        nm_vpn_set.secrets = Some(secrets);
+    }
+}
+
+// Without secrets retrieved, the PSK is shown as hidden
+pub(crate) fn nm_ipsec_to_nmstate(
+    nm_vpn_set: &NmSettingVpn,
+    nm_secrets: Option<&NmConnection>,
+) -> LibreswanConfig {
+    let empty = HashMap::new();
+    let data = nm_vpn_set.data.as_ref().unwrap_or(&empty);
+    let get = |key: &str| data.get(key).cloned();
+    let has_psk = data.get(NM_LIBRESWAN_PSK_INPUT_MODES_KEY).is_some();
+    LibreswanConfig {
+        right: get("right").unwrap_or_default(),
+        rightid: get("rightid"),
+        rightrsasigkey: get("rightrsasigkey"),
+        left: get("left"),
+        leftid: get("leftid"),
+        leftrsasigkey: get("leftrsasigkey"),
+        leftcert: get("leftcert"),
+        ikev2: get("ikev2"),
+        psk: match nm_secrets {
+            Some(c) => c
+                .vpn
+                .as_ref()
+                .and_then(|s| s.secrets.as_ref())
+                .and_then(|s| s.get(NM_LIBRESWAN_PSK_KEY).cloned()),
+            None if has_psk => {
+                Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string())
+            }
+            None => None,
+        },
+        ike: get("ike"),
+        esp: get("esp"),
+        ikelifetime: get("ikelifetime"),
+        salifetime: get("salifetime"),
+    }
+}
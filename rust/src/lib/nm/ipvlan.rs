@@ -0,0 +1,47 @@
+use nm_dbus::{NmConnection, NmSettingIpVlan};
+
+use crate::{IpVlanConfig, IpVlanInterface, IpVlanMode};
+
+const NM_IPVLAN_MODE_L2: u32 = 1;
+const NM_IPVLAN_MODE_L3: u32 = 2;
+const NM_IPVLAN_MODE_L3S: u32 = 3;
+
+pub(crate) fn gen_nm_ipvlan_setting(
+    iface: &IpVlanInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.ipvlan.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_ipvlan_set =
+        nm_conn.ipvlan.as_ref().cloned().unwrap_or_default();
+    nm_ipvlan_set.parent = Some(conf.base_iface.to_string());
+    nm_ipvlan_set.mode = Some(match conf.mode {
+        IpVlanMode::L2 => NM_IPVLAN_MODE_L2,
+        IpVlanMode::L3 => NM_IPVLAN_MODE_L3,
+        IpVlanMode::L3s => NM_IPVLAN_MODE_L3S,
+    });
+    if let Some(v) = conf.private {
+        nm_ipvlan_set.private = Some(v);
+    }
+    if let Some(v) = conf.vepa {
+        nm_ipvlan_set.vepa = Some(v);
+    }
+    nm_conn.ipvlan = Some(nm_ipvlan_set);
+}
+
+pub(crate) fn nm_ipvlan_to_nmstate(
+    nm_ipvlan_set: &NmSettingIpVlan,
+) -> IpVlanConfig {
+    IpVlanConfig {
+        base_iface: nm_ipvlan_set.parent.clone().unwrap_or_default(),
+        mode: match nm_ipvlan_set.mode {
+            Some(NM_IPVLAN_MODE_L2) => IpVlanMode::L2,
+            Some(NM_IPVLAN_MODE_L3S) => IpVlanMode::L3s,
+            _ => IpVlanMode::L3,
+        },
+        private: Some(nm_ipvlan_set.private.unwrap_or_default()),
+        vepa: Some(nm_ipvlan_set.vepa.unwrap_or_default()),
+    }
+}
@@ -0,0 +1,17 @@
+use nm_dbus::NmConnection;
+
+use crate::LoopbackInterface;
+
+// NetworkManager stores the MTU of loopback in its own setting instead of
+// the wired one.
+pub(crate) fn gen_nm_loopback_setting(
+    iface: &LoopbackInterface,
+    nm_conn: &mut NmConnection,
+) {
+    if let Some(mtu) = iface.base.mtu {
+        let mut nm_lo_set =
+            nm_conn.loopback.as_ref().cloned().unwrap_or_default();
+        nm_lo_set.mtu = Some(mtu as u32);
+        nm_conn.loopback = Some(nm_lo_set);
+    }
+}
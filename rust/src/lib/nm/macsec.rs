@@ -0,0 +1,113 @@
+use std::convert::TryFrom;
+
+use log::warn;
+use nm_dbus::{NmApi, NmConnection, NmSettingMacSec};
+
+use crate::{
+    nm::connection::NM_SETTING_MACSEC_SETTING_NAME, MacSecConfig,
+    MacSecInterface, MacSecOffload, MacSecValidate, NetworkState,
+};
+
+pub(crate) fn gen_nm_macsec_setting(
+    iface: &MacSecInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.macsec.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_macsec_set =
+        nm_conn.macsec.as_ref().cloned().unwrap_or_default();
+    nm_macsec_set.parent = Some(conf.base_iface.to_string());
+    nm_macsec_set.mode = Some(NmSettingMacSec::MODE_PSK);
+    if let Some(v) = conf.encrypt {
+        nm_macsec_set.encrypt = Some(v);
+    }
+    // The hidden CAK means preserving current one
+    if let Some(v) = conf
+        .mka_cak
+        .as_deref()
+        .filter(|c| *c != NetworkState::PASSWORD_HID_BY_NMSTATE)
+    {
+        nm_macsec_set.mka_cak = Some(v.to_string());
+    }
+    if let Some(v) = conf.mka_ckn.as_ref() {
+        nm_macsec_set.mka_ckn = Some(v.to_string());
+    }
+    if let Some(v) = conf.port {
+        nm_macsec_set.port = Some(v as i32);
+    }
+    if let Some(v) = conf.validation {
+        nm_macsec_set.validation = Some(v.to_u8().into());
+    }
+    if let Some(v) = conf.send_sci {
+        nm_macsec_set.send_sci = Some(v);
+    }
+    if let Some(v) = conf.offload {
+        nm_macsec_set.offload = Some(v.to_u8().into());
+    }
+    nm_conn.macsec = Some(nm_macsec_set);
+}
+
+pub(crate) fn nm_macsec_secrets_get(
+    nm_api: &NmApi,
+    nm_saved_conn: &NmConnection,
+) -> NmConnection {
+    match nm_api
+        .connection_secrets_get(nm_saved_conn, NM_SETTING_MACSEC_SETTING_NAME)
+    {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(
+                "Failed to retrieve {} secrets of connection {:?}: {}",
+                NM_SETTING_MACSEC_SETTING_NAME,
+                nm_saved_conn.uuid(),
+                e
+            );
+            NmConnection::new()
+        }
+    }
+}
+
+pub(crate) fn nm_macsec_secrets_merge(
+    nm_api: &NmApi,
+    nm_conn: &mut NmConnection,
+) {
+    let nm_secrets = nm_macsec_secrets_get(nm_api, nm_conn);
+    if let (Some(nm_macsec_set), Some(cak)) = (
+        nm_conn.macsec.as_mut(),
+        nm_secrets.macsec.and_then(|s| s.mka_cak),
+    ) {
+        nm_macsec_set.mka_cak = Some(cak);
+    }
+}
+
+// Without secrets retrieved, the CAK is shown as hidden
+pub(crate) fn nm_macsec_to_nmstate(
+    nm_macsec_set: &NmSettingMacSec,
+    nm_secrets: Option<&NmConnection>,
+) -> MacSecConfig {
+    MacSecConfig {
+        base_iface: nm_macsec_set.parent.clone().unwrap_or_default(),
+        encrypt: nm_macsec_set.encrypt,
+        mka_cak: match nm_secrets {
+            Some(c) => c.macsec.as_ref().and_then(|s| s.mka_cak.clone()),
+            None => nm_macsec_set
+                .mka_ckn
+                .as_ref()
+                .map(|_| NetworkState::PASSWORD_HID_BY_NMSTATE.to_string()),
+        },
+        mka_ckn: nm_macsec_set.mka_ckn.clone(),
+        port: nm_macsec_set.port.and_then(|p| u32::try_from(p).ok()),
+        validation: nm_macsec_set
+            .validation
+            .and_then(|v| u8::try_from(v).ok())
+            .and_then(MacSecValidate::from_u8),
+        send_sci: nm_macsec_set.send_sci,
+        // Negative value means using NetworkManager global default
+        offload: nm_macsec_set
+            .offload
+            .and_then(|v| u8::try_from(v).ok())
+            .and_then(MacSecOffload::from_u8),
+    }
+}
@@ -7,24 +7,46 @@ mod connection;
 mod device;
 mod dns;
 mod error;
+mod ethtool;
+mod gre;
+mod hostname;
+mod hsr;
+mod infiniband;
 mod ip;
+mod ipsec;
+mod ipvlan;
+mod loopback;
 mod mac_vlan;
+mod macsec;
 mod ovs;
+mod persist;
+mod pppoe;
 mod profile;
 mod route;
 mod route_rule;
 mod show;
 mod sriov;
+mod team;
 #[cfg(test)]
 mod unit_tests;
 mod version;
 mod vlan;
+mod wifi;
 mod wired;
+mod wireguard;
 
-pub(crate) use apply::nm_apply;
+#[cfg(feature = "debug-backend")]
+pub(crate) use apply::nm_gen_backend_plan;
+pub(crate) use apply::{nm_apply, nm_gen_conf};
 pub(crate) use checkpoint::{
-    nm_checkpoint_create, nm_checkpoint_destroy, nm_checkpoint_rollback,
-    nm_checkpoint_timeout_extend,
+    nm_checkpoint_create, nm_checkpoint_destroy, nm_checkpoint_get_last,
+    nm_checkpoint_rollback, nm_checkpoint_timeout_extend,
 };
-pub(crate) use connection::nm_gen_conf;
+#[cfg(feature = "debug-backend")]
+pub(crate) use connection::nm_conn_to_keyfile;
+pub(crate) use hostname::{
+    hostnamed_hostname_set, hostnamed_static_hostname_get,
+    hostnamed_static_hostname_set, nm_hostname_get, nm_hostname_set,
+};
+pub(crate) use persist::nm_persist_check;
 pub(crate) use show::nm_retrieve;
@@ -1,15 +1,28 @@
 use std::convert::TryFrom;
+use std::os::unix::net::UnixStream;
 
-use log::warn;
-use nm_dbus::NmConnection;
+use log::{debug, warn};
+use nm_dbus::{NmConnection, NmSettingOvsPort, NmSettingOvsPortTrunk};
 
 use crate::{
-    nm::connection::gen_nm_conn_setting, BaseInterface, Interface,
-    InterfaceType, NmstateError, OvsBridgeBondConfig, OvsBridgeBondMode,
-    OvsBridgeBondPortConfig, OvsBridgeConfig, OvsBridgeInterface,
-    OvsBridgeOptions, OvsBridgePortConfig, UnknownInterface,
+    nm::connection::gen_nm_conn_setting,
+    ovsdb::{OvsDbClient, OVSDB_SOCKET_PATH},
+    BaseInterface, Interface, InterfaceType, Interfaces,
+    LinuxBridgePortTunkTag, LinuxBridgePortVlanConfig, LinuxBridgePortVlanMode,
+    LinuxBridgePortVlanRange, NmstateError, OvsBridgeBondConfig,
+    OvsBridgeBondLacpMode, OvsBridgeBondMode, OvsBridgeBondPortConfig,
+    OvsBridgeConfig, OvsBridgeDatapath, OvsBridgeInterface, OvsBridgeOptions,
+    OvsBridgePortConfig, OvsDbIfaceConfig, UnknownInterface,
 };
 
+const OVS_MCAST_SNOOPING_AGING_TIME: &str = "mcast-snooping-aging-time";
+const OVS_MCAST_SNOOPING_DISABLE_FLOOD_UNREGISTERED: &str =
+    "mcast-snooping-disable-flood-unregistered";
+
+pub(crate) fn ovsdb_is_running() -> bool {
+    UnixStream::connect(OVSDB_SOCKET_PATH).is_ok()
+}
+
 pub(crate) fn nm_ovs_bridge_conf_get(
     nm_conn: &NmConnection,
     port_nm_conns: Option<&[&NmConnection]>,
@@ -36,6 +49,28 @@ pub(crate) fn nm_ovs_bridge_conf_get(
             Some(m) => Some(m.to_string()),
             None => Some("".to_string()),
         };
+        br_opts.datapath = match nm_ovs_setting.datapath_type.as_deref() {
+            Some("") | None => Some(OvsBridgeDatapath::System),
+            Some(d) => match OvsBridgeDatapath::try_from(d) {
+                Ok(d) => Some(d),
+                Err(_) => {
+                    warn!("Unsupported OVS bridge datapath {}", d);
+                    None
+                }
+            },
+        };
+        if let Some(data) = nm_conn
+            .ovs_other_config
+            .as_ref()
+            .and_then(|s| s.data.as_ref())
+        {
+            br_opts.mcast_snooping_aging_time = data
+                .get(OVS_MCAST_SNOOPING_AGING_TIME)
+                .and_then(|v| v.parse::<u32>().ok());
+            br_opts.mcast_snooping_flood_unregistered = data
+                .get(OVS_MCAST_SNOOPING_DISABLE_FLOOD_UNREGISTERED)
+                .map(|v| v != "true");
+        }
         ovs_br_conf.options = Some(br_opts);
         if let Some(port_nm_conns) = port_nm_conns {
             ovs_br_conf.ports =
@@ -62,9 +97,10 @@ fn nm_ovs_bridge_conf_port_get(
                     }
                 }
                 1 => {
-                    if let Some(p) =
-                        get_ovs_port_config_for_iface(nm_ovs_iface_conns[0])
-                    {
+                    if let Some(p) = get_ovs_port_config_for_iface(
+                        nm_conn,
+                        nm_ovs_iface_conns[0],
+                    ) {
                         ret.push(p);
                     }
                 }
@@ -104,6 +140,24 @@ fn get_ovs_port_config_for_bond(
 
     ovs_bond_conf.bond_downdelay = nm_port_set.down_delay;
     ovs_bond_conf.bond_updelay = nm_port_set.up_delay;
+    ovs_bond_conf.lacp = nm_port_set.lacp.as_ref().and_then(|nm_lacp| {
+        if let Ok(m) = OvsBridgeBondLacpMode::try_from(nm_lacp.as_str()) {
+            Some(m)
+        } else {
+            warn!("Unsupported OVS bond LACP mode {}", nm_lacp);
+            None
+        }
+    });
+    if let Some(mut data) = nm_ovs_port_conn
+        .ovs_other_config
+        .as_ref()
+        .and_then(|s| s.data.clone())
+    {
+        ovs_bond_conf.bond_primary = data.remove("bond-primary");
+        if !data.is_empty() {
+            ovs_bond_conf.other_config = Some(data);
+        }
+    }
     let mut ovs_iface_confs = Vec::new();
 
     for nm_ovs_iface_conn in nm_ovs_iface_conns {
@@ -116,22 +170,139 @@ fn get_ovs_port_config_for_bond(
 
     ovs_bond_conf.ports = Some(ovs_iface_confs);
     port_conf.bond = Some(ovs_bond_conf);
+    port_conf.vlan = get_ovs_port_vlan_config(nm_port_set);
 
     Some(port_conf)
 }
 
 fn get_ovs_port_config_for_iface(
+    nm_ovs_port_conn: &NmConnection,
     nm_conn: &NmConnection,
 ) -> Option<OvsBridgePortConfig> {
     if let Some(name) = nm_conn.iface_name() {
         let mut port_conf = OvsBridgePortConfig::new();
         port_conf.name = name.to_string();
+        port_conf.vlan = nm_ovs_port_conn
+            .ovs_port
+            .as_ref()
+            .and_then(get_ovs_port_vlan_config);
         Some(port_conf)
     } else {
         None
     }
 }
 
+// NetworkManager stores them into the `vlan_mode`, `tag` and `trunks`
+// columns of OVSDB Port table.
+fn gen_nm_ovs_port_vlan_setting(
+    vlan_conf: &LinuxBridgePortVlanConfig,
+    nm_ovs_port_set: &mut NmSettingOvsPort,
+) {
+    nm_ovs_port_set.vlan_mode = Some(
+        match vlan_conf.mode {
+            Some(LinuxBridgePortVlanMode::Access) | None => "access",
+            Some(LinuxBridgePortVlanMode::Trunk) => {
+                if vlan_conf.enable_native == Some(true) {
+                    "native-untagged"
+                } else {
+                    "trunk"
+                }
+            }
+        }
+        .to_string(),
+    );
+    if let Some(tag) = vlan_conf.tag {
+        nm_ovs_port_set.tag = Some(tag.into());
+    }
+    if let Some(trunk_tags) = vlan_conf.trunk_tags.as_ref() {
+        nm_ovs_port_set.trunks = Some(
+            trunk_tags
+                .iter()
+                .map(|t| {
+                    let (min, max) = t.get_vlan_tag_range();
+                    NmSettingOvsPortTrunk::new(min.into(), max.into())
+                })
+                .collect(),
+        );
+    }
+}
+
+pub(crate) fn get_ovs_port_vlan_config(
+    nm_ovs_port_set: &NmSettingOvsPort,
+) -> Option<LinuxBridgePortVlanConfig> {
+    let (mode, enable_native) = match nm_ovs_port_set.vlan_mode.as_deref() {
+        Some("access") => (LinuxBridgePortVlanMode::Access, None),
+        Some("trunk") => (LinuxBridgePortVlanMode::Trunk, Some(false)),
+        Some("native-untagged") => (LinuxBridgePortVlanMode::Trunk, Some(true)),
+        Some(m) => {
+            warn!("Unsupported OVS port VLAN mode {}", m);
+            return None;
+        }
+        None => return None,
+    };
+    Some(LinuxBridgePortVlanConfig {
+        mode: Some(mode),
+        tag: nm_ovs_port_set.tag.and_then(|t| u16::try_from(t).ok()),
+        trunk_tags: nm_ovs_port_set.trunks.as_ref().map(|trunks| {
+            trunks
+                .iter()
+                .filter_map(|t| {
+                    let min = u16::try_from(t.start).ok()?;
+                    let max = u16::try_from(t.end).ok()?;
+                    Some(if min == max {
+                        LinuxBridgePortTunkTag::Id(min)
+                    } else {
+                        LinuxBridgePortTunkTag::IdRange(
+                            LinuxBridgePortVlanRange { min, max },
+                        )
+                    })
+                })
+                .collect()
+        }),
+        enable_native,
+    })
+}
+
+// The OVS port VLAN configuration not found in NetworkManager profile is
+// retrieved from OVSDB, e.g. port VLAN changed by `ovs-vsctl`
+pub(crate) fn ovsdb_fill_port_vlan_confs(ifaces: &mut Interfaces) {
+    if !ifaces
+        .user_ifaces
+        .values()
+        .any(|i| i.iface_type() == InterfaceType::OvsBridge)
+    {
+        return;
+    }
+    let mut client = match OvsDbClient::connect(OVSDB_SOCKET_PATH) {
+        Ok(c) => c,
+        Err(e) => {
+            debug!("{}", e);
+            return;
+        }
+    };
+    let vlan_confs = match client.get_port_vlan_confs() {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Failed to retrieve OVS port VLAN configuration: {}", e);
+            return;
+        }
+    };
+    for iface in ifaces.user_ifaces.values_mut() {
+        if let Interface::OvsBridge(br_iface) = iface {
+            for port_conf in br_iface
+                .bridge
+                .as_mut()
+                .and_then(|b| b.ports.as_mut())
+                .into_iter()
+                .flatten()
+                .filter(|p| p.vlan.is_none())
+            {
+                port_conf.vlan = vlan_confs.get(&port_conf.name).cloned();
+            }
+        }
+    }
+}
+
 fn get_nm_ovs_iface_conns<'a>(
     nm_ovs_port_conn: &'a NmConnection,
     nm_conns: &'a [&'a NmConnection],
@@ -187,6 +358,28 @@ pub(crate) fn create_ovs_port_nm_conn(
         if let Some(bond_updelay) = bond_conf.bond_updelay {
             nm_ovs_port_set.up_delay = Some(bond_updelay);
         }
+
+        if let Some(lacp) = &bond_conf.lacp {
+            nm_ovs_port_set.lacp = Some(lacp.to_string());
+        }
+
+        if bond_conf.bond_primary.is_some() || bond_conf.other_config.is_some()
+        {
+            let mut nm_other_cfg_set = nm_conn
+                .ovs_other_config
+                .as_ref()
+                .cloned()
+                .unwrap_or_default();
+            let mut data = bond_conf.other_config.clone().unwrap_or_default();
+            if let Some(primary) = &bond_conf.bond_primary {
+                data.insert("bond-primary".to_string(), primary.to_string());
+            }
+            nm_other_cfg_set.data = Some(data);
+            nm_conn.ovs_other_config = Some(nm_other_cfg_set);
+        }
+    }
+    if let Some(vlan_conf) = &port_conf.vlan {
+        gen_nm_ovs_port_vlan_setting(vlan_conf, &mut nm_ovs_port_set);
     }
     nm_conn.ovs_port = Some(nm_ovs_port_set);
     Ok(nm_conn)
@@ -227,14 +420,79 @@ pub(crate) fn gen_nm_ovs_br_setting(
                     nm_ovs_br_set.fail_mode = Some(fail_mode.to_string());
                 }
             }
+            if let Some(datapath) = &br_opts.datapath {
+                nm_ovs_br_set.datapath_type = Some(datapath.to_string());
+            }
+            gen_nm_ovs_br_other_config(br_opts, nm_conn);
         }
     }
     nm_conn.ovs_bridge = Some(nm_ovs_br_set);
 }
 
+// The multicast snooping tunables are only exposed by the `other_config`
+// column of OVSDB Bridge table
+fn gen_nm_ovs_br_other_config(
+    br_opts: &OvsBridgeOptions,
+    nm_conn: &mut NmConnection,
+) {
+    if br_opts.mcast_snooping_aging_time.is_none()
+        && br_opts.mcast_snooping_flood_unregistered.is_none()
+    {
+        return;
+    }
+    let mut nm_other_cfg_set = nm_conn
+        .ovs_other_config
+        .as_ref()
+        .cloned()
+        .unwrap_or_default();
+    let mut data = nm_other_cfg_set.data.take().unwrap_or_default();
+    if let Some(v) = br_opts.mcast_snooping_aging_time {
+        data.insert(OVS_MCAST_SNOOPING_AGING_TIME.to_string(), v.to_string());
+    }
+    if let Some(v) = br_opts.mcast_snooping_flood_unregistered {
+        data.insert(
+            OVS_MCAST_SNOOPING_DISABLE_FLOOD_UNREGISTERED.to_string(),
+            (!v).to_string(),
+        );
+    }
+    nm_other_cfg_set.data = Some(data);
+    nm_conn.ovs_other_config = Some(nm_other_cfg_set);
+}
+
 pub(crate) fn gen_nm_ovs_iface_setting(nm_conn: &mut NmConnection) {
     let mut nm_ovs_iface_set =
         nm_conn.ovs_iface.as_ref().cloned().unwrap_or_default();
     nm_ovs_iface_set.iface_type = Some("internal".to_string());
     nm_conn.ovs_iface = Some(nm_ovs_iface_set);
 }
+
+pub(crate) fn gen_nm_ovs_ext_ids_setting(
+    iface: &Interface,
+    nm_conn: &mut NmConnection,
+) {
+    if let Some(ext_ids) = iface
+        .base_iface()
+        .ovsdb
+        .as_ref()
+        .and_then(|c| c.external_ids.as_ref())
+    {
+        let mut nm_ext_ids_set =
+            nm_conn.ovs_ext_ids.as_ref().cloned().unwrap_or_default();
+        nm_ext_ids_set.data = Some(ext_ids.clone());
+        nm_conn.ovs_ext_ids = Some(nm_ext_ids_set);
+    }
+}
+
+pub(crate) fn nm_ovs_ext_ids_to_nmstate(
+    nm_conn: &NmConnection,
+) -> Option<OvsDbIfaceConfig> {
+    nm_conn
+        .ovs_ext_ids
+        .as_ref()
+        .and_then(|s| s.data.as_ref())
+        .map(|data| {
+            let mut conf = OvsDbIfaceConfig::new();
+            conf.external_ids = Some(data.clone());
+            conf
+        })
+}
@@ -0,0 +1,122 @@
+use nm_dbus::{NmApi, NmConnection, NmConnectionStorage};
+
+use crate::{
+    nm::connection::iface_type_to_nm, nm::error::nm_error_to_nmstate,
+    nm::profile::get_exist_profile, Interface, InterfaceState, InterfaceType,
+    Interfaces, NmstateError, PersistIssue, PersistIssueReason,
+};
+
+pub(crate) fn nm_persist_check(
+    ifaces: &Interfaces,
+) -> Result<Vec<PersistIssue>, NmstateError> {
+    let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
+    let exist_nm_conns =
+        nm_api.connections_get().map_err(nm_error_to_nmstate)?;
+    let nm_acs = nm_api
+        .active_connections_get()
+        .map_err(nm_error_to_nmstate)?;
+    let nm_ac_uuids: Vec<&str> =
+        nm_acs.iter().map(|nm_ac| &nm_ac.uuid as &str).collect();
+
+    let mut ifaces: Vec<&Interface> = ifaces.iter().collect();
+    ifaces.sort_unstable_by_key(|i| (i.name(), i.iface_type().to_string()));
+
+    let mut ret = Vec::new();
+    for iface in ifaces {
+        // Memory only config is expected to be gone after reboot
+        if iface.base_iface().is_memory_only() {
+            continue;
+        }
+        let reason = match iface.base_iface().state {
+            InterfaceState::Up => {
+                let nm_conn = get_exist_profile(
+                    &exist_nm_conns,
+                    iface.name(),
+                    &iface.iface_type(),
+                    &nm_ac_uuids,
+                );
+                let storage = match nm_conn {
+                    Some(nm_conn) => Some(
+                        nm_api
+                            .connection_storage_get(nm_conn)
+                            .map_err(nm_error_to_nmstate)?,
+                    ),
+                    None => None,
+                };
+                check_up_iface_persist(nm_conn, storage.as_ref())
+            }
+            InterfaceState::Absent => {
+                let mut on_disk = false;
+                for nm_conn in get_iface_nm_conns(
+                    &exist_nm_conns,
+                    iface.name(),
+                    &iface.iface_type(),
+                ) {
+                    let storage = nm_api
+                        .connection_storage_get(nm_conn)
+                        .map_err(nm_error_to_nmstate)?;
+                    if !storage.is_in_memory() && !storage.is_generated() {
+                        on_disk = true;
+                        break;
+                    }
+                }
+                if on_disk {
+                    Some(PersistIssueReason::ProfileOnDisk)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            ret.push(PersistIssue {
+                name: iface.name().to_string(),
+                iface_type: iface.iface_type(),
+                reason,
+            });
+        }
+    }
+    Ok(ret)
+}
+
+// Interface of unknown type matches all profiles with the same interface
+// name.
+fn get_iface_nm_conns<'a>(
+    exist_nm_conns: &'a [NmConnection],
+    iface_name: &str,
+    iface_type: &InterfaceType,
+) -> Vec<&'a NmConnection> {
+    let nm_iface_type = iface_type_to_nm(iface_type).ok();
+    exist_nm_conns
+        .iter()
+        .filter(|c| {
+            c.iface_name() == Some(iface_name)
+                && (iface_type == &InterfaceType::Unknown
+                    || (nm_iface_type.is_some()
+                        && c.iface_type() == nm_iface_type.as_deref()))
+        })
+        .collect()
+}
+
+pub(crate) fn check_up_iface_persist(
+    nm_conn: Option<&NmConnection>,
+    storage: Option<&NmConnectionStorage>,
+) -> Option<PersistIssueReason> {
+    let (nm_conn, storage) = match (nm_conn, storage) {
+        (Some(c), Some(s)) => (c, s),
+        _ => return Some(PersistIssueReason::NoProfile),
+    };
+    if storage.is_generated() {
+        Some(PersistIssueReason::NoProfile)
+    } else if storage.is_in_memory() {
+        Some(PersistIssueReason::InMemoryOnly)
+    } else if storage.is_unsaved() {
+        Some(PersistIssueReason::UnsavedChanges)
+    } else if nm_conn.connection.as_ref().and_then(|c| c.autoconnect)
+        == Some(false)
+    {
+        Some(PersistIssueReason::AutoconnectDisabled)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,81 @@
+use log::warn;
+use nm_dbus::{NmApi, NmConnection, NmSettingPppoe};
+
+use crate::{
+    nm::connection::NM_SETTING_PPPOE_SETTING_NAME, NetworkState, PppoeConfig,
+    PppoeInterface,
+};
+
+pub(crate) fn gen_nm_pppoe_setting(
+    iface: &PppoeInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.pppoe.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_pppoe_set = nm_conn.pppoe.as_ref().cloned().unwrap_or_default();
+    nm_pppoe_set.parent = Some(conf.parent.to_string());
+    nm_pppoe_set.username = Some(conf.username.to_string());
+    // The hidden password means preserving current one
+    if let Some(v) = conf
+        .password
+        .as_deref()
+        .filter(|p| *p != NetworkState::PASSWORD_HID_BY_NMSTATE)
+    {
+        nm_pppoe_set.password = Some(v.to_string());
+    }
+    if let Some(v) = conf.service.as_ref() {
+        nm_pppoe_set.service = Some(v.to_string());
+    }
+    nm_conn.pppoe = Some(nm_pppoe_set);
+}
+
+pub(crate) fn nm_pppoe_secrets_get(
+    nm_api: &NmApi,
+    nm_saved_conn: &NmConnection,
+) -> NmConnection {
+    match nm_api
+        .connection_secrets_get(nm_saved_conn, NM_SETTING_PPPOE_SETTING_NAME)
+    {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(
+                "Failed to retrieve {} secrets of connection {:?}: {}",
+                NM_SETTING_PPPOE_SETTING_NAME,
+                nm_saved_conn.uuid(),
+                e
+            );
+            NmConnection::new()
+        }
+    }
+}
+
+pub(crate) fn nm_pppoe_secrets_merge(
+    nm_api: &NmApi,
+    nm_conn: &mut NmConnection,
+) {
+    let nm_secrets = nm_pppoe_secrets_get(nm_api, nm_conn);
+    if let (Some(nm_pppoe_set), Some(password)) = (
+        nm_conn.pppoe.as_mut(),
+        nm_secrets.pppoe.and_then(|s| s.password),
+    ) {
+        nm_pppoe_set.password = Some(password);
+    }
+}
+
+// Without secrets retrieved, the password is shown as hidden
+pub(crate) fn nm_pppoe_to_nmstate(
+    nm_pppoe_set: &NmSettingPppoe,
+    nm_secrets: Option<&NmConnection>,
+) -> PppoeConfig {
+    PppoeConfig {
+        parent: nm_pppoe_set.parent.clone().unwrap_or_default(),
+        username: nm_pppoe_set.username.clone().unwrap_or_default(),
+        password: match nm_secrets {
+            Some(c) => c.pppoe.as_ref().and_then(|s| s.password.clone()),
+            None => Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string()),
+        },
+        service: nm_pppoe_set.service.clone(),
+    }
+}
@@ -1,13 +1,14 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 use log::{error, info};
 use nm_dbus::{NmApi, NmConnection};
 
 use crate::{
     nm::checkpoint::nm_checkpoint_timeout_extend,
-    nm::connection::iface_type_to_nm, nm::error::nm_error_to_nmstate,
-    nm::ovs::get_ovs_port_name, ErrorKind, Interface, InterfaceType,
-    NmstateError,
+    nm::connection::{iface_type_to_nm, nm_conn_iface_name},
+    nm::error::nm_error_to_nmstate,
+    nm::ovs::get_ovs_port_name,
+    ErrorKind, Interface, InterfaceType, NmstateError,
 };
 
 // We only adjust timeout for every 20 profile additions.
@@ -30,7 +31,7 @@ pub(crate) fn get_exist_profile<'a>(
         } else {
             continue;
         };
-        if exist_nm_conn.iface_name() == Some(iface_name)
+        if nm_conn_iface_name(exist_nm_conn) == Some(iface_name)
             && exist_nm_conn.iface_type() == Some(&nm_iface_type)
         {
             if let Some(uuid) = exist_nm_conn.uuid() {
@@ -45,18 +46,20 @@ pub(crate) fn get_exist_profile<'a>(
     found_nm_conns.pop()
 }
 
+// Return the UUIDs of deleted profiles
 pub(crate) fn delete_exist_profiles(
     nm_api: &NmApi,
     exist_nm_conns: &[NmConnection],
     nm_conns: &[NmConnection],
-) -> Result<(), NmstateError> {
+) -> Result<HashSet<String>, NmstateError> {
+    let mut deleted_uuids: HashSet<String> = HashSet::new();
     let mut excluded_uuids: Vec<&str> = Vec::new();
     let mut changed_iface_name_types: Vec<(&str, &str)> = Vec::new();
     for nm_conn in nm_conns {
         if let Some(uuid) = nm_conn.uuid() {
             excluded_uuids.push(uuid);
         }
-        if let Some(name) = nm_conn.iface_name() {
+        if let Some(name) = nm_conn_iface_name(nm_conn) {
             if let Some(nm_iface_type) = nm_conn.iface_type() {
                 changed_iface_name_types.push((name, nm_iface_type));
             }
@@ -68,7 +71,7 @@ pub(crate) fn delete_exist_profiles(
         } else {
             continue;
         };
-        let iface_name = if let Some(i) = exist_nm_conn.iface_name() {
+        let iface_name = if let Some(i) = nm_conn_iface_name(exist_nm_conn) {
             i
         } else {
             continue;
@@ -85,14 +88,34 @@ pub(crate) fn delete_exist_profiles(
             nm_api
                 .connection_delete(uuid)
                 .map_err(nm_error_to_nmstate)?;
+            deleted_uuids.insert(uuid.to_string());
         }
     }
-    Ok(())
+    Ok(deleted_uuids)
+}
+
+// Update the NM connections retrieved at the beginning of apply with saved
+// and deleted profiles, so we do not need to list all profiles again.
+pub(crate) fn update_exist_profiles(
+    exist_nm_conns: &mut Vec<NmConnection>,
+    saved_nm_conns: &[NmConnection],
+    deleted_uuids: &HashSet<String>,
+) {
+    exist_nm_conns.retain(|c| match c.uuid() {
+        Some(uuid) => {
+            !deleted_uuids.contains(uuid)
+                && !saved_nm_conns.iter().any(|s| s.uuid() == Some(uuid))
+        }
+        None => true,
+    });
+    exist_nm_conns.extend_from_slice(saved_nm_conns);
 }
 
+// Profiles with UUID in `memory_only_uuids` will not be stored to disk.
 pub(crate) fn save_nm_profiles(
     nm_api: &nm_dbus::NmApi,
     nm_conns: &[NmConnection],
+    memory_only_uuids: &HashSet<String>,
     checkpoint: &str,
 ) -> Result<(), NmstateError> {
     for (index, nm_conn) in nm_conns.iter().enumerate() {
@@ -106,9 +129,16 @@ pub(crate) fn save_nm_profiles(
                 TIMEOUT_SECONDS_FOR_PROFILE_ADDTION,
             )?;
         }
-        info!("Creating/Modifying connection {:?}", nm_conn);
+        let memory_only = nm_conn
+            .uuid()
+            .map(|uuid| memory_only_uuids.contains(uuid))
+            .unwrap_or_default();
+        info!(
+            "Creating/Modifying connection {:?}, memory only: {}",
+            nm_conn, memory_only
+        );
         nm_api
-            .connection_add(nm_conn)
+            .connection_add(nm_conn, memory_only)
             .map_err(nm_error_to_nmstate)?;
     }
     Ok(())
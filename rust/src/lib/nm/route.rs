@@ -30,6 +30,8 @@ pub(crate) fn gen_nm_ip_routes(
             None => None,
         };
         nm_route.next_hop = route.next_hop_addr.as_ref().cloned();
+        nm_route.weight = route.weight.map(u32::from);
+        nm_route.route_type = route.route_type.map(|t| t.to_string());
 
         ret.push(nm_route);
     }
@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use nm_dbus::NmIpRouteRule;
 
-use crate::{ip::is_ipv6_addr, InterfaceIpAddr, NmstateError, RouteRuleEntry};
+use crate::{InterfaceIpAddr, NmstateError, RouteRuleEntry};
 
 // NM require route rule priority been set explicitly, use 30,000 when
 // desire state instruct to use USE_DEFAULT_PRIORITY
@@ -13,7 +13,9 @@ pub(crate) fn gen_nm_ip_rules(
     is_ipv6: bool,
 ) -> Result<Vec<NmIpRouteRule>, NmstateError> {
     let mut ret = Vec::new();
-    for rule in rules {
+    // Rules have been validated to hold the same IP family for ip_from, ip_to
+    // and family.
+    for rule in rules.iter().filter(|r| r.is_ipv6() == is_ipv6) {
         let mut nm_rule = NmIpRouteRule::new();
         nm_rule.family = Some(if is_ipv6 {
             libc::AF_INET6
@@ -21,24 +23,14 @@ pub(crate) fn gen_nm_ip_rules(
             libc::AF_INET
         });
         if let Some(addr) = rule.ip_from.as_deref() {
-            match (is_ipv6, is_ipv6_addr(addr)) {
-                (true, true) | (false, false) => {
-                    let ip_addr = InterfaceIpAddr::try_from(addr)?;
-                    nm_rule.from_len = Some(ip_addr.prefix_length);
-                    nm_rule.from = Some(ip_addr.ip);
-                }
-                _ => continue,
-            }
+            let ip_addr = InterfaceIpAddr::try_from(addr)?;
+            nm_rule.from_len = Some(ip_addr.prefix_length);
+            nm_rule.from = Some(ip_addr.ip);
         }
         if let Some(addr) = rule.ip_to.as_deref() {
-            match (is_ipv6, is_ipv6_addr(addr)) {
-                (true, true) | (false, false) => {
-                    let ip_addr = InterfaceIpAddr::try_from(addr)?;
-                    nm_rule.to_len = Some(ip_addr.prefix_length);
-                    nm_rule.to = Some(ip_addr.ip);
-                }
-                _ => continue,
-            }
+            let ip_addr = InterfaceIpAddr::try_from(addr)?;
+            nm_rule.to_len = Some(ip_addr.prefix_length);
+            nm_rule.to = Some(ip_addr.ip);
         }
         nm_rule.priority = match rule.priority {
             Some(RouteRuleEntry::USE_DEFAULT_PRIORITY) | None => {
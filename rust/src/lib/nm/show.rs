@@ -10,22 +10,53 @@ use crate::{
     nm::connection::{
         create_index_for_nm_conns_by_ctrler_type,
         create_index_for_nm_conns_by_name_type, get_port_nm_conns,
-        NM_SETTING_BOND_SETTING_NAME, NM_SETTING_BRIDGE_SETTING_NAME,
-        NM_SETTING_DUMMY_SETTING_NAME, NM_SETTING_MACVLAN_SETTING_NAME,
-        NM_SETTING_OVS_BRIDGE_SETTING_NAME, NM_SETTING_OVS_IFACE_SETTING_NAME,
-        NM_SETTING_VETH_SETTING_NAME, NM_SETTING_WIRED_SETTING_NAME,
+        nm_permissions_to_nmstate, nm_secondaries_to_nmstate,
+        nm_user_state_id_get, NM_DEVICE_TYPE_PPP, NM_SETTING_BOND_SETTING_NAME,
+        NM_SETTING_BRIDGE_SETTING_NAME, NM_SETTING_DUMMY_SETTING_NAME,
+        NM_SETTING_HSR_SETTING_NAME, NM_SETTING_INFINIBAND_SETTING_NAME,
+        NM_SETTING_IPVLAN_SETTING_NAME, NM_SETTING_IP_TUNNEL_SETTING_NAME,
+        NM_SETTING_LOOPBACK_SETTING_NAME, NM_SETTING_MACSEC_SETTING_NAME,
+        NM_SETTING_MACVLAN_SETTING_NAME, NM_SETTING_OVS_BRIDGE_SETTING_NAME,
+        NM_SETTING_OVS_IFACE_SETTING_NAME, NM_SETTING_PPPOE_SETTING_NAME,
+        NM_SETTING_TEAM_SETTING_NAME, NM_SETTING_VETH_SETTING_NAME,
+        NM_SETTING_VLAN_SETTING_NAME, NM_SETTING_VRF_SETTING_NAME,
+        NM_SETTING_WIRED_SETTING_NAME, NM_SETTING_WIREGUARD_SETTING_NAME,
+        NM_SETTING_WIRELESS_SETTING_NAME,
     },
     nm::dns::retrieve_dns_info,
     nm::error::nm_error_to_nmstate,
+    nm::gre::{nm_ip_tunnel_iface_type, nm_ip_tunnel_to_nmstate},
+    nm::hsr::nm_hsr_to_nmstate,
+    nm::infiniband::nm_infiniband_to_nmstate,
     nm::ip::{nm_ip_setting_to_nmstate4, nm_ip_setting_to_nmstate6},
-    nm::ovs::nm_ovs_bridge_conf_get,
-    BaseInterface, BondInterface, DummyInterface, EthernetInterface, Interface,
-    InterfaceState, InterfaceType, Interfaces, LinuxBridgeInterface,
-    MacVlanInterface, MacVtapInterface, NetworkState, NmstateError,
-    OvsBridgeInterface, OvsInterface, UnknownInterface,
+    nm::ipsec::{
+        is_libreswan_vpn, nm_ipsec_secrets_get, nm_ipsec_to_nmstate,
+        NM_SETTING_VPN_SETTING_NAME,
+    },
+    nm::ipvlan::nm_ipvlan_to_nmstate,
+    nm::macsec::{nm_macsec_secrets_get, nm_macsec_to_nmstate},
+    nm::ovs::{
+        nm_ovs_bridge_conf_get, nm_ovs_ext_ids_to_nmstate,
+        ovsdb_fill_port_vlan_confs,
+    },
+    nm::pppoe::{nm_pppoe_secrets_get, nm_pppoe_to_nmstate},
+    nm::team::nm_team_to_nmstate,
+    nm::vlan::nm_vlan_to_nmstate,
+    nm::wifi::{nm_wifi_secrets_get, nm_wifi_to_nmstate},
+    nm::wireguard::nm_wireguard_to_nmstate,
+    BaseInterface, BondInterface, DummyInterface, EthernetInterface,
+    GreInterface, HsrInterface, InfiniBandInterface, Interface, InterfaceState,
+    InterfaceType, Interfaces, IpVlanInterface, IpsecInterface,
+    LinuxBridgeInterface, LoopbackInterface, MacSecInterface, MacVlanInterface,
+    MacVtapInterface, NetworkState, NmstateError, OvsBridgeInterface,
+    OvsInterface, PppoeInterface, TeamInterface, UnknownInterface,
+    VlanInterface, VrfInterface, WifiInterface, WireGuardInterface,
 };
 
-pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
+pub(crate) fn nm_retrieve(
+    running_config_only: bool,
+    include_secrets: bool,
+) -> Result<NetworkState, NmstateError> {
     let mut net_state = NetworkState::new();
     net_state.prop_list = vec!["interfaces", "dns"];
     let nm_api = NmApi::new().map_err(nm_error_to_nmstate)?;
@@ -101,6 +132,51 @@ pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
                         iface.base = base_iface;
                         iface
                     }),
+                    InterfaceType::Vlan => Interface::Vlan({
+                        let mut iface = VlanInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::Vrf => Interface::Vrf({
+                        let mut iface = VrfInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::Hsr => Interface::Hsr({
+                        let mut iface = HsrInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::InfiniBand => Interface::InfiniBand({
+                        let mut iface = InfiniBandInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::IpVlan => Interface::IpVlan({
+                        let mut iface = IpVlanInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::MacSec => Interface::MacSec({
+                        let mut iface = MacSecInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::WireGuard => Interface::WireGuard({
+                        let mut iface = WireGuardInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::Team => Interface::Team({
+                        let mut iface = TeamInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
+                    InterfaceType::Wifi => Interface::Wifi({
+                        let mut iface = WifiInterface::new();
+                        iface.base = base_iface;
+                        iface
+                    }),
                     _ => Interface::Unknown({
                         let mut iface = UnknownInterface::new();
                         iface.base = base_iface;
@@ -148,10 +224,24 @@ pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
                     None
                 };
 
+                let nm_secrets = match (nm_saved_conn, &iface_type) {
+                    (Some(c), InterfaceType::Wifi) if include_secrets => {
+                        Some(nm_wifi_secrets_get(&nm_api, c))
+                    }
+                    (Some(c), InterfaceType::Pppoe) if include_secrets => {
+                        Some(nm_pppoe_secrets_get(&nm_api, c))
+                    }
+                    (Some(c), InterfaceType::MacSec) if include_secrets => {
+                        Some(nm_macsec_secrets_get(&nm_api, c))
+                    }
+                    _ => None,
+                };
+
                 if let Some(iface) = iface_get(
                     nm_dev,
                     nm_conn,
                     nm_saved_conn,
+                    nm_secrets.as_ref(),
                     port_saved_nm_conns.as_ref().map(Vec::as_ref),
                 ) {
                     debug!("Found interface {:?}", iface);
@@ -161,9 +251,38 @@ pub(crate) fn nm_retrieve() -> Result<NetworkState, NmstateError> {
         }
     }
 
+    // VPN connection has no NM device associated
+    for nm_ac in nm_acs
+        .iter()
+        .filter(|nm_ac| nm_ac.iface_type == NM_SETTING_VPN_SETTING_NAME)
+    {
+        if let Some(nm_saved_conn) =
+            nm_saved_conn_uuid_index.get(nm_ac.uuid.as_str())
+        {
+            if !is_libreswan_vpn(nm_saved_conn) {
+                continue;
+            }
+            let nm_secrets = if include_secrets {
+                Some(nm_ipsec_secrets_get(&nm_api, nm_saved_conn))
+            } else {
+                None
+            };
+            if let Some(iface) =
+                ipsec_iface_get(nm_saved_conn, nm_secrets.as_ref())
+            {
+                debug!("Found interface {:?}", iface);
+                net_state.append_interface_data(iface);
+            }
+        }
+    }
+
     net_state.dns = retrieve_dns_info(&nm_api, &net_state.interfaces)?;
+    if running_config_only {
+        net_state.dns.running = None;
+    }
 
     set_ovs_iface_controller_info(&mut net_state.interfaces);
+    ovsdb_fill_port_vlan_confs(&mut net_state.interfaces);
 
     Ok(net_state)
 }
@@ -177,6 +296,17 @@ fn nm_dev_iface_type_to_nmstate(nm_dev: &NmDevice) -> InterfaceType {
         NM_SETTING_BRIDGE_SETTING_NAME => InterfaceType::LinuxBridge,
         NM_SETTING_OVS_BRIDGE_SETTING_NAME => InterfaceType::OvsBridge,
         NM_SETTING_OVS_IFACE_SETTING_NAME => InterfaceType::OvsInterface,
+        NM_SETTING_VLAN_SETTING_NAME => InterfaceType::Vlan,
+        NM_SETTING_VRF_SETTING_NAME => InterfaceType::Vrf,
+        NM_SETTING_WIREGUARD_SETTING_NAME => InterfaceType::WireGuard,
+        NM_SETTING_TEAM_SETTING_NAME => InterfaceType::Team,
+        NM_SETTING_WIRELESS_SETTING_NAME => InterfaceType::Wifi,
+        NM_DEVICE_TYPE_PPP => InterfaceType::Pppoe,
+        NM_SETTING_LOOPBACK_SETTING_NAME => InterfaceType::Loopback,
+        NM_SETTING_HSR_SETTING_NAME => InterfaceType::Hsr,
+        NM_SETTING_IPVLAN_SETTING_NAME => InterfaceType::IpVlan,
+        NM_SETTING_INFINIBAND_SETTING_NAME => InterfaceType::InfiniBand,
+        NM_SETTING_MACSEC_SETTING_NAME => InterfaceType::MacSec,
         NM_SETTING_MACVLAN_SETTING_NAME => {
             if nm_dev.is_mac_vtap {
                 InterfaceType::MacVtap
@@ -202,9 +332,33 @@ fn nm_conn_to_base_iface(
             vec!["name", "state", "iface_type", "ipv4", "ipv6"];
         base_iface.state = InterfaceState::Up;
         base_iface.iface_type = nm_dev_iface_type_to_nmstate(nm_dev);
+        // Tunnel type is only known from the mode of ip-tunnel setting
+        if nm_dev.iface_type == NM_SETTING_IP_TUNNEL_SETTING_NAME {
+            if let Some(iface_type) =
+                nm_conn.ip_tunnel.as_ref().and_then(nm_ip_tunnel_iface_type)
+            {
+                base_iface.iface_type = iface_type;
+            }
+        }
         base_iface.ipv4 = ipv4;
         base_iface.ipv6 = ipv6;
         base_iface.controller = nm_conn.controller().map(|c| c.to_string());
+        base_iface.ovsdb = nm_ovs_ext_ids_to_nmstate(nm_conn);
+        if base_iface.ovsdb.is_some() {
+            base_iface.prop_list.push("ovsdb");
+        }
+        base_iface.state_id = nm_user_state_id_get(nm_conn);
+        if base_iface.state_id.is_some() {
+            base_iface.prop_list.push("state_id");
+        }
+        base_iface.permissions = nm_permissions_to_nmstate(nm_conn);
+        if base_iface.permissions.is_some() {
+            base_iface.prop_list.push("permissions");
+        }
+        base_iface.secondaries = nm_secondaries_to_nmstate(nm_conn);
+        if base_iface.secondaries.is_some() {
+            base_iface.prop_list.push("secondaries");
+        }
         return Some(base_iface);
     }
     None
@@ -216,6 +370,7 @@ fn iface_get(
     nm_dev: &NmDevice,
     nm_conn: &NmConnection,
     nm_saved_conn: Option<&NmConnection>,
+    nm_secrets: Option<&NmConnection>,
     port_saved_nm_conns: Option<&[&NmConnection]>,
 ) -> Option<Interface> {
     if let Some(base_iface) = nm_conn_to_base_iface(nm_dev, nm_conn) {
@@ -245,6 +400,11 @@ fn iface_get(
                 iface.base = base_iface;
                 iface
             }),
+            InterfaceType::Loopback => Interface::Loopback({
+                let mut iface = LoopbackInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
             InterfaceType::MacVlan => Interface::MacVlan({
                 let mut iface = MacVlanInterface::new();
                 iface.base = base_iface;
@@ -255,6 +415,88 @@ fn iface_get(
                 iface.base = base_iface;
                 iface
             }),
+            InterfaceType::Vlan => Interface::Vlan({
+                let mut iface = VlanInterface::new();
+                iface.base = base_iface;
+                iface.vlan = nm_conn.vlan.as_ref().map(nm_vlan_to_nmstate);
+                iface
+            }),
+            InterfaceType::Vrf => Interface::Vrf({
+                let mut iface = VrfInterface::new();
+                iface.base = base_iface;
+                iface
+            }),
+            InterfaceType::Gre | InterfaceType::GreTap => Interface::Gre({
+                let mut iface = GreInterface::new();
+                iface.base = base_iface;
+                iface.gre = nm_conn
+                    .ip_tunnel
+                    .as_ref()
+                    .and_then(nm_ip_tunnel_to_nmstate);
+                iface
+            }),
+            InterfaceType::Hsr => Interface::Hsr({
+                let mut iface = HsrInterface::new();
+                iface.base = base_iface;
+                iface.hsr = nm_conn.hsr.as_ref().map(nm_hsr_to_nmstate);
+                iface
+            }),
+            InterfaceType::InfiniBand => Interface::InfiniBand({
+                let mut iface = InfiniBandInterface::new();
+                iface.base = base_iface;
+                iface.infiniband =
+                    nm_conn.infiniband.as_ref().map(nm_infiniband_to_nmstate);
+                iface
+            }),
+            InterfaceType::IpVlan => Interface::IpVlan({
+                let mut iface = IpVlanInterface::new();
+                iface.base = base_iface;
+                iface.ipvlan =
+                    nm_conn.ipvlan.as_ref().map(nm_ipvlan_to_nmstate);
+                iface
+            }),
+            InterfaceType::MacSec => Interface::MacSec({
+                let mut iface = MacSecInterface::new();
+                iface.base = base_iface;
+                iface.macsec = nm_conn.macsec.as_ref().map(|nm_macsec_set| {
+                    nm_macsec_to_nmstate(nm_macsec_set, nm_secrets)
+                });
+                iface
+            }),
+            InterfaceType::WireGuard => Interface::WireGuard({
+                let mut iface = WireGuardInterface::new();
+                iface.base = base_iface;
+                iface.wireguard =
+                    nm_conn.wireguard.as_ref().map(nm_wireguard_to_nmstate);
+                iface
+            }),
+            InterfaceType::Team => Interface::Team({
+                let mut iface = TeamInterface::new();
+                iface.base = base_iface;
+                iface.team = nm_conn.team.as_ref().map(|nm_team_set| {
+                    nm_team_to_nmstate(
+                        nm_team_set,
+                        port_saved_nm_conns.unwrap_or_default(),
+                    )
+                });
+                iface
+            }),
+            InterfaceType::Pppoe => Interface::Pppoe({
+                let mut iface = PppoeInterface::new();
+                iface.base = base_iface;
+                iface.pppoe = nm_conn.pppoe.as_ref().map(|nm_pppoe_set| {
+                    nm_pppoe_to_nmstate(nm_pppoe_set, nm_secrets)
+                });
+                iface
+            }),
+            InterfaceType::Wifi => Interface::Wifi({
+                let mut iface = WifiInterface::new();
+                iface.base = base_iface;
+                iface.wifi = nm_conn.wireless.as_ref().map(|nm_wifi_set| {
+                    nm_wifi_to_nmstate(nm_wifi_set, nm_conn, nm_secrets)
+                });
+                iface
+            }),
             InterfaceType::OvsBridge => {
                 // NetworkManager applied connection does not
                 // have ovs configure
@@ -289,6 +531,23 @@ fn iface_get(
     }
 }
 
+fn ipsec_iface_get(
+    nm_conn: &NmConnection,
+    nm_secrets: Option<&NmConnection>,
+) -> Option<Interface> {
+    let mut iface = IpsecInterface::new();
+    iface.base.name = nm_conn.id()?.to_string();
+    iface.base.prop_list = vec!["name", "state", "iface_type", "ipv4", "ipv6"];
+    iface.base.state = InterfaceState::Up;
+    iface.base.ipv4 = nm_conn.ipv4.as_ref().map(nm_ip_setting_to_nmstate4);
+    iface.base.ipv6 = nm_conn.ipv6.as_ref().map(nm_ip_setting_to_nmstate6);
+    iface.libreswan = nm_conn
+        .vpn
+        .as_ref()
+        .map(|nm_vpn_set| nm_ipsec_to_nmstate(nm_vpn_set, nm_secrets));
+    Some(Interface::Ipsec(iface))
+}
+
 fn get_first_nm_conn<'a>(
     nm_conns_name_type_index: &'a HashMap<
         (&'a str, &'a str),
@@ -301,7 +560,7 @@ fn get_first_nm_conn<'a>(
     let nm_iface_type = if nm_iface_type == NM_SETTING_VETH_SETTING_NAME {
         NM_SETTING_WIRED_SETTING_NAME
     } else {
-        nm_iface_type
+        nm_dev_type_to_nm_conn_type(nm_iface_type)
     };
     if let Some(nm_conns) = nm_conns_name_type_index.get(&(name, nm_iface_type))
     {
@@ -323,7 +582,18 @@ fn get_nm_ac<'a>(
     name: &'a str,
     nm_iface_type: &'a str,
 ) -> Option<&'a NmActiveConnection> {
-    nm_acs_name_type_index.get(&(name, nm_iface_type)).copied()
+    nm_acs_name_type_index
+        .get(&(name, nm_dev_type_to_nm_conn_type(nm_iface_type)))
+        .copied()
+}
+
+// The PPP device is created by activating PPPoE connection
+fn nm_dev_type_to_nm_conn_type(nm_dev_type: &str) -> &str {
+    if nm_dev_type == NM_DEVICE_TYPE_PPP {
+        NM_SETTING_PPPOE_SETTING_NAME
+    } else {
+        nm_dev_type
+    }
 }
 
 fn set_ovs_iface_controller_info(ifaces: &mut Interfaces) {
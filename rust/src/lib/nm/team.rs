@@ -0,0 +1,170 @@
+use log::warn;
+use nm_dbus::{NmConnection, NmSettingTeam, NmSettingTeamPort};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    TeamConfig, TeamInterface, TeamLinkWatcherConfig, TeamPortConfig,
+    TeamRunnerConfig,
+};
+
+// NetworkManager stores team configuration as teamd JSON, the properties
+// not managed by nmstate are preserved.
+pub(crate) fn gen_nm_team_setting(
+    iface: &TeamInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let mut nm_team_set = nm_conn.team.as_ref().cloned().unwrap_or_default();
+    let mut teamd_conf = parse_teamd_conf(nm_team_set.config.as_deref());
+    if let Some(team_conf) = iface.team.as_ref() {
+        if let Some(runner) = team_conf.runner.as_ref() {
+            teamd_conf.insert(
+                "runner".to_string(),
+                json!({"name": runner.name.to_string()}),
+            );
+        }
+        if let Some(watchers) = team_conf.link_watchers.as_ref() {
+            teamd_conf.insert(
+                "link_watch".to_string(),
+                Value::Array(
+                    watchers.iter().map(gen_teamd_link_watch).collect(),
+                ),
+            );
+        }
+    }
+    nm_team_set.config = Some(Value::Object(teamd_conf).to_string());
+    nm_conn.team = Some(nm_team_set);
+}
+
+fn gen_teamd_link_watch(watcher: &TeamLinkWatcherConfig) -> Value {
+    let mut ret = Map::new();
+    ret.insert("name".to_string(), json!(watcher.name.to_string()));
+    if let Some(v) = watcher.delay_up {
+        ret.insert("delay_up".to_string(), json!(v));
+    }
+    if let Some(v) = watcher.delay_down {
+        ret.insert("delay_down".to_string(), json!(v));
+    }
+    if let Some(v) = watcher.interval {
+        ret.insert("interval".to_string(), json!(v));
+    }
+    if let Some(v) = watcher.target_host.as_ref() {
+        ret.insert("target_host".to_string(), json!(v));
+    }
+    Value::Object(ret)
+}
+
+pub(crate) fn gen_nm_team_port_setting(
+    team_iface: &TeamInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let port_conf = match nm_conn
+        .iface_name()
+        .and_then(|port_name| team_iface.get_port_conf(port_name))
+    {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_port_set =
+        nm_conn.team_port.as_ref().cloned().unwrap_or_default();
+    let mut teamd_conf = parse_teamd_conf(nm_port_set.config.as_deref());
+    if let Some(v) = port_conf.prio {
+        teamd_conf.insert("prio".to_string(), json!(v));
+    }
+    if let Some(v) = port_conf.sticky {
+        teamd_conf.insert("sticky".to_string(), json!(v));
+    }
+    if let Some(v) = port_conf.lacp_prio {
+        teamd_conf.insert("lacp_prio".to_string(), json!(v));
+    }
+    if let Some(v) = port_conf.lacp_key {
+        teamd_conf.insert("lacp_key".to_string(), json!(v));
+    }
+    nm_port_set.config = Some(Value::Object(teamd_conf).to_string());
+    nm_conn.team_port = Some(nm_port_set);
+}
+
+fn parse_teamd_conf(config: Option<&str>) -> Map<String, Value> {
+    match config.map(serde_json::from_str::<Value>) {
+        Some(Ok(Value::Object(m))) => m,
+        Some(Ok(_)) | None => Map::new(),
+        Some(Err(e)) => {
+            warn!("Ignoring invalid teamd config {:?}: {}", config, e);
+            Map::new()
+        }
+    }
+}
+
+pub(crate) fn nm_team_to_nmstate(
+    nm_team_set: &NmSettingTeam,
+    port_nm_conns: &[&NmConnection],
+) -> TeamConfig {
+    let teamd_conf = parse_teamd_conf(nm_team_set.config.as_deref());
+    let mut team_conf = TeamConfig::new();
+    team_conf.runner = teamd_conf
+        .get("runner")
+        .and_then(|r| r.get("name"))
+        .and_then(|n| serde_json::from_value(n.clone()).ok())
+        .map(|name| TeamRunnerConfig { name });
+    // The `link_watch` could be single object or array of objects
+    team_conf.link_watchers = match teamd_conf.get("link_watch") {
+        Some(Value::Array(watchers)) => {
+            Some(watchers.iter().filter_map(teamd_link_watch_get).collect())
+        }
+        Some(watcher) => teamd_link_watch_get(watcher).map(|w| vec![w]),
+        None => None,
+    };
+    team_conf.ports = Some(
+        port_nm_conns
+            .iter()
+            .filter_map(|nm_conn| {
+                nm_conn.iface_name().map(|name| {
+                    nm_team_port_to_nmstate(name, nm_conn.team_port.as_ref())
+                })
+            })
+            .collect(),
+    );
+    team_conf
+}
+
+fn teamd_link_watch_get(value: &Value) -> Option<TeamLinkWatcherConfig> {
+    Some(TeamLinkWatcherConfig {
+        name: serde_json::from_value(value.get("name")?.clone()).ok()?,
+        delay_up: get_u32(value, "delay_up"),
+        delay_down: get_u32(value, "delay_down"),
+        interval: get_u32(value, "interval"),
+        target_host: value
+            .get("target_host")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string()),
+    })
+}
+
+fn nm_team_port_to_nmstate(
+    port_name: &str,
+    nm_port_set: Option<&NmSettingTeamPort>,
+) -> TeamPortConfig {
+    let teamd_conf = parse_teamd_conf(
+        nm_port_set.and_then(|nm_set| nm_set.config.as_deref()),
+    );
+    let conf_value = Value::Object(teamd_conf);
+    let mut port_conf = TeamPortConfig::new();
+    port_conf.name = port_name.to_string();
+    port_conf.prio = conf_value
+        .get("prio")
+        .and_then(|v| v.as_i64())
+        .map(|v| v as i32);
+    port_conf.sticky = conf_value.get("sticky").and_then(|v| v.as_bool());
+    port_conf.lacp_prio = conf_value
+        .get("lacp_prio")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u16);
+    port_conf.lacp_key = conf_value
+        .get("lacp_key")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u16);
+    port_conf
+}
+
+fn get_u32(value: &Value, key: &str) -> Option<u32> {
+    value.get(key).and_then(|v| v.as_u64()).map(|v| v as u32)
+}
@@ -0,0 +1,57 @@
+use crate::NetworkState;
+
+#[test]
+fn test_gen_nm_bond_port_setting() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: bond99
+  type: bond
+  state: up
+  link-aggregation:
+    mode: active-backup
+    port:
+    - eth1
+    ports-config:
+    - name: eth1
+      priority: 10
+      queue-id: 2
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    let confs = net_state.gen_conf().unwrap();
+    let eth_keyfile = confs["NetworkManager"]
+        .iter()
+        .find(|k| k.contains("\nid=eth1\n"))
+        .unwrap();
+    assert!(eth_keyfile.contains("\n[bond-port]\nprio=10\nqueue-id=2\n"));
+}
+
+#[test]
+fn test_gen_nm_bond_ip_targets() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: bond99
+  type: bond
+  state: up
+  link-aggregation:
+    mode: active-backup
+    options:
+      arp_interval: 100
+      arp_ip_target:
+      - 192.0.2.1
+      - 192.0.2.2
+      ns_ip6_target:
+      - 2001:db8::1
+"#,
+    )
+    .unwrap();
+    let confs = net_state.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0];
+    assert!(keyfile.contains("\narp_ip_target=192.0.2.1,192.0.2.2\n"));
+    assert!(keyfile.contains("\nns_ip6_target=2001:db8::1\n"));
+}
@@ -0,0 +1,74 @@
+use nm_dbus::NmConnection;
+
+use crate::{nm::ethtool::gen_nm_ethtool_setting, Interface};
+
+#[test]
+fn test_nm_ethtool_feature_names() {
+    let mut iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+ethtool:
+  feature:
+    gro: true
+    tx-udp-segmentation: false
+"#,
+    )
+    .unwrap();
+    iface.base_iface_mut().pre_edit_cleanup().unwrap();
+    let mut nm_conn = NmConnection::new();
+
+    gen_nm_ethtool_setting(&iface, &mut nm_conn);
+
+    let features = nm_conn.ethtool.as_ref().unwrap().features.as_ref().unwrap();
+    assert_eq!(features.len(), 2);
+    assert_eq!(features.get("feature-gro"), Some(&true));
+    assert_eq!(features.get("feature-tx-udp-segmentation"), Some(&false));
+
+    let keyfile = nm_conn.to_keyfile().unwrap();
+    assert!(keyfile.contains("\n[ethtool]\n"));
+    assert!(keyfile.contains("\nfeature-gro=true\n"));
+}
+
+#[test]
+fn test_nm_ethtool_ring_coalesce_pause() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+ethtool:
+  pause:
+    autoneg: false
+    rx: true
+  coalesce:
+    adaptive-rx: true
+    rx-usecs: 100
+  ring:
+    rx: 1024
+    rx-jumbo: 256
+"#,
+    )
+    .unwrap();
+    let mut nm_conn = NmConnection::new();
+
+    gen_nm_ethtool_setting(&iface, &mut nm_conn);
+
+    let nm_ethtool_set = nm_conn.ethtool.as_ref().unwrap();
+    assert_eq!(nm_ethtool_set.features, None);
+    assert_eq!(nm_ethtool_set.pause_autoneg, Some(false));
+    assert_eq!(nm_ethtool_set.pause_rx, Some(true));
+    assert_eq!(nm_ethtool_set.pause_tx, None);
+    let coalesce = nm_ethtool_set.coalesce.as_ref().unwrap();
+    assert_eq!(coalesce.len(), 2);
+    assert_eq!(coalesce.get("coalesce-adaptive-rx"), Some(&1));
+    assert_eq!(coalesce.get("coalesce-rx-usecs"), Some(&100));
+    let ring = nm_ethtool_set.ring.as_ref().unwrap();
+    assert_eq!(ring.len(), 2);
+    assert_eq!(ring.get("ring-rx"), Some(&1024));
+    assert_eq!(ring.get("ring-rx-jumbo"), Some(&256));
+
+    let keyfile = nm_conn.to_keyfile().unwrap();
+    assert!(keyfile.contains("\ncoalesce-adaptive-rx=1\n"));
+    assert!(keyfile.contains("\npause-autoneg=false\n"));
+    assert!(keyfile.contains("\nring-rx=1024\n"));
+}
@@ -0,0 +1,49 @@
+use crate::NetworkState;
+
+fn get_keyfile_value<'a>(keyfile: &'a str, key: &str) -> Option<&'a str> {
+    keyfile
+        .lines()
+        .find_map(|l| l.strip_prefix(&format!("{}=", key)))
+}
+
+#[test]
+fn test_gen_conf_use_controller_uuid_as_apply() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: bond99
+  type: bond
+  state: up
+  link-aggregation:
+    mode: active-backup
+    port:
+    - eth1
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    let confs = net_state.gen_conf().unwrap();
+    let keyfiles = &confs["NetworkManager"];
+    assert_eq!(keyfiles.len(), 2);
+
+    let bond_keyfile = keyfiles
+        .iter()
+        .find(|k| get_keyfile_value(k, "id") == Some("bond99"))
+        .unwrap();
+    let eth_keyfile = keyfiles
+        .iter()
+        .find(|k| get_keyfile_value(k, "id") == Some("eth1"))
+        .unwrap();
+
+    assert!(get_keyfile_value(bond_keyfile, "uuid").is_some());
+    assert_eq!(
+        get_keyfile_value(eth_keyfile, "master"),
+        get_keyfile_value(bond_keyfile, "uuid")
+    );
+    assert!(bond_keyfile.contains(
+        "\n[connection]\nautoconnect=true\nautoconnect-slaves=1\n\
+        id=bond99\ninterface-name=bond99\ntype=bond\nuuid="
+    ));
+}
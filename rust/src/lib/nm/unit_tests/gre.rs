@@ -0,0 +1,42 @@
+use nm_dbus::{NmConnection, NmSettingIpTunnel};
+
+use crate::{
+    nm::gre::{gen_nm_gre_setting, nm_ip_tunnel_to_nmstate},
+    Interface, InterfaceType,
+};
+
+#[test]
+fn test_gretap_to_nm_ip_tunnel() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: gretap0
+type: gretap
+gre:
+  local: 192.0.2.1
+  remote: 198.51.100.1
+  ikey: 100
+  okey: 200
+  ttl: 64
+"#,
+    )
+    .unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::GreTap);
+
+    let mut nm_conn = NmConnection::new();
+    if let Interface::Gre(gre_iface) = &iface {
+        gen_nm_gre_setting(gre_iface, &mut nm_conn);
+    } else {
+        panic!("Expecting GRE interface, got {:?}", iface);
+    }
+    let nm_tunnel_set = nm_conn.ip_tunnel.as_ref().unwrap();
+    assert_eq!(nm_tunnel_set.mode, Some(NmSettingIpTunnel::MODE_GRETAP));
+    assert_eq!(nm_tunnel_set.remote.as_deref(), Some("198.51.100.1"));
+    assert_eq!(nm_tunnel_set.input_key.as_deref(), Some("100"));
+    assert_eq!(nm_tunnel_set.output_key.as_deref(), Some("200"));
+    assert_eq!(nm_tunnel_set.ttl, Some(64));
+
+    let conf = nm_ip_tunnel_to_nmstate(nm_tunnel_set).unwrap();
+    if let Interface::Gre(gre_iface) = &iface {
+        assert_eq!(gre_iface.gre.as_ref(), Some(&conf));
+    }
+}
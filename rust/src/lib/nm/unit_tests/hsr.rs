@@ -0,0 +1,45 @@
+use nm_dbus::NmConnection;
+
+use crate::{
+    nm::hsr::{gen_nm_hsr_setting, nm_hsr_to_nmstate},
+    HsrProtocol, Interface,
+};
+
+#[test]
+fn test_hsr_gen_and_show() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: prp0
+type: hsr
+hsr:
+  port1: eth1
+  port2: eth2
+  multicast-spec: 16
+  protocol: prp
+"#,
+    )
+    .unwrap();
+    let hsr_iface = if let Interface::Hsr(i) = &iface {
+        i
+    } else {
+        panic!("Expecting HSR interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    gen_nm_hsr_setting(hsr_iface, &mut nm_conn);
+
+    let nm_hsr_set = nm_conn.hsr.as_ref().unwrap();
+    assert_eq!(nm_hsr_set.port1.as_deref(), Some("eth1"));
+    assert_eq!(nm_hsr_set.port2.as_deref(), Some("eth2"));
+    assert_eq!(nm_hsr_set.multicast_spec, Some(16));
+    assert_eq!(nm_hsr_set.prp, Some(true));
+
+    assert_eq!(
+        &nm_hsr_to_nmstate(nm_hsr_set),
+        hsr_iface.hsr.as_ref().unwrap()
+    );
+    assert_eq!(
+        nm_hsr_to_nmstate(&Default::default()).protocol,
+        Some(HsrProtocol::Hsr)
+    );
+}
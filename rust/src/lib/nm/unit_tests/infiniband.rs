@@ -0,0 +1,51 @@
+use nm_dbus::{NmConnection, NmSettingInfiniBand};
+
+use crate::{
+    nm::infiniband::{gen_nm_infiniband_setting, nm_infiniband_to_nmstate},
+    InfiniBandMode, Interface,
+};
+
+#[test]
+fn test_infiniband_gen_and_show() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: mlx5_ib0.8001
+type: infiniband
+infiniband:
+  base-iface: mlx5_ib0
+  pkey: "0x8001"
+  mode: connected
+"#,
+    )
+    .unwrap();
+    let ib_iface = if let Interface::InfiniBand(i) = &iface {
+        i
+    } else {
+        panic!("Expecting InfiniBand interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    gen_nm_infiniband_setting(ib_iface, &mut nm_conn);
+
+    let nm_ib_set = nm_conn.infiniband.as_ref().unwrap();
+    assert_eq!(nm_ib_set.parent.as_deref(), Some("mlx5_ib0"));
+    assert_eq!(nm_ib_set.pkey, Some(0x8001));
+    assert_eq!(nm_ib_set.mode.as_deref(), Some("connected"));
+
+    let conf = nm_infiniband_to_nmstate(nm_ib_set);
+    assert_eq!(conf.base_iface.as_deref(), Some("mlx5_ib0"));
+    assert_eq!(conf.pkey, Some(0x8001));
+    assert_eq!(conf.mode, InfiniBandMode::Connected);
+}
+
+#[test]
+fn test_infiniband_show_base_iface() {
+    let mut nm_ib_set = NmSettingInfiniBand::new();
+    nm_ib_set.pkey = Some(NmSettingInfiniBand::PKEY_DEFAULT);
+    nm_ib_set.mode = Some("datagram".to_string());
+
+    let conf = nm_infiniband_to_nmstate(&nm_ib_set);
+    assert_eq!(conf.base_iface, None);
+    assert_eq!(conf.pkey, None);
+    assert_eq!(conf.mode, InfiniBandMode::Datagram);
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use nm_dbus::{NmConnection, NmSettingVpn};
+
+use crate::{
+    nm::connection::{gen_nm_conn_setting, nm_conn_iface_name},
+    nm::ipsec::{gen_nm_ipsec_vpn_setting, nm_ipsec_to_nmstate},
+    Interface, NetworkState,
+};
+
+#[test]
+fn test_ipsec_hidden_psk_keep_current() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: hosta_conn
+type: ipsec
+libreswan:
+  right: 192.0.2.251
+  ike: aes256-sha2
+  psk: <_password_hid_by_nmstate>
+"#,
+    )
+    .unwrap();
+    let ipsec_iface = if let Interface::Ipsec(i) = &iface {
+        i
+    } else {
+        panic!("Expecting IPsec interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    let mut nm_vpn_set = NmSettingVpn::new();
+    nm_vpn_set.data = Some(HashMap::from([(
+        "pskinputmodes".to_string(),
+        "save".to_string(),
+    )]));
+    nm_vpn_set.secrets = Some(HashMap::from([(
+        "pskvalue".to_string(),
+        "current-psk".to_string(),
+    )]));
+    nm_conn.vpn = Some(nm_vpn_set);
+
+    gen_nm_conn_setting(&iface, &mut nm_conn).unwrap();
+    gen_nm_ipsec_vpn_setting(ipsec_iface, &mut nm_conn);
+
+    assert_eq!(nm_conn.iface_type(), Some("vpn"));
+    assert_eq!(nm_conn.iface_name(), None);
+    assert_eq!(nm_conn_iface_name(&nm_conn), Some("hosta_conn"));
+    let nm_vpn_set = nm_conn.vpn.as_ref().unwrap();
+    assert_eq!(
+        nm_vpn_set.service_type.as_deref(),
+        Some("org.freedesktop.NetworkManager.libreswan")
+    );
+    assert_eq!(
+        nm_vpn_set
+            .secrets
+            .as_ref()
+            .and_then(|s| s.get("pskvalue"))
+            .map(String::as_str),
+        Some("current-psk")
+    );
+
+    let conf = nm_ipsec_to_nmstate(nm_vpn_set, None);
+    assert_eq!(conf.right, "192.0.2.251");
+    assert_eq!(conf.ike.as_deref(), Some("aes256-sha2"));
+    assert_eq!(
+        conf.psk.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+}
@@ -0,0 +1,41 @@
+use nm_dbus::NmConnection;
+
+use crate::{
+    nm::ipvlan::{gen_nm_ipvlan_setting, nm_ipvlan_to_nmstate},
+    Interface, IpVlanMode,
+};
+
+#[test]
+fn test_ipvlan_gen_and_show() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: ipvlan0
+type: ipvlan
+ipvlan:
+  base-iface: eth1
+  mode: l3s
+  vepa: true
+"#,
+    )
+    .unwrap();
+    let ipvlan_iface = if let Interface::IpVlan(i) = &iface {
+        i
+    } else {
+        panic!("Expecting IPVLAN interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    gen_nm_ipvlan_setting(ipvlan_iface, &mut nm_conn);
+
+    let nm_ipvlan_set = nm_conn.ipvlan.as_ref().unwrap();
+    assert_eq!(nm_ipvlan_set.parent.as_deref(), Some("eth1"));
+    assert_eq!(nm_ipvlan_set.mode, Some(3));
+    assert_eq!(nm_ipvlan_set.private, None);
+    assert_eq!(nm_ipvlan_set.vepa, Some(true));
+
+    let conf = nm_ipvlan_to_nmstate(nm_ipvlan_set);
+    assert_eq!(conf.base_iface, "eth1");
+    assert_eq!(conf.mode, IpVlanMode::L3s);
+    assert_eq!(conf.private, Some(false));
+    assert_eq!(conf.vepa, Some(true));
+}
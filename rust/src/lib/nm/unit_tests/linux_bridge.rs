@@ -0,0 +1,46 @@
+use crate::NetworkState;
+
+#[test]
+fn test_gen_nm_bridge_multicast_and_stp_options() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: br0
+  type: linux-bridge
+  state: up
+  bridge:
+    options:
+      group-fwd-mask: 8
+      multicast-querier: true
+      multicast-router: enabled
+      multicast-snooping: false
+      stp:
+        enabled: true
+        forward-delay: 16
+        hello-time: 3
+        max-age: 21
+        priority: 4096
+"#,
+    )
+    .unwrap();
+    let confs = net_state.gen_conf().unwrap();
+    let keyfile = &confs["NetworkManager"][0];
+    for line in [
+        "forward-delay=16",
+        "group-forward-mask=8",
+        "hello-time=3",
+        "max-age=21",
+        "multicast-querier=true",
+        "multicast-router=enabled",
+        "multicast-snooping=false",
+        "priority=4096",
+        "stp=true",
+    ] {
+        assert!(
+            keyfile.contains(&format!("\n{}\n", line)),
+            "{} not found in {}",
+            line,
+            keyfile
+        );
+    }
+}
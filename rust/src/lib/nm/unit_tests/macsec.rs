@@ -0,0 +1,91 @@
+use nm_dbus::{NmConnection, NmSettingMacSec};
+
+use crate::{
+    nm::macsec::{gen_nm_macsec_setting, nm_macsec_to_nmstate},
+    Interface, MacSecOffload, MacSecValidate, NetworkState,
+};
+
+const CKN: &str =
+    "f2b4297d39da7330910a74abc0449feb45b5c0b9fc23df1430e1898fcf1c4550";
+
+#[test]
+fn test_macsec_gen_and_show() {
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: macsec0
+type: macsec
+macsec:
+  base-iface: eth1
+  encrypt: true
+  mka-cak: 50b71a8ef0bd5751ea76de6d6c98c03a
+  mka-ckn: {}
+  port: 10
+  validation: check
+  offload: phy
+"#,
+        CKN
+    ))
+    .unwrap();
+    let macsec_iface = if let Interface::MacSec(i) = &iface {
+        i
+    } else {
+        panic!("Expecting MACsec interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    gen_nm_macsec_setting(macsec_iface, &mut nm_conn);
+
+    let nm_macsec_set = nm_conn.macsec.as_ref().unwrap();
+    assert_eq!(nm_macsec_set.parent.as_deref(), Some("eth1"));
+    assert_eq!(nm_macsec_set.mode, Some(NmSettingMacSec::MODE_PSK));
+    assert_eq!(nm_macsec_set.port, Some(10));
+    assert_eq!(nm_macsec_set.validation, Some(1));
+    assert_eq!(nm_macsec_set.offload, Some(1));
+    assert_eq!(nm_macsec_set.mka_ckn.as_deref(), Some(CKN));
+
+    let conf = nm_macsec_to_nmstate(nm_macsec_set, None);
+    assert_eq!(conf.base_iface, "eth1");
+    assert_eq!(conf.validation, Some(MacSecValidate::Check));
+    assert_eq!(conf.offload, Some(MacSecOffload::Phy));
+    assert_eq!(
+        conf.mka_cak.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+}
+
+#[test]
+fn test_macsec_rotate_ckn_keep_hidden_cak() {
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: macsec0
+type: macsec
+macsec:
+  base-iface: eth1
+  mka-cak: "{}"
+  mka-ckn: {}
+"#,
+        NetworkState::PASSWORD_HID_BY_NMSTATE,
+        CKN
+    ))
+    .unwrap();
+    let macsec_iface = if let Interface::MacSec(i) = &iface {
+        i
+    } else {
+        panic!("Expecting MACsec interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    let mut nm_macsec_set = NmSettingMacSec::default();
+    nm_macsec_set.mka_cak =
+        Some("50b71a8ef0bd5751ea76de6d6c98c03a".to_string());
+    nm_macsec_set.mka_ckn = Some("ab".to_string());
+    nm_conn.macsec = Some(nm_macsec_set);
+    gen_nm_macsec_setting(macsec_iface, &mut nm_conn);
+
+    let nm_macsec_set = nm_conn.macsec.as_ref().unwrap();
+    assert_eq!(
+        nm_macsec_set.mka_cak.as_deref(),
+        Some("50b71a8ef0bd5751ea76de6d6c98c03a")
+    );
+    assert_eq!(nm_macsec_set.mka_ckn.as_deref(), Some(CKN));
+}
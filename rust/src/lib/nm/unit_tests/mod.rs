@@ -1,2 +1,38 @@
 #[cfg(test)]
+mod bond;
+#[cfg(test)]
+mod ethtool;
+#[cfg(test)]
+mod gen_conf;
+#[cfg(test)]
+mod gre;
+#[cfg(test)]
+mod hsr;
+#[cfg(test)]
+mod infiniband;
+#[cfg(test)]
+mod ipsec;
+#[cfg(test)]
+mod ipvlan;
+#[cfg(test)]
+mod linux_bridge;
+#[cfg(test)]
+mod macsec;
+#[cfg(test)]
+mod ovs;
+#[cfg(test)]
+mod permissions;
+#[cfg(test)]
+mod persist;
+#[cfg(test)]
+mod pppoe;
+#[cfg(test)]
 mod profiles;
+#[cfg(test)]
+mod team;
+#[cfg(test)]
+mod user;
+#[cfg(test)]
+mod vlan;
+#[cfg(test)]
+mod wifi;
@@ -0,0 +1,67 @@
+use crate::{
+    nm::ovs::{create_ovs_port_nm_conn, get_ovs_port_vlan_config},
+    OvsBridgePortConfig,
+};
+
+#[test]
+fn test_ovs_port_vlan_trunk_gen_and_show() {
+    let port_conf: OvsBridgePortConfig = serde_yaml::from_str(
+        r#"---
+name: eth1
+vlan:
+  mode: trunk
+  tag: 10
+  enable-native: true
+  trunk-tags:
+  - id: 100
+  - id-range:
+      min: 200
+      max: 299
+"#,
+    )
+    .unwrap();
+
+    let nm_conn = create_ovs_port_nm_conn("br0", &port_conf, None).unwrap();
+
+    let nm_ovs_port_set = nm_conn.ovs_port.as_ref().unwrap();
+    assert_eq!(
+        nm_ovs_port_set.vlan_mode.as_deref(),
+        Some("native-untagged")
+    );
+    assert_eq!(nm_ovs_port_set.tag, Some(10));
+    let trunks = nm_ovs_port_set.trunks.as_ref().unwrap();
+    assert_eq!((trunks[0].start, trunks[0].end), (100, 100));
+    assert_eq!((trunks[1].start, trunks[1].end), (200, 299));
+    assert_eq!(
+        get_ovs_port_vlan_config(nm_ovs_port_set).as_ref(),
+        port_conf.vlan.as_ref()
+    );
+    assert!(nm_conn
+        .to_keyfile()
+        .unwrap()
+        .contains("trunks=100,200-299\n"));
+}
+
+#[test]
+fn test_ovs_port_vlan_access_gen_and_show() {
+    let port_conf: OvsBridgePortConfig = serde_yaml::from_str(
+        r#"---
+name: eth1
+vlan:
+  mode: access
+  tag: 20
+"#,
+    )
+    .unwrap();
+
+    let nm_conn = create_ovs_port_nm_conn("br0", &port_conf, None).unwrap();
+
+    let nm_ovs_port_set = nm_conn.ovs_port.as_ref().unwrap();
+    assert_eq!(nm_ovs_port_set.vlan_mode.as_deref(), Some("access"));
+    assert_eq!(nm_ovs_port_set.tag, Some(20));
+    assert_eq!(nm_ovs_port_set.trunks, None);
+    assert_eq!(
+        get_ovs_port_vlan_config(nm_ovs_port_set).as_ref(),
+        port_conf.vlan.as_ref()
+    );
+}
@@ -0,0 +1,45 @@
+use nm_dbus::NmConnection;
+
+use crate::{
+    nm::connection::{
+        gen_nm_conn_setting, nm_permissions_to_nmstate,
+        nm_secondaries_to_nmstate,
+    },
+    Interface,
+};
+
+#[test]
+fn test_nm_permissions_and_secondaries() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+permissions:
+- alice
+secondaries:
+- 7f628a95-d5aa-4664-a73e-05a1ffb173b1
+"#,
+    )
+    .unwrap();
+    let mut nm_conn = NmConnection::new();
+    gen_nm_conn_setting(&iface, &mut nm_conn).unwrap();
+
+    let nm_conn_set = nm_conn.connection.as_ref().unwrap();
+    assert_eq!(
+        nm_conn_set.permissions,
+        Some(vec!["user:alice:".to_string()])
+    );
+    assert_eq!(
+        nm_permissions_to_nmstate(&nm_conn),
+        Some(vec!["alice".to_string()])
+    );
+    assert_eq!(
+        nm_secondaries_to_nmstate(&nm_conn),
+        Some(vec!["7f628a95-d5aa-4664-a73e-05a1ffb173b1".to_string()])
+    );
+
+    let keyfile = nm_conn.to_keyfile().unwrap();
+    assert!(keyfile.contains("\npermissions=user:alice:\n"));
+    assert!(keyfile
+        .contains("\nsecondaries=7f628a95-d5aa-4664-a73e-05a1ffb173b1\n"));
+}
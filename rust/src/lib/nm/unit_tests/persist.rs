@@ -0,0 +1,70 @@
+use nm_dbus::{NmConnection, NmConnectionStorage, NmSettingConnection};
+
+use crate::{nm::persist::check_up_iface_persist, PersistIssueReason};
+
+fn gen_nm_conn(autoconnect: Option<bool>) -> NmConnection {
+    let mut nm_conn = NmConnection::new();
+    let mut nm_conn_set = NmSettingConnection::new();
+    nm_conn_set.iface_name = Some("eth1".to_string());
+    nm_conn_set.iface_type = Some("802-3-ethernet".to_string());
+    nm_conn_set.autoconnect = autoconnect;
+    nm_conn.connection = Some(nm_conn_set);
+    nm_conn
+}
+
+fn gen_storage(flags: u32, filename: &str) -> NmConnectionStorage {
+    NmConnectionStorage {
+        flags,
+        filename: filename.to_string(),
+    }
+}
+
+#[test]
+fn test_persist_check_up_iface() {
+    let nm_conn = gen_nm_conn(None);
+    let on_disk = gen_storage(
+        0,
+        "/etc/NetworkManager/system-connections/eth1.nmconnection",
+    );
+
+    assert_eq!(
+        check_up_iface_persist(None, None),
+        Some(PersistIssueReason::NoProfile)
+    );
+    assert_eq!(
+        check_up_iface_persist(
+            Some(&nm_conn),
+            Some(&gen_storage(0x2 | 0x4, ""))
+        ),
+        Some(PersistIssueReason::NoProfile)
+    );
+    assert_eq!(
+        check_up_iface_persist(
+            Some(&nm_conn),
+            Some(&gen_storage(
+                0,
+                "/run/NetworkManager/system-connections/eth1.nmconnection"
+            ))
+        ),
+        Some(PersistIssueReason::InMemoryOnly)
+    );
+    assert_eq!(
+        check_up_iface_persist(
+            Some(&nm_conn),
+            Some(&gen_storage(0x4, on_disk.filename.as_str()))
+        ),
+        Some(PersistIssueReason::InMemoryOnly)
+    );
+    assert_eq!(
+        check_up_iface_persist(
+            Some(&nm_conn),
+            Some(&gen_storage(0x1, on_disk.filename.as_str()))
+        ),
+        Some(PersistIssueReason::UnsavedChanges)
+    );
+    assert_eq!(
+        check_up_iface_persist(Some(&gen_nm_conn(Some(false))), Some(&on_disk)),
+        Some(PersistIssueReason::AutoconnectDisabled)
+    );
+    assert_eq!(check_up_iface_persist(Some(&nm_conn), Some(&on_disk)), None);
+}
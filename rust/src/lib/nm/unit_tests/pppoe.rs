@@ -0,0 +1,50 @@
+use nm_dbus::{NmConnection, NmSettingPppoe};
+
+use crate::{
+    nm::pppoe::{gen_nm_pppoe_setting, nm_pppoe_to_nmstate},
+    Interface, NetworkState,
+};
+
+#[test]
+fn test_pppoe_hidden_password_keep_current() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: ppp0
+type: pppoe
+pppoe:
+  parent: eth1
+  username: user
+  password: <_password_hid_by_nmstate>
+  service: isp
+"#,
+    )
+    .unwrap();
+    let pppoe_iface = if let Interface::Pppoe(i) = &iface {
+        i
+    } else {
+        panic!("Expecting PPPoE interface, got {:?}", iface);
+    };
+
+    let mut nm_conn = NmConnection::new();
+    let mut nm_pppoe_set = NmSettingPppoe::new();
+    nm_pppoe_set.password = Some("current-password".to_string());
+    nm_conn.pppoe = Some(nm_pppoe_set);
+
+    gen_nm_pppoe_setting(pppoe_iface, &mut nm_conn);
+
+    let nm_pppoe_set = nm_conn.pppoe.as_ref().unwrap();
+    assert_eq!(nm_pppoe_set.parent.as_deref(), Some("eth1"));
+    assert_eq!(nm_pppoe_set.username.as_deref(), Some("user"));
+    assert_eq!(nm_pppoe_set.password.as_deref(), Some("current-password"));
+    assert_eq!(nm_pppoe_set.service.as_deref(), Some("isp"));
+
+    let conf = nm_pppoe_to_nmstate(nm_pppoe_set, None);
+    assert_eq!(conf.parent, "eth1");
+    assert_eq!(conf.username, "user");
+    assert_eq!(
+        conf.password.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+    let conf = nm_pppoe_to_nmstate(nm_pppoe_set, Some(&nm_conn));
+    assert_eq!(conf.password.as_deref(), Some("current-password"));
+}
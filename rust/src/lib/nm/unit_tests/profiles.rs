@@ -1,10 +1,10 @@
 use crate::{
-    nm::profile::use_uuid_for_controller_reference, Interface, InterfaceType,
-    OvsBridgeBondConfig, OvsBridgeBondPortConfig, OvsBridgeConfig,
-    OvsBridgeInterface, OvsBridgePortConfig,
+    nm::profile::{update_exist_profiles, use_uuid_for_controller_reference},
+    Interface, InterfaceType, OvsBridgeBondConfig, OvsBridgeBondPortConfig,
+    OvsBridgeConfig, OvsBridgeInterface, OvsBridgePortConfig,
 };
 use nm_dbus::{NmConnection, NmSettingConnection};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 const UUID1: &str = "8aca0200-accc-4d13-a62f-3c89a6da53c5";
 const UUID2: &str = "1c646761-efcc-4d33-a0d9-cb3c1c2d3309";
 const UUID3: &str = "06935474-b8d3-4e7c-be52-48e2e6e6b3b9";
@@ -118,3 +118,37 @@ fn test_use_uuid_for_controller_reference_with_ovs_bond() {
     assert!(p2_nm_con_set.controller == Some(UUID2.to_string()));
     assert!(p2_nm_con_set.controller_type == Some("ovs-port".to_string()));
 }
+
+#[test]
+fn test_update_exist_profiles() {
+    let mut exist_nm_conns: Vec<NmConnection> = [UUID1, UUID2, UUID3]
+        .iter()
+        .map(|uuid| gen_nm_conn(uuid, "eth1"))
+        .collect();
+    let saved_nm_conns =
+        vec![gen_nm_conn(UUID2, "eth2"), gen_nm_conn(UUID4, "eth4")];
+    let mut deleted_uuids = HashSet::new();
+    deleted_uuids.insert(UUID1.to_string());
+
+    update_exist_profiles(
+        &mut exist_nm_conns,
+        saved_nm_conns.as_slice(),
+        &deleted_uuids,
+    );
+
+    let uuids: Vec<&str> =
+        exist_nm_conns.iter().filter_map(|c| c.uuid()).collect();
+    assert_eq!(uuids, vec![UUID3, UUID2, UUID4]);
+    assert_eq!(exist_nm_conns[1].iface_name(), Some("eth2"));
+}
+
+fn gen_nm_conn(uuid: &str, iface_name: &str) -> NmConnection {
+    let mut nm_conn = NmConnection::new();
+    let mut nm_conn_set = NmSettingConnection::new();
+    nm_conn_set.id = Some(iface_name.to_string());
+    nm_conn_set.uuid = Some(uuid.to_string());
+    nm_conn_set.iface_type = Some("802-3-ethernet".to_string());
+    nm_conn_set.iface_name = Some(iface_name.to_string());
+    nm_conn.connection = Some(nm_conn_set);
+    nm_conn
+}
@@ -0,0 +1,83 @@
+use nm_dbus::{NmConnection, NmSettingConnection};
+
+use crate::{
+    nm::team::{
+        gen_nm_team_port_setting, gen_nm_team_setting, nm_team_to_nmstate,
+    },
+    Interface, TeamLinkWatcherType, TeamRunnerMode,
+};
+
+#[test]
+fn test_team_to_nm_team_config() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: team0
+type: team
+team:
+  port:
+  - name: eth1
+    prio: 10
+    sticky: true
+  - name: eth2
+    lacp-prio: 100
+    lacp-key: 2
+  runner:
+    name: lacp
+  link-watchers:
+  - name: ethtool
+    delay-up: 100
+  - name: arp_ping
+    interval: 1000
+    target-host: 192.0.2.1
+"#,
+    )
+    .unwrap();
+    let team_iface = if let Interface::Team(i) = &iface {
+        i
+    } else {
+        panic!("Expecting team interface, got {:?}", iface);
+    };
+    team_iface.validate().unwrap();
+
+    let mut nm_conn = NmConnection::new();
+    gen_nm_team_setting(team_iface, &mut nm_conn);
+    let nm_team_set = nm_conn.team.as_ref().unwrap();
+
+    let mut port_nm_conns = Vec::new();
+    for port_name in ["eth1", "eth2"] {
+        let mut nm_port_conn = NmConnection::new();
+        let mut nm_conn_set = NmSettingConnection::new();
+        nm_conn_set.iface_name = Some(port_name.to_string());
+        nm_port_conn.connection = Some(nm_conn_set);
+        gen_nm_team_port_setting(team_iface, &mut nm_port_conn);
+        port_nm_conns.push(nm_port_conn);
+    }
+    let port_nm_conns: Vec<&NmConnection> = port_nm_conns.iter().collect();
+
+    let team_conf = nm_team_to_nmstate(nm_team_set, &port_nm_conns);
+    assert_eq!(
+        team_conf.runner.as_ref().map(|r| r.name),
+        Some(TeamRunnerMode::Lacp)
+    );
+    let watchers = team_conf.link_watchers.as_ref().unwrap();
+    assert_eq!(watchers[0].name, TeamLinkWatcherType::Ethtool);
+    assert_eq!(watchers[0].delay_up, Some(100));
+    assert_eq!(watchers[1].name, TeamLinkWatcherType::ArpPing);
+    assert_eq!(watchers[1].target_host.as_deref(), Some("192.0.2.1"));
+    assert_eq!(team_iface.team.as_ref().unwrap(), &team_conf);
+}
+
+#[test]
+fn test_team_arp_ping_without_target_host() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: team0
+type: team
+team:
+  link-watchers:
+  - name: arp_ping
+"#,
+    )
+    .unwrap();
+    assert!(iface.validate().is_err());
+}
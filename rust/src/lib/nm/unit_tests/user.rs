@@ -0,0 +1,21 @@
+use nm_dbus::{NmConnection, NmSettingConnection};
+
+use crate::nm::connection::{gen_nm_user_setting, nm_user_state_id_get};
+
+#[test]
+fn test_nm_user_state_id() {
+    let mut nm_conn = NmConnection::new();
+    let mut nm_conn_set = NmSettingConnection::new();
+    nm_conn_set.id = Some("eth1".to_string());
+    nm_conn.connection = Some(nm_conn_set);
+    assert_eq!(nm_user_state_id_get(&nm_conn), None);
+
+    gen_nm_user_setting("0123456789abcdef", &mut nm_conn);
+    assert_eq!(
+        nm_user_state_id_get(&nm_conn).as_deref(),
+        Some("0123456789abcdef")
+    );
+
+    let keyfile = nm_conn.to_keyfile().unwrap();
+    assert!(keyfile.contains("\n[user]\nnmstate.state-id=0123456789abcdef\n"));
+}
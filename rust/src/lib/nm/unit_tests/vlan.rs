@@ -0,0 +1,80 @@
+use nm_dbus::{NmSettingVlan, NmVlanProtocol};
+
+use crate::{nm::vlan::nm_vlan_to_nmstate, VlanConfig};
+
+#[test]
+fn test_vlan_qinq_to_nm_and_back() {
+    let conf: VlanConfig = serde_yaml::from_str(
+        r#"---
+base-iface: eth1
+id: 100
+protocol: 802.1ad
+ingress-qos-map:
+  - from: 7
+    to: 3
+"#,
+    )
+    .unwrap();
+    let nm_vlan_set = NmSettingVlan::from(&conf);
+    assert_eq!(nm_vlan_set.protocol, Some(NmVlanProtocol::Dot1Ad));
+    assert_eq!(
+        nm_vlan_set.ingress_priority_map,
+        Some(vec!["7:3".to_string()])
+    );
+    assert_eq!(nm_vlan_set.egress_priority_map, None);
+
+    assert_eq!(nm_vlan_to_nmstate(&nm_vlan_set), conf);
+}
+
+#[test]
+fn test_vlan_parent_uuid_not_shown() {
+    let mut nm_vlan_set = NmSettingVlan::new();
+    nm_vlan_set.parent =
+        Some("4b1d1b8c-3a1f-4e7c-9c53-9a0e5f3b6a7d".to_string());
+    nm_vlan_set.id = Some(10);
+    let conf = nm_vlan_to_nmstate(&nm_vlan_set);
+    assert!(conf.base_iface.is_empty());
+    assert_eq!(conf.id, 10);
+}
+
+#[test]
+fn test_vlan_flags_to_nm_and_back() {
+    let conf: VlanConfig = serde_yaml::from_str(
+        r#"---
+base-iface: eth1
+id: 100
+reorder-headers: false
+gvrp: true
+mvrp: false
+loose-binding: true
+"#,
+    )
+    .unwrap();
+    let nm_vlan_set = NmSettingVlan::from(&conf);
+    assert_eq!(nm_vlan_set.flags, Some(0x2 | 0x4));
+
+    assert_eq!(nm_vlan_to_nmstate(&nm_vlan_set), conf);
+}
+
+#[test]
+fn test_vlan_flags_partial_use_nm_default() {
+    let conf: VlanConfig = serde_yaml::from_str(
+        r#"---
+base-iface: eth1
+id: 100
+mvrp: true
+"#,
+    )
+    .unwrap();
+    let nm_vlan_set = NmSettingVlan::from(&conf);
+    assert_eq!(nm_vlan_set.flags, Some(0x1 | 0x8));
+
+    let conf = nm_vlan_to_nmstate(&nm_vlan_set);
+    assert_eq!(conf.reorder_headers, Some(true));
+    assert_eq!(conf.gvrp, Some(false));
+    assert_eq!(conf.loose_binding, Some(false));
+
+    let conf: VlanConfig =
+        serde_yaml::from_str("base-iface: eth1\nid: 100\n").unwrap();
+    assert_eq!(NmSettingVlan::from(&conf).flags, None);
+}
@@ -0,0 +1,96 @@
+use nm_dbus::{NmConnection, NmSettingWirelessSecurity};
+
+use crate::{
+    nm::wifi::{gen_nm_wifi_setting, nm_wifi_to_nmstate},
+    Interface, NetworkState, WifiBand, WifiKeyManagement,
+};
+
+#[test]
+fn test_wifi_hidden_psk_keep_current() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: wlan0
+type: wifi
+wifi:
+  ssid: test-ssid
+  band: 2.4GHz
+  security:
+    key-management: wpa-psk
+    psk: <_password_hid_by_nmstate>
+"#,
+    )
+    .unwrap();
+    let wifi_iface = if let Interface::Wifi(i) = &iface {
+        i
+    } else {
+        panic!("Expecting WiFi interface, got {:?}", iface);
+    };
+    wifi_iface.validate().unwrap();
+
+    let mut nm_conn = NmConnection::new();
+    let mut nm_sec_set = NmSettingWirelessSecurity::new();
+    nm_sec_set.key_mgmt = Some("wpa-psk".to_string());
+    nm_sec_set.psk = Some("current-psk".to_string());
+    nm_conn.wireless_security = Some(nm_sec_set);
+
+    gen_nm_wifi_setting(wifi_iface, &mut nm_conn);
+
+    let nm_wifi_set = nm_conn.wireless.as_ref().unwrap();
+    assert_eq!(nm_wifi_set.ssid.as_deref(), Some("test-ssid".as_bytes()));
+    assert_eq!(nm_wifi_set.band.as_deref(), Some("bg"));
+    assert_eq!(
+        nm_conn.wireless_security.as_ref().unwrap().psk.as_deref(),
+        Some("current-psk")
+    );
+
+    let conf = nm_wifi_to_nmstate(nm_wifi_set, &nm_conn, None);
+    assert_eq!(conf.ssid, "test-ssid");
+    assert_eq!(conf.band, Some(WifiBand::Band2_4GHz));
+    let sec = conf.security.unwrap();
+    assert_eq!(sec.key_management, WifiKeyManagement::WpaPsk);
+    assert_eq!(
+        sec.psk.as_deref(),
+        Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+    );
+}
+
+#[test]
+fn test_wifi_eap_to_nm_802_1x() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: wlan0
+type: wifi
+wifi:
+  ssid: corp
+  band: 5GHz
+  security:
+    key-management: wpa-eap
+    eap:
+      method: peap
+      identity: user
+      password: my-secret-password
+      phase2-auth: mschapv2
+"#,
+    )
+    .unwrap();
+    let wifi_iface = if let Interface::Wifi(i) = &iface {
+        i
+    } else {
+        panic!("Expecting WiFi interface, got {:?}", iface);
+    };
+    let mut nm_conn = NmConnection::new();
+    gen_nm_wifi_setting(wifi_iface, &mut nm_conn);
+
+    assert_eq!(
+        nm_conn.wireless.as_ref().unwrap().band.as_deref(),
+        Some("a")
+    );
+    let nm_sec_set = nm_conn.wireless_security.as_ref().unwrap();
+    assert_eq!(nm_sec_set.key_mgmt.as_deref(), Some("wpa-eap"));
+    assert_eq!(nm_sec_set.psk, None);
+    let nm_8021x_set = nm_conn.ieee8021x.as_ref().unwrap();
+    assert_eq!(nm_8021x_set.eap, Some(vec!["peap".to_string()]));
+    assert_eq!(nm_8021x_set.identity.as_deref(), Some("user"));
+    assert_eq!(nm_8021x_set.password.as_deref(), Some("my-secret-password"));
+    assert_eq!(nm_8021x_set.phase2_auth.as_deref(), Some("mschapv2"));
+}
@@ -7,18 +7,19 @@ pub(crate) fn nm_version() -> Result<String, NmstateError> {
 }
 
 // This helper function will help us to avoid introducing new dependencies to the project.
-pub(crate) fn nm_supports_accept_all_mac_addresses_mode(
+fn nm_version_is_at_least(
+    supported_version: &[u32],
 ) -> Result<bool, NmstateError> {
     let version = nm_version()?;
     let version_split = version.split('.');
-    let supported_version = Vec::<u32>::from([1, 32]);
     let mut supported_elem = supported_version.iter();
 
     for v_elem in version_split {
         if v_elem.chars().all(char::is_numeric) {
             if let Some(supported_v) = supported_elem.next() {
-                if v_elem.parse::<u32>().unwrap_or_default() < *supported_v {
-                    return Ok(false);
+                let v = v_elem.parse::<u32>().unwrap_or_default();
+                if v != *supported_v {
+                    return Ok(v > *supported_v);
                 }
             } else {
                 return Ok(true);
@@ -28,3 +29,24 @@ pub(crate) fn nm_supports_accept_all_mac_addresses_mode(
 
     Ok(true)
 }
+
+pub(crate) fn nm_supports_accept_all_mac_addresses_mode(
+) -> Result<bool, NmstateError> {
+    nm_version_is_at_least(&[1, 32])
+}
+
+pub(crate) fn nm_supports_clat() -> Result<bool, NmstateError> {
+    nm_version_is_at_least(&[1, 52])
+}
+
+pub(crate) fn nm_supports_hsr() -> Result<bool, NmstateError> {
+    nm_version_is_at_least(&[1, 46])
+}
+
+pub(crate) fn nm_supports_ipvlan() -> Result<bool, NmstateError> {
+    nm_version_is_at_least(&[1, 52])
+}
+
+pub(crate) fn nm_supports_macsec_offload() -> Result<bool, NmstateError> {
+    nm_version_is_at_least(&[1, 46])
+}
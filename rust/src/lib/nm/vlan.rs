@@ -1,11 +1,116 @@
-use crate::VlanConfig;
-use nm_dbus::NmSettingVlan;
+use crate::{VlanConfig, VlanProtocol, VlanQosMapping};
+use nm_dbus::{NmSettingVlan, NmVlanProtocol};
+
+const NM_VLAN_FLAG_REORDER_HEADERS: u32 = 0x1;
+const NM_VLAN_FLAG_GVRP: u32 = 0x2;
+const NM_VLAN_FLAG_LOOSE_BINDING: u32 = 0x4;
+const NM_VLAN_FLAG_MVRP: u32 = 0x8;
+// NetworkManager enables reorder headers by default
+const NM_VLAN_FLAGS_DEFAULT: u32 = NM_VLAN_FLAG_REORDER_HEADERS;
 
 impl From<&VlanConfig> for NmSettingVlan {
     fn from(config: &VlanConfig) -> Self {
         let mut settings = NmSettingVlan::new();
         settings.id = Some(config.id.into());
         settings.parent = Some(config.base_iface.clone());
+        settings.protocol = config.protocol.map(|p| match p {
+            VlanProtocol::Ieee8021Q => NmVlanProtocol::Dot1Q,
+            VlanProtocol::Ieee8021Ad => NmVlanProtocol::Dot1Ad,
+        });
+        settings.ingress_priority_map =
+            config.ingress_qos_map.as_deref().map(qos_map_to_nm);
+        settings.egress_priority_map =
+            config.egress_qos_map.as_deref().map(qos_map_to_nm);
+        settings.flags = vlan_flags_to_nm(config);
         settings
     }
 }
+
+fn vlan_flags_to_nm(config: &VlanConfig) -> Option<u32> {
+    let flags = [
+        (config.reorder_headers, NM_VLAN_FLAG_REORDER_HEADERS),
+        (config.gvrp, NM_VLAN_FLAG_GVRP),
+        (config.loose_binding, NM_VLAN_FLAG_LOOSE_BINDING),
+        (config.mvrp, NM_VLAN_FLAG_MVRP),
+    ];
+    if flags.iter().all(|(v, _)| v.is_none()) {
+        return None;
+    }
+    let mut nm_flags = NM_VLAN_FLAGS_DEFAULT;
+    for (value, flag) in flags {
+        match value {
+            Some(true) => nm_flags |= flag,
+            Some(false) => nm_flags &= !flag,
+            None => (),
+        }
+    }
+    Some(nm_flags)
+}
+
+fn qos_map_to_nm(qos_map: &[VlanQosMapping]) -> Vec<String> {
+    qos_map
+        .iter()
+        .map(|qos| format!("{}:{}", qos.from, qos.to))
+        .collect()
+}
+
+fn nm_qos_map_to_nmstate(nm_qos_map: &[String]) -> Vec<VlanQosMapping> {
+    let mut ret = Vec::new();
+    for nm_qos in nm_qos_map {
+        match nm_qos
+            .split_once(':')
+            .and_then(|(f, t)| Some((f.parse().ok()?, t.parse().ok()?)))
+        {
+            Some((from, to)) => ret.push(VlanQosMapping { from, to }),
+            None => {
+                log::warn!("Invalid VLAN priority mapping {}", nm_qos);
+            }
+        }
+    }
+    ret
+}
+
+pub(crate) fn nm_vlan_to_nmstate(nm_vlan_set: &NmSettingVlan) -> VlanConfig {
+    VlanConfig {
+        // The parent might be the UUID of parent profile, leave it empty
+        // for interface name provided by kernel.
+        base_iface: nm_vlan_set
+            .parent
+            .as_deref()
+            .filter(|p| !is_uuid(p))
+            .map(|p| p.to_string())
+            .unwrap_or_default(),
+        id: nm_vlan_set.id.unwrap_or_default() as u16,
+        protocol: nm_vlan_set.protocol.map(|p| match p {
+            NmVlanProtocol::Dot1Q => VlanProtocol::Ieee8021Q,
+            NmVlanProtocol::Dot1Ad => VlanProtocol::Ieee8021Ad,
+        }),
+        ingress_qos_map: nm_vlan_set
+            .ingress_priority_map
+            .as_deref()
+            .map(nm_qos_map_to_nmstate),
+        egress_qos_map: nm_vlan_set
+            .egress_priority_map
+            .as_deref()
+            .map(nm_qos_map_to_nmstate),
+        reorder_headers: nm_vlan_set
+            .flags
+            .map(|f| f & NM_VLAN_FLAG_REORDER_HEADERS > 0),
+        gvrp: nm_vlan_set.flags.map(|f| f & NM_VLAN_FLAG_GVRP > 0),
+        mvrp: nm_vlan_set.flags.map(|f| f & NM_VLAN_FLAG_MVRP > 0),
+        loose_binding: nm_vlan_set
+            .flags
+            .map(|f| f & NM_VLAN_FLAG_LOOSE_BINDING > 0),
+    }
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.chars().enumerate().all(|(i, c)| {
+            if [8, 13, 18, 23].contains(&i) {
+                c == '-'
+            } else {
+                c.is_ascii_hexdigit()
+            }
+        })
+}
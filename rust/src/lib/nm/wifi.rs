@@ -0,0 +1,228 @@
+use log::warn;
+use nm_dbus::{
+    NmApi, NmConnection, NmSetting8021X, NmSettingWireless,
+    NmSettingWirelessSecurity,
+};
+
+use crate::{
+    NetworkState, WifiBand, WifiConfig, WifiEapConfig, WifiInterface,
+    WifiKeyManagement, WifiSecurityConfig,
+};
+
+pub(crate) const NM_SETTING_WIRELESS_SECURITY_SETTING_NAME: &str =
+    "802-11-wireless-security";
+pub(crate) const NM_SETTING_802_1X_SETTING_NAME: &str = "802-1x";
+
+const NM_WIFI_MODE_INFRA: &str = "infrastructure";
+const NM_WIFI_BAND_A: &str = "a";
+const NM_WIFI_BAND_BG: &str = "bg";
+const NM_WIFI_KEY_MGMT_WPA_PSK: &str = "wpa-psk";
+const NM_WIFI_KEY_MGMT_WPA_EAP: &str = "wpa-eap";
+
+pub(crate) fn gen_nm_wifi_setting(
+    iface: &WifiInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.wifi.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_wifi_set =
+        nm_conn.wireless.as_ref().cloned().unwrap_or_default();
+    nm_wifi_set.ssid = Some(conf.ssid.as_bytes().to_vec());
+    nm_wifi_set.mode = Some(NM_WIFI_MODE_INFRA.to_string());
+    if let Some(band) = conf.band {
+        nm_wifi_set.band = Some(
+            match band {
+                WifiBand::Band2_4GHz => NM_WIFI_BAND_BG,
+                WifiBand::Band5GHz => NM_WIFI_BAND_A,
+            }
+            .to_string(),
+        );
+    }
+    nm_conn.wireless = Some(nm_wifi_set);
+
+    let sec = match conf.security.as_ref() {
+        Some(s) => s,
+        None => {
+            nm_conn.wireless_security = None;
+            nm_conn.ieee8021x = None;
+            return;
+        }
+    };
+    let mut nm_sec_set = nm_conn
+        .wireless_security
+        .as_ref()
+        .cloned()
+        .unwrap_or_default();
+    // The hidden secret means preserving current one
+    if let Some(psk) = secret_to_nm(sec.psk.as_deref()) {
+        nm_sec_set.psk = Some(psk.to_string());
+    }
+    match sec.key_management {
+        WifiKeyManagement::WpaPsk => {
+            nm_sec_set.key_mgmt = Some(NM_WIFI_KEY_MGMT_WPA_PSK.to_string());
+            nm_conn.ieee8021x = None;
+        }
+        WifiKeyManagement::WpaEap => {
+            nm_sec_set.key_mgmt = Some(NM_WIFI_KEY_MGMT_WPA_EAP.to_string());
+            nm_sec_set.psk = None;
+            if let Some(eap) = sec.eap.as_ref() {
+                gen_nm_802_1x_setting(eap, nm_conn);
+            }
+        }
+    }
+    nm_conn.wireless_security = Some(nm_sec_set);
+}
+
+fn gen_nm_802_1x_setting(eap: &WifiEapConfig, nm_conn: &mut NmConnection) {
+    let mut nm_8021x_set =
+        nm_conn.ieee8021x.as_ref().cloned().unwrap_or_default();
+    nm_8021x_set.eap = Some(vec![eap.method.to_string()]);
+    if let Some(v) = eap.identity.as_ref() {
+        nm_8021x_set.identity = Some(v.to_string());
+    }
+    if let Some(v) = secret_to_nm(eap.password.as_deref()) {
+        nm_8021x_set.password = Some(v.to_string());
+    }
+    if let Some(v) = eap.phase2_auth.as_ref() {
+        nm_8021x_set.phase2_auth = Some(v.to_string());
+    }
+    nm_conn.ieee8021x = Some(nm_8021x_set);
+}
+
+fn secret_to_nm(secret: Option<&str>) -> Option<&str> {
+    secret.filter(|s| *s != NetworkState::PASSWORD_HID_BY_NMSTATE)
+}
+
+// The secrets are stored in the setting profile or secret agent,
+// NetworkManager only expose them via GetSecrets.
+pub(crate) fn nm_wifi_secrets_get(
+    nm_api: &NmApi,
+    nm_saved_conn: &NmConnection,
+) -> NmConnection {
+    let mut ret = NmConnection::new();
+    for (setting_name, has_setting) in [
+        (
+            NM_SETTING_WIRELESS_SECURITY_SETTING_NAME,
+            nm_saved_conn.wireless_security.is_some(),
+        ),
+        (
+            NM_SETTING_802_1X_SETTING_NAME,
+            nm_saved_conn.ieee8021x.is_some(),
+        ),
+    ] {
+        if !has_setting {
+            continue;
+        }
+        match nm_api.connection_secrets_get(nm_saved_conn, setting_name) {
+            Ok(nm_secrets) => {
+                if nm_secrets.wireless_security.is_some() {
+                    ret.wireless_security = nm_secrets.wireless_security;
+                }
+                if nm_secrets.ieee8021x.is_some() {
+                    ret.ieee8021x = nm_secrets.ieee8021x;
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to retrieve {} secrets of connection {:?}: {}",
+                    setting_name,
+                    nm_saved_conn.uuid(),
+                    e
+                );
+            }
+        }
+    }
+    ret
+}
+
+pub(crate) fn nm_wifi_secrets_merge(
+    nm_api: &NmApi,
+    nm_conn: &mut NmConnection,
+) {
+    let nm_secrets = nm_wifi_secrets_get(nm_api, nm_conn);
+    if let (Some(nm_sec_set), Some(psk)) = (
+        nm_conn.wireless_security.as_mut(),
+        nm_secrets.wireless_security.and_then(|s| s.psk),
+    ) {
+        nm_sec_set.psk = Some(psk);
+    }
+    if let (Some(nm_8021x_set), Some(password)) = (
+        nm_conn.ieee8021x.as_mut(),
+        nm_secrets.ieee8021x.and_then(|s| s.password),
+    ) {
+        nm_8021x_set.password = Some(password);
+    }
+}
+
+// Without secrets retrieved, the secrets are shown as hidden
+pub(crate) fn nm_wifi_to_nmstate(
+    nm_wifi_set: &NmSettingWireless,
+    nm_conn: &NmConnection,
+    nm_secrets: Option<&NmConnection>,
+) -> WifiConfig {
+    WifiConfig {
+        ssid: nm_wifi_set
+            .ssid
+            .as_ref()
+            .map(|s| String::from_utf8_lossy(s).to_string())
+            .unwrap_or_default(),
+        band: match nm_wifi_set.band.as_deref() {
+            Some(NM_WIFI_BAND_A) => Some(WifiBand::Band5GHz),
+            Some(NM_WIFI_BAND_BG) => Some(WifiBand::Band2_4GHz),
+            _ => None,
+        },
+        security: nm_conn.wireless_security.as_ref().and_then(|nm_sec_set| {
+            nm_wifi_sec_to_nmstate(
+                nm_sec_set,
+                nm_conn.ieee8021x.as_ref(),
+                nm_secrets,
+            )
+        }),
+    }
+}
+
+fn nm_wifi_sec_to_nmstate(
+    nm_sec_set: &NmSettingWirelessSecurity,
+    nm_8021x_set: Option<&NmSetting8021X>,
+    nm_secrets: Option<&NmConnection>,
+) -> Option<WifiSecurityConfig> {
+    match nm_sec_set.key_mgmt.as_deref() {
+        Some(NM_WIFI_KEY_MGMT_WPA_PSK) => Some(WifiSecurityConfig {
+            key_management: WifiKeyManagement::WpaPsk,
+            psk: match nm_secrets {
+                Some(c) => {
+                    c.wireless_security.as_ref().and_then(|s| s.psk.clone())
+                }
+                None => Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string()),
+            },
+            eap: None,
+        }),
+        Some(NM_WIFI_KEY_MGMT_WPA_EAP) => Some(WifiSecurityConfig {
+            key_management: WifiKeyManagement::WpaEap,
+            psk: None,
+            eap: nm_8021x_set.map(|nm_8021x_set| WifiEapConfig {
+                method: nm_8021x_set
+                    .eap
+                    .as_ref()
+                    .and_then(|m| m.first().cloned())
+                    .unwrap_or_default(),
+                identity: nm_8021x_set.identity.clone(),
+                password: match nm_secrets {
+                    Some(c) => {
+                        c.ieee8021x.as_ref().and_then(|s| s.password.clone())
+                    }
+                    None => {
+                        Some(NetworkState::PASSWORD_HID_BY_NMSTATE.to_string())
+                    }
+                },
+                phase2_auth: nm_8021x_set.phase2_auth.clone(),
+            }),
+        }),
+        other => {
+            warn!("Unsupported WiFi key management {:?}", other);
+            None
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use nm_dbus::{NmConnection, NmSettingWireGuard, NmWireGuardPeer};
+
+use crate::{WireGuardConfig, WireGuardInterface, WireGuardPeerConfig};
+
+pub(crate) fn gen_nm_wireguard_setting(
+    iface: &WireGuardInterface,
+    nm_conn: &mut NmConnection,
+) {
+    let conf = match iface.wireguard.as_ref() {
+        Some(c) => c,
+        None => return,
+    };
+    let mut nm_wg_set = nm_conn.wireguard.as_ref().cloned().unwrap_or_default();
+    if let Some(v) = conf.private_key.as_ref() {
+        nm_wg_set.private_key = Some(v.to_string());
+    }
+    if let Some(v) = conf.listen_port {
+        nm_wg_set.listen_port = Some(v.into());
+    }
+    if let Some(v) = conf.fwmark {
+        nm_wg_set.fwmark = Some(v);
+    }
+    if let Some(peers) = conf.peers.as_ref() {
+        nm_wg_set.peers =
+            Some(peers.iter().map(gen_nm_wireguard_peer).collect());
+    }
+    nm_conn.wireguard = Some(nm_wg_set);
+}
+
+fn gen_nm_wireguard_peer(peer: &WireGuardPeerConfig) -> NmWireGuardPeer {
+    let mut nm_peer = NmWireGuardPeer::new();
+    nm_peer.public_key = Some(peer.public_key.to_string());
+    nm_peer.endpoint = peer.endpoint.clone();
+    nm_peer.allowed_ips = Some(peer.allowed_ips.clone());
+    nm_peer.persistent_keepalive = peer.persistent_keepalive;
+    nm_peer.preshared_key = peer.preshared_key.clone();
+    nm_peer
+}
+
+// Secrets are not included as applied connection does not hold them
+pub(crate) fn nm_wireguard_to_nmstate(
+    nm_wg_set: &NmSettingWireGuard,
+) -> WireGuardConfig {
+    WireGuardConfig {
+        private_key: None,
+        listen_port: nm_wg_set.listen_port.map(|p| p as u16),
+        fwmark: nm_wg_set.fwmark,
+        peers: nm_wg_set.peers.as_ref().map(|nm_peers| {
+            nm_peers
+                .iter()
+                .map(|nm_peer| WireGuardPeerConfig {
+                    public_key: nm_peer.public_key.clone().unwrap_or_default(),
+                    endpoint: nm_peer.endpoint.clone(),
+                    allowed_ips: nm_peer
+                        .allowed_ips
+                        .clone()
+                        .unwrap_or_default(),
+                    persistent_keepalive: nm_peer.persistent_keepalive,
+                    preshared_key: None,
+                })
+                .collect()
+        }),
+    }
+}
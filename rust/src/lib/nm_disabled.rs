@@ -0,0 +1,101 @@
+// Placeholder of NetworkManager backend when `nm-backend` feature is disabled,
+// only kernel mode is supported in this case.
+use crate::{ErrorKind, Interfaces, NetworkState, NmstateError, PersistIssue};
+
+fn nm_disabled_error() -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::DependencyError,
+        "NetworkManager backend is not enabled at build time, please use \
+        kernel only mode"
+            .to_string(),
+    );
+    log::error!("{}", e);
+    e
+}
+
+pub(crate) fn nm_retrieve(
+    _running_config_only: bool,
+    _include_secrets: bool,
+) -> Result<NetworkState, NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_apply(
+    _add_net_state: &NetworkState,
+    _chg_net_state: &NetworkState,
+    _del_net_state: &NetworkState,
+    _cur_net_state: &NetworkState,
+    _des_net_state: &NetworkState,
+    _checkpoint: &str,
+) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_gen_conf(
+    _add_net_state: &NetworkState,
+    _des_net_state: &NetworkState,
+) -> Result<Vec<String>, NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_checkpoint_create(
+    _timeout: u32,
+) -> Result<String, NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_checkpoint_get_last() -> Result<String, NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_checkpoint_rollback(
+    _checkpoint: &str,
+) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_checkpoint_destroy(
+    _checkpoint: &str,
+) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_checkpoint_timeout_extend(
+    _checkpoint: &str,
+    _added_time_sec: u32,
+) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_hostname_get() -> Result<Option<String>, NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_hostname_set(_hostname: &str) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+// The systemd-hostnamed D-Bus API is provided by the same D-Bus crate of
+// NetworkManager backend.
+pub(crate) fn hostnamed_static_hostname_get(
+) -> Result<Option<String>, NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn hostnamed_static_hostname_set(
+    _hostname: &str,
+) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn hostnamed_hostname_set(
+    _hostname: &str,
+) -> Result<(), NmstateError> {
+    Err(nm_disabled_error())
+}
+
+pub(crate) fn nm_persist_check(
+    _ifaces: &Interfaces,
+) -> Result<Vec<PersistIssue>, NmstateError> {
+    Err(nm_disabled_error())
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    ErrorKind, LinuxBridgePortTunkTag, LinuxBridgePortVlanConfig,
+    LinuxBridgePortVlanMode, LinuxBridgePortVlanRange, NmstateError,
+};
+
+pub(crate) const OVSDB_SOCKET_PATH: &str = "/run/openvswitch/db.sock";
+const OVSDB_DB_NAME: &str = "Open_vSwitch";
+const OVSDB_TIMEOUT: Duration = Duration::from_secs(5);
+const OVSDB_TRANSACT_RETRY_COUNT: u32 = 5;
+const OVSDB_TRANSACT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+const OVSDB_TRANSACT_RETRY_INTERVAL_MAX: Duration = Duration::from_secs(2);
+// RFC 7047 errors caused by busy server instead of the transaction itself
+const OVSDB_RETRIABLE_ERRORS: [&str; 3] =
+    ["timed out", "resources exhausted", "I/O error"];
+
+// Diagnostic information of OpenvSwitch database, the `ovs-db` section of
+// desired state is ignored when OVSDB is not reachable.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OvsDbStatus {
+    pub socket_path: String,
+    pub socket_exists: bool,
+    pub connected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+}
+
+impl OvsDbStatus {
+    pub fn retrieve() -> Self {
+        Self::retrieve_from(OVSDB_SOCKET_PATH)
+    }
+
+    pub(crate) fn retrieve_from(socket_path: &str) -> Self {
+        let mut status = Self {
+            socket_path: socket_path.to_string(),
+            socket_exists: Path::new(socket_path).exists(),
+            ..Default::default()
+        };
+        let mut client = match OvsDbClient::connect(socket_path) {
+            Ok(c) => c,
+            Err(e) => {
+                status.error = Some(e);
+                return status;
+            }
+        };
+        status.connected = true;
+        match client.call("get_schema", json!([OVSDB_DB_NAME])) {
+            Ok(reply) => {
+                status.schema_version =
+                    reply.get("version").and_then(value_to_string);
+            }
+            Err(e) => {
+                status.error = Some(e);
+                return status;
+            }
+        }
+        match client.transact(&[json!({
+            "op": "select",
+            "table": OVSDB_DB_NAME,
+            "where": [],
+            "columns": ["ovs_version"],
+        })]) {
+            Ok(reply) => {
+                status.server_version = reply
+                    .get(0)
+                    .and_then(|r| r.get("rows"))
+                    .and_then(|r| r.get(0))
+                    .and_then(|r| r.get("ovs_version"))
+                    .and_then(value_to_string);
+            }
+            Err(e) => status.error = Some(e.msg().to_string()),
+        }
+        status
+    }
+}
+
+// OVSDB JSON-RPC client(RFC 7047) with only synchronous call supported.
+pub(crate) struct OvsDbClient {
+    stream: UnixStream,
+    id: u64,
+}
+
+impl OvsDbClient {
+    pub(crate) fn connect(socket_path: &str) -> Result<Self, String> {
+        let stream = UnixStream::connect(socket_path).map_err(|e| {
+            format!("Failed to connect to {}: {}", socket_path, e)
+        })?;
+        stream
+            .set_read_timeout(Some(OVSDB_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(OVSDB_TIMEOUT)))
+            .map_err(|e| format!("Failed to set socket timeout: {}", e))?;
+        Ok(Self { stream, id: 0 })
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.id += 1;
+        let request = json!({
+            "method": method,
+            "params": params,
+            "id": self.id,
+        });
+        self.stream
+            .write_all(request.to_string().as_bytes())
+            .map_err(|e| format!("Failed to send {} request: {}", method, e))?;
+        let replies =
+            serde_json::Deserializer::from_reader(&self.stream).into_iter();
+        for reply in replies {
+            let reply: Value = reply.map_err(|e| {
+                format!("Failed to receive {} reply: {}", method, e)
+            })?;
+            // Skip notifications and echo request from server
+            if reply.get("id") != Some(&json!(self.id)) {
+                continue;
+            }
+            return match reply.get("error") {
+                Some(error) if !error.is_null() => {
+                    Err(format!("OVSDB {} failed: {}", method, error))
+                }
+                _ => Ok(reply.get("result").cloned().unwrap_or_default()),
+            };
+        }
+        Err(format!("OVSDB closed connection on {} request", method))
+    }
+
+    // Retry with exponential backoff when OVSDB is too busy to commit the
+    // transaction, other failures are reported with offending operation.
+    pub(crate) fn transact(
+        &mut self,
+        operations: &[Value],
+    ) -> Result<Value, NmstateError> {
+        let mut params = vec![json!(OVSDB_DB_NAME)];
+        params.extend_from_slice(operations);
+        let mut interval = OVSDB_TRANSACT_RETRY_INTERVAL;
+        let mut retry_left = OVSDB_TRANSACT_RETRY_COUNT;
+        loop {
+            let reply = self
+                .call("transact", Value::Array(params.clone()))
+                .map_err(|e| {
+                    let e = NmstateError::new(ErrorKind::OvsDbError, e);
+                    log::error!("{}", e);
+                    e
+                })?;
+            let (index, error) = match find_transact_error(&reply) {
+                Some(e) => e,
+                None => return Ok(reply),
+            };
+            let error_name = error
+                .get("error")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if retry_left > 0 && OVSDB_RETRIABLE_ERRORS.contains(&error_name) {
+                log::info!(
+                    "OVSDB transact failed with retriable error {}, \
+                    retrying in {:?}",
+                    error,
+                    interval
+                );
+                retry_left -= 1;
+                sleep(interval);
+                interval = std::cmp::min(
+                    interval * 2,
+                    OVSDB_TRANSACT_RETRY_INTERVAL_MAX,
+                );
+                continue;
+            }
+            // The extra error after all operations results is commit failure
+            let e = NmstateError::new(
+                ErrorKind::OvsDbError,
+                match operations.get(index) {
+                    Some(op) => format!(
+                        "OVSDB transact operation {} failed: {}",
+                        op, error
+                    ),
+                    None => format!("OVSDB transact commit failed: {}", error),
+                },
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    }
+
+    // The VLAN configuration of OVS ports indexed by port name
+    #[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+    pub(crate) fn get_port_vlan_confs(
+        &mut self,
+    ) -> Result<HashMap<String, LinuxBridgePortVlanConfig>, NmstateError> {
+        let reply = self.transact(&[json!({
+            "op": "select",
+            "table": "Port",
+            "where": [],
+            "columns": ["name", "tag", "trunks", "vlan_mode"],
+        })])?;
+        let mut ret = HashMap::new();
+        if let Some(rows) = reply
+            .get(0)
+            .and_then(|r| r.get("rows"))
+            .and_then(Value::as_array)
+        {
+            for row in rows {
+                if let (Some(name), Some(vlan_conf)) = (
+                    row.get("name").and_then(value_to_string),
+                    ovsdb_port_vlan_conf(row),
+                ) {
+                    ret.insert(name, vlan_conf);
+                }
+            }
+        }
+        Ok(ret)
+    }
+}
+
+// Return the index and error object of the first failed operation
+fn find_transact_error(reply: &Value) -> Option<(usize, &Value)> {
+    reply.as_array()?.iter().enumerate().find(|(_, result)| {
+        result
+            .get("error")
+            .map(|e| !e.is_null())
+            .unwrap_or_default()
+    })
+}
+
+// The optional column is stored as `["set", []]` when not defined
+fn value_to_string(value: &Value) -> Option<String> {
+    value.as_str().map(|s| s.to_string())
+}
+
+// The set column is stored as plain value when holding single member
+#[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+fn value_to_u16s(value: &Value) -> Vec<u16> {
+    let members = match value.as_array() {
+        Some(v) if v.first().and_then(Value::as_str) == Some("set") => {
+            v.get(1).and_then(Value::as_array).map(Vec::as_slice)
+        }
+        _ => Some(std::slice::from_ref(value)),
+    };
+    members
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_u64)
+        .filter_map(|v| u16::try_from(v).ok())
+        .collect()
+}
+
+// When `vlan_mode` is not defined, OVS treat port with `tag` as access port,
+// otherwise trunk port.
+#[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+fn ovsdb_port_vlan_conf(row: &Value) -> Option<LinuxBridgePortVlanConfig> {
+    let tag = row
+        .get("tag")
+        .and_then(|v| value_to_u16s(v).first().copied());
+    let trunks = row.get("trunks").map(value_to_u16s).unwrap_or_default();
+    let (mode, enable_native) =
+        match row.get("vlan_mode").and_then(value_to_string).as_deref() {
+            Some("access") => (LinuxBridgePortVlanMode::Access, None),
+            Some("trunk") => (LinuxBridgePortVlanMode::Trunk, Some(false)),
+            Some("native-untagged") => {
+                (LinuxBridgePortVlanMode::Trunk, Some(true))
+            }
+            Some(m) => {
+                log::warn!("Unsupported OVS port VLAN mode {}", m);
+                return None;
+            }
+            None if tag.is_some() => (LinuxBridgePortVlanMode::Access, None),
+            None if !trunks.is_empty() => {
+                (LinuxBridgePortVlanMode::Trunk, Some(false))
+            }
+            None => return None,
+        };
+    let trunk_tags = if mode == LinuxBridgePortVlanMode::Trunk {
+        Some(vlan_ids_to_trunk_tags(trunks))
+    } else {
+        None
+    };
+    Some(LinuxBridgePortVlanConfig {
+        mode: Some(mode),
+        tag,
+        trunk_tags,
+        enable_native,
+    })
+}
+
+// OVSDB stores each VLAN ID of trunks, merge the consecutive ones into range
+#[cfg_attr(not(feature = "nm-backend"), allow(dead_code))]
+fn vlan_ids_to_trunk_tags(
+    mut vlan_ids: Vec<u16>,
+) -> Vec<LinuxBridgePortTunkTag> {
+    vlan_ids.sort_unstable();
+    vlan_ids.dedup();
+    let mut ranges: Vec<(u16, u16)> = Vec::new();
+    for vlan_id in vlan_ids {
+        match ranges.last_mut() {
+            Some((_, max)) if *max + 1 == vlan_id => *max = vlan_id,
+            _ => ranges.push((vlan_id, vlan_id)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(min, max)| {
+            if min == max {
+                LinuxBridgePortTunkTag::Id(min)
+            } else {
+                LinuxBridgePortTunkTag::IdRange(LinuxBridgePortVlanRange {
+                    min,
+                    max,
+                })
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+use crate::{Interface, InterfaceType, Interfaces, MacVtapInterface};
+
+// Config which will not survive reboot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PersistIssue {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub iface_type: InterfaceType,
+    pub reason: PersistIssueReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PersistIssueReason {
+    // No profile or only the one generated by NetworkManager for externally
+    // configured interface.
+    NoProfile,
+    // Profile is stored in /run or flagged as volatile.
+    InMemoryOnly,
+    // Profile holds changes not saved to disk yet.
+    UnsavedChanges,
+    // Interface is desired up, but profile will not be activated on boot.
+    AutoconnectDisabled,
+    // Interface is desired absent, but profile is still stored on disk.
+    ProfileOnDisk,
+    // Owner of MacVtap tap device is changed at runtime only, as no backend
+    // could store it.
+    RuntimeOnlyTapOwner,
+}
+
+impl std::fmt::Display for PersistIssueReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::NoProfile => "no persistent profile",
+                Self::InMemoryOnly => "profile is stored in memory only",
+                Self::UnsavedChanges => "profile has changes not saved to disk",
+                Self::AutoconnectDisabled => "profile has autoconnect disabled",
+                Self::ProfileOnDisk =>
+                    "interface is absent but profile still on disk",
+                Self::RuntimeOnlyTapOwner =>
+                    "owner of tap device is changed at runtime only",
+            }
+        )
+    }
+}
+
+impl std::fmt::Display for PersistIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}: {}", self.name, self.iface_type, self.reason)
+    }
+}
+
+// The settings applied at runtime after backend, they are lost once the
+// interface recreated. The tap device is owned by root by default.
+pub(crate) fn get_runtime_only_issues(
+    ifaces: &Interfaces,
+) -> Vec<PersistIssue> {
+    let mut ret = Vec::new();
+    for iface in ifaces.kernel_ifaces.values().filter(|i| i.is_up()) {
+        if let Interface::MacVtap(MacVtapInterface {
+            mac_vtap: Some(conf),
+            ..
+        }) = iface
+        {
+            if conf.tap_owner.unwrap_or_default() != 0
+                || conf.tap_group.unwrap_or_default() != 0
+            {
+                ret.push(PersistIssue {
+                    name: iface.name().to_string(),
+                    iface_type: iface.iface_type(),
+                    reason: PersistIssueReason::RuntimeOnlyTapOwner,
+                });
+            }
+        }
+    }
+    ret.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    ret
+}
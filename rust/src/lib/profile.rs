@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BondConfig, BondInterface, BondMode, ErrorKind, EthernetInterface,
+    Interface, InterfaceType, Interfaces, LinuxBridgeConfig,
+    LinuxBridgeInterface, LinuxBridgePortConfig, NmstateError, VlanConfig,
+    VlanInterface,
+};
+
+const DEFAULT_BOND_NAME: &str = "bond0";
+const DEFAULT_BRIDGE_NAME: &str = "br0";
+
+// Profile is a short description of common network topology which will be
+// expanded to full interfaces before applying.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum NetworkProfile {
+    BondedUplink(BondedUplinkProfile),
+    BridgedUplink(BridgedUplinkProfile),
+    VlanUplink(VlanUplinkProfile),
+}
+
+impl NetworkProfile {
+    pub fn gen_ifaces(&self) -> Result<Interfaces, NmstateError> {
+        let mut ifaces = Interfaces::new();
+        let ifaces_vec = match self {
+            Self::BondedUplink(p) => p.gen_ifaces()?,
+            Self::BridgedUplink(p) => p.gen_ifaces()?,
+            Self::VlanUplink(p) => p.gen_ifaces(),
+        };
+        for iface in ifaces_vec {
+            ifaces.push(iface);
+        }
+        Ok(ifaces)
+    }
+}
+
+// Expand the profiles to interfaces in the form of JSON value, the properties
+// of explicitly defined interfaces are merged into the generated ones with
+// the same name.
+pub(crate) fn expand_profiles(
+    profiles_value: &serde_json::Value,
+    ifaces_value: Option<Vec<serde_json::Value>>,
+) -> Result<Vec<serde_json::Value>, NmstateError> {
+    let profiles = Vec::<NetworkProfile>::deserialize(profiles_value)?;
+    let mut ret: Vec<serde_json::Value> = Vec::new();
+    for profile in profiles {
+        for iface in profile.gen_ifaces()?.to_vec() {
+            let iface_value = serde_json::to_value(iface)?;
+            if let Some(exist_value) = ret
+                .iter_mut()
+                .find(|v| v.get("name") == iface_value.get("name"))
+            {
+                merge_json_value(exist_value, &iface_value);
+            } else {
+                ret.push(iface_value);
+            }
+        }
+    }
+    for iface_value in ifaces_value.unwrap_or_default() {
+        if let Some(exist_value) = ret.iter_mut().find(|v| {
+            v.get("name") == iface_value.get("name")
+                && (iface_value.get("type").is_none()
+                    || v.get("type") == iface_value.get("type"))
+        }) {
+            merge_json_value(exist_value, &iface_value);
+        } else {
+            ret.push(iface_value);
+        }
+    }
+    Ok(ret)
+}
+
+fn merge_json_value(value: &mut serde_json::Value, other: &serde_json::Value) {
+    match (value, other) {
+        (
+            serde_json::Value::Object(value),
+            serde_json::Value::Object(other),
+        ) => {
+            for (key, other_value) in other {
+                match value.get_mut(key) {
+                    Some(v) => merge_json_value(v, other_value),
+                    None => {
+                        value.insert(key.to_string(), other_value.clone());
+                    }
+                }
+            }
+        }
+        (value, other) => *value = other.clone(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BondedUplinkProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub nics: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<BondMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u64>,
+}
+
+impl BondedUplinkProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn gen_ifaces(&self) -> Result<Vec<Interface>, NmstateError> {
+        validate_nics("bonded-uplink", &self.nics)?;
+        let name = self.name.as_deref().unwrap_or(DEFAULT_BOND_NAME);
+        let mut ret = Vec::new();
+
+        let mut bond_iface = BondInterface::new();
+        bond_iface.base.name = name.to_string();
+        bond_iface.base.mtu = self.mtu;
+        bond_iface.bond = Some(BondConfig {
+            mode: Some(self.mode.clone().unwrap_or(BondMode::LACP)),
+            port: Some(self.nics.clone()),
+            ..Default::default()
+        });
+        ret.push(Interface::Bond(bond_iface));
+        ret.extend(gen_port_ifaces(&self.nics, self.mtu));
+        if let Some(vlan_id) = self.vlan {
+            ret.push(gen_vlan_iface(name, vlan_id, None, self.mtu));
+        }
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct BridgedUplinkProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub nics: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u64>,
+}
+
+impl BridgedUplinkProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn gen_ifaces(&self) -> Result<Vec<Interface>, NmstateError> {
+        validate_nics("bridged-uplink", &self.nics)?;
+        let name = self.name.as_deref().unwrap_or(DEFAULT_BRIDGE_NAME);
+        let mut ret = Vec::new();
+
+        let mut br_iface = LinuxBridgeInterface::new();
+        br_iface.base.name = name.to_string();
+        br_iface.base.mtu = self.mtu;
+        br_iface.bridge = Some(LinuxBridgeConfig {
+            port: Some(
+                self.nics
+                    .iter()
+                    .map(|nic| LinuxBridgePortConfig {
+                        name: nic.to_string(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        });
+        ret.push(Interface::LinuxBridge(br_iface));
+        ret.extend(gen_port_ifaces(&self.nics, self.mtu));
+        if let Some(vlan_id) = self.vlan {
+            ret.push(gen_vlan_iface(name, vlan_id, None, self.mtu));
+        }
+        Ok(ret)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct VlanUplinkProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub nic: String,
+    pub vlan: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u64>,
+}
+
+impl VlanUplinkProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn gen_ifaces(&self) -> Vec<Interface> {
+        vec![gen_vlan_iface(
+            &self.nic,
+            self.vlan,
+            self.name.as_deref(),
+            self.mtu,
+        )]
+    }
+}
+
+fn validate_nics(profile: &str, nics: &[String]) -> Result<(), NmstateError> {
+    if nics.is_empty() {
+        let e = NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Profile {} requires at least one NIC", profile),
+        );
+        log::error!("{}", e);
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn gen_port_ifaces(nics: &[String], mtu: Option<u64>) -> Vec<Interface> {
+    nics.iter()
+        .map(|nic| {
+            let mut iface = EthernetInterface::new();
+            iface.base.name = nic.to_string();
+            iface.base.mtu = mtu;
+            Interface::Ethernet(iface)
+        })
+        .collect()
+}
+
+fn gen_vlan_iface(
+    parent: &str,
+    vlan_id: u16,
+    name: Option<&str>,
+    mtu: Option<u64>,
+) -> Interface {
+    let mut iface = VlanInterface::new();
+    iface.base.name = match name {
+        Some(n) => n.to_string(),
+        None => format!("{}.{}", parent, vlan_id),
+    };
+    iface.base.iface_type = InterfaceType::Vlan;
+    iface.base.mtu = mtu;
+    iface.vlan = Some(VlanConfig {
+        base_iface: parent.to_string(),
+        id: vlan_id,
+        ..Default::default()
+    });
+    Interface::Vlan(iface)
+}
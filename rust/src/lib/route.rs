@@ -1,16 +1,28 @@
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 
-use log::{debug, error};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::{ip::is_ipv6_addr, ErrorKind, NmstateError};
 
+const IPV4_DEFAULT_GATEWAY: &str = "0.0.0.0/0";
+const IPV6_DEFAULT_GATEWAY: &str = "::/0";
+const MAIN_ROUTE_TABLE: u32 = 254;
+const AUTO_GATEWAY_METRIC_BASE: i64 = 100;
+const AUTO_GATEWAY_METRIC_STEP: i64 = 100;
+
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct Routes {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub running: Option<Vec<RouteEntry>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<Vec<RouteEntry>>,
+    // When set to true, desired default gateways without metric defined will
+    // be assigned with distinct metrics in the order of next hop interface
+    // name.
+    #[serde(skip_serializing)]
+    pub auto_gateway_metric: Option<bool>,
 }
 
 impl Routes {
@@ -19,10 +31,60 @@ impl Routes {
     }
 
     pub fn validate(&self) -> Result<(), NmstateError> {
+        if self.running.is_some() {
+            warn!("Running routes in desired state are ignored");
+        }
         // All desire non-absent route should have next hop interface
         if let Some(config_routes) = self.config.as_ref() {
             for route in config_routes.iter().filter(|r| !r.is_absent()) {
-                if route.next_hop_iface.is_none() {
+                if let Some(weight) = route.weight {
+                    if !(RouteEntry::MIN_WEIGHT..=RouteEntry::MAX_WEIGHT)
+                        .contains(&weight)
+                    {
+                        let e = NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "Route weight should be in the range of \
+                                {}-{}: {:?}",
+                                RouteEntry::MIN_WEIGHT,
+                                RouteEntry::MAX_WEIGHT,
+                                route
+                            ),
+                        );
+                        error!("{}", e);
+                        return Err(e);
+                    }
+                }
+                if route.next_hop_iface.as_deref().map(is_iface_glob)
+                    == Some(true)
+                {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Wildcard in next hop interface is only \
+                            supported by absent route: {:?}",
+                            route
+                        ),
+                    );
+                    error!("{}", e);
+                    return Err(e);
+                }
+                if let (Some(route_type), Some(_)) =
+                    (route.route_type, route.next_hop_addr.as_ref())
+                {
+                    let e = NmstateError::new(
+                        ErrorKind::InvalidArgument,
+                        format!(
+                            "Route of type {} cannot have next hop \
+                            address: {:?}",
+                            route_type, route
+                        ),
+                    );
+                    error!("{}", e);
+                    return Err(e);
+                }
+                if route.next_hop_iface.is_none() && route.route_type.is_none()
+                {
                     let e = NmstateError::new(
                         ErrorKind::NotImplementedError,
                         format!(
@@ -39,6 +101,215 @@ impl Routes {
         Ok(())
     }
 
+    // NetworkManager can only hold routes in profile of interface
+    pub(crate) fn validate_host_wide_routes(&self) -> Result<(), NmstateError> {
+        for route in self.config.as_deref().unwrap_or(&[]) {
+            if let (Some(route_type), false) =
+                (route.route_type, route.is_absent())
+            {
+                if route.next_hop_iface.is_none() {
+                    let e = NmstateError::new(
+                        ErrorKind::NotImplementedError,
+                        format!(
+                            "Route of type {} without next hop interface \
+                            is only supported in kernel mode: {:?}",
+                            route_type, route
+                        ),
+                    );
+                    error!("{}", e);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Return typed routes to add and current typed routes to remove.
+    // Only used by kernel mode as nispor does not support route.
+    #[cfg_attr(not(feature = "kernel-backend"), allow(dead_code))]
+    pub(crate) fn gen_typed_route_changes(
+        &self,
+        current: &Self,
+    ) -> (Vec<RouteEntry>, Vec<RouteEntry>) {
+        let des_routes = self.config.as_deref().unwrap_or(&[]);
+        let cur_typed_routes: Vec<&RouteEntry> = current
+            .config
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|r| r.route_type.is_some())
+            .collect();
+        let add_routes: Vec<RouteEntry> = des_routes
+            .iter()
+            .filter(|r| !r.is_absent() && r.route_type.is_some())
+            .cloned()
+            .collect();
+        let del_routes = cur_typed_routes
+            .into_iter()
+            .filter(|cur_route| {
+                des_routes
+                    .iter()
+                    .filter(|r| r.is_absent())
+                    .any(|absent_route| absent_route.is_match(cur_route))
+                    && !add_routes.iter().any(|r| r.is_match(cur_route))
+            })
+            .cloned()
+            .collect();
+        (add_routes, del_routes)
+    }
+
+    // Two default gateways in the same route table without distinct metrics
+    // is a common misconfiguration of multi-homing host, the winning route is
+    // decided by the order of route been added.
+    // Raise error if desired default gateways on different interfaces are
+    // sharing the same explicit metric, otherwise only warn.
+    pub(crate) fn validate_default_gateways(
+        &self,
+        current: &Self,
+    ) -> Result<(), NmstateError> {
+        let des_routes = match self.config.as_ref() {
+            Some(r) => r.as_slice(),
+            None => return Ok(()),
+        };
+        let des_gws: Vec<&RouteEntry> = des_routes
+            .iter()
+            .filter(|r| !r.is_absent() && r.is_default_gateway())
+            .collect();
+        if des_gws.is_empty() {
+            return Ok(());
+        }
+        let absent_routes = flat_absent_route(
+            des_routes,
+            current.config.as_deref().unwrap_or(&[]),
+        );
+        let cur_gws: Vec<&RouteEntry> = current
+            .config
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|r| {
+                r.is_default_gateway()
+                    && !absent_routes.iter().any(|a| a.is_match(r))
+            })
+            .collect();
+
+        for des_gw in &des_gws {
+            for other_gw in des_gws.iter().chain(cur_gws.iter()) {
+                if !des_gw.is_same_gateway_group(other_gw)
+                    || des_gw.next_hop_iface == other_gw.next_hop_iface
+                    || des_gw.is_ecmp_with(other_gw)
+                {
+                    continue;
+                }
+                match (des_gw.get_metric(), other_gw.get_metric()) {
+                    (Some(metric), Some(other_metric))
+                        if metric == other_metric =>
+                    {
+                        let e = NmstateError::new(
+                            ErrorKind::InvalidArgument,
+                            format!(
+                                "Default gateways on interface {} and {} \
+                                are sharing the same metric {}, please use \
+                                distinct metrics or enable \
+                                auto-gateway-metric",
+                                des_gw.next_hop_iface.as_deref().unwrap_or(""),
+                                other_gw
+                                    .next_hop_iface
+                                    .as_deref()
+                                    .unwrap_or(""),
+                                metric
+                            ),
+                        );
+                        error!("{}", e);
+                        return Err(e);
+                    }
+                    (Some(_), Some(_)) => (),
+                    _ => {
+                        warn!(
+                            "Default gateways on interface {} and {} \
+                            have no distinct metrics defined, the winning \
+                            route is depending on backend default metric: \
+                            {:?} {:?}",
+                            des_gw.next_hop_iface.as_deref().unwrap_or(""),
+                            other_gw.next_hop_iface.as_deref().unwrap_or(""),
+                            des_gw,
+                            other_gw
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Assign distinct metrics to desired default gateways without metric
+    // defined when `auto-gateway-metric` is enabled. The metrics used by
+    // other default gateways in the same route table are skipped.
+    pub(crate) fn assign_gateway_metrics(&mut self, current: &Self) {
+        if self.auto_gateway_metric != Some(true) {
+            return;
+        }
+        let des_routes = match self.config.as_mut() {
+            Some(r) => r,
+            None => return,
+        };
+        let absent_routes = flat_absent_route(
+            des_routes.as_slice(),
+            current.config.as_deref().unwrap_or(&[]),
+        );
+        // Current default gateways which are desired again or removed
+        // should not block their metrics
+        let cur_gws =
+            current.config.as_deref().unwrap_or(&[]).iter().filter(|r| {
+                !absent_routes.iter().any(|a| a.is_match(r))
+                    && !des_routes
+                        .iter()
+                        .any(|d| !d.is_absent() && d.is_match(r))
+            });
+        let mut used_metrics: Vec<(bool, u32, i64)> = Vec::new();
+        for route in des_routes.iter().chain(cur_gws) {
+            if route.is_absent() || !route.is_default_gateway() {
+                continue;
+            }
+            if let Some(metric) = route.get_metric() {
+                used_metrics.push((
+                    route.is_ipv6(),
+                    route.get_table_id(),
+                    metric,
+                ));
+            }
+        }
+
+        let mut pending_gws: Vec<&mut RouteEntry> = des_routes
+            .iter_mut()
+            .filter(|r| {
+                !r.is_absent()
+                    && r.is_default_gateway()
+                    && r.get_metric().is_none()
+            })
+            .collect();
+        pending_gws.sort_unstable_by(|a, b| {
+            (a.is_ipv6(), a.get_table_id(), a.next_hop_iface.as_deref()).cmp(&(
+                b.is_ipv6(),
+                b.get_table_id(),
+                b.next_hop_iface.as_deref(),
+            ))
+        });
+        for route in pending_gws {
+            let mut metric = AUTO_GATEWAY_METRIC_BASE;
+            while used_metrics.contains(&(
+                route.is_ipv6(),
+                route.get_table_id(),
+                metric,
+            )) {
+                metric += AUTO_GATEWAY_METRIC_STEP;
+            }
+            info!("Assigning metric {} to default gateway {:?}", metric, route);
+            used_metrics.push((route.is_ipv6(), route.get_table_id(), metric));
+            route.metric = Some(metric);
+        }
+    }
+
     // Kernel might append additional routes. For example, IPv6 default
     // gateway will generate /128 static direct route.
     // Hence, we only check:
@@ -47,13 +318,18 @@ impl Routes {
     // * desired static route exists.
     pub fn verify(&self, current: &Self) -> Result<(), NmstateError> {
         if let Some(config_routes) = self.config.as_ref() {
-            let cur_config_routes = match current.config.as_ref() {
-                Some(c) => c.to_vec(),
-                None => Vec::new(),
-            };
+            // Index by destination to avoid linear search on every desired
+            // route, as there might be full BGP tables in current.
+            let cur_routes_index = current
+                .config
+                .as_ref()
+                .map(|c| create_route_index_by_dest(c.as_slice()))
+                .unwrap_or_default();
+            let des_routes_index =
+                create_route_index_by_dest(config_routes.as_slice());
             for desire_route in config_routes.iter().filter(|r| !r.is_absent())
             {
-                if !cur_config_routes.iter().any(|r| desire_route.is_match(r)) {
+                if find_match_route(&cur_routes_index, desire_route).is_none() {
                     let e = NmstateError::new(
                         ErrorKind::VerificationError,
                         format!(
@@ -69,15 +345,12 @@ impl Routes {
             for absent_route in config_routes.iter().filter(|r| r.is_absent()) {
                 // We ignore absent route if user is replacing old route
                 // with new one.
-                if config_routes
-                    .iter()
-                    .any(|r| (!r.is_absent()) && absent_route.is_match(r))
-                {
+                if find_match_route(&des_routes_index, absent_route).is_some() {
                     continue;
                 }
 
                 if let Some(cur_route) =
-                    cur_config_routes.iter().find(|r| absent_route.is_match(r))
+                    find_match_route(&cur_routes_index, absent_route)
                 {
                     let e = NmstateError::new(
                         ErrorKind::VerificationError,
@@ -173,7 +446,11 @@ impl Routes {
                 .collect::<Vec<RouteEntry>>();
             match ret.entry(iface_name.to_string()) {
                 Entry::Occupied(o) => {
-                    o.into_mut().extend(new_routes);
+                    // Desired route overrides the metric and weight of
+                    // current one
+                    let routes = o.into_mut();
+                    routes.retain(|r| !new_routes.contains(r));
+                    routes.extend(new_routes);
                 }
                 Entry::Vacant(v) => {
                     v.insert(new_routes);
@@ -203,6 +480,62 @@ impl Default for RouteState {
     }
 }
 
+// Where the route or route rule is originated from, only for query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteProtocol {
+    Kernel,
+    Boot,
+    Static,
+    Dhcp,
+    Ra,
+    Redirect,
+    Other,
+}
+
+impl std::fmt::Display for RouteProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Kernel => "kernel",
+                Self::Boot => "boot",
+                Self::Static => "static",
+                Self::Dhcp => "dhcp",
+                Self::Ra => "ra",
+                Self::Redirect => "redirect",
+                Self::Other => "other",
+            }
+        )
+    }
+}
+
+// Type of route which drops the packet instead of forwarding it
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteType {
+    Blackhole,
+    Prohibit,
+    Unreachable,
+}
+
+impl std::fmt::Display for RouteType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Blackhole => "blackhole",
+                Self::Prohibit => "prohibit",
+                Self::Unreachable => "unreachable",
+            }
+        )
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct RouteEntry {
@@ -224,30 +557,91 @@ pub struct RouteEntry {
     pub metric: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub table_id: Option<u32>,
+    // Name of VRF interface whose route table ID will be used as table_id
+    #[serde(skip_serializing_if = "Option::is_none", rename = "table")]
+    pub vrf_name: Option<String>,
+    // Weight of next hop in multipath(ECMP) route, routes of the same
+    // destination, table and metric with weight defined are merged into
+    // single multipath route.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u16>,
+    // Typed route does not require next hop interface
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_type: Option<RouteType>,
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub protocol: Option<RouteProtocol>,
 }
 
 impl RouteEntry {
     pub const USE_DEFAULT_METRIC: i64 = -1;
     pub const USE_DEFAULT_ROUTE_TABLE: u32 = 0;
+    pub const MIN_WEIGHT: u16 = 1;
+    pub const MAX_WEIGHT: u16 = 256;
 
     pub fn new() -> Self {
         Self::default()
     }
 
-    fn is_absent(&self) -> bool {
+    pub(crate) fn is_absent(&self) -> bool {
         matches!(self.state, Some(RouteState::Absent))
     }
 
-    fn is_match(&self, other: &Self) -> bool {
+    pub(crate) fn is_ipv6(&self) -> bool {
+        self.destination
+            .as_ref()
+            .map(|d| is_ipv6_addr(d.as_str()))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_default_gateway(&self) -> bool {
+        [IPV4_DEFAULT_GATEWAY, IPV6_DEFAULT_GATEWAY]
+            .contains(&self.destination.as_deref().unwrap_or(""))
+    }
+
+    // Typed route not bound to any interface
+    pub(crate) fn is_host_wide(&self) -> bool {
+        self.route_type.is_some() && self.next_hop_iface.is_none()
+    }
+
+    pub(crate) fn get_metric(&self) -> Option<i64> {
+        self.metric.filter(|m| *m != RouteEntry::USE_DEFAULT_METRIC)
+    }
+
+    pub(crate) fn get_table_id(&self) -> u32 {
+        match self.table_id {
+            None | Some(RouteEntry::USE_DEFAULT_ROUTE_TABLE) => {
+                MAIN_ROUTE_TABLE
+            }
+            Some(t) => t,
+        }
+    }
+
+    // Whether two default gateways are in the same route table of the same
+    // IP family.
+    fn is_same_gateway_group(&self, other: &Self) -> bool {
+        self.is_ipv6() == other.is_ipv6()
+            && self.get_table_id() == other.get_table_id()
+    }
+
+    pub(crate) fn is_match(&self, other: &Self) -> bool {
         if self.destination.as_ref().is_some()
             && self.destination != other.destination
         {
             return false;
         }
-        if self.next_hop_iface.as_ref().is_some()
-            && self.next_hop_iface != other.next_hop_iface
-        {
-            return false;
+        if let Some(next_hop_iface) = self.next_hop_iface.as_deref() {
+            if is_iface_glob(next_hop_iface) {
+                if !other
+                    .next_hop_iface
+                    .as_deref()
+                    .map(|i| glob_match(next_hop_iface, i))
+                    .unwrap_or_default()
+                {
+                    return false;
+                }
+            } else if self.next_hop_iface != other.next_hop_iface {
+                return false;
+            }
         }
 
         if self.next_hop_addr.as_ref().is_some()
@@ -267,13 +661,31 @@ impl RouteEntry {
         {
             return false;
         }
+        // Weight is only shown for next hop of multipath route
+        if self.route_type.is_some() && self.route_type != other.route_type {
+            return false;
+        }
+        if self.weight.is_some()
+            && other.weight.is_some()
+            && self.weight != other.weight
+        {
+            return false;
+        }
         true
     }
 
+    // Default gateways with weight defined are next hops of the same ECMP
+    // route, they are expected to share the same metric.
+    fn is_ecmp_with(&self, other: &Self) -> bool {
+        self.weight.is_some() && other.weight.is_some()
+    }
+
     // Return tuple of (no_absent, is_ipv4, table_id, next_hop_iface,
-    // destination, next_hop_addr)
+    // destination, next_hop_addr, route_type)
     // The metric difference is ignored
-    fn sort_key(&self) -> (bool, bool, u32, &str, &str, &str) {
+    fn sort_key(
+        &self,
+    ) -> (bool, bool, u32, &str, &str, &str, Option<RouteType>) {
         (
             !matches!(self.state, Some(RouteState::Absent)),
             !self
@@ -285,6 +697,7 @@ impl RouteEntry {
             self.next_hop_iface.as_deref().unwrap_or(""),
             self.destination.as_deref().unwrap_or(""),
             self.next_hop_addr.as_deref().unwrap_or(""),
+            self.route_type,
         )
     }
 }
@@ -319,7 +732,7 @@ fn create_route_index_by_iface(
 ) -> HashMap<&str, Vec<&RouteEntry>> {
     let mut ret: HashMap<&str, Vec<&RouteEntry>> = HashMap::new();
     for route in routes {
-        if route.is_absent() {
+        if route.is_absent() || route.is_host_wide() {
             continue;
         }
         let next_hop_iface = route.next_hop_iface.as_deref().unwrap_or("");
@@ -335,14 +748,57 @@ fn create_route_index_by_iface(
     ret
 }
 
-// All the routes sending to this function has no interface defined.
+// Absent route will be ignored
+fn create_route_index_by_dest(
+    routes: &[RouteEntry],
+) -> HashMap<&str, Vec<&RouteEntry>> {
+    let mut ret: HashMap<&str, Vec<&RouteEntry>> = HashMap::new();
+    for route in routes {
+        if route.is_absent() {
+            continue;
+        }
+        let dest = route.destination.as_deref().unwrap_or("");
+        match ret.entry(dest) {
+            Entry::Occupied(o) => {
+                o.into_mut().push(route);
+            }
+            Entry::Vacant(v) => {
+                v.insert(vec![route]);
+            }
+        };
+    }
+    ret
+}
+
+// Find the first route in index matching specified route. Only routes of the
+// same destination are checked unless the destination is not defined.
+fn find_match_route<'a>(
+    routes_index: &HashMap<&str, Vec<&'a RouteEntry>>,
+    route: &RouteEntry,
+) -> Option<&'a RouteEntry> {
+    match route.destination.as_deref() {
+        Some(dest) => routes_index
+            .get(dest)
+            .and_then(|routes| routes.iter().find(|r| route.is_match(r))),
+        None => routes_index.values().flatten().find(|r| route.is_match(r)),
+    }
+    .copied()
+}
+
+// Absent route without interface or with wildcard interface is expanded to
+// routes with matching interface of current routes.
 fn flat_absent_route(
     desire_routes: &[RouteEntry],
     cur_routes: &[RouteEntry],
 ) -> Vec<RouteEntry> {
     let mut ret: Vec<RouteEntry> = Vec::new();
     for absent_route in desire_routes.iter().filter(|r| r.is_absent()) {
-        if absent_route.next_hop_iface.is_none() {
+        if absent_route
+            .next_hop_iface
+            .as_deref()
+            .map(is_iface_glob)
+            .unwrap_or(true)
+        {
             for cur_route in cur_routes {
                 if absent_route.is_match(cur_route) {
                     let mut new_absent_route = absent_route.clone();
@@ -357,3 +813,33 @@ fn flat_absent_route(
     }
     ret
 }
+
+fn is_iface_glob(iface_name: &str) -> bool {
+    iface_name.contains('*') || iface_name.contains('?')
+}
+
+// Shell style wildcard: `*` matches any characters, `?` matches single
+// character.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of last `*` in pattern and the name position it matched to
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            p = star_p + 1;
+            n = star_n + 1;
+            backtrack = Some((star_p, n));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
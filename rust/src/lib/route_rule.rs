@@ -3,10 +3,17 @@ use std::convert::TryFrom;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{ip::is_ipv6_addr, ErrorKind, InterfaceIpAddr, NmstateError};
+use crate::{
+    ip::is_ipv6_addr, ErrorKind, InterfaceIpAddr, NmstateError, RouteProtocol,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct RouteRules {
+    // All the route rules with table action, only for query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<Vec<RouteRuleEntry>>,
+    // Route rules excluding those created by kernel for each IP family.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<Vec<RouteRuleEntry>>,
 }
@@ -16,8 +23,11 @@ impl RouteRules {
         Self::default()
     }
 
-    // * Neither ip_from nor ip_to should be defined
+    // * ip_from, ip_to and family should be of the same IP family
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
+        if self.running.is_some() {
+            log::warn!("Running route rules in desired state are ignored");
+        }
         if let Some(rules) = self.config.as_ref() {
             for rule in rules.iter().filter(|r| !r.is_absent()) {
                 rule.validate()?;
@@ -31,6 +41,7 @@ impl RouteRules {
     // * desired static rule exists.
     pub fn verify(&self, current: &Self) -> Result<(), NmstateError> {
         if let Some(rules) = self.config.as_ref() {
+            let rules = expand_rule_families(rules);
             let empty_vec: Vec<RouteRuleEntry> = Vec::new();
             let cur_rules = match current.config.as_deref() {
                 Some(c) => c,
@@ -98,11 +109,12 @@ impl RouteRules {
             .as_ref()
             .map(|c| create_rule_index_by_table_id(c.as_slice()))
             .unwrap_or_default();
-        let des_rules_index = self
+        let des_rules = self
             .config
-            .as_ref()
-            .map(|c| create_rule_index_by_table_id(c.as_slice()))
+            .as_deref()
+            .map(expand_rule_families)
             .unwrap_or_default();
+        let des_rules_index = create_rule_index_by_table_id(&des_rules);
 
         let mut table_ids_in_desire: HashSet<u32> =
             des_rules_index.keys().copied().collect();
@@ -187,12 +199,44 @@ impl Default for RouteRuleState {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteRuleFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl std::fmt::Display for RouteRuleFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Ipv4 => "ipv4",
+                Self::Ipv6 => "ipv6",
+            }
+        )
+    }
+}
+
+impl RouteRuleFamily {
+    fn from_addr(addr: &str) -> Self {
+        if is_ipv6_addr(addr) {
+            Self::Ipv6
+        } else {
+            Self::Ipv4
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct RouteRuleEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<RouteRuleState>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<RouteRuleFamily>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ip_from: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ip_to: Option<String>,
@@ -200,6 +244,11 @@ pub struct RouteRuleEntry {
     pub priority: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "route-table")]
     pub table_id: Option<u32>,
+    // Name of VRF interface whose route table ID will be used as table_id
+    #[serde(skip_serializing_if = "Option::is_none", rename = "table")]
+    pub vrf_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub protocol: Option<RouteProtocol>,
 }
 
 impl RouteRuleEntry {
@@ -211,27 +260,76 @@ impl RouteRuleEntry {
         Self::default()
     }
 
-    // * Neither ip_from nor ip_to should be defined
+    // * ip_from, ip_to and family should be of the same IP family
     pub(crate) fn validate(&self) -> Result<(), NmstateError> {
-        if self.ip_from.is_none() && self.ip_to.is_none() {
-            let e = NmstateError::new(
-                ErrorKind::InvalidArgument,
-                format!(
-                    "Neither ip-from or ip-to is defined in route rule {:?}",
-                    self
-                ),
-            );
-            log::error!("{}", e);
-            return Err(e);
+        let mut families = self
+            .ip_from
+            .iter()
+            .chain(self.ip_to.iter())
+            .map(|addr| RouteRuleFamily::from_addr(addr))
+            .chain(self.family.iter().copied());
+        if let Some(first) = families.next() {
+            if families.any(|f| f != first) {
+                let e = NmstateError::new(
+                    ErrorKind::InvalidArgument,
+                    format!(
+                        "The ip-from, ip-to and family of route rule {:?} \
+                        are not of the same IP family",
+                        self
+                    ),
+                );
+                log::error!("{}", e);
+                return Err(e);
+            }
         }
         Ok(())
     }
 
+    // Detect IP family from ip_from or ip_to if family is not defined.
+    // Return None for rule with neither defined.
+    pub(crate) fn get_family(&self) -> Option<RouteRuleFamily> {
+        self.family.or_else(|| {
+            self.ip_from
+                .as_deref()
+                .or(self.ip_to.as_deref())
+                .map(RouteRuleFamily::from_addr)
+        })
+    }
+
+    pub(crate) fn is_ipv6(&self) -> bool {
+        self.get_family() == Some(RouteRuleFamily::Ipv6)
+    }
+
+    // Set the detected family explicitly. A rule with no ip_from, ip_to or
+    // family defined is expanded into both IPv4 and IPv6 rules.
+    fn expand_family(&self) -> Vec<Self> {
+        match self.get_family() {
+            Some(family) => {
+                let mut rule = self.clone();
+                rule.family = Some(family);
+                vec![rule]
+            }
+            None => [RouteRuleFamily::Ipv4, RouteRuleFamily::Ipv6]
+                .iter()
+                .map(|family| {
+                    let mut rule = self.clone();
+                    rule.family = Some(*family);
+                    rule
+                })
+                .collect(),
+        }
+    }
+
     fn is_absent(&self) -> bool {
         matches!(self.state, Some(RouteRuleState::Absent))
     }
 
     fn is_match(&self, other: &Self) -> bool {
+        if let Some(family) = self.get_family() {
+            if other.get_family() != Some(family) {
+                return false;
+            }
+        }
         if let Some(ip_from) = self.ip_from.as_deref() {
             let ip_from = if !ip_from.contains('/') {
                 match InterfaceIpAddr::try_from(ip_from) {
@@ -284,19 +382,7 @@ impl RouteRuleEntry {
     fn sort_key(&self) -> (bool, bool, u32, &str, &str, i64) {
         (
             !matches!(self.state, Some(RouteRuleState::Absent)),
-            {
-                if let Some(ip_from) = self.ip_from.as_ref() {
-                    !is_ipv6_addr(ip_from.as_str())
-                } else if let Some(ip_to) = self.ip_to.as_ref() {
-                    !is_ipv6_addr(ip_to.as_str())
-                } else {
-                    log::warn!(
-                        "Neither ip-from nor ip-to \
-                    is defined, treating it a IPv4 route rule"
-                    );
-                    true
-                }
-            },
+            !self.is_ipv6(),
             self.table_id
                 .unwrap_or(RouteRuleEntry::USE_DEFAULT_ROUTE_TABLE),
             self.ip_from.as_deref().unwrap_or(""),
@@ -379,3 +465,17 @@ fn flat_absent_rule(
     }
     ret
 }
+
+// Absent rules are kept untouched as they are matched against current rules
+// of both IP families.
+fn expand_rule_families(rules: &[RouteRuleEntry]) -> Vec<RouteRuleEntry> {
+    let mut ret = Vec::new();
+    for rule in rules {
+        if rule.is_absent() {
+            ret.push(rule.clone());
+        } else {
+            ret.extend(rule.expand_family());
+        }
+    }
+    ret
+}
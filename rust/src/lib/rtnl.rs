@@ -0,0 +1,452 @@
+use std::collections::HashMap;
+
+use crate::{dad::last_os_error, ErrorKind, NmstateError};
+
+const NLMSG_HDR_LEN: usize = 16;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLMSG_ERROR_LEN: usize = NLMSG_HDR_LEN + 4 + NLMSG_HDR_LEN;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_DUMP: u16 = 0x300;
+pub(crate) const NLM_F_REPLACE: u16 = 0x100;
+const NLM_F_EXCL: u16 = 0x200;
+pub(crate) const NLM_F_CREATE: u16 = 0x400;
+const NLA_F_NESTED: u16 = 0x8000;
+const NLA_TYPE_MASK: u16 = 0x3fff;
+const RTM_NEWLINK: u16 = 16;
+const RTM_GETLINK: u16 = 18;
+const IFINFOMSG_LEN: usize = 16;
+const IFLA_MTU: u16 = 4;
+const IFLA_LINK: u16 = 5;
+const IFLA_IFNAME: u16 = 3;
+const IFLA_LINKINFO: u16 = 18;
+const IFLA_INFO_KIND: u16 = 1;
+const IFLA_INFO_DATA: u16 = 2;
+const IFLA_INFO_SLAVE_DATA: u16 = 5;
+// Kernel replies immediately, only prevent hanging forever on lost reply
+const DEFAULT_RECV_TIMEOUT_MILLISECONDS: u32 = 5000;
+
+// Minimum rtnetlink client used for the features not supported by
+// NetworkManager or nispor. The generic netlink request and ACK handling is
+// also used for other netlink protocols like audit.
+pub(crate) struct RtnlSocket {
+    fd: libc::c_int,
+    seq: u32,
+}
+
+impl RtnlSocket {
+    pub(crate) fn new() -> Result<Self, NmstateError> {
+        Self::with_protocol(libc::NETLINK_ROUTE)
+    }
+
+    pub(crate) fn with_protocol(
+        protocol: libc::c_int,
+    ) -> Result<Self, NmstateError> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                protocol,
+            )
+        };
+        if fd < 0 {
+            return Err(last_os_error("Failed to create netlink socket"));
+        }
+        let socket = Self { fd, seq: 0 };
+        socket.set_recv_timeout(DEFAULT_RECV_TIMEOUT_MILLISECONDS)?;
+        Ok(socket)
+    }
+
+    // Receiving reply taking longer than this fails with ErrorKind::Timeout
+    pub(crate) fn set_recv_timeout(
+        &self,
+        timeout_ms: u32,
+    ) -> Result<(), NmstateError> {
+        let timeout = libc::timeval {
+            tv_sec: (timeout_ms / 1000) as libc::time_t,
+            tv_usec: ((timeout_ms % 1000) * 1000) as libc::suseconds_t,
+        };
+        let rc = unsafe {
+            libc::setsockopt(
+                self.fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(last_os_error(
+                "Failed to set receive timeout of netlink socket",
+            ));
+        }
+        Ok(())
+    }
+
+    // Send netlink message with ACK requested, return the error replied by
+    // kernel or None on success.
+    pub(crate) fn request(
+        &mut self,
+        msg_type: u16,
+        flags: u16,
+        payload: &[u8],
+    ) -> Result<Option<std::io::Error>, NmstateError> {
+        self.send(msg_type, flags | NLM_F_ACK, payload)?;
+        self.recv_ack()
+    }
+
+    fn send(
+        &mut self,
+        msg_type: u16,
+        flags: u16,
+        payload: &[u8],
+    ) -> Result<(), NmstateError> {
+        self.seq += 1;
+        let len = NLMSG_HDR_LEN + payload.len();
+        let mut msg = vec![0u8; len];
+        msg[0..4].copy_from_slice(&(len as u32).to_ne_bytes());
+        msg[4..6].copy_from_slice(&msg_type.to_ne_bytes());
+        msg[6..8].copy_from_slice(&(flags | NLM_F_REQUEST).to_ne_bytes());
+        msg[8..12].copy_from_slice(&self.seq.to_ne_bytes());
+        msg[NLMSG_HDR_LEN..].copy_from_slice(payload);
+
+        let mut kernel_addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        kernel_addr.nl_family = libc::AF_NETLINK as u16;
+        let rc = unsafe {
+            libc::sendto(
+                self.fd,
+                msg.as_ptr() as *const libc::c_void,
+                msg.len(),
+                0,
+                &kernel_addr as *const libc::sockaddr_nl
+                    as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(last_os_error("Failed to send netlink message"));
+        }
+        Ok(())
+    }
+
+    fn recv(&self, buffer: &mut [u8]) -> Result<usize, NmstateError> {
+        let rc = unsafe {
+            libc::recv(
+                self.fd,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+                0,
+            )
+        };
+        if rc < 0 {
+            let e = std::io::Error::last_os_error();
+            if e.kind() == std::io::ErrorKind::WouldBlock {
+                Err(NmstateError::new(
+                    ErrorKind::Timeout,
+                    "Timeout on waiting netlink reply".to_string(),
+                ))
+            } else {
+                Err(last_os_error("Failed to receive netlink message"))
+            }
+        } else {
+            Ok(rc as usize)
+        }
+    }
+
+    fn recv_ack(&self) -> Result<Option<std::io::Error>, NmstateError> {
+        let mut buffer = [0u8; 4096];
+        loop {
+            let len = self.recv(&mut buffer)?;
+            if len < NLMSG_ERROR_LEN {
+                continue;
+            }
+            let msg_type = u16::from_ne_bytes([buffer[4], buffer[5]]);
+            let seq = u32::from_ne_bytes([
+                buffer[8], buffer[9], buffer[10], buffer[11],
+            ]);
+            if msg_type != NLMSG_ERROR || seq != self.seq {
+                continue;
+            }
+            let errno = i32::from_ne_bytes([
+                buffer[16], buffer[17], buffer[18], buffer[19],
+            ]);
+            return Ok(if errno == 0 {
+                None
+            } else {
+                Some(std::io::Error::from_raw_os_error(-errno))
+            });
+        }
+    }
+}
+
+impl Drop for RtnlSocket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+// Append netlink attribute, the data is padded to 4 bytes
+pub(crate) fn append_nla(buffer: &mut Vec<u8>, nla_type: u16, data: &[u8]) {
+    let len = 4 + data.len();
+    buffer.extend_from_slice(&(len as u16).to_ne_bytes());
+    buffer.extend_from_slice(&nla_type.to_ne_bytes());
+    buffer.extend_from_slice(data);
+    buffer.resize(buffer.len() + ((4 - len % 4) % 4), 0);
+}
+
+// Append nested netlink attribute holding the attributes generated by `fill`
+pub(crate) fn append_nested_nla<F>(buffer: &mut Vec<u8>, nla_type: u16, fill: F)
+where
+    F: FnOnce(&mut Vec<u8>),
+{
+    let mut data = Vec::new();
+    fill(&mut data);
+    append_nla(buffer, nla_type | NLA_F_NESTED, &data);
+}
+
+// Parse netlink attributes into (type, data) pairs with the nested and byte
+// order flags removed from type.
+pub(crate) fn parse_nlas(mut data: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut ret = Vec::new();
+    while data.len() >= 4 {
+        let len = u16::from_ne_bytes([data[0], data[1]]) as usize;
+        let nla_type = u16::from_ne_bytes([data[2], data[3]]) & NLA_TYPE_MASK;
+        if len < 4 || len > data.len() {
+            break;
+        }
+        ret.push((nla_type, &data[4..len]));
+        let padded_len = (len + 3) & !3;
+        data = &data[padded_len.min(data.len())..];
+    }
+    ret
+}
+
+// Link information nispor does not provide
+#[derive(Debug, Default)]
+pub(crate) struct RtnlLinkInfo {
+    // Interface index of the lower link
+    pub(crate) link: Option<u32>,
+    // Raw IFLA_INFO_DATA attributes of the link kind
+    pub(crate) info_data: Vec<u8>,
+    // Raw IFLA_INFO_SLAVE_DATA attributes of the controller kind
+    pub(crate) info_port_data: Vec<u8>,
+}
+
+impl RtnlLinkInfo {
+    fn parse(data: &[u8]) -> Self {
+        let mut ret = Self::default();
+        for (nla_type, data) in parse_nlas(data) {
+            match nla_type {
+                IFLA_LINK if data.len() == 4 => {
+                    ret.link = Some(u32::from_ne_bytes([
+                        data[0], data[1], data[2], data[3],
+                    ]));
+                }
+                IFLA_LINKINFO => {
+                    for (info_type, info_data) in parse_nlas(data) {
+                        match info_type {
+                            IFLA_INFO_DATA => {
+                                ret.info_data = info_data.to_vec()
+                            }
+                            IFLA_INFO_SLAVE_DATA => {
+                                ret.info_port_data = info_data.to_vec()
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+        ret
+    }
+}
+
+impl RtnlSocket {
+    // Dump all links, return the link information indexed by interface index
+    pub(crate) fn dump_link_info(
+        &mut self,
+    ) -> Result<HashMap<u32, RtnlLinkInfo>, NmstateError> {
+        self.send(RTM_GETLINK, NLM_F_DUMP, &[0u8; IFINFOMSG_LEN])?;
+        let mut ret = HashMap::new();
+        let mut buffer = vec![0u8; 65536];
+        loop {
+            let len = self.recv(&mut buffer)?;
+            let mut msgs = &buffer[..len];
+            while msgs.len() >= NLMSG_HDR_LEN {
+                let msg_len =
+                    u32::from_ne_bytes([msgs[0], msgs[1], msgs[2], msgs[3]])
+                        as usize;
+                if msg_len < NLMSG_HDR_LEN || msg_len > msgs.len() {
+                    break;
+                }
+                let msg_type = u16::from_ne_bytes([msgs[4], msgs[5]]);
+                let seq =
+                    u32::from_ne_bytes([msgs[8], msgs[9], msgs[10], msgs[11]]);
+                let payload = &msgs[NLMSG_HDR_LEN..msg_len];
+                msgs = &msgs[((msg_len + 3) & !3).min(msgs.len())..];
+                if seq != self.seq {
+                    continue;
+                }
+                match msg_type {
+                    NLMSG_DONE => return Ok(ret),
+                    NLMSG_ERROR if payload.len() >= 4 => {
+                        let errno = i32::from_ne_bytes([
+                            payload[0], payload[1], payload[2], payload[3],
+                        ]);
+                        let e = NmstateError::new(
+                            ErrorKind::PluginFailure,
+                            format!(
+                                "Failed to dump links: {}",
+                                std::io::Error::from_raw_os_error(-errno)
+                            ),
+                        );
+                        log::error!("{}", e);
+                        return Err(e);
+                    }
+                    RTM_NEWLINK if payload.len() >= IFINFOMSG_LEN => {
+                        let iface_index = u32::from_ne_bytes([
+                            payload[4], payload[5], payload[6], payload[7],
+                        ]);
+                        ret.insert(
+                            iface_index,
+                            RtnlLinkInfo::parse(&payload[IFINFOMSG_LEN..]),
+                        );
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    // Create new link with IFLA_INFO_DATA attributes generated by `fill`
+    pub(crate) fn new_link<F>(
+        &mut self,
+        name: &str,
+        kind: &str,
+        link: Option<u32>,
+        fill: F,
+    ) -> Result<(), NmstateError>
+    where
+        F: FnOnce(&mut Vec<u8>),
+    {
+        self.set_link(name, kind, link, NLM_F_CREATE | NLM_F_EXCL, fill)
+    }
+
+    // Change existing link with IFLA_INFO_DATA attributes generated by `fill`
+    pub(crate) fn change_link<F>(
+        &mut self,
+        name: &str,
+        kind: &str,
+        link: Option<u32>,
+        fill: F,
+    ) -> Result<(), NmstateError>
+    where
+        F: FnOnce(&mut Vec<u8>),
+    {
+        self.set_link(name, kind, link, 0, fill)
+    }
+
+    fn set_link<F>(
+        &mut self,
+        name: &str,
+        kind: &str,
+        link: Option<u32>,
+        flags: u16,
+        fill: F,
+    ) -> Result<(), NmstateError>
+    where
+        F: FnOnce(&mut Vec<u8>),
+    {
+        let mut payload = vec![0u8; IFINFOMSG_LEN];
+        let mut name_bytes = name.as_bytes().to_vec();
+        name_bytes.push(0);
+        append_nla(&mut payload, IFLA_IFNAME, &name_bytes);
+        if let Some(link) = link {
+            append_nla(&mut payload, IFLA_LINK, &link.to_ne_bytes());
+        }
+        let mut kind_bytes = kind.as_bytes().to_vec();
+        kind_bytes.push(0);
+        append_nested_nla(&mut payload, IFLA_LINKINFO, |info| {
+            append_nla(info, IFLA_INFO_KIND, &kind_bytes);
+            append_nested_nla(info, IFLA_INFO_DATA, fill);
+        });
+        if let Some(e) = self.request(RTM_NEWLINK, flags, &payload)? {
+            let e = NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to {} {} interface {}: {}",
+                    if flags & NLM_F_CREATE > 0 {
+                        "create"
+                    } else {
+                        "change"
+                    },
+                    kind,
+                    name,
+                    e
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_link_mtu(
+        &mut self,
+        name: &str,
+        mtu: u32,
+    ) -> Result<(), NmstateError> {
+        let mut payload = vec![0u8; IFINFOMSG_LEN];
+        let mut name_bytes = name.as_bytes().to_vec();
+        name_bytes.push(0);
+        append_nla(&mut payload, IFLA_IFNAME, &name_bytes);
+        append_nla(&mut payload, IFLA_MTU, &mtu.to_ne_bytes());
+        if let Some(e) = self.request(RTM_NEWLINK, 0, &payload)? {
+            let e = NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to change MTU of interface {} to {}: {}",
+                    name, mtu, e
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_link_up(
+        &mut self,
+        name: &str,
+        up: bool,
+    ) -> Result<(), NmstateError> {
+        let mut payload = vec![0u8; IFINFOMSG_LEN];
+        // The ifi_flags and ifi_change of ifinfomsg
+        let iff_up = libc::IFF_UP as u32;
+        if up {
+            payload[8..12].copy_from_slice(&iff_up.to_ne_bytes());
+        }
+        payload[12..16].copy_from_slice(&iff_up.to_ne_bytes());
+        let mut name_bytes = name.as_bytes().to_vec();
+        name_bytes.push(0);
+        append_nla(&mut payload, IFLA_IFNAME, &name_bytes);
+        if let Some(e) = self.request(RTM_NEWLINK, 0, &payload)? {
+            let e = NmstateError::new(
+                ErrorKind::PluginFailure,
+                format!(
+                    "Failed to bring interface {} {}: {}",
+                    name,
+                    if up { "up" } else { "down" },
+                    e
+                ),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use std::convert::TryFrom;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use log::info;
+
+use crate::{
+    dad::get_iface_index,
+    rtnl::{append_nla, RtnlSocket, NLM_F_CREATE, NLM_F_REPLACE},
+    ErrorKind, InterfaceIpAddr, NmstateError, RouteEntry, RouteType, Routes,
+};
+
+const RTM_NEWROUTE: u16 = 24;
+const RTM_DELROUTE: u16 = 25;
+const RTA_DST: u16 = 1;
+const RTA_OIF: u16 = 4;
+const RTA_PRIORITY: u16 = 6;
+const RTA_TABLE: u16 = 15;
+const RTN_BLACKHOLE: u8 = 6;
+const RTN_UNREACHABLE: u8 = 7;
+const RTN_PROHIBIT: u8 = 8;
+const RTPROT_STATIC: u8 = 4;
+const RT_SCOPE_UNIVERSE: u8 = 0;
+// Table ID larger than 255 is only stored in RTA_TABLE
+const RT_TABLE_COMPAT: u32 = 252;
+
+impl RouteType {
+    fn to_kernel_type(self) -> u8 {
+        match self {
+            Self::Blackhole => RTN_BLACKHOLE,
+            Self::Prohibit => RTN_PROHIBIT,
+            Self::Unreachable => RTN_UNREACHABLE,
+        }
+    }
+}
+
+// Nispor does not support route configuration, hence in kernel mode, typed
+// routes are programmed via netlink after applied.
+pub(crate) fn apply_typed_routes(
+    desired: &Routes,
+    current: &Routes,
+) -> Result<(), NmstateError> {
+    let (add_routes, del_routes) = desired.gen_typed_route_changes(current);
+    if add_routes.is_empty() && del_routes.is_empty() {
+        return Ok(());
+    }
+    let mut socket = RtnlSocket::new()?;
+    for route in del_routes {
+        info!("Removing route {:?}", route);
+        let payload = gen_route_msg(&route)?;
+        match socket.request(RTM_DELROUTE, 0, &payload)? {
+            None => (),
+            // Route already been removed
+            Some(e) if e.raw_os_error() == Some(libc::ESRCH) => (),
+            Some(e) => return Err(route_error(&route, "remove", e)),
+        }
+    }
+    for route in add_routes {
+        info!("Adding route {:?}", route);
+        let payload = gen_route_msg(&route)?;
+        if let Some(e) = socket.request(
+            RTM_NEWROUTE,
+            NLM_F_CREATE | NLM_F_REPLACE,
+            &payload,
+        )? {
+            return Err(route_error(&route, "add", e));
+        }
+    }
+    Ok(())
+}
+
+fn gen_route_msg(route: &RouteEntry) -> Result<Vec<u8>, NmstateError> {
+    let dst = match route.destination.as_deref() {
+        Some(d) => InterfaceIpAddr::try_from(d)?,
+        None => {
+            let e = NmstateError::new(
+                ErrorKind::InvalidArgument,
+                format!("Route without destination: {:?}", route),
+            );
+            log::error!("{}", e);
+            return Err(e);
+        }
+    };
+    let dst_ip = IpAddr::from_str(&dst.ip).map_err(|e| {
+        NmstateError::new(
+            ErrorKind::InvalidArgument,
+            format!("Invalid route destination {}: {}", dst.ip, e),
+        )
+    })?;
+    let table = route.get_table_id();
+
+    // rtmsg
+    let mut payload = vec![
+        if dst_ip.is_ipv6() {
+            libc::AF_INET6 as u8
+        } else {
+            libc::AF_INET as u8
+        },
+        dst.prefix_length,
+        0,
+        0,
+        if table > 255 {
+            RT_TABLE_COMPAT as u8
+        } else {
+            table as u8
+        },
+        RTPROT_STATIC,
+        RT_SCOPE_UNIVERSE,
+        route
+            .route_type
+            .map(|t| t.to_kernel_type())
+            .unwrap_or_default(),
+    ];
+    payload.extend_from_slice(&0u32.to_ne_bytes());
+    if dst.prefix_length > 0 {
+        match dst_ip {
+            IpAddr::V4(ip) => append_nla(&mut payload, RTA_DST, &ip.octets()),
+            IpAddr::V6(ip) => append_nla(&mut payload, RTA_DST, &ip.octets()),
+        }
+    }
+    append_nla(&mut payload, RTA_TABLE, &table.to_ne_bytes());
+    if let Some(metric) = route.get_metric() {
+        append_nla(&mut payload, RTA_PRIORITY, &(metric as u32).to_ne_bytes());
+    }
+    if let Some(iface_name) = route.next_hop_iface.as_deref() {
+        let iface_index = get_iface_index(iface_name)? as u32;
+        append_nla(&mut payload, RTA_OIF, &iface_index.to_ne_bytes());
+    }
+    Ok(payload)
+}
+
+fn route_error(
+    route: &RouteEntry,
+    action: &str,
+    err: std::io::Error,
+) -> NmstateError {
+    let e = NmstateError::new(
+        ErrorKind::PluginFailure,
+        format!("Failed to {} route {:?}: {}", action, route, err),
+    );
+    log::error!("{}", e);
+    e
+}
@@ -0,0 +1,190 @@
+use crate::{anonymize::Anonymizer, Interface, NetworkState};
+
+const STATE_YAML: &str = r#"---
+hostname:
+  running: host1.example.com
+dns-resolver:
+  config:
+    search:
+      - corp.example.com
+interfaces:
+  - name: eth1
+    type: ethernet
+    mac-address: 00:23:45:67:89:1A
+    ipv4:
+      enabled: true
+      address:
+        - ip: 8.8.4.4
+          prefix-length: 24
+        - ip: 192.168.1.10
+          prefix-length: 24
+    ipv6:
+      enabled: true
+      address:
+        - ip: 2620:52:0:1::10
+          prefix-length: 64
+  - name: eth1.10
+    type: vlan
+    mac-address: 00:23:45:67:89:1A
+    vlan:
+      base-iface: eth1
+      id: 10
+  - name: ppp0
+    type: pppoe
+    pppoe:
+      parent: eth1
+      username: john.doe
+      password: very-secret
+  - name: wlan0
+    type: wifi
+    wifi:
+      ssid: Home-Network
+      security:
+        key-management: wpa-eap
+        eap:
+          method: peap
+          identity: jane.doe
+  - name: ipsec1
+    type: ipsec
+    libreswan:
+      right: vpn.corp.example.com
+      rightid: "@vpn-gw.corp.example.com"
+      left: "%defaultroute"
+routes:
+  config:
+    - destination: 0.0.0.0/0
+      next-hop-interface: eth1
+      next-hop-address: 8.8.4.1
+"#;
+
+#[test]
+fn test_anonymize_state() {
+    let net_state: NetworkState = serde_yaml::from_str(STATE_YAML).unwrap();
+    let anonymized = net_state.anonymize().unwrap();
+    let output = serde_yaml::to_string(&anonymized).unwrap();
+
+    for private_data in [
+        "host1.example.com",
+        "00:23:45:67:89:1A",
+        "8.8.4.4",
+        "8.8.4.1",
+        "2620:52:0:1::10",
+        "very-secret",
+        "corp.example.com",
+        "john.doe",
+        "Home-Network",
+        "jane.doe",
+    ] {
+        assert!(!output.contains(private_data), "{} found", private_data);
+    }
+    // Private address and interface names are kept
+    assert!(output.contains("192.168.1.10"));
+    assert!(output.contains("eth1.10"));
+    // libreswan keyword is kept
+    assert!(output.contains("%defaultroute"));
+}
+
+#[test]
+fn test_anonymize_random_key_per_call() {
+    let net_state: NetworkState = serde_yaml::from_str(STATE_YAML).unwrap();
+    let anonymized1 = net_state.anonymize().unwrap();
+    let anonymized2 = net_state.anonymize().unwrap();
+
+    assert_ne!(
+        anonymized1.hostname.as_ref().unwrap().running,
+        anonymized2.hostname.as_ref().unwrap().running
+    );
+    assert_ne!(
+        anonymized1.interfaces.kernel_ifaces["eth1"]
+            .base_iface()
+            .mac_address,
+        anonymized2.interfaces.kernel_ifaces["eth1"]
+            .base_iface()
+            .mac_address
+    );
+}
+
+#[test]
+fn test_anonymize_ip_host_part_scrambled() {
+    let mut anonymizer = Anonymizer::new();
+    let ip = anonymizer.anonymize_ip("8.8.4.4/24").unwrap().unwrap();
+    assert_eq!(ip, "240.0.0.1/24");
+    let ip = anonymizer.anonymize_ip("8.8.4.200").unwrap().unwrap();
+    assert_eq!(ip, "240.0.0.2");
+    let ip = anonymizer.anonymize_ip("2620:52:0:1::10").unwrap().unwrap();
+    assert_eq!(ip, "2001:db8::1");
+    // Network address and same address are kept consistent
+    assert_eq!(
+        anonymizer.anonymize_ip("8.8.4.0/24").unwrap().as_deref(),
+        Some("240.0.0.0/24")
+    );
+    assert_eq!(
+        anonymizer.anonymize_ip("8.8.4.4").unwrap().as_deref(),
+        Some("240.0.0.1")
+    );
+}
+
+#[test]
+fn test_anonymize_ip_networks_no_collision() {
+    let mut anonymizer = Anonymizer::new();
+    let mut new_nets = std::collections::HashSet::new();
+    // Networks only differ in the bits dropped by the old 12 bits hash
+    for i in 0..8192u32 {
+        let ip = std::net::Ipv4Addr::from(0x0800_0001 | (i << 8));
+        let new_ip = anonymizer.anonymize_ip(&ip.to_string()).unwrap().unwrap();
+        assert!(new_nets.insert(new_ip.rsplit_once('.').unwrap().0.to_string()));
+    }
+    for i in 0..8192u128 {
+        let ip = std::net::Ipv6Addr::from((0x2620u128 << 112) | (i << 64) | 1);
+        let new_ip = anonymizer.anonymize_ip(&ip.to_string()).unwrap().unwrap();
+        assert!(new_nets.insert(new_ip));
+    }
+}
+
+#[test]
+fn test_anonymize_ignore_private_data() {
+    let mut anonymizer = Anonymizer::new();
+    assert_eq!(anonymizer.anonymize_ip("192.168.1.1").unwrap(), None);
+    assert_eq!(anonymizer.anonymize_ip("240.0.0.1").unwrap(), None);
+    assert_eq!(anonymizer.anonymize_ip("2001:db8::1").unwrap(), None);
+    assert_eq!(anonymizer.anonymize_mac("01:00:5E:00:00:01"), None);
+}
+
+#[test]
+fn test_anonymize_preserve_topology() {
+    let net_state: NetworkState = serde_yaml::from_str(STATE_YAML).unwrap();
+    let anonymized = net_state.anonymize().unwrap();
+
+    let eth1 = anonymized.interfaces.kernel_ifaces.get("eth1").unwrap();
+    let vlan = anonymized.interfaces.kernel_ifaces.get("eth1.10").unwrap();
+    let mac = eth1.base_iface().mac_address.as_deref().unwrap();
+    assert_eq!(vlan.base_iface().mac_address.as_deref(), Some(mac));
+    // Unicast and locally administered
+    assert_eq!(u8::from_str_radix(&mac[..2], 16).unwrap() & 0x03, 0x02);
+
+    let addr = eth1.base_iface().ipv4.as_ref().unwrap().addresses[0]
+        .ip
+        .to_string();
+    let gateway = anonymized.routes.config.as_ref().unwrap()[0]
+        .next_hop_addr
+        .as_deref()
+        .unwrap()
+        .to_string();
+    // Still in the same /24 subnet
+    assert_eq!(
+        addr.rsplit_once('.').unwrap().0,
+        gateway.rsplit_once('.').unwrap().0
+    );
+    // Host part is not kept
+    assert_ne!(addr.rsplit_once('.').unwrap().1, "4");
+    if let Some(Interface::Pppoe(iface)) =
+        anonymized.interfaces.kernel_ifaces.get("ppp0")
+    {
+        assert_eq!(
+            iface.pppoe.as_ref().unwrap().password.as_deref(),
+            Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+        );
+    } else {
+        panic!("Expecting PPPoE interface ppp0");
+    }
+}
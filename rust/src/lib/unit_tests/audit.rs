@@ -0,0 +1,104 @@
+use crate::{
+    audit::{
+        audit_encode_value, gen_audit_summary,
+        gen_iface_type_resolutions_summary, sha256_hex, AuditOperation,
+        AuditRecord,
+    },
+    ErrorKind, InterfaceType, NetworkState, NmstateError,
+};
+
+#[test]
+fn test_audit_sha256() {
+    assert_eq!(
+        sha256_hex(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // Message spanning two blocks
+    assert_eq!(
+        sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+#[test]
+fn test_audit_encode_value() {
+    assert_eq!(audit_encode_value("eth1"), "\"eth1\"");
+    assert_eq!(audit_encode_value("add [eth1]"), "616464205B657468315D");
+    assert_eq!(audit_encode_value("a\"b"), "612262");
+}
+
+#[test]
+fn test_audit_record_msg() {
+    let mut record = AuditRecord::new(AuditOperation::Apply);
+    record.state_hash = "abcd".to_string();
+    record.summaries.push("change [eth1]".to_string());
+    record.checkpoint =
+        Some("/org/freedesktop/NetworkManager/Checkpoint/1".to_string());
+    record.set_result(&Ok(()));
+    assert_eq!(
+        record.to_audit_msg(),
+        "op=nmstate-apply state_hash=\"abcd\" \
+        summary=6368616E6765205B657468315D \
+        checkpoint=\"/org/freedesktop/NetworkManager/Checkpoint/1\" \
+        res=success"
+    );
+
+    let mut record = AuditRecord::new(AuditOperation::Rollback);
+    record.set_result(&Err(NmstateError::new(
+        ErrorKind::PluginFailure,
+        "failed".to_string(),
+    )));
+    let msg = record.to_audit_msg();
+    assert!(msg.starts_with("op=nmstate-rollback state_hash=\"\""));
+    assert!(msg.contains(" summary=\"none\""));
+    assert!(msg.ends_with(" res=failed"));
+}
+
+#[test]
+fn test_audit_summary() {
+    let add_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1.10
+  type: vlan
+- name: bond0
+  type: bond
+"#,
+    )
+    .unwrap();
+    let chg_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+hostname:
+  config: host1
+"#,
+    )
+    .unwrap();
+    let del_state = NetworkState::new();
+
+    assert_eq!(
+        gen_audit_summary(&add_state, &chg_state, &del_state),
+        "add [bond0, eth1.10], change [eth1], hostname"
+    );
+    assert_eq!(
+        gen_audit_summary(&del_state, &del_state, &del_state),
+        "no change"
+    );
+}
+
+#[test]
+fn test_audit_iface_type_resolutions_summary() {
+    assert_eq!(
+        gen_iface_type_resolutions_summary(&[
+            ("eth1".to_string(), InterfaceType::Ethernet),
+            ("veth1".to_string(), InterfaceType::Veth),
+        ]),
+        "eth1: ethernet, veth1: veth"
+    );
+}
@@ -0,0 +1,255 @@
+use crate::{BondInterface, ErrorKind, Interface};
+
+#[test]
+fn test_bond_ports_from_ports_config() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: balance-alb
+  ports-config:
+  - name: eth1
+    priority: 10
+  - name: eth2
+    queue-id: 2
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    if let Interface::Bond(iface) = iface {
+        assert_eq!(iface.ports(), Some(vec!["eth1", "eth2"]));
+        assert_eq!(iface.get_port_conf("eth1").unwrap().priority, Some(10));
+        assert_eq!(iface.get_port_conf("eth2").unwrap().queue_id, Some(2));
+    } else {
+        panic!("Expecting bond interface, got {:?}", iface);
+    }
+}
+
+#[test]
+fn test_bond_port_priority_invalid_mode() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: 802.3ad
+  port:
+  - eth1
+  ports-config:
+  - name: eth1
+    priority: 10
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_ports_config_not_in_port() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: balance-rr
+  port:
+  - eth1
+  ports-config:
+  - name: eth2
+    queue-id: 1
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_verify_partial_ports_config() {
+    let des_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: active-backup
+  port:
+  - eth1
+  - eth2
+  ports-config:
+  - name: eth2
+    priority: 10
+"#,
+    )
+    .unwrap();
+    let cur_iface: BondInterface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: active-backup
+  port:
+  - eth2
+  - eth1
+  ports-config:
+  - name: eth1
+    priority: 0
+    queue-id: 0
+  - name: eth2
+    priority: 10
+    queue-id: 0
+"#,
+    )
+    .unwrap();
+    des_iface.verify(&Interface::Bond(cur_iface)).unwrap();
+}
+
+#[test]
+fn test_bond_ip_targets_from_list_or_string() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: active-backup
+  options:
+    arp_interval: 100
+    arp_ip_target: 192.0.2.1,192.0.2.2
+    ns_ip6_target:
+    - 2001:db8::1
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    if let Interface::Bond(iface) = iface {
+        let opts = iface.bond.unwrap().options.unwrap();
+        assert_eq!(
+            opts.arp_ip_target,
+            Some(vec!["192.0.2.1".to_string(), "192.0.2.2".to_string()])
+        );
+        assert_eq!(opts.ns_ip6_target, Some(vec!["2001:db8::1".to_string()]));
+    } else {
+        panic!("Expecting bond interface, got {:?}", iface);
+    }
+}
+
+#[test]
+fn test_bond_arp_ip_target_wrong_family() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: active-backup
+  options:
+    arp_interval: 100
+    arp-ip-targets:
+    - 2001:db8::1
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_ip_targets_with_miimon() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: active-backup
+  options:
+    miimon: 100
+    arp_ip_target:
+    - 192.0.2.1
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_ip_targets_exceed_limit() {
+    let targets: Vec<String> =
+        (1..18).map(|i| format!("192.0.2.{}", i)).collect();
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: balance-xor
+  options:
+    arp_interval: 100
+    arp_ip_target: {}
+"#,
+        targets.join(",")
+    ))
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_option_not_supported_in_mode() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: active-backup
+  options:
+    lacp_rate: fast
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_mode_specific_options() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: bond99
+type: bond
+link-aggregation:
+  mode: balance-tlb
+  options:
+    lp_interval: 2
+    peer_notif_delay: 100
+    resend_igmp: 3
+    tlb_dynamic_lb: false
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    if let Interface::Bond(iface) = iface {
+        let opts = iface.bond.unwrap().options.unwrap();
+        assert_eq!(opts.peer_notif_delay, Some(100));
+        assert_eq!(opts.tlb_dynamic_lb, Some(false));
+    } else {
+        panic!("Expecting bond interface, got {:?}", iface);
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use crate::{ErrorKind, ErrorMsg, Interfaces, NmstateError};
+
+#[test]
+fn test_error_code() {
+    let e = NmstateError::new(ErrorKind::Timeout, "timeout".to_string());
+    assert_eq!(e.code(), 9);
+    assert_eq!(e.kind().as_str(), "timeout");
+    assert_eq!(
+        ErrorKind::VerificationError.as_str(),
+        "verification-mismatch"
+    );
+    assert!(e.context().is_empty());
+}
+
+#[test]
+fn test_error_context_not_overridden() {
+    let e = NmstateError::new(ErrorKind::Bug, "bug".to_string())
+        .with_context(NmstateError::CONTEXT_BACKEND, "kernel")
+        .with_context(NmstateError::CONTEXT_BACKEND, "NetworkManager");
+    assert_eq!(
+        e.context()
+            .get(NmstateError::CONTEXT_BACKEND)
+            .map(|s| s.as_str()),
+        Some("kernel")
+    );
+}
+
+#[test]
+fn test_verification_error_context() {
+    let des_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 1500
+"#,
+    )
+    .unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  mtu: 9000
+"#,
+    )
+    .unwrap();
+    let e = des_ifaces.verify(&cur_ifaces).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::VerificationError);
+    assert_eq!(
+        e.context()
+            .get(NmstateError::CONTEXT_INTERFACE)
+            .map(|s| s.as_str()),
+        Some("eth1")
+    );
+    assert_eq!(
+        e.context()
+            .get(NmstateError::CONTEXT_PROPERTY)
+            .map(|s| s.as_str()),
+        Some("eth1.interface.mtu")
+    );
+    assert_eq!(e.msg_id(), Some(ErrorMsg::VERIFY_MISMATCH.id));
+    assert_eq!(
+        e.msg_params().get("desire").map(|s| s.as_str()),
+        Some("1500")
+    );
+}
+
+#[test]
+fn test_error_msg_render() {
+    let e = NmstateError::from_msg(
+        ErrorKind::VerificationError,
+        ErrorMsg::VERIFY_IFACE_NOT_FOUND,
+        &[
+            ("interface", "eth1".to_string()),
+            ("type", "Ethernet".to_string()),
+        ],
+    );
+    assert_eq!(e.msg(), "Failed to find desired interface eth1 Ethernet");
+
+    // Render translated template with the same parameters
+    assert_eq!(
+        ErrorMsg::render(
+            "{type} {interface} not found {unknown}",
+            e.msg_params()
+        ),
+        "Ethernet eth1 not found {unknown}"
+    );
+    assert_eq!(ErrorMsg::render("{", &BTreeMap::new()), "{");
+    assert_eq!(
+        ErrorMsg::from_id("verify-iface-not-found"),
+        Some(ErrorMsg::VERIFY_IFACE_NOT_FOUND)
+    );
+}
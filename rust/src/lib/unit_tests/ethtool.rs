@@ -0,0 +1,193 @@
+use crate::{ErrorKind, EthtoolRingConfig, Interface, Interfaces};
+
+fn new_eth1_with_ethtool(ethtool: &str) -> Interface {
+    serde_yaml::from_str(&format!(
+        r#"---
+name: eth1
+type: ethernet
+ethtool:
+{}"#,
+        ethtool
+    ))
+    .unwrap()
+}
+
+fn new_eth1_with_features(features: &str) -> Interface {
+    new_eth1_with_ethtool(&format!("  feature:\n{}", features))
+}
+
+#[test]
+fn test_ethtool_feature_alias() {
+    let mut iface = new_eth1_with_features(
+        "    gro: true\n    rx-vlan-offload: false\n    tx-udp-segmentation: \
+         true\n",
+    );
+    iface.base_iface_mut().pre_edit_cleanup().unwrap();
+    let features = iface
+        .base_iface()
+        .ethtool
+        .as_ref()
+        .unwrap()
+        .feature
+        .as_ref()
+        .unwrap();
+    assert_eq!(features.len(), 3);
+    assert_eq!(features.get("rx-gro"), Some(&true));
+    assert_eq!(features.get("rx-vlan-hw-parse"), Some(&false));
+    assert_eq!(features.get("tx-udp-segmentation"), Some(&true));
+}
+
+#[test]
+fn test_ethtool_feature_verify_subset() {
+    let des_iface = new_eth1_with_features("    tso: true\n    gro: false\n");
+    let cur_iface = new_eth1_with_features(
+        "    rx-gro: false\n    tx-tcp-segmentation: true\n    \
+         rx-checksum: true\n    highdma: true\n",
+    );
+    des_iface.verify(&cur_iface).unwrap();
+}
+
+#[test]
+fn test_ethtool_feature_verify_mismatch() {
+    let des_iface = new_eth1_with_features("    gro: true\n");
+    let cur_iface =
+        new_eth1_with_features("    rx-gro: false\n    rx-checksum: true\n");
+    let result = des_iface.verify(&cur_iface);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+    }
+}
+
+#[test]
+fn test_ethtool_ring_coalesce_verify_driver_rounding() {
+    let des_iface = new_eth1_with_ethtool(
+        r#"  ring:
+    rx: 1000
+    tx: 512
+  coalesce:
+    adaptive-rx: false
+    rx-usecs: 10
+  pause:
+    rx: true
+"#,
+    );
+    let cur_iface = new_eth1_with_ethtool(
+        r#"  ring:
+    rx: 1024
+    tx: 512
+  coalesce:
+    adaptive-rx: false
+    rx-usecs: 8
+    tx-usecs: 8
+  pause:
+    rx: true
+    tx: true
+    autoneg: false
+"#,
+    );
+    des_iface.verify(&cur_iface).unwrap();
+}
+
+#[test]
+fn test_ethtool_coalesce_verify_rounding_boundary() {
+    for (des_usecs, cur_usecs, rounded) in [
+        (0, 0, true),
+        (0, 8, false),
+        (8, 0, false),
+        (100, 96, true),
+        (100, 93, true),
+        (100, 92, false),
+        (100, 101, false),
+        (100, 112, false),
+    ] {
+        let des_iface = new_eth1_with_ethtool(&format!(
+            "  coalesce:\n    rx-usecs: {}\n",
+            des_usecs
+        ));
+        let cur_iface = new_eth1_with_ethtool(&format!(
+            "  coalesce:\n    rx-usecs: {}\n",
+            cur_usecs
+        ));
+        let result = des_iface.verify(&cur_iface);
+        assert_eq!(result.is_ok(), rounded, "{} vs {}", des_usecs, cur_usecs);
+        if let Err(e) = result {
+            assert_eq!(e.kind(), ErrorKind::VerificationError);
+        }
+    }
+}
+
+#[test]
+fn test_ethtool_ring_verify_mismatch() {
+    let des_iface = new_eth1_with_ethtool("  ring:\n    rx: 1000\n");
+    let cur_iface = new_eth1_with_ethtool("  ring:\n    rx: 4096\n");
+    let result = des_iface.verify(&cur_iface);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+    }
+}
+
+fn new_eth1_ifaces_with_ethtool(ethtool: &str) -> Interfaces {
+    let mut ifaces = Interfaces::new();
+    ifaces.push(new_eth1_with_ethtool(ethtool));
+    ifaces
+}
+
+#[test]
+fn test_ethtool_ring_max_serialize() {
+    let iface = new_eth1_with_ethtool("  ring:\n    rx: max\n    tx: 512\n");
+    let ring = iface
+        .base_iface()
+        .ethtool
+        .as_ref()
+        .unwrap()
+        .ring
+        .as_ref()
+        .unwrap();
+    assert_eq!(ring.rx, Some(EthtoolRingConfig::RING_SIZE_MAX));
+    assert_eq!(ring.tx, Some(512));
+    let yaml = serde_yaml::to_string(ring).unwrap();
+    assert!(yaml.contains("rx: max"));
+    assert!(yaml.contains("tx: 512"));
+}
+
+#[test]
+fn test_ethtool_ring_max_resolve() {
+    let mut des_ifaces =
+        new_eth1_ifaces_with_ethtool("  ring:\n    rx: max\n    tx: 512\n");
+    let mut cur_ifaces =
+        new_eth1_ifaces_with_ethtool("  ring:\n    rx: 256\n    tx: 256\n");
+    if let Some(ring) = cur_ifaces
+        .kernel_ifaces
+        .get_mut("eth1")
+        .and_then(|i| i.base_iface_mut().ethtool.as_mut())
+        .and_then(|e| e.ring.as_mut())
+    {
+        ring.rx_max = Some(4096);
+        ring.tx_max = Some(4096);
+    }
+    des_ifaces.resolve_ethtool_ring_max(&cur_ifaces).unwrap();
+    let ring = des_ifaces.kernel_ifaces["eth1"]
+        .base_iface()
+        .ethtool
+        .as_ref()
+        .unwrap()
+        .ring
+        .as_ref()
+        .unwrap();
+    assert_eq!(ring.rx, Some(4096));
+    assert_eq!(ring.tx, Some(512));
+}
+
+#[test]
+fn test_ethtool_ring_max_without_device_max() {
+    let mut des_ifaces =
+        new_eth1_ifaces_with_ethtool("  ring:\n    rx-jumbo: max\n");
+    let cur_ifaces = new_eth1_ifaces_with_ethtool("  ring:\n    rx: 256\n");
+    let result = des_ifaces.resolve_ethtool_ring_max(&cur_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{ErrorKind, Interface, InterfaceType};
+
+#[test]
+fn test_geneve_deserialize() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: geneve0
+type: geneve
+geneve:
+  id: 1001
+  remote: 192.0.2.1
+  destination-port: 6081
+"#,
+    )
+    .unwrap();
+    assert_eq!(iface.iface_type(), InterfaceType::Geneve);
+    if let Interface::Geneve(iface) = &iface {
+        let conf = iface.geneve.as_ref().unwrap();
+        assert_eq!(conf.id, 1001);
+        assert_eq!(conf.remote, "192.0.2.1");
+        assert_eq!(conf.destination_port, Some(6081));
+    } else {
+        panic!("Expecting Geneve interface, got {:?}", iface);
+    }
+    iface.validate().unwrap();
+}
+
+#[test]
+fn test_geneve_invalid_id() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: geneve0
+type: geneve
+geneve:
+  id: 16777216
+  remote: 2001:db8::1
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
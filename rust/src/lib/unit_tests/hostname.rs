@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+
+use crate::{
+    hostname::with_hostname_backends, ErrorKind, HostNameBackend,
+    HostNameState, NetworkState, NmstateError,
+};
+
+#[test]
+fn test_hostname_backend_fallback() {
+    let tried = RefCell::new(Vec::new());
+    let result = with_hostname_backends(
+        &[
+            HostNameBackend::Hostnamed,
+            HostNameBackend::NetworkManager,
+            HostNameBackend::File,
+        ],
+        "test",
+        |backend| {
+            tried.borrow_mut().push(backend);
+            if backend == HostNameBackend::NetworkManager {
+                Ok("host1")
+            } else {
+                Err(NmstateError::new(
+                    ErrorKind::DependencyError,
+                    "not running".to_string(),
+                ))
+            }
+        },
+    );
+
+    assert_eq!(result.unwrap(), "host1");
+    assert_eq!(
+        tried.into_inner(),
+        vec![HostNameBackend::Hostnamed, HostNameBackend::NetworkManager]
+    );
+}
+
+#[test]
+fn test_hostname_backend_all_failed() {
+    let result: Result<(), NmstateError> = with_hostname_backends(
+        &[HostNameBackend::Hostnamed, HostNameBackend::File],
+        "test",
+        |backend| {
+            Err(NmstateError::new(
+                if backend == HostNameBackend::File {
+                    ErrorKind::PluginFailure
+                } else {
+                    ErrorKind::DependencyError
+                },
+                "failed".to_string(),
+            ))
+        },
+    );
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::PluginFailure);
+
+    let result: Result<(), NmstateError> =
+        with_hostname_backends(&[], "test", |_| Ok(()));
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_hostname_backend_no_fallback_on_failure() {
+    let tried = RefCell::new(Vec::new());
+    let result: Result<(), NmstateError> = with_hostname_backends(
+        &[HostNameBackend::Hostnamed, HostNameBackend::File],
+        "test",
+        |backend| {
+            tried.borrow_mut().push(backend);
+            Err(NmstateError::new(
+                ErrorKind::PluginFailure,
+                "permission denied".to_string(),
+            ))
+        },
+    );
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::PluginFailure);
+    assert_eq!(tried.into_inner(), vec![HostNameBackend::Hostnamed]);
+}
+
+#[test]
+fn test_hostname_verify() {
+    let current = HostNameState {
+        running: Some("host1".to_string()),
+        config: None,
+    };
+    let desired = HostNameState {
+        running: None,
+        config: Some("".to_string()),
+    };
+    desired.verify(Some(&current)).unwrap();
+
+    let desired = HostNameState {
+        running: Some("host2".to_string()),
+        config: None,
+    };
+    let result = desired.verify(Some(&current));
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::VerificationError);
+}
+
+#[test]
+fn test_hostname_invalid() {
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+hostname:
+  config: "host 1"
+"#,
+    )
+    .unwrap();
+
+    let result = des_state.gen_plan_with_current(&NetworkState::new());
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_hostname_plan_only_when_changed() {
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+hostname:
+  running: host1
+  config: host1
+"#,
+    )
+    .unwrap();
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+hostname:
+  config: host1
+"#,
+    )
+    .unwrap();
+    let (_, chg_state, _) =
+        des_state.gen_plan_with_current(&cur_state).unwrap();
+    assert!(chg_state.hostname.is_none());
+
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+hostname:
+  config: host2
+"#,
+    )
+    .unwrap();
+    let (_, chg_state, _) =
+        des_state.gen_plan_with_current(&cur_state).unwrap();
+    assert_eq!(chg_state.hostname.unwrap().config.as_deref(), Some("host2"));
+}
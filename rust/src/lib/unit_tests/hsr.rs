@@ -0,0 +1,46 @@
+use crate::{ErrorKind, HsrProtocol, Interface};
+
+#[test]
+fn test_hsr_same_ports() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: hsr0
+type: hsr
+hsr:
+  port1: eth1
+  port2: eth1
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_hsr_prp() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: prp0
+type: hsr
+hsr:
+  port1: eth1
+  port2: eth2
+  multicast-spec: 16
+  protocol: prp
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    if let Interface::Hsr(iface) = iface {
+        let conf = iface.hsr.as_ref().unwrap();
+        assert_eq!(conf.port1, "eth1");
+        assert_eq!(conf.port2, "eth2");
+        assert_eq!(conf.multicast_spec, Some(16));
+        assert_eq!(conf.protocol, Some(HsrProtocol::Prp));
+    } else {
+        panic!("Expecting HSR interface, got {:?}", iface);
+    }
+}
@@ -3,7 +3,8 @@ use crate::{
         new_eth_iface, new_ovs_br_iface, new_ovs_iface, new_unknown_iface,
         new_vlan_iface,
     },
-    InterfaceState, InterfaceType, Interfaces,
+    ErrorKind, ErrorMsg, Interface, InterfacePersist, InterfaceState,
+    InterfaceType, Interfaces,
 };
 
 #[test]
@@ -17,9 +18,14 @@ fn test_resolve_unknown_type_absent_eth() {
     let mut ifaces = Interfaces::new();
     ifaces.push(absent_iface);
 
-    ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
+    let resolutions = ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
     let (_, _, del_ifaces) = ifaces.gen_state_for_apply(&cur_ifaces).unwrap();
 
+    assert_eq!(
+        resolutions,
+        vec![("eth1".to_string(), InterfaceType::Ethernet)]
+    );
+
     let del_ifaces = del_ifaces.to_vec();
 
     assert_eq!(del_ifaces[0].name(), "eth1");
@@ -88,3 +94,402 @@ fn test_check_orphan_vlan_change_parent() {
     assert!(del_ifaces.kernel_ifaces["eth0"].is_absent());
     assert!(!chg_ifaces.kernel_ifaces["eth0.10"].is_absent());
 }
+
+#[test]
+fn test_has_ovs_ignore_absent() {
+    let mut ifaces = Interfaces::new();
+    ifaces.push(new_eth_iface("eth1"));
+    assert!(!ifaces.has_ovs());
+
+    let mut absent_iface = new_ovs_br_iface("br0", &[]);
+    absent_iface.base_iface_mut().state = InterfaceState::Absent;
+    ifaces.push(absent_iface);
+    assert!(!ifaces.has_ovs());
+
+    ifaces.push(new_ovs_iface("p1", "br1"));
+    assert!(ifaces.has_ovs());
+}
+
+#[test]
+fn test_resolve_unknown_type_same_name_ovs_br_and_iface() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_ovs_br_iface("br0", &["br0", "p1"]));
+    cur_ifaces.push(new_ovs_iface("br0", "br0"));
+    cur_ifaces.push(new_ovs_iface("p1", "br0"));
+
+    let mut iface = new_unknown_iface("br0");
+    iface.base_iface_mut().mtu = Some(1400);
+    let mut ifaces = Interfaces::new();
+    ifaces.push(iface);
+    ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
+
+    let ifaces = ifaces.to_vec();
+    assert_eq!(ifaces.len(), 1);
+    assert_eq!(ifaces[0].name(), "br0");
+    assert_eq!(ifaces[0].iface_type(), InterfaceType::OvsInterface);
+    assert_eq!(ifaces[0].base_iface().mtu, Some(1400));
+}
+
+#[test]
+fn test_resolve_unknown_type_with_typed_same_name_iface() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_ovs_br_iface("br0", &["br0"]));
+    cur_ifaces.push(new_ovs_iface("br0", "br0"));
+
+    let mut ifaces = Interfaces::new();
+    ifaces.push(new_ovs_iface("br0", "br0"));
+    ifaces.push(new_unknown_iface("br0"));
+    let resolutions = ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
+
+    assert_eq!(
+        resolutions,
+        vec![("br0".to_string(), InterfaceType::OvsBridge)]
+    );
+
+    assert!(ifaces
+        .user_ifaces
+        .contains_key(&("br0".to_string(), InterfaceType::OvsBridge)));
+    assert_eq!(
+        ifaces.kernel_ifaces["br0"].iface_type(),
+        InterfaceType::OvsInterface
+    );
+}
+
+#[test]
+fn test_ifaces_to_vec_order() {
+    let mut ifaces = Interfaces::new();
+    ifaces.push(new_ovs_br_iface("br0", &["p1"]));
+    ifaces.push(new_eth_iface("eth1"));
+    ifaces.push(new_ovs_iface("br0", "br0"));
+    ifaces.push(new_eth_iface("eth0"));
+    ifaces
+        .kernel_ifaces
+        .get_mut("eth0")
+        .unwrap()
+        .base_iface_mut()
+        .up_priority = 1;
+
+    let ifaces = ifaces.to_vec();
+    assert_eq!(ifaces[0].name(), "br0");
+    assert_eq!(ifaces[0].iface_type(), InterfaceType::OvsInterface);
+    assert_eq!(ifaces[1].name(), "br0");
+    assert_eq!(ifaces[1].iface_type(), InterfaceType::OvsBridge);
+    assert_eq!(ifaces[2].name(), "eth1");
+    assert_eq!(ifaces[3].name(), "eth0");
+}
+
+#[test]
+fn test_iface_persist_memory() {
+    let des_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+- name: eth1.10
+  type: vlan
+  state: up
+  persist: memory
+  vlan:
+    base-iface: eth1
+    id: 10
+"#,
+    )
+    .unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+- name: eth1.10
+  type: vlan
+  state: up
+  vlan:
+    base-iface: eth1
+    id: 10
+"#,
+    )
+    .unwrap();
+
+    let eth_iface = des_ifaces.get_iface("eth1", InterfaceType::Ethernet);
+    let vlan_iface = des_ifaces.get_iface("eth1.10", InterfaceType::Vlan);
+    assert_eq!(eth_iface.unwrap().base_iface().persist, None);
+    assert_eq!(
+        vlan_iface.unwrap().base_iface().persist,
+        Some(InterfacePersist::Memory)
+    );
+    assert!(vlan_iface.unwrap().base_iface().is_memory_only());
+
+    des_ifaces.verify(&cur_ifaces).unwrap();
+}
+
+#[test]
+fn test_iface_invalid_secondaries() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+secondaries:
+- not-a-uuid
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_iface_invalid_permissions() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+permissions:
+- "user:alice:"
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+fn new_can_iface(name: &str) -> Interface {
+    let mut iface = new_unknown_iface(name);
+    iface.base_iface_mut().iface_type = InterfaceType::Other("can".to_string());
+    iface
+}
+
+#[test]
+fn test_passthrough_other_type_iface() {
+    let mut cur_ifaces = Interfaces::new();
+    let mut cur_iface = new_can_iface("can0");
+    cur_iface.base_iface_mut().mtu = Some(16);
+    cur_ifaces.push(cur_iface);
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: can0
+  state: down
+  mtu: 72
+  passthrough: true
+"#,
+    )
+    .unwrap();
+
+    ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
+    let (add_ifaces, chg_ifaces, del_ifaces) =
+        ifaces.gen_state_for_apply(&cur_ifaces).unwrap();
+
+    assert!(add_ifaces.to_vec().is_empty());
+    assert!(del_ifaces.to_vec().is_empty());
+    let chg_ifaces = chg_ifaces.to_vec();
+    assert_eq!(chg_ifaces.len(), 1);
+    assert_eq!(chg_ifaces[0].name(), "can0");
+    assert_eq!(
+        chg_ifaces[0].iface_type(),
+        InterfaceType::Other("can".to_string())
+    );
+    assert!(chg_ifaces[0].is_down());
+    assert_eq!(chg_ifaces[0].base_iface().mtu, Some(72));
+    assert!(chg_ifaces[0].base_iface().is_passthrough());
+}
+
+#[test]
+fn test_passthrough_iface_not_exist() {
+    let cur_ifaces = Interfaces::new();
+    let mut ifaces = Interfaces::new();
+    let mut iface = new_can_iface("can0");
+    iface.base_iface_mut().passthrough = Some(true);
+    ifaces.push(iface);
+
+    let e = ifaces.gen_state_for_apply(&cur_ifaces).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_passthrough_iface_with_ip() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_can_iface("can0"));
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: can0
+  passthrough: true
+  ipv4:
+    enabled: true
+    dhcp: true
+"#,
+    )
+    .unwrap();
+
+    ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
+    let e = ifaces.gen_state_for_apply(&cur_ifaces).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_passthrough_supported_type_iface() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_eth_iface("eth1"));
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  passthrough: true
+"#,
+    )
+    .unwrap();
+
+    let e = ifaces.gen_state_for_apply(&cur_ifaces).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_passthrough_iface_with_ethtool() {
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(new_can_iface("can0"));
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: can0
+  passthrough: true
+  ethtool:
+    pause:
+      rx: true
+"#,
+    )
+    .unwrap();
+
+    ifaces.resolve_unknown_ifaces(&cur_ifaces).unwrap();
+    let e = ifaces.gen_state_for_apply(&cur_ifaces).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+}
+
+#[test]
+fn test_passthrough_iface_not_supported_by_nm() {
+    let mut ifaces = Interfaces::new();
+    let mut iface = new_can_iface("can0");
+    iface.base_iface_mut().passthrough = Some(true);
+    ifaces.push(iface);
+
+    let cur_ifaces = Interfaces::new();
+    ifaces.validate_backend_support(&cur_ifaces, true).unwrap();
+    let e = ifaces
+        .validate_backend_support(&cur_ifaces, false)
+        .unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+}
+
+#[test]
+fn test_verify_require_carrier() {
+    let des_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+state: up
+require-carrier: true
+"#,
+    )
+    .unwrap();
+    let mut des_ifaces = Interfaces::new();
+    des_ifaces.push(des_iface);
+    assert!(des_ifaces.has_carrier_required());
+
+    let mut cur_iface = new_eth_iface("eth1");
+    cur_iface.base_iface_mut().carrier = Some(false);
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(cur_iface.clone());
+
+    let result = des_ifaces.verify(&cur_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+        assert_eq!(e.msg_id(), Some(ErrorMsg::VERIFY_IFACE_NO_CARRIER.id));
+        assert!(e.msg().contains("eth1"));
+    }
+
+    cur_iface.base_iface_mut().carrier = Some(true);
+    let mut cur_ifaces = Interfaces::new();
+    cur_ifaces.push(cur_iface);
+    des_ifaces.verify(&cur_ifaces).unwrap();
+}
+
+#[test]
+fn test_validate_offline() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: br0
+  type: linux-bridge
+  state: up
+  bridge:
+    port:
+    - name: eth1
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    ifaces.validate_offline().unwrap();
+
+    for desired in [
+        "- name: eth1\n  state: up\n",
+        "- name: eth1\n  type: ethernet\n  state: absent\n",
+        "- name: br0\n  type: linux-bridge\n  bridge:\n    port:\n    \
+        - name: eth1\n",
+        "- name: eth1\n  type: ethernet\n  controller: br0\n",
+    ] {
+        let ifaces: Interfaces = serde_yaml::from_str(desired).unwrap();
+        let result = ifaces.validate_offline();
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+        }
+    }
+}
+
+#[test]
+fn test_gre_not_supported_by_kernel_mode() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: gre1
+  type: gre
+  state: up
+  gre:
+    remote: 192.0.2.1
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    let result = ifaces.validate_backend_support(&cur_ifaces, true);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+}
+
+#[test]
+fn test_existing_gre_without_gre_conf_in_kernel_mode() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: gre1
+  type: gre
+  state: up
+  mtu: 1400
+"#,
+    )
+    .unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: gre1
+  type: gre
+  state: up
+"#,
+    )
+    .unwrap();
+
+    ifaces.validate_backend_support(&cur_ifaces, true).unwrap();
+}
@@ -1,5 +1,7 @@
 use crate::{
-    ifaces::inter_ifaces_controller::check_overbook_ports,
+    ifaces::inter_ifaces_controller::{
+        check_overbook_ports, handle_changed_ports,
+    },
     unit_tests::testlib::{
         bond_with_ports, bridge_with_ports, new_br_iface, new_eth_iface,
         new_nested_4_ifaces, new_ovs_br_iface, new_ovs_iface,
@@ -340,3 +342,297 @@ fn test_overbook_swap_port_of_bond() {
 
     assert!(check_overbook_ports(&desired, &current).is_ok());
 }
+
+#[test]
+fn test_ifaces_up_order_with_priority() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth0
+  type: ethernet
+  priority: 10
+- name: eth1
+  type: ethernet
+- name: eth2
+  type: ethernet
+  priority: 5
+- name: bond0
+  type: bond
+  link-aggregation:
+    mode: balance-rr
+    port:
+    - eth1
+    - eth2
+"#,
+    )
+    .unwrap();
+
+    let (add_ifaces, _, _) =
+        ifaces.gen_state_for_apply(&Interfaces::new()).unwrap();
+
+    assert_eq!(ifaces.kernel_ifaces["bond0"].base_iface().up_priority, 0);
+    assert_eq!(ifaces.kernel_ifaces["eth1"].base_iface().up_priority, 1);
+    assert_eq!(ifaces.kernel_ifaces["eth2"].base_iface().up_priority, 5);
+    assert_eq!(ifaces.kernel_ifaces["eth0"].base_iface().up_priority, 10);
+
+    let ordered_ifaces: Vec<&str> =
+        add_ifaces.to_vec().iter().map(|i| i.name()).collect();
+    assert_eq!(ordered_ifaces, vec!["bond0", "eth1", "eth2", "eth0"]);
+}
+
+#[test]
+fn test_ifaces_priority_port_before_controller() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  priority: 2
+- name: bond0
+  type: bond
+  priority: 3
+  link-aggregation:
+    mode: balance-rr
+    port:
+    - eth1
+"#,
+    )
+    .unwrap();
+
+    let result = ifaces.gen_state_for_apply(&Interfaces::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+fn gen_bond_cur_ifaces() -> Interfaces {
+    serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  controller: bond0
+- name: eth2
+  type: ethernet
+- name: bond0
+  type: bond
+  link-aggregation:
+    mode: balance-rr
+    port:
+    - eth1
+"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_port_side_controller_attach() {
+    let cur_ifaces = gen_bond_cur_ifaces();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth2
+  type: ethernet
+  controller: bond0
+"#,
+    )
+    .unwrap();
+
+    handle_changed_ports(&mut ifaces, &cur_ifaces).unwrap();
+
+    let eth2 = &ifaces.kernel_ifaces["eth2"];
+    assert_eq!(eth2.base_iface().controller, Some("bond0".to_string()));
+    assert_eq!(eth2.base_iface().controller_type, Some(InterfaceType::Bond));
+    assert!(!ifaces.kernel_ifaces.contains_key("eth1"));
+    // The undeclared controller is included to edit without port list
+    let bond0 = &ifaces.kernel_ifaces["bond0"];
+    assert_eq!(bond0.iface_type(), InterfaceType::Bond);
+    assert!(bond0.is_up());
+    assert!(bond0.ports().is_none());
+}
+
+#[test]
+fn test_port_side_controller_already_attached() {
+    let cur_ifaces = gen_bond_cur_ifaces();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  controller: bond0
+  mtu: 1400
+"#,
+    )
+    .unwrap();
+
+    handle_changed_ports(&mut ifaces, &cur_ifaces).unwrap();
+
+    assert!(!ifaces.kernel_ifaces.contains_key("bond0"));
+}
+
+#[test]
+fn test_port_side_controller_detach() {
+    let cur_ifaces = gen_bond_cur_ifaces();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  controller: ""
+"#,
+    )
+    .unwrap();
+
+    handle_changed_ports(&mut ifaces, &cur_ifaces).unwrap();
+
+    let eth1 = &ifaces.kernel_ifaces["eth1"];
+    assert_eq!(eth1.base_iface().controller, None);
+    assert_eq!(eth1.base_iface().controller_type, None);
+}
+
+#[test]
+fn test_port_side_controller_conflict_with_port_list() {
+    let cur_ifaces = gen_bond_cur_ifaces();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth2
+  type: ethernet
+  controller: bond0
+- name: bond0
+  type: bond
+  link-aggregation:
+    mode: balance-rr
+    port:
+    - eth1
+"#,
+    )
+    .unwrap();
+
+    let result = handle_changed_ports(&mut ifaces, &cur_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_port_side_controller_not_found() {
+    let cur_ifaces = gen_bond_cur_ifaces();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth2
+  type: ethernet
+  controller: eth1
+"#,
+    )
+    .unwrap();
+
+    let result = handle_changed_ports(&mut ifaces, &cur_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_bond_ports_add_remove() {
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: bond0
+  type: bond
+  link-aggregation:
+    mode: balance-rr
+    port:
+    - eth1
+    - eth2
+"#,
+    )
+    .unwrap();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: bond0
+  type: bond
+  link-aggregation:
+    mode: balance-rr
+    ports-add:
+    - eth3
+    - eth1
+    ports-remove:
+    - eth2
+"#,
+    )
+    .unwrap();
+
+    ifaces.resolve_ports_add_remove(&cur_ifaces).unwrap();
+
+    let bond0 = &ifaces.kernel_ifaces["bond0"];
+    assert_eq!(bond0.ports(), Some(vec!["eth1", "eth3"]));
+    if let Interface::Bond(bond_iface) = bond0 {
+        let bond_conf = bond_iface.bond.as_ref().unwrap();
+        assert_eq!(bond_conf.ports_add, None);
+        assert_eq!(bond_conf.ports_remove, None);
+    }
+}
+
+#[test]
+fn test_linux_bridge_ports_add_remove() {
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: br0
+  type: linux-bridge
+  bridge:
+    port:
+    - name: eth1
+      stp-priority: 16
+      stp-path-cost: 100
+    - name: eth2
+"#,
+    )
+    .unwrap();
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: br0
+  type: linux-bridge
+  bridge:
+    ports-add:
+    - name: eth1
+      stp-priority: 32
+    - name: eth3
+    ports-remove:
+    - eth2
+"#,
+    )
+    .unwrap();
+
+    ifaces.resolve_ports_add_remove(&cur_ifaces).unwrap();
+
+    if let Interface::LinuxBridge(br_iface) = &ifaces.kernel_ifaces["br0"] {
+        let port_confs = br_iface.bridge.as_ref().unwrap().port.as_ref();
+        let port_confs = port_confs.unwrap();
+        assert_eq!(port_confs.len(), 2);
+        assert_eq!(port_confs[0].name, "eth1");
+        assert_eq!(port_confs[0].stp_priority, Some(32));
+        assert_eq!(port_confs[0].stp_path_cost, Some(100));
+        assert_eq!(port_confs[1].name, "eth3");
+    } else {
+        panic!("Should be linux bridge");
+    }
+}
+
+#[test]
+fn test_ports_add_conflict_with_port_list() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: bond0
+  type: bond
+  link-aggregation:
+    mode: balance-rr
+    port:
+    - eth1
+    ports-add:
+    - eth2
+"#,
+    )
+    .unwrap();
+
+    let result = ifaces.resolve_ports_add_remove(&Interfaces::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{ErrorKind, InfiniBandMode, Interface};
+
+#[test]
+fn test_infiniband_pkey_child() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: mlx5_ib0.8001
+type: infiniband
+infiniband:
+  base-iface: mlx5_ib0
+  pkey: "0x8001"
+  mode: connected
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    assert_eq!(iface.parent(), Some("mlx5_ib0"));
+    if let Interface::InfiniBand(ib_iface) = &iface {
+        let conf = ib_iface.infiniband.as_ref().unwrap();
+        assert_eq!(conf.pkey, Some(0x8001));
+        assert_eq!(conf.mode, InfiniBandMode::Connected);
+    } else {
+        panic!("Expecting InfiniBand interface, got {:?}", iface);
+    }
+    assert!(serde_yaml::to_string(&iface)
+        .unwrap()
+        .contains("pkey: \"0x8001\""));
+}
+
+#[test]
+fn test_infiniband_default_pkey() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: mlx5_ib0
+type: infiniband
+infiniband:
+  pkey: 65535
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    assert_eq!(iface.parent(), None);
+    if let Interface::InfiniBand(ib_iface) = &iface {
+        let conf = ib_iface.infiniband.as_ref().unwrap();
+        assert_eq!(conf.pkey, None);
+        assert_eq!(conf.mode, InfiniBandMode::Datagram);
+    } else {
+        panic!("Expecting InfiniBand interface, got {:?}", iface);
+    }
+}
+
+#[test]
+fn test_infiniband_pkey_without_base_iface() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: mlx5_ib0.8001
+type: infiniband
+infiniband:
+  pkey: "0x8001"
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_infiniband_invalid_pkey() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: mlx5_ib0.8000
+type: infiniband
+infiniband:
+  base-iface: mlx5_ib0
+  pkey: "0x8000"
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_infiniband_verify_full_membership_pkey() {
+    let mut iface: Interface = serde_yaml::from_str(
+        r#"---
+name: mlx5_ib0.0001
+type: infiniband
+infiniband:
+  base-iface: mlx5_ib0
+  pkey: 1
+"#,
+    )
+    .unwrap();
+    iface.pre_verify_cleanup();
+    if let Interface::InfiniBand(ib_iface) = &iface {
+        assert_eq!(ib_iface.infiniband.as_ref().unwrap().pkey, Some(0x8001));
+    } else {
+        panic!("Expecting InfiniBand interface, got {:?}", iface);
+    }
+}
@@ -0,0 +1,831 @@
+use crate::{
+    ip::{
+        check_duplicate_static_addrs, gen_ip_migration_pre_stage,
+        include_current_dhcp_routes_if_dhcp_on_to_off,
+        include_current_ip_address_if_dhcp_on_to_off,
+    },
+    ErrorKind, Interface, InterfaceIpAddr, InterfaceIpv4, InterfaceIpv4Clat,
+    InterfaceIpv4Method, Interfaces, RouteProtocol, Routes,
+};
+
+#[test]
+fn test_ip_migration_from_eth_to_bridge() {
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+    - ip: 198.51.100.1
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let des_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+- name: br0
+  type: linux-bridge
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+  bridge:
+    port:
+    - name: eth1
+"#,
+    )
+    .unwrap();
+
+    let pre_stage =
+        gen_ip_migration_pre_stage(&des_ifaces, &cur_ifaces).unwrap();
+    let ifaces = pre_stage.to_vec();
+    assert_eq!(ifaces.len(), 1);
+    assert_eq!(ifaces[0].name(), "eth1");
+    let addrs = &ifaces[0].base_iface().ipv4.as_ref().unwrap().addresses;
+    assert_eq!(addrs.len(), 1);
+    assert_eq!(addrs[0].ip, "198.51.100.1");
+}
+
+#[test]
+fn test_ip_migration_ignore_iface_not_desired() {
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let des_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+
+    assert!(gen_ip_migration_pre_stage(&des_ifaces, &cur_ifaces).is_none());
+}
+
+#[test]
+fn test_address_announce_default_value_and_not_serialized() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  address-announce:
+    interval: 200
+"#,
+    )
+    .unwrap();
+    let ifaces = ifaces.to_vec();
+    let announce = ifaces[0].base_iface().address_announce.as_ref().unwrap();
+    assert_eq!(announce.count(), 3);
+    assert_eq!(announce.interval(), 200);
+    assert!(!serde_yaml::to_string(ifaces[0])
+        .unwrap()
+        .contains("address-announce"));
+}
+
+#[test]
+fn test_verify_ignore_ipv6_addr_flags() {
+    let des_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  ipv6:
+    enabled: true
+    address:
+    - ip: 2001:db8::1
+      prefix-length: 64
+      flags: [nodad, home]
+"#,
+    )
+    .unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  ipv6:
+    enabled: true
+    address:
+    - ip: 2001:db8::1
+      prefix-length: 64
+"#,
+    )
+    .unwrap();
+
+    des_ifaces.verify(&cur_ifaces).unwrap();
+}
+
+#[test]
+fn test_address_announce_not_supported_by_nm() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  address-announce:
+    count: 5
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    ifaces.validate_backend_support(&cur_ifaces, true).unwrap();
+    let result = ifaces.validate_backend_support(&cur_ifaces, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+}
+
+#[test]
+fn test_ipv6_addr_flags_not_supported_by_nm() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  ipv6:
+    enabled: true
+    address:
+    - ip: 2001:db8::1
+      prefix-length: 64
+      flags: [nodad]
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    ifaces.validate_backend_support(&cur_ifaces, true).unwrap();
+    let result = ifaces.validate_backend_support(&cur_ifaces, false);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+}
+
+#[test]
+fn test_ipv4_addr_flags_not_supported() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+      flags: [nodad]
+"#,
+    )
+    .unwrap();
+    let mut ipv4 = ifaces.to_vec()[0]
+        .base_iface()
+        .ipv4
+        .as_ref()
+        .unwrap()
+        .clone();
+    let result = ipv4.pre_edit_cleanup();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_static_ip_never_default_serialize() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    auto-gateway: false
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let ifaces = ifaces.to_vec();
+    let ipv4 = ifaces[0].base_iface().ipv4.as_ref().unwrap();
+    assert!(ipv4.is_auto_gateway_disabled());
+    assert!(serde_yaml::to_string(ipv4)
+        .unwrap()
+        .contains("auto-gateway: false"));
+}
+
+#[test]
+fn test_ipv4_link_local_split_addresses() {
+    let mut cur_ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+address:
+- ip: 169.254.10.1
+  prefix-length: 16
+- ip: 192.0.2.1
+  prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let nm_ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+link-local: true
+"#,
+    )
+    .unwrap();
+    cur_ipv4.update(&nm_ipv4);
+
+    assert!(cur_ipv4.link_local);
+    assert_eq!(cur_ipv4.addresses.len(), 1);
+    assert_eq!(cur_ipv4.addresses[0].ip, "192.0.2.1");
+    assert_eq!(
+        cur_ipv4.link_local_addresses,
+        vec![InterfaceIpAddr {
+            ip: "169.254.10.1".to_string(),
+            prefix_length: 16,
+            flags: None,
+            allow_duplicate: None,
+        }]
+    );
+
+    // The query only link-local-address should be ignored when applying
+    let new_ipv4: InterfaceIpv4 =
+        serde_yaml::from_str(&serde_yaml::to_string(&cur_ipv4).unwrap())
+            .unwrap();
+    assert!(new_ipv4.link_local);
+    assert!(new_ipv4.link_local_addresses.is_empty());
+}
+
+#[test]
+fn test_ipv4_link_local_verify_ignore_link_local_address() {
+    let des_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+ipv4:
+  enabled: true
+  link-local: true
+"#,
+    )
+    .unwrap();
+    let mut cur_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+ipv4:
+  enabled: true
+  address:
+  - ip: 169.254.10.1
+    prefix-length: 16
+"#,
+    )
+    .unwrap();
+    if let (Some(cur_ipv4), Some(des_ipv4)) = (
+        cur_iface.base_iface_mut().ipv4.as_mut(),
+        des_iface.base_iface().ipv4.as_ref(),
+    ) {
+        cur_ipv4.update(des_ipv4);
+    }
+    assert!(!cur_iface
+        .base_iface()
+        .ipv4
+        .as_ref()
+        .unwrap()
+        .link_local_addresses
+        .is_empty());
+
+    des_iface.verify(&cur_iface).unwrap();
+}
+
+#[test]
+fn test_ipv4_link_local_with_dhcp() {
+    let mut ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+dhcp: true
+link-local: true
+"#,
+    )
+    .unwrap();
+    let result = ipv4.pre_edit_cleanup();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ipv4_shared_method_keep_addresses() {
+    let mut ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+method: shared
+address:
+  - ip: 10.42.0.1
+    prefix-length: 24
+"#,
+    )
+    .unwrap();
+    ipv4.pre_edit_cleanup().unwrap();
+    assert_eq!(ipv4.method, Some(InterfaceIpv4Method::Shared));
+    assert_eq!(ipv4.addresses.len(), 1);
+
+    let value = serde_yaml::to_value(&ipv4).unwrap();
+    assert_eq!(value["method"].as_str(), Some("shared"));
+}
+
+#[test]
+fn test_ipv4_shared_method_with_dhcp() {
+    let mut ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+dhcp: true
+method: shared
+"#,
+    )
+    .unwrap();
+    let result = ipv4.pre_edit_cleanup();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ipv4_switch_back_from_shared_method() {
+    let cur_ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+method: shared
+address:
+  - ip: 10.42.0.1
+    prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let des_ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+dhcp: true
+method: auto
+"#,
+    )
+    .unwrap();
+    let mut ipv4 = cur_ipv4.clone();
+    ipv4.update(&des_ipv4);
+    assert!(!ipv4.is_shared());
+    ipv4.pre_edit_cleanup().unwrap();
+    assert!(ipv4.dhcp);
+    assert!(ipv4.addresses.is_empty());
+
+    let mut des_ipv4 = des_ipv4;
+    des_ipv4.pre_verify_cleanup();
+    assert_eq!(des_ipv4.method, None);
+
+    let mut ipv4 = cur_ipv4;
+    ipv4.update(
+        &serde_yaml::from_str::<InterfaceIpv4>(
+            r#"---
+enabled: true
+method: null
+"#,
+        )
+        .unwrap(),
+    );
+    assert_eq!(ipv4.method, None);
+    assert!(!ipv4.is_shared());
+    assert_eq!(ipv4.addresses.len(), 1);
+}
+
+fn check_dup_addrs(
+    desired: &str,
+    current: &str,
+) -> Result<(), crate::NmstateError> {
+    let mut des_ifaces: Interfaces = serde_yaml::from_str(desired).unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(current).unwrap();
+    let (add_ifaces, chg_ifaces, del_ifaces) =
+        des_ifaces.gen_state_for_apply(&cur_ifaces).unwrap();
+    check_duplicate_static_addrs(
+        &add_ifaces,
+        &chg_ifaces,
+        &del_ifaces,
+        &cur_ifaces,
+    )
+}
+
+const DUP_ADDR_CUR_IFACES: &str = r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: false
+"#;
+
+#[test]
+fn test_duplicate_static_addr_with_current() {
+    let result = check_dup_addrs(
+        r#"---
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+        DUP_ADDR_CUR_IFACES,
+    );
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+        assert!(e.msg().contains("192.0.2.1/24"));
+    }
+}
+
+#[test]
+fn test_duplicate_static_addr_in_desire() {
+    let result = check_dup_addrs(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv6:
+    enabled: true
+    address:
+    - ip: 2001:db8::1
+      prefix-length: 64
+- name: eth2
+  type: ethernet
+  ipv6:
+    enabled: true
+    address:
+    - ip: 2001:db8:0::1
+      prefix-length: 64
+"#,
+        DUP_ADDR_CUR_IFACES,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_duplicate_static_addr_allowed() {
+    check_dup_addrs(
+        r#"---
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+      allow-duplicate: true
+"#,
+        DUP_ADDR_CUR_IFACES,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_duplicate_static_addr_moved_or_different_prefix() {
+    check_dup_addrs(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    address: []
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+        DUP_ADDR_CUR_IFACES,
+    )
+    .unwrap();
+    check_dup_addrs(
+        r#"---
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 32
+"#,
+        DUP_ADDR_CUR_IFACES,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_duplicate_static_addr_only_in_current() {
+    let cur_ifaces = r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+- name: eth3
+  type: ethernet
+"#;
+    check_dup_addrs(
+        r#"---
+- name: eth3
+  type: ethernet
+  mtu: 1400
+"#,
+        cur_ifaces,
+    )
+    .unwrap();
+    check_dup_addrs(
+        r#"---
+- name: eth1
+  type: ethernet
+  mtu: 1400
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+        cur_ifaces,
+    )
+    .unwrap();
+    let result = check_dup_addrs(
+        r#"---
+- name: eth3
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+        cur_ifaces,
+    );
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_duplicate_static_addr_in_different_vrf() {
+    check_dup_addrs(
+        r#"---
+- name: vrf1
+  type: vrf
+  vrf:
+    port:
+    - eth2
+    route-table-id: 100
+- name: eth2
+  type: ethernet
+  ipv4:
+    enabled: true
+    address:
+    - ip: 192.0.2.1
+      prefix-length: 24
+"#,
+        DUP_ADDR_CUR_IFACES,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_dhcp_on_to_off_include_dhcp_routes() {
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    dhcp: true
+    address:
+    - ip: 192.0.2.100
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let mut cur_routes: Routes = serde_yaml::from_str(
+        r#"---
+running:
+- destination: 0.0.0.0/0
+  next-hop-interface: eth1
+  next-hop-address: 192.0.2.1
+  metric: 100
+  table-id: 254
+- destination: 198.51.100.0/24
+  next-hop-interface: eth1
+  next-hop-address: 192.0.2.2
+  metric: 100
+  table-id: 500
+- destination: 203.0.113.0/24
+  next-hop-interface: eth1
+  next-hop-address: 192.0.2.3
+  metric: 100
+  table-id: 254
+"#,
+    )
+    .unwrap();
+    for route in cur_routes.running.as_mut().unwrap() {
+        route.protocol = Some(RouteProtocol::Dhcp);
+    }
+    let des_routes: Routes = serde_yaml::from_str(
+        r#"---
+config:
+- destination: 203.0.113.0/24
+  state: absent
+"#,
+    )
+    .unwrap();
+    let mut chg_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    dhcp: false
+"#,
+    )
+    .unwrap();
+
+    include_current_dhcp_routes_if_dhcp_on_to_off(
+        &mut chg_ifaces,
+        &cur_ifaces,
+        &des_routes,
+        &cur_routes,
+    );
+
+    let routes = chg_ifaces.kernel_ifaces["eth1"]
+        .base_iface()
+        .routes
+        .as_ref()
+        .unwrap();
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].destination.as_deref(), Some("0.0.0.0/0"));
+    assert_eq!(routes[0].metric, Some(100));
+    assert_eq!(routes[0].table_id, Some(254));
+    assert_eq!(routes[0].protocol, None);
+    assert_eq!(routes[1].destination.as_deref(), Some("198.51.100.0/24"));
+    assert_eq!(routes[1].table_id, Some(500));
+}
+
+#[test]
+fn test_dhcp_on_to_off_not_keep_dynamic_addresses() {
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ipv4:
+    enabled: true
+    dhcp: true
+    address:
+    - ip: 192.0.2.100
+      prefix-length: 24
+"#,
+    )
+    .unwrap();
+    let mut cur_routes: Routes = serde_yaml::from_str(
+        r#"---
+running:
+- destination: 0.0.0.0/0
+  next-hop-interface: eth1
+  next-hop-address: 192.0.2.1
+"#,
+    )
+    .unwrap();
+    for route in cur_routes.running.as_mut().unwrap() {
+        route.protocol = Some(RouteProtocol::Dhcp);
+    }
+    let mut chg_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  keep-dynamic-addresses: false
+  ipv4:
+    enabled: true
+    dhcp: false
+"#,
+    )
+    .unwrap();
+
+    include_current_ip_address_if_dhcp_on_to_off(&mut chg_ifaces, &cur_ifaces);
+    include_current_dhcp_routes_if_dhcp_on_to_off(
+        &mut chg_ifaces,
+        &cur_ifaces,
+        &Routes::new(),
+        &cur_routes,
+    );
+
+    let base_iface = chg_ifaces.kernel_ifaces["eth1"].base_iface();
+    assert!(base_iface.ipv4.as_ref().unwrap().addresses.is_empty());
+    assert!(base_iface.routes.is_none());
+    assert!(!serde_yaml::to_string(base_iface)
+        .unwrap()
+        .contains("keep-dynamic-addresses"));
+}
+
+#[test]
+fn test_ipv4_clat_without_dhcp() {
+    let mut ipv4: InterfaceIpv4 = serde_yaml::from_str(
+        r#"---
+enabled: true
+dhcp: false
+clat: auto
+"#,
+    )
+    .unwrap();
+    let result = ipv4.pre_edit_cleanup();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ipv4_clat_require_ipv6_autoconf() {
+    let mut iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+ipv4:
+  enabled: true
+  dhcp: true
+  clat: force
+ipv6:
+  enabled: true
+  dhcp: true
+  autoconf: false
+"#,
+    )
+    .unwrap();
+    let result = iface.pre_edit_cleanup();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ipv4_clat_serialize() {
+    let mut iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1
+type: ethernet
+ipv4:
+  enabled: true
+  dhcp: true
+  clat: auto
+ipv6:
+  enabled: true
+  dhcp: true
+  autoconf: true
+"#,
+    )
+    .unwrap();
+    iface.pre_edit_cleanup().unwrap();
+    let ipv4 = iface.base_iface().ipv4.as_ref().unwrap();
+    assert_eq!(ipv4.clat, Some(InterfaceIpv4Clat::Auto));
+
+    let value = serde_yaml::to_value(ipv4).unwrap();
+    assert_eq!(value["clat"].as_str(), Some("auto"));
+}
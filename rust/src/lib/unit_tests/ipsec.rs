@@ -0,0 +1,52 @@
+use crate::{ErrorKind, Interface, InterfaceType, Interfaces, NetworkState};
+
+#[test]
+fn test_ipsec_psk_with_cert() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: hosta_conn
+type: ipsec
+libreswan:
+  right: 192.0.2.251
+  leftcert: hosta.example.org
+  psk: my-secret-psk
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ipsec_is_userspace_and_hide_psk() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: hosta_conn
+  type: ipsec
+  libreswan:
+    right: 192.0.2.251
+    rightid: hostb.example.org
+    ikev2: insist
+    psk: my-secret-psk
+"#,
+    )
+    .unwrap();
+    ifaces.hide_secrets();
+    assert!(ifaces.kernel_ifaces.is_empty());
+    let iface = ifaces
+        .get_iface("hosta_conn", InterfaceType::Ipsec)
+        .unwrap()
+        .clone();
+    if let Interface::Ipsec(iface) = iface {
+        iface.validate().unwrap();
+        assert_eq!(
+            iface.libreswan.as_ref().unwrap().psk.as_deref(),
+            Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+        );
+    } else {
+        panic!("Expecting IPsec interface, got {:?}", iface);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{ErrorKind, Interface, IpVlanMode};
+
+#[test]
+fn test_ipvlan_private_and_vepa() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: ipvlan0
+type: ipvlan
+ipvlan:
+  base-iface: eth1
+  private: true
+  vepa: true
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ipvlan_default_mode() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: ipvlan0
+type: ipvlan
+ipvlan:
+  base-iface: eth1
+  private: true
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    assert_eq!(iface.parent(), Some("eth1"));
+    if let Interface::IpVlan(iface) = iface {
+        let conf = iface.ipvlan.as_ref().unwrap();
+        assert_eq!(conf.mode, IpVlanMode::L3);
+        assert_eq!(conf.private, Some(true));
+        assert_eq!(conf.vepa, None);
+    } else {
+        panic!("Expecting IPVLAN interface, got {:?}", iface);
+    }
+}
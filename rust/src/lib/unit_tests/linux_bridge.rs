@@ -0,0 +1,167 @@
+use crate::{
+    ErrorKind, LinuxBridgeInterface, LinuxBridgeOptions,
+    LinuxBridgeVlanProtocol,
+};
+
+#[test]
+fn test_linux_bridge_multicast_igmp_and_mld_version() {
+    let mut br_opts = LinuxBridgeOptions::new();
+    br_opts.multicast_igmp_version = Some(3);
+    br_opts.multicast_mld_version = Some(2);
+    br_opts.validate().unwrap();
+
+    br_opts.multicast_igmp_version = Some(1);
+    let result = br_opts.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+
+    br_opts.multicast_igmp_version = None;
+    br_opts.multicast_mld_version = Some(3);
+    let result = br_opts.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_linux_bridge_multicast_version_changed() {
+    let cur_iface: LinuxBridgeInterface = serde_yaml::from_str(
+        r#"---
+name: br0
+type: linux-bridge
+state: up
+bridge:
+  options:
+    multicast-igmp-version: 2
+    multicast-mld-version: 1
+"#,
+    )
+    .unwrap();
+    let mut des_iface = cur_iface.clone();
+    assert!(!des_iface.is_multicast_versions_changed(Some(&cur_iface)));
+    assert!(des_iface.is_multicast_versions_changed(None));
+
+    if let Some(br_opts) = des_iface
+        .bridge
+        .as_mut()
+        .and_then(|br_conf| br_conf.options.as_mut())
+    {
+        br_opts.multicast_igmp_version = Some(3);
+    }
+    assert!(des_iface.is_multicast_versions_changed(Some(&cur_iface)));
+}
+
+#[test]
+fn test_linux_bridge_group_forward_mask() {
+    let mut br_opts = LinuxBridgeOptions::new();
+    // LLDP
+    br_opts.group_forward_mask = Some(0x4000);
+    br_opts.validate().unwrap();
+
+    br_opts.group_fwd_mask = Some(0x4008);
+    let result = br_opts.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+
+    // LACP
+    br_opts.group_forward_mask = None;
+    br_opts.group_fwd_mask = Some(0x4);
+    let result = br_opts.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_linux_bridge_vlan_protocol() {
+    let br_opts: LinuxBridgeOptions =
+        serde_yaml::from_str("vlan-protocol: 802.1ad").unwrap();
+    assert_eq!(
+        br_opts.vlan_protocol,
+        Some(LinuxBridgeVlanProtocol::Ieee8021Ad)
+    );
+    assert_eq!(
+        br_opts.vlan_protocol.as_ref().map(|p| p.to_string()),
+        Some("802.1ad".to_string())
+    );
+}
+
+#[test]
+fn test_linux_bridge_port_vlan_trunk_native() {
+    let br_iface: LinuxBridgeInterface = serde_yaml::from_str(
+        r#"---
+name: br0
+type: linux-bridge
+bridge:
+  port:
+  - name: eth1
+    vlan:
+      mode: trunk
+      enable-native: true
+      tag: 100
+      trunk-tags:
+      - id: 101
+      - id-range:
+          min: 200
+          max: 299
+  - name: eth2
+    vlan: {}
+"#,
+    )
+    .unwrap();
+    br_iface.validate().unwrap();
+    assert!(br_iface.vlan_filtering_is_enabled());
+}
+
+#[test]
+fn test_linux_bridge_port_vlan_invalid() {
+    for vlan_conf in [
+        "{mode: access, tag: 100, trunk-tags: [{id: 101}]}",
+        "{mode: trunk, tag: 100}",
+        "{mode: trunk, tag: 100, trunk-tags: [{id: 101}]}",
+        "{mode: trunk, enable-native: true, trunk-tags: [{id: 101}]}",
+        "{mode: trunk, trunk-tags: [{id-range: {min: 300, max: 200}}]}",
+        "{mode: access, tag: 4095}",
+    ] {
+        let br_iface: LinuxBridgeInterface = serde_yaml::from_str(&format!(
+            r#"---
+name: br0
+type: linux-bridge
+bridge:
+  port:
+  - name: eth1
+    vlan: {}
+"#,
+            vlan_conf
+        ))
+        .unwrap();
+        let result = br_iface.validate();
+        assert!(result.is_err(), "{} should be invalid", vlan_conf);
+        if let Err(e) = result {
+            assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+        }
+    }
+}
+
+#[test]
+fn test_linux_bridge_empty_port_vlan_disable_filtering() {
+    let br_iface: LinuxBridgeInterface = serde_yaml::from_str(
+        r#"---
+name: br0
+type: linux-bridge
+bridge:
+  port:
+  - name: eth1
+    vlan: {}
+"#,
+    )
+    .unwrap();
+    br_iface.validate().unwrap();
+    assert!(!br_iface.vlan_filtering_is_enabled());
+}
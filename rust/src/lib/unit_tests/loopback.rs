@@ -0,0 +1,110 @@
+use crate::{ErrorKind, Interface};
+
+#[test]
+fn test_loopback_verify_ignore_implicit_addrs() {
+    let des_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: lo
+type: loopback
+state: up
+mtu: 65535
+ipv4:
+  enabled: true
+  address:
+  - ip: 192.0.2.1
+    prefix-length: 32
+ipv6:
+  enabled: true
+  address:
+  - ip: 2001:db8::1
+    prefix-length: 128
+"#,
+    )
+    .unwrap();
+    let cur_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: lo
+type: loopback
+state: up
+mtu: 65535
+ipv4:
+  enabled: true
+  address:
+  - ip: 127.0.0.1
+    prefix-length: 8
+  - ip: 192.0.2.1
+    prefix-length: 32
+ipv6:
+  enabled: true
+  address:
+  - ip: ::1
+    prefix-length: 128
+  - ip: 2001:db8::1
+    prefix-length: 128
+"#,
+    )
+    .unwrap();
+    des_iface.validate().unwrap();
+    des_iface.verify(&cur_iface).unwrap();
+}
+
+#[test]
+fn test_loopback_verify_missing_extra_addr() {
+    let des_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: lo
+type: loopback
+ipv4:
+  enabled: true
+  address:
+  - ip: 192.0.2.1
+    prefix-length: 32
+"#,
+    )
+    .unwrap();
+    let cur_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: lo
+type: loopback
+ipv4:
+  enabled: true
+  address:
+  - ip: 127.0.0.1
+    prefix-length: 8
+"#,
+    )
+    .unwrap();
+    let e = des_iface.verify(&cur_iface).unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::VerificationError);
+}
+
+#[test]
+fn test_loopback_cannot_enable_dhcp() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: lo
+type: loopback
+ipv4:
+  enabled: true
+  dhcp: true
+"#,
+    )
+    .unwrap();
+    let e = iface.validate().unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_loopback_cannot_disable_ipv6() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: lo
+type: loopback
+ipv6:
+  enabled: false
+"#,
+    )
+    .unwrap();
+    let e = iface.validate().unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+}
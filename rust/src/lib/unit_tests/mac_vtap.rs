@@ -0,0 +1,94 @@
+use crate::{
+    persist::get_runtime_only_issues, Interface, Interfaces, MacVtapInterface,
+    PersistIssueReason,
+};
+
+#[test]
+fn test_mac_vtap_tap_owner() {
+    let iface: MacVtapInterface = serde_yaml::from_str(
+        r#"---
+name: macvtap0
+type: mac-vtap
+state: up
+mac-vtap:
+  base-iface: eth1
+  mode: passthru
+  promiscuous: false
+  tap-owner: 1000
+  tap-group: 100
+"#,
+    )
+    .unwrap();
+
+    let conf = iface.mac_vtap.as_ref().unwrap();
+    assert_eq!(conf.accept_all_mac, Some(false));
+    assert_eq!(conf.tap_owner, Some(1000));
+    assert_eq!(conf.tap_group, Some(100));
+}
+
+#[test]
+fn test_mac_vtap_update_keep_tap_owner() {
+    let mut iface: Interface = serde_yaml::from_str(
+        r#"---
+name: macvtap0
+type: mac-vtap
+state: up
+mac-vtap:
+  base-iface: eth1
+  mode: passthru
+  tap-owner: 1000
+  tap-group: 100
+"#,
+    )
+    .unwrap();
+    let other: Interface = serde_yaml::from_str(
+        r#"---
+name: macvtap0
+type: mac-vtap
+state: up
+mac-vtap:
+  base-iface: eth1
+  mode: passthru
+  tap-group: 10
+"#,
+    )
+    .unwrap();
+
+    iface.update(&other);
+
+    if let Interface::MacVtap(iface) = iface {
+        let conf = iface.mac_vtap.as_ref().unwrap();
+        assert_eq!(conf.tap_owner, Some(1000));
+        assert_eq!(conf.tap_group, Some(10));
+    } else {
+        panic!("Expecting MacVtap interface");
+    }
+}
+
+#[test]
+fn test_mac_vtap_tap_owner_runtime_only() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: macvtap0
+  type: mac-vtap
+  state: up
+  mac-vtap:
+    base-iface: eth1
+    mode: passthru
+    tap-owner: 1000
+- name: macvtap1
+  type: mac-vtap
+  state: up
+  mac-vtap:
+    base-iface: eth1
+    mode: passthru
+    tap-owner: 0
+    tap-group: 0
+"#,
+    )
+    .unwrap();
+    let issues = get_runtime_only_issues(&ifaces);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].name, "macvtap0");
+    assert_eq!(issues[0].reason, PersistIssueReason::RuntimeOnlyTapOwner);
+}
@@ -0,0 +1,93 @@
+use crate::{ErrorKind, Interface, MacSecOffload, NetworkState};
+
+const CAK: &str = "50b71a8ef0bd5751ea76de6d6c98c03a";
+const CKN: &str =
+    "f2b4297d39da7330910a74abc0449feb45b5c0b9fc23df1430e1898fcf1c4550";
+
+#[test]
+fn test_macsec_offload_and_mka() {
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: macsec0
+type: macsec
+macsec:
+  base-iface: eth1
+  encrypt: true
+  mka-cak: {}
+  mka-ckn: {}
+  port: 1
+  validation: strict
+  send-sci: true
+  offload: mac
+"#,
+        CAK, CKN
+    ))
+    .unwrap();
+    iface.validate().unwrap();
+    assert_eq!(iface.parent(), Some("eth1"));
+    if let Interface::MacSec(iface) = iface {
+        let conf = iface.macsec.as_ref().unwrap();
+        assert_eq!(conf.offload, Some(MacSecOffload::Mac));
+    } else {
+        panic!("Expecting MACsec interface, got {:?}", iface);
+    }
+}
+
+#[test]
+fn test_macsec_invalid_cak() {
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: macsec0
+type: macsec
+macsec:
+  base-iface: eth1
+  mka-cak: 50b71a8ef0
+  mka-ckn: {}
+"#,
+        CKN
+    ))
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_macsec_cak_without_ckn() {
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: macsec0
+type: macsec
+macsec:
+  base-iface: eth1
+  mka-cak: {}
+"#,
+        CAK
+    ))
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_macsec_hidden_cak_is_valid() {
+    let iface: Interface = serde_yaml::from_str(&format!(
+        r#"---
+name: macsec0
+type: macsec
+macsec:
+  base-iface: eth1
+  mka-cak: "{}"
+  mka-ckn: {}
+"#,
+        NetworkState::PASSWORD_HID_BY_NMSTATE,
+        CKN
+    ))
+    .unwrap();
+    iface.validate().unwrap();
+}
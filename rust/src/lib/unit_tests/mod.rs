@@ -1,8 +1,52 @@
 #[cfg(test)]
+mod anonymize;
+#[cfg(test)]
+mod audit;
+#[cfg(test)]
+mod bond;
+#[cfg(test)]
+mod error;
+#[cfg(test)]
+mod ethtool;
+#[cfg(test)]
+mod geneve;
+#[cfg(test)]
+mod hostname;
+#[cfg(test)]
+mod hsr;
+#[cfg(test)]
 mod ifaces;
 #[cfg(test)]
 mod ifaces_ctrller;
 #[cfg(test)]
+mod infiniband;
+#[cfg(test)]
+mod ip;
+#[cfg(test)]
+mod ipsec;
+#[cfg(test)]
+mod ipvlan;
+#[cfg(test)]
+mod linux_bridge;
+#[cfg(test)]
+mod loopback;
+#[cfg(test)]
+mod mac_vtap;
+#[cfg(test)]
+mod macsec;
+#[cfg(test)]
+mod nat64;
+#[cfg(test)]
+mod net_state;
+#[cfg(test)]
+mod ovs;
+#[cfg(test)]
+mod ovsdb;
+#[cfg(test)]
+mod pppoe;
+#[cfg(test)]
+mod profile;
+#[cfg(test)]
 mod route;
 #[cfg(test)]
 mod route_rule;
@@ -10,3 +54,15 @@ mod route_rule;
 mod sriov;
 #[cfg(test)]
 mod testlib;
+#[cfg(test)]
+mod veth;
+#[cfg(test)]
+mod vlan;
+#[cfg(test)]
+mod vrf;
+#[cfg(test)]
+mod wifi;
+#[cfg(test)]
+mod wireguard;
+#[cfg(test)]
+mod xfrm;
@@ -0,0 +1,25 @@
+use std::net::Ipv6Addr;
+
+use crate::nat64::nat64_prefix_of;
+
+#[test]
+fn test_nat64_prefix_well_known() {
+    let addr: Ipv6Addr = "64:ff9b::c000:aa".parse().unwrap();
+    assert_eq!(nat64_prefix_of(&addr), Some("64:ff9b::/96".to_string()));
+}
+
+#[test]
+fn test_nat64_prefix_skip_reserved_octet() {
+    // 192.0.0.171 embedded into /56 prefix with byte 8 reserved
+    let addr: Ipv6Addr = "2001:db8:1:2c0:0:ab::".parse().unwrap();
+    assert_eq!(
+        nat64_prefix_of(&addr),
+        Some("2001:db8:1:200::/56".to_string())
+    );
+}
+
+#[test]
+fn test_nat64_prefix_not_synthesized() {
+    let addr: Ipv6Addr = "2001:db8::1".parse().unwrap();
+    assert_eq!(nat64_prefix_of(&addr), None);
+}
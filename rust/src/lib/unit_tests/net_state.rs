@@ -0,0 +1,292 @@
+use crate::{
+    net_state::with_retry, ErrorKind, ErrorMsg, InterfaceType, NetworkState,
+    NmstateError,
+};
+
+#[test]
+fn test_gen_plan_with_current() {
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    mtu: 1500
+"#,
+    )
+    .unwrap();
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    mtu: 9000
+  - name: dummy0
+    type: dummy
+    state: up
+"#,
+    )
+    .unwrap();
+
+    let (add_state, chg_state, del_state) =
+        des_state.gen_plan_with_current(&cur_state).unwrap();
+
+    let add_ifaces = add_state.interfaces.to_vec();
+    assert_eq!(add_ifaces.len(), 1);
+    assert_eq!(add_ifaces[0].name(), "dummy0");
+    let chg_ifaces = chg_state.interfaces.to_vec();
+    assert_eq!(chg_ifaces.len(), 1);
+    assert_eq!(chg_ifaces[0].name(), "eth1");
+    assert_eq!(chg_ifaces[0].iface_type(), InterfaceType::Ethernet);
+    assert_eq!(chg_ifaces[0].base_iface().mtu, Some(9000));
+    assert!(del_state.interfaces.to_vec().is_empty());
+}
+
+#[test]
+fn test_gen_plan_with_current_invalid_state() {
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+route-rules:
+  config:
+    - ip-from: 192.0.2.0/24
+      ip-to: 2001:db8::/64
+"#,
+    )
+    .unwrap();
+
+    let result = des_state.gen_plan_with_current(&NetworkState::new());
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidArgument);
+}
+
+#[test]
+fn test_deserialize_net_state_ignore_unknown_section() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    mtu: 1500
+unknown-section:
+  - foo: bar
+routes:
+  config:
+    - destination: 198.51.100.0/24
+      next-hop-interface: eth1
+"#,
+    )
+    .unwrap();
+
+    assert!(net_state.prop_list.contains(&"interfaces"));
+    assert!(net_state.prop_list.contains(&"routes"));
+    assert!(!net_state.prop_list.contains(&"dns"));
+    assert_eq!(
+        net_state.interfaces.kernel_ifaces["eth1"].iface_type(),
+        InterfaceType::Ethernet
+    );
+    assert_eq!(net_state.routes.config.as_ref().map(|r| r.len()), Some(1));
+}
+
+#[test]
+fn test_deserialize_net_state_empty() {
+    let net_state: NetworkState = serde_yaml::from_str("---\n").unwrap();
+    assert!(net_state.prop_list.is_empty());
+
+    let net_state = NetworkState::new_from_json("{}").unwrap();
+    assert!(net_state.prop_list.is_empty());
+}
+
+#[test]
+fn test_gen_state_id_ignore_key_order() {
+    let state1: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: br0
+    type: ovs-bridge
+    ovs-db:
+      external_ids:
+        a: "1"
+        b: "2"
+"#,
+    )
+    .unwrap();
+    let state2: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - type: ovs-bridge
+    name: br0
+    ovs-db:
+      external_ids:
+        b: "2"
+        a: "1"
+"#,
+    )
+    .unwrap();
+    let state3: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: br0
+    type: ovs-bridge
+"#,
+    )
+    .unwrap();
+
+    let state_id = state1.gen_state_id().unwrap();
+    assert_eq!(state_id.len(), 16);
+    assert_eq!(state_id, state2.gen_state_id().unwrap());
+    assert_ne!(state_id, state3.gen_state_id().unwrap());
+}
+
+#[test]
+fn test_gen_plan_dns_search_only_on_ipv6_only_iface() {
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    ipv4:
+      enabled: false
+    ipv6:
+      enabled: false
+"#,
+    )
+    .unwrap();
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+dns-resolver:
+  config:
+    search:
+      - example.org
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    ipv4:
+      enabled: false
+    ipv6:
+      enabled: true
+      dhcp: true
+      autoconf: true
+      auto-dns: false
+"#,
+    )
+    .unwrap();
+
+    let (_, chg_state, _) =
+        des_state.gen_plan_with_current(&cur_state).unwrap();
+
+    let iface = &chg_state.interfaces.kernel_ifaces["eth1"];
+    let dns = iface
+        .base_iface()
+        .ipv6
+        .as_ref()
+        .and_then(|i| i.dns.as_ref())
+        .unwrap();
+    assert_eq!(dns.search, Some(vec!["example.org".to_string()]));
+}
+
+#[test]
+fn test_verify_retry_exhausted_tagged_as_timeout() {
+    let e = with_retry(1, 3, || {
+        Err(NmstateError::from_msg(
+            ErrorKind::VerificationError,
+            ErrorMsg::VERIFY_IFACE_NO_CARRIER,
+            &[("interface", "eth1".to_string())],
+        ))
+    })
+    .unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::VerificationError);
+    assert_eq!(
+        e.context()
+            .get(NmstateError::CONTEXT_VERIFY_TIMEOUT)
+            .map(|s| s.as_str()),
+        Some("3")
+    );
+    with_retry(0, 3, || Ok(())).unwrap();
+}
+
+#[test]
+fn test_verify_retry_exhausted_mismatch_not_tagged_as_timeout() {
+    let e = with_retry(1, 3, || {
+        Err(NmstateError::from_msg(
+            ErrorKind::VerificationError,
+            ErrorMsg::VERIFY_MISMATCH,
+            &[
+                ("property", "eth1.interface.mtu".to_string()),
+                ("desire", "9000".to_string()),
+                ("current", "1500".to_string()),
+            ],
+        ))
+    })
+    .unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::VerificationError);
+    assert!(!e
+        .context()
+        .contains_key(NmstateError::CONTEXT_VERIFY_TIMEOUT));
+}
+
+#[test]
+fn test_batch_later_state_overrides_earlier() {
+    let stage1: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    mtu: 1500
+  - name: eth2
+    type: ethernet
+    state: up
+    mtu: 1400
+"#,
+    )
+    .unwrap();
+    let stage2: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    mtu: 9000
+"#,
+    )
+    .unwrap();
+    let merged = NetworkState::merge_batch_states(&[&stage1, &stage2]).unwrap();
+
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    mtu: 9000
+  - name: eth2
+    type: ethernet
+    state: up
+    mtu: 1400
+"#,
+    )
+    .unwrap();
+    merged.verify(&cur_state).unwrap();
+
+    // The earlier state not overridden is still verified
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: eth1
+    type: ethernet
+    state: up
+    mtu: 9000
+  - name: eth2
+    type: ethernet
+    state: up
+    mtu: 1500
+"#,
+    )
+    .unwrap();
+    let result = merged.verify(&cur_state);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+    }
+}
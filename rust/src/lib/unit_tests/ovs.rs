@@ -0,0 +1,168 @@
+use crate::{
+    ErrorKind, Interface, Interfaces, OvsBridgeBondConfig,
+    OvsBridgeBondLacpMode, OvsBridgeBondPortConfig,
+};
+
+fn new_ovs_bond_conf(port_names: &[&str]) -> OvsBridgeBondConfig {
+    let mut bond_conf = OvsBridgeBondConfig::new();
+    bond_conf.ports = Some(
+        port_names
+            .iter()
+            .map(|name| OvsBridgeBondPortConfig {
+                name: name.to_string(),
+            })
+            .collect(),
+    );
+    bond_conf
+}
+
+#[test]
+fn test_ovs_bond_primary_not_port() {
+    let mut bond_conf = new_ovs_bond_conf(&["eth1", "eth2"]);
+    bond_conf.lacp = Some(OvsBridgeBondLacpMode::Active);
+    bond_conf.bond_primary = Some("eth3".to_string());
+    let result = bond_conf.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+
+    bond_conf.bond_primary = Some("eth2".to_string());
+    bond_conf.validate().unwrap();
+}
+
+#[test]
+fn test_ovsdb_external_ids_template() {
+    let mut desired: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  mtu: 9000
+  ovs-db:
+    external_ids:
+      hw-id: "mac-${iface.mac-address}"
+      mtu: "${iface.mtu}"
+      static: "abc"
+"#,
+    )
+    .unwrap();
+    let current: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  mac-address: "00:23:45:67:89:1A"
+  mtu: 1500
+"#,
+    )
+    .unwrap();
+    desired.resolve_ovsdb_templates(&current).unwrap();
+    let ext_ids = desired.kernel_ifaces["eth1"]
+        .base_iface()
+        .ovsdb
+        .as_ref()
+        .unwrap()
+        .external_ids
+        .as_ref()
+        .unwrap();
+    assert_eq!(ext_ids["hw-id"], "mac-00:23:45:67:89:1A");
+    assert_eq!(ext_ids["mtu"], "9000");
+    assert_eq!(ext_ids["static"], "abc");
+}
+
+#[test]
+fn test_ovsdb_external_ids_template_not_found() {
+    let mut desired: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  ovs-db:
+    external_ids:
+      hw-id: "${iface.not-exist}"
+"#,
+    )
+    .unwrap();
+    let result = desired.resolve_ovsdb_templates(&Interfaces::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ovsdb_on_ovs_system_port() {
+    let mut desired: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: br0
+  type: ovs-bridge
+  state: up
+  bridge:
+    port:
+    - name: eth1
+- name: eth1
+  type: ethernet
+  state: up
+  ovs-db:
+    external_ids:
+      foo: "abc"
+"#,
+    )
+    .unwrap();
+    let current: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    desired.gen_state_for_apply(&current).unwrap();
+}
+
+#[test]
+fn test_ovsdb_on_non_ovs_iface() {
+    let mut desired: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+  ovs-db:
+    external_ids:
+      foo: "abc"
+"#,
+    )
+    .unwrap();
+    let current: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    let result = desired.gen_state_for_apply(&current);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ovs_port_vlan_invalid() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: br0
+type: ovs-bridge
+bridge:
+  port:
+  - name: eth1
+    vlan:
+      mode: access
+      tag: 10
+      trunk-tags:
+      - id: 20
+"#,
+    )
+    .unwrap();
+    let e = iface.validate().unwrap_err();
+    assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+}
@@ -0,0 +1,184 @@
+use std::io::Write;
+use std::os::unix::net::UnixListener;
+
+use serde_json::{json, Value};
+
+use crate::{
+    ovsdb::OvsDbClient, ErrorKind, LinuxBridgePortTunkTag,
+    LinuxBridgePortVlanMode, LinuxBridgePortVlanRange, OvsDbStatus,
+};
+
+#[test]
+fn test_ovsdb_status_not_running() {
+    let status = OvsDbStatus::retrieve_from("/tmp/nmstate_not_exist.sock");
+    assert!(!status.socket_exists);
+    assert!(!status.connected);
+    assert!(status.error.is_some());
+}
+
+#[test]
+fn test_ovsdb_status_get_versions() {
+    let socket_path = std::env::temp_dir()
+        .join(format!("nmstate_{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path).unwrap();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let replies = [
+            json!({"version": "8.3.0", "name": "Open_vSwitch"}),
+            json!([{"rows": [{"ovs_version": "2.17.2"}]}]),
+        ];
+        let requests =
+            serde_json::Deserializer::from_reader(&stream).into_iter::<Value>();
+        for (request, reply) in requests.zip(replies) {
+            let request = request.unwrap();
+            let msg = json!({
+                "id": request["id"],
+                "result": reply,
+                "error": null,
+            });
+            writer.write_all(msg.to_string().as_bytes()).unwrap();
+        }
+    });
+    let status = OvsDbStatus::retrieve_from(socket_path.to_str().unwrap());
+    server.join().unwrap();
+    let _ = std::fs::remove_file(&socket_path);
+
+    assert!(status.socket_exists);
+    assert!(status.connected);
+    assert_eq!(status.error, None);
+    assert_eq!(status.schema_version.as_deref(), Some("8.3.0"));
+    assert_eq!(status.server_version.as_deref(), Some("2.17.2"));
+}
+
+fn start_mock_ovsdb(
+    name: &str,
+    replies: Vec<Value>,
+) -> (std::path::PathBuf, std::thread::JoinHandle<usize>) {
+    let socket_path = std::env::temp_dir().join(format!(
+        "nmstate_{}_{}.sock",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path).unwrap();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let requests =
+            serde_json::Deserializer::from_reader(&stream).into_iter::<Value>();
+        let mut count = 0;
+        for (request, reply) in requests.zip(replies) {
+            let request = request.unwrap();
+            let msg = json!({
+                "id": request["id"],
+                "result": reply,
+                "error": null,
+            });
+            writer.write_all(msg.to_string().as_bytes()).unwrap();
+            count += 1;
+        }
+        count
+    });
+    (socket_path, server)
+}
+
+#[test]
+fn test_ovsdb_transact_retry_on_busy_server() {
+    let (socket_path, server) = start_mock_ovsdb(
+        "retry",
+        vec![
+            json!([{}, {"error": "timed out", "details": "busy"}]),
+            json!([{"rows": []}]),
+        ],
+    );
+    let mut client =
+        OvsDbClient::connect(socket_path.to_str().unwrap()).unwrap();
+    let reply = client.transact(&[json!({"op": "select"})]).unwrap();
+    drop(client);
+    assert_eq!(server.join().unwrap(), 2);
+    let _ = std::fs::remove_file(&socket_path);
+
+    assert_eq!(reply, json!([{"rows": []}]));
+}
+
+#[test]
+fn test_ovsdb_transact_permanent_error() {
+    let (socket_path, server) = start_mock_ovsdb(
+        "permanent",
+        vec![json!([
+            {"uuid": ["uuid", "2f9d4d6b-5b2c-4e21-8f93-bd8c2d15f9f1"]},
+            {"error": "constraint violation", "details": "bad name"},
+        ])],
+    );
+    let mut client =
+        OvsDbClient::connect(socket_path.to_str().unwrap()).unwrap();
+    let result = client.transact(&[
+        json!({"op": "insert", "table": "Bridge"}),
+        json!({"op": "update", "table": "Port"}),
+    ]);
+    drop(client);
+    assert_eq!(server.join().unwrap(), 1);
+    let _ = std::fs::remove_file(&socket_path);
+
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::OvsDbError);
+        assert!(e.msg().contains("\"table\":\"Port\""));
+        assert!(e.msg().contains("constraint violation"));
+    }
+}
+
+#[test]
+fn test_ovsdb_get_port_vlan_confs() {
+    let (socket_path, server) = start_mock_ovsdb(
+        "port_vlan",
+        vec![json!([{"rows": [
+            {
+                "name": "eth1",
+                "tag": 10,
+                "trunks": ["set", [100, 200, 201, 202]],
+                "vlan_mode": "native-untagged",
+            },
+            {
+                "name": "eth2",
+                "tag": 20,
+                "trunks": ["set", []],
+                "vlan_mode": ["set", []],
+            },
+            {
+                "name": "eth3",
+                "tag": ["set", []],
+                "trunks": ["set", []],
+                "vlan_mode": ["set", []],
+            },
+        ]}])],
+    );
+    let mut client =
+        OvsDbClient::connect(socket_path.to_str().unwrap()).unwrap();
+    let vlan_confs = client.get_port_vlan_confs().unwrap();
+    drop(client);
+    assert_eq!(server.join().unwrap(), 1);
+    let _ = std::fs::remove_file(&socket_path);
+
+    assert_eq!(vlan_confs.len(), 2);
+    let eth1_conf = &vlan_confs["eth1"];
+    assert_eq!(eth1_conf.mode, Some(LinuxBridgePortVlanMode::Trunk));
+    assert_eq!(eth1_conf.enable_native, Some(true));
+    assert_eq!(eth1_conf.tag, Some(10));
+    assert_eq!(
+        eth1_conf.trunk_tags,
+        Some(vec![
+            LinuxBridgePortTunkTag::Id(100),
+            LinuxBridgePortTunkTag::IdRange(LinuxBridgePortVlanRange {
+                min: 200,
+                max: 202,
+            }),
+        ])
+    );
+    let eth2_conf = &vlan_confs["eth2"];
+    assert_eq!(eth2_conf.mode, Some(LinuxBridgePortVlanMode::Access));
+    assert_eq!(eth2_conf.tag, Some(20));
+    assert_eq!(eth2_conf.trunk_tags, None);
+}
@@ -0,0 +1,47 @@
+use crate::{ErrorKind, Interface, Interfaces, NetworkState};
+
+#[test]
+fn test_pppoe_missing_username() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: ppp0
+type: pppoe
+pppoe:
+  parent: eth1
+  username: ""
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_pppoe_hide_password() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: ppp0
+  type: pppoe
+  pppoe:
+    parent: eth1
+    username: user
+    password: my-secret-password
+"#,
+    )
+    .unwrap();
+    ifaces.hide_secrets();
+    let iface = ifaces.to_vec()[0].clone();
+    assert_eq!(iface.parent(), Some("eth1"));
+    if let Interface::Pppoe(iface) = iface {
+        iface.validate().unwrap();
+        assert_eq!(
+            iface.pppoe.as_ref().unwrap().password.as_deref(),
+            Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+        );
+    } else {
+        panic!("Expecting PPPoE interface, got {:?}", iface);
+    }
+}
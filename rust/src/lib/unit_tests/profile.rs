@@ -0,0 +1,53 @@
+use crate::{BondMode, Interface, InterfaceType, NetworkState};
+
+#[test]
+fn test_bonded_uplink_profile() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+profiles:
+- bonded-uplink:
+    nics: [eno1, eno2]
+    vlan: 100
+interfaces:
+- name: bond0.100
+  type: vlan
+  mtu: 1400
+"#,
+    )
+    .unwrap();
+
+    let ifaces = &net_state.interfaces;
+    assert_eq!(ifaces.kernel_ifaces.len(), 4);
+    if let Some(Interface::Bond(bond_iface)) =
+        ifaces.get_iface("bond0", InterfaceType::Bond)
+    {
+        let bond_conf = bond_iface.bond.as_ref().unwrap();
+        assert_eq!(bond_conf.mode, Some(BondMode::LACP));
+        assert_eq!(
+            bond_conf.port.as_deref(),
+            Some(["eno1".to_string(), "eno2".to_string()].as_slice())
+        );
+    } else {
+        panic!("Should have bond0 bond interface");
+    }
+    assert_eq!(
+        ifaces.kernel_ifaces["eno1"].iface_type(),
+        InterfaceType::Ethernet
+    );
+    let vlan_iface = &ifaces.kernel_ifaces["bond0.100"];
+    assert_eq!(vlan_iface.iface_type(), InterfaceType::Vlan);
+    assert_eq!(vlan_iface.base_iface().mtu, Some(1400));
+    assert_eq!(vlan_iface.parent(), Some("bond0"));
+}
+
+#[test]
+fn test_bridged_uplink_profile_without_nics() {
+    let result = serde_yaml::from_str::<NetworkState>(
+        r#"---
+profiles:
+- bridged-uplink:
+    nics: []
+"#,
+    );
+    assert!(result.is_err());
+}
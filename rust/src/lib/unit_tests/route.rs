@@ -1,9 +1,12 @@
 use crate::{
     unit_tests::testlib::new_eth_iface, ErrorKind, InterfaceType, Interfaces,
-    NetworkState, RouteEntry, RouteState, Routes,
+    NetworkState, RouteEntry, RouteState, RouteType, Routes,
 };
 
 const TEST_NIC: &str = "eth1";
+const TEST_NIC2: &str = "eth2";
+const TEST_IPV4_DEFAULT_GW: &str = "0.0.0.0/0";
+const TEST_IPV4_GW_ADDR2: &str = "198.51.100.2";
 const TEST_IPV4_NET1: &str = "192.0.2.0/24";
 const TEST_IPV4_ADDR1: &str = "198.51.100.1";
 const TEST_IPV6_NET1: &str = "2001:db8:1::/64";
@@ -85,6 +88,45 @@ fn test_add_routes_to_new_interface() {
     );
 }
 
+#[test]
+fn test_add_routes_to_loopback() {
+    let cur_net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: lo
+  type: loopback
+  state: up
+"#,
+    )
+    .unwrap();
+    let des_net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+routes:
+  config:
+  - destination: 192.0.2.1/32
+    next-hop-interface: lo
+    table-id: 100
+route-rules:
+  config:
+  - ip-from: 198.51.100.0/24
+    route-table: 100
+"#,
+    )
+    .unwrap();
+
+    let (add_net_state, chg_net_state, _) =
+        des_net_state.gen_state_for_apply(&cur_net_state).unwrap();
+
+    assert!(add_net_state.interfaces.to_vec().is_empty());
+    let chg_ifaces = chg_net_state.interfaces.to_vec();
+    assert_eq!(chg_ifaces.len(), 1);
+    assert_eq!(chg_ifaces[0].name(), "lo");
+    assert_eq!(chg_ifaces[0].iface_type(), InterfaceType::Loopback);
+    let base_iface = chg_ifaces[0].base_iface();
+    assert_eq!(base_iface.routes.as_ref().unwrap().len(), 1);
+    assert_eq!(base_iface.rules.as_ref().unwrap().len(), 1);
+}
+
 #[test]
 fn test_wildcard_absent_routes() {
     let cur_iface = new_eth_iface(TEST_NIC);
@@ -199,6 +241,131 @@ fn test_verify_current_has_more_routes() {
     des_routes.verify(&cur_routes).unwrap();
 }
 
+#[test]
+fn test_verify_absent_route_replaced_by_desired() {
+    let cur_routes = gen_test_routes_conf();
+
+    let mut absent_route = RouteEntry::new();
+    absent_route.state = Some(RouteState::Absent);
+    absent_route.destination = Some(TEST_IPV4_NET1.to_string());
+    let mut des_routes = gen_test_routes_conf();
+    if let Some(config_routes) = des_routes.config.as_mut() {
+        config_routes.insert(0, absent_route);
+    }
+
+    des_routes.verify(&cur_routes).unwrap();
+}
+
+#[test]
+fn test_verify_ignore_desired_running_routes() {
+    let mut des_routes = gen_test_routes_conf();
+    des_routes.running = Some(vec![gen_route_entry(
+        TEST_IPV6_NET2,
+        TEST_NIC,
+        TEST_IPV6_ADDR2,
+    )]);
+
+    let cur_routes = gen_test_routes_conf();
+
+    des_routes.verify(&cur_routes).unwrap();
+}
+
+#[test]
+fn test_route_protocol_is_query_only() {
+    let route: RouteEntry = serde_yaml::from_str(
+        r#"
+destination: 192.0.2.0/24
+next-hop-interface: eth1
+protocol: static
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(route.protocol, None);
+}
+
+#[test]
+fn test_default_gateways_with_same_metric() {
+    let mut des_routes = Routes::new();
+    des_routes.config = Some(vec![
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC, TEST_IPV4_ADDR1),
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC2, TEST_IPV4_GW_ADDR2),
+    ]);
+
+    let result = des_routes.validate_default_gateways(&Routes::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_default_gateway_same_metric_with_current_in_other_table() {
+    let mut cur_routes = Routes::new();
+    let mut cur_route =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC2, TEST_IPV4_GW_ADDR2);
+    cur_route.table_id = Some(100);
+    cur_routes.config = Some(vec![cur_route]);
+    let mut des_routes = Routes::new();
+    des_routes.config = Some(vec![gen_route_entry(
+        TEST_IPV4_DEFAULT_GW,
+        TEST_NIC,
+        TEST_IPV4_ADDR1,
+    )]);
+
+    des_routes.validate_default_gateways(&cur_routes).unwrap();
+}
+
+#[test]
+fn test_auto_gateway_metric() {
+    let mut cur_routes = Routes::new();
+    cur_routes.config = Some(vec![gen_route_entry(
+        TEST_IPV4_DEFAULT_GW,
+        "eth0",
+        TEST_IPV4_GW_ADDR2,
+    )]);
+    let mut des_routes = Routes::new();
+    des_routes.auto_gateway_metric = Some(true);
+    let mut route1 =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC2, TEST_IPV4_GW_ADDR2);
+    route1.metric = None;
+    let mut route2 =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC, TEST_IPV4_ADDR1);
+    route2.metric = Some(RouteEntry::USE_DEFAULT_METRIC);
+    des_routes.config = Some(vec![route1, route2]);
+
+    des_routes.assign_gateway_metrics(&cur_routes);
+    des_routes.validate_default_gateways(&cur_routes).unwrap();
+
+    let routes = des_routes.config.as_ref().unwrap();
+    // eth0 is holding metric 100 in current
+    assert_eq!(routes[0].next_hop_iface, Some(TEST_NIC2.to_string()));
+    assert_eq!(routes[0].metric, Some(300));
+    assert_eq!(routes[1].next_hop_iface, Some(TEST_NIC.to_string()));
+    assert_eq!(routes[1].metric, Some(200));
+}
+
+#[test]
+fn test_auto_gateway_metric_reapply() {
+    let mut cur_routes = Routes::new();
+    cur_routes.config = Some(vec![gen_route_entry(
+        TEST_IPV4_DEFAULT_GW,
+        TEST_NIC,
+        TEST_IPV4_ADDR1,
+    )]);
+    let mut des_routes = Routes::new();
+    des_routes.auto_gateway_metric = Some(true);
+    let mut route =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC, TEST_IPV4_ADDR1);
+    route.metric = None;
+    des_routes.config = Some(vec![route]);
+
+    des_routes.assign_gateway_metrics(&cur_routes);
+
+    let routes = des_routes.config.as_ref().unwrap();
+    assert_eq!(routes[0].metric, Some(TEST_ROUTE_METRIC));
+}
+
 fn gen_test_routes_conf() -> Routes {
     let mut ret = Routes::new();
     ret.running = Some(gen_test_route_entries());
@@ -225,3 +392,250 @@ fn gen_route_entry(
     ret.metric = Some(TEST_ROUTE_METRIC);
     ret
 }
+
+#[test]
+fn test_default_gateway_on_never_default_iface() {
+    let des_net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+  state: up
+  ipv4:
+    enabled: true
+    dhcp: true
+    auto-gateway: false
+routes:
+  config:
+  - destination: 0.0.0.0/0
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.1
+"#,
+    )
+    .unwrap();
+
+    let result = des_net_state.gen_state_for_apply(&NetworkState::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_absent_routes_with_iface_glob() {
+    let mut cur_net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: ethernet
+- name: vlan10
+  type: vlan
+  vlan:
+    base-iface: eth1
+    id: 10
+- name: vlan11
+  type: vlan
+  vlan:
+    base-iface: eth1
+    id: 11
+- name: vlan2
+  type: vlan
+  vlan:
+    base-iface: eth1
+    id: 2
+"#,
+    )
+    .unwrap();
+    cur_net_state.routes.config = Some(vec![
+        gen_route_entry(TEST_IPV4_NET1, TEST_NIC, TEST_IPV4_ADDR1),
+        gen_route_entry(TEST_IPV4_NET1, "vlan10", TEST_IPV4_ADDR1),
+        gen_route_entry(TEST_IPV6_NET1, "vlan11", TEST_IPV6_ADDR1),
+        gen_route_entry(TEST_IPV6_NET1, "vlan2", TEST_IPV6_ADDR1),
+    ]);
+
+    let des_net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+routes:
+  config:
+  - next-hop-interface: "vlan1*"
+    state: absent
+"#,
+    )
+    .unwrap();
+
+    let (_, chg_net_state, _) =
+        des_net_state.gen_state_for_apply(&cur_net_state).unwrap();
+
+    let chg_ifaces = chg_net_state.interfaces.to_vec();
+
+    assert_eq!(chg_ifaces.len(), 2);
+    assert_eq!(chg_ifaces[0].name(), "vlan10");
+    assert_eq!(chg_ifaces[0].base_iface().routes, Some(Vec::new()));
+    assert_eq!(chg_ifaces[1].name(), "vlan11");
+    assert_eq!(chg_ifaces[1].base_iface().routes, Some(Vec::new()));
+
+    let mut des_routes = des_net_state.routes.clone();
+    des_routes.config.as_mut().unwrap()[0].next_hop_iface =
+        Some("vlan?0".to_string());
+    assert!(des_routes.verify(&cur_net_state.routes).is_err());
+    des_routes.config.as_mut().unwrap()[0].next_hop_iface =
+        Some("vlan?1*".to_string());
+    let mut cur_routes = cur_net_state.routes.clone();
+    cur_routes.config.as_mut().unwrap().remove(2);
+    des_routes.verify(&cur_routes).unwrap();
+}
+
+#[test]
+fn test_iface_glob_in_non_absent_route() {
+    let des_routes: Routes = serde_yaml::from_str(
+        r#"---
+config:
+- destination: 192.0.2.0/24
+  next-hop-interface: "eth*"
+"#,
+    )
+    .unwrap();
+
+    let result = des_routes.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_ecmp_default_gateways_with_weight() {
+    let mut des_routes = Routes::new();
+    let mut route1 =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC, TEST_IPV4_ADDR1);
+    route1.weight = Some(1);
+    let mut route2 =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC2, TEST_IPV4_GW_ADDR2);
+    route2.weight = Some(3);
+    des_routes.config = Some(vec![route1, route2]);
+    des_routes.validate().unwrap();
+    des_routes
+        .validate_default_gateways(&Routes::new())
+        .unwrap();
+
+    let changed_routes =
+        des_routes.gen_changed_ifaces_and_routes(&Routes::new());
+    assert_eq!(changed_routes[TEST_NIC].len(), 1);
+    assert_eq!(changed_routes[TEST_NIC][0].weight, Some(1));
+    assert_eq!(changed_routes[TEST_NIC2].len(), 1);
+    assert_eq!(changed_routes[TEST_NIC2][0].weight, Some(3));
+}
+
+#[test]
+fn test_route_weight_overrides_current() {
+    let mut cur_routes = Routes::new();
+    let mut cur_route =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC, TEST_IPV4_ADDR1);
+    cur_route.weight = Some(1);
+    cur_routes.config = Some(vec![cur_route]);
+    let mut des_routes = Routes::new();
+    let mut des_route =
+        gen_route_entry(TEST_IPV4_DEFAULT_GW, TEST_NIC, TEST_IPV4_ADDR1);
+    des_route.weight = Some(10);
+    des_routes.config = Some(vec![des_route]);
+
+    let changed_routes = des_routes.gen_changed_ifaces_and_routes(&cur_routes);
+    assert_eq!(changed_routes[TEST_NIC].len(), 1);
+    assert_eq!(changed_routes[TEST_NIC][0].weight, Some(10));
+    assert!(des_routes.verify(&cur_routes).is_err());
+}
+
+#[test]
+fn test_invalid_route_weight() {
+    let mut des_routes = Routes::new();
+    let mut route = gen_route_entry(TEST_IPV4_NET1, TEST_NIC, TEST_IPV4_ADDR1);
+    route.weight = Some(0);
+    des_routes.config = Some(vec![route]);
+    let result = des_routes.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_host_wide_blackhole_route() {
+    let des_routes: Routes = serde_yaml::from_str(
+        r#"---
+config:
+  - destination: 198.51.100.0/24
+    route-type: blackhole
+  - destination: 2001:db8:2::/64
+    route-type: unreachable
+    metric: 200
+"#,
+    )
+    .unwrap();
+    des_routes.validate().unwrap();
+
+    let result = des_routes.validate_host_wide_routes();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+
+    // Host-wide route should not be assigned to any interface
+    let changed_routes =
+        des_routes.gen_changed_ifaces_and_routes(&Routes::new());
+    assert!(changed_routes.is_empty());
+}
+
+#[test]
+fn test_typed_route_with_next_hop_address() {
+    let mut des_routes = Routes::new();
+    let mut route = gen_route_entry(TEST_IPV4_NET1, TEST_NIC, TEST_IPV4_ADDR1);
+    route.route_type = Some(RouteType::Prohibit);
+    des_routes.config = Some(vec![route]);
+    let result = des_routes.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_gen_typed_route_changes() {
+    let cur_routes: Routes = serde_yaml::from_str(
+        r#"---
+config:
+  - destination: 198.51.100.0/24
+    route-type: blackhole
+    table-id: 254
+  - destination: 192.0.2.0/24
+    route-type: prohibit
+    table-id: 254
+  - destination: 203.0.113.0/24
+    next-hop-interface: eth1
+    next-hop-address: 192.0.2.1
+    table-id: 254
+"#,
+    )
+    .unwrap();
+    let des_routes: Routes = serde_yaml::from_str(
+        r#"---
+config:
+  - destination: 198.51.100.0/24
+    state: absent
+  - destination: 192.0.2.0/24
+    state: absent
+  - destination: 192.0.2.0/24
+    route-type: unreachable
+  - destination: 203.0.113.0/24
+    state: absent
+"#,
+    )
+    .unwrap();
+
+    let (add_routes, del_routes) =
+        des_routes.gen_typed_route_changes(&cur_routes);
+    assert_eq!(add_routes.len(), 1);
+    assert_eq!(add_routes[0].route_type, Some(RouteType::Unreachable));
+    assert_eq!(del_routes.len(), 2);
+    assert_eq!(del_routes[0].route_type, Some(RouteType::Blackhole));
+    assert_eq!(del_routes[1].route_type, Some(RouteType::Prohibit));
+}
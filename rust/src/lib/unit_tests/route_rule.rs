@@ -1,6 +1,6 @@
 use crate::{
-    unit_tests::testlib::new_eth_iface, Interfaces, NetworkState, RouteEntry,
-    RouteRuleEntry, RouteRules, Routes,
+    unit_tests::testlib::new_eth_iface, ErrorKind, Interfaces, NetworkState,
+    RouteEntry, RouteRuleEntry, RouteRuleFamily, RouteRules, Routes,
 };
 
 const TEST_NIC: &str = "eth1";
@@ -80,6 +80,107 @@ fn test_add_rules_to_new_interface() {
     assert_eq!(config_rules[1].table_id.unwrap(), TEST_TABLE_ID2);
 }
 
+#[test]
+fn test_route_rule_mixed_family() {
+    let rules: RouteRules = serde_yaml::from_str(&format!(
+        r#"---
+config:
+  - ip-from: {}
+    ip-to: {}
+    route-table: {}
+"#,
+        TEST_RULE_IPV4_FROM, TEST_RULE_IPV6_TO, TEST_TABLE_ID1
+    ))
+    .unwrap();
+    let result = rules.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+
+    let rules: RouteRules = serde_yaml::from_str(&format!(
+        r#"---
+config:
+  - ip-from: {}
+    family: ipv6
+    route-table: {}
+"#,
+        TEST_RULE_IPV4_FROM, TEST_TABLE_ID1
+    ))
+    .unwrap();
+    assert!(rules.validate().is_err());
+}
+
+#[test]
+fn test_route_rule_without_ip_expand_to_both_family() {
+    let cur_net_state = NetworkState::new();
+
+    let mut des_net_state = NetworkState::new();
+    des_net_state.interfaces.push(new_eth_iface(TEST_NIC));
+    des_net_state.routes = gen_test_routes_conf();
+    des_net_state.rules = serde_yaml::from_str(&format!(
+        r#"---
+config:
+  - priority: {}
+    route-table: {}
+  - priority: {}
+    family: ipv4
+    route-table: {}
+"#,
+        TEST_RULE_PRIORITY1,
+        TEST_TABLE_ID1,
+        TEST_RULE_PRIORITY2,
+        TEST_TABLE_ID2
+    ))
+    .unwrap();
+
+    des_net_state.rules.validate().unwrap();
+    let (add_net_state, _, _) =
+        des_net_state.gen_state_for_apply(&cur_net_state).unwrap();
+
+    let add_ifaces = add_net_state.interfaces.to_vec();
+    assert_eq!(add_ifaces.len(), 1);
+    let config_rules = add_ifaces[0].base_iface().rules.as_ref().unwrap();
+    assert_eq!(config_rules.len(), 3);
+    let rule_families: Vec<(Option<RouteRuleFamily>, Option<u32>)> =
+        config_rules
+            .iter()
+            .map(|r| (r.family, r.table_id))
+            .collect();
+    assert!(rule_families
+        .contains(&(Some(RouteRuleFamily::Ipv4), Some(TEST_TABLE_ID1))));
+    assert!(rule_families
+        .contains(&(Some(RouteRuleFamily::Ipv6), Some(TEST_TABLE_ID1))));
+    assert!(rule_families
+        .contains(&(Some(RouteRuleFamily::Ipv4), Some(TEST_TABLE_ID2))));
+}
+
+#[test]
+fn test_route_rule_verify_family() {
+    let des_rules: RouteRules = serde_yaml::from_str(&format!(
+        r#"---
+config:
+  - priority: {}
+    route-table: {}
+"#,
+        TEST_RULE_PRIORITY1, TEST_TABLE_ID1
+    ))
+    .unwrap();
+    let mut cur_rule = RouteRuleEntry::new();
+    cur_rule.family = Some(RouteRuleFamily::Ipv4);
+    cur_rule.priority = Some(TEST_RULE_PRIORITY1);
+    cur_rule.table_id = Some(TEST_TABLE_ID1);
+    let mut cur_rules = RouteRules::new();
+    cur_rules.config = Some(vec![cur_rule.clone()]);
+
+    // IPv6 rule is missing
+    assert!(des_rules.verify(&cur_rules).is_err());
+
+    cur_rule.family = Some(RouteRuleFamily::Ipv6);
+    cur_rules.config.as_mut().unwrap().push(cur_rule);
+    des_rules.verify(&cur_rules).unwrap();
+}
+
 fn gen_test_routes_conf() -> Routes {
     let mut ret = Routes::new();
     ret.running = Some(gen_test_route_entries());
@@ -121,6 +222,7 @@ fn gen_route_entry(
 
 fn gen_test_rules_conf() -> RouteRules {
     RouteRules {
+        running: None,
         config: Some(gen_test_rule_entries()),
     }
 }
@@ -150,9 +252,12 @@ fn gen_rule_entry(
 ) -> RouteRuleEntry {
     RouteRuleEntry {
         state: None,
+        family: None,
         ip_from: Some(ip_from.to_string()),
         ip_to: Some(ip_to.to_string()),
         table_id: Some(table_id),
         priority: Some(priority),
+        vrf_name: None,
+        protocol: None,
     }
 }
@@ -0,0 +1,193 @@
+use crate::{ErrorKind, InterfaceType, Interfaces, NetworkState};
+
+#[test]
+fn test_veth_both_ends_in_single_state() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: veth1
+  type: veth
+  state: up
+  veth:
+    peer: veth1.ep
+- name: veth1.ep
+  type: veth
+  state: up
+  veth:
+    peer: veth1
+  mtu: 1400
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    let (add_ifaces, chg_ifaces, _) =
+        ifaces.gen_state_for_apply(&cur_ifaces).unwrap();
+
+    let add_ifaces = add_ifaces.to_vec();
+    let chg_ifaces = chg_ifaces.to_vec();
+    assert_eq!(add_ifaces.len(), 1);
+    assert_eq!(add_ifaces[0].name(), "veth1");
+    assert_eq!(chg_ifaces.len(), 1);
+    assert_eq!(chg_ifaces[0].name(), "veth1.ep");
+    assert_eq!(chg_ifaces[0].iface_type(), InterfaceType::Veth);
+    assert_eq!(chg_ifaces[0].base_iface().mtu, Some(1400));
+}
+
+#[test]
+fn test_veth_both_ends_with_conflict_peer() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: veth1
+  type: veth
+  state: up
+  veth:
+    peer: veth1.ep
+- name: veth1.ep
+  type: veth
+  state: up
+  veth:
+    peer: veth2
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    let result = ifaces.gen_state_for_apply(&cur_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+const CUR_VETH_PAIR: &str = r#"---
+- name: veth1
+  type: veth
+  state: up
+  veth:
+    peer: veth1.ep
+- name: veth1.ep
+  type: veth
+  state: up
+  veth:
+    peer: veth1
+"#;
+
+#[test]
+fn test_veth_absent_peer_also_removed() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: veth1.ep
+  type: veth
+  state: absent
+"#,
+    )
+    .unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(CUR_VETH_PAIR).unwrap();
+
+    let (_, _, del_ifaces) = ifaces.gen_state_for_apply(&cur_ifaces).unwrap();
+
+    let mut del_names: Vec<&str> =
+        del_ifaces.to_vec().iter().map(|i| i.name()).collect();
+    del_names.sort_unstable();
+    assert_eq!(del_names, vec!["veth1", "veth1.ep"]);
+    assert!(del_ifaces.to_vec().iter().all(|i| i.is_absent()));
+}
+
+#[test]
+fn test_veth_absent_with_peer_up() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: veth1
+  type: veth
+  state: absent
+- name: veth1.ep
+  type: veth
+  state: up
+"#,
+    )
+    .unwrap();
+    let cur_ifaces: Interfaces = serde_yaml::from_str(CUR_VETH_PAIR).unwrap();
+
+    let result = ifaces.gen_state_for_apply(&cur_ifaces);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_veth_absent_match_current_ethernet_type() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: veth1
+  type: veth
+  state: absent
+"#,
+    )
+    .unwrap();
+    // NetworkManager reports veth as ethernet
+    let cur_ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: veth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+
+    let (_, _, del_ifaces) = ifaces.gen_state_for_apply(&cur_ifaces).unwrap();
+
+    let del_ifaces = del_ifaces.to_vec();
+    assert_eq!(del_ifaces.len(), 1);
+    assert_eq!(del_ifaces[0].name(), "veth1");
+    assert!(del_ifaces[0].is_absent());
+}
+
+#[test]
+fn test_veth_strict_type() {
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: veth1
+  type: ethernet
+  state: up
+  veth:
+    peer: veth1.ep
+- name: eth1
+  type: ethernet
+  state: up
+"#,
+    )
+    .unwrap();
+    let mut desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: veth1
+  type: ethernet
+  state: up
+  mtu: 1400
+"#,
+    )
+    .unwrap();
+
+    assert!(desired.gen_plan_with_current(&cur_state).is_ok());
+
+    desired.set_strict_veth_type(true);
+    let result = desired.gen_plan_with_current(&cur_state);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+
+    let mut desired: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: eth1
+  type: veth
+  state: up
+"#,
+    )
+    .unwrap();
+    desired.set_strict_veth_type(true);
+    assert!(desired.gen_plan_with_current(&cur_state).is_err());
+}
@@ -0,0 +1,158 @@
+use crate::{
+    ErrorKind, Interface, Interfaces, VlanInterface, VlanProtocol,
+    VlanQosMapping,
+};
+
+#[test]
+fn test_vlan_qinq_with_qos_map() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1.100
+type: vlan
+vlan:
+  base-iface: eth1
+  id: 100
+  protocol: 802.1ad
+  ingress-qos-map:
+    - from: 7
+      to: 3
+  egress-qos-map:
+    - from: 2
+      to: 5
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    if let Interface::Vlan(vlan_iface) = iface {
+        let conf = vlan_iface.vlan.unwrap();
+        assert_eq!(conf.protocol, Some(VlanProtocol::Ieee8021Ad));
+        assert_eq!(
+            conf.ingress_qos_map,
+            Some(vec![VlanQosMapping { from: 7, to: 3 }])
+        );
+        assert_eq!(
+            conf.egress_qos_map,
+            Some(vec![VlanQosMapping { from: 2, to: 5 }])
+        );
+    } else {
+        panic!("Expecting VLAN interface");
+    }
+}
+
+#[test]
+fn test_vlan_invalid_egress_qos_map() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1.100
+type: vlan
+vlan:
+  base-iface: eth1
+  id: 100
+  egress-qos-map:
+    - from: 2
+      to: 8
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_vlan_verify_flags() {
+    let des_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1.100
+type: vlan
+vlan:
+  base-iface: eth1
+  id: 100
+  loose-binding: true
+"#,
+    )
+    .unwrap();
+    let cur_iface: Interface = serde_yaml::from_str(
+        r#"---
+name: eth1.100
+type: vlan
+vlan:
+  base-iface: eth1
+  id: 100
+  reorder-headers: true
+  gvrp: false
+  mvrp: false
+  loose-binding: false
+"#,
+    )
+    .unwrap();
+    let result = des_iface.verify(&cur_iface);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::VerificationError);
+        assert!(e.msg().contains("loose-binding"));
+    }
+}
+
+#[test]
+fn test_vlan_kernel_unsupported_prop() {
+    let cur_iface: VlanInterface = serde_yaml::from_str(
+        r#"---
+name: eth1.100
+type: vlan
+vlan:
+  base-iface: eth1
+  id: 100
+  protocol: 802.1q
+  reorder-headers: true
+  gvrp: false
+  mvrp: false
+  loose-binding: false
+"#,
+    )
+    .unwrap();
+    assert_eq!(cur_iface.get_kernel_unsupported_prop(None), None);
+    assert_eq!(
+        cur_iface.get_kernel_unsupported_prop(Some(&cur_iface)),
+        None
+    );
+
+    let mut des_iface = cur_iface.clone();
+    if let Some(conf) = des_iface.vlan.as_mut() {
+        conf.protocol = Some(VlanProtocol::Ieee8021Ad);
+    }
+    assert!(des_iface
+        .get_kernel_unsupported_prop(Some(&cur_iface))
+        .is_some());
+
+    let mut des_iface = cur_iface.clone();
+    if let Some(conf) = des_iface.vlan.as_mut() {
+        conf.gvrp = Some(true);
+    }
+    assert!(des_iface.get_kernel_unsupported_prop(None).is_some());
+}
+
+#[test]
+fn test_vlan_qinq_not_supported_by_kernel_mode() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: eth1.100
+  type: vlan
+  state: up
+  vlan:
+    base-iface: eth1
+    id: 100
+    protocol: 802.1ad
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    let result = ifaces.validate_backend_support(&cur_ifaces, true);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{ErrorKind, Interface, InterfaceType, NetworkState, VrfConfig};
+
+#[test]
+fn test_vrf_port_ip_kept_on_verify() {
+    let ifaces: Vec<Interface> = serde_yaml::from_str(
+        r#"---
+- name: vrf0
+  type: vrf
+  vrf:
+    port:
+      - eth2
+      - eth1
+    route-table-id: 100
+"#,
+    )
+    .unwrap();
+    assert_eq!(ifaces[0].iface_type(), InterfaceType::Vrf);
+    assert_eq!(ifaces[0].ports(), Some(vec!["eth2", "eth1"]));
+
+    let mut port = crate::unit_tests::testlib::new_eth_iface("eth1");
+    port.base_iface_mut().controller = Some("vrf0".to_string());
+    port.base_iface_mut().controller_type = Some(InterfaceType::Vrf);
+    assert!(port.base_iface().can_have_ip());
+}
+
+#[test]
+fn test_resolve_vrf_table_names() {
+    let mut des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf0
+    type: vrf
+    vrf:
+      route-table-id: 100
+routes:
+  config:
+    - destination: 198.51.100.0/24
+      next-hop-interface: eth1
+      next-hop-address: 192.0.2.1
+      table: vrf0
+route-rules:
+  config:
+    - ip-from: 192.0.2.0/24
+      table: vrf1
+"#,
+    )
+    .unwrap();
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf1
+    type: vrf
+    vrf:
+      route-table-id: 101
+"#,
+    )
+    .unwrap();
+
+    des_state.resolve_vrf_table_names(&cur_state).unwrap();
+
+    assert_eq!(
+        des_state.routes.config.as_ref().unwrap()[0].table_id,
+        Some(100)
+    );
+    assert_eq!(
+        des_state.rules.config.as_ref().unwrap()[0].table_id,
+        Some(101)
+    );
+}
+
+#[test]
+fn test_resolve_vrf_table_names_not_found() {
+    let mut des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+routes:
+  config:
+    - destination: 198.51.100.0/24
+      next-hop-interface: eth1
+      table: vrf0
+"#,
+    )
+    .unwrap();
+
+    let result = des_state.resolve_vrf_table_names(&NetworkState::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_resolve_vrf_table_names_conflict() {
+    let mut des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf0
+    type: vrf
+    vrf:
+      route-table-id: 100
+route-rules:
+  config:
+    - ip-from: 192.0.2.0/24
+      route-table: 200
+      table: vrf0
+"#,
+    )
+    .unwrap();
+
+    let result = des_state.resolve_vrf_table_names(&NetworkState::new());
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_vrf_table_id_conflict() {
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf1
+    type: vrf
+    vrf:
+      route-table-id: 100
+"#,
+    )
+    .unwrap();
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf0
+    type: vrf
+    vrf:
+      route-table-id: 100
+"#,
+    )
+    .unwrap();
+
+    let result = des_state.gen_plan_with_current(&cur_state);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_vrf_table_id_no_conflict_when_removing() {
+    let des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf0
+    type: vrf
+    state: absent
+  - name: vrf1
+    type: vrf
+    vrf:
+      route-table-id: 100
+"#,
+    )
+    .unwrap();
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf0
+    type: vrf
+    vrf:
+      route-table-id: 100
+"#,
+    )
+    .unwrap();
+
+    des_state.gen_plan_with_current(&cur_state).unwrap();
+}
+
+#[test]
+fn test_vrf_table_id_auto() {
+    let mut des_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf0
+    type: vrf
+    vrf:
+      route-table-id: auto
+  - name: vrf1
+    type: vrf
+    vrf:
+      route-table-id: auto
+  - name: vrf2
+    type: vrf
+    vrf:
+      route-table-id: auto
+"#,
+    )
+    .unwrap();
+    let cur_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+  - name: vrf2
+    type: vrf
+    vrf:
+      route-table-id: 100
+  - name: vrf3
+    type: vrf
+    vrf:
+      route-table-id: 1000
+routes:
+  running:
+    - destination: 198.51.100.0/24
+      next-hop-interface: eth1
+      table-id: 1001
+"#,
+    )
+    .unwrap();
+
+    des_state.resolve_vrf_auto_table_ids(&cur_state);
+
+    assert_eq!(des_state.interfaces.get_vrf_table_id("vrf0"), Some(1002));
+    assert_eq!(des_state.interfaces.get_vrf_table_id("vrf1"), Some(1003));
+    assert_eq!(des_state.interfaces.get_vrf_table_id("vrf2"), Some(100));
+}
+
+#[test]
+fn test_vrf_table_id_auto_serialize() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: vrf0
+type: vrf
+vrf:
+  route-table-id: auto
+"#,
+    )
+    .unwrap();
+    if let Interface::Vrf(vrf_iface) = &iface {
+        assert_eq!(vrf_iface.table_id(), Some(VrfConfig::TABLE_ID_AUTO));
+    } else {
+        panic!("Expecting VRF interface, got {:?}", iface);
+    }
+    assert!(serde_yaml::to_string(&iface)
+        .unwrap()
+        .contains("route-table-id: auto"));
+}
@@ -0,0 +1,71 @@
+use crate::{ErrorKind, Interface, Interfaces, NetworkState};
+
+#[test]
+fn test_wifi_invalid_psk() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: wlan0
+type: wifi
+wifi:
+  ssid: test-ssid
+  band: 5GHz
+  security:
+    key-management: wpa-psk
+    psk: short
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_wifi_hide_secrets() {
+    let mut ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: wlan0
+  type: wifi
+  wifi:
+    ssid: test-ssid
+    security:
+      key-management: wpa-psk
+      psk: my-secret-psk
+- name: wlan1
+  type: wifi
+  wifi:
+    ssid: test-ssid
+    security:
+      key-management: wpa-eap
+      eap:
+        method: peap
+        identity: user
+        password: my-secret-password
+        phase2-auth: mschapv2
+"#,
+    )
+    .unwrap();
+    ifaces.hide_secrets();
+    let ifaces = ifaces.to_vec();
+    for iface in ifaces {
+        if let Interface::Wifi(iface) = iface {
+            iface.validate().unwrap();
+            let sec = iface.wifi.as_ref().unwrap().security.as_ref().unwrap();
+            if let Some(eap) = sec.eap.as_ref() {
+                assert_eq!(
+                    eap.password.as_deref(),
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+                );
+            } else {
+                assert_eq!(
+                    sec.psk.as_deref(),
+                    Some(NetworkState::PASSWORD_HID_BY_NMSTATE)
+                );
+            }
+        } else {
+            panic!("Expecting WiFi interface, got {:?}", iface);
+        }
+    }
+}
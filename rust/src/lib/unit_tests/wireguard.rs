@@ -0,0 +1,92 @@
+use crate::{ErrorKind, Interface, InterfaceType, Interfaces};
+
+#[test]
+fn test_wireguard_verify_ignore_secrets() {
+    let desired: Interface = serde_yaml::from_str(
+        r#"---
+name: wg0
+type: wireguard
+wireguard:
+  private-key: yAnz5TF+lXXJte14tji3zlMNq+hd2rYUIgJBgB3fBmk=
+  listen-port: 51820
+  peers:
+    - public-key: xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+      endpoint: 192.0.2.1:51820
+      allowed-ips:
+        - 198.51.100.0/24
+        - 192.0.2.0/24
+      persistent-keepalive: 25
+      preshared-key: FpCyhws9cxwWoV4xELtfJvjJN+zQVRPISllRWgeopVE=
+"#,
+    )
+    .unwrap();
+    let current: Interface = serde_yaml::from_str(
+        r#"---
+name: wg0
+type: wireguard
+state: up
+wireguard:
+  listen-port: 51820
+  peers:
+    - public-key: xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+      endpoint: 192.0.2.1:51820
+      allowed-ips:
+        - 192.0.2.0/24
+        - 198.51.100.0/24
+      persistent-keepalive: 25
+"#,
+    )
+    .unwrap();
+    assert_eq!(desired.iface_type(), InterfaceType::WireGuard);
+    desired.verify(&current).unwrap();
+}
+
+#[test]
+fn test_wireguard_verify_peer_mismatch() {
+    let desired: Interface = serde_yaml::from_str(
+        r#"---
+name: wg0
+type: wireguard
+wireguard:
+  peers:
+    - public-key: xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+      persistent-keepalive: 25
+"#,
+    )
+    .unwrap();
+    let current: Interface = serde_yaml::from_str(
+        r#"---
+name: wg0
+type: wireguard
+state: up
+wireguard:
+  peers:
+    - public-key: xTIBA5rboUvnH4htodjb6e697QjLERt1NAB4mZqp8Dg=
+      persistent-keepalive: 10
+"#,
+    )
+    .unwrap();
+    assert!(desired.verify(&current).is_err());
+}
+
+#[test]
+fn test_wireguard_not_supported_by_kernel_mode() {
+    let ifaces: Interfaces = serde_yaml::from_str(
+        r#"---
+- name: wg0
+  type: wireguard
+  state: up
+  wireguard:
+    listen-port: 51820
+"#,
+    )
+    .unwrap();
+    let cur_ifaces = Interfaces::new();
+
+    ifaces.validate_backend_support(&cur_ifaces, false).unwrap();
+    let result = ifaces.validate_backend_support(&cur_ifaces, true);
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{ErrorKind, Interface, NetworkState};
+
+#[test]
+fn test_xfrm_zero_if_id() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: xfrm0
+type: xfrm
+xfrm:
+  if-id: 0
+"#,
+    )
+    .unwrap();
+    let result = iface.validate();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::InvalidArgument);
+    }
+}
+
+#[test]
+fn test_xfrm_parent() {
+    let iface: Interface = serde_yaml::from_str(
+        r#"---
+name: xfrm0
+type: xfrm
+xfrm:
+  if-id: 10
+  base-iface: eth1
+"#,
+    )
+    .unwrap();
+    iface.validate().unwrap();
+    assert_eq!(iface.parent(), Some("eth1"));
+}
+
+#[cfg(feature = "nm-backend")]
+#[test]
+fn test_xfrm_not_supported_by_nm() {
+    let net_state: NetworkState = serde_yaml::from_str(
+        r#"---
+interfaces:
+- name: xfrm0
+  type: xfrm
+  state: up
+  xfrm:
+    if-id: 10
+"#,
+    )
+    .unwrap();
+    let result = net_state.gen_conf();
+    assert!(result.is_err());
+    if let Err(e) = result {
+        assert_eq!(e.kind(), ErrorKind::NotImplementedError);
+    }
+}
@@ -49,9 +49,9 @@ impl NmSettingBond {
                 .filter(|(k, _)| !self.options.contains_key(*k))
                 .map(|(k, v)| (k.to_string(), v.to_string())),
         );
-        if let Some(arp_ip_target) = self.options.get("arp_ip_target") {
-            if arp_ip_target.is_empty() {
-                merged_opts.remove("arp_ip_target");
+        for opt_name in ["arp_ip_target", "ns_ip6_target"] {
+            if self.options.get(opt_name).map(|t| t.is_empty()) == Some(true) {
+                merged_opts.remove(opt_name);
             }
         }
 
@@ -73,3 +73,44 @@ impl NmSettingBond {
         self._other.get(&String::from("mode"))
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingBondPort {
+    pub queue_id: Option<u32>,
+    pub prio: Option<i32>,
+    _other: DbusDictionary,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingBondPort {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            queue_id: _from_map!(v, "queue-id", u32::try_from)?,
+            prio: _from_map!(v, "prio", i32::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingBondPort {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = self.queue_id {
+            ret.insert("queue-id", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.prio {
+            ret.insert("prio", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -23,21 +23,46 @@ use zbus::export::zvariant::Signature;
 use zvariant::Type;
 
 use crate::{
-    connection::bond::NmSettingBond,
+    connection::bond::{NmSettingBond, NmSettingBondPort},
     connection::bridge::{NmSettingBridge, NmSettingBridgePort},
+    connection::ethtool::NmSettingEthtool,
+    connection::hsr::NmSettingHsr,
+    connection::infiniband::NmSettingInfiniBand,
     connection::ip::NmSettingIp,
+    connection::ip_tunnel::NmSettingIpTunnel,
+    connection::ipvlan::NmSettingIpVlan,
+    connection::loopback::NmSettingLoopback,
     connection::mac_vlan::NmSettingMacVlan,
+    connection::macsec::NmSettingMacSec,
     connection::ovs::{
-        NmSettingOvsBridge, NmSettingOvsIface, NmSettingOvsPort,
+        NmSettingOvsBridge, NmSettingOvsExtIds, NmSettingOvsIface,
+        NmSettingOvsOtherConfig, NmSettingOvsPort,
     },
+    connection::pppoe::NmSettingPppoe,
     connection::sriov::NmSettingSriov,
+    connection::team::{NmSettingTeam, NmSettingTeamPort},
+    connection::user::NmSettingUser,
     connection::vlan::NmSettingVlan,
+    connection::vpn::NmSettingVpn,
+    connection::vrf::NmSettingVrf,
+    connection::wifi::{
+        NmSetting8021X, NmSettingWireless, NmSettingWirelessSecurity,
+    },
     connection::wired::NmSettingWired,
+    connection::wireguard::NmSettingWireGuard,
     dbus::{NM_DBUS_INTERFACE_ROOT, NM_DBUS_INTERFACE_SETTING},
     keyfile::zvariant_value_to_keyfile,
-    NmError,
+    ErrorKind, NmError,
 };
 
+const NM_SETTINGS_CONNECTION_FLAG_UNSAVED: u32 = 0x01;
+const NM_SETTINGS_CONNECTION_FLAG_NM_GENERATED: u32 = 0x02;
+const NM_SETTINGS_CONNECTION_FLAG_VOLATILE: u32 = 0x04;
+const NM_SETTINGS_CONNECTION_FLAG_EXTERNAL: u32 = 0x08;
+
+// In-memory profiles are stored in this folder
+const NM_RUN_PROFILE_DIR: &str = "/run/NetworkManager/";
+
 const NM_AUTOCONENCT_PORT_DEFAULT: i32 = -1;
 const NM_AUTOCONENCT_PORT_YES: i32 = 1;
 const NM_AUTOCONENCT_PORT_NO: i32 = 0;
@@ -55,17 +80,37 @@ pub(crate) type NmConnectionDbusValue<'a> =
 pub struct NmConnection {
     pub connection: Option<NmSettingConnection>,
     pub bond: Option<NmSettingBond>,
+    pub bond_port: Option<NmSettingBondPort>,
     pub bridge: Option<NmSettingBridge>,
     pub bridge_port: Option<NmSettingBridgePort>,
     pub ipv4: Option<NmSettingIp>,
     pub ipv6: Option<NmSettingIp>,
+    pub ip_tunnel: Option<NmSettingIpTunnel>,
     pub ovs_bridge: Option<NmSettingOvsBridge>,
     pub ovs_port: Option<NmSettingOvsPort>,
     pub ovs_iface: Option<NmSettingOvsIface>,
+    pub ovs_other_config: Option<NmSettingOvsOtherConfig>,
+    pub ovs_ext_ids: Option<NmSettingOvsExtIds>,
     pub wired: Option<NmSettingWired>,
     pub vlan: Option<NmSettingVlan>,
     pub mac_vlan: Option<NmSettingMacVlan>,
     pub sriov: Option<NmSettingSriov>,
+    pub vrf: Option<NmSettingVrf>,
+    pub wireguard: Option<NmSettingWireGuard>,
+    pub team: Option<NmSettingTeam>,
+    pub team_port: Option<NmSettingTeamPort>,
+    pub wireless: Option<NmSettingWireless>,
+    pub wireless_security: Option<NmSettingWirelessSecurity>,
+    pub ieee8021x: Option<NmSetting8021X>,
+    pub user: Option<NmSettingUser>,
+    pub pppoe: Option<NmSettingPppoe>,
+    pub hsr: Option<NmSettingHsr>,
+    pub ipvlan: Option<NmSettingIpVlan>,
+    pub infiniband: Option<NmSettingInfiniBand>,
+    pub macsec: Option<NmSettingMacSec>,
+    pub loopback: Option<NmSettingLoopback>,
+    pub ethtool: Option<NmSettingEthtool>,
+    pub vpn: Option<NmSettingVpn>,
     #[serde(skip)]
     pub(crate) obj_path: String,
     _other: HashMap<String, HashMap<String, zvariant::OwnedValue>>,
@@ -92,7 +137,9 @@ impl TryFrom<NmConnectionDbusOwnedValue> for NmConnection {
             )?,
             ipv4: _from_map!(v, "ipv4", NmSettingIp::try_from)?,
             ipv6: _from_map!(v, "ipv6", NmSettingIp::try_from)?,
+            ip_tunnel: _from_map!(v, "ip-tunnel", NmSettingIpTunnel::try_from)?,
             bond: _from_map!(v, "bond", NmSettingBond::try_from)?,
+            bond_port: _from_map!(v, "bond-port", NmSettingBondPort::try_from)?,
             bridge: _from_map!(v, "bridge", NmSettingBridge::try_from)?,
             bridge_port: _from_map!(
                 v,
@@ -110,10 +157,52 @@ impl TryFrom<NmConnectionDbusOwnedValue> for NmConnection {
                 "ovs-interface",
                 NmSettingOvsIface::try_from
             )?,
+            ovs_other_config: _from_map!(
+                v,
+                "ovs-other-config",
+                NmSettingOvsOtherConfig::try_from
+            )?,
+            ovs_ext_ids: _from_map!(
+                v,
+                "ovs-external-ids",
+                NmSettingOvsExtIds::try_from
+            )?,
             wired: _from_map!(v, "802-3-ethernet", NmSettingWired::try_from)?,
             vlan: _from_map!(v, "vlan", NmSettingVlan::try_from)?,
             sriov: _from_map!(v, "sriov", NmSettingSriov::try_from)?,
             mac_vlan: _from_map!(v, "macvlan", NmSettingMacVlan::try_from)?,
+            vrf: _from_map!(v, "vrf", NmSettingVrf::try_from)?,
+            wireguard: _from_map!(
+                v,
+                "wireguard",
+                NmSettingWireGuard::try_from
+            )?,
+            team: _from_map!(v, "team", NmSettingTeam::try_from)?,
+            team_port: _from_map!(v, "team-port", NmSettingTeamPort::try_from)?,
+            wireless: _from_map!(
+                v,
+                "802-11-wireless",
+                NmSettingWireless::try_from
+            )?,
+            wireless_security: _from_map!(
+                v,
+                "802-11-wireless-security",
+                NmSettingWirelessSecurity::try_from
+            )?,
+            ieee8021x: _from_map!(v, "802-1x", NmSetting8021X::try_from)?,
+            user: _from_map!(v, "user", NmSettingUser::try_from)?,
+            pppoe: _from_map!(v, "pppoe", NmSettingPppoe::try_from)?,
+            hsr: _from_map!(v, "hsr", NmSettingHsr::try_from)?,
+            ipvlan: _from_map!(v, "ipvlan", NmSettingIpVlan::try_from)?,
+            infiniband: _from_map!(
+                v,
+                "infiniband",
+                NmSettingInfiniBand::try_from
+            )?,
+            macsec: _from_map!(v, "macsec", NmSettingMacSec::try_from)?,
+            loopback: _from_map!(v, "loopback", NmSettingLoopback::try_from)?,
+            ethtool: _from_map!(v, "ethtool", NmSettingEthtool::try_from)?,
+            vpn: _from_map!(v, "vpn", NmSettingVpn::try_from)?,
             _other: v,
             ..Default::default()
         })
@@ -125,6 +214,10 @@ impl NmConnection {
         Default::default()
     }
 
+    pub fn id(&self) -> Option<&str> {
+        _connection_inner_string_member!(self, id)
+    }
+
     pub fn iface_name(&self) -> Option<&str> {
         _connection_inner_string_member!(self, iface_name)
     }
@@ -149,6 +242,40 @@ impl NmConnection {
         {
             nm_conn_dbus_value.insert("ethernet", wire_setting);
         }
+        // Keyfile store user data as keys of `user` section directly
+        if let Some(data) = self.user.as_ref().and_then(|u| u.data.as_ref()) {
+            nm_conn_dbus_value.insert(
+                "user",
+                data.iter()
+                    .map(|(k, v)| (k.as_str(), zvariant::Value::new(v)))
+                    .collect(),
+            );
+        }
+
+        // Keyfile store VPN data and secrets as keys of `vpn` and
+        // `vpn-secrets` sections directly
+        if let Some(vpn) = self.vpn.as_ref() {
+            let mut vpn_value: HashMap<&str, zvariant::Value> = HashMap::new();
+            if let Some(v) = vpn.service_type.as_ref() {
+                vpn_value.insert("service-type", zvariant::Value::new(v));
+            }
+            if let Some(data) = vpn.data.as_ref() {
+                vpn_value.extend(
+                    data.iter()
+                        .map(|(k, v)| (k.as_str(), zvariant::Value::new(v))),
+                );
+            }
+            nm_conn_dbus_value.insert("vpn", vpn_value);
+            if let Some(secrets) = vpn.secrets.as_ref() {
+                nm_conn_dbus_value.insert(
+                    "vpn-secrets",
+                    secrets
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), zvariant::Value::new(v)))
+                        .collect(),
+                );
+            }
+        }
 
         let nm_conn_value = zvariant::Dict::from(nm_conn_dbus_value);
 
@@ -163,6 +290,9 @@ impl NmConnection {
         if let Some(bond_set) = &self.bond {
             ret.insert("bond", bond_set.to_value()?);
         }
+        if let Some(bond_port_set) = &self.bond_port {
+            ret.insert("bond-port", bond_port_set.to_value()?);
+        }
         if let Some(br_set) = &self.bridge {
             ret.insert("bridge", br_set.to_value()?);
         }
@@ -175,6 +305,9 @@ impl NmConnection {
         if let Some(ipv6_set) = &self.ipv6 {
             ret.insert("ipv6", ipv6_set.to_value()?);
         }
+        if let Some(ip_tunnel_set) = &self.ip_tunnel {
+            ret.insert("ip-tunnel", ip_tunnel_set.to_value()?);
+        }
         if let Some(ovs_bridge_set) = &self.ovs_bridge {
             ret.insert("ovs-bridge", ovs_bridge_set.to_value()?);
         }
@@ -184,6 +317,12 @@ impl NmConnection {
         if let Some(ovs_iface_set) = &self.ovs_iface {
             ret.insert("ovs-interface", ovs_iface_set.to_value()?);
         }
+        if let Some(ovs_other_cfg_set) = &self.ovs_other_config {
+            ret.insert("ovs-other-config", ovs_other_cfg_set.to_value()?);
+        }
+        if let Some(ovs_ext_ids_set) = &self.ovs_ext_ids {
+            ret.insert("ovs-external-ids", ovs_ext_ids_set.to_value()?);
+        }
         if let Some(wired_set) = &self.wired {
             ret.insert("802-3-ethernet", wired_set.to_value()?);
         }
@@ -196,6 +335,57 @@ impl NmConnection {
         if let Some(mac_vlan) = &self.mac_vlan {
             ret.insert("macvlan", mac_vlan.to_value()?);
         }
+        if let Some(vrf) = &self.vrf {
+            ret.insert("vrf", vrf.to_value()?);
+        }
+        if let Some(wireguard) = &self.wireguard {
+            ret.insert("wireguard", wireguard.to_value()?);
+        }
+        if let Some(team) = &self.team {
+            ret.insert("team", team.to_value()?);
+        }
+        if let Some(team_port) = &self.team_port {
+            ret.insert("team-port", team_port.to_value()?);
+        }
+        if let Some(wireless) = &self.wireless {
+            ret.insert("802-11-wireless", wireless.to_value()?);
+        }
+        if let Some(wireless_security) = &self.wireless_security {
+            ret.insert(
+                "802-11-wireless-security",
+                wireless_security.to_value()?,
+            );
+        }
+        if let Some(ieee8021x) = &self.ieee8021x {
+            ret.insert("802-1x", ieee8021x.to_value()?);
+        }
+        if let Some(user) = &self.user {
+            ret.insert("user", user.to_value()?);
+        }
+        if let Some(pppoe) = &self.pppoe {
+            ret.insert("pppoe", pppoe.to_value()?);
+        }
+        if let Some(hsr) = &self.hsr {
+            ret.insert("hsr", hsr.to_value()?);
+        }
+        if let Some(ipvlan) = &self.ipvlan {
+            ret.insert("ipvlan", ipvlan.to_value()?);
+        }
+        if let Some(infiniband) = &self.infiniband {
+            ret.insert("infiniband", infiniband.to_value()?);
+        }
+        if let Some(macsec) = &self.macsec {
+            ret.insert("macsec", macsec.to_value()?);
+        }
+        if let Some(loopback) = &self.loopback {
+            ret.insert("loopback", loopback.to_value()?);
+        }
+        if let Some(ethtool) = &self.ethtool {
+            ret.insert("ethtool", ethtool.to_value()?);
+        }
+        if let Some(vpn) = &self.vpn {
+            ret.insert("vpn", vpn.to_value()?);
+        }
         for (key, setting_value) in &self._other {
             let mut other_setting_value: HashMap<&str, zvariant::Value> =
                 HashMap::new();
@@ -231,6 +421,10 @@ pub struct NmSettingConnection {
     pub controller_type: Option<String>,
     pub autoconnect: Option<bool>,
     pub autoconnect_ports: Option<bool>,
+    // In the format of `user:<user_name>:`
+    pub permissions: Option<Vec<String>>,
+    // UUIDs of connections activated after this connection
+    pub secondaries: Option<Vec<String>>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -249,6 +443,8 @@ impl TryFrom<DbusDictionary> for NmSettingConnection {
             autoconnect_ports: NmSettingConnection::i32_to_autoconnect_ports(
                 _from_map!(v, "autoconnect-slaves", i32::try_from)?,
             ),
+            permissions: _from_map!(v, "permissions", <Vec<String>>::try_from)?,
+            secondaries: _from_map!(v, "secondaries", <Vec<String>>::try_from)?,
             _other: v,
         })
     }
@@ -311,6 +507,12 @@ impl NmSettingConnection {
                 None => zvariant::Value::new(NM_AUTOCONENCT_PORT_DEFAULT),
             },
         );
+        if let Some(v) = &self.permissions {
+            ret.insert("permissions", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = &self.secondaries {
+            ret.insert("secondaries", zvariant::Value::new(v.clone()));
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
@@ -332,3 +534,88 @@ pub(crate) fn nm_con_get_from_obj_path(
     nm_conn.obj_path = con_obj_path.to_string();
     Ok(nm_conn)
 }
+
+// Only the secrets of specified setting are included in returned NmConnection
+pub(crate) fn nm_con_secrets_get_from_obj_path(
+    dbus_con: &zbus::Connection,
+    con_obj_path: &str,
+    setting_name: &str,
+) -> Result<NmConnection, NmError> {
+    let proxy = zbus::Proxy::new(
+        dbus_con,
+        NM_DBUS_INTERFACE_ROOT,
+        con_obj_path,
+        NM_DBUS_INTERFACE_SETTING,
+    )?;
+    let mut nm_conn =
+        proxy.call::<&str, NmConnection>("GetSecrets", &setting_name)?;
+    nm_conn.obj_path = con_obj_path.to_string();
+    Ok(nm_conn)
+}
+
+// Where and how the connection profile is stored by NetworkManager
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NmConnectionStorage {
+    pub flags: u32,
+    // Empty for in-memory profile on old NetworkManager
+    pub filename: String,
+}
+
+impl NmConnectionStorage {
+    // Profile holding changes which are not saved to disk yet
+    pub fn is_unsaved(&self) -> bool {
+        self.flags & NM_SETTINGS_CONNECTION_FLAG_UNSAVED > 0
+    }
+
+    // Profile generated by NetworkManager for externally configured device
+    pub fn is_generated(&self) -> bool {
+        self.flags
+            & (NM_SETTINGS_CONNECTION_FLAG_NM_GENERATED
+                | NM_SETTINGS_CONNECTION_FLAG_EXTERNAL)
+            > 0
+    }
+
+    // Profile which will be gone after reboot
+    pub fn is_in_memory(&self) -> bool {
+        self.flags & NM_SETTINGS_CONNECTION_FLAG_VOLATILE > 0
+            || self.filename.is_empty()
+            || self.filename.starts_with(NM_RUN_PROFILE_DIR)
+    }
+}
+
+pub(crate) fn nm_con_storage_get_from_obj_path(
+    dbus_con: &zbus::Connection,
+    con_obj_path: &str,
+) -> Result<NmConnectionStorage, NmError> {
+    let proxy = zbus::Proxy::new(
+        dbus_con,
+        NM_DBUS_INTERFACE_ROOT,
+        con_obj_path,
+        NM_DBUS_INTERFACE_SETTING,
+    )?;
+    let flags = match proxy.get_property::<u32>("Flags") {
+        Ok(f) => f,
+        Err(e) => {
+            return Err(NmError::new(
+                ErrorKind::Bug,
+                format!(
+                    "Failed to retrieve flags of connection {}: {}",
+                    con_obj_path, e
+                ),
+            ));
+        }
+    };
+    let filename = match proxy.get_property::<String>("Filename") {
+        Ok(f) => f,
+        Err(e) => {
+            return Err(NmError::new(
+                ErrorKind::Bug,
+                format!(
+                    "Failed to retrieve filename of connection {}: {}",
+                    con_obj_path, e
+                ),
+            ));
+        }
+    };
+    Ok(NmConnectionStorage { flags, filename })
+}
@@ -0,0 +1,135 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+const NM_ETHTOOL_FEATURE_PREFIX: &str = "feature-";
+const NM_ETHTOOL_COALESCE_PREFIX: &str = "coalesce-";
+const NM_ETHTOOL_RING_PREFIX: &str = "ring-";
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingEthtool {
+    // Keyed by NetworkManager property name, for example `feature-rx`
+    pub features: Option<HashMap<String, bool>>,
+    // Keyed by NetworkManager property name, for example `coalesce-rx-usecs`
+    pub coalesce: Option<HashMap<String, u32>>,
+    // Keyed by NetworkManager property name, for example `ring-rx`
+    pub ring: Option<HashMap<String, u32>>,
+    pub pause_autoneg: Option<bool>,
+    pub pause_rx: Option<bool>,
+    pub pause_tx: Option<bool>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingEthtool {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        let mut features = HashMap::new();
+        for key in prefixed_keys(&v, NM_ETHTOOL_FEATURE_PREFIX) {
+            if let Some(value) = _from_map!(v, key.as_str(), bool::try_from)? {
+                features.insert(key, value);
+            }
+        }
+        let mut coalesce = HashMap::new();
+        for key in prefixed_keys(&v, NM_ETHTOOL_COALESCE_PREFIX) {
+            if let Some(value) = _from_map!(v, key.as_str(), u32::try_from)? {
+                coalesce.insert(key, value);
+            }
+        }
+        let mut ring = HashMap::new();
+        for key in prefixed_keys(&v, NM_ETHTOOL_RING_PREFIX) {
+            if let Some(value) = _from_map!(v, key.as_str(), u32::try_from)? {
+                ring.insert(key, value);
+            }
+        }
+        Ok(Self {
+            features: if features.is_empty() {
+                None
+            } else {
+                Some(features)
+            },
+            coalesce: if coalesce.is_empty() {
+                None
+            } else {
+                Some(coalesce)
+            },
+            ring: if ring.is_empty() { None } else { Some(ring) },
+            pause_autoneg: _from_map!(v, "pause-autoneg", bool::try_from)?,
+            pause_rx: _from_map!(v, "pause-rx", bool::try_from)?,
+            pause_tx: _from_map!(v, "pause-tx", bool::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingEthtool {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(features) = &self.features {
+            for (k, v) in features {
+                ret.insert(k.as_str(), zvariant::Value::new(*v));
+            }
+        }
+        for values in self.coalesce.iter().chain(self.ring.iter()) {
+            for (k, v) in values {
+                ret.insert(k.as_str(), zvariant::Value::new(*v));
+            }
+        }
+        if let Some(v) = self.pause_autoneg {
+            ret.insert("pause-autoneg", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.pause_rx {
+            ret.insert("pause-rx", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.pause_tx {
+            ret.insert("pause-tx", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feature_name(name: &str) -> String {
+        format!("{}{}", NM_ETHTOOL_FEATURE_PREFIX, name)
+    }
+
+    pub fn coalesce_name(name: &str) -> String {
+        format!("{}{}", NM_ETHTOOL_COALESCE_PREFIX, name)
+    }
+
+    pub fn ring_name(name: &str) -> String {
+        format!("{}{}", NM_ETHTOOL_RING_PREFIX, name)
+    }
+}
+
+fn prefixed_keys(v: &DbusDictionary, prefix: &str) -> Vec<String> {
+    v.keys()
+        .filter(|k| k.starts_with(prefix))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,72 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingHsr {
+    pub port1: Option<String>,
+    pub port2: Option<String>,
+    pub multicast_spec: Option<u32>,
+    pub prp: Option<bool>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingHsr {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            port1: _from_map!(v, "port1", String::try_from)?,
+            port2: _from_map!(v, "port2", String::try_from)?,
+            multicast_spec: _from_map!(v, "multicast-spec", u32::try_from)?,
+            prp: _from_map!(v, "prp", bool::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingHsr {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.port1 {
+            ret.insert("port1", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.port2 {
+            ret.insert("port2", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.multicast_spec {
+            ret.insert("multicast-spec", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.prp {
+            ret.insert("prp", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingInfiniBand {
+    pub parent: Option<String>,
+    pub pkey: Option<i32>,
+    pub mode: Option<String>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingInfiniBand {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            parent: _from_map!(v, "parent", String::try_from)?,
+            pkey: _from_map!(v, "p-key", i32::try_from)?,
+            mode: _from_map!(v, "transport-mode", String::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingInfiniBand {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.parent {
+            ret.insert("parent", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.pkey {
+            ret.insert("p-key", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.mode {
+            ret.insert("transport-mode", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    // The p-key -1 means using the PKEY of parent
+    pub const PKEY_DEFAULT: i32 = -1;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
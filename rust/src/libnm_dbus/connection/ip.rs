@@ -103,10 +103,13 @@ pub struct NmSettingIp {
     pub dns: Option<Vec<String>>,
     pub ignore_auto_dns: Option<bool>,
     pub never_default: Option<bool>,
+    pub may_fail: Option<bool>,
     pub ignore_auto_routes: Option<bool>,
     pub route_table: Option<u32>,
     pub dhcp_client_id: Option<String>,
     pub dhcp_timeout: Option<i32>,
+    // IPv4 only
+    pub clat: Option<i32>,
     // IPv6 only
     pub ra_timeout: Option<i32>,
     // IPv6 only
@@ -137,11 +140,13 @@ impl TryFrom<DbusDictionary> for NmSettingIp {
         setting.ignore_auto_dns =
             _from_map!(v, "ignore-auto-dns", bool::try_from)?;
         setting.never_default = _from_map!(v, "never-default", bool::try_from)?;
+        setting.may_fail = _from_map!(v, "may-fail", bool::try_from)?;
         setting.ignore_auto_routes =
             _from_map!(v, "ignore-auto-routes", bool::try_from)?;
         setting.dhcp_client_id =
             _from_map!(v, "dhcp-client-id", String::try_from)?;
         setting.dhcp_timeout = _from_map!(v, "dhcp-timeout", i32::try_from)?;
+        setting.clat = _from_map!(v, "clat", i32::try_from)?;
         setting.ra_timeout = _from_map!(v, "ra-timeout", i32::try_from)?;
         setting.addr_gen_mode = _from_map!(v, "addr-gen-mode", i32::try_from)?;
         setting.dhcp_duid = _from_map!(v, "dhcp-duid", String::try_from)?;
@@ -228,12 +233,18 @@ impl NmSettingIp {
         if let Some(v) = self.never_default {
             ret.insert("never-default", zvariant::Value::new(v));
         }
+        if let Some(v) = self.may_fail {
+            ret.insert("may-fail", zvariant::Value::new(v));
+        }
         if let Some(v) = &self.dhcp_client_id {
             ret.insert("dhcp-client-id", zvariant::Value::new(v));
         }
         if let Some(v) = self.dhcp_timeout {
             ret.insert("dhcp-timeout", zvariant::Value::new(v));
         }
+        if let Some(v) = self.clat {
+            ret.insert("clat", zvariant::Value::new(v));
+        }
         if let Some(v) = self.ra_timeout {
             ret.insert("ra-timeout", zvariant::Value::new(v));
         }
@@ -0,0 +1,90 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingIpTunnel {
+    pub mode: Option<u32>,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    pub input_key: Option<String>,
+    pub output_key: Option<String>,
+    pub ttl: Option<u32>,
+    pub parent: Option<String>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingIpTunnel {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            mode: _from_map!(v, "mode", u32::try_from)?,
+            local: _from_map!(v, "local", String::try_from)?,
+            remote: _from_map!(v, "remote", String::try_from)?,
+            input_key: _from_map!(v, "input-key", String::try_from)?,
+            output_key: _from_map!(v, "output-key", String::try_from)?,
+            ttl: _from_map!(v, "ttl", u32::try_from)?,
+            parent: _from_map!(v, "parent", String::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingIpTunnel {
+    pub const MODE_GRE: u32 = 2;
+    pub const MODE_GRETAP: u32 = 10;
+
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = self.mode {
+            ret.insert("mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.local {
+            ret.insert("local", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.remote {
+            ret.insert("remote", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.input_key {
+            ret.insert("input-key", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.output_key {
+            ret.insert("output-key", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.ttl {
+            ret.insert("ttl", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.parent {
+            ret.insert("parent", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingMacSec {
+    pub parent: Option<String>,
+    pub mode: Option<i32>,
+    pub encrypt: Option<bool>,
+    pub mka_cak: Option<String>,
+    pub mka_ckn: Option<String>,
+    pub port: Option<i32>,
+    pub validation: Option<i32>,
+    pub send_sci: Option<bool>,
+    pub offload: Option<i32>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingMacSec {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            parent: _from_map!(v, "parent", String::try_from)?,
+            mode: _from_map!(v, "mode", i32::try_from)?,
+            encrypt: _from_map!(v, "encrypt", bool::try_from)?,
+            mka_cak: _from_map!(v, "mka-cak", String::try_from)?,
+            mka_ckn: _from_map!(v, "mka-ckn", String::try_from)?,
+            port: _from_map!(v, "port", i32::try_from)?,
+            validation: _from_map!(v, "validation", i32::try_from)?,
+            send_sci: _from_map!(v, "send-sci", bool::try_from)?,
+            offload: _from_map!(v, "offload", i32::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingMacSec {
+    pub const MODE_PSK: i32 = 0;
+
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.parent {
+            ret.insert("parent", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.mode {
+            ret.insert("mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.encrypt {
+            ret.insert("encrypt", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.mka_cak {
+            ret.insert("mka-cak", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.mka_ckn {
+            ret.insert("mka-ckn", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.port {
+            ret.insert("port", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.validation {
+            ret.insert("validation", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.send_sci {
+            ret.insert("send-sci", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.offload {
+            ret.insert("offload", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
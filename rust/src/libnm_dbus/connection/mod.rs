@@ -20,34 +20,68 @@ mod bond;
 mod bridge;
 mod conn;
 mod dns;
+mod ethtool;
+mod hsr;
+mod infiniband;
 mod ip;
+mod ip_tunnel;
+mod ipvlan;
+mod loopback;
 mod mac_vlan;
+mod macsec;
 mod ovs;
+mod pppoe;
 mod route;
 mod route_rule;
 mod sriov;
+mod team;
+mod user;
 mod vlan;
+mod vpn;
+mod vrf;
+mod wifi;
 mod wired;
+mod wireguard;
 
-pub use crate::connection::bond::NmSettingBond;
+pub use crate::connection::bond::{NmSettingBond, NmSettingBondPort};
 pub use crate::connection::bridge::{
     NmSettingBridge, NmSettingBridgePort, NmSettingBridgeVlanRange,
 };
-pub use crate::connection::conn::{NmConnection, NmSettingConnection};
+pub use crate::connection::conn::{
+    NmConnection, NmConnectionStorage, NmSettingConnection,
+};
+pub use crate::connection::ethtool::NmSettingEthtool;
+pub use crate::connection::hsr::NmSettingHsr;
+pub use crate::connection::infiniband::NmSettingInfiniBand;
 pub use crate::connection::ip::{NmSettingIp, NmSettingIpMethod};
+pub use crate::connection::ip_tunnel::NmSettingIpTunnel;
+pub use crate::connection::ipvlan::NmSettingIpVlan;
+pub use crate::connection::loopback::NmSettingLoopback;
 pub use crate::connection::mac_vlan::NmSettingMacVlan;
+pub use crate::connection::macsec::NmSettingMacSec;
 pub use crate::connection::ovs::{
-    NmSettingOvsBridge, NmSettingOvsIface, NmSettingOvsPort,
+    NmSettingOvsBridge, NmSettingOvsExtIds, NmSettingOvsIface,
+    NmSettingOvsOtherConfig, NmSettingOvsPort, NmSettingOvsPortTrunk,
 };
+pub use crate::connection::pppoe::NmSettingPppoe;
 pub use crate::connection::route::NmIpRoute;
 pub use crate::connection::route_rule::NmIpRouteRule;
 pub use crate::connection::sriov::{
     NmSettingSriov, NmSettingSriovVf, NmSettingSriovVfVlan,
 };
+pub use crate::connection::team::{NmSettingTeam, NmSettingTeamPort};
+pub use crate::connection::user::NmSettingUser;
 pub use crate::connection::vlan::{NmSettingVlan, NmVlanProtocol};
+pub use crate::connection::vpn::NmSettingVpn;
+pub use crate::connection::vrf::NmSettingVrf;
+pub use crate::connection::wifi::{
+    NmSetting8021X, NmSettingWireless, NmSettingWirelessSecurity,
+};
 pub use crate::connection::wired::NmSettingWired;
+pub use crate::connection::wireguard::{NmSettingWireGuard, NmWireGuardPeer};
 
 pub(crate) use crate::connection::conn::{
-    nm_con_get_from_obj_path, DbusDictionary, NmConnectionDbusValue,
+    nm_con_get_from_obj_path, nm_con_secrets_get_from_obj_path,
+    nm_con_storage_get_from_obj_path, DbusDictionary, NmConnectionDbusValue,
 };
 pub(crate) use crate::connection::macros::_from_map;
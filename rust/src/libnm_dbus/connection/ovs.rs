@@ -27,6 +27,7 @@ pub struct NmSettingOvsBridge {
     pub mcast_snooping_enable: Option<bool>,
     pub rstp: Option<bool>,
     pub fail_mode: Option<String>,
+    pub datapath_type: Option<String>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -42,6 +43,7 @@ impl TryFrom<DbusDictionary> for NmSettingOvsBridge {
             )?,
             rstp: _from_map!(v, "rstp-enable", bool::try_from)?,
             fail_mode: _from_map!(v, "fail-mode", String::try_from)?,
+            datapath_type: _from_map!(v, "datapath-type", String::try_from)?,
             _other: v,
         })
     }
@@ -64,6 +66,9 @@ impl NmSettingOvsBridge {
         if let Some(v) = &self.fail_mode {
             ret.insert("fail-mode", zvariant::Value::new(v));
         }
+        if let Some(v) = &self.datapath_type {
+            ret.insert("datapath-type", zvariant::Value::new(v));
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
@@ -81,6 +86,10 @@ pub struct NmSettingOvsPort {
     pub mode: Option<String>,
     pub up_delay: Option<u32>,
     pub down_delay: Option<u32>,
+    pub lacp: Option<String>,
+    pub vlan_mode: Option<String>,
+    pub tag: Option<u32>,
+    pub trunks: Option<Vec<NmSettingOvsPortTrunk>>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -91,6 +100,10 @@ impl TryFrom<DbusDictionary> for NmSettingOvsPort {
             mode: _from_map!(v, "bond-mode", String::try_from)?,
             up_delay: _from_map!(v, "bond-updelay", u32::try_from)?,
             down_delay: _from_map!(v, "bond-downdelay", u32::try_from)?,
+            lacp: _from_map!(v, "lacp", String::try_from)?,
+            vlan_mode: _from_map!(v, "vlan-mode", String::try_from)?,
+            tag: _from_map!(v, "tag", u32::try_from)?,
+            trunks: _from_map!(v, "trunks", own_value_to_trunks)?,
             _other: v,
         })
     }
@@ -114,6 +127,24 @@ impl NmSettingOvsPort {
         if let Some(v) = self.down_delay {
             ret.insert("bond-downdelay", zvariant::Value::new(v));
         }
+        if let Some(v) = &self.lacp {
+            ret.insert("lacp", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.vlan_mode {
+            ret.insert("vlan-mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.tag {
+            ret.insert("tag", zvariant::Value::new(v));
+        }
+        if let Some(trunks) = &self.trunks {
+            let mut trunk_values = zvariant::Array::new(
+                zvariant::Signature::from_str_unchecked("a{sv}"),
+            );
+            for trunk in trunks {
+                trunk_values.append(trunk.to_value()?)?;
+            }
+            ret.insert("trunks", zvariant::Value::Array(trunk_values));
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
@@ -121,6 +152,62 @@ impl NmSettingOvsPort {
     }
 }
 
+// The VLAN range allowed on OVS trunk port
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingOvsPortTrunk {
+    pub start: u32,
+    pub end: u32,
+    _other: DbusDictionary,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingOvsPortTrunk {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            start: _from_map!(v, "start", u32::try_from)?.unwrap_or_default(),
+            end: _from_map!(v, "end", u32::try_from)?.unwrap_or_default(),
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingOvsPortTrunk {
+    pub fn new(start: u32, end: u32) -> Self {
+        Self {
+            start,
+            end,
+            ..Default::default()
+        }
+    }
+
+    pub fn to_value(&self) -> Result<zvariant::Value, NmError> {
+        let mut ret = zvariant::Dict::new(
+            zvariant::Signature::from_str_unchecked("s"),
+            zvariant::Signature::from_str_unchecked("v"),
+        );
+        ret.append(
+            zvariant::Value::new("start"),
+            zvariant::Value::new(zvariant::Value::U32(self.start)),
+        )?;
+        ret.append(
+            zvariant::Value::new("end"),
+            zvariant::Value::new(zvariant::Value::U32(self.end)),
+        )?;
+        Ok(zvariant::Value::Dict(ret))
+    }
+}
+
+fn own_value_to_trunks(
+    value: zvariant::OwnedValue,
+) -> Result<Vec<NmSettingOvsPortTrunk>, NmError> {
+    let mut ret = Vec::new();
+    for raw_trunk in Vec::<DbusDictionary>::try_from(value)? {
+        ret.push(NmSettingOvsPortTrunk::try_from(raw_trunk)?);
+    }
+    Ok(ret)
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Deserialize)]
 #[serde(try_from = "DbusDictionary")]
 pub struct NmSettingOvsIface {
@@ -156,3 +243,75 @@ impl NmSettingOvsIface {
         Self::default()
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingOvsOtherConfig {
+    pub data: Option<HashMap<String, String>>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingOvsOtherConfig {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            data: _from_map!(v, "data", HashMap::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingOvsOtherConfig {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.data {
+            ret.insert("data", zvariant::Value::from(v.clone()));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingOvsExtIds {
+    pub data: Option<HashMap<String, String>>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingOvsExtIds {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            data: _from_map!(v, "data", HashMap::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingOvsExtIds {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.data {
+            ret.insert("data", zvariant::Value::from(v.clone()));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -27,6 +27,8 @@ pub struct NmIpRoute {
     pub next_hop: Option<String>,
     pub table: Option<u32>,
     pub metric: Option<u32>,
+    pub weight: Option<u32>,
+    pub route_type: Option<String>,
     _other: DbusDictionary,
 }
 
@@ -39,6 +41,8 @@ impl TryFrom<DbusDictionary> for NmIpRoute {
         setting.next_hop = _from_map!(v, "next-hop", String::try_from)?;
         setting.table = _from_map!(v, "table", u32::try_from)?;
         setting.metric = _from_map!(v, "metric", u32::try_from)?;
+        setting.weight = _from_map!(v, "weight", u32::try_from)?;
+        setting.route_type = _from_map!(v, "type", String::try_from)?;
 
         setting._other = v;
         Ok(setting)
@@ -85,6 +89,18 @@ impl NmIpRoute {
                 zvariant::Value::new(zvariant::Value::new(v)),
             )?;
         }
+        if let Some(v) = &self.weight {
+            ret.append(
+                zvariant::Value::new("weight"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.route_type {
+            ret.append(
+                zvariant::Value::new("type"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
 
         for (key, value) in self._other.iter() {
             ret.append(
@@ -11,6 +11,12 @@ use crate::{connection::DbusDictionary, ErrorKind, NmError};
 pub struct NmSettingVlan {
     pub parent: Option<String>,
     pub id: Option<u32>,
+    pub protocol: Option<NmVlanProtocol>,
+    // In the format of `from:to`
+    pub ingress_priority_map: Option<Vec<String>>,
+    pub egress_priority_map: Option<Vec<String>>,
+    // Bitmask of NMVlanFlags
+    pub flags: Option<u32>,
     _other: HashMap<String, zvariant::OwnedValue>,
 }
 
@@ -20,6 +26,20 @@ impl TryFrom<DbusDictionary> for NmSettingVlan {
         Ok(Self {
             parent: _from_map!(v, "parent", String::try_from)?,
             id: _from_map!(v, "id", u32::try_from)?,
+            protocol: _from_map!(v, "protocol", String::try_from)?
+                .map(NmVlanProtocol::try_from)
+                .transpose()?,
+            ingress_priority_map: _from_map!(
+                v,
+                "ingress-priority-map",
+                <Vec<String>>::try_from
+            )?,
+            egress_priority_map: _from_map!(
+                v,
+                "egress-priority-map",
+                <Vec<String>>::try_from
+            )?,
+            flags: _from_map!(v, "flags", u32::try_from)?,
             _other: v,
         })
     }
@@ -36,6 +56,18 @@ impl NmSettingVlan {
         if let Some(id) = self.id {
             ret.insert("id", zvariant::Value::new(id));
         }
+        if let Some(v) = self.protocol {
+            ret.insert("protocol", zvariant::Value::new(v.to_str()));
+        }
+        if let Some(v) = &self.ingress_priority_map {
+            ret.insert("ingress-priority-map", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = &self.egress_priority_map {
+            ret.insert("egress-priority-map", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = self.flags {
+            ret.insert("flags", zvariant::Value::new(v));
+        }
         ret.extend(self._other.iter().map(|(key, value)| {
             (key.as_str(), zvariant::Value::from(value.clone()))
         }));
@@ -0,0 +1,67 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingVpn {
+    pub service_type: Option<String>,
+    pub data: Option<HashMap<String, String>>,
+    pub secrets: Option<HashMap<String, String>>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingVpn {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            service_type: _from_map!(v, "service-type", String::try_from)?,
+            data: _from_map!(v, "data", HashMap::try_from)?,
+            secrets: _from_map!(v, "secrets", HashMap::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingVpn {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.service_type {
+            ret.insert("service-type", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.data {
+            ret.insert("data", zvariant::Value::from(v.clone()));
+        }
+        if let Some(v) = &self.secrets {
+            ret.insert("secrets", zvariant::Value::from(v.clone()));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
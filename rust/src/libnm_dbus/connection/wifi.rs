@@ -0,0 +1,161 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{
+    connection::DbusDictionary, convert::own_value_to_bytes_array, NmError,
+};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingWireless {
+    pub ssid: Option<Vec<u8>>,
+    pub mode: Option<String>,
+    pub band: Option<String>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingWireless {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ssid: _from_map!(v, "ssid", own_value_to_bytes_array)?,
+            mode: _from_map!(v, "mode", String::try_from)?,
+            band: _from_map!(v, "band", String::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingWireless {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.ssid {
+            ret.insert("ssid", zvariant::Value::new(v.clone()));
+        }
+        if let Some(v) = &self.mode {
+            ret.insert("mode", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.band {
+            ret.insert("band", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingWirelessSecurity {
+    pub key_mgmt: Option<String>,
+    pub psk: Option<String>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingWirelessSecurity {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            key_mgmt: _from_map!(v, "key-mgmt", String::try_from)?,
+            psk: _from_map!(v, "psk", String::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingWirelessSecurity {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.key_mgmt {
+            ret.insert("key-mgmt", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.psk {
+            ret.insert("psk", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSetting8021X {
+    pub eap: Option<Vec<String>>,
+    pub identity: Option<String>,
+    pub password: Option<String>,
+    pub phase2_auth: Option<String>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSetting8021X {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            eap: _from_map!(v, "eap", <Vec<String>>::try_from)?,
+            identity: _from_map!(v, "identity", String::try_from)?,
+            password: _from_map!(v, "password", String::try_from)?,
+            phase2_auth: _from_map!(v, "phase2-auth", String::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSetting8021X {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.eap {
+            ret.insert("eap", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.identity {
+            ret.insert("identity", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.password {
+            ret.insert("password", zvariant::Value::new(v));
+        }
+        if let Some(v) = &self.phase2_auth {
+            ret.insert("phase2-auth", zvariant::Value::new(v));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
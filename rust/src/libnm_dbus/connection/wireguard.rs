@@ -0,0 +1,167 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+
+use crate::{connection::DbusDictionary, NmError};
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmSettingWireGuard {
+    pub private_key: Option<String>,
+    pub listen_port: Option<u32>,
+    pub fwmark: Option<u32>,
+    pub peers: Option<Vec<NmWireGuardPeer>>,
+    _other: HashMap<String, zvariant::OwnedValue>,
+}
+
+impl TryFrom<DbusDictionary> for NmSettingWireGuard {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            private_key: _from_map!(v, "private-key", String::try_from)?,
+            listen_port: _from_map!(v, "listen-port", u32::try_from)?,
+            fwmark: _from_map!(v, "fwmark", u32::try_from)?,
+            peers: _from_map!(v, "peers", parse_nm_wireguard_peers)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmSettingWireGuard {
+    pub(crate) fn to_value(
+        &self,
+    ) -> Result<HashMap<&str, zvariant::Value>, NmError> {
+        let mut ret = HashMap::new();
+        if let Some(v) = &self.private_key {
+            ret.insert("private-key", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.listen_port {
+            ret.insert("listen-port", zvariant::Value::new(v));
+        }
+        if let Some(v) = self.fwmark {
+            ret.insert("fwmark", zvariant::Value::new(v));
+        }
+        if let Some(peers) = &self.peers {
+            let mut peer_values = zvariant::Array::new(
+                zvariant::Signature::from_str_unchecked("a{sv}"),
+            );
+            for peer in peers {
+                peer_values.append(peer.to_value()?)?;
+            }
+            ret.insert("peers", zvariant::Value::Array(peer_values));
+        }
+        ret.extend(self._other.iter().map(|(key, value)| {
+            (key.as_str(), zvariant::Value::from(value.clone()))
+        }));
+        Ok(ret)
+    }
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(try_from = "DbusDictionary")]
+pub struct NmWireGuardPeer {
+    pub public_key: Option<String>,
+    pub endpoint: Option<String>,
+    pub allowed_ips: Option<Vec<String>>,
+    pub persistent_keepalive: Option<u32>,
+    pub preshared_key: Option<String>,
+    _other: DbusDictionary,
+}
+
+impl TryFrom<DbusDictionary> for NmWireGuardPeer {
+    type Error = NmError;
+    fn try_from(mut v: DbusDictionary) -> Result<Self, Self::Error> {
+        Ok(Self {
+            public_key: _from_map!(v, "public-key", String::try_from)?,
+            endpoint: _from_map!(v, "endpoint", String::try_from)?,
+            allowed_ips: _from_map!(v, "allowed-ips", <Vec<String>>::try_from)?,
+            persistent_keepalive: _from_map!(
+                v,
+                "persistent-keepalive",
+                u32::try_from
+            )?,
+            preshared_key: _from_map!(v, "preshared-key", String::try_from)?,
+            _other: v,
+        })
+    }
+}
+
+impl NmWireGuardPeer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn to_value(&self) -> Result<zvariant::Value, NmError> {
+        let mut ret = zvariant::Dict::new(
+            zvariant::Signature::from_str_unchecked("s"),
+            zvariant::Signature::from_str_unchecked("v"),
+        );
+        if let Some(v) = &self.public_key {
+            ret.append(
+                zvariant::Value::new("public-key"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.endpoint {
+            ret.append(
+                zvariant::Value::new("endpoint"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.allowed_ips {
+            ret.append(
+                zvariant::Value::new("allowed-ips"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.persistent_keepalive {
+            ret.append(
+                zvariant::Value::new("persistent-keepalive"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        if let Some(v) = &self.preshared_key {
+            ret.append(
+                zvariant::Value::new("preshared-key"),
+                zvariant::Value::new(zvariant::Value::new(v)),
+            )?;
+        }
+        for (key, value) in self._other.iter() {
+            ret.append(
+                zvariant::Value::new(key.as_str()),
+                zvariant::Value::from(value.clone()),
+            )?;
+        }
+        Ok(zvariant::Value::Dict(ret))
+    }
+}
+
+fn parse_nm_wireguard_peers(
+    value: zvariant::OwnedValue,
+) -> Result<Vec<NmWireGuardPeer>, NmError> {
+    let mut peers = Vec::new();
+    for nm_peer_value in <Vec<DbusDictionary>>::try_from(value)? {
+        peers.push(NmWireGuardPeer::try_from(nm_peer_value)?);
+    }
+    Ok(peers)
+}
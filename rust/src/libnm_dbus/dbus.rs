@@ -31,8 +31,6 @@ const NM_CHECKPOINT_CREATE_FLAG_DISCONNECT_NEW_DEVICES: u32 = 0x04;
 pub(crate) const NM_TERNARY_TRUE: i32 = 1;
 pub(crate) const NM_TERNARY_FALSE: i32 = 0;
 
-const CHECKPOINT_TMO: u32 = 30;
-
 const OBJ_PATH_NULL_STR: &str = "/";
 
 pub(crate) const NM_DBUS_INTERFACE_ROOT: &str =
@@ -47,9 +45,11 @@ pub(crate) const NM_DBUS_INTERFACE_DEV: &str =
 const NM_DBUS_INTERFACE_DEVICE: &str = "org.freedesktop.NetworkManager.Device";
 
 const NM_SETTINGS_CREATE2_FLAGS_TO_DISK: u32 = 1;
+const NM_SETTINGS_CREATE2_FLAGS_IN_MEMORY: u32 = 2;
 const NM_SETTINGS_CREATE2_FLAGS_BLOCK_AUTOCONNECT: u32 = 32;
 
 const NM_SETTINGS_UPDATE2_FLAGS_TO_DISK: u32 = 1;
+const NM_SETTINGS_UPDATE2_FLAGS_IN_MEMORY: u32 = 2;
 const NM_SETTINGS_UPDATE2_FLAGS_BLOCK_AUTOCONNECT: u32 = 32;
 
 pub(crate) struct NmDbus<'a> {
@@ -78,10 +78,13 @@ impl<'a> NmDbus<'a> {
         Ok(self.proxy.version()?)
     }
 
-    pub(crate) fn checkpoint_create(&self) -> Result<String, NmError> {
+    pub(crate) fn checkpoint_create(
+        &self,
+        timeout: u32,
+    ) -> Result<String, NmError> {
         match self.proxy.checkpoint_create(
             &[],
-            CHECKPOINT_TMO,
+            timeout,
             NM_CHECKPOINT_CREATE_FLAG_DELETE_NEW_CONNECTIONS
                 | NM_CHECKPOINT_CREATE_FLAG_DISCONNECT_NEW_DEVICES,
         ) {
@@ -107,6 +110,15 @@ impl<'a> NmDbus<'a> {
         }
     }
 
+    pub(crate) fn checkpoints(&self) -> Result<Vec<String>, NmError> {
+        Ok(self
+            .proxy
+            .checkpoints()?
+            .into_iter()
+            .map(obj_path_to_string)
+            .collect())
+    }
+
     pub(crate) fn checkpoint_destroy(
         &self,
         checkpoint: &str,
@@ -127,6 +139,14 @@ impl<'a> NmDbus<'a> {
         Ok(())
     }
 
+    pub(crate) fn hostname_get(&self) -> Result<String, NmError> {
+        Ok(self.setting_proxy.hostname()?)
+    }
+
+    pub(crate) fn hostname_set(&self, hostname: &str) -> Result<(), NmError> {
+        Ok(self.setting_proxy.save_hostname(hostname)?)
+    }
+
     pub(crate) fn get_connection_by_uuid(
         &self,
         uuid: &str,
@@ -186,12 +206,16 @@ impl<'a> NmDbus<'a> {
     pub(crate) fn connection_add(
         &self,
         nm_conn: &NmConnection,
+        memory_only: bool,
     ) -> Result<(), NmError> {
         let value = nm_conn.to_value()?;
         self.setting_proxy.add_connection2(
             value,
-            NM_SETTINGS_CREATE2_FLAGS_TO_DISK
-                + NM_SETTINGS_CREATE2_FLAGS_BLOCK_AUTOCONNECT,
+            if memory_only {
+                NM_SETTINGS_CREATE2_FLAGS_IN_MEMORY
+            } else {
+                NM_SETTINGS_CREATE2_FLAGS_TO_DISK
+            } + NM_SETTINGS_CREATE2_FLAGS_BLOCK_AUTOCONNECT,
             HashMap::new(),
         )?;
         Ok(())
@@ -215,6 +239,7 @@ impl<'a> NmDbus<'a> {
         &self,
         con_obj_path: &str,
         nm_conn: &NmConnection,
+        memory_only: bool,
     ) -> Result<(), NmError> {
         let value = nm_conn.to_value()?;
         let proxy = zbus::Proxy::new(
@@ -232,7 +257,13 @@ impl<'a> NmDbus<'a> {
                 &(
                     value,
                     NM_SETTINGS_UPDATE2_FLAGS_BLOCK_AUTOCONNECT
-                        + NM_SETTINGS_UPDATE2_FLAGS_TO_DISK,
+                        + if memory_only {
+                            // The on-disk file is untouched and will be
+                            // used again after reboot.
+                            NM_SETTINGS_UPDATE2_FLAGS_IN_MEMORY
+                        } else {
+                            NM_SETTINGS_UPDATE2_FLAGS_TO_DISK
+                        },
                     HashMap::new()
                 ),
             )?;
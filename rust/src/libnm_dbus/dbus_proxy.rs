@@ -30,6 +30,9 @@ trait NetworkManager {
         &self,
     ) -> zbus::Result<Vec<zvariant::OwnedObjectPath>>;
 
+    #[dbus_proxy(property)]
+    fn checkpoints(&self) -> zbus::Result<Vec<zvariant::OwnedObjectPath>>;
+
     /// CheckpointCreate method
     fn checkpoint_create(
         &self,
@@ -107,6 +110,13 @@ trait NetworkManagerSetting {
     /// ListConnections method
     fn list_connections(&self) -> zbus::Result<Vec<zvariant::OwnedObjectPath>>;
 
+    /// Hostname property
+    #[dbus_proxy(property)]
+    fn hostname(&self) -> zbus::Result<String>;
+
+    /// SaveHostname method
+    fn save_hostname(&self, hostname: &str) -> zbus::Result<()>;
+
     /// GetAllDevices method
     fn get_all_devices(&self) -> zbus::Result<Vec<zvariant::OwnedObjectPath>>;
 }
@@ -123,3 +133,32 @@ trait NetworkManagerDns {
         &self,
     ) -> zbus::Result<Vec<HashMap<String, zvariant::OwnedValue>>>;
 }
+
+#[dbus_proxy(
+    interface = "org.freedesktop.hostname1",
+    default_service = "org.freedesktop.hostname1",
+    default_path = "/org/freedesktop/hostname1"
+)]
+trait Hostnamed {
+    /// Hostname property
+    #[dbus_proxy(property)]
+    fn hostname(&self) -> zbus::Result<String>;
+
+    /// StaticHostname property
+    #[dbus_proxy(property)]
+    fn static_hostname(&self) -> zbus::Result<String>;
+
+    /// SetHostname method
+    fn set_hostname(
+        &self,
+        hostname: &str,
+        interactive: bool,
+    ) -> zbus::Result<()>;
+
+    /// SetStaticHostname method
+    fn set_static_hostname(
+        &self,
+        hostname: &str,
+        interactive: bool,
+    ) -> zbus::Result<()>;
+}
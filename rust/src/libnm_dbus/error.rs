@@ -21,6 +21,8 @@ pub enum ErrorKind {
     IncompatibleReapply,
     Bug,
     Timeout,
+    PermissionDenied,
+    ServiceUnavailable,
 }
 
 impl std::fmt::Display for ErrorKind {
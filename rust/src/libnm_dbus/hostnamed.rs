@@ -0,0 +1,105 @@
+// Copyright 2021 Red Hat, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use log::debug;
+
+use crate::{
+    dbus_proxy::HostnamedProxy,
+    error::{ErrorKind, NmError},
+};
+
+const DBUS_ERROR_SERVICE_UNKNOWN: &str =
+    "org.freedesktop.DBus.Error.ServiceUnknown";
+const DBUS_ERROR_NAME_HAS_NO_OWNER: &str =
+    "org.freedesktop.DBus.Error.NameHasNoOwner";
+const DBUS_ERROR_ACCESS_DENIED: &str =
+    "org.freedesktop.DBus.Error.AccessDenied";
+const DBUS_ERROR_INTERACTIVE_AUTH_REQUIRED: &str =
+    "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired";
+
+// API of systemd-hostnamed
+pub struct HostnamedApi<'a> {
+    proxy: HostnamedProxy<'a>,
+}
+
+impl<'a> HostnamedApi<'a> {
+    pub fn new() -> Result<Self, NmError> {
+        let connection = zbus::Connection::new_system()?;
+        Ok(Self {
+            proxy: HostnamedProxy::new(&connection)?,
+        })
+    }
+
+    // Empty string means static hostname is not set
+    pub fn static_hostname_get(&self) -> Result<String, NmError> {
+        debug!("static_hostname_get");
+        self.proxy.static_hostname().map_err(hostnamed_error)
+    }
+
+    pub fn static_hostname_set(&self, hostname: &str) -> Result<(), NmError> {
+        debug!("static_hostname_set: {}", hostname);
+        self.proxy
+            .set_static_hostname(hostname, false)
+            .map_err(hostnamed_error)
+    }
+
+    pub fn hostname_get(&self) -> Result<String, NmError> {
+        debug!("hostname_get");
+        self.proxy.hostname().map_err(hostnamed_error)
+    }
+
+    pub fn hostname_set(&self, hostname: &str) -> Result<(), NmError> {
+        debug!("hostname_set: {}", hostname);
+        self.proxy
+            .set_hostname(hostname, false)
+            .map_err(hostnamed_error)
+    }
+}
+
+fn hostnamed_error(e: zbus::Error) -> NmError {
+    let kind = match &e {
+        zbus::Error::MethodError(error_type, ..) => match error_type.as_str() {
+            DBUS_ERROR_SERVICE_UNKNOWN | DBUS_ERROR_NAME_HAS_NO_OWNER => {
+                Some(ErrorKind::ServiceUnavailable)
+            }
+            DBUS_ERROR_ACCESS_DENIED | DBUS_ERROR_INTERACTIVE_AUTH_REQUIRED => {
+                Some(ErrorKind::PermissionDenied)
+            }
+            _ => None,
+        },
+        // No system D-Bus
+        zbus::Error::Io(_) => Some(ErrorKind::ServiceUnavailable),
+        // Property retrieval failure
+        zbus::Error::FDO(fdo_error) => match fdo_error.as_ref() {
+            zbus::fdo::Error::ServiceUnknown(_)
+            | zbus::fdo::Error::NameHasNoOwner(_) => {
+                Some(ErrorKind::ServiceUnavailable)
+            }
+            zbus::fdo::Error::AccessDenied(_)
+            | zbus::fdo::Error::InteractiveAuthorizationRequired(_) => {
+                Some(ErrorKind::PermissionDenied)
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    match kind {
+        Some(kind) => NmError {
+            kind,
+            msg: format!("systemd-hostnamed: {}", e),
+            dbus_error: Some(e),
+        },
+        None => e.into(),
+    }
+}
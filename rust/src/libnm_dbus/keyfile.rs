@@ -35,13 +35,14 @@ pub(crate) fn zvariant_value_to_keyfile(
                 // Only sort the top level sections
                 names.sort_unstable();
             } else {
-                // place the sub-section at the end of section_names.
+                // place the sub-section at the end of section_names, keys
+                // are sorted to generate identical keyfile for same value.
                 names.sort_unstable_by(|a, b| {
                     let a_is_subsection =
                         matches!(data.get(a), Some(zvariant::Value::Dict(_)));
                     let b_is_subsection =
                         matches!(data.get(b), Some(zvariant::Value::Dict(_)));
-                    a_is_subsection.cmp(&b_is_subsection)
+                    (a_is_subsection, a).cmp(&(b_is_subsection, b))
                 });
             }
 
@@ -82,6 +83,11 @@ pub(crate) fn zvariant_value_to_keyfile(
                         );
                     } else if key == "address-data" {
                         ret += &ip_address_value_to_string(section_value);
+                    } else if key == "trunks" && section_name == "ovs-port" {
+                        ret += &format!(
+                            "trunks={}\n",
+                            ovs_trunks_value_to_string(section_value)
+                        );
                     } else if let zvariant::Value::Dict(_) = section_value {
                         let sub_section: HashMap<String, zvariant::Value> =
                             HashMap::try_from(section_value.clone())?;
@@ -183,3 +189,35 @@ fn ip_address_value_to_string(value: &zvariant::Value) -> String {
     }
     ret
 }
+
+// Keyfile stores OVS trunks like `100,200-300`
+fn ovs_trunks_value_to_string(value: &zvariant::Value) -> String {
+    let mut trunks = Vec::new();
+    if let zvariant::Value::Array(a) = value {
+        for trunk in a.get() {
+            if let zvariant::Value::Dict(d) = trunk {
+                let start = get_dict_u32(d, "start");
+                let end = get_dict_u32(d, "end");
+                match (start, end) {
+                    (Some(s), Some(e)) if s == e => {
+                        trunks.push(format!("{}", s))
+                    }
+                    (Some(s), Some(e)) => trunks.push(format!("{}-{}", s, e)),
+                    _ => (),
+                }
+            }
+        }
+    }
+    trunks.join(",")
+}
+
+fn get_dict_u32(dict: &zvariant::Dict, key: &str) -> Option<u32> {
+    match dict.get::<str, zvariant::Value>(key).ok().flatten() {
+        Some(zvariant::Value::U32(v)) => Some(*v),
+        Some(zvariant::Value::Value(v)) => match v.as_ref() {
+            zvariant::Value::U32(v) => Some(*v),
+            _ => None,
+        },
+        _ => None,
+    }
+}
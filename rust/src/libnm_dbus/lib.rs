@@ -20,18 +20,27 @@ mod dbus_proxy;
 mod device;
 mod dns;
 mod error;
+mod hostnamed;
 mod keyfile;
 mod nm_api;
 
 pub use crate::active_connection::NmActiveConnection;
 pub use crate::connection::{
-    NmConnection, NmIpRoute, NmIpRouteRule, NmSettingBond, NmSettingBridge,
-    NmSettingBridgeVlanRange, NmSettingConnection, NmSettingIp,
-    NmSettingIpMethod, NmSettingMacVlan, NmSettingOvsBridge, NmSettingOvsIface,
-    NmSettingOvsPort, NmSettingSriov, NmSettingSriovVf, NmSettingSriovVfVlan,
-    NmSettingVlan, NmSettingWired, NmVlanProtocol,
+    NmConnection, NmConnectionStorage, NmIpRoute, NmIpRouteRule,
+    NmSetting8021X, NmSettingBond, NmSettingBondPort, NmSettingBridge,
+    NmSettingBridgeVlanRange, NmSettingConnection, NmSettingEthtool,
+    NmSettingHsr, NmSettingInfiniBand, NmSettingIp, NmSettingIpMethod,
+    NmSettingIpTunnel, NmSettingIpVlan, NmSettingLoopback, NmSettingMacSec,
+    NmSettingMacVlan, NmSettingOvsBridge, NmSettingOvsExtIds,
+    NmSettingOvsIface, NmSettingOvsOtherConfig, NmSettingOvsPort,
+    NmSettingOvsPortTrunk, NmSettingPppoe, NmSettingSriov, NmSettingSriovVf,
+    NmSettingSriovVfVlan, NmSettingTeam, NmSettingTeamPort, NmSettingUser,
+    NmSettingVlan, NmSettingVpn, NmSettingVrf, NmSettingWireGuard,
+    NmSettingWired, NmSettingWireless, NmSettingWirelessSecurity,
+    NmVlanProtocol, NmWireGuardPeer,
 };
 pub use crate::device::{NmDevice, NmDeviceState, NmDeviceStateReason};
 pub use crate::dns::NmDnsEntry;
 pub use crate::error::{ErrorKind, NmError};
+pub use crate::hostnamed::HostnamedApi;
 pub use crate::nm_api::NmApi;
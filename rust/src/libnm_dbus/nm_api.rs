@@ -21,7 +21,10 @@ use crate::{
     active_connection::{
         get_nm_ac_by_obj_path, nm_ac_obj_path_uuid_get, NmActiveConnection,
     },
-    connection::{nm_con_get_from_obj_path, NmConnection},
+    connection::{
+        nm_con_get_from_obj_path, nm_con_secrets_get_from_obj_path,
+        nm_con_storage_get_from_obj_path, NmConnection, NmConnectionStorage,
+    },
     dbus::NmDbus,
     device::{
         nm_dev_delete, nm_dev_from_obj_path, NmDevice, NmDeviceState,
@@ -49,13 +52,18 @@ impl<'a> NmApi<'a> {
         self.dbus.version()
     }
 
-    pub fn checkpoint_create(&self) -> Result<String, NmError> {
-        debug!("checkpoint_create");
-        let cp = self.dbus.checkpoint_create()?;
+    pub fn checkpoint_create(&self, timeout: u32) -> Result<String, NmError> {
+        debug!("checkpoint_create: timeout {}", timeout);
+        let cp = self.dbus.checkpoint_create(timeout)?;
         debug!("checkpoint created: {}", &cp);
         Ok(cp)
     }
 
+    pub fn checkpoints_get(&self) -> Result<Vec<String>, NmError> {
+        debug!("checkpoints_get");
+        self.dbus.checkpoints()
+    }
+
     pub fn checkpoint_destroy(&self, checkpoint: &str) -> Result<(), NmError> {
         debug!("checkpoint_destroy: {}", checkpoint);
         self.dbus.checkpoint_destroy(checkpoint)
@@ -66,6 +74,17 @@ impl<'a> NmApi<'a> {
         self.dbus.checkpoint_rollback(checkpoint)
     }
 
+    // The static hostname managed by NetworkManager
+    pub fn hostname_get(&self) -> Result<String, NmError> {
+        debug!("hostname_get");
+        self.dbus.hostname_get()
+    }
+
+    pub fn hostname_set(&self, hostname: &str) -> Result<(), NmError> {
+        debug!("hostname_set: {}", hostname);
+        self.dbus.hostname_set(hostname)
+    }
+
     pub fn connection_activate(&self, uuid: &str) -> Result<(), NmError> {
         debug!("connection_activate: {}", uuid);
         // Race: Connection might just created
@@ -92,6 +111,36 @@ impl<'a> NmApi<'a> {
         nm_con_get_from_obj_path(&self.dbus.connection, &con_obj_path)
     }
 
+    // The NmConnection should be the one retrieved by connections_get()
+    pub fn connection_storage_get(
+        &self,
+        nm_conn: &NmConnection,
+    ) -> Result<NmConnectionStorage, NmError> {
+        debug!("connection_storage_get: {:?}", nm_conn.uuid());
+        nm_con_storage_get_from_obj_path(
+            &self.dbus.connection,
+            &nm_conn.obj_path,
+        )
+    }
+
+    // The NmConnection should be the one retrieved by connections_get()
+    pub fn connection_secrets_get(
+        &self,
+        nm_conn: &NmConnection,
+        setting_name: &str,
+    ) -> Result<NmConnection, NmError> {
+        debug!(
+            "connection_secrets_get: {:?} {}",
+            nm_conn.uuid(),
+            setting_name
+        );
+        nm_con_secrets_get_from_obj_path(
+            &self.dbus.connection,
+            &nm_conn.obj_path,
+            setting_name,
+        )
+    }
+
     pub fn connections_get(&self) -> Result<Vec<NmConnection>, NmError> {
         debug!("connections_get");
         let mut nm_conns = Vec::new();
@@ -130,17 +179,24 @@ impl<'a> NmApi<'a> {
         Ok(nm_conns)
     }
 
+    // When memory_only is true, the profile is not stored to disk and will
+    // be discarded on reboot.
     pub fn connection_add(
         &self,
         nm_conn: &NmConnection,
+        memory_only: bool,
     ) -> Result<(), NmError> {
-        debug!("connection_add: {:?}", nm_conn);
+        debug!("connection_add: {:?} memory_only: {}", nm_conn, memory_only);
         if let Some(uuid) = nm_conn.uuid() {
             if let Ok(con_obj_path) = self.dbus.get_connection_by_uuid(uuid) {
-                return self.dbus.connection_update(&con_obj_path, nm_conn);
+                return self.dbus.connection_update(
+                    &con_obj_path,
+                    nm_conn,
+                    memory_only,
+                );
             }
         };
-        self.dbus.connection_add(nm_conn)?;
+        self.dbus.connection_add(nm_conn, memory_only)?;
         Ok(())
     }
 
@@ -23,6 +23,8 @@ from .error import (
     NmstatePluginError,
     NmstateNotImplementedError,
     NmstateKernelIntegerRoundedError,
+    NmstateDependencyError,
+    NmstateTimeoutError,
 )
 
 lib = cdll.LoadLibrary("libnmstate.so.2")
@@ -132,5 +134,11 @@ def apply_net_state(
             raise NmstateNotImplementedError(err_msg)
         elif err_kind == "KernelIntegerRoundedError":
             raise NmstateKernelIntegerRoundedError(err_msg)
+        elif err_kind == "DependencyError":
+            raise NmstateDependencyError(err_msg)
+        elif err_kind == "IpAddressConflict":
+            raise NmstateValueError(err_msg)
+        elif err_kind == "Timeout":
+            raise NmstateTimeoutError(err_msg)
         else:
             raise NmstateError(f"{err_kind}: {err_msg}")